@@ -1,4 +1,4 @@
--- Version: 1
+-- Version: 7
 -- Registry Schema for Multi-Database Architecture
 -- Contains: shops, users, roles, modules, shop_templates
 -- This database is ALWAYS SQLite and shared across all shops
@@ -199,6 +199,178 @@ CREATE TABLE IF NOT EXISTS shop_templates (
 CREATE INDEX IF NOT EXISTS idx_shop_templates_code ON shop_templates(code) WHERE _status != 'deleted';
 CREATE INDEX IF NOT EXISTS idx_shop_templates_category ON shop_templates(category) WHERE _status != 'deleted';
 
+-- ============================================================
+-- 9. SHOP MODULE CHANGES (Histórico de módulos por loja)
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS shop_module_changes (
+    id TEXT PRIMARY KEY,
+    shop_id TEXT NOT NULL REFERENCES shops(id) ON DELETE CASCADE,
+    changed_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    enabled_modules TEXT DEFAULT '[]',      -- JSON array: módulos habilitados na mudança
+    disabled_modules TEXT DEFAULT '[]',     -- JSON array: módulos desabilitados na mudança
+    previous_config TEXT,                   -- JSON: features_config anterior
+    new_config TEXT NOT NULL,               -- JSON: features_config aplicado
+    reason TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_shop_module_changes_shop ON shop_module_changes(shop_id, created_at);
+
+-- ============================================================
+-- 10. SHOP TEMPLATE SEEDS (Dados iniciais e versão do template)
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS shop_template_seeds (
+    template_id TEXT PRIMARY KEY REFERENCES shop_templates(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL DEFAULT 1,     -- Incrementada a cada nova exportação
+    seed_data TEXT NOT NULL DEFAULT '{}',   -- JSON: locations, categories, customer_groups, roles, tax_rules
+    source_shop_id TEXT REFERENCES shops(id) ON DELETE SET NULL, -- Loja de referência (templates exportados)
+    _status TEXT DEFAULT 'created',
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- ============================================================
+-- 11. SHOP TEMPLATE APPLICATIONS (Histórico de aplicação)
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS shop_template_applications (
+    id TEXT PRIMARY KEY,
+    shop_id TEXT NOT NULL REFERENCES shops(id) ON DELETE CASCADE,
+    template_id TEXT NOT NULL REFERENCES shop_templates(id) ON DELETE CASCADE,
+    template_version INTEGER NOT NULL DEFAULT 1,
+    mode TEXT NOT NULL CHECK (mode IN ('create', 'apply', 'upgrade')),
+    applied_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    summary TEXT DEFAULT '{}',              -- JSON: o que foi alterado/criado
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_shop_template_applications_shop ON shop_template_applications(shop_id, created_at);
+
+-- ============================================================
+-- 12. EXCHANGE RATES (Conversão entre moedas das lojas)
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS exchange_rates (
+    base_currency TEXT NOT NULL,            -- 'USD'
+    quote_currency TEXT NOT NULL,           -- 'BRL'
+    rate REAL NOT NULL CHECK (rate > 0),    -- 1 base_currency = rate quote_currency
+    _status TEXT DEFAULT 'created',
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (base_currency, quote_currency)
+);
+
+-- ============================================================
+-- 13. SHOP TRANSFERS (Transferências de estoque entre lojas)
+-- ============================================================
+-- Coordena as duas bases: saída da loja de origem para um local de trânsito
+-- e recebimento na loja de destino. O status intermediário ('dispatching',
+-- 'receiving', 'cancelling') indica uma etapa que deve ser retomada.
+
+CREATE TABLE IF NOT EXISTS shop_transfers (
+    id TEXT PRIMARY KEY,
+    source_shop_id TEXT NOT NULL REFERENCES shops(id) ON DELETE CASCADE,
+    source_location_id TEXT NOT NULL,       -- Local na base da loja de origem
+    transit_location_id TEXT NOT NULL,      -- Local de trânsito na base da loja de origem
+    dest_shop_id TEXT NOT NULL REFERENCES shops(id) ON DELETE CASCADE,
+    dest_location_id TEXT NOT NULL,         -- Local na base da loja de destino
+    status TEXT NOT NULL DEFAULT 'draft' CHECK (status IN (
+        'draft', 'dispatching', 'in_transit', 'receiving', 'received', 'cancelling', 'cancelled'
+    )),
+    notes TEXT,
+    last_error TEXT,                        -- Último erro de uma etapa interrompida
+    created_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    dispatched_at DATETIME,
+    received_at DATETIME,
+    cancelled_at DATETIME,
+    _status TEXT DEFAULT 'created',
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_shop_transfers_source ON shop_transfers(source_shop_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_shop_transfers_dest ON shop_transfers(dest_shop_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_shop_transfers_status ON shop_transfers(status);
+
+-- ============================================================
+-- 14. SHOP TRANSFER ITEMS
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS shop_transfer_items (
+    id TEXT PRIMARY KEY,
+    transfer_id TEXT NOT NULL REFERENCES shop_transfers(id) ON DELETE CASCADE,
+    sku TEXT NOT NULL,
+    gtin TEXT,
+    product_name TEXT NOT NULL,
+    quantity REAL NOT NULL CHECK (quantity > 0),
+    source_product_id TEXT NOT NULL,        -- Produto na base da loja de origem
+    dest_product_id TEXT NOT NULL,          -- Produto na base da loja de destino
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_shop_transfer_items_transfer ON shop_transfer_items(transfer_id);
+
+-- ============================================================
+-- 15. DATABASE MAINTENANCE RUNS (Manutenção das bases SQLite das lojas)
+-- ============================================================
+-- Resultado de cada execução de optimize, integrity_check, checkpoint do
+-- WAL e vacuum incremental, agendada ou manual.
+
+CREATE TABLE IF NOT EXISTS database_maintenance_runs (
+    id TEXT PRIMARY KEY,
+    shop_id TEXT NOT NULL REFERENCES shops(id) ON DELETE CASCADE,
+    triggered_by TEXT NOT NULL CHECK (triggered_by IN ('schedule', 'manual')),
+    status TEXT NOT NULL CHECK (status IN ('ok', 'warning', 'failed')),
+    integrity_check TEXT,                   -- 'ok' ou problemas encontrados; NULL se não executado
+    optimized INTEGER NOT NULL DEFAULT 0,
+    checkpoint_mode TEXT,                   -- 'PASSIVE', 'TRUNCATE'
+    checkpoint_busy INTEGER,                -- 1 se o checkpoint não pôde terminar
+    checkpoint_log_frames INTEGER,
+    checkpointed_frames INTEGER,
+    vacuum_mode TEXT,                       -- 'incremental', 'full'; NULL se não executado
+    pages_freed INTEGER NOT NULL DEFAULT 0,
+    page_count INTEGER,
+    freelist_count INTEGER,
+    size_bytes INTEGER,
+    error TEXT,
+    duration_ms INTEGER NOT NULL DEFAULT 0,
+    started_at DATETIME NOT NULL,
+    finished_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_database_maintenance_runs_shop ON database_maintenance_runs(shop_id, started_at);
+
+-- ============================================================
+-- 16. SHOP DATABASE MIGRATIONS (Troca de SQLite para Postgres e vice-versa)
+-- ============================================================
+-- A base de origem é mantida intacta como ponto de rollback; a troca de
+-- shops.database_config acontece na mesma transação que marca 'completed'.
+
+CREATE TABLE IF NOT EXISTS shop_database_migrations (
+    id TEXT PRIMARY KEY,
+    shop_id TEXT NOT NULL REFERENCES shops(id) ON DELETE CASCADE,
+    source_type TEXT NOT NULL CHECK (source_type IN ('sqlite', 'postgres')),
+    target_type TEXT NOT NULL CHECK (target_type IN ('sqlite', 'postgres')),
+    source_config TEXT NOT NULL,            -- database_config anterior (JSON)
+    target_config TEXT NOT NULL,            -- database_config novo (JSON)
+    status TEXT NOT NULL DEFAULT 'running' CHECK (status IN (
+        'running', 'completed', 'failed', 'rolled_back'
+    )),
+    tables_copied INTEGER NOT NULL DEFAULT 0,
+    rows_copied INTEGER NOT NULL DEFAULT 0,
+    tables TEXT,                            -- JSON: linhas e checksum por tabela na origem e no destino
+    archived_path TEXT,                     -- Arquivo SQLite substituído pelo destino
+    error TEXT,
+    created_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    started_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at DATETIME,
+    rolled_back_at DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_shop_database_migrations_shop ON shop_database_migrations(shop_id, started_at);
+
 -- ============================================================
 -- SEED DATA: MODULES
 -- ============================================================
@@ -220,7 +392,10 @@ INSERT OR IGNORE INTO modules (id, code, name, description, category, required_m
 -- Módulos Opcionais - Vendas
 INSERT OR IGNORE INTO modules (id, code, name, description, category, required_modules, tables_used) VALUES
 ('mod-checkout', 'checkout', 'Checkout', 'Carrinho de compras e checkout', 'sales', '[]', '["checkouts"]'),
-('mod-pos', 'pos', 'Ponto de Venda', 'Sistema de ponto de venda (PDV)', 'sales', '[]', '["pos_sessions"]');
+('mod-pos', 'pos', 'Ponto de Venda', 'Sistema de ponto de venda (PDV)', 'sales', '["inventory", "locations"]', '["pos_sessions"]');
+
+-- O PDV baixa estoque por local (registros criados antes da versão 2)
+UPDATE modules SET required_modules = '["inventory", "locations"]' WHERE code = 'pos' AND required_modules = '[]';
 
 -- Módulos Opcionais - Marketing e Suporte
 INSERT OR IGNORE INTO modules (id, code, name, description, category, required_modules, tables_used) VALUES
@@ -308,3 +483,13 @@ INSERT OR IGNORE INTO shop_templates (id, code, name, description, category, fea
     '{"product_type_default": "digital", "require_shipping": false, "allow_guest_checkout": false}',
     '["checkout", "reviews", "inquiries"]'
 );
+
+-- ============================================================
+-- SEED DATA: TEMPLATE SEEDS
+-- ============================================================
+
+INSERT OR IGNORE INTO shop_template_seeds (template_id, version, seed_data) VALUES
+('tpl-online-store', 1, '{"locations": [{"name": "Depósito", "type": "warehouse", "is_sellable": true}]}'),
+('tpl-physical-store', 1, '{"locations": [{"name": "Loja", "type": "store", "is_sellable": true}, {"name": "Estoque", "type": "warehouse", "is_sellable": false}]}'),
+('tpl-marketplace', 1, '{"locations": [{"name": "Depósito", "type": "warehouse", "is_sellable": true}]}'),
+('tpl-hybrid-store', 1, '{"locations": [{"name": "Loja", "type": "store", "is_sellable": true}, {"name": "Depósito", "type": "warehouse", "is_sellable": true}]}');
@@ -1,4 +1,4 @@
--- Version: 1
+-- Version: 17
 -- Shop Schema for Multi-Database Architecture (PostgreSQL version)
 -- Contains all business tables WITHOUT shop_id columns
 -- Each shop gets its own database with this schema
@@ -709,6 +709,667 @@ CREATE TABLE IF NOT EXISTS audit_logs (
 CREATE INDEX IF NOT EXISTS idx_audit_logs_table ON audit_logs(table_name, created_at);
 CREATE INDEX IF NOT EXISTS idx_audit_logs_record ON audit_logs(record_id);
 
+-- ============================================================
+-- 27. RETURN REQUESTS (RMA)
+-- Note: order returns reference the sale transaction that fulfilled the order
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS return_requests (
+    id TEXT PRIMARY KEY,
+    transaction_id TEXT NOT NULL REFERENCES transactions(id) ON DELETE RESTRICT,
+    order_id TEXT REFERENCES orders(id) ON DELETE SET NULL,
+    customer_id TEXT REFERENCES customers(id) ON DELETE SET NULL,
+    type TEXT NOT NULL DEFAULT 'return' CHECK (type IN ('return', 'exchange')),
+    status TEXT NOT NULL DEFAULT 'requested' CHECK (status IN ('requested', 'received', 'resolved', 'cancelled')),
+    resolution TEXT CHECK (resolution IN ('refund', 'store_credit', 'exchange')),
+    reason TEXT,
+    notes TEXT,
+    return_transaction_id TEXT REFERENCES transactions(id) ON DELETE SET NULL,
+    exchange_transaction_id TEXT REFERENCES transactions(id) ON DELETE SET NULL,
+    total_amount NUMERIC(10, 2) DEFAULT 0,
+    resolved_amount NUMERIC(10, 2) DEFAULT 0,
+    created_by TEXT, -- References users in registry (validated at app layer)
+    received_at TIMESTAMP WITH TIME ZONE,
+    resolved_at TIMESTAMP WITH TIME ZONE,
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_return_requests_transaction ON return_requests(transaction_id) WHERE _status != 'deleted';
+CREATE INDEX IF NOT EXISTS idx_return_requests_order ON return_requests(order_id) WHERE _status != 'deleted';
+CREATE INDEX IF NOT EXISTS idx_return_requests_customer ON return_requests(customer_id) WHERE _status != 'deleted';
+CREATE INDEX IF NOT EXISTS idx_return_requests_status ON return_requests(status) WHERE _status != 'deleted';
+
+-- ============================================================
+-- 28. RETURN ITEMS
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS return_items (
+    id TEXT PRIMARY KEY,
+    return_request_id TEXT NOT NULL REFERENCES return_requests(id) ON DELETE CASCADE,
+    transaction_item_id TEXT NOT NULL REFERENCES transaction_items(id) ON DELETE RESTRICT,
+    product_id TEXT REFERENCES products(id) ON DELETE SET NULL,
+    quantity NUMERIC(10, 2) NOT NULL CHECK (quantity > 0),
+    unit_price NUMERIC(10, 2) NOT NULL,
+    reason TEXT,
+    condition TEXT CHECK (condition IN ('sellable', 'damaged', 'quarantine')),
+    location_id TEXT REFERENCES locations(id) ON DELETE SET NULL,
+    inventory_level_id TEXT REFERENCES inventory_levels(id) ON DELETE SET NULL,
+    refund_id TEXT REFERENCES refunds(id) ON DELETE SET NULL,
+    refund_amount NUMERIC(10, 2),
+    received_at TIMESTAMP WITH TIME ZONE,
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_return_items_request ON return_items(return_request_id) WHERE _status != 'deleted';
+CREATE INDEX IF NOT EXISTS idx_return_items_transaction_item ON return_items(transaction_item_id) WHERE _status != 'deleted';
+CREATE INDEX IF NOT EXISTS idx_return_items_refund ON return_items(refund_id) WHERE _status != 'deleted';
+
+-- ============================================================
+-- 29. GIFT CARDS
+-- Balance is not stored: it is always the sum of gift_card_ledger
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS gift_cards (
+    id TEXT PRIMARY KEY,
+    code TEXT UNIQUE NOT NULL,
+    initial_balance NUMERIC(10, 2) NOT NULL CHECK (initial_balance > 0),
+    currency TEXT DEFAULT 'BRL',
+    customer_id TEXT REFERENCES customers(id) ON DELETE SET NULL,
+    source TEXT NOT NULL DEFAULT 'manual' CHECK (source IN ('sale', 'manual')),
+    source_transaction_id TEXT REFERENCES transactions(id) ON DELETE SET NULL,
+    status TEXT NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'disabled')),
+    expires_at TIMESTAMP WITH TIME ZONE,
+    notes TEXT,
+    created_by TEXT, -- References users in registry (validated at app layer)
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_gift_cards_code ON gift_cards(code) WHERE _status != 'deleted';
+CREATE INDEX IF NOT EXISTS idx_gift_cards_customer ON gift_cards(customer_id) WHERE _status != 'deleted';
+
+-- ============================================================
+-- 30. GIFT CARD LEDGER (append-only)
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS gift_card_ledger (
+    id TEXT PRIMARY KEY,
+    gift_card_id TEXT NOT NULL REFERENCES gift_cards(id) ON DELETE RESTRICT,
+    type TEXT NOT NULL CHECK (type IN ('credit', 'debit')),
+    amount NUMERIC(10, 2) NOT NULL CHECK (amount > 0),
+    reason TEXT NOT NULL CHECK (reason IN ('issue', 'redeem', 'refund', 'adjustment')),
+    payment_id TEXT REFERENCES payments(id) ON DELETE SET NULL,
+    transaction_id TEXT REFERENCES transactions(id) ON DELETE SET NULL,
+    notes TEXT,
+    created_by TEXT, -- References users in registry (validated at app layer)
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_gift_card_ledger_card ON gift_card_ledger(gift_card_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_gift_card_ledger_payment ON gift_card_ledger(payment_id);
+
+-- ============================================================
+-- 31. STORE CREDIT ACCOUNTS
+-- Balance is not stored: it is always the sum of store_credit_ledger
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS store_credit_accounts (
+    id TEXT PRIMARY KEY,
+    customer_id TEXT UNIQUE NOT NULL REFERENCES customers(id) ON DELETE RESTRICT,
+    currency TEXT DEFAULT 'BRL',
+    status TEXT NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'frozen')),
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- ============================================================
+-- 32. STORE CREDIT LEDGER (append-only)
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS store_credit_ledger (
+    id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL REFERENCES store_credit_accounts(id) ON DELETE RESTRICT,
+    type TEXT NOT NULL CHECK (type IN ('credit', 'debit')),
+    amount NUMERIC(10, 2) NOT NULL CHECK (amount > 0),
+    reason TEXT NOT NULL CHECK (reason IN ('return', 'redeem', 'refund', 'adjustment')),
+    payment_id TEXT REFERENCES payments(id) ON DELETE SET NULL,
+    transaction_id TEXT REFERENCES transactions(id) ON DELETE SET NULL,
+    return_request_id TEXT REFERENCES return_requests(id) ON DELETE SET NULL,
+    notes TEXT,
+    created_by TEXT, -- References users in registry (validated at app layer)
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_store_credit_ledger_account ON store_credit_ledger(account_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_store_credit_ledger_payment ON store_credit_ledger(payment_id);
+
+-- ============================================================
+-- 33. INSTALLMENT RATES
+-- Installment plans offered by the shop, with the monthly interest
+-- charged on each installment (0 = interest-free)
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS installment_rates (
+    id TEXT PRIMARY KEY,
+    method TEXT NOT NULL DEFAULT 'credit_card',
+    installments INTEGER NOT NULL CHECK (installments >= 1),
+    interest_rate NUMERIC(10, 2) NOT NULL DEFAULT 0 CHECK (interest_rate >= 0), -- % per month
+    min_installment_amount NUMERIC(10, 2),
+    is_active BOOLEAN DEFAULT true,
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (method, installments)
+);
+
+-- ============================================================
+-- 34. PAYMENT INSTALLMENTS
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS payment_installments (
+    id TEXT PRIMARY KEY,
+    payment_id TEXT NOT NULL REFERENCES payments(id) ON DELETE CASCADE,
+    number INTEGER NOT NULL CHECK (number >= 1),
+    due_date TIMESTAMP WITH TIME ZONE NOT NULL,
+    principal NUMERIC(10, 2) NOT NULL,
+    interest NUMERIC(10, 2) NOT NULL DEFAULT 0,
+    amount NUMERIC(10, 2) NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'paid', 'cancelled')),
+    paid_at TIMESTAMP WITH TIME ZONE,
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (payment_id, number)
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_installments_payment ON payment_installments(payment_id) WHERE _status != 'deleted';
+CREATE INDEX IF NOT EXISTS idx_payment_installments_due ON payment_installments(due_date) WHERE status = 'pending';
+
+-- ============================================================
+-- 35. CARRIER ACCOUNTS
+-- Credentials and settings of the shipping carriers integrated by the shop
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS carrier_accounts (
+    id TEXT PRIMARY KEY,
+    carrier TEXT UNIQUE NOT NULL CHECK (carrier IN ('correios', 'melhor_envio')),
+    name TEXT,
+    base_url TEXT, -- Overrides the carrier API endpoint (e.g. a local stub)
+    is_sandbox BOOLEAN DEFAULT false,
+    origin_postal_code TEXT NOT NULL,
+    credentials TEXT, -- JSONB
+    settings TEXT, -- JSONB (services, sender address, user agent)
+    is_active BOOLEAN DEFAULT true,
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- ============================================================
+-- 36. SHIPPING BOXES
+-- Catalog of box sizes the shop packs orders into
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS shipping_boxes (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    package_type TEXT DEFAULT 'box' CHECK (package_type IN ('box', 'envelope', 'tube')),
+    width_mm INTEGER NOT NULL CHECK (width_mm > 0),
+    height_mm INTEGER NOT NULL CHECK (height_mm > 0),
+    depth_mm INTEGER NOT NULL CHECK (depth_mm > 0),
+    empty_weight_g INTEGER DEFAULT 0,
+    max_weight_g INTEGER,
+    is_active BOOLEAN DEFAULT true,
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- ============================================================
+-- 37. PRODUCT OPTIONS
+-- Option axes (size, color...) of a parent product; variants are
+-- child products (parent_id) carrying one value per axis in attributes
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS product_options (
+    id TEXT PRIMARY KEY,
+    product_id TEXT NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    position INTEGER DEFAULT 0,
+    option_values TEXT NOT NULL, -- JSON array of strings
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (product_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_product_options_product ON product_options(product_id);
+
+-- ============================================================
+-- 38. BUNDLE COMPONENTS
+-- Bill of materials of bundle/kit products
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS bundle_components (
+    id TEXT PRIMARY KEY,
+    bundle_id TEXT NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    component_id TEXT NOT NULL REFERENCES products(id) ON DELETE RESTRICT,
+    quantity NUMERIC(10, 2) NOT NULL CHECK (quantity > 0),
+    price_share NUMERIC(10, 2) CHECK (price_share IS NULL OR price_share >= 0), -- Allocation weight; defaults to component price
+    position INTEGER DEFAULT 0,
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (bundle_id, component_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_bundle_components_bundle ON bundle_components(bundle_id);
+CREATE INDEX IF NOT EXISTS idx_bundle_components_component ON bundle_components(component_id);
+
+-- ============================================================
+-- 39. TRANSACTION ITEM COMPONENTS
+-- Components moved for a sold bundle line and the share of the
+-- line price allocated to each one
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS transaction_item_components (
+    id TEXT PRIMARY KEY,
+    transaction_id TEXT NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+    transaction_item_id TEXT NOT NULL REFERENCES transaction_items(id) ON DELETE CASCADE,
+    bundle_id TEXT REFERENCES products(id) ON DELETE SET NULL,
+    component_id TEXT REFERENCES products(id) ON DELETE SET NULL,
+    quantity NUMERIC(10, 2) NOT NULL,
+    allocated_amount NUMERIC(10, 2) NOT NULL,
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_transaction_item_components_transaction ON transaction_item_components(transaction_id);
+CREATE INDEX IF NOT EXISTS idx_transaction_item_components_item ON transaction_item_components(transaction_item_id);
+
+-- ============================================================
+-- 40. PRICE LISTS
+-- Referenced by customer_groups.price_list_id
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS price_lists (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    code TEXT UNIQUE,
+    currency TEXT DEFAULT 'BRL',
+    priority INTEGER DEFAULT 0,
+    starts_at TIMESTAMP WITH TIME ZONE,
+    ends_at TIMESTAMP WITH TIME ZONE,
+    is_active BOOLEAN DEFAULT true,
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- ============================================================
+-- 41. PRICE LIST RULES
+-- A fixed price or percentage adjustment for a product, category,
+-- brand or (all scopes empty) every product, from a minimum quantity
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS price_list_rules (
+    id TEXT PRIMARY KEY,
+    price_list_id TEXT NOT NULL REFERENCES price_lists(id) ON DELETE CASCADE,
+    product_id TEXT REFERENCES products(id) ON DELETE CASCADE,
+    category_id TEXT REFERENCES categories(id) ON DELETE CASCADE,
+    brand_id TEXT REFERENCES brands(id) ON DELETE CASCADE,
+    adjustment_type TEXT NOT NULL CHECK (adjustment_type IN ('fixed', 'percentage')),
+    value NUMERIC(10, 2) NOT NULL, -- Price for 'fixed'; percent over the base price for 'percentage' (-10 = 10% off)
+    min_quantity NUMERIC(10, 2) DEFAULT 1,
+    starts_at TIMESTAMP WITH TIME ZONE,
+    ends_at TIMESTAMP WITH TIME ZONE,
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_price_list_rules_list ON price_list_rules(price_list_id) WHERE _status != 'deleted';
+CREATE INDEX IF NOT EXISTS idx_price_list_rules_product ON price_list_rules(product_id) WHERE _status != 'deleted';
+
+-- ============================================================
+-- 42. PRICE HISTORY
+-- One row per price state of a product; effective_to is NULL for the
+-- current one. Kept as evidence of previous prices for promotions
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS price_history (
+    id TEXT PRIMARY KEY,
+    product_id TEXT NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    price NUMERIC(10, 2) NOT NULL,
+    promotional_price NUMERIC(10, 2),
+    effective_from TIMESTAMP WITH TIME ZONE NOT NULL,
+    effective_to TIMESTAMP WITH TIME ZONE,
+    source TEXT NOT NULL DEFAULT 'manual', -- 'baseline', 'manual', 'bulk', 'schedule', 'promotion'
+    schedule_id TEXT,
+    changed_by TEXT,
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_price_history_product ON price_history(product_id, effective_from);
+
+-- ============================================================
+-- 43. PRICE SCHEDULES
+-- Future price changes and promotional windows, applied and reverted
+-- by the price scheduler
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS price_schedules (
+    id TEXT PRIMARY KEY,
+    product_id TEXT NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('price_change', 'promotion')),
+    price NUMERIC(10, 2), -- New regular price ('price_change')
+    promotional_price NUMERIC(10, 2), -- New promotional price
+    starts_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    ends_at TIMESTAMP WITH TIME ZONE, -- Promotions are reverted at this time
+    status TEXT NOT NULL DEFAULT 'scheduled' CHECK (status IN ('scheduled', 'active', 'completed', 'cancelled')),
+    previous_promotional_price NUMERIC(10, 2), -- Restored when a promotion ends
+    created_by TEXT,
+    applied_at TIMESTAMP WITH TIME ZONE,
+    reverted_at TIMESTAMP WITH TIME ZONE,
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_price_schedules_due ON price_schedules(status, starts_at) WHERE _status != 'deleted';
+CREATE INDEX IF NOT EXISTS idx_price_schedules_product ON price_schedules(product_id) WHERE _status != 'deleted';
+
+-- ============================================================
+-- 44. FULL-TEXT SEARCH
+-- tsvector documents over products and customers, accent-folded
+-- with unaccent and kept in sync by the search triggers
+-- ============================================================
+
+CREATE EXTENSION IF NOT EXISTS unaccent;
+
+CREATE TABLE IF NOT EXISTS products_fts (
+    product_id TEXT PRIMARY KEY REFERENCES products(id) ON DELETE CASCADE,
+    document TSVECTOR NOT NULL -- A: name, sku, gtin; B: brand, category; C: attributes
+);
+
+CREATE TABLE IF NOT EXISTS customers_fts (
+    customer_id TEXT PRIMARY KEY REFERENCES customers(id) ON DELETE CASCADE,
+    document TSVECTOR NOT NULL -- A: name, tax id; B: email, phone
+);
+
+CREATE INDEX IF NOT EXISTS idx_products_fts_document ON products_fts USING GIN (document);
+CREATE INDEX IF NOT EXISTS idx_customers_fts_document ON customers_fts USING GIN (document);
+
+-- ============================================================
+-- 45. PRODUCT BARCODES
+-- Additional codes resolving to a product on scan: internal codes,
+-- supplier codes, case GTINs (quantity > 1) and scale PLUs
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS product_barcodes (
+    id TEXT PRIMARY KEY,
+    product_id TEXT NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    barcode TEXT NOT NULL,
+    kind TEXT NOT NULL DEFAULT 'internal' CHECK (kind IN ('gtin', 'internal', 'supplier', 'plu')),
+    quantity NUMERIC(10, 2) NOT NULL DEFAULT 1 CHECK (quantity > 0), -- Units added per scan
+    description TEXT,
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_product_barcodes_code ON product_barcodes(kind, barcode) WHERE _status != 'deleted';
+CREATE INDEX IF NOT EXISTS idx_product_barcodes_barcode ON product_barcodes(barcode) WHERE _status != 'deleted';
+CREATE INDEX IF NOT EXISTS idx_product_barcodes_product ON product_barcodes(product_id);
+CREATE INDEX IF NOT EXISTS idx_products_gtin_ean ON products(gtin_ean);
+
+-- ============================================================
+-- 46. MEDIA ASSETS
+-- Stored files, deduplicated by content hash. Files live in the shop's
+-- media directory or an S3-compatible bucket (shop storage_config).
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS media_assets (
+    id TEXT PRIMARY KEY,
+    content_hash TEXT NOT NULL UNIQUE, -- SHA-256 of the file, hex
+    media_type TEXT NOT NULL CHECK (media_type IN ('image', 'video')),
+    mime_type TEXT NOT NULL,
+    file_size BIGINT NOT NULL,
+    width INTEGER,
+    height INTEGER,
+    storage_provider TEXT NOT NULL DEFAULT 'local' CHECK (storage_provider IN ('local', 's3')),
+    storage_key TEXT NOT NULL,
+    url TEXT, -- Public URL, remote storage only
+    thumbnail_key TEXT, -- Images only
+    thumbnail_url TEXT,
+    original_filename TEXT,
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- ============================================================
+-- 47. PRODUCT MEDIA
+-- Ordered images and videos of a product or variant
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS product_media (
+    id TEXT PRIMARY KEY,
+    product_id TEXT NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    asset_id TEXT NOT NULL REFERENCES media_assets(id),
+    position INTEGER NOT NULL DEFAULT 0, -- Lowest position is the cover
+    alt_text TEXT,
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (product_id, asset_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_product_media_product ON product_media(product_id, position);
+CREATE INDEX IF NOT EXISTS idx_product_media_asset ON product_media(asset_id);
+
+-- Deleting a product releases its media; unreferenced assets are purged
+-- by the media service
+CREATE OR REPLACE FUNCTION release_product_media()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW._status = 'deleted' THEN
+        DELETE FROM product_media WHERE product_id = NEW.id;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_products_release_media ON products;
+CREATE TRIGGER trg_products_release_media
+AFTER UPDATE OF _status ON products
+FOR EACH ROW
+EXECUTE FUNCTION release_product_media();
+
+-- ============================================================
+-- 48. DIGITAL PRODUCTS
+-- How a digital product is fulfilled: a license key from the pool or a
+-- download link. Digital products without a row are fulfilled manually.
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS digital_products (
+    product_id TEXT PRIMARY KEY REFERENCES products(id) ON DELETE CASCADE,
+    delivery_method TEXT NOT NULL DEFAULT 'license_key' CHECK (delivery_method IN ('license_key', 'download_link')),
+    download_url TEXT, -- File the download links lead to
+    download_limit INTEGER CHECK (download_limit IS NULL OR download_limit > 0), -- Per link, unlimited when NULL
+    link_expiry_days INTEGER CHECK (link_expiry_days IS NULL OR link_expiry_days > 0), -- Never expire when NULL
+    instructions TEXT, -- Shown to the customer with the key or link
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- ============================================================
+-- 49. LICENSE KEYS
+-- Key pool of license-key products, handed out oldest first
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS license_keys (
+    id TEXT PRIMARY KEY,
+    product_id TEXT NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    license_key TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'available' CHECK (status IN ('available', 'assigned', 'revoked')),
+    assigned_at TIMESTAMP WITH TIME ZONE,
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (product_id, license_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_license_keys_pool ON license_keys(product_id, status, created_at);
+
+-- ============================================================
+-- 50. DIGITAL DELIVERIES
+-- One license key or download link per delivery. Deliveries waiting for
+-- keys stay 'pending' and are fulfilled when keys are added.
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS digital_deliveries (
+    id TEXT PRIMARY KEY,
+    transaction_id TEXT NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+    transaction_item_id TEXT NOT NULL REFERENCES transaction_items(id) ON DELETE CASCADE,
+    product_id TEXT REFERENCES products(id) ON DELETE SET NULL, -- The digital product (component for bundles)
+    customer_id TEXT REFERENCES customers(id) ON DELETE SET NULL,
+    delivery_method TEXT NOT NULL CHECK (delivery_method IN ('license_key', 'download_link')),
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'delivered', 'revoked')),
+    license_key_id TEXT REFERENCES license_keys(id) ON DELETE SET NULL,
+    license_key TEXT, -- Snapshot of the delivered key
+    download_token TEXT UNIQUE,
+    download_limit INTEGER,
+    download_count INTEGER NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP WITH TIME ZONE,
+    delivered_at TIMESTAMP WITH TIME ZONE,
+    last_downloaded_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_digital_deliveries_transaction ON digital_deliveries(transaction_id);
+CREATE INDEX IF NOT EXISTS idx_digital_deliveries_pending ON digital_deliveries(product_id, status, created_at);
+
+-- ============================================================
+-- 51. SERVICE APPOINTMENTS
+-- Schedule and staff assignment of sold services
+-- Note: staff_id references users in registry database (validated at app layer)
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS service_appointments (
+    id TEXT PRIMARY KEY,
+    transaction_item_id TEXT NOT NULL REFERENCES transaction_items(id) ON DELETE CASCADE,
+    product_id TEXT REFERENCES products(id) ON DELETE SET NULL,
+    customer_id TEXT REFERENCES customers(id) ON DELETE SET NULL,
+    staff_id TEXT, -- References users in registry (validated at app layer)
+    location_id TEXT REFERENCES locations(id) ON DELETE SET NULL,
+    scheduled_at TIMESTAMP WITH TIME ZONE, -- Unscheduled when NULL
+    duration_minutes INTEGER NOT NULL DEFAULT 60 CHECK (duration_minutes > 0),
+    status TEXT NOT NULL DEFAULT 'scheduled' CHECK (status IN ('scheduled', 'completed', 'cancelled', 'no_show')),
+    notes TEXT,
+    _status TEXT DEFAULT 'created',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_service_appointments_item ON service_appointments(transaction_item_id);
+CREATE INDEX IF NOT EXISTS idx_service_appointments_staff ON service_appointments(staff_id, scheduled_at);
+CREATE INDEX IF NOT EXISTS idx_service_appointments_scheduled ON service_appointments(scheduled_at);
+
+-- ============================================================
+-- 52. SHOP TRANSFER PHASES
+-- Inter-shop transfer steps applied to this shop's stock
+-- Note: transfer_id references shop_transfers in registry database
+-- A row is written in the same transaction as the stock movements of its
+-- phase, so a phase replayed after a crash is never applied twice
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS shop_transfer_phases (
+    transfer_id TEXT NOT NULL, -- References shop_transfers in registry
+    phase TEXT NOT NULL CHECK (phase IN ('dispatch', 'receive', 'settle', 'return')),
+    movement_ids TEXT NOT NULL DEFAULT '[]', -- JSON array of inventory_movements ids
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (transfer_id, phase)
+);
+
+-- ============================================================
+-- 53. CUSTOMER METRICS
+-- RFM scores, churn probability and predicted lifetime value of the
+-- customers who bought at least once. Derived data: the whole table is
+-- rewritten by each metrics run
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS customer_metrics (
+    customer_id TEXT PRIMARY KEY REFERENCES customers(id) ON DELETE CASCADE,
+    first_order_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    last_order_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    orders_count INTEGER NOT NULL CHECK (orders_count > 0),
+    total_spent NUMERIC(10, 2) NOT NULL DEFAULT 0,
+    recency_days INTEGER NOT NULL,
+    recency_score INTEGER NOT NULL CHECK (recency_score BETWEEN 1 AND 5),
+    frequency_score INTEGER NOT NULL CHECK (frequency_score BETWEEN 1 AND 5),
+    monetary_score INTEGER NOT NULL CHECK (monetary_score BETWEEN 1 AND 5),
+    rfm_segment TEXT NOT NULL,
+    expected_order_gap_days NUMERIC(10, 2) NOT NULL,
+    churn_probability NUMERIC(5, 4) NOT NULL CHECK (churn_probability BETWEEN 0 AND 1),
+    predicted_clv NUMERIC(10, 2) NOT NULL DEFAULT 0,
+    clv_horizon_months INTEGER NOT NULL,
+    computed_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_customer_metrics_segment ON customer_metrics(rfm_segment);
+CREATE INDEX IF NOT EXISTS idx_customer_metrics_churn ON customer_metrics(churn_probability);
+
+-- ============================================================
+-- 54. CUSTOMER MERGES
+-- One row per duplicate customer folded into another. The merged
+-- record is soft-deleted; its snapshot and the rows moved to the
+-- survivor are kept here
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS customer_merges (
+    id TEXT PRIMARY KEY,
+    survivor_id TEXT NOT NULL REFERENCES customers(id) ON DELETE CASCADE,
+    merged_id TEXT NOT NULL UNIQUE REFERENCES customers(id) ON DELETE CASCADE,
+    match_score NUMERIC(5, 4),
+    merged_snapshot TEXT NOT NULL, -- JSONB
+    moved_records TEXT NOT NULL, -- JSONB: table -> rows re-pointed
+    merged_by TEXT, -- References users in registry (validated at app layer)
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_customer_merges_survivor ON customer_merges(survivor_id, created_at);
+
+-- ============================================================
+-- 55. CUSTOMER DUPLICATE DISMISSALS
+-- Pairs reviewed and found to be different people, left out of
+-- duplicate detection. customer_id_a sorts before customer_id_b
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS customer_duplicate_dismissals (
+    customer_id_a TEXT NOT NULL REFERENCES customers(id) ON DELETE CASCADE,
+    customer_id_b TEXT NOT NULL REFERENCES customers(id) ON DELETE CASCADE,
+    dismissed_by TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (customer_id_a, customer_id_b),
+    CHECK (customer_id_a < customer_id_b)
+);
+
 -- ============================================================
 -- TRIGGERS: Stock Validation and Updates
 -- ============================================================
@@ -1111,3 +1772,103 @@ CREATE TRIGGER trg_audit_refunds_insert
 AFTER INSERT ON refunds
 FOR EACH ROW
 EXECUTE FUNCTION audit_refunds_insert();
+
+-- ============================================================
+-- TRIGGERS: Full-Text Search Index
+-- ============================================================
+
+CREATE OR REPLACE FUNCTION refresh_product_search(p_product_id TEXT)
+RETURNS VOID AS $$
+BEGIN
+    INSERT INTO products_fts (product_id, document)
+    SELECT
+        p.id,
+        setweight(to_tsvector('simple', unaccent(concat_ws(' ', p.name, p.sku, p.gtin_ean))), 'A') ||
+        setweight(to_tsvector('simple', unaccent(concat_ws(' ', b.name, c.name))), 'B') ||
+        setweight(to_tsvector('simple', unaccent(COALESCE(p.attributes, ''))), 'C')
+    FROM products p
+    LEFT JOIN brands b ON b.id = p.brand_id
+    LEFT JOIN categories c ON c.id = p.category_id
+    WHERE p.id = p_product_id
+    ON CONFLICT (product_id) DO UPDATE SET document = EXCLUDED.document;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION refresh_customer_search(p_customer_id TEXT)
+RETURNS VOID AS $$
+BEGIN
+    INSERT INTO customers_fts (customer_id, document)
+    SELECT
+        c.id,
+        setweight(to_tsvector('simple', unaccent(concat_ws(' ',
+            c.first_name, c.last_name, c.company_name,
+            c.tax_id, regexp_replace(COALESCE(c.tax_id, ''), '\D', '', 'g')))), 'A') ||
+        setweight(to_tsvector('simple', unaccent(concat_ws(' ',
+            c.email, c.phone, regexp_replace(COALESCE(c.phone, ''), '\D', '', 'g')))), 'B')
+    FROM customers c
+    WHERE c.id = p_customer_id
+    ON CONFLICT (customer_id) DO UPDATE SET document = EXCLUDED.document;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION products_fts_sync()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM refresh_product_search(NEW.id);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_products_fts_sync ON products;
+CREATE TRIGGER trg_products_fts_sync
+AFTER INSERT OR UPDATE OF name, sku, gtin_ean, brand_id, category_id, attributes ON products
+FOR EACH ROW
+EXECUTE FUNCTION products_fts_sync();
+
+-- Brand and category names are part of the product document
+CREATE OR REPLACE FUNCTION brands_fts_sync()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM refresh_product_search(id) FROM products WHERE brand_id = NEW.id;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_brands_fts_sync ON brands;
+CREATE TRIGGER trg_brands_fts_sync
+AFTER UPDATE OF name ON brands
+FOR EACH ROW
+EXECUTE FUNCTION brands_fts_sync();
+
+CREATE OR REPLACE FUNCTION categories_fts_sync()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM refresh_product_search(id) FROM products WHERE category_id = NEW.id;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_categories_fts_sync ON categories;
+CREATE TRIGGER trg_categories_fts_sync
+AFTER UPDATE OF name ON categories
+FOR EACH ROW
+EXECUTE FUNCTION categories_fts_sync();
+
+CREATE OR REPLACE FUNCTION customers_fts_sync()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM refresh_customer_search(NEW.id);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_customers_fts_sync ON customers;
+CREATE TRIGGER trg_customers_fts_sync
+AFTER INSERT OR UPDATE OF first_name, last_name, company_name, email, phone, tax_id ON customers
+FOR EACH ROW
+EXECUTE FUNCTION customers_fts_sync();
+
+-- Index rows that existed before the search index (no-op once indexed)
+SELECT refresh_product_search(id) FROM products WHERE id NOT IN (SELECT product_id FROM products_fts);
+
+SELECT refresh_customer_search(id) FROM customers WHERE id NOT IN (SELECT customer_id FROM customers_fts);
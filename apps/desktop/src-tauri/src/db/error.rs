@@ -41,6 +41,14 @@ pub enum DatabaseError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    /// Module disabled for the shop
+    #[error("Module '{0}' is not enabled for this shop")]
+    ModuleDisabled(String),
+
+    /// Shop database temporarily unavailable (e.g. being migrated)
+    #[error("Shop database unavailable: {0}")]
+    ShopUnavailable(String),
+
     /// IO error
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
@@ -90,6 +98,16 @@ impl DatabaseError {
         Self::Validation(message.into())
     }
 
+    /// Create a new module disabled error
+    pub fn module_disabled(module_code: impl Into<String>) -> Self {
+        Self::ModuleDisabled(module_code.into())
+    }
+
+    /// Create a new shop unavailable error
+    pub fn shop_unavailable(message: impl Into<String>) -> Self {
+        Self::ShopUnavailable(message.into())
+    }
+
     /// Create a new internal error
     pub fn internal(message: impl Into<String>) -> Self {
         Self::Internal(message.into())
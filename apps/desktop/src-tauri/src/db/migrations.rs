@@ -5,7 +5,7 @@
 //! - Shop databases (products, customers, orders, etc.)
 
 use crate::db::error::{DatabaseError, DbResult};
-use crate::db::pool_manager::PoolManager;
+use crate::db::pool_manager::{PoolManager, ShopPool};
 use crate::db::types::DatabaseType;
 use sqlx::{PgPool, SqlitePool};
 use std::sync::Arc;
@@ -62,6 +62,21 @@ impl MigrationService {
         }
     }
 
+    /// Migrate a shop database reached through a standalone pool.
+    ///
+    /// Used for databases the shop's configuration doesn't point to yet,
+    /// such as the target of a SQLite/Postgres move.
+    pub async fn migrate_shop_pool(&self, pool: &ShopPool, name: &str) -> DbResult<()> {
+        match pool {
+            ShopPool::Sqlite(pool) => {
+                self.run_migration_sqlite(pool, SHOP_SCHEMA_SQLITE, name).await
+            }
+            ShopPool::Postgres(pool) => {
+                self.run_migration_postgres(pool, SHOP_SCHEMA_POSTGRES, name).await
+            }
+        }
+    }
+
     /// Run a migration script on a SQLite pool.
     async fn run_migration_sqlite(&self, pool: &SqlitePool, sql: &str, name: &str) -> DbResult<()> {
         // Check if migration tracking table exists
@@ -316,12 +331,16 @@ impl MigrationService {
     }
 
     /// Split SQL into individual statements.
+    ///
+    /// Splits on `;` outside of string literals, quoted identifiers, `--`
+    /// comments and dollar-quoted (`$$`/`$tag$`) bodies. Semicolons inside
+    /// `BEGIN ... END` and `CASE ... END` blocks are kept, so SQLite trigger
+    /// bodies stay in one statement.
     fn split_sql_statements<'a>(&self, sql: &'a str) -> Vec<&'a str> {
-        // Simple split by semicolon, but respect string literals
         // Uses byte indices for correct UTF-8 handling
         let mut statements = Vec::new();
         let mut start_byte = 0;
-        let mut in_string = false;
+        let mut block_depth: usize = 0;
 
         let bytes = sql.as_bytes();
         let mut i = 0;
@@ -329,11 +348,39 @@ impl MigrationService {
         while i < bytes.len() {
             let b = bytes[i];
 
-            if !in_string {
-                if b == b'\'' {
-                    // Entering a string
-                    in_string = true;
-                } else if b == b';' {
+            match b {
+                b'\'' | b'"' => {
+                    // Skip a string literal or quoted identifier ('' / "" escape themselves)
+                    i += 1;
+                    while i < bytes.len() {
+                        if bytes[i] == b {
+                            if i + 1 < bytes.len() && bytes[i + 1] == b {
+                                i += 1;
+                            } else {
+                                break;
+                            }
+                        }
+                        i += 1;
+                    }
+                }
+                b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                    // Skip a line comment
+                    while i < bytes.len() && bytes[i] != b'\n' {
+                        i += 1;
+                    }
+                }
+                b'$' => {
+                    // Skip a dollar-quoted body up to its matching tag
+                    if let Some(tag_len) = Self::dollar_tag_len(&bytes[i..]) {
+                        let tag = &sql[i..i + tag_len];
+                        i += tag_len;
+                        match sql[i..].find(tag) {
+                            Some(pos) => i += pos + tag_len - 1,
+                            None => i = bytes.len(),
+                        }
+                    }
+                }
+                b';' if block_depth == 0 => {
                     // End of statement
                     let stmt = &sql[start_byte..i];
                     if !stmt.trim().is_empty() {
@@ -341,18 +388,22 @@ impl MigrationService {
                     }
                     start_byte = i + 1;
                 }
-            } else {
-                // We're in a string
-                if b == b'\'' {
-                    // Check if this is an escaped quote ('')
-                    if i + 1 < bytes.len() && bytes[i + 1] == b'\'' {
-                        // Escaped quote - skip both quotes
+                _ if b.is_ascii_alphabetic() || b == b'_' => {
+                    // Track BEGIN/CASE ... END nesting by whole words
+                    let word_start = i;
+                    while i + 1 < bytes.len()
+                        && (bytes[i + 1].is_ascii_alphanumeric() || bytes[i + 1] == b'_')
+                    {
                         i += 1;
-                    } else {
-                        // End of string
-                        in_string = false;
+                    }
+                    let word = &sql[word_start..=i];
+                    if word.eq_ignore_ascii_case("BEGIN") || word.eq_ignore_ascii_case("CASE") {
+                        block_depth += 1;
+                    } else if word.eq_ignore_ascii_case("END") {
+                        block_depth = block_depth.saturating_sub(1);
                     }
                 }
+                _ => {}
             }
 
             i += 1;
@@ -360,12 +411,29 @@ impl MigrationService {
 
         // Don't forget the last statement
         let last = &sql[start_byte..];
-        if !last.trim().is_empty() && !last.trim().starts_with("--") {
+        if !Self::strip_leading_comments(last.trim()).trim().is_empty() {
             statements.push(last);
         }
 
         statements
     }
+
+    /// Length of the dollar-quote tag (`$$` or `$tag$`) at the start of `bytes`, if any.
+    fn dollar_tag_len(bytes: &[u8]) -> Option<usize> {
+        if bytes.get(1).is_some_and(|c| c.is_ascii_digit()) {
+            // `$1` is a positional parameter, not a tag
+            return None;
+        }
+        let mut len = 1;
+        while len < bytes.len() {
+            match bytes[len] {
+                b'$' => return Some(len + 1),
+                c if c.is_ascii_alphanumeric() || c == b'_' => len += 1,
+                _ => return None,
+            }
+        }
+        None
+    }
 }
 
 impl std::fmt::Debug for MigrationService {
@@ -2,18 +2,35 @@
 //!
 //! Manages the registry database pool (always SQLite) and
 //! lazy-loaded shop database pools (SQLite or Postgres).
+//!
+//! Shop pools are not kept forever: `run_pool_maintenance` periodically
+//! probes every open pool, reconnects broken Postgres pools and closes the
+//! ones left idle. Opening a pool beyond `max_open_shop_pools` closes the
+//! least recently used idle one.
 
 use crate::db::error::{DatabaseError, DbResult};
-use crate::db::types::{DatabaseConfig, DatabaseType};
+use crate::db::types::{
+    DatabaseConfig, DatabaseType, JournalMode, PoolLifecycleConfig, PoolStats, ShopPoolFailure,
+    ShopPoolStats, SqliteTuning, SynchronousMode,
+};
 use crate::features::shop::models::shop_model::Shop;
-use dashmap::DashMap;
+use chrono::{DateTime, Utc};
+use dashmap::mapref::entry::Entry;
+use dashmap::{DashMap, DashSet};
 use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
-use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
-use sqlx::{PgPool, SqlitePool};
+use sqlx::sqlite::{
+    SqliteAutoVacuum, SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous,
+};
+use sqlx::{Connection, Database, PgPool, Pool, Postgres, Sqlite, SqlitePool};
 use std::path::PathBuf;
 use std::str::FromStr;
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+/// Open events kept for subscribers that fall behind
+const SHOP_OPENED_CAPACITY: usize = 64;
 
 /// Manages database connection pools for the multi-database architecture.
 ///
@@ -24,11 +41,180 @@ pub struct PoolManager {
     /// Registry database pool (always SQLite)
     registry_pool: SqlitePool,
     /// Shop SQLite database pools, keyed by shop_id
-    shop_sqlite_pools: DashMap<String, Arc<SqlitePool>>,
+    shop_sqlite_pools: DashMap<String, ShopPoolEntry<Sqlite>>,
     /// Shop Postgres database pools, keyed by shop_id
-    shop_postgres_pools: DashMap<String, Arc<PgPool>>,
+    shop_postgres_pools: DashMap<String, ShopPoolEntry<Postgres>>,
+    /// Shops whose pool failed to open, keyed by shop_id
+    failed_opens: DashMap<String, ShopPoolFailure>,
+    /// Shops whose database can't be opened right now (being migrated)
+    blocked_shops: DashSet<String>,
     /// Application data directory for SQLite database files
     data_dir: PathBuf,
+    /// Eviction and health check limits
+    lifecycle: PoolLifecycleConfig,
+    /// Shop pools closed by eviction since startup
+    evicted_total: AtomicU64,
+    /// Set once shutdown started; no pool is opened afterwards
+    closed: AtomicBool,
+    /// Ids of shops whose database was just opened and migrated
+    shop_opened: broadcast::Sender<String>,
+}
+
+/// A cached shop pool with its usage and health bookkeeping
+struct ShopPoolEntry<DB: Database> {
+    pool: Arc<Pool<DB>>,
+    opened_at: DateTime<Utc>,
+    usage: Mutex<PoolUsage>,
+}
+
+struct PoolUsage {
+    last_used: Instant,
+    last_used_at: DateTime<Utc>,
+    checkouts: u64,
+    acquire_samples: u64,
+    acquire_total: Duration,
+    acquire_max: Duration,
+    last_acquire: Option<Duration>,
+    healthy: bool,
+    last_health_check_at: Option<DateTime<Utc>>,
+    errors: u64,
+    last_error: Option<String>,
+    last_error_at: Option<DateTime<Utc>>,
+    reconnects: u64,
+}
+
+impl PoolUsage {
+    fn new() -> Self {
+        Self {
+            last_used: Instant::now(),
+            last_used_at: Utc::now(),
+            checkouts: 0,
+            acquire_samples: 0,
+            acquire_total: Duration::ZERO,
+            acquire_max: Duration::ZERO,
+            last_acquire: None,
+            healthy: true,
+            last_health_check_at: None,
+            errors: 0,
+            last_error: None,
+            last_error_at: None,
+            reconnects: 0,
+        }
+    }
+
+    fn touch(&mut self) {
+        self.last_used = Instant::now();
+        self.last_used_at = Utc::now();
+        self.checkouts += 1;
+    }
+
+    fn record_probe(&mut self, result: &Result<Duration, String>) {
+        self.last_health_check_at = Some(Utc::now());
+        match result {
+            Ok(waited) => {
+                self.healthy = true;
+                self.acquire_samples += 1;
+                self.acquire_total += *waited;
+                self.acquire_max = self.acquire_max.max(*waited);
+                self.last_acquire = Some(*waited);
+            }
+            Err(e) => {
+                self.healthy = false;
+                self.record_error(e.clone());
+            }
+        }
+    }
+
+    fn record_error(&mut self, error: String) {
+        self.errors += 1;
+        self.last_error = Some(error);
+        self.last_error_at = Some(Utc::now());
+    }
+}
+
+fn duration_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+impl<DB: Database> ShopPoolEntry<DB> {
+    fn new(pool: Pool<DB>) -> Self {
+        Self::with_usage(Arc::new(pool), PoolUsage::new())
+    }
+
+    fn with_usage(pool: Arc<Pool<DB>>, usage: PoolUsage) -> Self {
+        Self {
+            pool,
+            opened_at: Utc::now(),
+            usage: Mutex::new(usage),
+        }
+    }
+
+    fn usage(&self) -> MutexGuard<'_, PoolUsage> {
+        self.usage.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Hand out the pool, counting it as used
+    fn checkout(&self) -> Arc<Pool<DB>> {
+        self.usage().touch();
+        Arc::clone(&self.pool)
+    }
+
+    /// Whether anything still holds the pool or one of its connections
+    fn in_use(&self) -> bool {
+        Arc::strong_count(&self.pool) > 1 || self.pool.size() as usize > self.pool.num_idle()
+    }
+
+    fn idle_for(&self) -> Duration {
+        self.usage().last_used.elapsed()
+    }
+
+    fn stats(&self, shop_id: &str, database_type: DatabaseType) -> ShopPoolStats {
+        let usage = self.usage();
+        ShopPoolStats {
+            shop_id: shop_id.to_string(),
+            database_type,
+            size: self.pool.size(),
+            idle: self.pool.num_idle(),
+            max_connections: self.pool.options().get_max_connections(),
+            opened_at: self.opened_at,
+            last_used_at: usage.last_used_at,
+            idle_secs: usage.last_used.elapsed().as_secs(),
+            checkouts: usage.checkouts,
+            acquire_samples: usage.acquire_samples,
+            avg_acquire_ms: (usage.acquire_samples > 0)
+                .then(|| duration_ms(usage.acquire_total) / usage.acquire_samples as f64),
+            max_acquire_ms: (usage.acquire_samples > 0).then(|| duration_ms(usage.acquire_max)),
+            last_acquire_ms: usage.last_acquire.map(duration_ms),
+            healthy: usage.healthy,
+            last_health_check_at: usage.last_health_check_at,
+            errors: usage.errors,
+            last_error: usage.last_error.clone(),
+            last_error_at: usage.last_error_at,
+            reconnects: usage.reconnects,
+        }
+    }
+}
+
+/// Acquire a connection and ping it, returning how long the acquire waited.
+async fn probe_pool<DB: Database>(pool: &Pool<DB>, timeout: Duration) -> Result<Duration, String> {
+    let probe = async {
+        let started = Instant::now();
+        let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+        let waited = started.elapsed();
+        conn.ping().await.map_err(|e| e.to_string())?;
+        Ok(waited)
+    };
+
+    tokio::time::timeout(timeout, probe)
+        .await
+        .unwrap_or_else(|_| Err(format!("Health check timed out after {:?}", timeout)))
+}
+
+/// Close a pool, giving in-flight queries up to `timeout` to finish.
+async fn close_pool<DB: Database>(pool: &Pool<DB>, timeout: Duration) {
+    if tokio::time::timeout(timeout, pool.close()).await.is_err() {
+        eprintln!("[pool_manager] pool close timed out after {:?}", timeout);
+    }
 }
 
 impl PoolManager {
@@ -38,7 +224,13 @@ impl PoolManager {
             registry_pool,
             shop_sqlite_pools: DashMap::new(),
             shop_postgres_pools: DashMap::new(),
+            failed_opens: DashMap::new(),
+            blocked_shops: DashSet::new(),
             data_dir,
+            lifecycle: PoolLifecycleConfig::default(),
+            evicted_total: AtomicU64::new(0),
+            closed: AtomicBool::new(false),
+            shop_opened: broadcast::channel(SHOP_OPENED_CAPACITY).0,
         }
     }
 
@@ -57,7 +249,10 @@ impl PoolManager {
         let connect_options = SqliteConnectOptions::from_str(&registry_url)
             .map_err(|e| DatabaseError::connection(e.to_string()))?
             .create_if_missing(true)
-            .foreign_keys(true);
+            .foreign_keys(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .synchronous(SqliteSynchronous::Normal)
+            .busy_timeout(Duration::from_secs(10));
 
         let registry_pool = SqlitePoolOptions::new()
             .max_connections(5)
@@ -77,23 +272,30 @@ impl PoolManager {
         &self.registry_pool
     }
 
+    /// Get the eviction and health check limits.
+    pub fn lifecycle(&self) -> &PoolLifecycleConfig {
+        &self.lifecycle
+    }
+
     /// Get or create a shop database pool (SQLite).
     ///
     /// Shop databases are lazy-loaded on first access. If the pool doesn't exist,
     /// it will be created based on the shop's database configuration.
     /// This method returns SQLite pool - use get_shop_pool_with_config for Postgres.
     pub async fn get_shop_pool(&self, shop_id: &str) -> DbResult<Arc<SqlitePool>> {
+        self.ensure_shop_available(shop_id)?;
+
         // Check if SQLite pool already exists
-        if let Some(pool) = self.shop_sqlite_pools.get(shop_id) {
-            return Ok(Arc::clone(&pool));
+        if let Some(entry) = self.shop_sqlite_pools.get(shop_id) {
+            return Ok(entry.checkout());
         }
 
-        // Create new SQLite pool for this shop (default)
-        let pool = self.create_sqlite_shop_pool(shop_id, &DatabaseConfig::default()).await?;
-        let pool = Arc::new(pool);
-        self.shop_sqlite_pools.insert(shop_id.to_string(), Arc::clone(&pool));
-
-        Ok(pool)
+        // Create new SQLite pool for this shop, tuned by its configuration
+        let config = match self.get_shop_database_config(shop_id).await {
+            Ok(config) if config.database_type == DatabaseType::Sqlite => config,
+            _ => DatabaseConfig::default(),
+        };
+        self.open_sqlite_shop_pool(shop_id, &config).await
     }
 
     /// Get or create a shop database pool with specific configuration.
@@ -103,34 +305,55 @@ impl PoolManager {
         shop_id: &str,
         config: &DatabaseConfig,
     ) -> DbResult<ShopPool> {
+        self.ensure_shop_available(shop_id)?;
+
         match config.database_type {
             DatabaseType::Sqlite => {
                 // Check if SQLite pool already exists
-                if let Some(pool) = self.shop_sqlite_pools.get(shop_id) {
-                    return Ok(ShopPool::Sqlite(Arc::clone(&pool)));
+                if let Some(entry) = self.shop_sqlite_pools.get(shop_id) {
+                    return Ok(ShopPool::Sqlite(entry.checkout()));
                 }
 
                 // Create new SQLite pool
-                let pool = self.create_sqlite_shop_pool(shop_id, config).await?;
-                let pool = Arc::new(pool);
-                self.shop_sqlite_pools.insert(shop_id.to_string(), Arc::clone(&pool));
+                let pool = self.open_sqlite_shop_pool(shop_id, config).await?;
                 Ok(ShopPool::Sqlite(pool))
             }
             DatabaseType::Postgres => {
                 // Check if Postgres pool already exists
-                if let Some(pool) = self.shop_postgres_pools.get(shop_id) {
-                    return Ok(ShopPool::Postgres(Arc::clone(&pool)));
+                if let Some(entry) = self.shop_postgres_pools.get(shop_id) {
+                    return Ok(ShopPool::Postgres(entry.checkout()));
                 }
 
                 // Create new Postgres pool
-                let pool = self.create_postgres_shop_pool(shop_id, config).await?;
-                let pool = Arc::new(pool);
-                self.shop_postgres_pools.insert(shop_id.to_string(), Arc::clone(&pool));
+                let pool = self.open_postgres_shop_pool(shop_id, config).await?;
                 Ok(ShopPool::Postgres(pool))
             }
         }
     }
 
+    /// Get an open SQLite shop pool without counting it as use.
+    ///
+    /// Background jobs use this so they don't keep idle shops open.
+    pub fn peek_shop_pool(&self, shop_id: &str) -> Option<Arc<SqlitePool>> {
+        self.shop_sqlite_pools
+            .get(shop_id)
+            .map(|entry| Arc::clone(&entry.pool))
+    }
+
+    /// Announce that a shop's database was opened and is ready for use.
+    pub fn notify_shop_opened(&self, shop_id: &str) {
+        // Fails only when nobody is subscribed, which is fine
+        let _ = self.shop_opened.send(shop_id.to_string());
+    }
+
+    /// Follow shops as their database gets opened.
+    ///
+    /// Background jobs use this to catch up on shops that were closed at
+    /// their last run.
+    pub fn subscribe_shop_opened(&self) -> broadcast::Receiver<String> {
+        self.shop_opened.subscribe()
+    }
+
     /// Get shop database configuration from registry.
     pub async fn get_shop_database_config(&self, shop_id: &str) -> DbResult<DatabaseConfig> {
         let shop: Option<Shop> = sqlx::query_as::<_, Shop>(
@@ -152,6 +375,136 @@ impl PoolManager {
         }
     }
 
+    /// Create a SQLite shop pool and cache it.
+    ///
+    /// When another caller opened the same shop meanwhile, its pool is kept
+    /// and the new one closed.
+    async fn open_sqlite_shop_pool(
+        &self,
+        shop_id: &str,
+        config: &DatabaseConfig,
+    ) -> DbResult<Arc<SqlitePool>> {
+        self.ensure_open()?;
+        let pool = self
+            .create_sqlite_shop_pool(shop_id, config)
+            .await
+            .map_err(|e| self.record_failed_open(shop_id, e))?;
+        self.failed_opens.remove(shop_id);
+
+        // Checked and inserted under the map's lock, so a pool opened
+        // concurrently is never overwritten
+        let opened = ShopPoolEntry::new(pool);
+        let (pool, duplicate) = match self.shop_sqlite_pools.entry(shop_id.to_string()) {
+            Entry::Occupied(existing) => (existing.get().checkout(), Some(opened)),
+            Entry::Vacant(slot) => (slot.insert(opened).checkout(), None),
+        };
+        if let Some(duplicate) = duplicate {
+            duplicate.pool.close().await;
+            return Ok(pool);
+        }
+
+        self.enforce_pool_limit(shop_id).await;
+        Ok(pool)
+    }
+
+    /// Create a Postgres shop pool and cache it.
+    async fn open_postgres_shop_pool(
+        &self,
+        shop_id: &str,
+        config: &DatabaseConfig,
+    ) -> DbResult<Arc<PgPool>> {
+        self.ensure_open()?;
+        let pool = self
+            .create_postgres_shop_pool(shop_id, config)
+            .await
+            .map_err(|e| self.record_failed_open(shop_id, e))?;
+        self.failed_opens.remove(shop_id);
+
+        // Checked and inserted under the map's lock, so a pool opened
+        // concurrently is never overwritten
+        let opened = ShopPoolEntry::new(pool);
+        let (pool, duplicate) = match self.shop_postgres_pools.entry(shop_id.to_string()) {
+            Entry::Occupied(existing) => (existing.get().checkout(), Some(opened)),
+            Entry::Vacant(slot) => (slot.insert(opened).checkout(), None),
+        };
+        if let Some(duplicate) = duplicate {
+            duplicate.pool.close().await;
+            return Ok(pool);
+        }
+
+        self.enforce_pool_limit(shop_id).await;
+        Ok(pool)
+    }
+
+    /// Stop handing out a shop's pool until `unblock_shop` is called.
+    ///
+    /// Returns false when the shop was already blocked.
+    pub fn block_shop(&self, shop_id: &str) -> bool {
+        self.blocked_shops.insert(shop_id.to_string())
+    }
+
+    /// Let a blocked shop's pool be opened again.
+    pub fn unblock_shop(&self, shop_id: &str) {
+        self.blocked_shops.remove(shop_id);
+    }
+
+    fn ensure_shop_available(&self, shop_id: &str) -> DbResult<()> {
+        if self.blocked_shops.contains(shop_id) {
+            return Err(DatabaseError::shop_unavailable(format!(
+                "Shop {} database is being migrated",
+                shop_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Open a standalone pool on a shop database, outside the cache.
+    ///
+    /// Used to reach a database the shop's configuration doesn't point to
+    /// (yet), such as the target of a migration. The caller closes it.
+    pub async fn connect_shop_database(
+        &self,
+        shop_id: &str,
+        config: &DatabaseConfig,
+    ) -> DbResult<ShopPool> {
+        self.ensure_open()?;
+        match config.database_type {
+            DatabaseType::Sqlite => {
+                let pool = self.create_sqlite_shop_pool(shop_id, config).await?;
+                Ok(ShopPool::Sqlite(Arc::new(pool)))
+            }
+            DatabaseType::Postgres => {
+                let pool = self.create_postgres_shop_pool(shop_id, config).await?;
+                Ok(ShopPool::Postgres(Arc::new(pool)))
+            }
+        }
+    }
+
+    fn ensure_open(&self) -> DbResult<()> {
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(DatabaseError::connection("Pool manager is shut down"));
+        }
+        Ok(())
+    }
+
+    fn record_failed_open(&self, shop_id: &str, error: DatabaseError) -> DatabaseError {
+        let message = error.to_string();
+        self.failed_opens
+            .entry(shop_id.to_string())
+            .and_modify(|failure| {
+                failure.failures += 1;
+                failure.last_error = message.clone();
+                failure.last_error_at = Utc::now();
+            })
+            .or_insert_with(|| ShopPoolFailure {
+                shop_id: shop_id.to_string(),
+                failures: 1,
+                last_error: message.clone(),
+                last_error_at: Utc::now(),
+            });
+        error
+    }
+
     /// Create a SQLite pool for a shop database.
     async fn create_sqlite_shop_pool(
         &self,
@@ -178,6 +531,7 @@ impl PoolManager {
             .map_err(|e| DatabaseError::connection(e.to_string()))?
             .create_if_missing(true)
             .foreign_keys(true);
+        let connect_options = Self::apply_sqlite_tuning(connect_options, &config.sqlite);
 
         let pool = SqlitePoolOptions::new()
             .max_connections(config.max_connections)
@@ -190,6 +544,44 @@ impl PoolManager {
         Ok(pool)
     }
 
+    /// Apply a shop's SQLite tuning to its connection options.
+    ///
+    /// Every pragma here is run on each new connection of the pool; only
+    /// `auto_vacuum` is stored in the file, and only takes effect on a new
+    /// database or after a full VACUUM.
+    fn apply_sqlite_tuning(
+        options: SqliteConnectOptions,
+        tuning: &SqliteTuning,
+    ) -> SqliteConnectOptions {
+        let journal_mode = match tuning.journal_mode {
+            JournalMode::Wal => SqliteJournalMode::Wal,
+            JournalMode::Delete => SqliteJournalMode::Delete,
+            JournalMode::Truncate => SqliteJournalMode::Truncate,
+        };
+        let synchronous = match tuning.synchronous {
+            SynchronousMode::Off => SqliteSynchronous::Off,
+            SynchronousMode::Normal => SqliteSynchronous::Normal,
+            SynchronousMode::Full => SqliteSynchronous::Full,
+            SynchronousMode::Extra => SqliteSynchronous::Extra,
+        };
+        let auto_vacuum = if tuning.incremental_vacuum {
+            SqliteAutoVacuum::Incremental
+        } else {
+            SqliteAutoVacuum::None
+        };
+
+        options
+            .auto_vacuum(auto_vacuum)
+            .journal_mode(journal_mode)
+            .synchronous(synchronous)
+            .busy_timeout(Duration::from_millis(tuning.busy_timeout_ms))
+            // Negative cache_size is in KiB rather than pages
+            .pragma("cache_size", format!("-{}", tuning.cache_size_kib))
+            .pragma("mmap_size", tuning.mmap_size_bytes.to_string())
+            // Keep query planner statistics fresh as connections close
+            .optimize_on_close(true, 400)
+    }
+
     /// Create a Postgres pool for a shop database.
     async fn create_postgres_shop_pool(
         &self,
@@ -227,6 +619,17 @@ impl PoolManager {
         self.data_dir.join("shops").join(format!("shop_{}.db", shop_id))
     }
 
+    /// Get the directory holding a shop's locally stored media files.
+    pub fn get_shop_media_dir(&self, shop_id: &str) -> PathBuf {
+        self.data_dir.join("shops").join(format!("shop_{}_media", shop_id))
+    }
+
+    /// Get the path a shop's SQLite database is built at before replacing
+    /// the one at `get_shop_db_path`.
+    pub fn get_shop_staging_db_path(&self, shop_id: &str) -> PathBuf {
+        self.data_dir.join("shops").join(format!("shop_{}.migrating.db", shop_id))
+    }
+
     /// Check if a shop database file exists (SQLite only).
     pub fn shop_db_exists(&self, shop_id: &str) -> bool {
         self.get_shop_db_path(shop_id).exists()
@@ -236,12 +639,14 @@ impl PoolManager {
     ///
     /// This should be called when a shop is deleted or its configuration changes.
     pub async fn invalidate_shop_pool(&self, shop_id: &str) {
-        if let Some((_, pool)) = self.shop_sqlite_pools.remove(shop_id) {
-            pool.close().await;
+        let timeout = Duration::from_secs(self.lifecycle.shutdown_timeout_secs);
+        if let Some((_, entry)) = self.shop_sqlite_pools.remove(shop_id) {
+            close_pool(&entry.pool, timeout).await;
         }
-        if let Some((_, pool)) = self.shop_postgres_pools.remove(shop_id) {
-            pool.close().await;
+        if let Some((_, entry)) = self.shop_postgres_pools.remove(shop_id) {
+            close_pool(&entry.pool, timeout).await;
         }
+        self.failed_opens.remove(shop_id);
     }
 
     /// Delete a shop's database file.
@@ -256,6 +661,15 @@ impl PoolManager {
         Ok(())
     }
 
+    /// Delete a shop's local media directory.
+    pub fn delete_shop_media_dir(&self, shop_id: &str) -> DbResult<()> {
+        let media_dir = self.get_shop_media_dir(shop_id);
+        if media_dir.exists() {
+            std::fs::remove_dir_all(&media_dir)?;
+        }
+        Ok(())
+    }
+
     /// Get the number of active shop pools.
     pub fn active_shop_pool_count(&self) -> usize {
         self.shop_sqlite_pools.len() + self.shop_postgres_pools.len()
@@ -266,22 +680,251 @@ impl PoolManager {
         &self.data_dir
     }
 
+    // ============================================================
+    // Eviction, health checks and stats
+    // ============================================================
+
+    /// Close the least recently used idle pools while more than
+    /// `max_open_shop_pools` are open. The pool just opened is never closed.
+    async fn enforce_pool_limit(&self, opened_shop_id: &str) {
+        while self.active_shop_pool_count() > self.lifecycle.max_open_shop_pools {
+            let candidate = self
+                .shop_sqlite_pools
+                .iter()
+                .filter(|e| e.key() != opened_shop_id && !e.in_use())
+                .map(|e| (e.key().clone(), e.idle_for()))
+                .chain(
+                    self.shop_postgres_pools
+                        .iter()
+                        .filter(|e| e.key() != opened_shop_id && !e.in_use())
+                        .map(|e| (e.key().clone(), e.idle_for())),
+                )
+                .max_by_key(|(_, idle)| *idle);
+
+            // Every other pool is busy: stay over the limit until they're released
+            let Some((shop_id, _)) = candidate else {
+                break;
+            };
+            if !self.evict_shop_pool(&shop_id, Duration::ZERO).await {
+                break;
+            }
+        }
+    }
+
+    /// Close a shop's pool if it has been unused for at least `min_idle`
+    /// and nothing holds it. Returns whether a pool was closed.
+    async fn evict_shop_pool(&self, shop_id: &str, min_idle: Duration) -> bool {
+        let evictable = |entry_in_use: bool, idle: Duration| !entry_in_use && idle >= min_idle;
+
+        if let Some((_, entry)) = self
+            .shop_sqlite_pools
+            .remove_if(shop_id, |_, e| evictable(e.in_use(), e.idle_for()))
+        {
+            entry.pool.close().await;
+            self.evicted_total.fetch_add(1, Ordering::Relaxed);
+            return true;
+        }
+        if let Some((_, entry)) = self
+            .shop_postgres_pools
+            .remove_if(shop_id, |_, e| evictable(e.in_use(), e.idle_for()))
+        {
+            entry.pool.close().await;
+            self.evicted_total.fetch_add(1, Ordering::Relaxed);
+            return true;
+        }
+        false
+    }
+
+    /// Close every shop pool unused for longer than `shop_pool_idle_secs`.
+    ///
+    /// Returns the number of pools closed.
+    pub async fn evict_idle_pools(&self) -> usize {
+        let max_idle = Duration::from_secs(self.lifecycle.shop_pool_idle_secs);
+        let shop_ids: Vec<String> = self
+            .shop_sqlite_pools
+            .iter()
+            .map(|e| e.key().clone())
+            .chain(self.shop_postgres_pools.iter().map(|e| e.key().clone()))
+            .collect();
+
+        let mut evicted = 0;
+        for shop_id in shop_ids {
+            if self.evict_shop_pool(&shop_id, max_idle).await {
+                evicted += 1;
+            }
+        }
+        evicted
+    }
+
+    /// Probe every open shop pool.
+    ///
+    /// A Postgres pool that fails its probe is replaced by a fresh one built
+    /// from the shop's current configuration; a SQLite pool is only marked
+    /// unhealthy, since its file is local.
+    pub async fn check_shop_pools(&self) {
+        let timeout = Duration::from_secs(self.lifecycle.health_check_timeout_secs);
+
+        let sqlite_pools: Vec<(String, Arc<SqlitePool>)> = self
+            .shop_sqlite_pools
+            .iter()
+            .map(|e| (e.key().clone(), Arc::clone(&e.pool)))
+            .collect();
+        for (shop_id, pool) in sqlite_pools {
+            let result = probe_pool(&pool, timeout).await;
+            if let Some(entry) = self.shop_sqlite_pools.get(&shop_id) {
+                if Arc::ptr_eq(&entry.pool, &pool) {
+                    entry.usage().record_probe(&result);
+                }
+            }
+        }
+
+        let postgres_pools: Vec<(String, Arc<PgPool>)> = self
+            .shop_postgres_pools
+            .iter()
+            .map(|e| (e.key().clone(), Arc::clone(&e.pool)))
+            .collect();
+        for (shop_id, pool) in postgres_pools {
+            let result = probe_pool(&pool, timeout).await;
+            let failed = result.is_err();
+            match self.shop_postgres_pools.get(&shop_id) {
+                Some(entry) if Arc::ptr_eq(&entry.pool, &pool) => {
+                    entry.usage().record_probe(&result)
+                }
+                _ => continue,
+            }
+            if failed {
+                self.reconnect_postgres_pool(&shop_id, pool).await;
+            }
+        }
+    }
+
+    /// Replace a broken Postgres pool, keeping its usage history.
+    async fn reconnect_postgres_pool(&self, shop_id: &str, broken: Arc<PgPool>) {
+        let reconnected = match self.get_shop_database_config(shop_id).await {
+            Ok(config) => self.create_postgres_shop_pool(shop_id, &config).await,
+            Err(e) => Err(e),
+        };
+        let pool = match reconnected {
+            Ok(pool) => Arc::new(pool),
+            Err(e) => {
+                if let Some(entry) = self.shop_postgres_pools.get(shop_id) {
+                    entry
+                        .usage()
+                        .record_error(format!("Reconnect failed: {}", e));
+                }
+                return;
+            }
+        };
+
+        let replaced = match self.shop_postgres_pools.get_mut(shop_id) {
+            Some(mut entry) if Arc::ptr_eq(&entry.pool, &broken) => {
+                let mut usage = std::mem::replace(&mut *entry.usage(), PoolUsage::new());
+                usage.reconnects += 1;
+                usage.healthy = true;
+                *entry = ShopPoolEntry::with_usage(Arc::clone(&pool), usage);
+                true
+            }
+            _ => false,
+        };
+
+        let timeout = Duration::from_secs(self.lifecycle.shutdown_timeout_secs);
+        if replaced {
+            close_pool(&broken, timeout).await;
+        } else {
+            // The pool was evicted or replaced meanwhile
+            close_pool(&pool, timeout).await;
+        }
+    }
+
+    /// Snapshot the size, usage and health of every pool.
+    pub fn pool_stats(&self) -> PoolStats {
+        let mut shops: Vec<ShopPoolStats> = self
+            .shop_sqlite_pools
+            .iter()
+            .map(|e| e.stats(e.key(), DatabaseType::Sqlite))
+            .chain(
+                self.shop_postgres_pools
+                    .iter()
+                    .map(|e| e.stats(e.key(), DatabaseType::Postgres)),
+            )
+            .collect();
+        shops.sort_by(|a, b| a.shop_id.cmp(&b.shop_id));
+
+        let mut failed_opens: Vec<ShopPoolFailure> = self
+            .failed_opens
+            .iter()
+            .map(|e| e.value().clone())
+            .collect();
+        failed_opens.sort_by(|a, b| a.shop_id.cmp(&b.shop_id));
+
+        PoolStats {
+            registry_size: self.registry_pool.size(),
+            registry_idle: self.registry_pool.num_idle(),
+            open_shop_pools: shops.len(),
+            max_open_shop_pools: self.lifecycle.max_open_shop_pools,
+            shop_pool_idle_secs: self.lifecycle.shop_pool_idle_secs,
+            evicted_total: self.evicted_total.load(Ordering::Relaxed),
+            shops,
+            failed_opens,
+        }
+    }
+
     /// Close all pools and prepare for shutdown.
+    ///
+    /// In-flight queries get `shutdown_timeout_secs` to finish before each
+    /// pool is dropped.
     pub async fn shutdown(&self) {
+        if self.closed.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let timeout = Duration::from_secs(self.lifecycle.shutdown_timeout_secs);
+
         // Close all SQLite shop pools
-        for entry in self.shop_sqlite_pools.iter() {
-            entry.value().close().await;
+        let shop_ids: Vec<String> = self
+            .shop_sqlite_pools
+            .iter()
+            .map(|e| e.key().clone())
+            .collect();
+        for shop_id in shop_ids {
+            if let Some((_, entry)) = self.shop_sqlite_pools.remove(&shop_id) {
+                close_pool(&entry.pool, timeout).await;
+            }
         }
-        self.shop_sqlite_pools.clear();
 
         // Close all Postgres shop pools
-        for entry in self.shop_postgres_pools.iter() {
-            entry.value().close().await;
+        let shop_ids: Vec<String> = self
+            .shop_postgres_pools
+            .iter()
+            .map(|e| e.key().clone())
+            .collect();
+        for shop_id in shop_ids {
+            if let Some((_, entry)) = self.shop_postgres_pools.remove(&shop_id) {
+                close_pool(&entry.pool, timeout).await;
+            }
         }
-        self.shop_postgres_pools.clear();
 
         // Close registry pool
-        self.registry_pool.close().await;
+        close_pool(&self.registry_pool, timeout).await;
+    }
+}
+
+/// Background job probing shop pools and closing idle ones
+///
+/// Started from the app setup; runs every `maintenance_interval_secs`
+/// until the pool manager shuts down.
+pub async fn run_pool_maintenance(pool_manager: Arc<PoolManager>) {
+    let interval = Duration::from_secs(pool_manager.lifecycle().maintenance_interval_secs);
+    loop {
+        tokio::time::sleep(interval).await;
+        if pool_manager.closed.load(Ordering::SeqCst) {
+            break;
+        }
+
+        pool_manager.check_shop_pools().await;
+        let evicted = pool_manager.evict_idle_pools().await;
+        if evicted > 0 {
+            eprintln!("[pool_manager] closed {} idle shop pool(s)", evicted);
+        }
     }
 }
 
@@ -291,6 +934,7 @@ impl std::fmt::Debug for PoolManager {
             .field("data_dir", &self.data_dir)
             .field("active_sqlite_pools", &self.shop_sqlite_pools.len())
             .field("active_postgres_pools", &self.shop_postgres_pools.len())
+            .field("evicted_total", &self.evicted_total.load(Ordering::Relaxed))
             .finish()
     }
 }
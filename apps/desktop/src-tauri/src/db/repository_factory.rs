@@ -3,10 +3,11 @@
 //! Provides a centralized way to obtain repository instances that are
 //! properly configured for the target database (registry or shop).
 
-use crate::db::error::DbResult;
+use crate::db::error::{DatabaseError, DbResult};
 use crate::db::migrations::MigrationService;
 use crate::db::pool_manager::{PoolManager, ShopPool};
 use crate::db::types::DatabaseConfig;
+use crate::features::module::services::modules_service::ModulesService;
 use std::sync::Arc;
 
 /// Factory for creating repository instances.
@@ -50,7 +51,8 @@ impl RepositoryFactory {
     /// This method will:
     /// 1. Check if the shop database exists
     /// 2. Create and migrate the database if needed
-    /// 3. Return the SQLite pool
+    /// 3. Announce the shop to background jobs if its pool was just opened
+    /// 4. Return the SQLite pool
     pub async fn shop_pool(&self, shop_id: &str) -> DbResult<Arc<sqlx::SqlitePool>> {
         // Get shop configuration
         let config = self.pool_manager.get_shop_database_config(shop_id).await?;
@@ -63,6 +65,7 @@ impl RepositoryFactory {
         }
 
         // Get or create the pool
+        let opening = self.pool_manager.peek_shop_pool(shop_id).is_none();
         let pool = self.pool_manager.get_shop_pool(shop_id).await?;
 
         // Always run migrations (migrate_shop checks version and only migrates if needed)
@@ -77,9 +80,32 @@ impl RepositoryFactory {
         .execute(&*pool)
         .await?;
 
+        if opening {
+            self.pool_manager.notify_shop_opened(shop_id);
+        }
         Ok(pool)
     }
 
+    /// Get a shop's database pool for a command of an optional module.
+    ///
+    /// Fails with `ModuleDisabled` unless the module is enabled for the shop
+    /// and every module it requires is active too.
+    pub async fn shop_module_pool(
+        &self,
+        shop_id: &str,
+        module_code: &str,
+    ) -> DbResult<Arc<sqlx::SqlitePool>> {
+        let active = ModulesService::new(self.registry_pool().clone())
+            .is_module_active(shop_id, module_code)
+            .await
+            .map_err(DatabaseError::internal)?;
+        if !active {
+            return Err(DatabaseError::module_disabled(module_code));
+        }
+
+        self.shop_pool(shop_id).await
+    }
+
     /// Get a shop's database pool with configuration (supports both SQLite and Postgres).
     ///
     /// This method will:
@@ -187,7 +213,7 @@ impl RepositoryFactory {
 
     /// Delete a shop's database.
     ///
-    /// This closes the pool and removes the database file.
+    /// This closes the pool and removes the database file and local media.
     /// Call this when deleting a shop (after soft-deleting from registry).
     pub async fn delete_shop_database(&self, shop_id: &str) -> DbResult<()> {
         // Close and remove the pool
@@ -196,6 +222,9 @@ impl RepositoryFactory {
         // Delete the database file
         self.pool_manager.delete_shop_db(shop_id)?;
 
+        // Delete the media files stored alongside it
+        self.pool_manager.delete_shop_media_dir(shop_id)?;
+
         Ok(())
     }
 
@@ -1,5 +1,6 @@
 //! Database types and configuration
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Supported database types for shop databases
@@ -57,6 +58,9 @@ pub struct DatabaseConfig {
     /// Idle connection timeout in seconds
     #[serde(default = "default_idle_timeout")]
     pub idle_timeout_secs: u64,
+    /// Per-connection settings for SQLite shops (ignored for Postgres)
+    #[serde(default)]
+    pub sqlite: SqliteTuning,
 }
 
 fn default_max_connections() -> u32 {
@@ -84,6 +88,60 @@ impl Default for DatabaseConfig {
             min_connections: default_min_connections(),
             connect_timeout_secs: default_connect_timeout(),
             idle_timeout_secs: default_idle_timeout(),
+            sqlite: SqliteTuning::default(),
+        }
+    }
+}
+
+/// SQLite journal mode of a shop database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalMode {
+    /// Write-ahead log: readers don't block the writer (default)
+    #[default]
+    Wal,
+    Delete,
+    Truncate,
+}
+
+/// SQLite `synchronous` level of a shop database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SynchronousMode {
+    Off,
+    /// Durable in WAL mode except for the last commits on power loss (default)
+    #[default]
+    Normal,
+    Full,
+    Extra,
+}
+
+/// Settings applied to every connection of a shop's SQLite pool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SqliteTuning {
+    pub journal_mode: JournalMode,
+    pub synchronous: SynchronousMode,
+    /// How long a connection waits for a lock before failing with "database is locked"
+    pub busy_timeout_ms: u64,
+    /// Page cache per connection, in KiB
+    pub cache_size_kib: u32,
+    /// Bytes of the file memory-mapped per connection; 0 disables mmap
+    pub mmap_size_bytes: u64,
+    /// Create new databases with `auto_vacuum = INCREMENTAL`, so maintenance
+    /// can return free pages to the filesystem without a full VACUUM
+    pub incremental_vacuum: bool,
+}
+
+impl Default for SqliteTuning {
+    fn default() -> Self {
+        Self {
+            journal_mode: JournalMode::Wal,
+            synchronous: SynchronousMode::Normal,
+            busy_timeout_ms: 10_000,
+            cache_size_kib: 16_384,
+            mmap_size_bytes: 128 * 1024 * 1024,
+            incremental_vacuum: true,
         }
     }
 }
@@ -133,3 +191,82 @@ pub struct ShopDatabase {
     /// Full configuration
     pub config: DatabaseConfig,
 }
+
+/// Limits applied by the PoolManager to the shop pools it keeps open
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolLifecycleConfig {
+    /// Maximum number of shop pools open at once; the least recently used
+    /// idle pool is closed when a new one would exceed it
+    pub max_open_shop_pools: usize,
+    /// Close a shop pool after this many seconds without use
+    pub shop_pool_idle_secs: u64,
+    /// Interval between health checks and idle eviction sweeps
+    pub maintenance_interval_secs: u64,
+    /// Timeout of a single health probe
+    pub health_check_timeout_secs: u64,
+    /// Time given to in-flight queries when pools are closed at shutdown
+    pub shutdown_timeout_secs: u64,
+}
+
+impl Default for PoolLifecycleConfig {
+    fn default() -> Self {
+        Self {
+            max_open_shop_pools: 8,
+            shop_pool_idle_secs: 900,
+            maintenance_interval_secs: 60,
+            health_check_timeout_secs: 5,
+            shutdown_timeout_secs: 10,
+        }
+    }
+}
+
+/// Usage and health of one open shop pool
+#[derive(Debug, Clone, Serialize)]
+pub struct ShopPoolStats {
+    pub shop_id: String,
+    pub database_type: DatabaseType,
+    /// Open connections
+    pub size: u32,
+    /// Open connections not checked out
+    pub idle: usize,
+    pub max_connections: u32,
+    pub opened_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub idle_secs: u64,
+    /// Times the pool was handed out since it was opened
+    pub checkouts: u64,
+    /// Connection acquire waits, sampled by the health probes
+    pub acquire_samples: u64,
+    pub avg_acquire_ms: Option<f64>,
+    pub max_acquire_ms: Option<f64>,
+    pub last_acquire_ms: Option<f64>,
+    pub healthy: bool,
+    pub last_health_check_at: Option<DateTime<Utc>>,
+    pub errors: u64,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+    pub reconnects: u64,
+}
+
+/// A shop whose pool could not be opened
+#[derive(Debug, Clone, Serialize)]
+pub struct ShopPoolFailure {
+    pub shop_id: String,
+    pub failures: u64,
+    pub last_error: String,
+    pub last_error_at: DateTime<Utc>,
+}
+
+/// Snapshot of every pool held by the PoolManager
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolStats {
+    pub registry_size: u32,
+    pub registry_idle: usize,
+    pub open_shop_pools: usize,
+    pub max_open_shop_pools: usize,
+    pub shop_pool_idle_secs: u64,
+    /// Shop pools closed for being idle or least recently used
+    pub evicted_total: u64,
+    pub shops: Vec<ShopPoolStats>,
+    pub failed_opens: Vec<ShopPoolFailure>,
+}
@@ -4,6 +4,7 @@ use tauri::State;
 use crate::db::RepositoryFactory;
 use crate::features::analytics::dtos::analytics_dto::*;
 use crate::features::analytics::services::analytics_service::AnalyticsService;
+use crate::features::analytics::services::consolidated_analytics_service::ConsolidatedAnalyticsService;
 
 async fn analytics_service(
     repo_factory: &RepositoryFactory,
@@ -81,9 +82,10 @@ pub async fn get_top_products(
 pub async fn get_revenue_by_category(
     repo_factory: State<'_, Arc<RepositoryFactory>>,
     shop_id: String,
+    rollup: Option<bool>,
 ) -> Result<Vec<RevenueByCategoryDto>, String> {
     let service = analytics_service(repo_factory.inner(), &shop_id).await?;
-    service.get_revenue_by_category(Some(shop_id)).await
+    service.get_revenue_by_category(Some(shop_id), rollup).await
 }
 
 #[tauri::command]
@@ -292,3 +294,63 @@ pub async fn get_rating_distribution(
     let service = analytics_service(repo_factory.inner(), &shop_id).await?;
     service.get_rating_distribution(Some(shop_id)).await
 }
+
+#[tauri::command]
+pub async fn get_price_realization(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    days: Option<i64>,
+    limit: Option<i64>,
+) -> Result<Vec<PriceRealizationDto>, String> {
+    let service = analytics_service(repo_factory.inner(), &shop_id).await?;
+    service.get_price_realization(Some(shop_id), days, limit).await
+}
+
+// ============================================================
+// Consolidated (multi-shop) reports
+// ============================================================
+
+#[tauri::command]
+pub async fn get_consolidated_revenue(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    payload: ConsolidatedAnalyticsFilterDto,
+) -> Result<ConsolidatedRevenueDto, String> {
+    let service = ConsolidatedAnalyticsService::new(repo_factory.inner().clone());
+    service.get_revenue(payload).await
+}
+
+#[tauri::command]
+pub async fn get_consolidated_top_products(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    payload: ConsolidatedAnalyticsFilterDto,
+) -> Result<ConsolidatedTopProductsDto, String> {
+    let service = ConsolidatedAnalyticsService::new(repo_factory.inner().clone());
+    service.get_top_products(payload).await
+}
+
+#[tauri::command]
+pub async fn get_consolidated_stock_status(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    payload: ConsolidatedAnalyticsFilterDto,
+) -> Result<ConsolidatedStockStatusDto, String> {
+    let service = ConsolidatedAnalyticsService::new(repo_factory.inner().clone());
+    service.get_stock_status(payload).await
+}
+
+#[tauri::command]
+pub async fn get_consolidated_average_order_value(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    payload: ConsolidatedAnalyticsFilterDto,
+) -> Result<ConsolidatedAverageOrderValueDto, String> {
+    let service = ConsolidatedAnalyticsService::new(repo_factory.inner().clone());
+    service.get_average_order_value(payload).await
+}
+
+#[tauri::command]
+pub async fn get_consolidated_customer_growth(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    payload: ConsolidatedAnalyticsFilterDto,
+) -> Result<ConsolidatedCustomerGrowthDto, String> {
+    let service = ConsolidatedAnalyticsService::new(repo_factory.inner().clone());
+    service.get_customer_growth(payload).await
+}
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::db::RepositoryFactory;
+use crate::features::analytics::dtos::customer_metrics_dto::*;
+use crate::features::analytics::services::customer_metrics_service::ShopCustomerMetricsService;
+
+async fn customer_metrics_service(
+    repo_factory: &RepositoryFactory,
+    shop_id: String,
+) -> Result<ShopCustomerMetricsService, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    Ok(ShopCustomerMetricsService::new(pool, shop_id))
+}
+
+#[tauri::command]
+pub async fn refresh_customer_metrics(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    horizon_months: Option<i64>,
+) -> Result<CustomerMetricsRefreshDto, String> {
+    let service = customer_metrics_service(repo_factory.inner(), shop_id).await?;
+    service.refresh(horizon_months).await
+}
+
+#[tauri::command]
+pub async fn list_customer_metrics(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: Option<CustomerMetricsFilterDto>,
+) -> Result<Vec<CustomerMetricsDto>, String> {
+    let service = customer_metrics_service(repo_factory.inner(), shop_id).await?;
+    service.list_metrics(payload.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn get_customer_metrics(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    customer_id: String,
+) -> Result<Option<CustomerMetricsDto>, String> {
+    let service = customer_metrics_service(repo_factory.inner(), shop_id).await?;
+    service.get_metrics(&customer_id).await
+}
+
+#[tauri::command]
+pub async fn get_rfm_segment_summary(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+) -> Result<Vec<RfmSegmentSummaryDto>, String> {
+    let service = customer_metrics_service(repo_factory.inner(), shop_id).await?;
+    service.segment_summary().await
+}
+
+#[tauri::command]
+pub async fn get_customer_cohorts(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    months: Option<i64>,
+) -> Result<Vec<CustomerCohortDto>, String> {
+    let service = customer_metrics_service(repo_factory.inner(), shop_id).await?;
+    service.cohorts(months).await
+}
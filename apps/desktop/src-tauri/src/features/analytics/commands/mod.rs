@@ -1 +1,2 @@
 pub mod analytics_commands;
+pub mod customer_metrics_commands;
@@ -67,6 +67,7 @@ pub struct TopProductDto {
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RevenueByCategoryDto {
+    pub category_id: String,
     pub category_name: String,
     pub total_revenue: f64,
     pub product_count: i64,
@@ -294,3 +295,112 @@ pub struct RatingDistributionDto {
     pub count: i64,
     pub percentage: f64,
 }
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceRealizationDto {
+    pub product_id: String,
+    pub product_name: String,
+    pub total_quantity: f64,
+    pub total_revenue: f64,
+    pub list_revenue: f64,
+    pub discount_amount: f64,
+    pub realization_rate: f64,
+}
+
+// Consolidated (multi-shop) DTOs
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsolidatedAnalyticsFilterDto {
+    pub owner_id: String,
+    pub shop_ids: Option<Vec<String>>, // Only these shops of the owner
+    pub currency: Option<String>,      // Report currency, defaults to the one most shops use
+    pub days: Option<i64>,
+    pub months: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedShopDto {
+    pub shop_id: String,
+    pub shop_name: String,
+    pub reason: String,
+}
+
+/// One shop's share of a consolidated report, amounts in the report currency
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShopBreakdownDto<T> {
+    pub shop_id: String,
+    pub shop_name: String,
+    pub currency: String,
+    pub exchange_rate: Option<f64>,
+    pub data: T,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShopRevenueDto {
+    pub total_revenue: f64,
+    pub order_count: i64,
+    pub avg_order_value: f64,
+    pub revenue_share: f64,
+    pub monthly: Vec<MonthlySalesDto>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsolidatedRevenueDto {
+    pub currency: String,
+    pub total_revenue: f64,
+    pub order_count: i64,
+    pub avg_order_value: f64,
+    pub monthly: Vec<MonthlySalesDto>,
+    pub shops: Vec<ShopBreakdownDto<ShopRevenueDto>>,
+    pub skipped_shops: Vec<SkippedShopDto>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsolidatedTopProductDto {
+    pub product_name: String,
+    pub total_quantity: f64,
+    pub total_revenue: f64,
+    pub order_count: i64,
+    pub shop_count: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsolidatedTopProductsDto {
+    pub currency: String,
+    pub products: Vec<ConsolidatedTopProductDto>,
+    pub shops: Vec<ShopBreakdownDto<Vec<TopProductDto>>>,
+    pub skipped_shops: Vec<SkippedShopDto>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsolidatedStockStatusDto {
+    pub statuses: Vec<StockStatusDto>,
+    pub shops: Vec<ShopBreakdownDto<Vec<StockStatusDto>>>,
+    pub skipped_shops: Vec<SkippedShopDto>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsolidatedAverageOrderValueDto {
+    pub currency: String,
+    pub monthly: Vec<AverageOrderValueDto>,
+    pub shops: Vec<ShopBreakdownDto<Vec<AverageOrderValueDto>>>,
+    pub skipped_shops: Vec<SkippedShopDto>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsolidatedCustomerGrowthDto {
+    pub monthly: Vec<CustomerGrowthDto>,
+    pub shops: Vec<ShopBreakdownDto<Vec<CustomerGrowthDto>>>,
+    pub skipped_shops: Vec<SkippedShopDto>,
+}
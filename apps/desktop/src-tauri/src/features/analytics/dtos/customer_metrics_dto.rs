@@ -0,0 +1,75 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Stored metrics of one customer
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomerMetricsDto {
+    pub customer_id: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub company_name: Option<String>,
+    pub email: Option<String>,
+    pub accepts_marketing: Option<bool>,
+    pub first_order_at: DateTime<Utc>,
+    pub last_order_at: DateTime<Utc>,
+    pub orders_count: i64,
+    pub total_spent: f64,
+    pub recency_days: i64,
+    pub recency_score: i64,
+    pub frequency_score: i64,
+    pub monetary_score: i64,
+    pub rfm_segment: String,
+    pub expected_order_gap_days: f64,
+    pub churn_probability: f64,
+    pub predicted_clv: f64,
+    pub clv_horizon_months: i64,
+    pub computed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomerMetricsFilterDto {
+    pub rfm_segment: Option<String>,
+    pub min_churn_probability: Option<f64>,
+    pub accepts_marketing: Option<bool>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct RfmSegmentSummaryDto {
+    pub rfm_segment: String,
+    pub customers: i64,
+    pub total_spent: f64,
+    pub avg_churn_probability: f64,
+    pub predicted_clv: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomerMetricsRefreshDto {
+    pub customers_scored: i64,
+    pub median_order_gap_days: f64,
+    pub clv_horizon_months: i64,
+    pub segments: Vec<RfmSegmentSummaryDto>,
+}
+
+/// Customers whose first purchase fell in `cohort` (YYYY-MM) and how many
+/// of them bought again in each following month
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomerCohortDto {
+    pub cohort: String,
+    pub customers: i64,
+    pub periods: Vec<CohortPeriodDto>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CohortPeriodDto {
+    pub month_offset: i64,
+    pub month: String,
+    pub active_customers: i64,
+    pub retention_rate: f64,
+}
@@ -1 +1,2 @@
 pub mod analytics_dto;
+pub mod customer_metrics_dto;
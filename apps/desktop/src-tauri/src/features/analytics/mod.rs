@@ -2,4 +2,3 @@ pub mod commands;
 pub mod dtos;
 pub mod repositories;
 pub mod services;
-pub mod utils;
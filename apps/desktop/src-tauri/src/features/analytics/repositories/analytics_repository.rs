@@ -1,4 +1,5 @@
-use crate::features::analytics::utils::module_checker;
+use crate::features::module::utils::module_checker;
+use crate::features::category::repositories::shop_category_repository::CATEGORY_CLOSURE_CTE;
 use sqlx::SqlitePool;
 
 #[derive(Debug, sqlx::FromRow)]
@@ -54,6 +55,7 @@ pub struct TopProductRow {
 
 #[derive(Debug, sqlx::FromRow)]
 pub struct RevenueByCategoryRow {
+    pub category_id: String,
     pub category_name: String,
     pub total_revenue: f64,
     pub product_count: i64,
@@ -241,6 +243,15 @@ pub struct RatingDistributionRow {
     pub percentage: f64,
 }
 
+#[derive(Debug, sqlx::FromRow)]
+pub struct PriceRealizationRow {
+    pub product_id: String,
+    pub product_name: String,
+    pub total_quantity: f64,
+    pub total_revenue: f64,
+    pub list_revenue: f64,
+}
+
 pub struct AnalyticsRepository {
     pool: SqlitePool,
 }
@@ -518,6 +529,7 @@ impl AnalyticsRepository {
     pub async fn get_revenue_by_category(&self) -> sqlx::Result<Vec<RevenueByCategoryRow>> {
         let sql = r#"
             SELECT
+                c.id AS category_id,
                 c.name AS category_name,
                 SUM(ti.total_line) AS total_revenue,
                 COUNT(DISTINCT ti.product_id) AS product_count,
@@ -537,6 +549,46 @@ impl AnalyticsRepository {
             .await
     }
 
+    /// Query 5 rolled up: each category also counts the sales of its
+    /// descendants. A line in several categories of one subtree counts once
+    /// for their common ancestors. Shop DB only.
+    pub async fn get_revenue_by_category_rollup(&self) -> sqlx::Result<Vec<RevenueByCategoryRow>> {
+        let sql = format!(
+            r#"
+            WITH RECURSIVE {},
+            category_lines AS (
+                SELECT DISTINCT
+                    cc.ancestor_id AS category_id,
+                    ti.id AS item_id,
+                    ti.total_line,
+                    ti.product_id,
+                    t.id AS transaction_id
+                FROM transaction_items ti
+                INNER JOIN transactions t ON t.id = ti.transaction_id AND t._status != 'deleted'
+                INNER JOIN products p ON p.id = ti.product_id AND p._status != 'deleted'
+                INNER JOIN product_categories pc ON pc.product_id = p.id
+                INNER JOIN category_closure cc ON cc.category_id = pc.category_id
+                WHERE t.type = 'sale'
+                  AND t.status = 'completed'
+            )
+            SELECT
+                c.id AS category_id,
+                c.name AS category_name,
+                SUM(cl.total_line) AS total_revenue,
+                COUNT(DISTINCT cl.product_id) AS product_count,
+                COUNT(DISTINCT cl.transaction_id) AS order_count
+            FROM category_lines cl
+            INNER JOIN categories c ON c.id = cl.category_id
+            GROUP BY c.id, c.name
+            ORDER BY total_revenue DESC
+            "#,
+            CATEGORY_CLOSURE_CTE
+        );
+        sqlx::query_as::<_, RevenueByCategoryRow>(&sql)
+            .fetch_all(&self.pool)
+            .await
+    }
+
     /// Query 6: Vendas Mensais. Shop DB only.
     pub async fn get_monthly_sales(
         &self,
@@ -1257,4 +1309,43 @@ impl AnalyticsRepository {
             .fetch_all(&self.pool)
             .await
     }
+
+    /// Query 27: Realização de Preço (vendido vs. preço de tabela na data da venda). Shop DB only.
+    pub async fn get_price_realization(
+        &self,
+        days: i64,
+        limit: i64,
+    ) -> sqlx::Result<Vec<PriceRealizationRow>> {
+        let sql = r#"
+            SELECT
+                ti.product_id,
+                COALESCE(ti.name_snapshot, p.name) AS product_name,
+                SUM(ti.quantity) AS total_quantity,
+                SUM(ti.total_line) AS total_revenue,
+                SUM(ti.quantity * COALESCE(
+                    (SELECT ph.price FROM price_history ph
+                     WHERE ph.product_id = ti.product_id
+                       AND ph.effective_from <= t.created_at
+                       AND (ph.effective_to IS NULL OR ph.effective_to > t.created_at)
+                     ORDER BY ph.effective_from DESC
+                     LIMIT 1),
+                    p.price,
+                    ti.unit_price
+                )) AS list_revenue
+            FROM transaction_items ti
+            LEFT JOIN products p ON p.id = ti.product_id AND p._status != 'deleted'
+            INNER JOIN transactions t ON t.id = ti.transaction_id AND t._status != 'deleted'
+            WHERE t.type = 'sale'
+              AND t.status = 'completed'
+              AND t.created_at >= date('now', '-' || $1 || ' days')
+            GROUP BY ti.product_id, COALESCE(ti.name_snapshot, p.name)
+            ORDER BY list_revenue - total_revenue DESC
+            LIMIT $2
+        "#;
+        sqlx::query_as::<_, PriceRealizationRow>(sql)
+            .bind(days)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+    }
 }
@@ -0,0 +1,194 @@
+//! Shop-scoped storage of per-customer RFM, churn and CLV metrics
+
+use crate::features::analytics::dtos::customer_metrics_dto::{
+    CustomerMetricsDto, CustomerMetricsFilterDto, RfmSegmentSummaryDto,
+};
+use crate::features::customer::repositories::shop_customer_repository::{
+    PURCHASES_SQL, RETURNED_SQL,
+};
+use chrono::{DateTime, Utc};
+use sqlx::{QueryBuilder, Result, Sqlite, SqlitePool, Transaction};
+use std::sync::Arc;
+
+/// One purchase: a completed POS sale or a paid, non-cancelled order
+#[derive(Debug, sqlx::FromRow)]
+pub struct PurchaseEventRow {
+    pub customer_id: String,
+    pub ordered_at: DateTime<Utc>,
+    pub amount: f64,
+}
+
+/// What a customer got back through resolved returns
+#[derive(Debug, sqlx::FromRow)]
+pub struct ReturnedAmountRow {
+    pub customer_id: String,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct CustomerMetricsRow {
+    pub customer_id: String,
+    pub first_order_at: DateTime<Utc>,
+    pub last_order_at: DateTime<Utc>,
+    pub orders_count: i64,
+    pub total_spent: f64,
+    pub recency_days: i64,
+    pub recency_score: i64,
+    pub frequency_score: i64,
+    pub monetary_score: i64,
+    pub rfm_segment: String,
+    pub expected_order_gap_days: f64,
+    pub churn_probability: f64,
+    pub predicted_clv: f64,
+    pub clv_horizon_months: i64,
+}
+
+const METRICS_SELECT: &str = r#"
+    SELECT
+        m.customer_id, c.first_name, c.last_name, c.company_name, c.email, c.accepts_marketing,
+        m.first_order_at, m.last_order_at, m.orders_count, m.total_spent,
+        m.recency_days, m.recency_score, m.frequency_score, m.monetary_score, m.rfm_segment,
+        m.expected_order_gap_days, m.churn_probability, m.predicted_clv, m.clv_horizon_months,
+        m.computed_at
+    FROM customer_metrics m
+    INNER JOIN customers c ON c.id = m.customer_id
+    WHERE (c._status IS NULL OR c._status != 'deleted')
+"#;
+
+pub struct ShopCustomerMetricsRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopCustomerMetricsRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Purchases of live customers, ordered by customer then date
+    pub async fn list_purchase_events_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+    ) -> Result<Vec<PurchaseEventRow>> {
+        let sql = format!(
+            r#"
+            SELECT p.customer_id, p.ordered_at, p.amount
+            FROM ({}) p
+            INNER JOIN customers c ON c.id = p.customer_id
+            WHERE c._status IS NULL OR c._status != 'deleted'
+            ORDER BY p.customer_id, p.ordered_at
+            "#,
+            PURCHASES_SQL
+        );
+        sqlx::query_as::<_, PurchaseEventRow>(&sql)
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    /// Resolved return refunds per customer
+    pub async fn list_returned_amounts_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+    ) -> Result<Vec<ReturnedAmountRow>> {
+        sqlx::query_as::<_, ReturnedAmountRow>(RETURNED_SQL)
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    /// Replace every stored metric with `rows`
+    pub async fn replace_all_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        rows: &[CustomerMetricsRow],
+    ) -> Result<()> {
+        sqlx::query("DELETE FROM customer_metrics")
+            .execute(&mut **tx)
+            .await?;
+
+        let sql = r#"
+            INSERT INTO customer_metrics (
+                customer_id, first_order_at, last_order_at, orders_count, total_spent,
+                recency_days, recency_score, frequency_score, monetary_score, rfm_segment,
+                expected_order_gap_days, churn_probability, predicted_clv, clv_horizon_months,
+                computed_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, datetime('now'))
+        "#;
+        for row in rows {
+            sqlx::query(sql)
+                .bind(&row.customer_id)
+                .bind(row.first_order_at)
+                .bind(row.last_order_at)
+                .bind(row.orders_count)
+                .bind(row.total_spent)
+                .bind(row.recency_days)
+                .bind(row.recency_score)
+                .bind(row.frequency_score)
+                .bind(row.monetary_score)
+                .bind(&row.rfm_segment)
+                .bind(row.expected_order_gap_days)
+                .bind(row.churn_probability)
+                .bind(row.predicted_clv)
+                .bind(row.clv_horizon_months)
+                .execute(&mut **tx)
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn segment_summary(&self) -> Result<Vec<RfmSegmentSummaryDto>> {
+        let sql = r#"
+            SELECT
+                m.rfm_segment,
+                COUNT(*) AS customers,
+                CAST(SUM(m.total_spent) AS REAL) AS total_spent,
+                CAST(AVG(m.churn_probability) AS REAL) AS avg_churn_probability,
+                CAST(SUM(m.predicted_clv) AS REAL) AS predicted_clv
+            FROM customer_metrics m
+            INNER JOIN customers c ON c.id = m.customer_id
+            WHERE c._status IS NULL OR c._status != 'deleted'
+            GROUP BY m.rfm_segment
+            ORDER BY predicted_clv DESC
+        "#;
+        sqlx::query_as::<_, RfmSegmentSummaryDto>(sql)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// Highest predicted value first, so win-back lists start with the
+    /// customers most worth the effort
+    pub async fn list(&self, filter: &CustomerMetricsFilterDto) -> Result<Vec<CustomerMetricsDto>> {
+        let mut builder = QueryBuilder::<Sqlite>::new(METRICS_SELECT);
+        if let Some(segment) = &filter.rfm_segment {
+            builder.push(" AND m.rfm_segment = ").push_bind(segment);
+        }
+        if let Some(min_churn) = filter.min_churn_probability {
+            builder
+                .push(" AND m.churn_probability >= ")
+                .push_bind(min_churn);
+        }
+        if let Some(accepts_marketing) = filter.accepts_marketing {
+            builder
+                .push(" AND COALESCE(c.accepts_marketing, 0) = ")
+                .push_bind(accepts_marketing);
+        }
+        builder
+            .push(" ORDER BY m.predicted_clv DESC, m.customer_id LIMIT ")
+            .push_bind(filter.limit.unwrap_or(100));
+
+        builder
+            .build_query_as::<CustomerMetricsDto>()
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn get(&self, customer_id: &str) -> Result<Option<CustomerMetricsDto>> {
+        let sql = format!("{} AND m.customer_id = $1", METRICS_SELECT);
+        sqlx::query_as::<_, CustomerMetricsDto>(&sql)
+            .bind(customer_id)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn list_purchase_events(&self) -> Result<Vec<PurchaseEventRow>> {
+        let mut tx = self.pool.begin().await?;
+        let rows = Self::list_purchase_events_in_tx(&mut tx).await?;
+        tx.commit().await?;
+        Ok(rows)
+    }
+}
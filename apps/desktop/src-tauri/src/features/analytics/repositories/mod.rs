@@ -1 +1,2 @@
 pub mod analytics_repository;
+pub mod customer_metrics_repository;
@@ -233,22 +233,26 @@ impl AnalyticsService {
             .collect())
     }
 
-    /// Query 5: Receita por Categoria
+    /// Query 5: Receita por Categoria. With `rollup`, each category also
+    /// includes the revenue of its descendant categories.
     pub async fn get_revenue_by_category(
         &self,
         shop_id: Option<String>,
+        rollup: Option<bool>,
     ) -> Result<Vec<RevenueByCategoryDto>, String> {
         let _ = self.get_or_resolve_shop_id(shop_id).await?;
 
-        let rows = self
-            .repo
-            .get_revenue_by_category()
-            .await
-            .map_err(|e| format!("Failed to fetch revenue by category: {}", e))?;
+        let rows = if rollup.unwrap_or(false) {
+            self.repo.get_revenue_by_category_rollup().await
+        } else {
+            self.repo.get_revenue_by_category().await
+        }
+        .map_err(|e| format!("Failed to fetch revenue by category: {}", e))?;
 
         Ok(rows
             .into_iter()
             .map(|row| RevenueByCategoryDto {
+                category_id: row.category_id,
                 category_name: row.category_name,
                 total_revenue: row.total_revenue,
                 product_count: row.product_count,
@@ -805,6 +809,42 @@ impl AnalyticsService {
             })
             .collect())
     }
+
+    /// Query 27: Realização de Preço — receita efetiva vs. preço de tabela
+    /// vigente na data de cada venda (price_history)
+    pub async fn get_price_realization(
+        &self,
+        shop_id: Option<String>,
+        days: Option<i64>,
+        limit: Option<i64>,
+    ) -> Result<Vec<PriceRealizationDto>, String> {
+        let _ = self.get_or_resolve_shop_id(shop_id).await?;
+        let days = days.unwrap_or(30);
+        let limit = limit.unwrap_or(10);
+
+        let rows = self
+            .repo
+            .get_price_realization(days, limit)
+            .await
+            .map_err(|e| format!("Failed to fetch price realization: {}", e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| PriceRealizationDto {
+                discount_amount: row.list_revenue - row.total_revenue,
+                realization_rate: if row.list_revenue > 0.0 {
+                    (row.total_revenue / row.list_revenue * 10000.0).round() / 100.0
+                } else {
+                    100.0
+                },
+                product_id: row.product_id,
+                product_name: row.product_name,
+                total_quantity: row.total_quantity,
+                total_revenue: row.total_revenue,
+                list_revenue: row.list_revenue,
+            })
+            .collect())
+    }
 }
 
 fn parse_time_range(value: &str) -> Result<ParsedTimeRange, String> {
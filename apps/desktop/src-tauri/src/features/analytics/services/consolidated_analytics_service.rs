@@ -0,0 +1,522 @@
+//! Consolidated analytics across the shops of an owner
+//!
+//! Each shop has its own database, so every report runs the single-shop
+//! `AnalyticsService` query on each shop and merges the results. Shops are
+//! queried a few at a time so that opening many lazy-loaded shop pools at
+//! once does not exhaust file handles. Amounts are converted to the report
+//! currency with the registry exchange rates; a shop that fails or has no
+//! rate is listed in `skipped_shops` instead of failing the whole report.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::db::RepositoryFactory;
+use crate::features::analytics::dtos::analytics_dto::*;
+use crate::features::analytics::services::analytics_service::AnalyticsService;
+use crate::features::exchange_rate::services::exchange_rate_service::{
+    ExchangeRateService, RateTable,
+};
+use crate::features::shop::models::shop_model::Shop;
+use crate::features::shop::repositories::shop_repository::ShopsRepository;
+
+/// Shops queried at the same time
+const MAX_CONCURRENT_SHOPS: usize = 4;
+
+/// Each shop returns this many times the requested number of top products,
+/// so products that rank lower in every shop can still make the merged top
+const TOP_PRODUCTS_OVERFETCH: i64 = 5;
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn change_percentage(current: f64, previous: f64) -> Option<f64> {
+    (previous != 0.0).then(|| round2((current - previous) * 100.0 / previous))
+}
+
+/// Shops in a report and the currency amounts are converted to
+struct ReportScope {
+    shops: Vec<Shop>,
+    currency: String,
+    rates: RateTable,
+}
+
+/// Result of one shop, with the rate converting its amounts
+struct ShopResult<T> {
+    shop: Shop,
+    rate: f64,
+    data: T,
+}
+
+impl<T> ShopResult<T> {
+    fn breakdown<D>(&self, converted: bool, data: D) -> ShopBreakdownDto<D> {
+        ShopBreakdownDto {
+            shop_id: self.shop.id.clone(),
+            shop_name: self.shop.name.clone(),
+            currency: self.shop.currency.clone(),
+            exchange_rate: converted.then_some(self.rate),
+            data,
+        }
+    }
+}
+
+pub struct ConsolidatedAnalyticsService {
+    repo_factory: Arc<RepositoryFactory>,
+}
+
+impl ConsolidatedAnalyticsService {
+    pub fn new(repo_factory: Arc<RepositoryFactory>) -> Self {
+        Self { repo_factory }
+    }
+
+    async fn scope(&self, filter: &ConsolidatedAnalyticsFilterDto) -> Result<ReportScope, String> {
+        let registry = self.repo_factory.registry_pool().clone();
+        let mut shops = ShopsRepository::new(registry.clone())
+            .list_by_owner(&filter.owner_id)
+            .await
+            .map_err(|e| format!("Failed to list shops: {}", e))?;
+
+        if let Some(shop_ids) = &filter.shop_ids {
+            if let Some(missing) = shop_ids
+                .iter()
+                .find(|id| !shops.iter().any(|s| &s.id == *id))
+            {
+                return Err(format!("Shop {} not found for this owner", missing));
+            }
+            shops.retain(|shop| shop_ids.contains(&shop.id));
+        }
+        if shops.is_empty() {
+            return Err("No shops found for this owner".to_string());
+        }
+
+        let currency = match &filter.currency {
+            Some(currency) => currency.trim().to_uppercase(),
+            None => {
+                let mut counts: HashMap<String, usize> = HashMap::new();
+                for shop in &shops {
+                    *counts.entry(shop.currency.to_uppercase()).or_default() += 1;
+                }
+                counts
+                    .into_iter()
+                    .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+                    .map(|(currency, _)| currency)
+                    .unwrap_or_default()
+            }
+        };
+        let rates = ExchangeRateService::new(registry).rate_table().await?;
+
+        Ok(ReportScope {
+            shops,
+            currency,
+            rates,
+        })
+    }
+
+    /// Run `query` on every shop of the scope, at most
+    /// `MAX_CONCURRENT_SHOPS` at a time. Results keep the order of the shops.
+    async fn fan_out<T, F, Fut>(&self, shops: &[Shop], query: F) -> Vec<Result<T, String>>
+    where
+        T: Send + 'static,
+        F: Fn(AnalyticsService, String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, String>> + Send + 'static,
+    {
+        let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_SHOPS));
+        let query = Arc::new(query);
+        let mut tasks = JoinSet::new();
+
+        for (index, shop) in shops.iter().enumerate() {
+            let semaphore = semaphore.clone();
+            let repo_factory = self.repo_factory.clone();
+            let query = query.clone();
+            let shop_id = shop.id.clone();
+
+            tasks.spawn(async move {
+                let _permit = semaphore.acquire_owned().await;
+                let result = async {
+                    let shop_pool = repo_factory
+                        .shop_pool(&shop_id)
+                        .await
+                        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+                    let service = AnalyticsService::new(
+                        repo_factory.registry_pool().clone(),
+                        (*shop_pool).clone(),
+                    );
+                    query(service, shop_id).await
+                }
+                .await;
+                (index, result)
+            });
+        }
+
+        let mut results: Vec<Option<Result<T, String>>> = shops.iter().map(|_| None).collect();
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((index, result)) => results[index] = Some(result),
+                Err(e) => eprintln!("[consolidated_analytics] Shop query task failed: {}", e),
+            }
+        }
+
+        results
+            .into_iter()
+            .map(|result| result.unwrap_or_else(|| Err("Shop query task failed".to_string())))
+            .collect()
+    }
+
+    /// Pair results with their shops, setting aside the shops that failed or
+    /// whose currency cannot be converted
+    fn collect<T>(
+        scope: &ReportScope,
+        results: Vec<Result<T, String>>,
+        convert: bool,
+    ) -> (Vec<ShopResult<T>>, Vec<SkippedShopDto>) {
+        let mut collected = Vec::new();
+        let mut skipped = Vec::new();
+
+        for (shop, result) in scope.shops.iter().zip(results) {
+            let rate = if convert {
+                scope.rates.rate(&shop.currency, &scope.currency)
+            } else {
+                Some(1.0)
+            };
+            let outcome = match (result, rate) {
+                (Err(e), _) => Err(e),
+                (Ok(_), None) => Err(format!(
+                    "No exchange rate from {} to {}",
+                    shop.currency, scope.currency
+                )),
+                (Ok(data), Some(rate)) => Ok(ShopResult {
+                    shop: shop.clone(),
+                    rate,
+                    data,
+                }),
+            };
+            match outcome {
+                Ok(result) => collected.push(result),
+                Err(reason) => skipped.push(SkippedShopDto {
+                    shop_id: shop.id.clone(),
+                    shop_name: shop.name.clone(),
+                    reason,
+                }),
+            }
+        }
+
+        (collected, skipped)
+    }
+
+    /// Monthly revenue and orders of all shops, with each shop's share
+    pub async fn get_revenue(
+        &self,
+        filter: ConsolidatedAnalyticsFilterDto,
+    ) -> Result<ConsolidatedRevenueDto, String> {
+        let scope = self.scope(&filter).await?;
+        let months = filter.months;
+        let results = self
+            .fan_out(&scope.shops, move |service, shop_id| async move {
+                service.get_monthly_sales(Some(shop_id), months).await
+            })
+            .await;
+        let (results, skipped_shops) = Self::collect(&scope, results, true);
+
+        let mut monthly: HashMap<String, (f64, i64)> = HashMap::new();
+        let mut shops = Vec::new();
+        for result in &results {
+            let rows: Vec<MonthlySalesDto> = result
+                .data
+                .iter()
+                .map(|row| MonthlySalesDto {
+                    month: row.month.clone(),
+                    monthly_revenue: row.monthly_revenue * result.rate,
+                    order_count: row.order_count,
+                    avg_order_value: row.avg_order_value * result.rate,
+                })
+                .collect();
+            for row in &rows {
+                let entry = monthly.entry(row.month.clone()).or_default();
+                entry.0 += row.monthly_revenue;
+                entry.1 += row.order_count;
+            }
+
+            let total_revenue: f64 = rows.iter().map(|r| r.monthly_revenue).sum();
+            let order_count: i64 = rows.iter().map(|r| r.order_count).sum();
+            shops.push(result.breakdown(
+                true,
+                ShopRevenueDto {
+                    total_revenue,
+                    order_count,
+                    avg_order_value: if order_count > 0 {
+                        total_revenue / order_count as f64
+                    } else {
+                        0.0
+                    },
+                    revenue_share: 0.0,
+                    monthly: rows,
+                },
+            ));
+        }
+
+        let total_revenue: f64 = shops.iter().map(|s| s.data.total_revenue).sum();
+        let order_count: i64 = shops.iter().map(|s| s.data.order_count).sum();
+        for shop in &mut shops {
+            if total_revenue > 0.0 {
+                shop.data.revenue_share = round2(shop.data.total_revenue * 100.0 / total_revenue);
+            }
+        }
+
+        let mut monthly: Vec<MonthlySalesDto> = monthly
+            .into_iter()
+            .map(|(month, (revenue, orders))| MonthlySalesDto {
+                month,
+                monthly_revenue: revenue,
+                order_count: orders,
+                avg_order_value: if orders > 0 {
+                    revenue / orders as f64
+                } else {
+                    0.0
+                },
+            })
+            .collect();
+        monthly.sort_by(|a, b| a.month.cmp(&b.month));
+
+        Ok(ConsolidatedRevenueDto {
+            currency: scope.currency,
+            total_revenue,
+            order_count,
+            avg_order_value: if order_count > 0 {
+                total_revenue / order_count as f64
+            } else {
+                0.0
+            },
+            monthly,
+            shops,
+            skipped_shops,
+        })
+    }
+
+    /// Best selling products of all shops. Products are matched across shops
+    /// by name, since each shop database has its own product ids.
+    pub async fn get_top_products(
+        &self,
+        filter: ConsolidatedAnalyticsFilterDto,
+    ) -> Result<ConsolidatedTopProductsDto, String> {
+        let scope = self.scope(&filter).await?;
+        let days = filter.days;
+        let limit = filter.limit.unwrap_or(10).max(1);
+        let results = self
+            .fan_out(&scope.shops, move |service, shop_id| async move {
+                service
+                    .get_top_products(Some(shop_id), days, Some(limit * TOP_PRODUCTS_OVERFETCH))
+                    .await
+            })
+            .await;
+        let (results, skipped_shops) = Self::collect(&scope, results, true);
+
+        let mut products: Vec<ConsolidatedTopProductDto> = Vec::new();
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        let mut shops = Vec::new();
+        for result in &results {
+            let rows: Vec<TopProductDto> = result
+                .data
+                .iter()
+                .map(|row| TopProductDto {
+                    product_id: row.product_id.clone(),
+                    product_name: row.product_name.clone(),
+                    total_quantity: row.total_quantity,
+                    total_revenue: row.total_revenue * result.rate,
+                    order_count: row.order_count,
+                })
+                .collect();
+
+            for row in &rows {
+                let key = row.product_name.trim().to_lowercase();
+                let index = *positions.entry(key).or_insert_with(|| {
+                    products.push(ConsolidatedTopProductDto {
+                        product_name: row.product_name.trim().to_string(),
+                        total_quantity: 0.0,
+                        total_revenue: 0.0,
+                        order_count: 0,
+                        shop_count: 0,
+                    });
+                    products.len() - 1
+                });
+                let product = &mut products[index];
+                product.total_quantity += row.total_quantity;
+                product.total_revenue += row.total_revenue;
+                product.order_count += row.order_count;
+                product.shop_count += 1;
+            }
+
+            shops.push(result.breakdown(true, rows.into_iter().take(limit as usize).collect()));
+        }
+
+        products.sort_by(|a, b| b.total_revenue.total_cmp(&a.total_revenue));
+        products.truncate(limit as usize);
+
+        Ok(ConsolidatedTopProductsDto {
+            currency: scope.currency,
+            products,
+            shops,
+            skipped_shops,
+        })
+    }
+
+    /// Products per stock level, summed over all shops
+    pub async fn get_stock_status(
+        &self,
+        filter: ConsolidatedAnalyticsFilterDto,
+    ) -> Result<ConsolidatedStockStatusDto, String> {
+        let scope = self.scope(&filter).await?;
+        let results = self
+            .fan_out(&scope.shops, |service, shop_id| async move {
+                service.get_stock_status(Some(shop_id)).await
+            })
+            .await;
+        let (results, skipped_shops) = Self::collect(&scope, results, false);
+
+        let mut statuses: Vec<StockStatusDto> = Vec::new();
+        let mut shops = Vec::new();
+        for mut result in results {
+            for row in &result.data {
+                match statuses
+                    .iter_mut()
+                    .find(|s| s.stock_status == row.stock_status)
+                {
+                    Some(status) => {
+                        status.product_count += row.product_count;
+                        status.total_quantity += row.total_quantity;
+                    }
+                    None => statuses.push(StockStatusDto {
+                        stock_status: row.stock_status.clone(),
+                        product_count: row.product_count,
+                        total_quantity: row.total_quantity,
+                    }),
+                }
+            }
+            let data = std::mem::take(&mut result.data);
+            shops.push(result.breakdown(false, data));
+        }
+
+        Ok(ConsolidatedStockStatusDto {
+            statuses,
+            shops,
+            skipped_shops,
+        })
+    }
+
+    /// Monthly average order value over the orders of all shops
+    pub async fn get_average_order_value(
+        &self,
+        filter: ConsolidatedAnalyticsFilterDto,
+    ) -> Result<ConsolidatedAverageOrderValueDto, String> {
+        let scope = self.scope(&filter).await?;
+        let months = filter.months;
+        let results = self
+            .fan_out(&scope.shops, move |service, shop_id| async move {
+                service.get_average_order_value(Some(shop_id), months).await
+            })
+            .await;
+        let (results, skipped_shops) = Self::collect(&scope, results, true);
+
+        // month -> (revenue, orders)
+        let mut totals: HashMap<String, (f64, i64)> = HashMap::new();
+        let mut shops = Vec::new();
+        for result in &results {
+            let rows: Vec<AverageOrderValueDto> = result
+                .data
+                .iter()
+                .map(|row| AverageOrderValueDto {
+                    month: row.month.clone(),
+                    order_count: row.order_count,
+                    avg_order_value: row.avg_order_value * result.rate,
+                    previous_avg: row.previous_avg.map(|avg| avg * result.rate),
+                    avg_change_percentage: row.avg_change_percentage,
+                })
+                .collect();
+            for row in &rows {
+                let entry = totals.entry(row.month.clone()).or_default();
+                entry.0 += row.avg_order_value * row.order_count as f64;
+                entry.1 += row.order_count;
+            }
+            shops.push(result.breakdown(true, rows));
+        }
+
+        let mut totals: Vec<(String, (f64, i64))> = totals.into_iter().collect();
+        totals.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut monthly: Vec<AverageOrderValueDto> = Vec::new();
+        for (month, (revenue, orders)) in totals {
+            let avg_order_value = if orders > 0 {
+                revenue / orders as f64
+            } else {
+                0.0
+            };
+            let previous_avg = monthly.last().map(|m| m.avg_order_value);
+            monthly.push(AverageOrderValueDto {
+                month,
+                order_count: orders,
+                avg_order_value,
+                previous_avg,
+                avg_change_percentage: previous_avg
+                    .and_then(|previous| change_percentage(avg_order_value, previous)),
+            });
+        }
+
+        Ok(ConsolidatedAverageOrderValueDto {
+            currency: scope.currency,
+            monthly,
+            shops,
+            skipped_shops,
+        })
+    }
+
+    /// New customers per month over all shops. A customer of several shops
+    /// is counted once per shop.
+    pub async fn get_customer_growth(
+        &self,
+        filter: ConsolidatedAnalyticsFilterDto,
+    ) -> Result<ConsolidatedCustomerGrowthDto, String> {
+        let scope = self.scope(&filter).await?;
+        let months = filter.months;
+        let results = self
+            .fan_out(&scope.shops, move |service, shop_id| async move {
+                service.get_customer_growth(Some(shop_id), months).await
+            })
+            .await;
+        let (results, skipped_shops) = Self::collect(&scope, results, false);
+
+        let mut new_customers: HashMap<String, i64> = HashMap::new();
+        let mut shops = Vec::new();
+        for mut result in results {
+            for row in &result.data {
+                *new_customers.entry(row.month.clone()).or_default() += row.new_customers;
+            }
+            let data = std::mem::take(&mut result.data);
+            shops.push(result.breakdown(false, data));
+        }
+
+        let mut new_customers: Vec<(String, i64)> = new_customers.into_iter().collect();
+        new_customers.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut monthly: Vec<CustomerGrowthDto> = Vec::new();
+        let mut cumulative_customers = 0;
+        for (month, count) in new_customers {
+            cumulative_customers += count;
+            let previous_month = monthly.last().map(|m| m.new_customers);
+            monthly.push(CustomerGrowthDto {
+                month,
+                new_customers: count,
+                cumulative_customers,
+                previous_month,
+                growth_percentage: previous_month
+                    .and_then(|previous| change_percentage(count as f64, previous as f64)),
+            });
+        }
+
+        Ok(ConsolidatedCustomerGrowthDto {
+            monthly,
+            shops,
+            skipped_shops,
+        })
+    }
+}
@@ -0,0 +1,29 @@
+//! Background job recomputing customer RFM, churn and CLV metrics
+//!
+//! Started from the app setup; it runs `ShopCustomerMetricsService::refresh`
+//! when a shop's database opens and then once a day while it stays open
+//! (see `run_for_open_shops`). Recency moves with the calendar even when
+//! nobody buys, so the stored scores would drift without it.
+
+use crate::db::RepositoryFactory;
+use crate::features::analytics::services::customer_metrics_service::ShopCustomerMetricsService;
+use crate::features::shop::services::shop_scheduler::run_for_open_shops;
+use std::sync::Arc;
+use std::time::Duration;
+
+const CUSTOMER_METRICS_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+
+pub async fn run_customer_metrics_scheduler(repo_factory: Arc<RepositoryFactory>) {
+    run_for_open_shops(
+        repo_factory,
+        CUSTOMER_METRICS_INTERVAL,
+        "customer_metrics",
+        |shop_id, pool| async move {
+            ShopCustomerMetricsService::new(pool, shop_id)
+                .refresh(None)
+                .await
+                .map(|_| ())
+        },
+    )
+    .await
+}
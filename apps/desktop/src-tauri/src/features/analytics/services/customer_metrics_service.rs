@@ -0,0 +1,381 @@
+//! Customer-level analytics: RFM scoring, churn, CLV and cohort retention
+//!
+//! Purchases are completed POS sales plus paid orders, and spend is net of
+//! resolved returns, as in the stored customer stats. Each refresh scores
+//! every purchasing customer against the others (quintiles, so scores are
+//! relative to the shop), stores the result in `customer_metrics` and
+//! re-evaluates dynamic customer groups, which can filter on those metrics.
+//!
+//! Churn assumes a customer's next purchase arrives after an exponentially
+//! distributed gap whose mean is their own average gap between purchase
+//! days, shrunk towards the shop median so one-off buyers get a sane value.
+//! CLV is the expected number of purchases over the horizon times the
+//! average order value, discounted by the chance they have already churned.
+
+use crate::features::analytics::dtos::customer_metrics_dto::{
+    CohortPeriodDto, CustomerCohortDto, CustomerMetricsDto, CustomerMetricsFilterDto,
+    CustomerMetricsRefreshDto, RfmSegmentSummaryDto,
+};
+use crate::features::analytics::repositories::customer_metrics_repository::{
+    CustomerMetricsRow, PurchaseEventRow, ReturnedAmountRow, ShopCustomerMetricsRepository,
+};
+use crate::features::customer_group::services::shop_customer_segment_service::ShopCustomerSegmentService;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+
+pub const RFM_SEGMENTS: [&str; 9] = [
+    "champions",
+    "loyal",
+    "potential_loyalist",
+    "new",
+    "need_attention",
+    "cant_lose",
+    "at_risk",
+    "hibernating",
+    "lost",
+];
+
+const DEFAULT_CLV_HORIZON_MONTHS: i64 = 12;
+const MAX_CLV_HORIZON_MONTHS: i64 = 60;
+const DEFAULT_COHORT_MONTHS: i64 = 12;
+const MAX_COHORT_MONTHS: i64 = 36;
+/// Used as the typical gap between purchases until some customer has bought
+/// on two different days
+const FALLBACK_ORDER_GAP_DAYS: f64 = 90.0;
+/// Weight, in purchase gaps, of the shop median in each customer's gap
+const GAP_PRIOR_WEIGHT: f64 = 2.0;
+const DAYS_PER_MONTH: f64 = 30.4375;
+
+/// Purchases of one customer, oldest first
+struct CustomerHistory {
+    customer_id: String,
+    first_order_at: DateTime<Utc>,
+    last_order_at: DateTime<Utc>,
+    orders_count: i64,
+    total_spent: f64,
+    gaps: Vec<f64>,
+}
+
+pub struct ShopCustomerMetricsService {
+    pool: Arc<SqlitePool>,
+    shop_id: String,
+    repo: ShopCustomerMetricsRepository,
+}
+
+impl ShopCustomerMetricsService {
+    pub fn new(pool: Arc<SqlitePool>, shop_id: String) -> Self {
+        Self {
+            repo: ShopCustomerMetricsRepository::new(pool.clone()),
+            pool,
+            shop_id,
+        }
+    }
+
+    pub fn shop_id(&self) -> &str {
+        &self.shop_id
+    }
+
+    /// Recompute and store the metrics of every customer who has purchased
+    pub async fn refresh(
+        &self,
+        horizon_months: Option<i64>,
+    ) -> Result<CustomerMetricsRefreshDto, String> {
+        let horizon_months = horizon_months.unwrap_or(DEFAULT_CLV_HORIZON_MONTHS);
+        if !(1..=MAX_CLV_HORIZON_MONTHS).contains(&horizon_months) {
+            return Err(format!(
+                "CLV horizon must be between 1 and {} months",
+                MAX_CLV_HORIZON_MONTHS
+            ));
+        }
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let events = ShopCustomerMetricsRepository::list_purchase_events_in_tx(&mut tx)
+            .await
+            .map_err(|e| format!("Failed to list customer purchases: {}", e))?;
+        let returned = ShopCustomerMetricsRepository::list_returned_amounts_in_tx(&mut tx)
+            .await
+            .map_err(|e| format!("Failed to list customer returns: {}", e))?;
+        let histories = histories(&events, &returned);
+        let median_gap = median(histories.iter().flat_map(|h| h.gaps.iter().copied()))
+            .unwrap_or(FALLBACK_ORDER_GAP_DAYS);
+        let rows = score(&histories, median_gap, horizon_months, Utc::now());
+
+        ShopCustomerMetricsRepository::replace_all_in_tx(&mut tx, &rows)
+            .await
+            .map_err(|e| format!("Failed to save customer metrics: {}", e))?;
+        ShopCustomerSegmentService::refresh_all_in_tx(&mut tx).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(CustomerMetricsRefreshDto {
+            customers_scored: rows.len() as i64,
+            median_order_gap_days: round(median_gap, 2),
+            clv_horizon_months: horizon_months,
+            segments: self.segment_summary().await?,
+        })
+    }
+
+    pub async fn list_metrics(
+        &self,
+        mut filter: CustomerMetricsFilterDto,
+    ) -> Result<Vec<CustomerMetricsDto>, String> {
+        if let Some(segment) = filter.rfm_segment.as_deref() {
+            if !RFM_SEGMENTS.contains(&segment) {
+                return Err(format!("Unknown RFM segment: {}", segment));
+            }
+        }
+        filter.limit = Some(filter.limit.unwrap_or(100).clamp(1, 1000));
+
+        self.repo
+            .list(&filter)
+            .await
+            .map_err(|e| format!("Failed to list customer metrics: {}", e))
+    }
+
+    pub async fn get_metrics(
+        &self,
+        customer_id: &str,
+    ) -> Result<Option<CustomerMetricsDto>, String> {
+        self.repo
+            .get(customer_id)
+            .await
+            .map_err(|e| format!("Failed to get customer metrics: {}", e))
+    }
+
+    pub async fn segment_summary(&self) -> Result<Vec<RfmSegmentSummaryDto>, String> {
+        self.repo
+            .segment_summary()
+            .await
+            .map_err(|e| format!("Failed to summarize RFM segments: {}", e))
+    }
+
+    /// Retention of the customers acquired in each of the last `months`
+    /// months, computed live from purchases
+    pub async fn cohorts(&self, months: Option<i64>) -> Result<Vec<CustomerCohortDto>, String> {
+        let months = months.unwrap_or(DEFAULT_COHORT_MONTHS);
+        if !(1..=MAX_COHORT_MONTHS).contains(&months) {
+            return Err(format!(
+                "Cohort range must be between 1 and {} months",
+                MAX_COHORT_MONTHS
+            ));
+        }
+
+        let events = self
+            .repo
+            .list_purchase_events()
+            .await
+            .map_err(|e| format!("Failed to list customer purchases: {}", e))?;
+
+        let mut active: HashMap<&str, BTreeSet<i64>> = HashMap::new();
+        for event in &events {
+            active
+                .entry(event.customer_id.as_str())
+                .or_default()
+                .insert(month_index(event.ordered_at.date_naive()));
+        }
+
+        let current = month_index(Utc::now().date_naive());
+        let oldest = current - months + 1;
+        let mut cohorts: BTreeMap<i64, Vec<&BTreeSet<i64>>> = BTreeMap::new();
+        for purchases in active.values() {
+            let Some(&first) = purchases.first() else {
+                continue;
+            };
+            if first >= oldest {
+                cohorts.entry(first).or_default().push(purchases);
+            }
+        }
+
+        Ok(cohorts
+            .into_iter()
+            .map(|(cohort, members)| {
+                let customers = members.len() as i64;
+                let periods = (cohort..=current)
+                    .map(|month| {
+                        let active_customers =
+                            members.iter().filter(|m| m.contains(&month)).count() as i64;
+                        CohortPeriodDto {
+                            month_offset: month - cohort,
+                            month: month_label(month),
+                            active_customers,
+                            retention_rate: round(active_customers as f64 / customers as f64, 4),
+                        }
+                    })
+                    .collect();
+                CustomerCohortDto {
+                    cohort: month_label(cohort),
+                    customers,
+                    periods,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Fold events (ordered by customer, then date) into one history each, its
+/// spend net of the customer's returns as in the stored customer stats
+fn histories(events: &[PurchaseEventRow], returned: &[ReturnedAmountRow]) -> Vec<CustomerHistory> {
+    let mut histories: Vec<CustomerHistory> = Vec::new();
+    let mut last_day: Option<NaiveDate> = None;
+
+    for event in events {
+        let day = event.ordered_at.date_naive();
+        match histories.last_mut() {
+            Some(h) if h.customer_id == event.customer_id => {
+                h.last_order_at = event.ordered_at;
+                h.orders_count += 1;
+                h.total_spent += event.amount;
+                if let Some(previous) = last_day.filter(|d| *d != day) {
+                    h.gaps.push((day - previous).num_days() as f64);
+                }
+            }
+            _ => histories.push(CustomerHistory {
+                customer_id: event.customer_id.clone(),
+                first_order_at: event.ordered_at,
+                last_order_at: event.ordered_at,
+                orders_count: 1,
+                total_spent: event.amount,
+                gaps: Vec::new(),
+            }),
+        }
+        last_day = Some(day);
+    }
+
+    let returned: HashMap<&str, f64> = returned
+        .iter()
+        .map(|r| (r.customer_id.as_str(), r.amount))
+        .collect();
+    for h in &mut histories {
+        if let Some(amount) = returned.get(h.customer_id.as_str()) {
+            h.total_spent = (h.total_spent - amount).max(0.0);
+        }
+    }
+
+    histories
+}
+
+fn score(
+    histories: &[CustomerHistory],
+    median_gap: f64,
+    horizon_months: i64,
+    now: DateTime<Utc>,
+) -> Vec<CustomerMetricsRow> {
+    let recency: Vec<i64> = histories
+        .iter()
+        .map(|h| (now - h.last_order_at).num_days().max(0))
+        .collect();
+    // Negated so that more recent buyers rank higher
+    let r_scores = quintiles(&recency.iter().map(|d| -(*d as f64)).collect::<Vec<_>>());
+    let f_scores = quintiles(
+        &histories
+            .iter()
+            .map(|h| h.orders_count as f64)
+            .collect::<Vec<_>>(),
+    );
+    let m_scores = quintiles(&histories.iter().map(|h| h.total_spent).collect::<Vec<_>>());
+    let horizon_days = horizon_months as f64 * DAYS_PER_MONTH;
+
+    histories
+        .iter()
+        .enumerate()
+        .map(|(i, h)| {
+            let gap_sum: f64 = h.gaps.iter().sum();
+            let expected_gap = ((gap_sum + GAP_PRIOR_WEIGHT * median_gap)
+                / (h.gaps.len() as f64 + GAP_PRIOR_WEIGHT))
+                .max(1.0);
+            let churn = 1.0 - (-(recency[i] as f64) / expected_gap).exp();
+            let average_order = h.total_spent / h.orders_count as f64;
+            let clv = average_order * (horizon_days / expected_gap) * (1.0 - churn);
+
+            CustomerMetricsRow {
+                customer_id: h.customer_id.clone(),
+                first_order_at: h.first_order_at,
+                last_order_at: h.last_order_at,
+                orders_count: h.orders_count,
+                total_spent: round(h.total_spent, 2),
+                recency_days: recency[i],
+                recency_score: r_scores[i],
+                frequency_score: f_scores[i],
+                monetary_score: m_scores[i],
+                rfm_segment: rfm_segment(r_scores[i], f_scores[i], m_scores[i]).to_string(),
+                expected_order_gap_days: round(expected_gap, 2),
+                churn_probability: round(churn, 4),
+                predicted_clv: round(clv, 2),
+                clv_horizon_months: horizon_months,
+            }
+        })
+        .collect()
+}
+
+/// 1-5 score from each value's mid-rank percentile; ties share a score and
+/// a lone or uniform population lands in the middle
+fn quintiles(values: &[f64]) -> Vec<i64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let n = sorted.len() as f64;
+
+    values
+        .iter()
+        .map(|v| {
+            let below = sorted.partition_point(|x| x < v) as f64;
+            let equal = sorted.partition_point(|x| x <= v) as f64 - below;
+            let percentile = (below + equal / 2.0) / n;
+            ((percentile * 5.0).ceil() as i64).clamp(1, 5)
+        })
+        .collect()
+}
+
+fn rfm_segment(r: i64, f: i64, m: i64) -> &'static str {
+    let fm = (f + m + 1) / 2;
+    match (r, fm) {
+        (4..=5, 4..=5) => "champions",
+        (3, 4..=5) => "loyal",
+        (4..=5, 2..=3) => "potential_loyalist",
+        (4..=5, _) => "new",
+        (3, _) => "need_attention",
+        (1, 5) => "cant_lose",
+        (1..=2, 3..=5) => "at_risk",
+        (2, _) | (1, 2) => "hibernating",
+        _ => "lost",
+    }
+}
+
+fn median(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let mut values: Vec<f64> = values.collect();
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+fn round(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+fn month_index(date: NaiveDate) -> i64 {
+    date.year() as i64 * 12 + date.month0() as i64
+}
+
+fn month_label(index: i64) -> String {
+    format!(
+        "{:04}-{:02}",
+        index.div_euclid(12),
+        index.rem_euclid(12) + 1
+    )
+}
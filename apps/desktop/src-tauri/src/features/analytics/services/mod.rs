@@ -1 +1,4 @@
 pub mod analytics_service;
+pub mod consolidated_analytics_service;
+pub mod customer_metrics_scheduler;
+pub mod customer_metrics_service;
@@ -1,7 +1,7 @@
 //! Shop-scoped Audit Log Repository for Multi-Database Architecture
 
 use crate::features::audit_log::models::audit_log_model::AuditLog;
-use sqlx::{Result, SqlitePool};
+use sqlx::{Executor, Result, Sqlite, SqlitePool, Transaction};
 use std::sync::Arc;
 
 pub struct ShopAuditLogRepository {
@@ -14,6 +14,20 @@ impl ShopAuditLogRepository {
     }
 
     pub async fn create(&self, log: &AuditLog) -> Result<AuditLog> {
+        Self::create_with(&*self.pool, log).await
+    }
+
+    pub async fn create_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        log: &AuditLog,
+    ) -> Result<AuditLog> {
+        Self::create_with(&mut **tx, log).await
+    }
+
+    async fn create_with<'e, E>(executor: E, log: &AuditLog) -> Result<AuditLog>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
         let sql = r#"
             INSERT INTO audit_logs (
                 id, table_name, record_id, action, old_data, new_data,
@@ -33,7 +47,7 @@ impl ShopAuditLogRepository {
             .bind(&log.ip_address)
             .bind(&log.user_agent)
             .bind(&log.created_at)
-            .fetch_one(&*self.pool)
+            .fetch_one(executor)
             .await
     }
 
@@ -0,0 +1,76 @@
+use crate::db::RepositoryFactory;
+use crate::features::barcode::dtos::barcode_dto::{CreateProductBarcodeDTO, GenerateLabelsDTO};
+use crate::features::barcode::models::barcode_model::{
+    BarcodeSettings, LabelDocument, ProductBarcode, ScanResult,
+};
+use crate::features::barcode::services::shop_barcode_service::ShopBarcodeService;
+use crate::features::shop::services::shop_service::ShopService;
+use std::sync::Arc;
+use tauri::State;
+
+/// Barcode service configured with the shop's barcode settings
+async fn barcode_service(
+    repo_factory: &RepositoryFactory,
+    shop_id: String,
+) -> Result<ShopBarcodeService, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let shop = ShopService::new(repo_factory.registry_pool().clone())
+        .get_shop(&shop_id)
+        .await?;
+    let settings = BarcodeSettings::from_shop_settings(shop.and_then(|s| s.settings).as_deref());
+
+    Ok(ShopBarcodeService::new(pool, shop_id, settings))
+}
+
+#[tauri::command]
+pub async fn create_product_barcode(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: CreateProductBarcodeDTO,
+) -> Result<ProductBarcode, String> {
+    let service = barcode_service(&repo_factory, shop_id).await?;
+    service.create_barcode(payload).await
+}
+
+#[tauri::command]
+pub async fn delete_product_barcode(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+) -> Result<(), String> {
+    let service = barcode_service(&repo_factory, shop_id).await?;
+    service.delete_barcode(&id).await
+}
+
+#[tauri::command]
+pub async fn list_product_barcodes(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    product_id: String,
+) -> Result<Vec<ProductBarcode>, String> {
+    let service = barcode_service(&repo_factory, shop_id).await?;
+    service.list_barcodes(&product_id).await
+}
+
+#[tauri::command]
+pub async fn scan_barcode(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    code: String,
+) -> Result<Option<ScanResult>, String> {
+    let service = barcode_service(&repo_factory, shop_id).await?;
+    service.scan(&code).await
+}
+
+#[tauri::command]
+pub async fn generate_product_labels(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: GenerateLabelsDTO,
+) -> Result<LabelDocument, String> {
+    let service = barcode_service(&repo_factory, shop_id).await?;
+    service.generate_labels(payload).await
+}
@@ -0,0 +1,47 @@
+use crate::features::barcode::models::barcode_model::ProductBarcode;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateProductBarcodeDTO {
+    pub product_id: String,
+    pub barcode: Option<String>, // Generated for internal codes when omitted
+    pub kind: Option<String>,    // DEFAULT 'internal'
+    pub quantity: Option<f64>,   // DEFAULT 1
+    pub description: Option<String>,
+}
+
+impl CreateProductBarcodeDTO {
+    pub fn into_model(self, barcode: String) -> ProductBarcode {
+        let now = Utc::now();
+        ProductBarcode {
+            id: Uuid::new_v4().to_string(),
+            product_id: self.product_id,
+            barcode,
+            kind: self.kind.unwrap_or_else(|| "internal".to_string()),
+            quantity: self.quantity.unwrap_or(1.0),
+            description: self.description,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LabelItemDTO {
+    pub product_id: String,
+    pub copies: Option<u32>, // DEFAULT 1
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GenerateLabelsDTO {
+    pub items: Vec<LabelItemDTO>,
+    pub template: Option<String>,  // 'shelf', 'product' (DEFAULT)
+    pub symbology: Option<String>, // 'ean13', 'code128'; EAN-13 when available
+    pub format: String,            // 'svg', 'pdf', 'zpl'
+    pub width_mm: Option<f64>,
+    pub height_mm: Option<f64>,
+    pub dpi: Option<u32>, // ZPL only, DEFAULT 203
+}
@@ -0,0 +1 @@
+pub mod barcode_dto;
@@ -0,0 +1,92 @@
+use crate::features::product::models::product_model::Product;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ProductBarcode {
+    pub id: String,
+    pub product_id: String,
+    pub barcode: String,
+    pub kind: String,  // 'gtin', 'internal', 'supplier', 'plu'
+    pub quantity: f64, // Units added per scan
+    pub description: Option<String>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Barcode settings of a shop, read from the `barcodes` key of the shop
+/// settings.
+///
+/// Weighted items are labelled by the scale with an EAN-13 whose digits
+/// carry the product PLU and the total price (or weight). The defaults
+/// follow the usual Brazilian scale layout `2 PPPP 0 VVVVVV D`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct BarcodeSettings {
+    pub internal_prefix: String, // Prefix of generated internal EAN-13 codes
+    pub scale_prefixes: Vec<String>,
+    pub plu_start: usize,
+    pub plu_length: usize,
+    pub value_start: usize,
+    pub value_length: usize,
+    pub value_kind: String,  // 'price', 'weight'
+    pub value_decimals: u32, // 2 for cents, 3 for grams
+}
+
+impl Default for BarcodeSettings {
+    fn default() -> Self {
+        Self {
+            internal_prefix: "20".to_string(),
+            scale_prefixes: vec!["2".to_string()],
+            plu_start: 1,
+            plu_length: 4,
+            value_start: 6,
+            value_length: 6,
+            value_kind: "price".to_string(),
+            value_decimals: 2,
+        }
+    }
+}
+
+impl BarcodeSettings {
+    /// Settings from the shop settings JSON, defaults when absent
+    pub fn from_shop_settings(settings: Option<&str>) -> Self {
+        settings
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok())
+            .and_then(|v| v.get("barcodes").cloned())
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// PLU and embedded value read from a weighted-item label
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScaleReading {
+    pub plu: String,
+    pub value: f64, // Total price or weight in kg, per `value_kind`
+}
+
+/// A scanned code resolved to a product and quantity
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScanResult {
+    pub code: String,
+    pub matched_by: String, // 'barcode', 'gtin', 'sku', 'scale'
+    pub product: Product,
+    pub quantity: f64,
+    pub unit_price: f64,
+    pub total_price: Option<f64>, // Set for weighted items
+    pub barcode_id: Option<String>,
+}
+
+/// A rendered label sheet; PDF content is plain ASCII
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LabelDocument {
+    pub format: String, // 'svg', 'pdf', 'zpl'
+    pub mime_type: String,
+    pub content: String,
+    pub label_count: usize,
+}
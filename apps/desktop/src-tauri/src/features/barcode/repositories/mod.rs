@@ -0,0 +1 @@
+pub mod shop_barcode_repository;
@@ -0,0 +1,126 @@
+//! Shop-scoped Product Barcode Repository for Multi-Database Architecture
+
+use crate::features::barcode::models::barcode_model::ProductBarcode;
+use sqlx::{QueryBuilder, Result, Sqlite, SqlitePool};
+use std::sync::Arc;
+
+pub struct ShopBarcodeRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopBarcodeRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, barcode: &ProductBarcode) -> Result<ProductBarcode> {
+        let sql = r#"
+            INSERT INTO product_barcodes (
+                id, product_id, barcode, kind, quantity, description, _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, ProductBarcode>(sql)
+            .bind(&barcode.id)
+            .bind(&barcode.product_id)
+            .bind(&barcode.barcode)
+            .bind(&barcode.kind)
+            .bind(barcode.quantity)
+            .bind(&barcode.description)
+            .bind(&barcode.sync_status)
+            .bind(barcode.created_at)
+            .bind(barcode.updated_at)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        let sql = "UPDATE product_barcodes SET _status = 'deleted', updated_at = datetime('now') WHERE id = $1";
+        sqlx::query(sql).bind(id).execute(&*self.pool).await?;
+        Ok(())
+    }
+
+    pub async fn list_by_product(&self, product_id: &str) -> Result<Vec<ProductBarcode>> {
+        let sql = r#"
+            SELECT * FROM product_barcodes
+            WHERE product_id = $1 AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY created_at ASC
+        "#;
+        sqlx::query_as::<_, ProductBarcode>(sql)
+            .bind(product_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// Scannable code (any kind but PLU) matching `code` exactly
+    pub async fn find_by_code(&self, code: &str) -> Result<Option<ProductBarcode>> {
+        let sql = r#"
+            SELECT * FROM product_barcodes
+            WHERE barcode = $1 AND kind != 'plu'
+              AND (_status IS NULL OR _status != 'deleted')
+            LIMIT 1
+        "#;
+        sqlx::query_as::<_, ProductBarcode>(sql)
+            .bind(code)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    /// Scale PLU, ignoring leading zeros
+    pub async fn find_by_plu(&self, plu: &str) -> Result<Option<ProductBarcode>> {
+        let sql = r#"
+            SELECT * FROM product_barcodes
+            WHERE kind = 'plu' AND LTRIM(barcode, '0') = LTRIM($1, '0')
+              AND (_status IS NULL OR _status != 'deleted')
+            LIMIT 1
+        "#;
+        sqlx::query_as::<_, ProductBarcode>(sql)
+            .bind(plu)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    /// Highest internal code issued under `prefix`, among all-digit codes
+    pub async fn last_internal_code(&self, prefix: &str) -> Result<Option<String>> {
+        let sql = r#"
+            SELECT barcode FROM product_barcodes
+            WHERE kind = 'internal' AND LENGTH(barcode) = 13 AND barcode NOT GLOB '*[^0-9]*'
+              AND SUBSTR(barcode, 1, LENGTH($1)) = $1
+            ORDER BY barcode DESC
+            LIMIT 1
+        "#;
+        sqlx::query_scalar::<_, String>(sql)
+            .bind(prefix)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    /// Product whose GTIN is any of `gtins`
+    pub async fn find_product_id_by_gtin(&self, gtins: &[String]) -> Result<Option<String>> {
+        if gtins.is_empty() {
+            return Ok(None);
+        }
+
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT id FROM products WHERE _status != 'deleted' AND gtin_ean IN (",
+        );
+        let mut separated = builder.separated(", ");
+        for gtin in gtins {
+            separated.push_bind(gtin);
+        }
+        separated.push_unseparated(") LIMIT 1");
+
+        builder
+            .build_query_scalar::<String>()
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn find_product_id_by_sku(&self, sku: &str) -> Result<Option<String>> {
+        let sql = "SELECT id FROM products WHERE sku = $1 COLLATE NOCASE AND _status != 'deleted' LIMIT 1";
+        sqlx::query_scalar::<_, String>(sql)
+            .bind(sku)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+}
@@ -0,0 +1,100 @@
+//! GTIN (EAN-8, UPC-A, EAN-13, GTIN-14) check digits and weighted-item
+//! EAN-13 parsing
+
+use crate::features::barcode::models::barcode_model::{BarcodeSettings, ScaleReading};
+
+pub const GTIN_LENGTHS: [usize; 4] = [8, 12, 13, 14];
+
+/// Mod-10 check digit of a GTIN body (the code without its check digit)
+pub fn check_digit(body: &str) -> Option<u32> {
+    let mut sum = 0;
+    for (i, c) in body.chars().rev().enumerate() {
+        let digit = c.to_digit(10)?;
+        sum += if i % 2 == 0 { digit * 3 } else { digit };
+    }
+    Some((10 - sum % 10) % 10)
+}
+
+/// Append the check digit to a GTIN body
+pub fn with_check_digit(body: &str) -> Option<String> {
+    check_digit(body).map(|digit| format!("{}{}", body, digit))
+}
+
+pub fn validate_gtin(code: &str) -> Result<(), String> {
+    if code.is_empty() || !code.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("GTIN must contain only digits: {}", code));
+    }
+    if !GTIN_LENGTHS.contains(&code.len()) {
+        return Err(format!(
+            "GTIN must have 8, 12, 13 or 14 digits, got {}: {}",
+            code.len(),
+            code
+        ));
+    }
+
+    let (body, check) = code.split_at(code.len() - 1);
+    let expected = check_digit(body).unwrap_or_default();
+    if check.parse::<u32>().ok() != Some(expected) {
+        return Err(format!(
+            "Invalid GTIN check digit for {}: expected {}",
+            code, expected
+        ));
+    }
+    Ok(())
+}
+
+pub fn is_valid_gtin(code: &str) -> bool {
+    validate_gtin(code).is_ok()
+}
+
+/// Every stored form the same item may have: GTINs compare equal when
+/// left-padded with zeros, so a UPC-A scan finds an item stored as EAN-13
+pub fn gtin_variants(code: &str) -> Vec<String> {
+    let significant = code.trim_start_matches('0');
+    GTIN_LENGTHS
+        .iter()
+        .filter(|len| **len >= significant.len())
+        .map(|len| format!("{:0>width$}", significant, width = *len))
+        .collect()
+}
+
+/// The EAN-13 form of a GTIN, when it has one (GTIN-12 is zero-padded)
+pub fn to_ean13(code: &str) -> Option<String> {
+    if !is_valid_gtin(code) {
+        return None;
+    }
+    match code.len() {
+        13 => Some(code.to_string()),
+        12 => Some(format!("0{}", code)),
+        _ => None,
+    }
+}
+
+/// Read the PLU and embedded value of a weighted-item label
+pub fn parse_scale_barcode(code: &str, settings: &BarcodeSettings) -> Option<ScaleReading> {
+    if code.len() != 13 || !is_valid_gtin(code) {
+        return None;
+    }
+    if !settings
+        .scale_prefixes
+        .iter()
+        .any(|prefix| !prefix.is_empty() && code.starts_with(prefix.as_str()))
+    {
+        return None;
+    }
+
+    // The last digit is the check digit
+    let plu_end = settings.plu_start + settings.plu_length;
+    let value_end = settings.value_start + settings.value_length;
+    if settings.plu_length == 0 || plu_end > 12 || settings.value_length == 0 || value_end > 12 {
+        return None;
+    }
+    let plu = &code[settings.plu_start..plu_end];
+    let raw_value = &code[settings.value_start..value_end];
+
+    let value = raw_value.parse::<u64>().ok()? as f64 / 10f64.powi(settings.value_decimals as i32);
+    Some(ScaleReading {
+        plu: plu.to_string(),
+        value,
+    })
+}
@@ -0,0 +1,611 @@
+//! Shelf and product label layout, rendered to SVG, PDF or ZPL
+//!
+//! Layouts are computed once in millimetres; each renderer converts to its
+//! own units. ZPL leaves barcode drawing to the printer (`^BE`/`^BC`).
+
+use crate::features::barcode::services::symbology::{EncodedBarcode, Symbology};
+
+const MARGIN_MM: f64 = 2.0;
+const MAX_MODULE_MM: f64 = 0.5;
+const PT_PER_MM: f64 = 72.0 / 25.4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelTemplate {
+    Shelf,
+    Product,
+}
+
+impl LabelTemplate {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "shelf" => Ok(Self::Shelf),
+            "product" => Ok(Self::Product),
+            other => Err(format!("Invalid label template: {}", other)),
+        }
+    }
+
+    /// Default label size in millimetres (width, height)
+    pub fn default_size(&self) -> (f64, f64) {
+        match self {
+            Self::Shelf => (60.0, 40.0),
+            Self::Product => (50.0, 25.0),
+        }
+    }
+}
+
+/// What goes on one label
+#[derive(Debug, Clone)]
+pub struct LabelContent {
+    pub name: String,
+    pub price: String,
+    pub sku: String,
+    pub barcode: EncodedBarcode,
+    pub copies: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Anchor {
+    Start,
+    Middle,
+    End,
+}
+
+#[derive(Debug, Clone)]
+enum Element {
+    Text {
+        x: f64,
+        y: f64, // Baseline
+        size: f64,
+        bold: bool,
+        anchor: Anchor,
+        text: String,
+    },
+    Bars {
+        center_x: f64,
+        y: f64, // Top
+        height: f64,
+        max_width: f64,
+        barcode: EncodedBarcode,
+    },
+}
+
+pub struct LabelRenderer {
+    template: LabelTemplate,
+    width: f64,
+    height: f64,
+}
+
+impl LabelRenderer {
+    pub fn new(template: LabelTemplate, width_mm: f64, height_mm: f64) -> Result<Self, String> {
+        if !(15.0..=300.0).contains(&width_mm) || !(10.0..=300.0).contains(&height_mm) {
+            return Err(format!(
+                "Label size out of range: {} x {} mm",
+                width_mm, height_mm
+            ));
+        }
+        Ok(Self {
+            template,
+            width: width_mm,
+            height: height_mm,
+        })
+    }
+
+    // ============================================================
+    // Layout
+    // ============================================================
+
+    fn layout(&self, label: &LabelContent) -> Vec<Element> {
+        let (w, h) = (self.width, self.height);
+        let inner = w - 2.0 * MARGIN_MM;
+        let mut elements = Vec::new();
+
+        let bars_top = match self.template {
+            LabelTemplate::Shelf => {
+                let name_size = 3.5;
+                let price_size = (h * 0.22).clamp(5.0, 12.0);
+                let price_y = MARGIN_MM + name_size + 1.5 + price_size;
+                let price = fit_text(&label.price, price_size, true, inner);
+                let price_width = text_width(&price, price_size, true);
+
+                elements.push(text(
+                    MARGIN_MM,
+                    MARGIN_MM + name_size,
+                    name_size,
+                    true,
+                    Anchor::Start,
+                    fit_text(&label.name, name_size, true, inner),
+                ));
+                elements.push(text(
+                    w - MARGIN_MM,
+                    price_y,
+                    price_size,
+                    true,
+                    Anchor::End,
+                    price,
+                ));
+                elements.push(text(
+                    MARGIN_MM,
+                    price_y,
+                    2.2,
+                    false,
+                    Anchor::Start,
+                    fit_text(&label.sku, 2.2, false, inner - price_width - 2.0),
+                ));
+                price_y + 2.0
+            }
+            LabelTemplate::Product => {
+                let name_size = 2.8;
+                let price = fit_text(&label.price, 3.2, true, inner / 2.0);
+                let price_width = text_width(&price, 3.2, true);
+                let name_y = MARGIN_MM + 3.0;
+
+                elements.push(text(w - MARGIN_MM, name_y, 3.2, true, Anchor::End, price));
+                elements.push(text(
+                    MARGIN_MM,
+                    name_y,
+                    name_size,
+                    true,
+                    Anchor::Start,
+                    fit_text(&label.name, name_size, true, inner - price_width - 1.5),
+                ));
+                elements.push(text(
+                    MARGIN_MM,
+                    name_y + 2.6,
+                    2.0,
+                    false,
+                    Anchor::Start,
+                    fit_text(&label.sku, 2.0, false, inner),
+                ));
+                name_y + 4.0
+            }
+        };
+
+        // Barcode with its human readable text along the bottom edge
+        let digits_size = 2.2;
+        let digits_y = h - MARGIN_MM;
+        let bars_height = (digits_y - digits_size - 0.6 - bars_top).clamp(3.0, 15.0);
+        elements.push(Element::Bars {
+            center_x: w / 2.0,
+            y: digits_y - digits_size - 0.6 - bars_height,
+            height: bars_height,
+            max_width: inner,
+            barcode: label.barcode.clone(),
+        });
+        elements.push(text(
+            w / 2.0,
+            digits_y,
+            digits_size,
+            false,
+            Anchor::Middle,
+            label.barcode.data.clone(),
+        ));
+
+        elements
+    }
+
+    // ============================================================
+    // SVG
+    // ============================================================
+
+    /// One SVG with the labels stacked top to bottom
+    pub fn render_svg(&self, labels: &[LabelContent]) -> String {
+        let pages: Vec<&LabelContent> = expand_copies(labels);
+        let total_height = self.height * pages.len() as f64;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}mm\" height=\"{h}mm\" viewBox=\"0 0 {w} {h}\">\n",
+            w = num(self.width),
+            h = num(total_height)
+        );
+        for (i, label) in pages.iter().enumerate() {
+            svg.push_str(&format!(
+                "<g transform=\"translate(0 {})\">\n<rect width=\"{}\" height=\"{}\" fill=\"#fff\"/>\n",
+                num(self.height * i as f64),
+                num(self.width),
+                num(self.height)
+            ));
+            for element in self.layout(label) {
+                match element {
+                    Element::Text {
+                        x,
+                        y,
+                        size,
+                        bold,
+                        anchor,
+                        text,
+                    } => {
+                        svg.push_str(&format!(
+                            "<text x=\"{}\" y=\"{}\" font-family=\"Helvetica, Arial, sans-serif\" font-size=\"{}\"{} text-anchor=\"{}\">{}</text>\n",
+                            num(x),
+                            num(y),
+                            num(size),
+                            if bold { " font-weight=\"bold\"" } else { "" },
+                            match anchor {
+                                Anchor::Start => "start",
+                                Anchor::Middle => "middle",
+                                Anchor::End => "end",
+                            },
+                            xml_escape(&text)
+                        ));
+                    }
+                    Element::Bars {
+                        center_x,
+                        y,
+                        height,
+                        max_width,
+                        barcode,
+                    } => {
+                        let module = module_width(&barcode, max_width);
+                        let left = bars_left(&barcode, center_x, module);
+                        for (start, len) in bar_runs(&barcode.modules) {
+                            svg.push_str(&format!(
+                                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#000\"/>\n",
+                                num(left + start as f64 * module),
+                                num(y),
+                                num(len as f64 * module),
+                                num(height)
+                            ));
+                        }
+                    }
+                }
+            }
+            svg.push_str("</g>\n");
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    // ============================================================
+    // PDF
+    // ============================================================
+
+    /// A PDF with one page per label, sized to the label
+    pub fn render_pdf(&self, labels: &[LabelContent]) -> String {
+        let pages: Vec<&LabelContent> = expand_copies(labels);
+        let (page_w, page_h) = (self.width * PT_PER_MM, self.height * PT_PER_MM);
+
+        // 1: catalog, 2: pages, 3-4: fonts, then a page and its content per label
+        let mut objects: Vec<String> = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                (0..pages.len())
+                    .map(|i| format!("{} 0 R", 5 + i * 2))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                pages.len()
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+        ];
+
+        for (i, label) in pages.iter().enumerate() {
+            let mut stream = String::new();
+            for element in self.layout(label) {
+                match element {
+                    Element::Text {
+                        x,
+                        y,
+                        size,
+                        bold,
+                        anchor,
+                        text,
+                    } => {
+                        let width = text_width(&text, size, bold);
+                        let left = match anchor {
+                            Anchor::Start => x,
+                            Anchor::Middle => x - width / 2.0,
+                            Anchor::End => x - width,
+                        };
+                        stream.push_str(&format!(
+                            "BT /{} {} Tf {} {} Td ({}) Tj ET\n",
+                            if bold { "F2" } else { "F1" },
+                            num(size * PT_PER_MM),
+                            num(left * PT_PER_MM),
+                            num(page_h - y * PT_PER_MM),
+                            pdf_escape(&text)
+                        ));
+                    }
+                    Element::Bars {
+                        center_x,
+                        y,
+                        height,
+                        max_width,
+                        barcode,
+                    } => {
+                        let module = module_width(&barcode, max_width);
+                        let left = bars_left(&barcode, center_x, module);
+                        stream.push_str("0 0 0 rg\n");
+                        for (start, len) in bar_runs(&barcode.modules) {
+                            stream.push_str(&format!(
+                                "{} {} {} {} re\n",
+                                num((left + start as f64 * module) * PT_PER_MM),
+                                num(page_h - (y + height) * PT_PER_MM),
+                                num(len as f64 * module * PT_PER_MM),
+                                num(height * PT_PER_MM)
+                            ));
+                        }
+                        stream.push_str("f\n");
+                    }
+                }
+            }
+
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                num(page_w),
+                num(page_h),
+                6 + i * 2
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                stream.len(),
+                stream
+            ));
+        }
+
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+        }
+        let xref_offset = pdf.len();
+        pdf.push_str(&format!(
+            "xref\n0 {}\n0000000000 65535 f \n",
+            objects.len() + 1
+        ));
+        for offset in offsets {
+            pdf.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        pdf.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        ));
+        pdf
+    }
+
+    // ============================================================
+    // ZPL
+    // ============================================================
+
+    /// ZPL II for thermal label printers, one format per label
+    pub fn render_zpl(&self, labels: &[LabelContent], dpi: u32) -> String {
+        let dots_per_mm = dpi as f64 / 25.4;
+        let dots = |mm: f64| (mm * dots_per_mm).round().max(0.0) as i64;
+        let width_dots = dots(self.width);
+
+        let mut zpl = String::new();
+        for label in labels {
+            zpl.push_str(&format!(
+                "^XA\n^CI28\n^PW{}\n^LL{}\n",
+                width_dots,
+                dots(self.height)
+            ));
+            for element in self.layout(label) {
+                match element {
+                    Element::Text {
+                        x,
+                        y,
+                        size,
+                        bold,
+                        anchor,
+                        text,
+                    } => {
+                        let font = dots(size * if bold { 1.1 } else { 1.0 });
+                        let top = dots(y - size * 0.8);
+                        let field = match anchor {
+                            Anchor::Start => format!("^FO{},{}", dots(x), top),
+                            Anchor::Middle => {
+                                let half = dots(x.min(self.width - x));
+                                format!("^FO{},{}^FB{},1,0,C", dots(x) - half, top, half * 2)
+                            }
+                            Anchor::End => format!("^FO0,{}^FB{},1,0,R", top, dots(x)),
+                        };
+                        zpl.push_str(&format!(
+                            "{}^A0N,{},{}^FH_^FD{}^FS\n",
+                            field,
+                            font,
+                            font,
+                            zpl_escape(&text)
+                        ));
+                    }
+                    Element::Bars {
+                        center_x,
+                        y,
+                        height,
+                        max_width,
+                        barcode,
+                    } => {
+                        let module = ((max_width * dots_per_mm) / barcode.total_modules() as f64)
+                            .floor()
+                            .max(1.0) as i64;
+                        let (quiet_left, _) = barcode.symbology.quiet_zone();
+                        let symbol_width = barcode.total_modules() as i64 * module;
+                        let left = dots(center_x) - symbol_width / 2 + quiet_left as i64 * module;
+                        let bar_height = dots(height);
+                        let command = match barcode.symbology {
+                            // The printer appends the check digit
+                            Symbology::Ean13 => {
+                                format!("^BEN,{},N,N^FD{}^FS", bar_height, &barcode.data[..12])
+                            }
+                            Symbology::Code128 => format!(
+                                "^BCN,{},N,N,N,A^FH_^FD{}^FS",
+                                bar_height,
+                                zpl_escape(&barcode.data)
+                            ),
+                        };
+                        zpl.push_str(&format!(
+                            "^FO{},{}^BY{},2,{}{}\n",
+                            left.max(0),
+                            dots(y),
+                            module,
+                            bar_height,
+                            command
+                        ));
+                    }
+                }
+            }
+            zpl.push_str(&format!("^PQ{}\n^XZ\n", label.copies.max(1)));
+        }
+        zpl
+    }
+}
+
+fn text(x: f64, y: f64, size: f64, bold: bool, anchor: Anchor, text: String) -> Element {
+    Element::Text {
+        x,
+        y,
+        size,
+        bold,
+        anchor,
+        text,
+    }
+}
+
+fn expand_copies(labels: &[LabelContent]) -> Vec<&LabelContent> {
+    labels
+        .iter()
+        .flat_map(|label| std::iter::repeat_n(label, label.copies.max(1) as usize))
+        .collect()
+}
+
+fn module_width(barcode: &EncodedBarcode, max_width: f64) -> f64 {
+    (max_width / barcode.total_modules() as f64).min(MAX_MODULE_MM)
+}
+
+/// Left edge of the first module, centring the symbol and its quiet zones
+fn bars_left(barcode: &EncodedBarcode, center_x: f64, module: f64) -> f64 {
+    let (quiet_left, _) = barcode.symbology.quiet_zone();
+    center_x - barcode.total_modules() as f64 * module / 2.0 + quiet_left as f64 * module
+}
+
+/// Consecutive bar modules as (start, length)
+fn bar_runs(modules: &[bool]) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut i = 0;
+    while i < modules.len() {
+        if modules[i] {
+            let start = i;
+            while i < modules.len() && modules[i] {
+                i += 1;
+            }
+            runs.push((start, i - start));
+        } else {
+            i += 1;
+        }
+    }
+    runs
+}
+
+// ============================================================
+// Text
+// ============================================================
+
+/// Helvetica advance widths (1/1000 em) for ASCII 32..=126
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Approximate rendered width in mm of `text` at `size` mm
+fn text_width(text: &str, size: f64, bold: bool) -> f64 {
+    let units: u32 = text
+        .chars()
+        .map(|c| match c as u32 {
+            code @ 32..=126 => HELVETICA_WIDTHS[(code - 32) as usize] as u32,
+            0x2026 => 1000, // Ellipsis
+            _ => 556,
+        })
+        .sum();
+    units as f64 / 1000.0 * size * if bold { 1.06 } else { 1.0 }
+}
+
+/// Truncate `text` with an ellipsis so it fits in `max_width` mm
+fn fit_text(text: &str, size: f64, bold: bool, max_width: f64) -> String {
+    let text = text.trim();
+    if text_width(text, size, bold) <= max_width {
+        return text.to_string();
+    }
+    let mut fitted: String = text.to_string();
+    while !fitted.is_empty() && text_width(&format!("{}…", fitted), size, bold) > max_width {
+        fitted.pop();
+    }
+    if fitted.is_empty() {
+        String::new()
+    } else {
+        format!("{}…", fitted.trim_end())
+    }
+}
+
+/// Price text for a label, e.g. "R$ 1.234,90" for BRL
+pub fn format_price(amount: f64, currency: &str) -> String {
+    let cents = (amount * 100.0).round() as i64;
+    let (units, fraction) = (cents.abs() / 100, cents.abs() % 100);
+    let sign = if cents < 0 { "-" } else { "" };
+    match currency {
+        "BRL" => {
+            let digits = units.to_string();
+            let mut grouped = String::new();
+            for (i, c) in digits.chars().enumerate() {
+                if i > 0 && (digits.len() - i) % 3 == 0 {
+                    grouped.push('.');
+                }
+                grouped.push(c);
+            }
+            format!("{}R$ {},{:02}", sign, grouped, fraction)
+        }
+        other => format!("{}{} {}.{:02}", sign, other, units, fraction),
+    }
+}
+
+fn num(value: f64) -> String {
+    let formatted = format!("{:.3}", value);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// PDF literal string in WinAnsi encoding, kept ASCII with octal escapes
+fn pdf_escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        let code = match c {
+            '…' => 0x85,
+            '€' => 0x80,
+            '–' => 0x96,
+            '—' => 0x97,
+            c if (c as u32) < 0x100 => c as u32,
+            _ => '?' as u32,
+        };
+        match code {
+            0x28 | 0x29 | 0x5C => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            0x20..=0x7E => escaped.push(c),
+            _ => escaped.push_str(&format!("\\{:03o}", code)),
+        }
+    }
+    escaped
+}
+
+/// Field data for `^FH_`: the control characters become hex escapes
+fn zpl_escape(text: &str) -> String {
+    text.replace('_', "_5F")
+        .replace('^', "_5E")
+        .replace('~', "_7E")
+}
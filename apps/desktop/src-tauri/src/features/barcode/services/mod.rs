@@ -0,0 +1,4 @@
+pub mod gtin;
+pub mod label_renderer;
+pub mod shop_barcode_service;
+pub mod symbology;
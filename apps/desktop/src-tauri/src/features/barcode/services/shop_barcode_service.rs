@@ -0,0 +1,396 @@
+//! Shop-scoped Barcode Service for Multi-Database Architecture
+//!
+//! Resolves scanned codes to products and renders product/shelf labels.
+
+use crate::features::barcode::dtos::barcode_dto::{CreateProductBarcodeDTO, GenerateLabelsDTO};
+use crate::features::barcode::models::barcode_model::{
+    BarcodeSettings, LabelDocument, ProductBarcode, ScanResult,
+};
+use crate::features::barcode::repositories::shop_barcode_repository::ShopBarcodeRepository;
+use crate::features::barcode::services::gtin;
+use crate::features::barcode::services::label_renderer::{
+    format_price, LabelContent, LabelRenderer, LabelTemplate,
+};
+use crate::features::barcode::services::symbology::{EncodedBarcode, Symbology};
+use crate::features::product::models::product_model::Product;
+use crate::features::product::repositories::shop_product_repository::ShopProductRepository;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+const DEFAULT_ZPL_DPI: u32 = 203;
+const MAX_LABELS: u32 = 1000;
+
+/// Price charged for one unit: the promotional price when it undercuts the regular one
+fn selling_price(product: &Product) -> f64 {
+    match product.promotional_price {
+        Some(promotional_price) if promotional_price > 0.0 && promotional_price < product.price => {
+            promotional_price
+        }
+        _ => product.price,
+    }
+}
+
+fn round_to(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+pub struct ShopBarcodeService {
+    pool: Arc<SqlitePool>,
+    repo: ShopBarcodeRepository,
+    product_repo: ShopProductRepository,
+    settings: BarcodeSettings,
+}
+
+impl ShopBarcodeService {
+    pub fn new(pool: Arc<SqlitePool>, shop_id: String, settings: BarcodeSettings) -> Self {
+        let repo = ShopBarcodeRepository::new(pool.clone());
+        let product_repo = ShopProductRepository::new(pool.clone(), shop_id);
+        Self {
+            pool,
+            repo,
+            product_repo,
+            settings,
+        }
+    }
+
+    pub fn pool(&self) -> Arc<SqlitePool> {
+        self.pool.clone()
+    }
+
+    // ============================================================
+    // Product barcodes
+    // ============================================================
+
+    pub async fn create_barcode(
+        &self,
+        payload: CreateProductBarcodeDTO,
+    ) -> Result<ProductBarcode, String> {
+        self.get_product(&payload.product_id).await?;
+
+        let kind = payload.kind.as_deref().unwrap_or("internal");
+        let code = payload
+            .barcode
+            .as_deref()
+            .map(str::trim)
+            .filter(|code| !code.is_empty())
+            .map(str::to_string);
+
+        let code = match (kind, code) {
+            ("internal", None) => self.next_internal_code().await?,
+            (_, None) => return Err("Barcode is required".to_string()),
+            ("gtin", Some(code)) => {
+                gtin::validate_gtin(&code)?;
+                code
+            }
+            ("plu", Some(code)) => {
+                if !code.chars().all(|c| c.is_ascii_digit())
+                    || code.trim_start_matches('0').len() > self.settings.plu_length
+                {
+                    return Err(format!(
+                        "PLU must be numeric with at most {} digits: {}",
+                        self.settings.plu_length, code
+                    ));
+                }
+                code
+            }
+            ("internal" | "supplier", Some(code)) => code,
+            (other, _) => return Err(format!("Invalid barcode kind: {}", other)),
+        };
+
+        if payload.quantity.is_some_and(|q| q <= 0.0) {
+            return Err("Quantity per scan must be greater than zero".to_string());
+        }
+
+        let existing = if kind == "plu" {
+            self.repo.find_by_plu(&code).await
+        } else {
+            self.repo.find_by_code(&code).await
+        }
+        .map_err(|e| format!("Failed to check barcode: {}", e))?;
+        if let Some(existing) = existing {
+            return Err(format!(
+                "Barcode {} is already assigned to product {}",
+                code, existing.product_id
+            ));
+        }
+
+        let barcode = payload.into_model(code);
+        self.repo
+            .create(&barcode)
+            .await
+            .map_err(|e| format!("Failed to create barcode: {}", e))
+    }
+
+    pub async fn delete_barcode(&self, id: &str) -> Result<(), String> {
+        self.repo
+            .delete(id)
+            .await
+            .map_err(|e| format!("Failed to delete barcode: {}", e))
+    }
+
+    pub async fn list_barcodes(&self, product_id: &str) -> Result<Vec<ProductBarcode>, String> {
+        self.repo
+            .list_by_product(product_id)
+            .await
+            .map_err(|e| format!("Failed to list barcodes: {}", e))
+    }
+
+    /// Next free internal EAN-13 under the shop's internal prefix
+    async fn next_internal_code(&self) -> Result<String, String> {
+        let prefix = &self.settings.internal_prefix;
+        if prefix.is_empty() || prefix.len() > 10 || !prefix.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("Invalid internal barcode prefix: {}", prefix));
+        }
+        let counter_digits = 12 - prefix.len();
+
+        let last = self
+            .repo
+            .last_internal_code(prefix)
+            .await
+            .map_err(|e| format!("Failed to get last internal barcode: {}", e))?;
+        let next = match last {
+            Some(code) => {
+                code.get(prefix.len()..12)
+                    .and_then(|counter| counter.parse::<u64>().ok())
+                    .unwrap_or(0)
+                    + 1
+            }
+            None => 1,
+        };
+        if next >= 10u64.pow(counter_digits as u32) {
+            return Err(format!(
+                "Internal barcodes under prefix {} are exhausted",
+                prefix
+            ));
+        }
+
+        let body = format!("{}{:0>width$}", prefix, next, width = counter_digits);
+        gtin::with_check_digit(&body)
+            .ok_or_else(|| format!("Failed to compute check digit for {}", body))
+    }
+
+    // ============================================================
+    // Scanning
+    // ============================================================
+
+    /// Resolve a scanned code, trying in order: registered barcodes, product
+    /// GTINs, SKUs and weighted-item labels
+    pub async fn scan(&self, code: &str) -> Result<Option<ScanResult>, String> {
+        let code = code.trim();
+        if code.is_empty() {
+            return Err("Scanned code is empty".to_string());
+        }
+
+        if let Some(barcode) = self
+            .repo
+            .find_by_code(code)
+            .await
+            .map_err(|e| format!("Failed to look up barcode: {}", e))?
+        {
+            if let Some(product) = self.find_product(&barcode.product_id).await? {
+                let unit_price = selling_price(&product);
+                return Ok(Some(ScanResult {
+                    code: code.to_string(),
+                    matched_by: "barcode".to_string(),
+                    product,
+                    quantity: barcode.quantity,
+                    unit_price,
+                    total_price: None,
+                    barcode_id: Some(barcode.id),
+                }));
+            }
+        }
+
+        if gtin::is_valid_gtin(code) {
+            let product_id = self
+                .repo
+                .find_product_id_by_gtin(&gtin::gtin_variants(code))
+                .await
+                .map_err(|e| format!("Failed to look up GTIN: {}", e))?;
+            if let Some(product) = self.find_optional(product_id).await? {
+                return Ok(Some(self.unit_scan(code, "gtin", product)));
+            }
+        }
+
+        let product_id = self
+            .repo
+            .find_product_id_by_sku(code)
+            .await
+            .map_err(|e| format!("Failed to look up SKU: {}", e))?;
+        if let Some(product) = self.find_optional(product_id).await? {
+            return Ok(Some(self.unit_scan(code, "sku", product)));
+        }
+
+        if let Some(reading) = gtin::parse_scale_barcode(code, &self.settings) {
+            let plu = self
+                .repo
+                .find_by_plu(&reading.plu)
+                .await
+                .map_err(|e| format!("Failed to look up PLU: {}", e))?;
+            if let Some(plu) = plu {
+                if let Some(product) = self.find_product(&plu.product_id).await? {
+                    let unit_price = selling_price(&product);
+                    let (quantity, total_price) = if self.settings.value_kind == "weight" {
+                        (reading.value, round_to(reading.value * unit_price, 2))
+                    } else if unit_price > 0.0 {
+                        (round_to(reading.value / unit_price, 3), reading.value)
+                    } else {
+                        (1.0, reading.value)
+                    };
+                    return Ok(Some(ScanResult {
+                        code: code.to_string(),
+                        matched_by: "scale".to_string(),
+                        product,
+                        quantity,
+                        unit_price,
+                        total_price: Some(total_price),
+                        barcode_id: Some(plu.id),
+                    }));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn unit_scan(&self, code: &str, matched_by: &str, product: Product) -> ScanResult {
+        ScanResult {
+            code: code.to_string(),
+            matched_by: matched_by.to_string(),
+            unit_price: selling_price(&product),
+            product,
+            quantity: 1.0,
+            total_price: None,
+            barcode_id: None,
+        }
+    }
+
+    async fn find_product(&self, id: &str) -> Result<Option<Product>, String> {
+        self.product_repo
+            .get_by_id(id)
+            .await
+            .map_err(|e| format!("Failed to fetch product: {}", e))
+    }
+
+    async fn find_optional(&self, id: Option<String>) -> Result<Option<Product>, String> {
+        match id {
+            Some(id) => self.find_product(&id).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn get_product(&self, id: &str) -> Result<Product, String> {
+        self.find_product(id)
+            .await?
+            .ok_or_else(|| format!("Product not found: {}", id))
+    }
+
+    // ============================================================
+    // Labels
+    // ============================================================
+
+    pub async fn generate_labels(
+        &self,
+        payload: GenerateLabelsDTO,
+    ) -> Result<LabelDocument, String> {
+        if payload.items.is_empty() {
+            return Err("No products to label".to_string());
+        }
+        let total: u32 = payload
+            .items
+            .iter()
+            .map(|i| i.copies.unwrap_or(1).max(1))
+            .sum();
+        if total > MAX_LABELS {
+            return Err(format!("Too many labels: {} (max {})", total, MAX_LABELS));
+        }
+
+        let template = LabelTemplate::parse(payload.template.as_deref().unwrap_or("product"))?;
+        let symbology = payload
+            .symbology
+            .as_deref()
+            .map(Symbology::parse)
+            .transpose()?;
+        let (default_width, default_height) = template.default_size();
+        let renderer = LabelRenderer::new(
+            template,
+            payload.width_mm.unwrap_or(default_width),
+            payload.height_mm.unwrap_or(default_height),
+        )?;
+
+        let mut labels = Vec::with_capacity(payload.items.len());
+        for item in &payload.items {
+            let product = self.get_product(&item.product_id).await?;
+            let barcode = self.label_barcode(&product, symbology).await?;
+            labels.push(LabelContent {
+                name: product.name.clone(),
+                price: format_price(
+                    selling_price(&product),
+                    product.currency.as_deref().unwrap_or("BRL"),
+                ),
+                sku: product.sku.clone(),
+                barcode,
+                copies: item.copies.unwrap_or(1).max(1),
+            });
+        }
+
+        let (content, mime_type) = match payload.format.as_str() {
+            "svg" => (renderer.render_svg(&labels), "image/svg+xml"),
+            "pdf" => (renderer.render_pdf(&labels), "application/pdf"),
+            "zpl" => (
+                renderer.render_zpl(&labels, payload.dpi.unwrap_or(DEFAULT_ZPL_DPI)),
+                "application/zpl",
+            ),
+            other => return Err(format!("Invalid label format: {}", other)),
+        };
+
+        Ok(LabelDocument {
+            format: payload.format,
+            mime_type: mime_type.to_string(),
+            content,
+            label_count: total as usize,
+        })
+    }
+
+    /// Barcode printed on a product's label: its EAN-13 (GTIN or internal
+    /// code) when it has one, otherwise Code 128 of its GTIN, internal code
+    /// or SKU
+    async fn label_barcode(
+        &self,
+        product: &Product,
+        symbology: Option<Symbology>,
+    ) -> Result<EncodedBarcode, String> {
+        let gtin = product
+            .gtin_ean
+            .as_deref()
+            .map(str::trim)
+            .filter(|g| !g.is_empty());
+        let internal = self
+            .list_barcodes(&product.id)
+            .await?
+            .into_iter()
+            .find(|b| b.kind == "internal" && b.quantity == 1.0)
+            .map(|b| b.barcode);
+
+        let ean13 = gtin
+            .and_then(gtin::to_ean13)
+            .or_else(|| internal.as_deref().and_then(gtin::to_ean13));
+
+        match (symbology, ean13) {
+            (Some(Symbology::Ean13) | None, Some(ean13)) => {
+                EncodedBarcode::encode(Symbology::Ean13, &ean13)
+            }
+            (Some(Symbology::Ean13), None) => {
+                Err(format!("Product {} has no EAN-13 code", product.sku))
+            }
+            _ => {
+                let data = gtin
+                    .map(str::to_string)
+                    .or(internal)
+                    .unwrap_or_else(|| product.sku.clone());
+                EncodedBarcode::encode(Symbology::Code128, &data)
+            }
+        }
+    }
+}
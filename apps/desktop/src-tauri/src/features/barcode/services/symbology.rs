@@ -0,0 +1,170 @@
+//! Barcode symbologies encoded as module sequences (`true` = bar)
+
+use crate::features::barcode::services::gtin;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symbology {
+    Ean13,
+    Code128,
+}
+
+impl Symbology {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "ean13" => Ok(Self::Ean13),
+            "code128" => Ok(Self::Code128),
+            other => Err(format!("Invalid symbology: {}", other)),
+        }
+    }
+
+    /// Quiet zone width in modules (left, right)
+    pub fn quiet_zone(&self) -> (usize, usize) {
+        match self {
+            Self::Ean13 => (11, 7),
+            Self::Code128 => (10, 10),
+        }
+    }
+}
+
+/// An encoded barcode ready to be laid out
+#[derive(Debug, Clone)]
+pub struct EncodedBarcode {
+    pub symbology: Symbology,
+    pub data: String, // Encoded data, also the human readable text
+    pub modules: Vec<bool>,
+}
+
+impl EncodedBarcode {
+    pub fn encode(symbology: Symbology, data: &str) -> Result<Self, String> {
+        let modules = match symbology {
+            Symbology::Ean13 => ean13_modules(data)?,
+            Symbology::Code128 => code128_modules(data)?,
+        };
+        Ok(Self {
+            symbology,
+            data: data.to_string(),
+            modules,
+        })
+    }
+
+    /// Width including quiet zones, in modules
+    pub fn total_modules(&self) -> usize {
+        let (left, right) = self.symbology.quiet_zone();
+        left + self.modules.len() + right
+    }
+}
+
+// ============================================================
+// EAN-13
+// ============================================================
+
+const EAN_L: [&str; 10] = [
+    "0001101", "0011001", "0010011", "0111101", "0100011", "0110001", "0101111", "0111011",
+    "0110111", "0001011",
+];
+
+/// Left-half parity (L/G) selected by the first digit
+const EAN_PARITY: [&str; 10] = [
+    "LLLLLL", "LLGLGG", "LLGGLG", "LLGGGL", "LGLLGG", "LGGLLG", "LGGGLL", "LGLGLG", "LGLGGL",
+    "LGGLGL",
+];
+
+fn bits(pattern: &str) -> impl Iterator<Item = bool> + '_ {
+    pattern.chars().map(|c| c == '1')
+}
+
+/// The 95 modules of an EAN-13 symbol
+pub fn ean13_modules(code: &str) -> Result<Vec<bool>, String> {
+    if code.len() != 13 {
+        return Err(format!("EAN-13 needs 13 digits: {}", code));
+    }
+    gtin::validate_gtin(code)?;
+
+    let digits: Vec<usize> = code.bytes().map(|b| (b - b'0') as usize).collect();
+    let parity = EAN_PARITY[digits[0]].as_bytes();
+
+    let mut modules = Vec::with_capacity(95);
+    modules.extend(bits("101"));
+    for (i, digit) in digits[1..7].iter().enumerate() {
+        let l = EAN_L[*digit];
+        if parity[i] == b'L' {
+            modules.extend(bits(l));
+        } else {
+            // G code: the R code (inverted L) read backwards
+            modules.extend(bits(l).map(|b| !b).collect::<Vec<_>>().into_iter().rev());
+        }
+    }
+    modules.extend(bits("01010"));
+    for digit in &digits[7..13] {
+        modules.extend(bits(EAN_L[*digit]).map(|b| !b));
+    }
+    modules.extend(bits("101"));
+    Ok(modules)
+}
+
+// ============================================================
+// Code 128
+// ============================================================
+
+/// Bar/space widths of each Code 128 symbol value
+const CODE128_WIDTHS: [&str; 107] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212",
+    "221213", "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221",
+    "223211", "221132", "221231", "213212", "223112", "312131", "311222", "321122", "321221",
+    "312212", "322112", "322211", "212123", "212321", "232121", "111323", "131123", "131321",
+    "112313", "132113", "132311", "211313", "231113", "231311", "112133", "112331", "132131",
+    "113123", "113321", "133121", "313121", "211331", "231131", "213113", "213311", "213131",
+    "311123", "311321", "331121", "312113", "312311", "332111", "314111", "221411", "431111",
+    "111224", "111422", "121124", "121421", "141122", "141221", "112214", "112412", "122114",
+    "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111", "111242",
+    "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311",
+    "113141", "114131", "311141", "411131", "211412", "211214", "211232", "2331112",
+];
+
+const CODE128_START_B: usize = 104;
+const CODE128_START_C: usize = 105;
+const CODE128_STOP: usize = 106;
+
+/// Modules of a Code 128 symbol: code set C for even-length digit strings,
+/// code set B otherwise (printable ASCII)
+pub fn code128_modules(data: &str) -> Result<Vec<bool>, String> {
+    if data.is_empty() {
+        return Err("Code 128 data cannot be empty".to_string());
+    }
+
+    let use_set_c = data.len().is_multiple_of(2) && data.bytes().all(|b| b.is_ascii_digit());
+    let mut values = Vec::with_capacity(data.len() + 3);
+    if use_set_c {
+        values.push(CODE128_START_C);
+        for pair in data.as_bytes().chunks(2) {
+            values.push(((pair[0] - b'0') * 10 + (pair[1] - b'0')) as usize);
+        }
+    } else {
+        values.push(CODE128_START_B);
+        for c in data.chars() {
+            if !(' '..='~').contains(&c) {
+                return Err(format!("Code 128 cannot encode character {:?}", c));
+            }
+            values.push(c as usize - 32);
+        }
+    }
+
+    let checksum = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| i.max(1) * value)
+        .sum::<usize>()
+        % 103;
+    values.push(checksum);
+    values.push(CODE128_STOP);
+
+    let mut modules = Vec::new();
+    for value in values {
+        for (i, width) in CODE128_WIDTHS[value].bytes().enumerate() {
+            let bar = i % 2 == 0;
+            modules.extend(std::iter::repeat_n(bar, (width - b'0') as usize));
+        }
+    }
+    Ok(modules)
+}
@@ -7,7 +7,7 @@
 use crate::features::brand::models::brand_model::Brand;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, Result, SqlitePool};
+use sqlx::{Executor, FromRow, Result, Sqlite, SqlitePool, Transaction};
 use std::sync::Arc;
 
 /// Internal struct for deserializing from shop database (no shop_id column)
@@ -72,6 +72,21 @@ impl ShopBrandRepository {
     }
 
     pub async fn create(&self, brand: &Brand) -> Result<Brand> {
+        self.create_with(&*self.pool, brand).await
+    }
+
+    pub async fn create_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        brand: &Brand,
+    ) -> Result<Brand> {
+        self.create_with(&mut **tx, brand).await
+    }
+
+    async fn create_with<'e, E>(&self, executor: E, brand: &Brand) -> Result<Brand>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
         let sql = r#"
             INSERT INTO brands (
                 id, name, slug, logo_url, banner_url, description, rich_description,
@@ -100,7 +115,7 @@ impl ShopBrandRepository {
             .bind(&brand.sync_status)
             .bind(brand.created_at)
             .bind(brand.updated_at)
-            .fetch_one(&*self.pool)
+            .fetch_one(executor)
             .await?;
 
         Ok(shop_brand.into_brand(self.shop_id.clone()))
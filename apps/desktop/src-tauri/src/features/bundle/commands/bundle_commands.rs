@@ -0,0 +1,71 @@
+use crate::db::RepositoryFactory;
+use crate::features::bundle::dtos::bundle_dto::SetBundleComponentsDTO;
+use crate::features::bundle::models::bundle_model::{
+    BundleAvailability, BundleComponent, TransactionItemComponent,
+};
+use crate::features::bundle::services::shop_bundle_service::ShopBundleService;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn list_bundle_components(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    bundle_id: String,
+) -> Result<Vec<BundleComponent>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopBundleService::new(pool, shop_id);
+    service.list_components(&bundle_id).await
+}
+
+#[tauri::command]
+pub async fn set_bundle_components(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: SetBundleComponentsDTO,
+) -> Result<Vec<BundleComponent>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopBundleService::new(pool, shop_id);
+    service.set_components(payload).await
+}
+
+#[tauri::command]
+pub async fn get_bundle_availability(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    bundle_id: String,
+    location_id: Option<String>,
+) -> Result<BundleAvailability, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopBundleService::new(pool, shop_id);
+    service
+        .get_availability(&bundle_id, location_id.as_deref())
+        .await
+}
+
+#[tauri::command]
+pub async fn list_transaction_bundle_components(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    transaction_id: String,
+) -> Result<Vec<TransactionItemComponent>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopBundleService::new(pool, shop_id);
+    service.list_transaction_components(&transaction_id).await
+}
@@ -0,0 +1,42 @@
+use crate::features::bundle::models::bundle_model::BundleComponent;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleComponentDTO {
+    pub component_id: String,
+    pub quantity: f64,
+    pub price_share: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetBundleComponentsDTO {
+    pub bundle_id: String,
+    pub components: Vec<BundleComponentDTO>,
+}
+
+impl SetBundleComponentsDTO {
+    pub fn into_models(self) -> Vec<BundleComponent> {
+        let now = Utc::now();
+        self.components
+            .into_iter()
+            .enumerate()
+            .map(|(position, c)| BundleComponent {
+                id: Uuid::new_v4().to_string(),
+                bundle_id: self.bundle_id.clone(),
+                component_id: c.component_id,
+                quantity: c.quantity,
+                price_share: c.price_share,
+                position: Some(position as i64),
+                sync_status: Some("created".to_string()),
+                created_at: Some(now),
+                updated_at: Some(now),
+                component_sku: None,
+                component_name: None,
+                component_type: None,
+                component_price: None,
+            })
+            .collect()
+    }
+}
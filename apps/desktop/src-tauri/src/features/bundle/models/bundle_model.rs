@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct BundleComponent {
+    pub id: String,
+    pub bundle_id: String,
+    pub component_id: String,
+    pub quantity: f64,            // Units of the component per bundle
+    pub price_share: Option<f64>, // Allocation weight; defaults to component price
+    pub position: Option<i64>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    // Joined from products
+    #[sqlx(default)]
+    pub component_sku: Option<String>,
+    #[sqlx(default)]
+    pub component_name: Option<String>,
+    #[sqlx(default)]
+    pub component_price: Option<f64>,
+    #[sqlx(default)]
+    pub component_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct TransactionItemComponent {
+    pub id: String,
+    pub transaction_id: String,
+    pub transaction_item_id: String,
+    pub bundle_id: Option<String>,
+    pub component_id: Option<String>,
+    pub quantity: f64,         // Component units moved for the whole line
+    pub allocated_amount: f64, // Share of the line total
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ComponentAvailability {
+    pub component_id: String,
+    pub quantity_per_bundle: f64,
+    pub available: f64,
+    pub bundles_possible: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BundleAvailability {
+    pub bundle_id: String,
+    pub location_id: Option<String>, // None sums every location
+    pub available: Option<f64>,      // Minimum over stocked components, None if unlimited
+    pub components: Vec<ComponentAvailability>, // Digital goods and services left out
+}
@@ -0,0 +1 @@
+pub mod bundle_model;
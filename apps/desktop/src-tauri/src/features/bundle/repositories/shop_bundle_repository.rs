@@ -0,0 +1,162 @@
+//! Shop-scoped Bundle Repository for Multi-Database Architecture
+
+use crate::features::bundle::models::bundle_model::{BundleComponent, TransactionItemComponent};
+use sqlx::{Result, Sqlite, SqlitePool, Transaction};
+use std::sync::Arc;
+
+const COMPONENTS_SQL: &str = r#"
+    SELECT bc.*, p.sku AS component_sku, p.name AS component_name, p.price AS component_price,
+        p.type AS component_type
+    FROM bundle_components bc
+    JOIN products p ON p.id = bc.component_id
+    WHERE bc.bundle_id = $1 AND (bc._status IS NULL OR bc._status != 'deleted')
+    ORDER BY bc.position ASC
+"#;
+
+pub struct ShopBundleRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopBundleRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    // ============================================================
+    // Components
+    // ============================================================
+
+    pub async fn list_components(&self, bundle_id: &str) -> Result<Vec<BundleComponent>> {
+        sqlx::query_as::<_, BundleComponent>(COMPONENTS_SQL)
+            .bind(bundle_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn list_components_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        bundle_id: &str,
+    ) -> Result<Vec<BundleComponent>> {
+        sqlx::query_as::<_, BundleComponent>(COMPONENTS_SQL)
+            .bind(bundle_id)
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    /// Replace the bill of materials of a bundle
+    pub async fn replace_components_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        bundle_id: &str,
+        components: &[BundleComponent],
+    ) -> Result<()> {
+        sqlx::query("DELETE FROM bundle_components WHERE bundle_id = $1")
+            .bind(bundle_id)
+            .execute(&mut **tx)
+            .await?;
+
+        let sql = r#"
+            INSERT INTO bundle_components (
+                id, bundle_id, component_id, quantity, price_share, position,
+                _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#;
+
+        for component in components {
+            sqlx::query(sql)
+                .bind(&component.id)
+                .bind(&component.bundle_id)
+                .bind(&component.component_id)
+                .bind(component.quantity)
+                .bind(component.price_share)
+                .bind(component.position)
+                .bind(&component.sync_status)
+                .bind(component.created_at)
+                .bind(component.updated_at)
+                .execute(&mut **tx)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Sellable quantity (on hand minus reserved) of a product, at one
+    /// location or across all of them
+    pub async fn get_available_quantity(
+        &self,
+        product_id: &str,
+        location_id: Option<&str>,
+    ) -> Result<f64> {
+        let sql = r#"
+            SELECT COALESCE(SUM(quantity_on_hand - quantity_reserved), 0)
+            FROM inventory_levels
+            WHERE product_id = $1
+              AND ($2 IS NULL OR location_id = $2)
+              AND stock_status = 'sellable'
+              AND (_status IS NULL OR _status != 'deleted')
+        "#;
+        sqlx::query_scalar::<_, f64>(sql)
+            .bind(product_id)
+            .bind(location_id)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    // ============================================================
+    // Transaction item components
+    // ============================================================
+
+    pub async fn create_item_component_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        component: &TransactionItemComponent,
+    ) -> Result<TransactionItemComponent> {
+        let sql = r#"
+            INSERT INTO transaction_item_components (
+                id, transaction_id, transaction_item_id, bundle_id, component_id,
+                quantity, allocated_amount, _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, TransactionItemComponent>(sql)
+            .bind(&component.id)
+            .bind(&component.transaction_id)
+            .bind(&component.transaction_item_id)
+            .bind(&component.bundle_id)
+            .bind(&component.component_id)
+            .bind(component.quantity)
+            .bind(component.allocated_amount)
+            .bind(&component.sync_status)
+            .bind(component.created_at)
+            .bind(component.updated_at)
+            .fetch_one(&mut **tx)
+            .await
+    }
+
+    pub async fn list_item_components_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        transaction_item_id: &str,
+    ) -> Result<Vec<TransactionItemComponent>> {
+        let sql = r#"
+            SELECT * FROM transaction_item_components
+            WHERE transaction_item_id = $1 AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY created_at ASC
+        "#;
+        sqlx::query_as::<_, TransactionItemComponent>(sql)
+            .bind(transaction_item_id)
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    pub async fn list_item_components_by_transaction(
+        &self,
+        transaction_id: &str,
+    ) -> Result<Vec<TransactionItemComponent>> {
+        let sql = r#"
+            SELECT * FROM transaction_item_components
+            WHERE transaction_id = $1 AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY created_at ASC
+        "#;
+        sqlx::query_as::<_, TransactionItemComponent>(sql)
+            .bind(transaction_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+}
@@ -0,0 +1 @@
+pub mod shop_bundle_service;
@@ -0,0 +1,381 @@
+//! Shop-scoped Bundle Service for Multi-Database Architecture
+//!
+//! Bundles (kits) have no stock of their own: availability is the minimum over
+//! their components, and selling or shipping a bundle moves component stock.
+//! The line price of a sold bundle is split across its components in
+//! transaction_item_components so reports and returns can use it.
+
+use crate::db::DbTransaction;
+use crate::features::bundle::dtos::bundle_dto::SetBundleComponentsDTO;
+use crate::features::bundle::models::bundle_model::{
+    BundleAvailability, BundleComponent, ComponentAvailability, TransactionItemComponent,
+};
+use crate::features::bundle::repositories::shop_bundle_repository::ShopBundleRepository;
+use crate::features::inventory::repositories::inventory_levels_repository::InventoryLevelsRepository;
+use crate::features::inventory::repositories::inventory_movements_repository::InventoryMovementsRepository;
+use crate::features::inventory::repositories::shop_inventory_repository::ShopInventoryRepository;
+use crate::features::product::models::product_model::type_tracks_inventory;
+use crate::features::product::repositories::shop_product_repository::ShopProductRepository;
+use crate::features::transaction::models::transaction_model::{InventoryMovement, TransactionItem};
+use chrono::Utc;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn round_cents(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+pub struct ShopBundleService {
+    pool: Arc<SqlitePool>,
+    shop_id: String,
+    repo: ShopBundleRepository,
+}
+
+impl ShopBundleService {
+    pub fn new(pool: Arc<SqlitePool>, shop_id: String) -> Self {
+        let repo = ShopBundleRepository::new(pool.clone());
+        Self {
+            pool,
+            shop_id,
+            repo,
+        }
+    }
+
+    pub fn pool(&self) -> Arc<SqlitePool> {
+        self.pool.clone()
+    }
+
+    // ============================================================
+    // Composition
+    // ============================================================
+
+    pub async fn list_components(&self, bundle_id: &str) -> Result<Vec<BundleComponent>, String> {
+        self.repo
+            .list_components(bundle_id)
+            .await
+            .map_err(|e| format!("Failed to list bundle components: {}", e))
+    }
+
+    /// Replace the components of a bundle product
+    pub async fn set_components(
+        &self,
+        payload: SetBundleComponentsDTO,
+    ) -> Result<Vec<BundleComponent>, String> {
+        let product_repo = ShopProductRepository::new(self.pool.clone(), self.shop_id.clone());
+
+        let bundle = product_repo
+            .get_by_id(&payload.bundle_id)
+            .await
+            .map_err(|e| format!("Failed to fetch product: {}", e))?
+            .ok_or_else(|| format!("Product not found: {}", payload.bundle_id))?;
+        if bundle.r#type != "bundle" {
+            return Err(format!("Product {} is not a bundle", bundle.sku));
+        }
+
+        for (index, component) in payload.components.iter().enumerate() {
+            if component.quantity <= 0.0 {
+                return Err("Component quantity must be greater than zero".to_string());
+            }
+            if component.price_share.is_some_and(|share| share < 0.0) {
+                return Err("Component price share cannot be negative".to_string());
+            }
+            if component.component_id == payload.bundle_id {
+                return Err("A bundle cannot contain itself".to_string());
+            }
+            if payload.components[..index]
+                .iter()
+                .any(|c| c.component_id == component.component_id)
+            {
+                return Err(format!("Duplicate component: {}", component.component_id));
+            }
+
+            let product = product_repo
+                .get_by_id(&component.component_id)
+                .await
+                .map_err(|e| format!("Failed to fetch product: {}", e))?
+                .ok_or_else(|| format!("Product not found: {}", component.component_id))?;
+            if product.r#type == "bundle" {
+                return Err(format!("Bundle {} cannot be a component", product.sku));
+            }
+        }
+
+        let bundle_id = payload.bundle_id.clone();
+        let components = payload.into_models();
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        ShopBundleRepository::replace_components_in_tx(&mut tx, &bundle_id, &components)
+            .await
+            .map_err(|e| format!("Failed to save bundle components: {}", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        self.list_components(&bundle_id).await
+    }
+
+    /// How many bundles can be assembled from component stock. Unlimited
+    /// (None) when no component tracks stock.
+    pub async fn get_availability(
+        &self,
+        bundle_id: &str,
+        location_id: Option<&str>,
+    ) -> Result<BundleAvailability, String> {
+        let components = self.list_components(bundle_id).await?;
+        if components.is_empty() {
+            return Err("Bundle has no components".to_string());
+        }
+
+        let mut availability = Vec::with_capacity(components.len());
+        for component in components {
+            if component
+                .component_type
+                .as_deref()
+                .is_some_and(|t| !type_tracks_inventory(t))
+            {
+                continue;
+            }
+            let available = self
+                .repo
+                .get_available_quantity(&component.component_id, location_id)
+                .await
+                .map_err(|e| format!("Failed to get available quantity: {}", e))?;
+
+            availability.push(ComponentAvailability {
+                bundles_possible: (available / component.quantity).floor().max(0.0),
+                component_id: component.component_id,
+                quantity_per_bundle: component.quantity,
+                available,
+            });
+        }
+
+        Ok(BundleAvailability {
+            bundle_id: bundle_id.to_string(),
+            location_id: location_id.map(str::to_string),
+            available: availability
+                .iter()
+                .map(|c| c.bundles_possible)
+                .reduce(f64::min),
+            components: availability,
+        })
+    }
+
+    pub async fn list_transaction_components(
+        &self,
+        transaction_id: &str,
+    ) -> Result<Vec<TransactionItemComponent>, String> {
+        self.repo
+            .list_item_components_by_transaction(transaction_id)
+            .await
+            .map_err(|e| format!("Failed to list transaction item components: {}", e))
+    }
+
+    // ============================================================
+    // Stock and allocation helpers (run inside the caller's transaction)
+    // ============================================================
+
+    /// Products whose stock moves when `quantity` units of `product_id` leave:
+    /// the components for a bundle, the product itself otherwise. Digital
+    /// goods and services have no stock and are left out.
+    pub async fn stock_lines_in_tx(
+        tx: &mut DbTransaction<'_>,
+        product_id: &str,
+        quantity: f64,
+    ) -> Result<Vec<(String, f64)>, String> {
+        let components = ShopBundleRepository::list_components_in_tx(tx, product_id)
+            .await
+            .map_err(|e| format!("Failed to list bundle components: {}", e))?;
+
+        let lines = if components.is_empty() {
+            vec![(product_id.to_string(), quantity)]
+        } else {
+            components
+                .into_iter()
+                .map(|c| (c.component_id, c.quantity * quantity))
+                .collect()
+        };
+        Self::stocked_lines_in_tx(tx, lines).await
+    }
+
+    /// Drop the lines of products that have no stock
+    pub async fn stocked_lines_in_tx(
+        tx: &mut DbTransaction<'_>,
+        lines: Vec<(String, f64)>,
+    ) -> Result<Vec<(String, f64)>, String> {
+        let ids: Vec<String> = lines.iter().map(|(id, _)| id.clone()).collect();
+        let non_stock = ShopProductRepository::non_stock_ids_in_tx(tx, &ids)
+            .await
+            .map_err(|e| format!("Failed to fetch product types: {}", e))?;
+
+        Ok(lines
+            .into_iter()
+            .filter(|(id, _)| !non_stock.contains(id))
+            .collect())
+    }
+
+    /// Create the OUT movements for `quantity` units of a product leaving a location
+    pub async fn stock_out_in_tx(
+        tx: &mut DbTransaction<'_>,
+        product_id: &str,
+        quantity: f64,
+        location_id: &str,
+        transaction_id: Option<&str>,
+    ) -> Result<(), String> {
+        for (stock_product_id, stock_quantity) in
+            Self::stock_lines_in_tx(tx, product_id, quantity).await?
+        {
+            let level = InventoryLevelsRepository::find_by_product_and_location_with_tx(
+                tx,
+                &stock_product_id,
+                location_id,
+            )
+            .await
+            .map_err(|e| format!("Failed to fetch inventory level: {}", e))?
+            .ok_or_else(|| {
+                format!(
+                    "No inventory level for product {} at location {}",
+                    stock_product_id, location_id
+                )
+            })?;
+
+            let now = Utc::now();
+            let movement = InventoryMovement {
+                id: Uuid::new_v4().to_string(),
+                transaction_id: transaction_id.map(str::to_string),
+                inventory_level_id: Some(level.id.clone()),
+                movement_type: Some("out".to_string()),
+                quantity: stock_quantity,
+                previous_balance: Some(level.quantity_on_hand),
+                new_balance: Some(level.quantity_on_hand - stock_quantity),
+                sync_status: Some("created".to_string()),
+                created_at: Some(now),
+                updated_at: Some(now),
+            };
+
+            // The trigger 'trg_inventory_movement_update_level' updates quantity_on_hand
+            InventoryMovementsRepository::create_with_tx(tx, movement)
+                .await
+                .map_err(|e| format!("Failed to create inventory movement: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Put back the stock a transaction's OUT movements took, e.g. when a
+    /// completed sale is cancelled
+    pub async fn restock_transaction_in_tx(
+        tx: &mut DbTransaction<'_>,
+        transaction_id: &str,
+    ) -> Result<(), String> {
+        let movements =
+            ShopInventoryRepository::list_movements_by_transaction_in_tx(tx, transaction_id)
+                .await
+                .map_err(|e| format!("Failed to list inventory movements: {}", e))?;
+
+        for movement in movements {
+            let level_id = match (&movement.movement_type, &movement.inventory_level_id) {
+                (Some(movement_type), Some(level_id)) if movement_type == "out" => level_id,
+                _ => continue,
+            };
+            let level = match ShopInventoryRepository::get_level_by_id_in_tx(tx, level_id)
+                .await
+                .map_err(|e| format!("Failed to fetch inventory level: {}", e))?
+            {
+                Some(level) => level,
+                None => continue,
+            };
+
+            let now = Utc::now();
+            let restock = InventoryMovement {
+                id: Uuid::new_v4().to_string(),
+                transaction_id: Some(transaction_id.to_string()),
+                inventory_level_id: Some(level.id.clone()),
+                movement_type: Some("in".to_string()),
+                quantity: movement.quantity,
+                previous_balance: Some(level.quantity_on_hand),
+                new_balance: Some(level.quantity_on_hand + movement.quantity),
+                sync_status: Some("created".to_string()),
+                created_at: Some(now),
+                updated_at: Some(now),
+            };
+
+            // The trigger 'trg_inventory_movement_update_level' updates quantity_on_hand
+            InventoryMovementsRepository::create_with_tx(tx, restock)
+                .await
+                .map_err(|e| format!("Failed to create inventory movement: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Split the total of a sold bundle line across its components, weighted by
+    /// `price_share` or else by component price × quantity. Rounding differences
+    /// go to the last component so the shares add up to the line total.
+    /// Lines that are not bundles record nothing.
+    pub async fn record_allocation_in_tx(
+        tx: &mut DbTransaction<'_>,
+        item: &TransactionItem,
+    ) -> Result<Vec<TransactionItemComponent>, String> {
+        let product_id = match &item.product_id {
+            Some(product_id) => product_id,
+            None => return Ok(Vec::new()),
+        };
+
+        let components = ShopBundleRepository::list_components_in_tx(tx, product_id)
+            .await
+            .map_err(|e| format!("Failed to list bundle components: {}", e))?;
+        if components.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut weights: Vec<f64> = components
+            .iter()
+            .map(|c| {
+                c.price_share
+                    .unwrap_or_else(|| c.component_price.unwrap_or(0.0) * c.quantity)
+            })
+            .collect();
+        if weights.iter().sum::<f64>() <= 0.0 {
+            weights = components.iter().map(|c| c.quantity).collect();
+        }
+        let total_weight: f64 = weights.iter().sum();
+
+        let line_total = round_cents(item.quantity * item.unit_price);
+        let mut remaining = line_total;
+        let now = Utc::now();
+        let mut recorded = Vec::with_capacity(components.len());
+
+        for (index, (component, weight)) in components.iter().zip(weights).enumerate() {
+            let allocated_amount = if index + 1 == components.len() {
+                round_cents(remaining)
+            } else {
+                round_cents(line_total * weight / total_weight)
+            };
+            remaining -= allocated_amount;
+
+            let row = TransactionItemComponent {
+                id: Uuid::new_v4().to_string(),
+                transaction_id: item.transaction_id.clone(),
+                transaction_item_id: item.id.clone(),
+                bundle_id: Some(product_id.clone()),
+                component_id: Some(component.component_id.clone()),
+                quantity: component.quantity * item.quantity,
+                allocated_amount,
+                sync_status: Some("created".to_string()),
+                created_at: Some(now),
+                updated_at: Some(now),
+            };
+
+            let created = ShopBundleRepository::create_item_component_in_tx(tx, &row)
+                .await
+                .map_err(|e| format!("Failed to record bundle allocation: {}", e))?;
+            recorded.push(created);
+        }
+
+        Ok(recorded)
+    }
+}
@@ -0,0 +1,114 @@
+use crate::db::RepositoryFactory;
+use crate::features::carrier::dtos::carrier_dto::{
+    BuyShippingLabelDTO, QuoteShippingDTO, UpsertCarrierAccountDTO,
+};
+use crate::features::carrier::models::carrier_model::{
+    CarrierAccount, ShippingQuote, TrackingPollResult,
+};
+use crate::features::carrier::services::shop_carrier_service::ShopCarrierService;
+use crate::features::shipment::models::shipment_model::Shipment;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn list_carrier_accounts(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+) -> Result<Vec<CarrierAccount>, String> {
+    let pool = repo_factory
+        .shop_module_pool(&shop_id, "shipping")
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopCarrierService::new(pool, shop_id);
+    service.list_accounts().await
+}
+
+#[tauri::command]
+pub async fn upsert_carrier_account(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: UpsertCarrierAccountDTO,
+) -> Result<CarrierAccount, String> {
+    let pool = repo_factory
+        .shop_module_pool(&shop_id, "shipping")
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopCarrierService::new(pool, shop_id);
+    service.upsert_account(payload).await
+}
+
+#[tauri::command]
+pub async fn delete_carrier_account(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+) -> Result<(), String> {
+    let pool = repo_factory
+        .shop_module_pool(&shop_id, "shipping")
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopCarrierService::new(pool, shop_id);
+    service.delete_account(&id).await
+}
+
+#[tauri::command]
+pub async fn quote_shipping_rates(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: QuoteShippingDTO,
+) -> Result<Vec<ShippingQuote>, String> {
+    let pool = repo_factory
+        .shop_module_pool(&shop_id, "shipping")
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopCarrierService::new(pool, shop_id);
+    service.quote_rates(payload).await
+}
+
+#[tauri::command]
+pub async fn buy_shipping_label(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: BuyShippingLabelDTO,
+) -> Result<Shipment, String> {
+    let pool = repo_factory
+        .shop_module_pool(&shop_id, "shipping")
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopCarrierService::new(pool, shop_id);
+    service.buy_label(payload).await
+}
+
+#[tauri::command]
+pub async fn refresh_shipment_tracking(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    shipment_id: String,
+) -> Result<TrackingPollResult, String> {
+    let pool = repo_factory
+        .shop_module_pool(&shop_id, "shipping")
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopCarrierService::new(pool, shop_id);
+    service.refresh_tracking(&shipment_id).await
+}
+
+#[tauri::command]
+pub async fn poll_shipment_tracking(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+) -> Result<Vec<TrackingPollResult>, String> {
+    let pool = repo_factory
+        .shop_module_pool(&shop_id, "shipping")
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopCarrierService::new(pool, shop_id);
+    service.poll_tracking().await
+}
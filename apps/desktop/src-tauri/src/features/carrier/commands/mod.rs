@@ -0,0 +1 @@
+pub mod carrier_commands;
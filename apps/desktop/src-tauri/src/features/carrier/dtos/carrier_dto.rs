@@ -0,0 +1,60 @@
+use crate::features::carrier::models::carrier_model::CarrierAccount;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpsertCarrierAccountDTO {
+    pub carrier: String, // 'correios', 'melhor_envio'
+    pub name: Option<String>,
+    pub base_url: Option<String>,
+    pub is_sandbox: Option<bool>,
+    pub origin_postal_code: String,
+    pub credentials: Option<String>, // JSONB
+    pub settings: Option<String>,    // JSONB
+    pub is_active: Option<bool>,
+}
+
+impl UpsertCarrierAccountDTO {
+    pub fn into_model(self) -> CarrierAccount {
+        let now = Utc::now();
+        CarrierAccount {
+            id: Uuid::new_v4().to_string(),
+            carrier: self.carrier,
+            name: self.name,
+            base_url: self.base_url.filter(|url| !url.trim().is_empty()),
+            is_sandbox: self.is_sandbox.or(Some(false)),
+            origin_postal_code: self.origin_postal_code,
+            credentials: self.credentials,
+            settings: self.settings,
+            is_active: self.is_active.or(Some(true)),
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShippingItemDTO {
+    pub product_id: String,
+    pub quantity: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuoteShippingDTO {
+    pub destination_postal_code: String,
+    pub items: Vec<ShippingItemDTO>,
+    pub declared_value: Option<f64>,
+    pub carrier: Option<String>, // Quote a single carrier instead of every active one
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BuyShippingLabelDTO {
+    pub shipment_id: String,
+    pub carrier: String,
+    pub service_code: String,
+    /// Used to compute the package when the shipment has no weight/dimensions yet
+    pub items: Option<Vec<ShippingItemDTO>>,
+    pub declared_value: Option<f64>,
+}
@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct CarrierAccount {
+    pub id: String,
+    pub carrier: String, // 'correios', 'melhor_envio'
+    pub name: Option<String>,
+    pub base_url: Option<String>,
+    pub is_sandbox: Option<bool>,
+    pub origin_postal_code: String,
+    pub credentials: Option<String>, // JSONB
+    pub settings: Option<String>,    // JSONB
+    pub is_active: Option<bool>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Physical package sent to a carrier
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Package {
+    pub weight_g: i64,
+    pub height_mm: i64,
+    pub width_mm: i64,
+    pub depth_mm: i64,
+}
+
+/// Postal address, with the same field names as customer_addresses
+/// plus the extra fields Brazilian carriers require
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ShippingAddress {
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub company: Option<String>,
+    pub document: Option<String>, // CPF/CNPJ
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub address1: Option<String>,
+    pub address2: Option<String>,
+    pub number: Option<String>,
+    pub district: Option<String>,
+    pub city: Option<String>,
+    pub province_code: Option<String>,
+    pub country_code: Option<String>,
+    pub postal_code: Option<String>,
+}
+
+impl ShippingAddress {
+    pub fn full_name(&self) -> String {
+        let name = [self.first_name.as_deref(), self.last_name.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if name.is_empty() {
+            self.company.clone().unwrap_or_default()
+        } else {
+            name
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuoteRequest {
+    pub origin_postal_code: String,
+    pub destination_postal_code: String,
+    pub package: Package,
+    pub declared_value: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShippingQuote {
+    pub carrier: String,
+    pub service_code: String,
+    pub service_name: String,
+    pub price: f64,
+    pub delivery_days: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LabelRequest {
+    pub service_code: String,
+    pub sender: ShippingAddress,
+    pub recipient: ShippingAddress,
+    pub package: Package,
+    pub declared_value: Option<f64>,
+    pub invoice_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PurchasedLabel {
+    pub tracking_number: Option<String>,
+    pub label_url: Option<String>,
+    pub cost_amount: Option<f64>,
+    pub provider_reference: String, // Carrier-side id used to track the shipment
+}
+
+/// Tracking event normalized to shipment statuses:
+/// 'shipped', 'in_transit', 'out_for_delivery', 'delivered', 'exception'
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrackingUpdate {
+    pub status: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub happened_at: DateTime<Utc>,
+    pub raw_data: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrackingPollResult {
+    pub shipment_id: String,
+    pub new_events: usize,
+    pub status: Option<String>,
+    pub error: Option<String>,
+}
@@ -0,0 +1,91 @@
+//! Carrier provider abstraction
+//!
+//! Each carrier integration implements `CarrierProvider`. Adapters talk to the
+//! carrier's HTTP API at `base_url`, which an account can override to point
+//! at a local stub.
+
+use crate::features::carrier::models::carrier_model::{
+    CarrierAccount, LabelRequest, PurchasedLabel, QuoteRequest, ShippingQuote, TrackingUpdate,
+};
+use crate::features::carrier::providers::correios_provider::CorreiosProvider;
+use crate::features::carrier::providers::melhor_envio_provider::MelhorEnvioProvider;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::time::Duration;
+
+#[async_trait]
+pub trait CarrierProvider: Send + Sync {
+    /// Carrier identifier, as stored in `shipments.carrier_company`
+    fn carrier(&self) -> &str;
+
+    /// Quote every service the carrier offers for a package
+    async fn quote(&self, request: &QuoteRequest) -> Result<Vec<ShippingQuote>, String>;
+
+    /// Buy a label for one service
+    async fn buy_label(&self, request: &LabelRequest) -> Result<PurchasedLabel, String>;
+
+    /// Fetch all tracking events known for a shipment, oldest first
+    async fn track(
+        &self,
+        tracking_number: Option<&str>,
+        provider_reference: Option<&str>,
+    ) -> Result<Vec<TrackingUpdate>, String>;
+}
+
+/// Build the provider adapter for a carrier account
+pub fn provider_for(account: &CarrierAccount) -> Result<Box<dyn CarrierProvider>, String> {
+    let credentials = parse_json(account.credentials.as_deref());
+    let settings = parse_json(account.settings.as_deref());
+    let sandbox = account.is_sandbox.unwrap_or(false);
+
+    match account.carrier.as_str() {
+        "correios" => Ok(Box::new(CorreiosProvider::new(
+            account.base_url.clone(),
+            sandbox,
+            credentials,
+            settings,
+        )?)),
+        "melhor_envio" => Ok(Box::new(MelhorEnvioProvider::new(
+            account.base_url.clone(),
+            sandbox,
+            credentials,
+            settings,
+        )?)),
+        other => Err(format!("Unsupported carrier: {}", other)),
+    }
+}
+
+pub(crate) fn http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
+
+pub(crate) fn parse_json(value: Option<&str>) -> Value {
+    value
+        .and_then(|v| serde_json::from_str(v).ok())
+        .unwrap_or(Value::Null)
+}
+
+/// Read a response body as JSON, turning non-2xx statuses into errors
+pub(crate) async fn read_json(response: reqwest::Response, context: &str) -> Result<Value, String> {
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read {} response: {}", context, e))?;
+    if !status.is_success() {
+        return Err(format!("{} failed with status {}: {}", context, status, body));
+    }
+    serde_json::from_str(&body).map_err(|e| format!("Invalid {} response: {}", context, e))
+}
+
+pub(crate) fn only_digits(value: &str) -> String {
+    value.chars().filter(|c| c.is_ascii_digit()).collect()
+}
+
+/// Millimetres to whole centimetres, rounded up
+pub(crate) fn mm_to_cm(mm: i64) -> i64 {
+    (mm + 9) / 10
+}
@@ -0,0 +1,330 @@
+//! Correios adapter (api.correios.com.br)
+//!
+//! Credentials: `{ "username", "access_code", "posting_card" }` (contract user,
+//! API access code and cartão de postagem). Settings: `{ "services": ["03220", "03298"] }`.
+
+use crate::features::carrier::models::carrier_model::{
+    LabelRequest, Package, PurchasedLabel, QuoteRequest, ShippingAddress, ShippingQuote,
+    TrackingUpdate,
+};
+use crate::features::carrier::providers::carrier_provider::{
+    http_client, mm_to_cm, only_digits, read_json, CarrierProvider,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+use serde_json::{json, Value};
+
+const PRODUCTION_URL: &str = "https://api.correios.com.br";
+const SANDBOX_URL: &str = "https://apihom.correios.com.br";
+const DEFAULT_SERVICES: [&str; 2] = ["03220", "03298"];
+
+// Correios' minimum package: 16 x 11 x 2 cm, 300 g
+const MIN_LENGTH_CM: i64 = 16;
+const MIN_WIDTH_CM: i64 = 11;
+const MIN_HEIGHT_CM: i64 = 2;
+const MIN_WEIGHT_G: i64 = 300;
+
+pub struct CorreiosProvider {
+    client: reqwest::Client,
+    base_url: String,
+    username: String,
+    access_code: String,
+    posting_card: String,
+    services: Vec<String>,
+}
+
+impl CorreiosProvider {
+    pub fn new(
+        base_url: Option<String>,
+        sandbox: bool,
+        credentials: Value,
+        settings: Value,
+    ) -> Result<Self, String> {
+        let credential = |key: &str| {
+            credentials[key]
+                .as_str()
+                .map(|s| s.to_string())
+                .ok_or_else(|| format!("Correios credentials are missing '{}'", key))
+        };
+
+        let services = settings["services"]
+            .as_array()
+            .map(|list| {
+                list.iter()
+                    .filter_map(|s| s.as_str().map(|s| s.to_string()))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|list| !list.is_empty())
+            .unwrap_or_else(|| DEFAULT_SERVICES.iter().map(|s| s.to_string()).collect());
+
+        let default_url = if sandbox { SANDBOX_URL } else { PRODUCTION_URL };
+
+        Ok(Self {
+            client: http_client()?,
+            base_url: base_url
+                .unwrap_or_else(|| default_url.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            username: credential("username")?,
+            access_code: credential("access_code")?,
+            posting_card: credential("posting_card")?,
+            services,
+        })
+    }
+
+    async fn token(&self) -> Result<String, String> {
+        let response = self
+            .client
+            .post(format!("{}/token/v1/autentica/cartaopostagem", self.base_url))
+            .basic_auth(&self.username, Some(&self.access_code))
+            .json(&json!({ "numero": self.posting_card }))
+            .send()
+            .await
+            .map_err(|e| format!("Correios authentication failed: {}", e))?;
+
+        let body = read_json(response, "Correios authentication").await?;
+        body["token"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| "Correios authentication returned no token".to_string())
+    }
+
+    fn service_name(code: &str) -> String {
+        match code {
+            "03220" | "04014" => "SEDEX".to_string(),
+            "03298" | "04510" => "PAC".to_string(),
+            "04227" => "Mini Envios".to_string(),
+            other => other.to_string(),
+        }
+    }
+
+    /// Package dimensions in the units Correios expects, raised to its minimums
+    fn dimensions(package: &Package) -> (i64, i64, i64, i64) {
+        (
+            package.weight_g.max(MIN_WEIGHT_G),
+            mm_to_cm(package.depth_mm).max(MIN_LENGTH_CM),
+            mm_to_cm(package.width_mm).max(MIN_WIDTH_CM),
+            mm_to_cm(package.height_mm).max(MIN_HEIGHT_CM),
+        )
+    }
+
+    /// Prices come as Brazilian-formatted strings ("1.234,56")
+    fn parse_price(value: &Value) -> Option<f64> {
+        match value {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.replace('.', "").replace(',', ".").parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn address(address: &ShippingAddress) -> Value {
+        json!({
+            "nome": address.full_name(),
+            "cpfCnpj": address.document.as_deref().map(only_digits),
+            "email": address.email,
+            "celular": address.phone.as_deref().map(only_digits),
+            "endereco": {
+                "cep": address.postal_code.as_deref().map(only_digits),
+                "logradouro": address.address1,
+                "numero": address.number.clone().unwrap_or_else(|| "S/N".to_string()),
+                "complemento": address.address2,
+                "bairro": address.district,
+                "cidade": address.city,
+                "uf": address.province_code,
+            }
+        })
+    }
+
+    /// Map a SRO event code/type to a shipment status
+    fn map_status(code: &str, event_type: &str) -> &'static str {
+        match (code, event_type) {
+            ("BDE" | "BDI" | "BDR", "01") => "delivered",
+            ("BDE" | "BDI" | "BDR", _) => "exception",
+            ("OEC", _) => "out_for_delivery",
+            ("PO", _) => "shipped",
+            ("FC" | "LDI", _) => "exception",
+            _ => "in_transit",
+        }
+    }
+
+    /// SRO timestamps are local Brasília time without an offset
+    fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+        if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+            return Some(dt.with_timezone(&Utc));
+        }
+        let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S").ok()?;
+        let brasilia = FixedOffset::west_opt(3 * 3600)?;
+        naive
+            .and_local_timezone(brasilia)
+            .single()
+            .map(|dt| dt.with_timezone(&Utc))
+    }
+}
+
+#[async_trait]
+impl CarrierProvider for CorreiosProvider {
+    fn carrier(&self) -> &str {
+        "correios"
+    }
+
+    async fn quote(&self, request: &QuoteRequest) -> Result<Vec<ShippingQuote>, String> {
+        let token = self.token().await?;
+        let origin = only_digits(&request.origin_postal_code);
+        let destination = only_digits(&request.destination_postal_code);
+        let (weight, length, width, height) = Self::dimensions(&request.package);
+
+        let mut quotes = Vec::new();
+        for service in &self.services {
+            let mut query = vec![
+                ("cepOrigem", origin.clone()),
+                ("cepDestino", destination.clone()),
+                ("psObjeto", weight.to_string()),
+                ("tpObjeto", "2".to_string()),
+                ("comprimento", length.to_string()),
+                ("largura", width.to_string()),
+                ("altura", height.to_string()),
+            ];
+            if let Some(value) = request.declared_value {
+                query.push(("vlDeclarado", format!("{:.2}", value)));
+            }
+
+            let response = self
+                .client
+                .get(format!("{}/preco/v1/nacional/{}", self.base_url, service))
+                .bearer_auth(&token)
+                .query(&query)
+                .send()
+                .await
+                .map_err(|e| format!("Correios price request failed: {}", e))?;
+            let price_body = read_json(response, "Correios price").await?;
+
+            let price = match Self::parse_price(&price_body["pcFinal"]) {
+                Some(price) => price,
+                // Services not offered for this route are skipped
+                None => continue,
+            };
+
+            let response = self
+                .client
+                .get(format!("{}/prazo/v1/nacional/{}", self.base_url, service))
+                .bearer_auth(&token)
+                .query(&[("cepOrigem", &origin), ("cepDestino", &destination)])
+                .send()
+                .await
+                .map_err(|e| format!("Correios delivery time request failed: {}", e))?;
+            let delivery_days = read_json(response, "Correios delivery time")
+                .await
+                .ok()
+                .and_then(|body| body["prazoEntrega"].as_i64());
+
+            quotes.push(ShippingQuote {
+                carrier: self.carrier().to_string(),
+                service_code: service.clone(),
+                service_name: Self::service_name(service),
+                price,
+                delivery_days,
+            });
+        }
+
+        Ok(quotes)
+    }
+
+    async fn buy_label(&self, request: &LabelRequest) -> Result<PurchasedLabel, String> {
+        let token = self.token().await?;
+        let (weight, length, width, height) = Self::dimensions(&request.package);
+
+        let mut body = json!({
+            "remetente": Self::address(&request.sender),
+            "destinatario": Self::address(&request.recipient),
+            "codigoServico": request.service_code,
+            "pesoInformado": weight.to_string(),
+            "codigoFormatoObjetoInformado": "2",
+            "comprimentoInformado": length.to_string(),
+            "larguraInformada": width.to_string(),
+            "alturaInformada": height.to_string(),
+            "cienteObjetoNaoProibido": "1",
+        });
+        if let Some(key) = &request.invoice_key {
+            body["chaveNFe"] = json!(key);
+        }
+        if let Some(value) = request.declared_value {
+            body["valorDeclarado"] = json!(format!("{:.2}", value));
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/prepostagem/v1/prepostagens", self.base_url))
+            .bearer_auth(&token)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("Correios pre-posting request failed: {}", e))?;
+        let created = read_json(response, "Correios pre-posting").await?;
+
+        let reference = created["id"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| "Correios pre-posting returned no id".to_string())?;
+
+        Ok(PurchasedLabel {
+            tracking_number: created["codigoObjeto"].as_str().map(|s| s.to_string()),
+            // Labels are printed from the Correios portal for the pre-posting id
+            label_url: None,
+            cost_amount: None,
+            provider_reference: reference,
+        })
+    }
+
+    async fn track(
+        &self,
+        tracking_number: Option<&str>,
+        _provider_reference: Option<&str>,
+    ) -> Result<Vec<TrackingUpdate>, String> {
+        let tracking_number =
+            tracking_number.ok_or_else(|| "Correios tracking needs a tracking number".to_string())?;
+        let token = self.token().await?;
+
+        let response = self
+            .client
+            .get(format!("{}/srorastro/v1/objetos/{}", self.base_url, tracking_number))
+            .bearer_auth(&token)
+            .query(&[("resultado", "T")])
+            .send()
+            .await
+            .map_err(|e| format!("Correios tracking request failed: {}", e))?;
+        let body = read_json(response, "Correios tracking").await?;
+
+        let events = body["objetos"][0]["eventos"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+
+        let mut updates: Vec<TrackingUpdate> = events
+            .into_iter()
+            .filter_map(|event| {
+                let happened_at = Self::parse_timestamp(event["dtHrCriado"].as_str()?)?;
+                let code = event["codigo"].as_str().unwrap_or_default();
+                let event_type = event["tipo"].as_str().unwrap_or_default();
+                let city = event["unidade"]["endereco"]["cidade"].as_str();
+                let state = event["unidade"]["endereco"]["uf"].as_str();
+                let location = match (city, state) {
+                    (Some(city), Some(state)) => Some(format!("{} - {}", city, state)),
+                    (Some(city), None) => Some(city.to_string()),
+                    _ => None,
+                };
+
+                Some(TrackingUpdate {
+                    status: Self::map_status(code, event_type).to_string(),
+                    description: event["descricao"].as_str().map(|s| s.to_string()),
+                    location,
+                    happened_at,
+                    raw_data: Some(event.to_string()),
+                })
+            })
+            .collect();
+
+        // SRO lists the most recent event first
+        updates.sort_by_key(|u| u.happened_at);
+        Ok(updates)
+    }
+}
@@ -0,0 +1,302 @@
+//! Melhor Envio adapter (melhorenvio.com.br API v2)
+//!
+//! Credentials: `{ "token" }` (personal access token). Settings:
+//! `{ "user_agent": "Shop Name (contact@email)", "services": [1, 2] }`;
+//! Melhor Envio requires a User-Agent identifying the integrator.
+
+use crate::features::carrier::models::carrier_model::{
+    LabelRequest, Package, PurchasedLabel, QuoteRequest, ShippingAddress, ShippingQuote,
+    TrackingUpdate,
+};
+use crate::features::carrier::providers::carrier_provider::{
+    http_client, mm_to_cm, only_digits, read_json, CarrierProvider,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, USER_AGENT};
+use serde_json::{json, Value};
+
+const PRODUCTION_URL: &str = "https://www.melhorenvio.com.br";
+const SANDBOX_URL: &str = "https://sandbox.melhorenvio.com.br";
+const DEFAULT_USER_AGENT: &str = "Uru Desktop";
+
+pub struct MelhorEnvioProvider {
+    client: reqwest::Client,
+    base_url: String,
+    token: String,
+    user_agent: String,
+    services: Option<String>,
+}
+
+impl MelhorEnvioProvider {
+    pub fn new(
+        base_url: Option<String>,
+        sandbox: bool,
+        credentials: Value,
+        settings: Value,
+    ) -> Result<Self, String> {
+        let token = credentials["token"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| "Melhor Envio credentials are missing 'token'".to_string())?;
+
+        // The calculate endpoint takes the service filter as a comma-separated list
+        let services = settings["services"].as_array().map(|list| {
+            list.iter()
+                .map(|s| match s {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        });
+
+        let default_url = if sandbox { SANDBOX_URL } else { PRODUCTION_URL };
+
+        Ok(Self {
+            client: http_client()?,
+            base_url: base_url
+                .unwrap_or_else(|| default_url.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            token,
+            user_agent: settings["user_agent"]
+                .as_str()
+                .unwrap_or(DEFAULT_USER_AGENT)
+                .to_string(),
+            services: services.filter(|s| !s.is_empty()),
+        })
+    }
+
+    fn headers(&self) -> Result<HeaderMap, String> {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+        headers.insert(
+            USER_AGENT,
+            HeaderValue::from_str(&self.user_agent)
+                .map_err(|e| format!("Invalid Melhor Envio user agent: {}", e))?,
+        );
+        Ok(headers)
+    }
+
+    async fn post(&self, path: &str, body: &Value, context: &str) -> Result<Value, String> {
+        let response = self
+            .client
+            .post(format!("{}{}", self.base_url, path))
+            .headers(self.headers()?)
+            .bearer_auth(&self.token)
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| format!("{} request failed: {}", context, e))?;
+        read_json(response, context).await
+    }
+
+    /// Melhor Envio works in centimetres and kilograms
+    fn volume(package: &Package) -> Value {
+        json!({
+            "height": mm_to_cm(package.height_mm).max(1),
+            "width": mm_to_cm(package.width_mm).max(1),
+            "length": mm_to_cm(package.depth_mm).max(1),
+            "weight": (package.weight_g.max(1) as f64) / 1000.0,
+        })
+    }
+
+    fn address(address: &ShippingAddress) -> Value {
+        json!({
+            "name": address.full_name(),
+            "phone": address.phone.as_deref().map(only_digits),
+            "email": address.email,
+            "document": address.document.as_deref().map(only_digits),
+            "address": address.address1,
+            "complement": address.address2,
+            "number": address.number,
+            "district": address.district,
+            "city": address.city,
+            "state_abbr": address.province_code,
+            "country_id": address.country_code.clone().unwrap_or_else(|| "BR".to_string()),
+            "postal_code": address.postal_code.as_deref().map(only_digits),
+        })
+    }
+
+    fn parse_price(value: &Value) -> Option<f64> {
+        match value {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn parse_timestamp(value: &Value) -> Option<DateTime<Utc>> {
+        let value = value.as_str()?;
+        if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+            return Some(dt.with_timezone(&Utc));
+        }
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+            .ok()
+            .map(|naive| naive.and_utc())
+    }
+}
+
+#[async_trait]
+impl CarrierProvider for MelhorEnvioProvider {
+    fn carrier(&self) -> &str {
+        "melhor_envio"
+    }
+
+    async fn quote(&self, request: &QuoteRequest) -> Result<Vec<ShippingQuote>, String> {
+        let mut body = json!({
+            "from": { "postal_code": only_digits(&request.origin_postal_code) },
+            "to": { "postal_code": only_digits(&request.destination_postal_code) },
+            "package": Self::volume(&request.package),
+            "options": {
+                "insurance_value": request.declared_value.unwrap_or(0.0),
+                "receipt": false,
+                "own_hand": false,
+            },
+        });
+        if let Some(services) = &self.services {
+            body["services"] = json!(services);
+        }
+
+        let result = self
+            .post("/api/v2/me/shipment/calculate", &body, "Melhor Envio quote")
+            .await?;
+
+        let quotes = result
+            .as_array()
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            // Services unavailable for the route come back with an `error` field
+            .filter(|service| service["error"].is_null())
+            .filter_map(|service| {
+                let price = Self::parse_price(&service["custom_price"])
+                    .or_else(|| Self::parse_price(&service["price"]))?;
+                let company = service["company"]["name"].as_str().unwrap_or_default();
+                let name = service["name"].as_str().unwrap_or_default();
+                Some(ShippingQuote {
+                    carrier: self.carrier().to_string(),
+                    service_code: service["id"].to_string(),
+                    service_name: format!("{} {}", company, name).trim().to_string(),
+                    price,
+                    delivery_days: service["custom_delivery_time"]
+                        .as_i64()
+                        .or_else(|| service["delivery_time"].as_i64()),
+                })
+            })
+            .collect();
+
+        Ok(quotes)
+    }
+
+    async fn buy_label(&self, request: &LabelRequest) -> Result<PurchasedLabel, String> {
+        let service: i64 = request
+            .service_code
+            .parse()
+            .map_err(|_| format!("Invalid Melhor Envio service: {}", request.service_code))?;
+
+        let mut options = json!({
+            "insurance_value": request.declared_value.unwrap_or(0.0),
+            "receipt": false,
+            "own_hand": false,
+            "non_commercial": request.invoice_key.is_none(),
+        });
+        if let Some(key) = &request.invoice_key {
+            options["invoice"] = json!({ "key": key });
+        }
+
+        // 1. Add the shipment to the cart
+        let cart = self
+            .post(
+                "/api/v2/me/cart",
+                &json!({
+                    "service": service,
+                    "from": Self::address(&request.sender),
+                    "to": Self::address(&request.recipient),
+                    "volumes": [Self::volume(&request.package)],
+                    "options": options,
+                }),
+                "Melhor Envio cart",
+            )
+            .await?;
+
+        let order_id = cart["id"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| "Melhor Envio cart returned no order id".to_string())?;
+        let orders = json!({ "orders": [order_id] });
+
+        // 2. Pay for it with the account balance, 3. generate and 4. print the label
+        self.post("/api/v2/me/shipment/checkout", &orders, "Melhor Envio checkout")
+            .await?;
+        self.post("/api/v2/me/shipment/generate", &orders, "Melhor Envio label generation")
+            .await?;
+        let printed = self
+            .post(
+                "/api/v2/me/shipment/print",
+                &json!({ "mode": "public", "orders": [order_id] }),
+                "Melhor Envio label print",
+            )
+            .await?;
+
+        // The tracking code is usually assigned on generation
+        let tracking = self
+            .post("/api/v2/me/shipment/tracking", &orders, "Melhor Envio tracking")
+            .await
+            .ok()
+            .and_then(|body| body[order_id.as_str()]["tracking"].as_str().map(|s| s.to_string()));
+
+        Ok(PurchasedLabel {
+            tracking_number: tracking,
+            label_url: printed["url"].as_str().map(|s| s.to_string()),
+            cost_amount: Self::parse_price(&cart["price"]),
+            provider_reference: order_id,
+        })
+    }
+
+    async fn track(
+        &self,
+        _tracking_number: Option<&str>,
+        provider_reference: Option<&str>,
+    ) -> Result<Vec<TrackingUpdate>, String> {
+        let order_id = provider_reference
+            .ok_or_else(|| "Melhor Envio tracking needs the Melhor Envio order id".to_string())?;
+
+        let body = self
+            .post(
+                "/api/v2/me/shipment/tracking",
+                &json!({ "orders": [order_id] }),
+                "Melhor Envio tracking",
+            )
+            .await?;
+        let order = &body[order_id];
+        if order.is_null() {
+            return Ok(Vec::new());
+        }
+
+        // Melhor Envio exposes milestones rather than an event log
+        let milestones = [
+            ("posted_at", "shipped", "Objeto postado"),
+            ("delivered_at", "delivered", "Objeto entregue"),
+            ("canceled_at", "exception", "Envio cancelado"),
+            ("expired_at", "exception", "Etiqueta expirada"),
+        ];
+
+        let mut updates: Vec<TrackingUpdate> = milestones
+            .iter()
+            .filter_map(|(field, status, description)| {
+                Some(TrackingUpdate {
+                    status: status.to_string(),
+                    description: Some(description.to_string()),
+                    location: None,
+                    happened_at: Self::parse_timestamp(&order[*field])?,
+                    raw_data: Some(order.to_string()),
+                })
+            })
+            .collect();
+
+        updates.sort_by_key(|u| u.happened_at);
+        Ok(updates)
+    }
+}
@@ -0,0 +1,3 @@
+pub mod carrier_provider;
+pub mod correios_provider;
+pub mod melhor_envio_provider;
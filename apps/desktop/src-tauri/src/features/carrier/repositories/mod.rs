@@ -0,0 +1 @@
+pub mod shop_carrier_repository;
@@ -0,0 +1,84 @@
+//! Shop-scoped Carrier Account Repository for Multi-Database Architecture
+
+use crate::features::carrier::models::carrier_model::CarrierAccount;
+use sqlx::{Result, SqlitePool};
+use std::sync::Arc;
+
+pub struct ShopCarrierRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopCarrierRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self) -> Result<Vec<CarrierAccount>> {
+        let sql = "SELECT * FROM carrier_accounts WHERE _status IS NULL OR _status != 'deleted' ORDER BY carrier";
+        sqlx::query_as::<_, CarrierAccount>(sql)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn list_active(&self) -> Result<Vec<CarrierAccount>> {
+        let sql = r#"
+            SELECT * FROM carrier_accounts
+            WHERE is_active = true AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY carrier
+        "#;
+        sqlx::query_as::<_, CarrierAccount>(sql)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn get_by_carrier(&self, carrier: &str) -> Result<Option<CarrierAccount>> {
+        let sql = "SELECT * FROM carrier_accounts WHERE carrier = $1 AND (_status IS NULL OR _status != 'deleted')";
+        sqlx::query_as::<_, CarrierAccount>(sql)
+            .bind(carrier)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    /// Insert an account, or replace the one already configured for the same carrier
+    pub async fn upsert(&self, account: &CarrierAccount) -> Result<CarrierAccount> {
+        let sql = r#"
+            INSERT INTO carrier_accounts (
+                id, carrier, name, base_url, is_sandbox, origin_postal_code,
+                credentials, settings, is_active, _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ON CONFLICT (carrier) DO UPDATE SET
+                name = excluded.name,
+                base_url = excluded.base_url,
+                is_sandbox = excluded.is_sandbox,
+                origin_postal_code = excluded.origin_postal_code,
+                credentials = excluded.credentials,
+                settings = excluded.settings,
+                is_active = excluded.is_active,
+                _status = 'modified',
+                updated_at = excluded.updated_at
+            RETURNING *
+        "#;
+
+        sqlx::query_as::<_, CarrierAccount>(sql)
+            .bind(&account.id)
+            .bind(&account.carrier)
+            .bind(&account.name)
+            .bind(&account.base_url)
+            .bind(account.is_sandbox)
+            .bind(&account.origin_postal_code)
+            .bind(&account.credentials)
+            .bind(&account.settings)
+            .bind(account.is_active)
+            .bind(&account.sync_status)
+            .bind(account.created_at)
+            .bind(account.updated_at)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        let sql = "UPDATE carrier_accounts SET _status = 'deleted', updated_at = datetime('now') WHERE id = $1";
+        sqlx::query(sql).bind(id).execute(&*self.pool).await?;
+        Ok(())
+    }
+}
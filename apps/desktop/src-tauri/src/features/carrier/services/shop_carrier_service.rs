@@ -0,0 +1,475 @@
+//! Shop-scoped Carrier Service for Multi-Database Architecture
+//!
+//! Quotes shipping rates, buys labels and polls tracking through the carrier
+//! accounts configured for the shop. Tracking polls record new events in
+//! shipment_events and move the shipment forward through
+//! pending → label_created → shipped → in_transit → out_for_delivery → delivered.
+
+use crate::features::carrier::dtos::carrier_dto::{
+    BuyShippingLabelDTO, QuoteShippingDTO, ShippingItemDTO, UpsertCarrierAccountDTO,
+};
+use crate::features::carrier::models::carrier_model::{
+    CarrierAccount, LabelRequest, Package, QuoteRequest, ShippingAddress, ShippingQuote,
+    TrackingPollResult,
+};
+use crate::features::carrier::providers::carrier_provider::{parse_json, provider_for};
+use crate::features::carrier::repositories::shop_carrier_repository::ShopCarrierRepository;
+use crate::features::packing::dtos::packing_dto::PackItemDTO;
+use crate::features::packing::services::shop_packing_service::ShopPackingService;
+use crate::features::shipment::models::shipment_model::{Shipment, ShipmentEvent};
+use crate::features::shipment::repositories::shop_shipment_repository::ShopShipmentRepository;
+use crate::features::shipment::services::shop_shipment_service::ShopShipmentService;
+use chrono::Utc;
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const CARRIERS: [&str; 2] = ["correios", "melhor_envio"];
+
+/// Position of a status in the shipment lifecycle; statuses outside it never advance
+fn status_rank(status: &str) -> Option<u8> {
+    match status {
+        "pending" => Some(0),
+        "label_created" => Some(1),
+        "shipped" => Some(2),
+        "in_transit" => Some(3),
+        "out_for_delivery" => Some(4),
+        "delivered" => Some(5),
+        _ => None,
+    }
+}
+
+pub struct ShopCarrierService {
+    pool: Arc<SqlitePool>,
+    shop_id: String,
+    repo: ShopCarrierRepository,
+    shipment_repo: ShopShipmentRepository,
+}
+
+impl ShopCarrierService {
+    pub fn new(pool: Arc<SqlitePool>, shop_id: String) -> Self {
+        let repo = ShopCarrierRepository::new(pool.clone());
+        let shipment_repo = ShopShipmentRepository::new(pool.clone());
+        Self {
+            pool,
+            shop_id,
+            repo,
+            shipment_repo,
+        }
+    }
+
+    pub fn pool(&self) -> Arc<SqlitePool> {
+        self.pool.clone()
+    }
+
+    // ============================================================
+    // Carrier accounts
+    // ============================================================
+
+    pub async fn list_accounts(&self) -> Result<Vec<CarrierAccount>, String> {
+        self.repo
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list carrier accounts: {}", e))
+    }
+
+    pub async fn upsert_account(
+        &self,
+        payload: UpsertCarrierAccountDTO,
+    ) -> Result<CarrierAccount, String> {
+        if !CARRIERS.contains(&payload.carrier.as_str()) {
+            return Err(format!("Unsupported carrier: {}", payload.carrier));
+        }
+        let account = payload.into_model();
+
+        // Fails early on missing credentials
+        provider_for(&account)?;
+
+        self.repo
+            .upsert(&account)
+            .await
+            .map_err(|e| format!("Failed to save carrier account: {}", e))
+    }
+
+    pub async fn delete_account(&self, id: &str) -> Result<(), String> {
+        self.repo
+            .delete(id)
+            .await
+            .map_err(|e| format!("Failed to delete carrier account: {}", e))
+    }
+
+    async fn active_account(&self, carrier: &str) -> Result<CarrierAccount, String> {
+        self.repo
+            .get_by_carrier(carrier)
+            .await
+            .map_err(|e| format!("Failed to fetch carrier account: {}", e))?
+            .filter(|account| account.is_active.unwrap_or(true))
+            .ok_or_else(|| format!("No active carrier account for {}", carrier))
+    }
+
+    // ============================================================
+    // Quotes and labels
+    // ============================================================
+
+    /// Pack the items into the shop's boxes and return one carrier package per box
+    pub async fn build_packages(&self, items: &[ShippingItemDTO]) -> Result<Vec<Package>, String> {
+        let pack_items: Vec<PackItemDTO> = items
+            .iter()
+            .map(|item| PackItemDTO {
+                order_item_id: None,
+                product_id: item.product_id.clone(),
+                quantity: item.quantity,
+            })
+            .collect();
+
+        let plan = ShopPackingService::new(self.pool.clone(), self.shop_id.clone())
+            .plan_packing(&pack_items)
+            .await?;
+
+        if plan.total_weight_g <= 0 {
+            return Err("None of the items has a shipping weight".to_string());
+        }
+        Ok(plan
+            .packages
+            .into_iter()
+            .map(|p| Package {
+                weight_g: p.weight_g,
+                height_mm: p.height_mm,
+                width_mm: p.width_mm,
+                depth_mm: p.depth_mm,
+            })
+            .collect())
+    }
+
+    /// Quote every package with one carrier and merge the results per service:
+    /// prices add up, the slowest package sets the delivery time, and services
+    /// that cannot carry every package are dropped
+    async fn quote_packages(
+        &self,
+        account: &CarrierAccount,
+        destination_postal_code: &str,
+        packages: &[Package],
+        declared_value: Option<f64>,
+    ) -> Result<Vec<ShippingQuote>, String> {
+        let provider = provider_for(account)?;
+        // Declared value is split evenly so the insured total stays the same
+        let declared_value = declared_value.map(|v| v / packages.len() as f64);
+
+        let mut merged: Vec<ShippingQuote> = Vec::new();
+        for (index, package) in packages.iter().enumerate() {
+            let request = QuoteRequest {
+                origin_postal_code: account.origin_postal_code.clone(),
+                destination_postal_code: destination_postal_code.to_string(),
+                package: package.clone(),
+                declared_value,
+            };
+            let quotes = provider.quote(&request).await?;
+
+            if index == 0 {
+                merged = quotes;
+                continue;
+            }
+            merged.retain_mut(|current| {
+                match quotes
+                    .iter()
+                    .find(|q| q.service_code == current.service_code)
+                {
+                    Some(quote) => {
+                        current.price += quote.price;
+                        current.delivery_days = current.delivery_days.max(quote.delivery_days);
+                        true
+                    }
+                    None => false,
+                }
+            });
+        }
+        Ok(merged)
+    }
+
+    /// Quote every active carrier (or only `payload.carrier`), cheapest first.
+    /// A carrier failing does not hide the quotes of the others.
+    pub async fn quote_rates(
+        &self,
+        payload: QuoteShippingDTO,
+    ) -> Result<Vec<ShippingQuote>, String> {
+        let packages = self.build_packages(&payload.items).await?;
+
+        let accounts = match &payload.carrier {
+            Some(carrier) => vec![self.active_account(carrier).await?],
+            None => self
+                .repo
+                .list_active()
+                .await
+                .map_err(|e| format!("Failed to list carrier accounts: {}", e))?,
+        };
+        if accounts.is_empty() {
+            return Err("No carrier account is configured".to_string());
+        }
+
+        let mut quotes = Vec::new();
+        let mut errors = Vec::new();
+        for account in &accounts {
+            let result = self
+                .quote_packages(
+                    account,
+                    &payload.destination_postal_code,
+                    &packages,
+                    payload.declared_value,
+                )
+                .await;
+            match result {
+                Ok(mut carrier_quotes) => quotes.append(&mut carrier_quotes),
+                Err(e) => {
+                    log::warn!("Shipping quote failed for {}: {}", account.carrier, e);
+                    errors.push(format!("{}: {}", account.carrier, e));
+                }
+            }
+        }
+
+        if quotes.is_empty() && !errors.is_empty() {
+            return Err(format!("Failed to quote shipping: {}", errors.join("; ")));
+        }
+
+        quotes.sort_by(|a, b| a.price.total_cmp(&b.price));
+        Ok(quotes)
+    }
+
+    /// Buy a label for a shipment and store the carrier, service, tracking
+    /// number, label URL and cost on it
+    pub async fn buy_label(&self, payload: BuyShippingLabelDTO) -> Result<Shipment, String> {
+        let shipment = self
+            .shipment_repo
+            .get_by_id(&payload.shipment_id)
+            .await
+            .map_err(|e| format!("Failed to get shipment: {}", e))?
+            .ok_or_else(|| format!("Shipment not found: {}", payload.shipment_id))?;
+
+        if shipment.shipping_label_url.is_some() || shipment.tracking_number.is_some() {
+            return Err("Shipment already has a label".to_string());
+        }
+
+        let account = self.active_account(&payload.carrier).await?;
+        let provider = provider_for(&account)?;
+
+        let package = match (shipment.weight_g, &payload.items) {
+            (Some(weight_g), _) if weight_g > 0 => Package {
+                weight_g: weight_g as i64,
+                height_mm: shipment.height_mm.unwrap_or(0) as i64,
+                width_mm: shipment.width_mm.unwrap_or(0) as i64,
+                depth_mm: shipment.depth_mm.unwrap_or(0) as i64,
+            },
+            (_, Some(items)) => {
+                let mut packages = self.build_packages(items).await?;
+                if packages.len() > 1 {
+                    return Err(format!(
+                        "Items need {} packages; create one shipment per package first",
+                        packages.len()
+                    ));
+                }
+                packages.remove(0)
+            }
+            _ => {
+                return Err(
+                    "Shipment has no weight; provide the items to compute the package".to_string(),
+                )
+            }
+        };
+
+        let sql = "SELECT shipping_address FROM orders WHERE id = $1";
+        let (shipping_address,): (Option<String>,) = sqlx::query_as(sql)
+            .bind(&shipment.order_id)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| format!("Failed to fetch order: {}", e))?
+            .ok_or_else(|| format!("Order not found: {}", shipment.order_id))?;
+
+        let recipient: ShippingAddress = shipping_address
+            .as_deref()
+            .and_then(|a| serde_json::from_str(a).ok())
+            .ok_or_else(|| "Order has no shipping address".to_string())?;
+
+        let settings = parse_json(account.settings.as_deref());
+        let mut sender: ShippingAddress =
+            serde_json::from_value(settings["sender"].clone()).unwrap_or_default();
+        if sender.postal_code.is_none() {
+            sender.postal_code = Some(account.origin_postal_code.clone());
+        }
+
+        let label = provider
+            .buy_label(&LabelRequest {
+                service_code: payload.service_code.clone(),
+                sender,
+                recipient,
+                package: package.clone(),
+                declared_value: payload.declared_value,
+                invoice_key: shipment.invoice_key.clone(),
+            })
+            .await?;
+
+        let mut metadata = parse_json(shipment.metadata.as_deref());
+        if !metadata.is_object() {
+            metadata = json!({});
+        }
+        metadata["carrier_reference"] = json!(label.provider_reference);
+
+        let updated = Shipment {
+            status: Some("label_created".to_string()),
+            carrier_company: Some(account.carrier.clone()),
+            carrier_service: Some(payload.service_code),
+            tracking_number: label.tracking_number,
+            shipping_label_url: label.label_url,
+            cost_amount: label.cost_amount.or(shipment.cost_amount),
+            weight_g: Some(package.weight_g as i32),
+            height_mm: Some(package.height_mm as i32),
+            width_mm: Some(package.width_mm as i32),
+            depth_mm: Some(package.depth_mm as i32),
+            metadata: Some(metadata.to_string()),
+            sync_status: Some("modified".to_string()),
+            updated_at: Some(Utc::now()),
+            ..shipment
+        };
+
+        self.shipment_repo
+            .update(&updated)
+            .await
+            .map_err(|e| format!("Failed to update shipment: {}", e))
+    }
+
+    // ============================================================
+    // Tracking
+    // ============================================================
+
+    /// Fetch tracking for one shipment, record unseen events and advance its status
+    pub async fn refresh_tracking(&self, shipment_id: &str) -> Result<TrackingPollResult, String> {
+        let shipment = self
+            .shipment_repo
+            .get_by_id(shipment_id)
+            .await
+            .map_err(|e| format!("Failed to get shipment: {}", e))?
+            .ok_or_else(|| format!("Shipment not found: {}", shipment_id))?;
+
+        let carrier = shipment
+            .carrier_company
+            .clone()
+            .ok_or_else(|| "Shipment has no carrier".to_string())?;
+        let account = self.active_account(&carrier).await?;
+        let provider = provider_for(&account)?;
+
+        let metadata = parse_json(shipment.metadata.as_deref());
+        let updates = provider
+            .track(
+                shipment.tracking_number.as_deref(),
+                metadata["carrier_reference"].as_str(),
+            )
+            .await?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let mut new_events = 0;
+        for update in &updates {
+            let exists = ShopShipmentRepository::event_exists_in_tx(
+                &mut tx,
+                &shipment.id,
+                &update.status,
+                update.happened_at,
+            )
+            .await
+            .map_err(|e| format!("Failed to check shipment events: {}", e))?;
+            if exists {
+                continue;
+            }
+
+            let now = Utc::now();
+            let event = ShipmentEvent {
+                id: Uuid::new_v4().to_string(),
+                shipment_id: Some(shipment.id.clone()),
+                status: Some(update.status.clone()),
+                description: update.description.clone(),
+                location: update.location.clone(),
+                happened_at: Some(update.happened_at),
+                raw_data: update.raw_data.clone(),
+                sync_status: Some("created".to_string()),
+                created_at: Some(now),
+                updated_at: Some(now),
+            };
+            ShopShipmentRepository::create_event_in_tx(&mut tx, &event)
+                .await
+                .map_err(|e| format!("Failed to create shipment event: {}", e))?;
+            new_events += 1;
+        }
+
+        // Advance to the furthest lifecycle status reported, never backwards
+        let current = shipment
+            .status
+            .clone()
+            .unwrap_or_else(|| "pending".to_string());
+        let mut status = shipment.status.clone();
+        if let Some(current_rank) = status_rank(&current) {
+            let furthest = updates
+                .iter()
+                .filter_map(|u| status_rank(&u.status).map(|rank| (rank, u)))
+                .filter(|(rank, _)| *rank > current_rank)
+                .max_by_key(|(rank, u)| (*rank, u.happened_at));
+
+            if let Some((_, update)) = furthest {
+                let advanced = ShopShipmentRepository::advance_status_in_tx(
+                    &mut tx,
+                    &shipment.id,
+                    &update.status,
+                    update.happened_at,
+                )
+                .await
+                .map_err(|e| format!("Failed to update shipment status: {}", e))?;
+
+                if shipment.shipped_at.is_none() && advanced.shipped_at.is_some() {
+                    ShopShipmentService::fulfil_stock_in_tx(&mut tx, &advanced).await?;
+                }
+                status = advanced.status;
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(TrackingPollResult {
+            shipment_id: shipment.id,
+            new_events,
+            status,
+            error: None,
+        })
+    }
+
+    /// Poll tracking for every undelivered carrier shipment.
+    /// Failures are reported per shipment and do not stop the poll.
+    pub async fn poll_tracking(&self) -> Result<Vec<TrackingPollResult>, String> {
+        let shipments = self
+            .shipment_repo
+            .list_trackable()
+            .await
+            .map_err(|e| format!("Failed to list shipments: {}", e))?;
+
+        let mut results = Vec::with_capacity(shipments.len());
+        for shipment in shipments {
+            match self.refresh_tracking(&shipment.id).await {
+                Ok(result) => results.push(result),
+                Err(e) => {
+                    log::warn!("Tracking poll failed for shipment {}: {}", shipment.id, e);
+                    results.push(TrackingPollResult {
+                        shipment_id: shipment.id,
+                        new_events: 0,
+                        status: shipment.status,
+                        error: Some(e),
+                    });
+                }
+            }
+        }
+
+        Ok(results)
+    }
+}
@@ -1,6 +1,10 @@
 use crate::db::RepositoryFactory;
-use crate::features::category::dtos::category_dto::{CreateCategoryDTO, UpdateCategoryDTO};
-use crate::features::category::models::category_model::Category;
+use crate::features::category::dtos::category_dto::{
+    CreateCategoryDTO, MoveCategoryDTO, UpdateCategoryDTO,
+};
+use crate::features::category::models::category_model::{
+    Category, CategoryDeleteResult, CategoryNode, CategoryPath, CategoryProductCount,
+};
 use crate::features::category::services::shop_category_service::ShopCategoryService;
 use std::sync::Arc;
 use tauri::State;
@@ -43,14 +47,18 @@ pub async fn delete_category(
     repo_factory: State<'_, Arc<RepositoryFactory>>,
     shop_id: String,
     id: String,
-) -> Result<(), String> {
+    mode: Option<String>,        // 'reassign' (default) or 'cascade'
+    reassign_to: Option<String>, // Reassign target; the category's parent when absent
+) -> Result<CategoryDeleteResult, String> {
     let pool = repo_factory
         .shop_pool(&shop_id)
         .await
         .map_err(|e| format!("Failed to get shop pool: {}", e))?;
 
     let service = ShopCategoryService::new(pool, shop_id);
-    service.delete_category(&id).await
+    service
+        .delete_category(&id, mode.as_deref(), reassign_to.as_deref())
+        .await
 }
 
 #[tauri::command]
@@ -95,3 +103,61 @@ pub async fn list_categories(
     let service = ShopCategoryService::new(pool, shop_id);
     service.list_categories().await
 }
+
+#[tauri::command]
+pub async fn get_category_tree(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+) -> Result<Vec<CategoryNode>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopCategoryService::new(pool, shop_id);
+    service.get_category_tree().await
+}
+
+#[tauri::command]
+pub async fn move_category(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: MoveCategoryDTO,
+) -> Result<Category, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopCategoryService::new(pool, shop_id);
+    service.move_category(payload).await
+}
+
+#[tauri::command]
+pub async fn get_category_path(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+) -> Result<CategoryPath, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopCategoryService::new(pool, shop_id);
+    service.get_category_path(&id).await
+}
+
+#[tauri::command]
+pub async fn get_category_product_counts(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+) -> Result<Vec<CategoryProductCount>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopCategoryService::new(pool, shop_id);
+    service.get_category_product_counts().await
+}
@@ -122,3 +122,10 @@ impl UpdateCategoryDTO {
         category
     }
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MoveCategoryDTO {
+    pub id: String,
+    pub parent_id: Option<String>, // None moves the category to the root
+    pub sort_order: Option<i64>,
+}
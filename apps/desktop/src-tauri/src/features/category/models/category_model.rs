@@ -28,3 +28,47 @@ pub struct Category {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
+
+/// A category with its subtree, as returned by the tree API
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CategoryNode {
+    #[serde(flatten)]
+    pub category: Category,
+    pub depth: usize,             // 0 for root categories
+    pub product_count: i64,       // Products directly in this category
+    pub total_product_count: i64, // Distinct products in the whole subtree
+    pub children: Vec<CategoryNode>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CategoryBreadcrumb {
+    pub id: String,
+    pub name: String,
+    pub slug: String,
+}
+
+/// Breadcrumbs of a category, from its root down to the category itself
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CategoryPath {
+    pub category_id: String,
+    pub breadcrumbs: Vec<CategoryBreadcrumb>,
+    pub path: String, // "Clothing > T-shirts"
+    pub depth: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct CategoryProductCount {
+    pub category_id: String,
+    pub product_count: i64,
+    pub total_product_count: i64,
+}
+
+/// What a category deletion changed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CategoryDeleteResult {
+    pub mode: String, // 'reassign' or 'cascade'
+    pub deleted_category_ids: Vec<String>,
+    pub reassigned_to: Option<String>, // Reassign only; None means root / uncategorized
+    pub children_moved: u64,
+    pub products_moved: u64,
+}
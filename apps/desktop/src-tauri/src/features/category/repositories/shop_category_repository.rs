@@ -4,12 +4,26 @@
 //! has its own isolated database file. The categories table in shop databases
 //! does NOT have a shop_id column.
 
-use crate::features::category::models::category_model::Category;
+use crate::features::category::models::category_model::{Category, CategoryProductCount};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, Result, SqlitePool};
+use sqlx::{Executor, FromRow, QueryBuilder, Result, Sqlite, SqlitePool, Transaction};
 use std::sync::Arc;
 
+/// Recursive CTE pairing every live category with itself and each of its
+/// descendants: `category_closure(ancestor_id, category_id)`. UNION (not
+/// UNION ALL) keeps it finite should the data ever contain a cycle.
+pub const CATEGORY_CLOSURE_CTE: &str = r#"
+    category_closure(ancestor_id, category_id) AS (
+        SELECT id, id FROM categories WHERE _status IS NULL OR _status != 'deleted'
+        UNION
+        SELECT cc.ancestor_id, c.id
+        FROM categories c
+        INNER JOIN category_closure cc ON c.parent_id = cc.category_id
+        WHERE c._status IS NULL OR c._status != 'deleted'
+    )
+"#;
+
 /// Internal struct for deserializing from shop database (no shop_id column)
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
 struct ShopCategory {
@@ -76,6 +90,21 @@ impl ShopCategoryRepository {
     }
 
     pub async fn create(&self, category: &Category) -> Result<Category> {
+        self.create_with(&*self.pool, category).await
+    }
+
+    pub async fn create_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        category: &Category,
+    ) -> Result<Category> {
+        self.create_with(&mut **tx, category).await
+    }
+
+    async fn create_with<'e, E>(&self, executor: E, category: &Category) -> Result<Category>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
         let sql = r#"
             INSERT INTO categories (
                 id, parent_id, name, slug, description, image_url, banner_url,
@@ -105,7 +134,7 @@ impl ShopCategoryRepository {
             .bind(&category.sync_status)
             .bind(category.created_at)
             .bind(category.updated_at)
-            .fetch_one(&*self.pool)
+            .fetch_one(executor)
             .await?;
 
         Ok(shop_category.into_category(self.shop_id.clone()))
@@ -218,4 +247,124 @@ impl ShopCategoryRepository {
             .map(|c| c.into_category(self.shop_id.clone()))
             .collect())
     }
+
+    /// Products of each category, counted directly and across its subtree.
+    /// A product belongs to its primary category (products.category_id) and
+    /// to those linked in product_categories; variants are not counted.
+    pub async fn product_counts(&self) -> Result<Vec<CategoryProductCount>> {
+        let sql = format!(
+            r#"
+            WITH RECURSIVE {},
+            memberships AS (
+                SELECT id AS product_id, category_id
+                FROM products
+                WHERE category_id IS NOT NULL AND parent_id IS NULL
+                  AND (_status IS NULL OR _status != 'deleted')
+                UNION
+                SELECT pc.product_id, pc.category_id
+                FROM product_categories pc
+                INNER JOIN products p ON p.id = pc.product_id
+                WHERE p.parent_id IS NULL AND (p._status IS NULL OR p._status != 'deleted')
+            )
+            SELECT
+                cc.ancestor_id AS category_id,
+                COUNT(DISTINCT CASE WHEN cc.category_id = cc.ancestor_id THEN m.product_id END)
+                    AS product_count,
+                COUNT(DISTINCT m.product_id) AS total_product_count
+            FROM category_closure cc
+            LEFT JOIN memberships m ON m.category_id = cc.category_id
+            GROUP BY cc.ancestor_id
+            "#,
+            CATEGORY_CLOSURE_CTE
+        );
+        sqlx::query_as::<_, CategoryProductCount>(&sql)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// Move the children of `parent_id` under `new_parent_id` (None: roots)
+    pub async fn reparent_children_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        parent_id: &str,
+        new_parent_id: Option<&str>,
+    ) -> Result<u64> {
+        let sql = r#"
+            UPDATE categories SET parent_id = $2, _status = 'updated', updated_at = datetime('now')
+            WHERE parent_id = $1 AND (_status IS NULL OR _status != 'deleted')
+        "#;
+        let result = sqlx::query(sql)
+            .bind(parent_id)
+            .bind(new_parent_id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Point the products of `category_ids` at `target_id` (None: uncategorized),
+    /// both as primary category and in product_categories. Returns the number
+    /// of products whose primary category changed.
+    pub async fn reassign_products_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        category_ids: &[String],
+        target_id: Option<&str>,
+    ) -> Result<u64> {
+        if category_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let mut qb = QueryBuilder::<Sqlite>::new("UPDATE products SET category_id = ");
+        qb.push_bind(target_id);
+        qb.push(", _status = 'modified', updated_at = datetime('now') WHERE category_id IN (");
+        let mut separated = qb.separated(", ");
+        for id in category_ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(")");
+        let moved = qb.build().execute(&mut **tx).await?.rows_affected();
+
+        if let Some(target_id) = target_id {
+            let mut qb = QueryBuilder::<Sqlite>::new(
+                "INSERT OR IGNORE INTO product_categories (product_id, category_id, position) \
+                 SELECT product_id, ",
+            );
+            qb.push_bind(target_id);
+            qb.push(", MIN(position) FROM product_categories WHERE category_id IN (");
+            let mut separated = qb.separated(", ");
+            for id in category_ids {
+                separated.push_bind(id);
+            }
+            separated.push_unseparated(") GROUP BY product_id");
+            qb.build().execute(&mut **tx).await?;
+        }
+
+        let mut qb =
+            QueryBuilder::<Sqlite>::new("DELETE FROM product_categories WHERE category_id IN (");
+        let mut separated = qb.separated(", ");
+        for id in category_ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(")");
+        qb.build().execute(&mut **tx).await?;
+
+        Ok(moved)
+    }
+
+    pub async fn delete_many_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        ids: &[String],
+    ) -> Result<u64> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let mut qb = QueryBuilder::<Sqlite>::new(
+            "UPDATE categories SET _status = 'deleted', updated_at = datetime('now') WHERE id IN (",
+        );
+        let mut separated = qb.separated(", ");
+        for id in ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(")");
+        Ok(qb.build().execute(&mut **tx).await?.rows_affected())
+    }
 }
@@ -3,22 +3,58 @@
 //! This service operates on a shop-specific database where each shop
 //! has its own isolated database file.
 
-use crate::features::category::dtos::category_dto::{CreateCategoryDTO, UpdateCategoryDTO};
-use crate::features::category::models::category_model::Category;
+use crate::features::category::dtos::category_dto::{
+    CreateCategoryDTO, MoveCategoryDTO, UpdateCategoryDTO,
+};
+use crate::features::category::models::category_model::{
+    Category, CategoryBreadcrumb, CategoryDeleteResult, CategoryNode, CategoryPath,
+    CategoryProductCount,
+};
 use crate::features::category::repositories::shop_category_repository::ShopCategoryRepository;
+use chrono::Utc;
 use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+/// Parent of each category, by id
+fn parent_map(categories: &[Category]) -> HashMap<&str, Option<&str>> {
+    categories
+        .iter()
+        .map(|c| (c.id.as_str(), c.parent_id.as_deref()))
+        .collect()
+}
+
+/// Whether `candidate` is `root` or one of its descendants
+fn in_subtree(parents: &HashMap<&str, Option<&str>>, candidate: &str, root: &str) -> bool {
+    let mut seen = HashSet::new();
+    let mut current = Some(candidate);
+    while let Some(id) = current {
+        if id == root {
+            return true;
+        }
+        if !seen.insert(id) {
+            return false; // Existing cycle that does not involve root
+        }
+        current = parents.get(id).copied().flatten();
+    }
+    false
+}
+
 /// Category service that operates on a shop-specific database.
 pub struct ShopCategoryService {
+    pool: Arc<SqlitePool>,
     shop_id: String,
     repo: ShopCategoryRepository,
 }
 
 impl ShopCategoryService {
     pub fn new(pool: Arc<SqlitePool>, shop_id: String) -> Self {
-        let repo = ShopCategoryRepository::new(pool, shop_id.clone());
-        Self { shop_id, repo }
+        let repo = ShopCategoryRepository::new(pool.clone(), shop_id.clone());
+        Self {
+            pool,
+            shop_id,
+            repo,
+        }
     }
 
     pub fn shop_id(&self) -> &str {
@@ -41,6 +77,12 @@ impl ShopCategoryService {
             .map_err(|e| format!("Failed to fetch category: {}", e))?
             .ok_or_else(|| format!("Category not found: {}", payload.id))?;
 
+        if let Some(parent_id) = payload.parent_id.as_deref() {
+            if existing.parent_id.as_deref() != Some(parent_id) {
+                self.validate_parent(&existing.id, parent_id).await?;
+            }
+        }
+
         let updated = payload.apply_to_model(existing);
         self.repo
             .update(&updated)
@@ -48,11 +90,107 @@ impl ShopCategoryService {
             .map_err(|e| format!("Failed to update category: {}", e))
     }
 
-    pub async fn delete_category(&self, id: &str) -> Result<(), String> {
-        self.repo
-            .delete(id)
+    /// Delete a category. With mode 'reassign' (the default) only the
+    /// category goes: its children and products move to `reassign_to`, or to
+    /// its parent when absent. With mode 'cascade' the whole subtree goes and
+    /// its products are left uncategorized.
+    pub async fn delete_category(
+        &self,
+        id: &str,
+        mode: Option<&str>,
+        reassign_to: Option<&str>,
+    ) -> Result<CategoryDeleteResult, String> {
+        let categories = self.list_categories().await?;
+        let category = categories
+            .iter()
+            .find(|c| c.id == id)
+            .ok_or_else(|| format!("Category not found: {}", id))?;
+        let parents = parent_map(&categories);
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let result = match mode.unwrap_or("reassign") {
+            "reassign" => {
+                let target = match reassign_to {
+                    Some(target) => {
+                        if !parents.contains_key(target) {
+                            return Err(format!("Category not found: {}", target));
+                        }
+                        if in_subtree(&parents, target, id) {
+                            return Err(
+                                "Cannot reassign to the category itself or one of its descendants"
+                                    .to_string(),
+                            );
+                        }
+                        Some(target)
+                    }
+                    None => category.parent_id.as_deref(),
+                };
+                let ids = vec![id.to_string()];
+
+                let children_moved =
+                    ShopCategoryRepository::reparent_children_in_tx(&mut tx, id, target)
+                        .await
+                        .map_err(|e| format!("Failed to move child categories: {}", e))?;
+                let products_moved =
+                    ShopCategoryRepository::reassign_products_in_tx(&mut tx, &ids, target)
+                        .await
+                        .map_err(|e| format!("Failed to reassign products: {}", e))?;
+                ShopCategoryRepository::delete_many_in_tx(&mut tx, &ids)
+                    .await
+                    .map_err(|e| format!("Failed to delete category: {}", e))?;
+
+                CategoryDeleteResult {
+                    mode: "reassign".to_string(),
+                    deleted_category_ids: ids,
+                    reassigned_to: target.map(str::to_string),
+                    children_moved,
+                    products_moved,
+                }
+            }
+            "cascade" => {
+                if reassign_to.is_some() {
+                    return Err("reassign_to is only allowed with mode 'reassign'".to_string());
+                }
+                let ids: Vec<String> = categories
+                    .iter()
+                    .filter(|c| in_subtree(&parents, &c.id, id))
+                    .map(|c| c.id.clone())
+                    .collect();
+
+                let products_moved =
+                    ShopCategoryRepository::reassign_products_in_tx(&mut tx, &ids, None)
+                        .await
+                        .map_err(|e| format!("Failed to detach products: {}", e))?;
+                ShopCategoryRepository::delete_many_in_tx(&mut tx, &ids)
+                    .await
+                    .map_err(|e| format!("Failed to delete categories: {}", e))?;
+
+                CategoryDeleteResult {
+                    mode: "cascade".to_string(),
+                    deleted_category_ids: ids,
+                    reassigned_to: None,
+                    children_moved: 0,
+                    products_moved,
+                }
+            }
+            other => {
+                return Err(format!(
+                    "Invalid delete mode: {} (expected reassign or cascade)",
+                    other
+                ))
+            }
+        };
+
+        tx.commit()
             .await
-            .map_err(|e| format!("Failed to delete category: {}", e))
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(result)
     }
 
     pub async fn get_category(&self, id: &str) -> Result<Option<Category>, String> {
@@ -96,4 +234,151 @@ impl ShopCategoryService {
             .await
             .map_err(|e| format!("Failed to list visible categories: {}", e))
     }
+
+    // ============================================================
+    // Tree operations
+    // ============================================================
+
+    /// Fail when `parent_id` does not exist or lies in the subtree of `id`
+    async fn validate_parent(&self, id: &str, parent_id: &str) -> Result<(), String> {
+        let categories = self.list_categories().await?;
+        let parents = parent_map(&categories);
+        if !parents.contains_key(parent_id) {
+            return Err(format!("Parent category not found: {}", parent_id));
+        }
+        if in_subtree(&parents, parent_id, id) {
+            return Err(
+                "A category cannot be moved under itself or one of its descendants".to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Move a category, with its subtree, under another parent or to the root
+    pub async fn move_category(&self, payload: MoveCategoryDTO) -> Result<Category, String> {
+        let mut category = self
+            .get_category(&payload.id)
+            .await?
+            .ok_or_else(|| format!("Category not found: {}", payload.id))?;
+
+        if let Some(parent_id) = payload.parent_id.as_deref() {
+            self.validate_parent(&category.id, parent_id).await?;
+        }
+
+        category.parent_id = payload.parent_id;
+        if let Some(sort_order) = payload.sort_order {
+            category.sort_order = sort_order;
+        }
+        category.sync_status = Some("updated".to_string());
+        category.updated_at = Utc::now();
+
+        self.repo
+            .update(&category)
+            .await
+            .map_err(|e| format!("Failed to move category: {}", e))
+    }
+
+    pub async fn get_category_product_counts(&self) -> Result<Vec<CategoryProductCount>, String> {
+        self.repo
+            .product_counts()
+            .await
+            .map_err(|e| format!("Failed to count category products: {}", e))
+    }
+
+    /// The whole category tree in one call, with product counts. Categories
+    /// whose parent is missing (or that sit in a cycle) are listed as roots.
+    pub async fn get_category_tree(&self) -> Result<Vec<CategoryNode>, String> {
+        let categories = self.list_categories().await?;
+        let counts: HashMap<String, CategoryProductCount> = self
+            .get_category_product_counts()
+            .await?
+            .into_iter()
+            .map(|c| (c.category_id.clone(), c))
+            .collect();
+
+        let ids: HashSet<&str> = categories.iter().map(|c| c.id.as_str()).collect();
+        let mut children: HashMap<&str, Vec<&Category>> = HashMap::new();
+        let mut roots: Vec<&Category> = Vec::new();
+        for category in &categories {
+            match category.parent_id.as_deref() {
+                Some(parent_id) if ids.contains(parent_id) => {
+                    children.entry(parent_id).or_default().push(category)
+                }
+                _ => roots.push(category),
+            }
+        }
+
+        fn build(
+            category: &Category,
+            depth: usize,
+            children: &HashMap<&str, Vec<&Category>>,
+            counts: &HashMap<String, CategoryProductCount>,
+            visited: &mut HashSet<String>,
+        ) -> CategoryNode {
+            visited.insert(category.id.clone());
+            let mut nodes = Vec::new();
+            for child in children.get(category.id.as_str()).into_iter().flatten() {
+                if !visited.contains(&child.id) {
+                    nodes.push(build(child, depth + 1, children, counts, visited));
+                }
+            }
+            let count = counts.get(&category.id);
+            CategoryNode {
+                category: category.clone(),
+                depth,
+                product_count: count.map(|c| c.product_count).unwrap_or(0),
+                total_product_count: count.map(|c| c.total_product_count).unwrap_or(0),
+                children: nodes,
+            }
+        }
+
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut tree: Vec<CategoryNode> = roots
+            .into_iter()
+            .map(|c| build(c, 0, &children, &counts, &mut visited))
+            .collect();
+        for category in &categories {
+            if !visited.contains(&category.id) {
+                tree.push(build(category, 0, &children, &counts, &mut visited));
+            }
+        }
+        Ok(tree)
+    }
+
+    /// Breadcrumbs of a category, root first
+    pub async fn get_category_path(&self, id: &str) -> Result<CategoryPath, String> {
+        let categories = self.list_categories().await?;
+        let by_id: HashMap<&str, &Category> =
+            categories.iter().map(|c| (c.id.as_str(), c)).collect();
+        if !by_id.contains_key(id) {
+            return Err(format!("Category not found: {}", id));
+        }
+
+        let mut breadcrumbs = Vec::new();
+        let mut seen = HashSet::new();
+        let mut current = Some(id);
+        while let Some(category) = current.and_then(|id| by_id.get(id)) {
+            if !seen.insert(category.id.as_str()) {
+                break;
+            }
+            breadcrumbs.push(CategoryBreadcrumb {
+                id: category.id.clone(),
+                name: category.name.clone(),
+                slug: category.slug.clone(),
+            });
+            current = category.parent_id.as_deref();
+        }
+        breadcrumbs.reverse();
+
+        Ok(CategoryPath {
+            category_id: id.to_string(),
+            path: breadcrumbs
+                .iter()
+                .map(|b| b.name.as_str())
+                .collect::<Vec<_>>()
+                .join(" > "),
+            depth: breadcrumbs.len() - 1,
+            breadcrumbs,
+        })
+    }
 }
@@ -3,6 +3,9 @@
 use crate::features::checkout::dtos::checkout_dto::{CreateCheckoutDTO, UpdateCheckoutDTO};
 use crate::features::checkout::models::checkout_model::Checkout;
 use crate::features::checkout::repositories::shop_checkout_repository::ShopCheckoutRepository;
+use crate::features::customer::repositories::shop_customer_repository::ShopCustomerRepository;
+use crate::features::price_list::services::shop_pricing_service::ShopPricingService;
+use serde_json::Value;
 use sqlx::SqlitePool;
 use std::sync::Arc;
 
@@ -31,7 +34,7 @@ impl ShopCheckoutService {
     }
 
     pub async fn create_checkout(&self, payload: CreateCheckoutDTO) -> Result<Checkout, String> {
-        let checkout = payload.into_model();
+        let checkout = self.price_checkout(payload.into_model()).await?;
         self.repo
             .create(&checkout)
             .await
@@ -46,7 +49,9 @@ impl ShopCheckoutService {
             .map_err(|e| format!("Failed to fetch checkout: {}", e))?
             .ok_or_else(|| format!("Checkout not found: {}", payload.id))?;
 
-        let updated = payload.apply_to_checkout(existing);
+        let updated = self
+            .price_checkout(payload.apply_to_checkout(existing))
+            .await?;
         self.repo
             .update(&updated)
             .await
@@ -87,4 +92,79 @@ impl ShopCheckoutService {
             .await
             .map_err(|e| format!("Failed to update checkout status: {}", e))
     }
+
+    /// Customer the checkout is priced for: `metadata.customer_id`, else the
+    /// customer registered with the checkout email
+    async fn resolve_customer_id(&self, checkout: &Checkout) -> Result<Option<String>, String> {
+        let from_metadata = checkout
+            .metadata
+            .as_deref()
+            .and_then(|m| serde_json::from_str::<Value>(m).ok())
+            .and_then(|m| {
+                m.get("customer_id")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            });
+        if from_metadata.is_some() {
+            return Ok(from_metadata);
+        }
+
+        match &checkout.email {
+            Some(email) => Ok(
+                ShopCustomerRepository::new(self.pool.clone(), self.shop_id.clone())
+                    .find_by_email(email)
+                    .await
+                    .map_err(|e| format!("Failed to fetch customer: {}", e))?
+                    .map(|c| c.id),
+            ),
+            None => Ok(None),
+        }
+    }
+
+    /// Fill unit_price and line_total of each cart line with the price resolved
+    /// for the customer, and recompute the subtotal and total
+    async fn price_checkout(&self, mut checkout: Checkout) -> Result<Checkout, String> {
+        let mut items: Vec<Value> = match checkout.items.as_deref() {
+            Some(items) => {
+                serde_json::from_str(items).map_err(|e| format!("Invalid checkout items: {}", e))?
+            }
+            None => return Ok(checkout),
+        };
+
+        let customer_id = self.resolve_customer_id(&checkout).await?;
+        let pricing = ShopPricingService::new(self.pool.clone(), self.shop_id.clone());
+
+        let mut subtotal = 0.0;
+        for item in items.iter_mut() {
+            let product_id = match item.get("product_id").and_then(Value::as_str) {
+                Some(product_id) => product_id.to_string(),
+                None => continue,
+            };
+            let quantity = item.get("quantity").and_then(Value::as_f64).unwrap_or(1.0);
+
+            let price = pricing
+                .resolve(&product_id, customer_id.as_deref(), quantity, None)
+                .await?;
+            let line_total = (quantity * price.unit_price * 100.0).round() / 100.0;
+            subtotal += line_total;
+
+            if let Some(line) = item.as_object_mut() {
+                line.insert("unit_price".to_string(), Value::from(price.unit_price));
+                line.insert("line_total".to_string(), Value::from(line_total));
+                line.insert("price_source".to_string(), Value::from(price.source));
+                line.insert(
+                    "price_list_id".to_string(),
+                    Value::from(price.price_list_id),
+                );
+            }
+        }
+
+        checkout.items = Some(Value::Array(items).to_string());
+        checkout.subtotal_price = Some(subtotal);
+        checkout.total_price = Some(
+            subtotal + checkout.total_tax.unwrap_or(0.0) + checkout.total_shipping.unwrap_or(0.0)
+                - checkout.total_discounts.unwrap_or(0.0),
+        );
+        Ok(checkout)
+    }
 }
@@ -1,6 +1,13 @@
 use crate::db::RepositoryFactory;
-use crate::features::customer::dtos::customer_dto::{CreateCustomerDTO, UpdateCustomerDTO};
+use crate::features::customer::dtos::customer_dto::{
+    CreateCustomerDTO, DismissDuplicateCustomersDTO, FindDuplicateCustomersDTO, MergeCustomersDTO,
+    UpdateCustomerDTO,
+};
+use crate::features::customer::models::customer_merge_model::{
+    CustomerMerge, CustomerMergeResult, DuplicateCandidate,
+};
 use crate::features::customer::models::customer_model::Customer;
+use crate::features::customer::services::shop_customer_merge_service::ShopCustomerMergeService;
 use crate::features::customer::services::shop_customer_service::ShopCustomerService;
 use std::sync::Arc;
 use tauri::State;
@@ -92,3 +99,63 @@ pub async fn list_customers_by_shop(
     let service = ShopCustomerService::new(pool, shop_id);
     service.list_customers().await
 }
+
+#[tauri::command]
+pub async fn find_duplicate_customers(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    payload: FindDuplicateCustomersDTO,
+) -> Result<Vec<DuplicateCandidate>, String> {
+    let shop_id = payload.shop_id.clone();
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopCustomerMergeService::new(pool, shop_id);
+    service.find_duplicates(payload).await
+}
+
+#[tauri::command]
+pub async fn merge_customers(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    payload: MergeCustomersDTO,
+) -> Result<CustomerMergeResult, String> {
+    let shop_id = payload.shop_id.clone();
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopCustomerMergeService::new(pool, shop_id);
+    service.merge(payload).await
+}
+
+#[tauri::command]
+pub async fn dismiss_duplicate_customers(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    payload: DismissDuplicateCustomersDTO,
+) -> Result<(), String> {
+    let shop_id = payload.shop_id.clone();
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopCustomerMergeService::new(pool, shop_id);
+    service.dismiss(payload).await
+}
+
+#[tauri::command]
+pub async fn list_customer_merges(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    customer_id: Option<String>,
+) -> Result<Vec<CustomerMerge>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopCustomerMergeService::new(pool, shop_id);
+    service.list_merges(customer_id.as_deref()).await
+}
@@ -163,3 +163,28 @@ impl UpdateCustomerDTO {
         }
     }
 }
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct FindDuplicateCustomersDTO {
+    pub shop_id: String,
+    pub customer_id: Option<String>, // Only candidates involving this customer
+    pub min_score: Option<f64>,      // DEFAULT 0.6
+    pub limit: Option<i64>,          // DEFAULT 50
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MergeCustomersDTO {
+    pub shop_id: String,
+    pub survivor_id: String,
+    pub merged_id: String,
+    pub match_score: Option<f64>, // As proposed by duplicate detection
+    pub merged_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DismissDuplicateCustomersDTO {
+    pub shop_id: String,
+    pub customer_id: String,
+    pub other_customer_id: String,
+    pub dismissed_by: Option<String>,
+}
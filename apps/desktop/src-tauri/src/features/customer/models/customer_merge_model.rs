@@ -0,0 +1,54 @@
+use crate::features::customer::models::customer_model::Customer;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// The fields duplicate detection compares, and what staff need to see to
+/// pick the record to keep
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct DuplicateCustomer {
+    pub id: String,
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub r#type: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub company_name: Option<String>,
+    pub tax_id: Option<String>,
+    pub total_spent: Option<f64>,
+    pub orders_count: Option<i64>,
+    pub last_order_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Two customers that are probably the same person. `score` is in 0..=1;
+/// `reasons` lists the signals that matched (same_tax_id, same_email,
+/// same_phone, shared_address, similar_name).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DuplicateCandidate {
+    pub score: f64,
+    pub reasons: Vec<String>,
+    pub name_similarity: Option<f64>,
+    pub suggested_survivor_id: String,
+    pub customers: Vec<DuplicateCustomer>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct CustomerMerge {
+    pub id: String,
+    pub survivor_id: String,
+    pub merged_id: String,
+    pub match_score: Option<f64>,
+    pub merged_snapshot: String, // JSONB
+    pub moved_records: String,   // JSONB
+    pub merged_by: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CustomerMergeResult {
+    pub survivor: Customer,
+    pub merge: CustomerMerge,
+}
@@ -1 +1,2 @@
+pub mod customer_merge_model;
 pub mod customer_model;
@@ -1,2 +1,3 @@
 pub mod customer_repository;
+pub mod shop_customer_merge_repository;
 pub mod shop_customer_repository;
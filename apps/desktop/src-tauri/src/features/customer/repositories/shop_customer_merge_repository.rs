@@ -0,0 +1,254 @@
+//! Shop-scoped queries behind duplicate detection and customer merges
+
+use crate::features::customer::models::customer_merge_model::{CustomerMerge, DuplicateCustomer};
+use sqlx::{FromRow, Result, Sqlite, SqlitePool, Transaction};
+use std::sync::Arc;
+
+/// Tables whose rows follow the surviving customer on a merge. Group
+/// memberships and store credit need more than a re-point and are handled
+/// separately.
+pub const REASSIGNED_TABLES: [&str; 9] = [
+    "orders",
+    "transactions",
+    "reviews",
+    "inquiries",
+    "customer_addresses",
+    "return_requests",
+    "gift_cards",
+    "digital_deliveries",
+    "service_appointments",
+];
+
+#[derive(Debug, FromRow, Clone)]
+pub struct DuplicateAddressRow {
+    pub customer_id: String,
+    pub address1: Option<String>,
+    pub postal_code: Option<String>,
+}
+
+#[derive(Debug, FromRow, Clone)]
+pub struct DismissalRow {
+    pub customer_id_a: String,
+    pub customer_id_b: String,
+}
+
+pub struct ShopCustomerMergeRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopCustomerMergeRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_customers(&self) -> Result<Vec<DuplicateCustomer>> {
+        let sql = r#"
+            SELECT id, type, email, phone, first_name, last_name, company_name, tax_id,
+                   total_spent, orders_count, last_order_at, created_at
+            FROM customers
+            WHERE _status IS NULL OR _status != 'deleted'
+        "#;
+        sqlx::query_as::<_, DuplicateCustomer>(sql)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn list_addresses(&self) -> Result<Vec<DuplicateAddressRow>> {
+        let sql = r#"
+            SELECT a.customer_id, a.address1, a.postal_code
+            FROM customer_addresses a
+            INNER JOIN customers c ON c.id = a.customer_id
+            WHERE (a._status IS NULL OR a._status != 'deleted')
+              AND (c._status IS NULL OR c._status != 'deleted')
+        "#;
+        sqlx::query_as::<_, DuplicateAddressRow>(sql)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn list_dismissals(&self) -> Result<Vec<DismissalRow>> {
+        let sql = "SELECT customer_id_a, customer_id_b FROM customer_duplicate_dismissals";
+        sqlx::query_as::<_, DismissalRow>(sql)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// `customer_id_a` must sort before `customer_id_b`
+    pub async fn dismiss(
+        &self,
+        customer_id_a: &str,
+        customer_id_b: &str,
+        dismissed_by: Option<&str>,
+    ) -> Result<()> {
+        let sql = r#"
+            INSERT INTO customer_duplicate_dismissals (
+                customer_id_a, customer_id_b, dismissed_by, created_at
+            ) VALUES ($1, $2, $3, datetime('now'))
+            ON CONFLICT (customer_id_a, customer_id_b) DO NOTHING
+        "#;
+        sqlx::query(sql)
+            .bind(customer_id_a)
+            .bind(customer_id_b)
+            .bind(dismissed_by)
+            .execute(&*self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Merges a customer survived or was folded into, newest first
+    pub async fn list_merges(&self, customer_id: Option<&str>) -> Result<Vec<CustomerMerge>> {
+        let sql = r#"
+            SELECT * FROM customer_merges
+            WHERE $1 IS NULL OR survivor_id = $1 OR merged_id = $1
+            ORDER BY created_at DESC
+        "#;
+        sqlx::query_as::<_, CustomerMerge>(sql)
+            .bind(customer_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    // ============================================================
+    // Transaction-aware methods used by the merge
+    // ============================================================
+
+    /// Move every row of `table` from one customer to the other, deleted
+    /// ones included so history follows the survivor. `table` must be one
+    /// of `REASSIGNED_TABLES`.
+    pub async fn reassign_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        table: &str,
+        from_customer_id: &str,
+        to_customer_id: &str,
+    ) -> Result<u64> {
+        debug_assert!(REASSIGNED_TABLES.contains(&table));
+        let sql = format!(
+            r#"
+            UPDATE {} SET
+                customer_id = $2,
+                _status = CASE WHEN _status = 'deleted' THEN _status ELSE 'modified' END,
+                updated_at = datetime('now')
+            WHERE customer_id = $1
+            "#,
+            table
+        );
+        let result = sqlx::query(&sql)
+            .bind(from_customer_id)
+            .bind(to_customer_id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Keep a single default address: the merged customer's addresses stop
+    /// being default when the survivor already has one
+    pub async fn clear_default_addresses_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        merged_id: &str,
+        survivor_id: &str,
+    ) -> Result<()> {
+        let sql = r#"
+            UPDATE customer_addresses SET is_default = 0
+            WHERE customer_id = $1 AND is_default = 1
+              AND EXISTS (
+                  SELECT 1 FROM customer_addresses
+                  WHERE customer_id = $2 AND is_default = 1
+                    AND (_status IS NULL OR _status != 'deleted')
+              )
+        "#;
+        sqlx::query(sql)
+            .bind(merged_id)
+            .bind(survivor_id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    /// Copy the merged customer's memberships of non-dynamic groups to the
+    /// survivor, then end all of the merged customer's memberships. Dynamic
+    /// groups are recomputed for the survivor afterwards.
+    pub async fn move_memberships_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        merged_id: &str,
+        survivor_id: &str,
+    ) -> Result<u64> {
+        let copy = r#"
+            INSERT INTO customer_group_memberships (
+                customer_id, customer_group_id, _status, created_at, updated_at
+            )
+            SELECT $2, m.customer_group_id, 'created', datetime('now'), datetime('now')
+            FROM customer_group_memberships m
+            INNER JOIN customer_groups g ON g.id = m.customer_group_id
+            WHERE m.customer_id = $1
+              AND (m._status IS NULL OR m._status != 'deleted')
+              AND (g.type IS NULL OR g.type != 'dynamic')
+            ON CONFLICT (customer_id, customer_group_id) DO UPDATE SET
+                _status = 'modified', updated_at = datetime('now')
+            WHERE customer_group_memberships._status = 'deleted'
+        "#;
+        let copied = sqlx::query(copy)
+            .bind(merged_id)
+            .bind(survivor_id)
+            .execute(&mut **tx)
+            .await?
+            .rows_affected();
+
+        sqlx::query(
+            r#"
+            UPDATE customer_group_memberships SET _status = 'deleted', updated_at = datetime('now')
+            WHERE customer_id = $1 AND (_status IS NULL OR _status != 'deleted')
+            "#,
+        )
+        .bind(merged_id)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(copied)
+    }
+
+    /// Soft-delete the merged customer, releasing its unique email and tax id
+    /// for the survivor. Its stored metrics go; the survivor's are rebuilt
+    /// by the next metrics run.
+    pub async fn retire_in_tx(tx: &mut Transaction<'_, Sqlite>, merged_id: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE customers SET
+                email = NULL, tax_id = NULL, _status = 'deleted', updated_at = datetime('now')
+            WHERE id = $1
+            "#,
+        )
+        .bind(merged_id)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query("DELETE FROM customer_metrics WHERE customer_id = $1")
+            .bind(merged_id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn create_merge_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        merge: &CustomerMerge,
+    ) -> Result<CustomerMerge> {
+        let sql = r#"
+            INSERT INTO customer_merges (
+                id, survivor_id, merged_id, match_score, merged_snapshot, moved_records,
+                merged_by, created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, CustomerMerge>(sql)
+            .bind(&merge.id)
+            .bind(&merge.survivor_id)
+            .bind(&merge.merged_id)
+            .bind(merge.match_score)
+            .bind(&merge.merged_snapshot)
+            .bind(&merge.moved_records)
+            .bind(&merge.merged_by)
+            .bind(merge.created_at)
+            .fetch_one(&mut **tx)
+            .await
+    }
+}
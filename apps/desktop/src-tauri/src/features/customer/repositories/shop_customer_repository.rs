@@ -5,11 +5,45 @@
 //! does NOT have a shop_id column.
 
 use crate::features::customer::models::customer_model::Customer;
+use crate::features::search::repositories::shop_search_repository::{
+    build_match_query, CUSTOMER_RANK,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, Result, Sqlite, SqlitePool, Transaction};
+use sqlx::{Executor, FromRow, QueryBuilder, Result, Sqlite, SqlitePool, Transaction};
 use std::sync::Arc;
 
+/// Purchases that count towards a customer's spend, one row each: completed
+/// POS sales and paid, non-cancelled orders
+pub const PURCHASES_SQL: &str = r#"
+    SELECT customer_id, created_at AS ordered_at, CAST(COALESCE(total_net, 0) AS REAL) AS amount
+    FROM transactions
+    WHERE type = 'sale' AND status = 'completed' AND customer_id IS NOT NULL
+      AND (_status IS NULL OR _status != 'deleted')
+    UNION ALL
+    SELECT customer_id, created_at, CAST(COALESCE(total_price, 0) AS REAL)
+    FROM orders
+    WHERE payment_status = 'paid' AND (status IS NULL OR status != 'cancelled')
+      AND customer_id IS NOT NULL AND (_status IS NULL OR _status != 'deleted')
+"#;
+
+/// Refunds of resolved returns per customer, which come off its spend
+pub const RETURNED_SQL: &str = r#"
+    SELECT customer_id, CAST(COALESCE(SUM(resolved_amount), 0) AS REAL) AS amount
+    FROM return_requests
+    WHERE status = 'resolved' AND customer_id IS NOT NULL
+      AND (_status IS NULL OR _status != 'deleted')
+    GROUP BY customer_id
+"#;
+
+/// A customer's spend counted from its purchases less its returns
+#[derive(Debug, FromRow, Clone)]
+pub struct PurchaseStatsRow {
+    pub total_spent: f64,
+    pub orders_count: i64,
+    pub last_order_at: Option<DateTime<Utc>>,
+}
+
 /// Internal struct for deserializing from shop database (no shop_id column)
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
 struct ShopCustomer {
@@ -190,6 +224,21 @@ impl ShopCustomerRepository {
     }
 
     pub async fn update(&self, customer: &Customer) -> Result<Customer> {
+        self.update_with(&*self.pool, customer).await
+    }
+
+    pub async fn update_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        customer: &Customer,
+    ) -> Result<Customer> {
+        self.update_with(&mut **tx, customer).await
+    }
+
+    async fn update_with<'e, E>(&self, executor: E, customer: &Customer) -> Result<Customer>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
         let sql = r#"
             UPDATE customers SET
                 type = $2,
@@ -244,7 +293,7 @@ impl ShopCustomerRepository {
             .bind(&customer.custom_attributes)
             .bind(&customer.sync_status)
             .bind(&customer.updated_at)
-            .fetch_one(&*self.pool)
+            .fetch_one(executor)
             .await?;
 
         Ok(shop_customer.into_customer(self.shop_id.clone()))
@@ -260,6 +309,20 @@ impl ShopCustomerRepository {
         Ok(result.map(|c| c.into_customer(self.shop_id.clone())))
     }
 
+    pub async fn get_by_id_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+    ) -> Result<Option<Customer>> {
+        let sql = "SELECT * FROM customers WHERE id = $1 AND (_status IS NULL OR _status != 'deleted')";
+        let result = sqlx::query_as::<_, ShopCustomer>(sql)
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(result.map(|c| c.into_customer(self.shop_id.clone())))
+    }
+
     pub async fn list(&self) -> Result<Vec<Customer>> {
         let sql = "SELECT * FROM customers WHERE _status IS NULL OR _status != 'deleted' ORDER BY created_at DESC";
         let results = sqlx::query_as::<_, ShopCustomer>(sql)
@@ -278,21 +341,51 @@ impl ShopCustomerRepository {
         Ok(())
     }
 
+    /// Full-text search over name, email, phone and tax id, most relevant first
     pub async fn search(&self, query_str: &str) -> Result<Vec<Customer>> {
-        let sql = r#"
-            SELECT * FROM customers
-            WHERE (_status IS NULL OR _status != 'deleted')
-              AND (first_name LIKE $1
-                   OR last_name LIKE $1
-                   OR email LIKE $1
-                   OR company_name LIKE $1
-                   OR tax_id LIKE $1
-                   OR phone LIKE $1)
-            ORDER BY created_at DESC
-        "#;
-        let search_pattern = format!("%{}%", query_str);
-        let results = sqlx::query_as::<_, ShopCustomer>(sql)
-            .bind(search_pattern)
+        let Some(match_query) = build_match_query(query_str) else {
+            return self.list().await;
+        };
+
+        let sql = format!(
+            r#"
+            SELECT c.* FROM customers_fts
+            JOIN search_index_keys k ON k.rowid = customers_fts.rowid
+            JOIN customers c ON c.id = k.entity_id
+            WHERE customers_fts MATCH $1
+              AND (c._status IS NULL OR c._status != 'deleted')
+            ORDER BY {}
+            "#,
+            CUSTOMER_RANK
+        );
+        let results = sqlx::query_as::<_, ShopCustomer>(&sql)
+            .bind(match_query)
+            .fetch_all(&*self.pool)
+            .await?;
+
+        Ok(results
+            .into_iter()
+            .map(|c| c.into_customer(self.shop_id.clone()))
+            .collect())
+    }
+
+    /// Customers with the given ids, in no particular order
+    pub async fn list_by_ids(&self, ids: &[String]) -> Result<Vec<Customer>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT * FROM customers WHERE (_status IS NULL OR _status != 'deleted') AND id IN (",
+        );
+        let mut separated = builder.separated(", ");
+        for id in ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(")");
+
+        let results = builder
+            .build_query_as::<ShopCustomer>()
             .fetch_all(&*self.pool)
             .await?;
 
@@ -322,48 +415,52 @@ impl ShopCustomerRepository {
         Ok(result.map(|c| c.into_customer(self.shop_id.clone())))
     }
 
-    /// Increment customer stats after a completed sale within a transaction
-    pub async fn increment_stats_in_tx(
+    /// Spend of a customer as defined by `PURCHASES_SQL` and `RETURNED_SQL`
+    pub async fn purchase_stats_in_tx(
         tx: &mut Transaction<'_, Sqlite>,
         customer_id: &str,
-        amount: f64,
-        shop_id: String,
-    ) -> Result<Customer> {
-        let sql = r#"
-            UPDATE customers
-            SET total_spent = COALESCE(total_spent, 0) + $2,
-                orders_count = COALESCE(orders_count, 0) + 1,
-                last_order_at = CURRENT_TIMESTAMP,
-                updated_at = CURRENT_TIMESTAMP
-            WHERE id = $1
-            RETURNING *
-        "#;
-        let shop_customer = sqlx::query_as::<_, ShopCustomer>(sql)
+    ) -> Result<PurchaseStatsRow> {
+        let sql = format!(
+            r#"
+            WITH purchases AS ({}), returned AS ({})
+            SELECT
+                MAX(0.0, COALESCE(SUM(p.amount), 0.0)
+                    - COALESCE((SELECT amount FROM returned WHERE customer_id = $1), 0.0)) AS total_spent,
+                COUNT(p.amount) AS orders_count,
+                MAX(p.ordered_at) AS last_order_at
+            FROM purchases p
+            WHERE p.customer_id = $1
+            "#,
+            PURCHASES_SQL, RETURNED_SQL
+        );
+        sqlx::query_as::<_, PurchaseStatsRow>(&sql)
             .bind(customer_id)
-            .bind(amount)
             .fetch_one(&mut **tx)
-            .await?;
-
-        Ok(shop_customer.into_customer(shop_id))
+            .await
     }
 
-    /// Decrement customer stats after a return within a transaction
-    pub async fn decrement_stats_in_tx(
+    /// Recount the stored stats of a customer after one of its purchases or
+    /// returns changed, within a transaction
+    pub async fn refresh_stats_in_tx(
         tx: &mut Transaction<'_, Sqlite>,
         customer_id: &str,
-        amount: f64,
         shop_id: String,
     ) -> Result<Customer> {
+        let stats = Self::purchase_stats_in_tx(tx, customer_id).await?;
         let sql = r#"
             UPDATE customers
-            SET total_spent = MAX(0, COALESCE(total_spent, 0) - $2),
+            SET total_spent = $2,
+                orders_count = $3,
+                last_order_at = $4,
                 updated_at = CURRENT_TIMESTAMP
             WHERE id = $1
             RETURNING *
         "#;
         let shop_customer = sqlx::query_as::<_, ShopCustomer>(sql)
             .bind(customer_id)
-            .bind(amount)
+            .bind(stats.total_spent)
+            .bind(stats.orders_count)
+            .bind(stats.last_order_at)
             .fetch_one(&mut **tx)
             .await?;
 
@@ -0,0 +1,231 @@
+//! Duplicate customer detection
+//!
+//! Each customer is reduced to a `CustomerFingerprint` of normalized keys.
+//! Two customers are only compared when they share a blocking key (tax id,
+//! email, phone, address or name initials), so a full scan stays close to
+//! linear. The signals that match are combined as independent evidence:
+//! score = 1 - (1 - w1)(1 - w2)...
+
+use crate::features::customer::models::customer_merge_model::DuplicateCustomer;
+use crate::features::data_transfer::services::row_reader::{ascii_words, digits};
+use std::collections::HashSet;
+
+/// Left out of names so that "Maria da Silva" matches "Maria Silva"
+const NAME_PARTICLES: [&str; 6] = ["da", "das", "de", "do", "dos", "e"];
+
+const TAX_ID_WEIGHT: f64 = 0.99;
+const EMAIL_WEIGHT: f64 = 0.9;
+const PHONE_WEIGHT: f64 = 0.6;
+const ADDRESS_WEIGHT: f64 = 0.35;
+const NAME_WEIGHT: f64 = 0.5;
+/// Jaro-Winkler similarity from which two names count as the same
+const MIN_NAME_SIMILARITY: f64 = 0.85;
+
+pub struct CustomerFingerprint {
+    pub id: String,
+    name: Vec<String>,
+    tax_id: Option<String>,
+    email: Option<String>,
+    phone: Option<String>,
+    addresses: HashSet<String>,
+}
+
+pub struct MatchScore {
+    pub score: f64,
+    pub reasons: Vec<String>,
+    pub name_similarity: Option<f64>,
+}
+
+impl CustomerFingerprint {
+    /// `addresses` are the customer's `address_key`s
+    pub fn new(customer: &DuplicateCustomer, addresses: HashSet<String>) -> Self {
+        Self {
+            id: customer.id.clone(),
+            name: name_tokens(customer),
+            tax_id: customer.tax_id.as_deref().and_then(normalize_tax_id),
+            email: customer.email.as_deref().and_then(normalize_email),
+            phone: customer.phone.as_deref().and_then(normalize_phone),
+            addresses,
+        }
+    }
+
+    pub fn blocking_keys(&self) -> Vec<String> {
+        let mut keys = Vec::new();
+        if let Some(tax_id) = &self.tax_id {
+            keys.push(format!("tax:{}", tax_id));
+        }
+        if let Some(email) = &self.email {
+            keys.push(format!("email:{}", email));
+        }
+        if let Some(phone) = &self.phone {
+            keys.push(format!("phone:{}", phone));
+        }
+        keys.extend(self.addresses.iter().map(|a| format!("address:{}", a)));
+        // Initials survive most misspellings
+        if let (Some(first), Some(last)) = (self.name.first(), self.name.last()) {
+            let initial = |token: &String| token.chars().next().unwrap_or_default();
+            keys.push(format!("name:{}{}", initial(first), initial(last)));
+        }
+        keys
+    }
+
+    /// None when nothing matches, or when both have valid and different
+    /// tax ids, which makes them different people whatever else they share
+    pub fn compare(&self, other: &Self) -> Option<MatchScore> {
+        if let (Some(a), Some(b)) = (&self.tax_id, &other.tax_id) {
+            if a != b {
+                return None;
+            }
+        }
+
+        let mut signals: Vec<(&str, f64)> = Vec::new();
+        if self.tax_id.is_some() && self.tax_id == other.tax_id {
+            signals.push(("same_tax_id", TAX_ID_WEIGHT));
+        }
+        if self.email.is_some() && self.email == other.email {
+            signals.push(("same_email", EMAIL_WEIGHT));
+        }
+        if self.phone.is_some() && self.phone == other.phone {
+            signals.push(("same_phone", PHONE_WEIGHT));
+        }
+        if !self.addresses.is_disjoint(&other.addresses) {
+            signals.push(("shared_address", ADDRESS_WEIGHT));
+        }
+
+        let name_similarity = (!self.name.is_empty() && !other.name.is_empty())
+            .then(|| round(name_similarity(&self.name, &other.name)));
+        if let Some(similarity) = name_similarity.filter(|s| *s >= MIN_NAME_SIMILARITY) {
+            let closeness = (similarity - MIN_NAME_SIMILARITY) / (1.0 - MIN_NAME_SIMILARITY);
+            signals.push(("similar_name", NAME_WEIGHT * (0.5 + closeness / 2.0)));
+        }
+
+        if signals.is_empty() {
+            return None;
+        }
+        let missed: f64 = signals.iter().map(|(_, weight)| 1.0 - weight).product();
+        Some(MatchScore {
+            score: round(1.0 - missed),
+            reasons: signals
+                .iter()
+                .map(|(reason, _)| reason.to_string())
+                .collect(),
+            name_similarity,
+        })
+    }
+}
+
+/// CPF (11 digits) or CNPJ (14 digits) without punctuation. Placeholders
+/// such as 000.000.000-00, common on records created at the till, are
+/// ignored.
+pub fn normalize_tax_id(raw: &str) -> Option<String> {
+    let tax_id = digits(raw);
+    let placeholder = tax_id.chars().all(|c| tax_id.starts_with(c));
+    ((tax_id.len() == 11 || tax_id.len() == 14) && !placeholder).then_some(tax_id)
+}
+
+/// The last 8 digits: "+55 (11) 98765-4321", "11 98765 4321" and
+/// "8765-4321" all match, with or without area code and mobile ninth digit
+pub fn normalize_phone(raw: &str) -> Option<String> {
+    let phone = digits(raw);
+    (phone.len() >= 8).then(|| phone[phone.len() - 8..].to_string())
+}
+
+pub fn normalize_email(raw: &str) -> Option<String> {
+    let email = raw.trim().to_lowercase();
+    email.contains('@').then_some(email)
+}
+
+/// Postal code and street line, e.g. "01310100|av paulista 1000"
+pub fn address_key(address1: Option<&str>, postal_code: Option<&str>) -> Option<String> {
+    let street = ascii_words(address1?, " ");
+    let postal_code = digits(postal_code?);
+    (!street.is_empty() && postal_code.len() >= 4).then(|| format!("{}|{}", postal_code, street))
+}
+
+/// Company name for companies, first and last name otherwise
+fn name_tokens(customer: &DuplicateCustomer) -> Vec<String> {
+    let person = [
+        customer.first_name.as_deref(),
+        customer.last_name.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" ");
+    let name = match customer.company_name.as_deref() {
+        Some(company) if customer.r#type == "company" || person.trim().is_empty() => company,
+        _ => person.as_str(),
+    };
+
+    ascii_words(name, " ")
+        .split(' ')
+        .filter(|token| !token.is_empty() && !NAME_PARTICLES.contains(token))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Best of the names as written and with their words sorted, so that
+/// "Silva Maria" matches "Maria Silva"
+fn name_similarity(a: &[String], b: &[String]) -> f64 {
+    let sorted = |tokens: &[String]| {
+        let mut tokens = tokens.to_vec();
+        tokens.sort();
+        tokens.join(" ")
+    };
+    jaro_winkler(&a.join(" "), &b.join(" ")).max(jaro_winkler(&sorted(a), &sorted(b)))
+}
+
+fn jaro_winkler(a: &str, b: &str) -> f64 {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let jaro = jaro(&a, &b);
+    let prefix = a.iter().zip(&b).take(4).take_while(|(x, y)| x == y).count();
+    jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+}
+
+fn jaro(a: &[char], b: &[char]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return if a.is_empty() && b.is_empty() {
+            1.0
+        } else {
+            0.0
+        };
+    }
+
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0usize;
+    for (i, c) in a.iter().enumerate() {
+        let end = (i + window + 1).min(b.len());
+        for j in i.saturating_sub(window)..end {
+            if !b_matched[j] && b[j] == *c {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    if matches == 0 {
+        return 0.0;
+    }
+
+    let a_order = a
+        .iter()
+        .zip(&a_matched)
+        .filter(|(_, m)| **m)
+        .map(|(c, _)| c);
+    let b_order = b
+        .iter()
+        .zip(&b_matched)
+        .filter(|(_, m)| **m)
+        .map(|(c, _)| c);
+    let transpositions = a_order.zip(b_order).filter(|(x, y)| x != y).count();
+
+    let m = matches as f64;
+    (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64 / 2.0) / m) / 3.0
+}
+
+fn round(value: f64) -> f64 {
+    (value * 10_000.0).round() / 10_000.0
+}
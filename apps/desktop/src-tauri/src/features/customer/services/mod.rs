@@ -1,2 +1,4 @@
+pub mod customer_match;
 pub mod customer_service;
+pub mod shop_customer_merge_service;
 pub mod shop_customer_service;
@@ -0,0 +1,421 @@
+//! Shop-scoped duplicate detection and merging of customers
+//!
+//! Detection only proposes candidates; staff confirm each merge. A merge
+//! folds one customer into another in a single transaction: everything the
+//! merged customer owns is re-pointed to the survivor, the survivor's blank
+//! fields are filled in, stats are added up, the merged record is
+//! soft-deleted and the operation is recorded in `customer_merges` and
+//! `audit_logs`.
+
+use crate::features::audit_log::models::audit_log_model::AuditLog;
+use crate::features::audit_log::repositories::shop_audit_log_repository::ShopAuditLogRepository;
+use crate::features::customer::dtos::customer_dto::{
+    DismissDuplicateCustomersDTO, FindDuplicateCustomersDTO, MergeCustomersDTO,
+};
+use crate::features::customer::models::customer_merge_model::{
+    CustomerMerge, CustomerMergeResult, DuplicateCandidate, DuplicateCustomer,
+};
+use crate::features::customer::models::customer_model::Customer;
+use crate::features::customer::repositories::shop_customer_merge_repository::{
+    ShopCustomerMergeRepository, REASSIGNED_TABLES,
+};
+use crate::features::customer::repositories::shop_customer_repository::{
+    PurchaseStatsRow, ShopCustomerRepository,
+};
+use crate::features::customer::services::customer_match::{address_key, CustomerFingerprint};
+use crate::features::customer_group::services::shop_customer_segment_service::ShopCustomerSegmentService;
+use crate::features::store_credit::services::shop_store_credit_service::ShopStoreCreditService;
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const DEFAULT_MIN_SCORE: f64 = 0.6;
+const DEFAULT_CANDIDATE_LIMIT: i64 = 50;
+const MAX_CANDIDATE_LIMIT: i64 = 500;
+
+pub struct ShopCustomerMergeService {
+    pool: Arc<SqlitePool>,
+    shop_id: String,
+    repo: ShopCustomerMergeRepository,
+    customer_repo: ShopCustomerRepository,
+}
+
+impl ShopCustomerMergeService {
+    pub fn new(pool: Arc<SqlitePool>, shop_id: String) -> Self {
+        Self {
+            repo: ShopCustomerMergeRepository::new(pool.clone()),
+            customer_repo: ShopCustomerRepository::new(pool.clone(), shop_id.clone()),
+            pool,
+            shop_id,
+        }
+    }
+
+    pub fn shop_id(&self) -> &str {
+        &self.shop_id
+    }
+
+    /// Likely duplicates, best match first, leaving out dismissed pairs
+    pub async fn find_duplicates(
+        &self,
+        payload: FindDuplicateCustomersDTO,
+    ) -> Result<Vec<DuplicateCandidate>, String> {
+        let min_score = payload.min_score.unwrap_or(DEFAULT_MIN_SCORE);
+        if !(0.0..=1.0).contains(&min_score) {
+            return Err("Minimum score must be between 0 and 1".to_string());
+        }
+        let limit = payload
+            .limit
+            .unwrap_or(DEFAULT_CANDIDATE_LIMIT)
+            .clamp(1, MAX_CANDIDATE_LIMIT) as usize;
+
+        let customers = self
+            .repo
+            .list_customers()
+            .await
+            .map_err(|e| format!("Failed to list customers: {}", e))?;
+        let addresses = self
+            .repo
+            .list_addresses()
+            .await
+            .map_err(|e| format!("Failed to list customer addresses: {}", e))?;
+        let dismissed: HashSet<(String, String)> = self
+            .repo
+            .list_dismissals()
+            .await
+            .map_err(|e| format!("Failed to list dismissed duplicates: {}", e))?
+            .into_iter()
+            .map(|d| (d.customer_id_a, d.customer_id_b))
+            .collect();
+
+        let mut address_keys: HashMap<String, HashSet<String>> = HashMap::new();
+        for address in addresses {
+            if let Some(key) =
+                address_key(address.address1.as_deref(), address.postal_code.as_deref())
+            {
+                address_keys
+                    .entry(address.customer_id)
+                    .or_default()
+                    .insert(key);
+            }
+        }
+        let fingerprints: Vec<CustomerFingerprint> = customers
+            .iter()
+            .map(|c| CustomerFingerprint::new(c, address_keys.remove(&c.id).unwrap_or_default()))
+            .collect();
+
+        let mut blocks: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, fingerprint) in fingerprints.iter().enumerate() {
+            for key in fingerprint.blocking_keys() {
+                blocks.entry(key).or_default().push(i);
+            }
+        }
+
+        let mut pairs: HashSet<(usize, usize)> = HashSet::new();
+        match payload.customer_id.as_deref() {
+            Some(customer_id) => {
+                let target = customers
+                    .iter()
+                    .position(|c| c.id == customer_id)
+                    .ok_or_else(|| format!("Customer not found: {}", customer_id))?;
+                for key in fingerprints[target].blocking_keys() {
+                    for &other in blocks.get(&key).into_iter().flatten() {
+                        if other != target {
+                            pairs.insert((target.min(other), target.max(other)));
+                        }
+                    }
+                }
+            }
+            None => {
+                for members in blocks.values() {
+                    for (n, &i) in members.iter().enumerate() {
+                        pairs.extend(members[n + 1..].iter().map(|&j| (i.min(j), i.max(j))));
+                    }
+                }
+            }
+        }
+
+        let mut candidates: Vec<DuplicateCandidate> = pairs
+            .into_iter()
+            .filter(|&(i, j)| !dismissed.contains(&ordered_ids(&customers[i].id, &customers[j].id)))
+            .filter_map(|(i, j)| {
+                let found = fingerprints[i].compare(&fingerprints[j])?;
+                if found.score < min_score {
+                    return None;
+                }
+                let (survivor, merged) = survivor_first(&customers[i], &customers[j]);
+                Some(DuplicateCandidate {
+                    score: found.score,
+                    reasons: found.reasons,
+                    name_similarity: found.name_similarity,
+                    suggested_survivor_id: survivor.id.clone(),
+                    customers: vec![survivor.clone(), merged.clone()],
+                })
+            })
+            .collect();
+
+        candidates.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.suggested_survivor_id.cmp(&b.suggested_survivor_id))
+        });
+        candidates.truncate(limit);
+        Ok(candidates)
+    }
+
+    /// Fold `merged_id` into `survivor_id`. Stats are recounted from the
+    /// sales, orders and returns both records now share.
+    pub async fn merge(&self, payload: MergeCustomersDTO) -> Result<CustomerMergeResult, String> {
+        if payload.survivor_id == payload.merged_id {
+            return Err("A customer cannot be merged into itself".to_string());
+        }
+        if payload
+            .match_score
+            .is_some_and(|s| !(0.0..=1.0).contains(&s))
+        {
+            return Err("Match score must be between 0 and 1".to_string());
+        }
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let survivor = self
+            .get_customer_in_tx(&mut tx, &payload.survivor_id)
+            .await?;
+        let merged = self.get_customer_in_tx(&mut tx, &payload.merged_id).await?;
+
+        let mut moved = Map::new();
+        ShopCustomerMergeRepository::clear_default_addresses_in_tx(
+            &mut tx,
+            &merged.id,
+            &survivor.id,
+        )
+        .await
+        .map_err(|e| format!("Failed to update customer addresses: {}", e))?;
+        for table in REASSIGNED_TABLES {
+            let count = ShopCustomerMergeRepository::reassign_in_tx(
+                &mut tx,
+                table,
+                &merged.id,
+                &survivor.id,
+            )
+            .await
+            .map_err(|e| format!("Failed to move {}: {}", table, e))?;
+            moved.insert(table.to_string(), json!(count));
+        }
+        let memberships =
+            ShopCustomerMergeRepository::move_memberships_in_tx(&mut tx, &merged.id, &survivor.id)
+                .await
+                .map_err(|e| format!("Failed to move customer group memberships: {}", e))?;
+        moved.insert("customer_group_memberships".to_string(), json!(memberships));
+        let store_credit = ShopStoreCreditService::transfer_customer_in_tx(
+            &mut tx,
+            &merged.id,
+            &survivor.id,
+            payload.merged_by.clone(),
+        )
+        .await?;
+        moved.insert("store_credit".to_string(), json!(store_credit));
+
+        // Frees the merged email and tax id before the survivor can take them
+        ShopCustomerMergeRepository::retire_in_tx(&mut tx, &merged.id)
+            .await
+            .map_err(|e| format!("Failed to retire merged customer: {}", e))?;
+        let stats = ShopCustomerRepository::purchase_stats_in_tx(&mut tx, &survivor.id)
+            .await
+            .map_err(|e| format!("Failed to recount customer stats: {}", e))?;
+        let updated = self
+            .customer_repo
+            .update_in_tx(&mut tx, &absorb(&survivor, &merged, &stats))
+            .await
+            .map_err(|e| format!("Failed to update surviving customer: {}", e))?;
+
+        let snapshot = to_json(&merged)?;
+        let merge = ShopCustomerMergeRepository::create_merge_in_tx(
+            &mut tx,
+            &CustomerMerge {
+                id: Uuid::new_v4().to_string(),
+                survivor_id: survivor.id.clone(),
+                merged_id: merged.id.clone(),
+                match_score: payload.match_score,
+                merged_snapshot: snapshot.clone(),
+                moved_records: Value::Object(moved).to_string(),
+                merged_by: payload.merged_by.clone(),
+                created_at: Some(Utc::now()),
+            },
+        )
+        .await
+        .map_err(|e| format!("Failed to record customer merge: {}", e))?;
+
+        let merge_ref = json!({ "merge_id": merge.id, "merged_into": survivor.id }).to_string();
+        for (record_id, action, old_data, new_data) in [
+            (
+                &survivor.id,
+                "UPDATE",
+                to_json(&survivor)?,
+                to_json(&updated)?,
+            ),
+            (&merged.id, "DELETE", snapshot, merge_ref),
+        ] {
+            let log = AuditLog {
+                id: Uuid::new_v4().to_string(),
+                table_name: "customers".to_string(),
+                record_id: record_id.clone(),
+                action: action.to_string(),
+                old_data: Some(old_data),
+                new_data: Some(new_data),
+                changed_by: payload.merged_by.clone(),
+                ip_address: None,
+                user_agent: None,
+                created_at: Utc::now(),
+            };
+            ShopAuditLogRepository::create_in_tx(&mut tx, &log)
+                .await
+                .map_err(|e| format!("Failed to create audit log: {}", e))?;
+        }
+
+        // Merged tags, stats and addresses can change dynamic memberships
+        ShopCustomerSegmentService::refresh_customer_in_tx(&mut tx, &updated.id).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(CustomerMergeResult {
+            survivor: updated,
+            merge,
+        })
+    }
+
+    /// Mark a proposed pair as different people
+    pub async fn dismiss(&self, payload: DismissDuplicateCustomersDTO) -> Result<(), String> {
+        if payload.customer_id == payload.other_customer_id {
+            return Err("A customer cannot be a duplicate of itself".to_string());
+        }
+        let (a, b) = ordered_ids(&payload.customer_id, &payload.other_customer_id);
+        self.repo
+            .dismiss(&a, &b, payload.dismissed_by.as_deref())
+            .await
+            .map_err(|e| format!("Failed to dismiss duplicate customers: {}", e))
+    }
+
+    pub async fn list_merges(
+        &self,
+        customer_id: Option<&str>,
+    ) -> Result<Vec<CustomerMerge>, String> {
+        self.repo
+            .list_merges(customer_id)
+            .await
+            .map_err(|e| format!("Failed to list customer merges: {}", e))
+    }
+
+    async fn get_customer_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+    ) -> Result<Customer, String> {
+        self.customer_repo
+            .get_by_id_in_tx(tx, id)
+            .await
+            .map_err(|e| format!("Failed to fetch customer: {}", e))?
+            .ok_or_else(|| format!("Customer not found: {}", id))
+    }
+}
+
+/// The customer to keep: most orders, then most spent, then the oldest
+fn survivor_first<'a>(
+    a: &'a DuplicateCustomer,
+    b: &'a DuplicateCustomer,
+) -> (&'a DuplicateCustomer, &'a DuplicateCustomer) {
+    let order = b
+        .orders_count
+        .unwrap_or(0)
+        .cmp(&a.orders_count.unwrap_or(0))
+        .then_with(|| {
+            b.total_spent
+                .unwrap_or(0.0)
+                .total_cmp(&a.total_spent.unwrap_or(0.0))
+        })
+        .then_with(|| a.created_at.cmp(&b.created_at));
+    if order == Ordering::Greater {
+        (b, a)
+    } else {
+        (a, b)
+    }
+}
+
+fn ordered_ids(a: &str, b: &str) -> (String, String) {
+    if a < b {
+        (a.to_string(), b.to_string())
+    } else {
+        (b.to_string(), a.to_string())
+    }
+}
+
+/// Comma separated tags of both customers, each kept once
+fn union_tags(kept: Option<&str>, added: Option<&str>) -> Option<String> {
+    let mut seen = HashSet::new();
+    let tags: Vec<&str> = [kept, added]
+        .into_iter()
+        .flatten()
+        .flat_map(|tags| tags.split(','))
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty() && seen.insert(tag.to_lowercase()))
+        .collect();
+    (!tags.is_empty()).then(|| tags.join(", "))
+}
+
+/// The survivor with its blank fields taken from the merged customer, the
+/// tags of both and the stats recounted once their records are combined
+fn absorb(survivor: &Customer, merged: &Customer, stats: &PurchaseStatsRow) -> Customer {
+    let fill = |target: &mut Option<String>, source: &Option<String>| {
+        if target.as_deref().is_none_or(|t| t.trim().is_empty()) {
+            *target = source.clone();
+        }
+    };
+
+    let mut customer = survivor.clone();
+    fill(&mut customer.email, &merged.email);
+    fill(&mut customer.phone, &merged.phone);
+    fill(&mut customer.first_name, &merged.first_name);
+    fill(&mut customer.last_name, &merged.last_name);
+    fill(&mut customer.company_name, &merged.company_name);
+    if customer
+        .tax_id
+        .as_deref()
+        .is_none_or(|t| t.trim().is_empty())
+    {
+        customer.tax_id = merged.tax_id.clone();
+        customer.tax_id_type = merged.tax_id_type.clone();
+    }
+    fill(&mut customer.state_tax_id, &merged.state_tax_id);
+    customer.tags = union_tags(survivor.tags.as_deref(), merged.tags.as_deref());
+    fill(&mut customer.customer_group_id, &merged.customer_group_id);
+    match (survivor.notes.as_deref(), merged.notes.as_deref()) {
+        (Some(kept), Some(added)) if !kept.trim().is_empty() && !added.trim().is_empty() => {
+            customer.notes = Some(format!("{}\n{}", kept, added));
+        }
+        _ => fill(&mut customer.notes, &merged.notes),
+    }
+    // Marketing consent only stands if both records gave it
+    customer.accepts_marketing = Some(
+        survivor.accepts_marketing.unwrap_or(false) && merged.accepts_marketing.unwrap_or(false),
+    );
+
+    customer.total_spent = Some(stats.total_spent);
+    customer.orders_count = Some(stats.orders_count);
+    customer.last_order_at = stats.last_order_at;
+    customer.sync_status = Some("modified".to_string());
+    customer.updated_at = Some(Utc::now());
+    customer
+}
+
+fn to_json(customer: &Customer) -> Result<String, String> {
+    serde_json::to_string(customer).map_err(|e| format!("Failed to serialize customer: {}", e))
+}
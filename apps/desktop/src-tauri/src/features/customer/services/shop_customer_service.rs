@@ -6,6 +6,7 @@
 use crate::features::customer::dtos::customer_dto::{CreateCustomerDTO, UpdateCustomerDTO};
 use crate::features::customer::models::customer_model::Customer;
 use crate::features::customer::repositories::shop_customer_repository::ShopCustomerRepository;
+use crate::features::customer_group::services::shop_customer_segment_service::ShopCustomerSegmentService;
 use sqlx::SqlitePool;
 use std::sync::Arc;
 
@@ -103,6 +104,8 @@ impl ShopCustomerService {
             }
         }
 
+        ShopCustomerSegmentService::refresh_customer_in_tx(&mut tx, &created_customer.id).await?;
+
         tx.commit()
             .await
             .map_err(|e| format!("Failed to commit transaction: {}", e))?;
@@ -112,10 +115,27 @@ impl ShopCustomerService {
 
     pub async fn update_customer(&self, payload: UpdateCustomerDTO) -> Result<Customer, String> {
         let customer = payload.into_models();
-        self.repo
-            .update(&customer)
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let updated = self
+            .repo
+            .update_in_tx(&mut tx, &customer)
+            .await
+            .map_err(|e| format!("Failed to update customer: {}", e))?;
+
+        // Tags, marketing consent and stats all feed dynamic groups
+        ShopCustomerSegmentService::refresh_customer_in_tx(&mut tx, &updated.id).await?;
+
+        tx.commit()
             .await
-            .map_err(|e| format!("Failed to update customer: {}", e))
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(updated)
     }
 
     pub async fn delete_customer(&self, id: &str) -> Result<(), String> {
@@ -1,7 +1,7 @@
 //! Shop-scoped Customer Address Repository for Multi-Database Architecture
 
 use crate::features::customer::models::customer_model::CustomerAddress;
-use sqlx::{Result, SqlitePool};
+use sqlx::{Executor, Result, Sqlite, SqlitePool, Transaction};
 use std::sync::Arc;
 
 pub struct ShopCustomerAddressRepository {
@@ -14,6 +14,25 @@ impl ShopCustomerAddressRepository {
     }
 
     pub async fn create(&self, address: &CustomerAddress) -> Result<CustomerAddress> {
+        self.create_with(&*self.pool, address).await
+    }
+
+    pub async fn create_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        address: &CustomerAddress,
+    ) -> Result<CustomerAddress> {
+        self.create_with(&mut **tx, address).await
+    }
+
+    async fn create_with<'e, E>(
+        &self,
+        executor: E,
+        address: &CustomerAddress,
+    ) -> Result<CustomerAddress>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
         let sql = r#"
             INSERT INTO customer_addresses (
                 id, customer_id, type, is_default, first_name, last_name, company,
@@ -41,7 +60,7 @@ impl ShopCustomerAddressRepository {
             .bind(&address.sync_status)
             .bind(&address.created_at)
             .bind(&address.updated_at)
-            .fetch_one(&*self.pool)
+            .fetch_one(executor)
             .await
     }
 
@@ -88,6 +107,25 @@ impl ShopCustomerAddressRepository {
     }
 
     pub async fn update(&self, address: &CustomerAddress) -> Result<CustomerAddress> {
+        self.update_with(&*self.pool, address).await
+    }
+
+    pub async fn update_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        address: &CustomerAddress,
+    ) -> Result<CustomerAddress> {
+        self.update_with(&mut **tx, address).await
+    }
+
+    async fn update_with<'e, E>(
+        &self,
+        executor: E,
+        address: &CustomerAddress,
+    ) -> Result<CustomerAddress>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
         let sql = r#"
             UPDATE customer_addresses SET
                 customer_id = $2,
@@ -127,7 +165,7 @@ impl ShopCustomerAddressRepository {
             .bind(&address.metadata)
             .bind(&address.sync_status)
             .bind(&address.updated_at)
-            .fetch_one(&*self.pool)
+            .fetch_one(executor)
             .await
     }
 
@@ -160,6 +198,18 @@ impl ShopCustomerAddressRepository {
         Ok(())
     }
 
+    /// Soft-delete an address, returning its customer
+    pub async fn delete_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+    ) -> Result<Option<String>> {
+        let sql = "UPDATE customer_addresses SET _status = 'deleted', updated_at = datetime('now') WHERE id = $1 RETURNING customer_id";
+        sqlx::query_scalar::<_, String>(sql)
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
     pub async fn delete_by_customer_id(&self, customer_id: &str) -> Result<()> {
         let sql = "UPDATE customer_addresses SET _status = 'deleted', updated_at = datetime('now') WHERE customer_id = $1";
         sqlx::query(sql)
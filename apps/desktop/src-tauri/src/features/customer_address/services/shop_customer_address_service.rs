@@ -2,6 +2,7 @@
 
 use crate::features::customer::models::customer_model::CustomerAddress;
 use crate::features::customer_address::repositories::shop_customer_address_repository::ShopCustomerAddressRepository;
+use crate::features::customer_group::services::shop_customer_segment_service::ShopCustomerSegmentService;
 use sqlx::SqlitePool;
 use std::sync::Arc;
 
@@ -20,11 +21,27 @@ impl ShopCustomerAddressService {
         self.pool.clone()
     }
 
+    /// Addresses feed the city/state rules of dynamic groups, so their
+    /// writes refresh the customer's memberships in the same transaction.
     pub async fn create_address(&self, address: &CustomerAddress) -> Result<CustomerAddress, String> {
-        self.repo
-            .create(address)
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let created = self
+            .repo
+            .create_in_tx(&mut tx, address)
+            .await
+            .map_err(|e| format!("Failed to create customer address: {}", e))?;
+        ShopCustomerSegmentService::refresh_customer_in_tx(&mut tx, &created.customer_id).await?;
+
+        tx.commit()
             .await
-            .map_err(|e| format!("Failed to create customer address: {}", e))
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(created)
     }
 
     pub async fn create_many(&self, addresses: Vec<CustomerAddress>) -> Result<Vec<CustomerAddress>, String> {
@@ -35,10 +52,24 @@ impl ShopCustomerAddressService {
     }
 
     pub async fn update_address(&self, address: &CustomerAddress) -> Result<CustomerAddress, String> {
-        self.repo
-            .update(address)
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let updated = self
+            .repo
+            .update_in_tx(&mut tx, address)
             .await
-            .map_err(|e| format!("Failed to update customer address: {}", e))
+            .map_err(|e| format!("Failed to update customer address: {}", e))?;
+        ShopCustomerSegmentService::refresh_customer_in_tx(&mut tx, &updated.customer_id).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(updated)
     }
 
     pub async fn get_address(&self, id: &str) -> Result<Option<CustomerAddress>, String> {
@@ -63,10 +94,22 @@ impl ShopCustomerAddressService {
     }
 
     pub async fn delete_address(&self, id: &str) -> Result<(), String> {
-        self.repo
-            .delete(id)
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let customer_id = ShopCustomerAddressRepository::delete_in_tx(&mut tx, id)
+            .await
+            .map_err(|e| format!("Failed to delete customer address: {}", e))?;
+        if let Some(customer_id) = customer_id {
+            ShopCustomerSegmentService::refresh_customer_in_tx(&mut tx, &customer_id).await?;
+        }
+
+        tx.commit()
             .await
-            .map_err(|e| format!("Failed to delete customer address: {}", e))
+            .map_err(|e| format!("Failed to commit transaction: {}", e))
     }
 
     pub async fn delete_by_customer(&self, customer_id: &str) -> Result<(), String> {
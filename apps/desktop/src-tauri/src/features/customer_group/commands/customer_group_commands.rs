@@ -1,9 +1,13 @@
 use crate::db::RepositoryFactory;
 use crate::features::customer_group::dtos::customer_group_dto::{
-    CreateCustomerGroupDTO, UpdateCustomerGroupDTO,
+    CreateCustomerGroupDTO, PreviewCustomerGroupRulesDTO, UpdateCustomerGroupDTO,
 };
 use crate::features::customer_group::models::customer_group_model::CustomerGroup;
+use crate::features::customer_group::models::customer_segment_model::{
+    CustomerGroupPreview, CustomerGroupRefresh,
+};
 use crate::features::customer_group::services::shop_customer_group_service::ShopCustomerGroupService;
+use crate::features::customer_group::services::shop_customer_segment_service::ShopCustomerSegmentService;
 use std::sync::Arc;
 use tauri::State;
 
@@ -97,3 +101,47 @@ pub async fn list_customer_groups_by_shop(
     let service = ShopCustomerGroupService::new(pool, shop_id);
     service.list_groups().await
 }
+
+#[tauri::command]
+pub async fn preview_customer_group_rules(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    payload: PreviewCustomerGroupRulesDTO,
+) -> Result<CustomerGroupPreview, String> {
+    let shop_id = payload.shop_id.clone();
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopCustomerSegmentService::new(pool, shop_id);
+    service.preview(payload).await
+}
+
+#[tauri::command]
+pub async fn refresh_customer_group(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+) -> Result<CustomerGroupRefresh, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopCustomerSegmentService::new(pool, shop_id);
+    service.refresh_group(&id).await
+}
+
+#[tauri::command]
+pub async fn refresh_customer_groups(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+) -> Result<Vec<CustomerGroupRefresh>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopCustomerSegmentService::new(pool, shop_id);
+    service.refresh_all().await
+}
@@ -107,3 +107,12 @@ impl UpdateCustomerGroupDTO {
         group
     }
 }
+
+/// Rules to try out on a shop's customers. Without `rules` the group's
+/// saved rules are used; without `group_id` everyone matching is "joining".
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreviewCustomerGroupRulesDTO {
+    pub shop_id: String,
+    pub group_id: Option<String>,
+    pub rules: Option<String>,
+}
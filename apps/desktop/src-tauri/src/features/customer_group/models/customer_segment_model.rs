@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashSet;
+
+/// Customer attribute a segment rule looks at
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentField {
+    TotalSpent,
+    OrdersCount,
+    LastOrderAt,
+    Tags,
+    City,
+    State,
+    AcceptsMarketing,
+    PurchasedCategory,
+    PurchasedBrand,
+    RfmSegment,
+    ChurnProbability,
+    PredictedClv,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentOperator {
+    Eq,
+    Neq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Between,
+    In,
+    NotIn,
+    Contains,
+    NotContains,
+    ContainsAll,
+    WithinDays,
+    NotWithinDays,
+    Never,
+}
+
+/// One condition of a dynamic customer group. `customer_groups.rules` holds
+/// a JSON array of these; a customer belongs to the group when all hold.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SegmentRule {
+    pub field: SegmentField,
+    pub operator: SegmentOperator,
+    #[serde(default)]
+    pub value: serde_json::Value,
+}
+
+/// What the rules are evaluated against, one per live customer. Text sets
+/// (tags, cities, states) are trimmed and lowercased; purchased categories
+/// include the ancestors of the categories actually bought. The RFM fields
+/// come from `customer_metrics` and are empty until metrics are computed.
+#[derive(Debug, Clone, Default)]
+pub struct CustomerSegmentFacts {
+    pub customer_id: String,
+    pub total_spent: f64,
+    pub orders_count: i64,
+    pub last_order_at: Option<DateTime<Utc>>,
+    pub accepts_marketing: bool,
+    pub tags: HashSet<String>,
+    pub cities: HashSet<String>,
+    pub states: HashSet<String>,
+    pub category_ids: HashSet<String>,
+    pub brand_ids: HashSet<String>,
+    pub rfm_segment: Option<String>,
+    pub churn_probability: Option<f64>,
+    pub predicted_clv: Option<f64>,
+}
+
+/// Customer listed in a segment preview
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct SegmentCustomer {
+    pub id: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub company_name: Option<String>,
+    pub email: Option<String>,
+}
+
+/// Who would join or leave a group if its rules were applied now
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CustomerGroupPreview {
+    pub group_id: Option<String>,
+    pub matched_count: i64,
+    pub unchanged_count: i64,
+    pub joining: Vec<SegmentCustomer>,
+    pub leaving: Vec<SegmentCustomer>,
+}
+
+/// Outcome of recomputing a dynamic group's memberships
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CustomerGroupRefresh {
+    pub group_id: String,
+    pub member_count: i64,
+    pub joined: i64,
+    pub left: i64,
+}
@@ -1 +1,2 @@
 pub mod customer_group_model;
+pub mod customer_segment_model;
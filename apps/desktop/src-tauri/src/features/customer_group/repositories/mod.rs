@@ -1,2 +1,3 @@
 pub mod customer_groups_repository;
 pub mod shop_customer_group_repository;
+pub mod shop_customer_segment_repository;
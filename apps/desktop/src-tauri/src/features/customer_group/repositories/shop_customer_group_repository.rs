@@ -3,7 +3,7 @@
 use crate::features::customer_group::models::customer_group_model::CustomerGroup;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, Result, SqlitePool};
+use sqlx::{Executor, FromRow, Result, Sqlite, SqlitePool, Transaction};
 use std::sync::Arc;
 
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
@@ -63,6 +63,21 @@ impl ShopCustomerGroupRepository {
     }
 
     pub async fn create(&self, group: &CustomerGroup) -> Result<CustomerGroup> {
+        self.create_with(&*self.pool, group).await
+    }
+
+    pub async fn create_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        group: &CustomerGroup,
+    ) -> Result<CustomerGroup> {
+        self.create_with(&mut **tx, group).await
+    }
+
+    async fn create_with<'e, E>(&self, executor: E, group: &CustomerGroup) -> Result<CustomerGroup>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
         let sql = r#"
             INSERT INTO customer_groups (
                 id, name, code, description, type, rules,
@@ -91,13 +106,28 @@ impl ShopCustomerGroupRepository {
             .bind(&group.sync_status)
             .bind(&group.created_at)
             .bind(&group.updated_at)
-            .fetch_one(&*self.pool)
+            .fetch_one(executor)
             .await?;
 
         Ok(shop_group.into_customer_group(self.shop_id.clone()))
     }
 
     pub async fn update(&self, group: &CustomerGroup) -> Result<CustomerGroup> {
+        self.update_with(&*self.pool, group).await
+    }
+
+    pub async fn update_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        group: &CustomerGroup,
+    ) -> Result<CustomerGroup> {
+        self.update_with(&mut **tx, group).await
+    }
+
+    async fn update_with<'e, E>(&self, executor: E, group: &CustomerGroup) -> Result<CustomerGroup>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
         let sql = r#"
             UPDATE customer_groups SET
                 name = $2,
@@ -130,7 +160,7 @@ impl ShopCustomerGroupRepository {
             .bind(&group.allowed_payment_methods)
             .bind(&group.min_order_amount)
             .bind(&group.metadata)
-            .fetch_one(&*self.pool)
+            .fetch_one(executor)
             .await?;
 
         Ok(shop_group.into_customer_group(self.shop_id.clone()))
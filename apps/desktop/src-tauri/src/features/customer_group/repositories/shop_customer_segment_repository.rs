@@ -0,0 +1,295 @@
+//! Shop-scoped queries behind dynamic customer groups
+//!
+//! Everything runs inside the caller's transaction so that a membership
+//! refresh commits (or rolls back) together with the change that caused it.
+
+use crate::features::category::repositories::shop_category_repository::CATEGORY_CLOSURE_CTE;
+use crate::features::customer_group::models::customer_segment_model::SegmentCustomer;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, QueryBuilder, Result, Sqlite, Transaction};
+
+#[derive(Debug, FromRow, Clone)]
+pub struct SegmentGroupRow {
+    pub id: String,
+    #[sqlx(rename = "type")]
+    pub r#type: Option<String>,
+    pub rules: Option<String>,
+}
+
+/// Customer columns the segment rules read
+#[derive(Debug, FromRow, Clone)]
+pub struct SegmentCustomerRow {
+    pub id: String,
+    pub total_spent: Option<f64>,
+    pub orders_count: Option<i64>,
+    pub last_order_at: Option<DateTime<Utc>>,
+    pub tags: Option<String>,
+    pub accepts_marketing: Option<bool>,
+    pub rfm_segment: Option<String>,
+    pub churn_probability: Option<f64>,
+    pub predicted_clv: Option<f64>,
+}
+
+#[derive(Debug, FromRow, Clone)]
+pub struct SegmentAddressRow {
+    pub customer_id: String,
+    pub city: Option<String>,
+    pub province_code: Option<String>,
+}
+
+/// `kind` is 'category' or 'brand'
+#[derive(Debug, FromRow, Clone)]
+pub struct SegmentPurchaseRow {
+    pub customer_id: String,
+    pub kind: String,
+    pub value: String,
+}
+
+pub struct ShopCustomerSegmentRepository;
+
+impl ShopCustomerSegmentRepository {
+    pub async fn get_group_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        group_id: &str,
+    ) -> Result<Option<SegmentGroupRow>> {
+        let sql = r#"
+            SELECT id, type, rules FROM customer_groups
+            WHERE id = $1 AND (_status IS NULL OR _status != 'deleted')
+        "#;
+        sqlx::query_as::<_, SegmentGroupRow>(sql)
+            .bind(group_id)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    pub async fn list_dynamic_groups_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+    ) -> Result<Vec<SegmentGroupRow>> {
+        let sql = r#"
+            SELECT id, type, rules FROM customer_groups
+            WHERE type = 'dynamic' AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY id
+        "#;
+        sqlx::query_as::<_, SegmentGroupRow>(sql)
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    /// Live customers ordered by id, `limit` at a time after `after_id`
+    pub async fn list_customers_page_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        after_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<SegmentCustomerRow>> {
+        let sql = r#"
+            SELECT
+                c.id, c.total_spent, c.orders_count, c.last_order_at, c.tags, c.accepts_marketing,
+                m.rfm_segment, m.churn_probability, m.predicted_clv
+            FROM customers c
+            LEFT JOIN customer_metrics m ON m.customer_id = c.id
+            WHERE (c._status IS NULL OR c._status != 'deleted') AND ($1 IS NULL OR c.id > $1)
+            ORDER BY c.id
+            LIMIT $2
+        "#;
+        sqlx::query_as::<_, SegmentCustomerRow>(sql)
+            .bind(after_id)
+            .bind(limit)
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    pub async fn get_customer_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        customer_id: &str,
+    ) -> Result<Option<SegmentCustomerRow>> {
+        let sql = r#"
+            SELECT
+                c.id, c.total_spent, c.orders_count, c.last_order_at, c.tags, c.accepts_marketing,
+                m.rfm_segment, m.churn_probability, m.predicted_clv
+            FROM customers c
+            LEFT JOIN customer_metrics m ON m.customer_id = c.id
+            WHERE c.id = $1 AND (c._status IS NULL OR c._status != 'deleted')
+        "#;
+        sqlx::query_as::<_, SegmentCustomerRow>(sql)
+            .bind(customer_id)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    pub async fn list_addresses_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        customer_ids: &[String],
+    ) -> Result<Vec<SegmentAddressRow>> {
+        if customer_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT customer_id, city, province_code FROM customer_addresses
+            WHERE (_status IS NULL OR _status != 'deleted') AND customer_id IN (",
+        );
+        let mut separated = builder.separated(", ");
+        for id in customer_ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(")");
+
+        builder
+            .build_query_as::<SegmentAddressRow>()
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    /// Categories (with their ancestors) and brands of everything the
+    /// customers bought in completed sales. A variant counts for its parent
+    /// product's categories and brand as well as its own.
+    pub async fn list_purchases_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        customer_ids: &[String],
+    ) -> Result<Vec<SegmentPurchaseRow>> {
+        if customer_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut builder = QueryBuilder::<Sqlite>::new(format!(
+            r#"
+            WITH RECURSIVE {},
+            purchased_items AS (
+                SELECT DISTINCT t.customer_id, ti.product_id
+                FROM transactions t
+                INNER JOIN transaction_items ti ON ti.transaction_id = t.id
+                    AND (ti._status IS NULL OR ti._status != 'deleted')
+                WHERE t.type = 'sale' AND t.status = 'completed'
+                  AND (t._status IS NULL OR t._status != 'deleted')
+                  AND ti.product_id IS NOT NULL
+                  AND t.customer_id IN ("#,
+            CATEGORY_CLOSURE_CTE
+        ));
+        let mut separated = builder.separated(", ");
+        for id in customer_ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(
+            r#")
+            ),
+            purchased AS (
+                SELECT customer_id, product_id FROM purchased_items
+                UNION
+                SELECT pi.customer_id, p.parent_id
+                FROM purchased_items pi
+                INNER JOIN products p ON p.id = pi.product_id
+                WHERE p.parent_id IS NOT NULL
+            ),
+            product_taxonomy AS (
+                SELECT id AS product_id, category_id FROM products WHERE category_id IS NOT NULL
+                UNION
+                SELECT product_id, category_id FROM product_categories
+                WHERE _status IS NULL OR _status != 'deleted'
+            )
+            SELECT DISTINCT pu.customer_id, 'category' AS kind, cc.ancestor_id AS value
+            FROM purchased pu
+            INNER JOIN product_taxonomy pt ON pt.product_id = pu.product_id
+            INNER JOIN category_closure cc ON cc.category_id = pt.category_id
+            UNION
+            SELECT pu.customer_id, 'brand' AS kind, p.brand_id AS value
+            FROM purchased pu
+            INNER JOIN products p ON p.id = pu.product_id
+            WHERE p.brand_id IS NOT NULL
+            "#,
+        );
+
+        builder
+            .build_query_as::<SegmentPurchaseRow>()
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    pub async fn list_member_ids_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        group_id: &str,
+    ) -> Result<Vec<String>> {
+        let sql = r#"
+            SELECT customer_id FROM customer_group_memberships
+            WHERE customer_group_id = $1 AND (_status IS NULL OR _status != 'deleted')
+        "#;
+        sqlx::query_scalar::<_, String>(sql)
+            .bind(group_id)
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    pub async fn list_customer_group_ids_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        customer_id: &str,
+    ) -> Result<Vec<String>> {
+        let sql = r#"
+            SELECT customer_group_id FROM customer_group_memberships
+            WHERE customer_id = $1 AND (_status IS NULL OR _status != 'deleted')
+        "#;
+        sqlx::query_scalar::<_, String>(sql)
+            .bind(customer_id)
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    /// Adds the membership, reviving it if it was soft-deleted
+    pub async fn add_member_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        group_id: &str,
+        customer_id: &str,
+    ) -> Result<()> {
+        let sql = r#"
+            INSERT INTO customer_group_memberships (
+                customer_id, customer_group_id, _status, created_at, updated_at
+            ) VALUES ($1, $2, 'created', datetime('now'), datetime('now'))
+            ON CONFLICT (customer_id, customer_group_id) DO UPDATE SET
+                _status = 'modified', updated_at = datetime('now')
+        "#;
+        sqlx::query(sql)
+            .bind(customer_id)
+            .bind(group_id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_member_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        group_id: &str,
+        customer_id: &str,
+    ) -> Result<()> {
+        let sql = r#"
+            UPDATE customer_group_memberships SET _status = 'deleted', updated_at = datetime('now')
+            WHERE customer_id = $1 AND customer_group_id = $2
+        "#;
+        sqlx::query(sql)
+            .bind(customer_id)
+            .bind(group_id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn list_segment_customers_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        customer_ids: &[String],
+    ) -> Result<Vec<SegmentCustomer>> {
+        if customer_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT id, first_name, last_name, company_name, email FROM customers WHERE id IN (",
+        );
+        let mut separated = builder.separated(", ");
+        for id in customer_ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(") ORDER BY first_name, last_name, id");
+
+        builder
+            .build_query_as::<SegmentCustomer>()
+            .fetch_all(&mut **tx)
+            .await
+    }
+}
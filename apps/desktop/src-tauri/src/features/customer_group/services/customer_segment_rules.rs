@@ -0,0 +1,330 @@
+//! Rule engine for dynamic customer groups
+//!
+//! `customer_groups.rules` is parsed and validated into a `CustomerSegment`
+//! once, which then tells from a customer's facts whether they belong.
+
+use crate::features::customer_group::models::customer_segment_model::{
+    CustomerSegmentFacts, SegmentField, SegmentOperator, SegmentRule,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+use std::collections::HashSet;
+
+pub const DYNAMIC_GROUP_TYPE: &str = "dynamic";
+
+enum NumberTest {
+    Eq(f64),
+    Neq(f64),
+    Gt(f64),
+    Gte(f64),
+    Lt(f64),
+    Lte(f64),
+    Between(f64, f64),
+}
+
+impl NumberTest {
+    fn holds(&self, value: f64) -> bool {
+        match *self {
+            NumberTest::Eq(n) => (value - n).abs() < 1e-9,
+            NumberTest::Neq(n) => (value - n).abs() >= 1e-9,
+            NumberTest::Gt(n) => value > n,
+            NumberTest::Gte(n) => value >= n,
+            NumberTest::Lt(n) => value < n,
+            NumberTest::Lte(n) => value <= n,
+            NumberTest::Between(min, max) => value >= min && value <= max,
+        }
+    }
+}
+
+enum SetTest {
+    Any(HashSet<String>),
+    All(HashSet<String>),
+    None(HashSet<String>),
+}
+
+impl SetTest {
+    fn holds(&self, values: &HashSet<String>) -> bool {
+        match self {
+            SetTest::Any(wanted) => !wanted.is_disjoint(values),
+            SetTest::All(wanted) => wanted.is_subset(values),
+            SetTest::None(wanted) => wanted.is_disjoint(values),
+        }
+    }
+}
+
+enum Condition {
+    TotalSpent(NumberTest),
+    OrdersCount(NumberTest),
+    OrderedWithinDays(i64),
+    NotOrderedWithinDays(i64),
+    NeverOrdered,
+    AcceptsMarketing(bool),
+    Tags(SetTest),
+    City(SetTest),
+    State(SetTest),
+    PurchasedCategory(SetTest),
+    PurchasedBrand(SetTest),
+    RfmSegment(SetTest),
+    ChurnProbability(NumberTest),
+    PredictedClv(NumberTest),
+}
+
+/// Validated rules of a dynamic group; a customer matches when every rule
+/// holds.
+pub struct CustomerSegment {
+    conditions: Vec<Condition>,
+}
+
+impl CustomerSegment {
+    /// Parse the JSON stored in `customer_groups.rules`
+    pub fn parse(rules: Option<&str>) -> Result<Self, String> {
+        let rules: Vec<SegmentRule> = serde_json::from_str(rules.unwrap_or("[]"))
+            .map_err(|e| format!("Invalid customer group rules: {}", e))?;
+        Self::compile(&rules)
+    }
+
+    pub fn compile(rules: &[SegmentRule]) -> Result<Self, String> {
+        if rules.is_empty() {
+            return Err("A dynamic customer group needs at least one rule".to_string());
+        }
+
+        let conditions = rules
+            .iter()
+            .enumerate()
+            .map(|(i, rule)| compile_rule(rule).map_err(|e| format!("Rule {}: {}", i + 1, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { conditions })
+    }
+
+    /// Whether addresses must be loaded into the facts
+    pub fn uses_addresses(&self) -> bool {
+        self.conditions
+            .iter()
+            .any(|c| matches!(c, Condition::City(_) | Condition::State(_)))
+    }
+
+    /// Whether purchased categories and brands must be loaded into the facts
+    pub fn uses_purchases(&self) -> bool {
+        self.conditions.iter().any(|c| {
+            matches!(
+                c,
+                Condition::PurchasedCategory(_) | Condition::PurchasedBrand(_)
+            )
+        })
+    }
+
+    pub fn matches(&self, facts: &CustomerSegmentFacts, now: DateTime<Utc>) -> bool {
+        self.conditions.iter().all(|condition| match condition {
+            Condition::TotalSpent(test) => test.holds(facts.total_spent),
+            Condition::OrdersCount(test) => test.holds(facts.orders_count as f64),
+            Condition::OrderedWithinDays(days) => facts
+                .last_order_at
+                .is_some_and(|at| at >= now - Duration::days(*days)),
+            Condition::NotOrderedWithinDays(days) => facts
+                .last_order_at
+                .is_none_or(|at| at < now - Duration::days(*days)),
+            Condition::NeverOrdered => facts.last_order_at.is_none(),
+            Condition::AcceptsMarketing(wanted) => facts.accepts_marketing == *wanted,
+            Condition::Tags(test) => test.holds(&facts.tags),
+            Condition::City(test) => test.holds(&facts.cities),
+            Condition::State(test) => test.holds(&facts.states),
+            Condition::PurchasedCategory(test) => test.holds(&facts.category_ids),
+            Condition::PurchasedBrand(test) => test.holds(&facts.brand_ids),
+            Condition::RfmSegment(test) => match &facts.rfm_segment {
+                Some(segment) => test.holds(&HashSet::from([segment.clone()])),
+                None => false,
+            },
+            Condition::ChurnProbability(test) => {
+                facts.churn_probability.is_some_and(|p| test.holds(p))
+            }
+            Condition::PredictedClv(test) => facts.predicted_clv.is_some_and(|v| test.holds(v)),
+        })
+    }
+}
+
+/// Trimmed, lowercased form used for tags, cities and states
+pub fn normalize_text(value: &str) -> String {
+    value.trim().to_lowercase()
+}
+
+/// `customers.tags` is free text: a JSON array, a Postgres array literal or
+/// a comma-separated list are all accepted.
+pub fn parse_tags(raw: Option<&str>) -> HashSet<String> {
+    let Some(raw) = raw.map(str::trim).filter(|r| !r.is_empty()) else {
+        return HashSet::new();
+    };
+
+    if let Ok(tags) = serde_json::from_str::<Vec<String>>(raw) {
+        return tags
+            .iter()
+            .map(|t| normalize_text(t))
+            .filter(|t| !t.is_empty())
+            .collect();
+    }
+
+    raw.trim_start_matches('{')
+        .trim_end_matches('}')
+        .split(',')
+        .map(|t| normalize_text(t.trim().trim_matches('"')))
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+fn compile_rule(rule: &SegmentRule) -> Result<Condition, String> {
+    use SegmentOperator as Op;
+
+    let unsupported = || {
+        Err(format!(
+            "{} does not support the {} operator",
+            label(&rule.field),
+            label(&rule.operator)
+        ))
+    };
+
+    match rule.field {
+        SegmentField::TotalSpent => number_test(rule).map(Condition::TotalSpent),
+        SegmentField::OrdersCount => number_test(rule).map(Condition::OrdersCount),
+        SegmentField::LastOrderAt => match rule.operator {
+            Op::WithinDays => days(&rule.value).map(Condition::OrderedWithinDays),
+            Op::NotWithinDays => days(&rule.value).map(Condition::NotOrderedWithinDays),
+            Op::Never => Ok(Condition::NeverOrdered),
+            _ => unsupported(),
+        },
+        SegmentField::AcceptsMarketing => {
+            let wanted = rule
+                .value
+                .as_bool()
+                .ok_or_else(|| "accepts_marketing expects true or false".to_string())?;
+            match rule.operator {
+                Op::Eq => Ok(Condition::AcceptsMarketing(wanted)),
+                Op::Neq => Ok(Condition::AcceptsMarketing(!wanted)),
+                _ => unsupported(),
+            }
+        }
+        SegmentField::Tags => {
+            let values = text_values(&rule.value, true)?;
+            match rule.operator {
+                Op::Contains | Op::In => Ok(Condition::Tags(SetTest::Any(values))),
+                Op::ContainsAll => Ok(Condition::Tags(SetTest::All(values))),
+                Op::NotContains | Op::NotIn => Ok(Condition::Tags(SetTest::None(values))),
+                _ => unsupported(),
+            }
+        }
+        SegmentField::City | SegmentField::State => {
+            let values = text_values(&rule.value, true)?;
+            let test = match rule.operator {
+                Op::Eq | Op::In => SetTest::Any(values),
+                Op::Neq | Op::NotIn => SetTest::None(values),
+                _ => return unsupported(),
+            };
+            Ok(if rule.field == SegmentField::City {
+                Condition::City(test)
+            } else {
+                Condition::State(test)
+            })
+        }
+        SegmentField::PurchasedCategory | SegmentField::PurchasedBrand => {
+            let values = text_values(&rule.value, false)?;
+            let test = match rule.operator {
+                Op::In | Op::Contains => SetTest::Any(values),
+                Op::ContainsAll => SetTest::All(values),
+                Op::NotIn | Op::NotContains => SetTest::None(values),
+                _ => return unsupported(),
+            };
+            Ok(if rule.field == SegmentField::PurchasedCategory {
+                Condition::PurchasedCategory(test)
+            } else {
+                Condition::PurchasedBrand(test)
+            })
+        }
+        SegmentField::RfmSegment => {
+            let values = text_values(&rule.value, true)?;
+            match rule.operator {
+                Op::Eq | Op::In => Ok(Condition::RfmSegment(SetTest::Any(values))),
+                Op::Neq | Op::NotIn => Ok(Condition::RfmSegment(SetTest::None(values))),
+                _ => unsupported(),
+            }
+        }
+        SegmentField::ChurnProbability => number_test(rule).map(Condition::ChurnProbability),
+        SegmentField::PredictedClv => number_test(rule).map(Condition::PredictedClv),
+    }
+}
+
+fn number_test(rule: &SegmentRule) -> Result<NumberTest, String> {
+    let number = |value: &Value| {
+        value
+            .as_f64()
+            .ok_or_else(|| format!("{} expects a number", label(&rule.field)))
+    };
+
+    Ok(match rule.operator {
+        SegmentOperator::Eq => NumberTest::Eq(number(&rule.value)?),
+        SegmentOperator::Neq => NumberTest::Neq(number(&rule.value)?),
+        SegmentOperator::Gt => NumberTest::Gt(number(&rule.value)?),
+        SegmentOperator::Gte => NumberTest::Gte(number(&rule.value)?),
+        SegmentOperator::Lt => NumberTest::Lt(number(&rule.value)?),
+        SegmentOperator::Lte => NumberTest::Lte(number(&rule.value)?),
+        SegmentOperator::Between => {
+            let bounds = rule
+                .value
+                .as_array()
+                .filter(|b| b.len() == 2)
+                .ok_or_else(|| "between expects [min, max]".to_string())?;
+            let (min, max) = (number(&bounds[0])?, number(&bounds[1])?);
+            if min > max {
+                return Err("between expects min <= max".to_string());
+            }
+            NumberTest::Between(min, max)
+        }
+        _ => {
+            return Err(format!(
+                "{} does not support the {} operator",
+                label(&rule.field),
+                label(&rule.operator)
+            ))
+        }
+    })
+}
+
+fn days(value: &Value) -> Result<i64, String> {
+    value
+        .as_i64()
+        .filter(|d| *d > 0)
+        .ok_or_else(|| "last_order_at expects a positive number of days".to_string())
+}
+
+/// A string or a non-empty array of strings
+fn text_values(value: &Value, normalize: bool) -> Result<HashSet<String>, String> {
+    let raw: Vec<&str> = match value {
+        Value::String(s) => vec![s.as_str()],
+        Value::Array(items) => items
+            .iter()
+            .map(|item| item.as_str().ok_or("expected a list of strings"))
+            .collect::<Result<_, _>>()?,
+        _ => return Err("expected a string or a list of strings".to_string()),
+    };
+
+    let values: HashSet<String> = raw
+        .into_iter()
+        .map(|v| {
+            if normalize {
+                normalize_text(v)
+            } else {
+                v.trim().to_string()
+            }
+        })
+        .filter(|v| !v.is_empty())
+        .collect();
+    if values.is_empty() {
+        return Err("expected at least one value".to_string());
+    }
+    Ok(values)
+}
+
+/// The snake_case name the frontend sends
+fn label<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
@@ -0,0 +1,30 @@
+//! Background job recomputing dynamic customer groups
+//!
+//! Started from the app setup; it runs `ShopCustomerSegmentService::refresh_all`
+//! when a shop's database opens and then every hour while it stays open
+//! (see `run_for_open_shops`). Events keep memberships current between
+//! passes; this catches what only time changes, such as "ordered within 30
+//! days".
+
+use crate::db::RepositoryFactory;
+use crate::features::customer_group::services::shop_customer_segment_service::ShopCustomerSegmentService;
+use crate::features::shop::services::shop_scheduler::run_for_open_shops;
+use std::sync::Arc;
+use std::time::Duration;
+
+const CUSTOMER_SEGMENT_INTERVAL: Duration = Duration::from_secs(3600);
+
+pub async fn run_customer_segment_scheduler(repo_factory: Arc<RepositoryFactory>) {
+    run_for_open_shops(
+        repo_factory,
+        CUSTOMER_SEGMENT_INTERVAL,
+        "customer_segments",
+        |shop_id, pool| async move {
+            ShopCustomerSegmentService::new(pool, shop_id)
+                .refresh_all()
+                .await
+                .map(|_| ())
+        },
+    )
+    .await
+}
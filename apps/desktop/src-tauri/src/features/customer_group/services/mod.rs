@@ -1,2 +1,5 @@
 pub mod customer_group_service;
+pub mod customer_segment_rules;
+pub mod customer_segment_scheduler;
 pub mod shop_customer_group_service;
+pub mod shop_customer_segment_service;
@@ -5,9 +5,21 @@ use crate::features::customer_group::dtos::customer_group_dto::{
 };
 use crate::features::customer_group::models::customer_group_model::CustomerGroup;
 use crate::features::customer_group::repositories::shop_customer_group_repository::ShopCustomerGroupRepository;
+use crate::features::customer_group::services::customer_segment_rules::{
+    CustomerSegment, DYNAMIC_GROUP_TYPE,
+};
+use crate::features::customer_group::services::shop_customer_segment_service::ShopCustomerSegmentService;
 use sqlx::SqlitePool;
 use std::sync::Arc;
 
+/// Validated rules of a dynamic group; None for manual groups
+fn dynamic_segment(group: &CustomerGroup) -> Result<Option<CustomerSegment>, String> {
+    if group.r#type.as_deref() != Some(DYNAMIC_GROUP_TYPE) {
+        return Ok(None);
+    }
+    CustomerSegment::parse(group.rules.as_deref()).map(Some)
+}
+
 pub struct ShopCustomerGroupService {
     pool: Arc<SqlitePool>,
     shop_id: String,
@@ -28,15 +40,40 @@ impl ShopCustomerGroupService {
         &self.shop_id
     }
 
-    pub async fn create_group(&self, payload: CreateCustomerGroupDTO) -> Result<CustomerGroup, String> {
+    pub async fn create_group(
+        &self,
+        payload: CreateCustomerGroupDTO,
+    ) -> Result<CustomerGroup, String> {
         let group = payload.into_model();
-        self.repo
-            .create(&group)
+        let segment = dynamic_segment(&group)?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let created = self
+            .repo
+            .create_in_tx(&mut tx, &group)
+            .await
+            .map_err(|e| format!("Failed to create customer group: {}", e))?;
+
+        if let Some(segment) = segment {
+            ShopCustomerSegmentService::refresh_group_in_tx(&mut tx, &created.id, &segment).await?;
+        }
+
+        tx.commit()
             .await
-            .map_err(|e| format!("Failed to create customer group: {}", e))
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(created)
     }
 
-    pub async fn update_group(&self, payload: UpdateCustomerGroupDTO) -> Result<CustomerGroup, String> {
+    pub async fn update_group(
+        &self,
+        payload: UpdateCustomerGroupDTO,
+    ) -> Result<CustomerGroup, String> {
         let existing = self
             .repo
             .get_by_id(&payload.id)
@@ -45,10 +82,30 @@ impl ShopCustomerGroupService {
             .ok_or_else(|| format!("Customer group not found: {}", payload.id))?;
 
         let updated = payload.apply_to_model(existing);
-        self.repo
-            .update(&updated)
+        let segment = dynamic_segment(&updated)?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let saved = self
+            .repo
+            .update_in_tx(&mut tx, &updated)
+            .await
+            .map_err(|e| format!("Failed to update customer group: {}", e))?;
+
+        // Members of a group turned manual stay; they are just no longer managed
+        if let Some(segment) = segment {
+            ShopCustomerSegmentService::refresh_group_in_tx(&mut tx, &saved.id, &segment).await?;
+        }
+
+        tx.commit()
             .await
-            .map_err(|e| format!("Failed to update customer group: {}", e))
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(saved)
     }
 
     pub async fn delete_group(&self, id: &str) -> Result<(), String> {
@@ -0,0 +1,393 @@
+//! Shop-scoped membership engine for dynamic customer groups
+//!
+//! Groups of type 'dynamic' own their memberships: they are recomputed from
+//! the group's rules when the group is saved, for a single customer inside
+//! the transaction of any change that can move them in or out (sale, return,
+//! profile or address edit), and for every customer on a schedule so that
+//! recency rules follow the calendar.
+
+use crate::features::customer_group::dtos::customer_group_dto::PreviewCustomerGroupRulesDTO;
+use crate::features::customer_group::models::customer_segment_model::{
+    CustomerGroupPreview, CustomerGroupRefresh, CustomerSegmentFacts,
+};
+use crate::features::customer_group::repositories::shop_customer_segment_repository::{
+    SegmentCustomerRow, ShopCustomerSegmentRepository,
+};
+use crate::features::customer_group::services::customer_segment_rules::{
+    normalize_text, parse_tags, CustomerSegment, DYNAMIC_GROUP_TYPE,
+};
+use chrono::Utc;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Customers evaluated per query round-trip during a full pass
+const SEGMENT_PAGE_SIZE: i64 = 500;
+
+pub struct ShopCustomerSegmentService {
+    pool: Arc<SqlitePool>,
+    shop_id: String,
+}
+
+impl ShopCustomerSegmentService {
+    pub fn new(pool: Arc<SqlitePool>, shop_id: String) -> Self {
+        Self { pool, shop_id }
+    }
+
+    pub fn shop_id(&self) -> &str {
+        &self.shop_id
+    }
+
+    /// Who would join or leave the group under the given (or saved) rules.
+    /// Nothing is written.
+    pub async fn preview(
+        &self,
+        payload: PreviewCustomerGroupRulesDTO,
+    ) -> Result<CustomerGroupPreview, String> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let mut saved_rules = None;
+        let mut current = HashSet::new();
+        if let Some(group_id) = &payload.group_id {
+            let group = ShopCustomerSegmentRepository::get_group_in_tx(&mut tx, group_id)
+                .await
+                .map_err(|e| format!("Failed to fetch customer group: {}", e))?
+                .ok_or_else(|| format!("Customer group not found: {}", group_id))?;
+            saved_rules = group.rules;
+            current = ShopCustomerSegmentRepository::list_member_ids_in_tx(&mut tx, group_id)
+                .await
+                .map_err(|e| format!("Failed to fetch group members: {}", e))?
+                .into_iter()
+                .collect();
+        }
+
+        let segment = CustomerSegment::parse(payload.rules.as_deref().or(saved_rules.as_deref()))?;
+        let matched = Self::evaluate_in_tx(&mut tx, &[&segment])
+            .await?
+            .pop()
+            .unwrap_or_default();
+
+        let joining: Vec<String> = matched.difference(&current).cloned().collect();
+        let leaving: Vec<String> = current.difference(&matched).cloned().collect();
+        let unchanged_count = matched.intersection(&current).count() as i64;
+
+        let mut preview = CustomerGroupPreview {
+            group_id: payload.group_id,
+            matched_count: matched.len() as i64,
+            unchanged_count,
+            joining: Vec::with_capacity(joining.len()),
+            leaving: Vec::with_capacity(leaving.len()),
+        };
+        for chunk in joining.chunks(SEGMENT_PAGE_SIZE as usize) {
+            preview.joining.extend(
+                ShopCustomerSegmentRepository::list_segment_customers_in_tx(&mut tx, chunk)
+                    .await
+                    .map_err(|e| format!("Failed to fetch customers: {}", e))?,
+            );
+        }
+        for chunk in leaving.chunks(SEGMENT_PAGE_SIZE as usize) {
+            preview.leaving.extend(
+                ShopCustomerSegmentRepository::list_segment_customers_in_tx(&mut tx, chunk)
+                    .await
+                    .map_err(|e| format!("Failed to fetch customers: {}", e))?,
+            );
+        }
+
+        Ok(preview)
+    }
+
+    /// Recompute the memberships of one dynamic group
+    pub async fn refresh_group(&self, group_id: &str) -> Result<CustomerGroupRefresh, String> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let group = ShopCustomerSegmentRepository::get_group_in_tx(&mut tx, group_id)
+            .await
+            .map_err(|e| format!("Failed to fetch customer group: {}", e))?
+            .ok_or_else(|| format!("Customer group not found: {}", group_id))?;
+        if group.r#type.as_deref() != Some(DYNAMIC_GROUP_TYPE) {
+            return Err(format!("Customer group {} is not dynamic", group_id));
+        }
+
+        let segment = CustomerSegment::parse(group.rules.as_deref())?;
+        let refresh = Self::refresh_group_in_tx(&mut tx, group_id, &segment).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(refresh)
+    }
+
+    /// Recompute every dynamic group of the shop in a single pass over the
+    /// customers. Groups whose stored rules no longer validate are skipped.
+    pub async fn refresh_all(&self) -> Result<Vec<CustomerGroupRefresh>, String> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let refreshes = Self::refresh_all_in_tx(&mut tx).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(refreshes)
+    }
+
+    /// Re-evaluate every valid dynamic group inside the caller's transaction
+    pub async fn refresh_all_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+    ) -> Result<Vec<CustomerGroupRefresh>, String> {
+        let groups = Self::dynamic_segments_in_tx(tx).await?;
+        if groups.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let segments: Vec<&CustomerSegment> = groups.iter().map(|(_, s)| s).collect();
+        let matches = Self::evaluate_in_tx(tx, &segments).await?;
+
+        let mut refreshes = Vec::with_capacity(groups.len());
+        for ((group_id, _), matched) in groups.iter().zip(matches) {
+            refreshes.push(Self::apply_in_tx(tx, group_id, &matched).await?);
+        }
+
+        Ok(refreshes)
+    }
+
+    /// Recompute one group's memberships within a transaction
+    pub async fn refresh_group_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        group_id: &str,
+        segment: &CustomerSegment,
+    ) -> Result<CustomerGroupRefresh, String> {
+        let matched = Self::evaluate_in_tx(tx, &[segment])
+            .await?
+            .pop()
+            .unwrap_or_default();
+        Self::apply_in_tx(tx, group_id, &matched).await
+    }
+
+    /// Move one customer in or out of every dynamic group within a
+    /// transaction. Deleted customers are left alone: their memberships are
+    /// removed with them.
+    pub async fn refresh_customer_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        customer_id: &str,
+    ) -> Result<(), String> {
+        let groups = Self::dynamic_segments_in_tx(tx).await?;
+        if groups.is_empty() {
+            return Ok(());
+        }
+
+        let Some(row) = ShopCustomerSegmentRepository::get_customer_in_tx(tx, customer_id)
+            .await
+            .map_err(|e| format!("Failed to fetch customer: {}", e))?
+        else {
+            return Ok(());
+        };
+
+        let segments: Vec<&CustomerSegment> = groups.iter().map(|(_, s)| s).collect();
+        let Some(facts) = Self::load_facts_in_tx(tx, vec![row], &segments)
+            .await?
+            .pop()
+        else {
+            return Ok(());
+        };
+
+        let current: HashSet<String> =
+            ShopCustomerSegmentRepository::list_customer_group_ids_in_tx(tx, customer_id)
+                .await
+                .map_err(|e| format!("Failed to fetch customer groups: {}", e))?
+                .into_iter()
+                .collect();
+
+        let now = Utc::now();
+        for (group_id, segment) in &groups {
+            match (segment.matches(&facts, now), current.contains(group_id)) {
+                (true, false) => {
+                    ShopCustomerSegmentRepository::add_member_in_tx(tx, group_id, customer_id)
+                        .await
+                        .map_err(|e| format!("Failed to add group member: {}", e))?
+                }
+                (false, true) => {
+                    ShopCustomerSegmentRepository::remove_member_in_tx(tx, group_id, customer_id)
+                        .await
+                        .map_err(|e| format!("Failed to remove group member: {}", e))?
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn dynamic_segments_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+    ) -> Result<Vec<(String, CustomerSegment)>, String> {
+        let groups = ShopCustomerSegmentRepository::list_dynamic_groups_in_tx(tx)
+            .await
+            .map_err(|e| format!("Failed to list dynamic customer groups: {}", e))?;
+
+        Ok(groups
+            .into_iter()
+            .filter_map(
+                |group| match CustomerSegment::parse(group.rules.as_deref()) {
+                    Ok(segment) => Some((group.id, segment)),
+                    Err(e) => {
+                        eprintln!("[customer_segments] group {} skipped: {}", group.id, e);
+                        None
+                    }
+                },
+            )
+            .collect())
+    }
+
+    /// Ids of the live customers matching each segment, in order
+    async fn evaluate_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        segments: &[&CustomerSegment],
+    ) -> Result<Vec<HashSet<String>>, String> {
+        let now = Utc::now();
+        let mut matched = vec![HashSet::new(); segments.len()];
+        let mut after_id: Option<String> = None;
+
+        loop {
+            let rows = ShopCustomerSegmentRepository::list_customers_page_in_tx(
+                tx,
+                after_id.as_deref(),
+                SEGMENT_PAGE_SIZE,
+            )
+            .await
+            .map_err(|e| format!("Failed to list customers: {}", e))?;
+            let Some(last) = rows.last() else {
+                break;
+            };
+            after_id = Some(last.id.clone());
+            let page_len = rows.len() as i64;
+
+            for facts in Self::load_facts_in_tx(tx, rows, segments).await? {
+                for (segment, members) in segments.iter().zip(matched.iter_mut()) {
+                    if segment.matches(&facts, now) {
+                        members.insert(facts.customer_id.clone());
+                    }
+                }
+            }
+
+            if page_len < SEGMENT_PAGE_SIZE {
+                break;
+            }
+        }
+
+        Ok(matched)
+    }
+
+    /// Facts of the given customers; addresses and purchases are only
+    /// queried when a segment needs them.
+    async fn load_facts_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        rows: Vec<SegmentCustomerRow>,
+        segments: &[&CustomerSegment],
+    ) -> Result<Vec<CustomerSegmentFacts>, String> {
+        let mut facts: HashMap<String, CustomerSegmentFacts> = rows
+            .into_iter()
+            .map(|row| {
+                let facts = CustomerSegmentFacts {
+                    customer_id: row.id.clone(),
+                    total_spent: row.total_spent.unwrap_or(0.0),
+                    orders_count: row.orders_count.unwrap_or(0),
+                    last_order_at: row.last_order_at,
+                    accepts_marketing: row.accepts_marketing.unwrap_or(false),
+                    tags: parse_tags(row.tags.as_deref()),
+                    rfm_segment: row.rfm_segment,
+                    churn_probability: row.churn_probability,
+                    predicted_clv: row.predicted_clv,
+                    ..Default::default()
+                };
+                (row.id, facts)
+            })
+            .collect();
+        let ids: Vec<String> = facts.keys().cloned().collect();
+
+        if segments.iter().any(|s| s.uses_addresses()) {
+            let addresses = ShopCustomerSegmentRepository::list_addresses_in_tx(tx, &ids)
+                .await
+                .map_err(|e| format!("Failed to list customer addresses: {}", e))?;
+            for address in addresses {
+                let Some(entry) = facts.get_mut(&address.customer_id) else {
+                    continue;
+                };
+                if let Some(city) = address.city.as_deref().map(normalize_text) {
+                    entry.cities.insert(city);
+                }
+                if let Some(state) = address.province_code.as_deref().map(normalize_text) {
+                    entry.states.insert(state);
+                }
+            }
+        }
+
+        if segments.iter().any(|s| s.uses_purchases()) {
+            let purchases = ShopCustomerSegmentRepository::list_purchases_in_tx(tx, &ids)
+                .await
+                .map_err(|e| format!("Failed to list customer purchases: {}", e))?;
+            for purchase in purchases {
+                let Some(entry) = facts.get_mut(&purchase.customer_id) else {
+                    continue;
+                };
+                if purchase.kind == "brand" {
+                    entry.brand_ids.insert(purchase.value);
+                } else {
+                    entry.category_ids.insert(purchase.value);
+                }
+            }
+        }
+
+        Ok(facts.into_values().collect())
+    }
+
+    /// Bring the group's memberships in line with `matched`
+    async fn apply_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        group_id: &str,
+        matched: &HashSet<String>,
+    ) -> Result<CustomerGroupRefresh, String> {
+        let current: HashSet<String> =
+            ShopCustomerSegmentRepository::list_member_ids_in_tx(tx, group_id)
+                .await
+                .map_err(|e| format!("Failed to fetch group members: {}", e))?
+                .into_iter()
+                .collect();
+
+        let mut joined = 0;
+        for customer_id in matched.difference(&current) {
+            ShopCustomerSegmentRepository::add_member_in_tx(tx, group_id, customer_id)
+                .await
+                .map_err(|e| format!("Failed to add group member: {}", e))?;
+            joined += 1;
+        }
+
+        let mut left = 0;
+        for customer_id in current.difference(matched) {
+            ShopCustomerSegmentRepository::remove_member_in_tx(tx, group_id, customer_id)
+                .await
+                .map_err(|e| format!("Failed to remove group member: {}", e))?;
+            left += 1;
+        }
+
+        Ok(CustomerGroupRefresh {
+            group_id: group_id.to_string(),
+            member_count: matched.len() as i64,
+            joined,
+            left,
+        })
+    }
+}
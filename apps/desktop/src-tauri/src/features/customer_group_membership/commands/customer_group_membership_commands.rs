@@ -22,25 +22,26 @@ pub async fn assign_customer_groups(
     // Delete existing memberships for this customer
     service.delete_by_customer(&payload.customer_id).await?;
 
-    if payload.group_ids.is_empty() {
-        return Ok(Vec::new());
+    if !payload.group_ids.is_empty() {
+        // Create new memberships
+        let now = Utc::now();
+        let memberships: Vec<CustomerGroupMembership> = payload
+            .group_ids
+            .iter()
+            .map(|group_id| CustomerGroupMembership {
+                customer_id: payload.customer_id.clone(),
+                customer_group_id: group_id.clone(),
+                sync_status: "created".to_string(),
+                created_at: now,
+                updated_at: now,
+            })
+            .collect();
+        service.create_many(memberships).await?;
     }
 
-    // Create new memberships
-    let now = Utc::now();
-    let memberships: Vec<CustomerGroupMembership> = payload
-        .group_ids
-        .iter()
-        .map(|group_id| CustomerGroupMembership {
-            customer_id: payload.customer_id.clone(),
-            customer_group_id: group_id.clone(),
-            sync_status: "created".to_string(),
-            created_at: now,
-            updated_at: now,
-        })
-        .collect();
-
-    service.create_many(memberships).await
+    // Dynamic groups keep exactly the customers their rules select
+    service.refresh_dynamic_groups(&payload.customer_id).await?;
+    service.list_by_customer(&payload.customer_id).await
 }
 
 #[tauri::command]
@@ -86,5 +87,6 @@ pub async fn delete_customer_group_membership(
         .map_err(|e| format!("Failed to get shop pool: {}", e))?;
 
     let service = ShopCustomerGroupMembershipService::new(pool);
-    service.delete_membership(&customer_id, &group_id).await
+    service.delete_membership(&customer_id, &group_id).await?;
+    service.refresh_dynamic_groups(&customer_id).await
 }
@@ -1,6 +1,7 @@
 //! Shop-scoped Customer Group Membership Service for Multi-Database Architecture
 
 use crate::features::customer::models::customer_model::CustomerGroupMembership;
+use crate::features::customer_group::services::shop_customer_segment_service::ShopCustomerSegmentService;
 use crate::features::customer_group_membership::repositories::shop_customer_group_membership_repository::ShopCustomerGroupMembershipRepository;
 use sqlx::SqlitePool;
 use std::sync::Arc;
@@ -68,4 +69,20 @@ impl ShopCustomerGroupMembershipService {
             .await
             .map_err(|e| format!("Failed to delete memberships: {}", e))
     }
+
+    /// Dynamic groups own their memberships: undo manual changes to them
+    /// by re-evaluating the customer against their rules.
+    pub async fn refresh_dynamic_groups(&self, customer_id: &str) -> Result<(), String> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        ShopCustomerSegmentService::refresh_customer_in_tx(&mut tx, customer_id).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))
+    }
 }
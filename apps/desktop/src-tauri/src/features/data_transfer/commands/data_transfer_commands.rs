@@ -0,0 +1,71 @@
+use crate::db::RepositoryFactory;
+use crate::features::data_transfer::dtos::data_transfer_dto::{
+    ExportFileDTO, ImportFileDTO, PreviewImportDTO,
+};
+use crate::features::data_transfer::models::data_transfer_model::{
+    ExportResult, ImportField, ImportPreview, ImportReport, IMPORT_PROGRESS_EVENT,
+};
+use crate::features::data_transfer::services::row_reader::import_fields;
+use crate::features::data_transfer::services::shop_export_service::ShopExportService;
+use crate::features::data_transfer::services::shop_import_service::{
+    fields_for, ShopImportService,
+};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, State};
+
+#[tauri::command]
+pub async fn list_import_fields(entity: String) -> Result<Vec<ImportField>, String> {
+    fields_for(&entity).map(import_fields)
+}
+
+#[tauri::command]
+pub async fn preview_import_file(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: PreviewImportDTO,
+) -> Result<ImportPreview, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopImportService::new(pool, shop_id);
+    service.preview(payload)
+}
+
+/// Import a file, emitting IMPORT_PROGRESS_EVENT as rows are validated and
+/// chunks are committed
+#[tauri::command]
+pub async fn import_data_file(
+    app: AppHandle,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: ImportFileDTO,
+) -> Result<ImportReport, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopImportService::new(pool, shop_id);
+    service
+        .import(payload, |progress| {
+            let _ = app.emit(IMPORT_PROGRESS_EVENT, progress);
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn export_data_file(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: ExportFileDTO,
+) -> Result<ExportResult, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopExportService::new(pool, shop_id);
+    service.export(payload).await
+}
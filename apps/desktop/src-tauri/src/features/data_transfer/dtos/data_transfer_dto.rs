@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreviewImportDTO {
+    pub entity: String,
+    pub path: String,
+    pub format: Option<String>, // 'csv', 'xlsx'; from the extension when absent
+    pub delimiter: Option<String>, // CSV only; detected when absent
+    pub sheet: Option<String>,  // Spreadsheets only; first sheet when absent
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportFileDTO {
+    pub entity: String, // 'products', 'customers', 'inventory'
+    pub path: String,
+    pub format: Option<String>,
+    pub delimiter: Option<String>,
+    pub sheet: Option<String>,
+    pub mapping: Option<HashMap<String, String>>, // File column -> field; matched by header when absent
+    pub dry_run: Option<bool>,
+    pub update_existing: Option<bool>, // DEFAULT true; false skips rows matching an existing record
+    pub chunk_size: Option<usize>,     // Rows per transaction
+    pub import_id: Option<String>,     // Echoed in progress events
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportFileDTO {
+    pub entity: String,
+    pub path: String,
+    pub format: Option<String>,
+    pub delimiter: Option<String>,
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashMap;
+
+/// Entities that can be imported and exported
+pub const ENTITIES: [&str; 3] = ["products", "customers", "inventory"];
+
+/// Event emitted to the frontend while an import runs
+pub const IMPORT_PROGRESS_EVENT: &str = "data-import-progress";
+
+/// A target field of an import, as offered in the column mapping
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportField {
+    pub name: String,
+    pub required: bool, // Required when creating a new record
+    pub description: String,
+}
+
+/// First rows of a file, with the column mapping guessed from its headers
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportPreview {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub total_rows: usize,
+    pub sheets: Vec<String>,                        // Spreadsheets only
+    pub suggested_mapping: HashMap<String, String>, // File column -> field
+    pub fields: Vec<ImportField>,
+}
+
+/// A problem with one row. Rows are numbered as in a spreadsheet: the
+/// header is row 1 and the first record row 2.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportRowError {
+    pub row: usize,
+    pub column: Option<String>,
+    pub message: String,
+}
+
+/// Outcome of an import. In a dry run `created` and `updated` count what
+/// would have been written.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportReport {
+    pub import_id: String,
+    pub entity: String,
+    pub dry_run: bool,
+    pub total_rows: usize,
+    pub valid_rows: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize, // Matched an existing record with update_existing off
+    pub failed: usize,
+    pub categories_created: usize,
+    pub brands_created: usize,
+    pub chunks_committed: usize,
+    pub completed: bool, // False when a chunk failed and the import stopped
+    pub errors: Vec<ImportRowError>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportProgress {
+    pub import_id: String,
+    pub entity: String,
+    pub stage: String, // 'validating', 'importing', 'finished'
+    pub processed: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportResult {
+    pub entity: String,
+    pub format: String,
+    pub path: String,
+    pub row_count: usize,
+}
+
+/// Identity columns of a product, deleted ones included (SKU and slug stay
+/// unique after a soft delete)
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ProductKey {
+    pub id: String,
+    pub sku: String,
+    pub slug: String,
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub r#type: String,
+    pub parent_id: Option<String>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+}
+
+/// Identity columns of a customer, deleted ones included
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct CustomerKey {
+    pub id: String,
+    pub email: Option<String>,
+    pub tax_id: Option<String>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+}
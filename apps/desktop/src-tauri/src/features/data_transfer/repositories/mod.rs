@@ -0,0 +1 @@
+pub mod shop_data_transfer_repository;
@@ -0,0 +1,45 @@
+//! Shop-scoped lookups for bulk import and export
+
+use crate::features::data_transfer::models::data_transfer_model::{CustomerKey, ProductKey};
+use sqlx::{Result, SqlitePool};
+use std::sync::Arc;
+
+pub struct ShopDataTransferRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopDataTransferRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Identity of every product, deleted ones included
+    pub async fn product_keys(&self) -> Result<Vec<ProductKey>> {
+        let sql = "SELECT id, sku, slug, type, parent_id, _status FROM products";
+        sqlx::query_as::<_, ProductKey>(sql)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// Identity of every customer, deleted ones included
+    pub async fn customer_keys(&self) -> Result<Vec<CustomerKey>> {
+        let sql = "SELECT id, email, tax_id, _status FROM customers";
+        sqlx::query_as::<_, CustomerKey>(sql)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// Every category slug, deleted ones included
+    pub async fn category_slugs(&self) -> Result<Vec<String>> {
+        sqlx::query_scalar::<_, String>("SELECT slug FROM categories")
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// Every brand slug, deleted ones included
+    pub async fn brand_slugs(&self) -> Result<Vec<String>> {
+        sqlx::query_scalar::<_, String>("SELECT slug FROM brands")
+            .fetch_all(&*self.pool)
+            .await
+    }
+}
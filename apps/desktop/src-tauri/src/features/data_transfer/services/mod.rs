@@ -0,0 +1,4 @@
+pub mod row_reader;
+pub mod shop_export_service;
+pub mod shop_import_service;
+pub mod tabular;
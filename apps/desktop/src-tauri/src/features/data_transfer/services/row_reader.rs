@@ -0,0 +1,285 @@
+//! Column mapping and typed reading of imported rows
+
+use crate::features::data_transfer::models::data_transfer_model::{ImportField, ImportRowError};
+use std::collections::HashMap;
+
+/// A field definition: name, required on create, description and header
+/// aliases recognised when no mapping is given
+pub type FieldSpec = (&'static str, bool, &'static str, &'static [&'static str]);
+
+pub fn import_fields(specs: &[FieldSpec]) -> Vec<ImportField> {
+    specs
+        .iter()
+        .map(|(name, required, description, _)| ImportField {
+            name: name.to_string(),
+            required: *required,
+            description: description.to_string(),
+        })
+        .collect()
+}
+
+/// Fold the accented letters of Portuguese and Spanish to ASCII
+fn fold_accent(c: char) -> char {
+    match c {
+        'á' | 'à' | 'â' | 'ã' | 'ä' => 'a',
+        'é' | 'è' | 'ê' | 'ë' => 'e',
+        'í' | 'ì' | 'î' | 'ï' => 'i',
+        'ó' | 'ò' | 'ô' | 'õ' | 'ö' => 'o',
+        'ú' | 'ù' | 'û' | 'ü' => 'u',
+        'ç' => 'c',
+        'ñ' => 'n',
+        other => other,
+    }
+}
+
+/// Lowercase ASCII words joined by `separator`
+pub fn ascii_words(value: &str, separator: &str) -> String {
+    value
+        .to_lowercase()
+        .chars()
+        .map(fold_accent)
+        .collect::<String>()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// "Preço Promocional" -> "preco_promocional"
+pub fn normalize_header(header: &str) -> String {
+    ascii_words(header, "_")
+}
+
+/// "Camisetas Básicas" -> "camisetas-basicas"
+pub fn slugify(value: &str) -> String {
+    ascii_words(value, "-")
+}
+
+pub fn digits(value: &str) -> String {
+    value.chars().filter(|c| c.is_ascii_digit()).collect()
+}
+
+/// Parse a decimal written either way: "1234.5", "1234,5", "1.234,50",
+/// "1,234.50" or with a currency symbol. With both separators present the
+/// last one is the decimal separator.
+pub fn parse_decimal(raw: &str) -> Result<f64, String> {
+    let cleaned: String = raw
+        .trim()
+        .trim_start_matches("R$")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let normalized = match (cleaned.rfind(','), cleaned.rfind('.')) {
+        (Some(comma), Some(dot)) if comma > dot => cleaned.replace('.', "").replace(',', "."),
+        (Some(_), Some(_)) => cleaned.replace(',', ""),
+        (Some(_), None) => cleaned.replace(',', "."),
+        _ => cleaned,
+    };
+    normalized
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .ok_or_else(|| format!("Invalid number: {}", raw.trim()))
+}
+
+pub fn parse_integer(raw: &str) -> Result<i64, String> {
+    let value = parse_decimal(raw)?;
+    if value.fract() != 0.0 {
+        return Err(format!("Expected a whole number: {}", raw.trim()));
+    }
+    Ok(value as i64)
+}
+
+pub fn parse_bool(raw: &str) -> Result<bool, String> {
+    match ascii_words(raw, " ").as_str() {
+        "1" | "true" | "yes" | "y" | "sim" | "s" | "x" => Ok(true),
+        "0" | "false" | "no" | "n" | "nao" => Ok(false),
+        _ => Err(format!("Invalid yes/no value: {}", raw.trim())),
+    }
+}
+
+/// Which file column feeds each field
+#[derive(Debug, Clone)]
+pub struct ColumnMap {
+    columns: HashMap<&'static str, usize>,
+}
+
+impl ColumnMap {
+    /// Map file columns to fields, using `mapping` (file column -> field)
+    /// when given and the headers otherwise. Fails on unknown target fields,
+    /// on a field fed by two columns and when one of `required` has no
+    /// column.
+    pub fn resolve(
+        headers: &[String],
+        mapping: Option<&HashMap<String, String>>,
+        specs: &[FieldSpec],
+        required: &[&str],
+    ) -> Result<Self, String> {
+        let mut columns: HashMap<&'static str, usize> = HashMap::new();
+
+        match mapping {
+            Some(mapping) => {
+                for (column, field) in mapping {
+                    let field = field.trim();
+                    if field.is_empty() {
+                        continue; // Column deliberately ignored
+                    }
+                    let spec = specs
+                        .iter()
+                        .find(|(name, ..)| *name == field)
+                        .ok_or_else(|| format!("Unknown field: {}", field))?;
+                    let index = headers
+                        .iter()
+                        .position(|h| h == column.trim())
+                        .ok_or_else(|| format!("Column not found in file: {}", column))?;
+                    if columns.insert(spec.0, index).is_some() {
+                        return Err(format!("Field {} is mapped to more than one column", field));
+                    }
+                }
+            }
+            None => {
+                for (header, field) in suggest_mapping(headers, specs) {
+                    if let Some(index) = headers.iter().position(|h| *h == header) {
+                        columns.entry(field).or_insert(index);
+                    }
+                }
+            }
+        }
+
+        let missing: Vec<&str> = required
+            .iter()
+            .filter(|name| !columns.contains_key(*name))
+            .copied()
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!("Missing required columns: {}", missing.join(", ")));
+        }
+        Ok(Self { columns })
+    }
+
+    pub fn has(&self, field: &str) -> bool {
+        self.columns.contains_key(field)
+    }
+
+    /// Trimmed value of a field, None when unmapped or blank
+    pub fn get<'a>(&self, values: &'a [String], field: &str) -> Option<&'a str> {
+        self.columns
+            .get(field)
+            .and_then(|index| values.get(*index))
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+    }
+}
+
+/// Field matching each header, by name or alias
+pub fn suggest_mapping(headers: &[String], specs: &[FieldSpec]) -> Vec<(String, &'static str)> {
+    let mut taken: Vec<&'static str> = Vec::new();
+    let mut mapping = Vec::new();
+    for header in headers {
+        let normalized = normalize_header(header);
+        let field = specs.iter().find(|(name, _, _, aliases)| {
+            *name == normalized || aliases.iter().any(|alias| *alias == normalized)
+        });
+        if let Some((name, ..)) = field {
+            if !taken.contains(name) {
+                taken.push(name);
+                mapping.push((header.clone(), *name));
+            }
+        }
+    }
+    mapping
+}
+
+/// Typed access to one row, collecting the errors of its cells
+pub struct RowReader<'a> {
+    columns: &'a ColumnMap,
+    values: &'a [String],
+    pub row: usize,
+    pub errors: Vec<ImportRowError>,
+}
+
+impl<'a> RowReader<'a> {
+    pub fn new(columns: &'a ColumnMap, values: &'a [String], row: usize) -> Self {
+        Self {
+            columns,
+            values,
+            row,
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn error(&mut self, column: Option<&str>, message: impl Into<String>) {
+        self.errors.push(ImportRowError {
+            row: self.row,
+            column: column.map(str::to_string),
+            message: message.into(),
+        });
+    }
+
+    pub fn text(&self, field: &str) -> Option<String> {
+        self.columns.get(self.values, field).map(str::to_string)
+    }
+
+    fn parsed<T>(&mut self, field: &str, parse: fn(&str) -> Result<T, String>) -> Option<T> {
+        let raw = self.columns.get(self.values, field)?;
+        match parse(raw) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.error(Some(field), e);
+                None
+            }
+        }
+    }
+
+    pub fn decimal(&mut self, field: &str) -> Option<f64> {
+        self.parsed(field, parse_decimal)
+    }
+
+    /// A decimal that cannot be negative
+    pub fn amount(&mut self, field: &str) -> Option<f64> {
+        let value = self.decimal(field)?;
+        if value < 0.0 {
+            self.error(Some(field), format!("{} cannot be negative", field));
+            return None;
+        }
+        Some(value)
+    }
+
+    /// A whole number that cannot be negative
+    pub fn count(&mut self, field: &str) -> Option<i64> {
+        let value = self.parsed(field, parse_integer)?;
+        if value < 0 {
+            self.error(Some(field), format!("{} cannot be negative", field));
+            return None;
+        }
+        Some(value)
+    }
+
+    pub fn boolean(&mut self, field: &str) -> Option<bool> {
+        self.parsed(field, parse_bool)
+    }
+
+    /// A value restricted to `allowed`, compared case-insensitively
+    pub fn choice(&mut self, field: &str, allowed: &[&str]) -> Option<String> {
+        let raw = self.text(field)?;
+        let value = raw.to_lowercase();
+        if allowed.contains(&value.as_str()) {
+            Some(value)
+        } else {
+            self.error(
+                Some(field),
+                format!(
+                    "Invalid {}: {} (expected {})",
+                    field,
+                    raw,
+                    allowed.join(", ")
+                ),
+            );
+            None
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
@@ -0,0 +1,313 @@
+//! Shop-scoped Export Service for Multi-Database Architecture
+//!
+//! Writes products, customers and inventory levels to CSV or XLSX. The
+//! columns are the import field names, so an exported file can be edited
+//! and imported back.
+
+use crate::features::brand::repositories::shop_brand_repository::ShopBrandRepository;
+use crate::features::category::repositories::shop_category_repository::ShopCategoryRepository;
+use crate::features::customer::models::customer_model::CustomerAddress;
+use crate::features::customer::repositories::shop_customer_repository::ShopCustomerRepository;
+use crate::features::customer_address::repositories::shop_customer_address_repository::ShopCustomerAddressRepository;
+use crate::features::data_transfer::dtos::data_transfer_dto::ExportFileDTO;
+use crate::features::data_transfer::models::data_transfer_model::ExportResult;
+use crate::features::data_transfer::services::shop_import_service::{
+    fields_for, CUSTOMER_FIELDS, INVENTORY_FIELDS, PRODUCT_FIELDS,
+};
+use crate::features::data_transfer::services::tabular::{
+    parse_delimiter, write_csv, write_xlsx, ExportCell, FileFormat,
+};
+use crate::features::inventory::repositories::shop_inventory_repository::ShopInventoryRepository;
+use crate::features::location::repositories::shop_location_repository::ShopLocationRepository;
+use crate::features::product::repositories::shop_product_repository::ShopProductRepository;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub struct ShopExportService {
+    pool: Arc<SqlitePool>,
+    product_repo: ShopProductRepository,
+    category_repo: ShopCategoryRepository,
+    brand_repo: ShopBrandRepository,
+    customer_repo: ShopCustomerRepository,
+    address_repo: ShopCustomerAddressRepository,
+    location_repo: ShopLocationRepository,
+    inventory_repo: ShopInventoryRepository,
+}
+
+impl ShopExportService {
+    pub fn new(pool: Arc<SqlitePool>, shop_id: String) -> Self {
+        Self {
+            product_repo: ShopProductRepository::new(pool.clone(), shop_id.clone()),
+            category_repo: ShopCategoryRepository::new(pool.clone(), shop_id.clone()),
+            brand_repo: ShopBrandRepository::new(pool.clone(), shop_id.clone()),
+            customer_repo: ShopCustomerRepository::new(pool.clone(), shop_id.clone()),
+            address_repo: ShopCustomerAddressRepository::new(pool.clone()),
+            location_repo: ShopLocationRepository::new(pool.clone(), shop_id),
+            inventory_repo: ShopInventoryRepository::new(pool.clone()),
+            pool,
+        }
+    }
+
+    pub fn pool(&self) -> Arc<SqlitePool> {
+        self.pool.clone()
+    }
+
+    pub async fn export(&self, payload: ExportFileDTO) -> Result<ExportResult, String> {
+        fields_for(&payload.entity)?;
+        let format = FileFormat::resolve(payload.format.as_deref(), &payload.path)?;
+        let delimiter = parse_delimiter(payload.delimiter.as_deref())?.unwrap_or(b',');
+
+        let (specs, rows) = match payload.entity.as_str() {
+            "products" => (PRODUCT_FIELDS, self.product_rows().await?),
+            "customers" => (CUSTOMER_FIELDS, self.customer_rows().await?),
+            _ => (INVENTORY_FIELDS, self.inventory_rows().await?),
+        };
+        let headers: Vec<&str> = specs.iter().map(|(name, ..)| *name).collect();
+
+        match format {
+            FileFormat::Csv => write_csv(&payload.path, &headers, &rows, delimiter)?,
+            FileFormat::Xlsx => write_xlsx(&payload.path, &payload.entity, &headers, &rows)?,
+        }
+
+        Ok(ExportResult {
+            entity: payload.entity,
+            format: format.as_str().to_string(),
+            path: payload.path,
+            row_count: rows.len(),
+        })
+    }
+
+    /// One row per product, parents before their variants (PRODUCT_FIELDS order)
+    async fn product_rows(&self) -> Result<Vec<Vec<ExportCell>>, String> {
+        let mut products = self
+            .product_repo
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list products: {}", e))?;
+        let categories = self
+            .category_repo
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list categories: {}", e))?;
+        let brands: HashMap<String, String> = self
+            .brand_repo
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list brands: {}", e))?
+            .into_iter()
+            .map(|b| (b.id, b.name))
+            .collect();
+
+        let parents: HashMap<&str, (Option<&str>, &str)> = categories
+            .iter()
+            .map(|c| (c.id.as_str(), (c.parent_id.as_deref(), c.name.as_str())))
+            .collect();
+        let category_path = |id: &str| -> String {
+            let mut names = Vec::new();
+            let mut current = Some(id);
+            while let Some(id) = current {
+                match parents.get(id) {
+                    Some((parent_id, name)) if names.len() < parents.len() => {
+                        names.push(*name);
+                        current = *parent_id;
+                    }
+                    _ => break,
+                }
+            }
+            names.reverse();
+            names.join(" > ")
+        };
+
+        products.sort_by_key(|p| p.parent_id.is_some());
+        let skus: HashMap<String, String> = products
+            .iter()
+            .map(|p| (p.id.clone(), p.sku.clone()))
+            .collect();
+
+        let rows = products
+            .into_iter()
+            .map(|p| {
+                let options = p
+                    .attributes
+                    .as_deref()
+                    .and_then(|a| serde_json::from_str::<Value>(a).ok())
+                    .and_then(|a| a.get("options").and_then(Value::as_object).cloned())
+                    .map(|options| {
+                        options
+                            .iter()
+                            .map(|(name, value)| {
+                                let value = value
+                                    .as_str()
+                                    .map(str::to_string)
+                                    .unwrap_or_else(|| value.to_string());
+                                format!("{}={}", name, value)
+                            })
+                            .collect::<Vec<_>>()
+                            .join("; ")
+                    });
+                vec![
+                    p.sku.into(),
+                    p.name.into(),
+                    p.r#type.into(),
+                    ExportCell::text(p.status.as_deref()),
+                    ExportCell::Number(p.price),
+                    ExportCell::number(p.promotional_price),
+                    ExportCell::number(p.cost_price),
+                    ExportCell::text(p.currency.as_deref()),
+                    ExportCell::text(p.gtin_ean.as_deref()),
+                    ExportCell::text(p.tax_ncm.as_deref()),
+                    p.is_shippable.into(),
+                    ExportCell::Number(p.weight_g as f64),
+                    ExportCell::Number(p.width_mm as f64),
+                    ExportCell::Number(p.height_mm as f64),
+                    ExportCell::Number(p.depth_mm as f64),
+                    ExportCell::text(p.category_id.as_deref().map(&category_path).as_deref()),
+                    ExportCell::text(
+                        p.brand_id
+                            .as_ref()
+                            .and_then(|id| brands.get(id))
+                            .map(String::as_str),
+                    ),
+                    ExportCell::text(
+                        p.parent_id
+                            .as_ref()
+                            .and_then(|id| skus.get(id))
+                            .map(String::as_str),
+                    ),
+                    ExportCell::text(options.as_deref()),
+                    p.slug.into(),
+                ]
+            })
+            .collect();
+        Ok(rows)
+    }
+
+    /// One row per customer with its default address (CUSTOMER_FIELDS order)
+    async fn customer_rows(&self) -> Result<Vec<Vec<ExportCell>>, String> {
+        let customers = self
+            .customer_repo
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list customers: {}", e))?;
+
+        // Default address first, shipping before billing
+        let mut addresses: HashMap<String, CustomerAddress> = HashMap::new();
+        let mut all = self
+            .address_repo
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list customer addresses: {}", e))?;
+        all.sort_by_key(|a| {
+            (
+                a.is_default != Some(true),
+                a.r#type.as_deref() == Some("billing"),
+            )
+        });
+        for address in all {
+            addresses
+                .entry(address.customer_id.clone())
+                .or_insert(address);
+        }
+
+        let rows = customers
+            .into_iter()
+            .map(|c| {
+                let address = addresses.remove(&c.id);
+                let address_text = |f: fn(&CustomerAddress) -> Option<&str>| {
+                    ExportCell::text(address.as_ref().and_then(f))
+                };
+                vec![
+                    ExportCell::text(c.email.as_deref()),
+                    ExportCell::text(c.tax_id.as_deref()),
+                    c.r#type.into(),
+                    ExportCell::text(c.first_name.as_deref()),
+                    ExportCell::text(c.last_name.as_deref()),
+                    ExportCell::text(c.company_name.as_deref()),
+                    ExportCell::text(c.phone.as_deref()),
+                    ExportCell::text(c.tax_id_type.as_deref()),
+                    ExportCell::text(c.state_tax_id.as_deref()),
+                    ExportCell::text(c.status.as_deref()),
+                    ExportCell::text(c.language.as_deref()),
+                    ExportCell::text(c.currency.as_deref()),
+                    ExportCell::text(c.tags.as_deref()),
+                    c.accepts_marketing
+                        .map(ExportCell::from)
+                        .unwrap_or(ExportCell::Empty),
+                    ExportCell::text(c.notes.as_deref()),
+                    address_text(|a| a.r#type.as_deref()),
+                    address_text(|a| a.address1.as_deref()),
+                    address_text(|a| a.address2.as_deref()),
+                    address_text(|a| a.city.as_deref()),
+                    address_text(|a| a.province_code.as_deref()),
+                    address_text(|a| a.postal_code.as_deref()),
+                    address_text(|a| a.country_code.as_deref()),
+                ]
+            })
+            .collect();
+        Ok(rows)
+    }
+
+    /// One row per inventory level (INVENTORY_FIELDS order). Batch and serial
+    /// levels are summed into their product, location and status.
+    async fn inventory_rows(&self) -> Result<Vec<Vec<ExportCell>>, String> {
+        let skus: HashMap<String, String> = self
+            .product_repo
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list products: {}", e))?
+            .into_iter()
+            .map(|p| (p.id, p.sku))
+            .collect();
+        let locations: HashMap<String, String> = self
+            .location_repo
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list locations: {}", e))?
+            .into_iter()
+            .map(|l| (l.id, l.name))
+            .collect();
+        let levels = self
+            .inventory_repo
+            .list_levels()
+            .await
+            .map_err(|e| format!("Failed to list inventory levels: {}", e))?;
+
+        let mut totals: Vec<((&str, &str, String), f64)> = Vec::new();
+        let mut index: HashMap<(&str, &str, String), usize> = HashMap::new();
+        for level in &levels {
+            let (Some(sku), Some(location)) = (
+                skus.get(&level.product_id),
+                locations.get(&level.location_id),
+            ) else {
+                continue;
+            };
+            let status = level
+                .stock_status
+                .clone()
+                .unwrap_or_else(|| "sellable".to_string());
+            let key = (sku.as_str(), location.as_str(), status);
+            match index.get(&key) {
+                Some(i) => totals[*i].1 += level.quantity_on_hand,
+                None => {
+                    index.insert(key.clone(), totals.len());
+                    totals.push((key, level.quantity_on_hand));
+                }
+            }
+        }
+
+        let rows = totals
+            .into_iter()
+            .map(|((sku, location, status), quantity)| {
+                vec![
+                    sku.to_string().into(),
+                    location.to_string().into(),
+                    ExportCell::Number(quantity),
+                    status.into(),
+                ]
+            })
+            .collect();
+        Ok(rows)
+    }
+}
@@ -0,0 +1,2038 @@
+//! Shop-scoped Import Service for Multi-Database Architecture
+//!
+//! Bulk import of products (with categories, brands and variants), customers
+//! (with an address) and inventory levels from CSV or spreadsheet files.
+//!
+//! Every row is validated before anything is written; a dry run stops there
+//! and reports what would change. Otherwise the valid rows are written in
+//! chunks, one transaction per chunk. Rows are upserted by SKU (products,
+//! inventory) or by e-mail and tax ID (customers); blank cells keep the
+//! current value of an existing record. When a chunk fails it is rolled back
+//! and the import stops, keeping the chunks already committed.
+
+use crate::features::barcode::services::gtin;
+use crate::features::brand::models::brand_model::Brand;
+use crate::features::brand::repositories::shop_brand_repository::ShopBrandRepository;
+use crate::features::category::models::category_model::Category;
+use crate::features::category::repositories::shop_category_repository::ShopCategoryRepository;
+use crate::features::customer::models::customer_model::{Customer, CustomerAddress};
+use crate::features::customer::repositories::shop_customer_repository::ShopCustomerRepository;
+use crate::features::customer_address::repositories::shop_customer_address_repository::ShopCustomerAddressRepository;
+use crate::features::data_transfer::dtos::data_transfer_dto::{ImportFileDTO, PreviewImportDTO};
+use crate::features::data_transfer::models::data_transfer_model::{
+    ImportField, ImportPreview, ImportProgress, ImportReport, ImportRowError, ProductKey, ENTITIES,
+};
+use crate::features::data_transfer::repositories::shop_data_transfer_repository::ShopDataTransferRepository;
+use crate::features::data_transfer::services::row_reader::{
+    digits, import_fields, slugify, suggest_mapping, ColumnMap, FieldSpec, RowReader,
+};
+use crate::features::data_transfer::services::tabular::{
+    parse_delimiter, read_table, FileFormat, Table, TableRow,
+};
+use crate::features::inventory::models::inventory_level_model::InventoryLevel;
+use crate::features::inventory::repositories::inventory_movements_repository::InventoryMovementsRepository;
+use crate::features::inventory::repositories::shop_inventory_repository::ShopInventoryRepository;
+use crate::features::location::repositories::shop_location_repository::ShopLocationRepository;
+use crate::features::price_history::services::shop_price_history_service::ShopPriceHistoryService;
+use crate::features::product::dtos::product_dto::{ProductOptionDTO, SetProductOptionsDTO};
+use crate::features::product::models::product_model::Product;
+use crate::features::product::repositories::shop_product_option_repository::ShopProductOptionRepository;
+use crate::features::product::repositories::shop_product_repository::ShopProductRepository;
+use crate::features::transaction::models::transaction_model::InventoryMovement;
+use chrono::Utc;
+use serde_json::{json, Value};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const DEFAULT_CHUNK_SIZE: usize = 500;
+const MAX_CHUNK_SIZE: usize = 5000;
+const DEFAULT_PREVIEW_ROWS: usize = 20;
+
+const PRODUCT_TYPES: [&str; 4] = ["physical", "digital", "service", "bundle"];
+const CUSTOMER_TYPES: [&str; 2] = ["individual", "company"];
+const CUSTOMER_STATUSES: [&str; 3] = ["active", "inactive", "blocked"];
+const ADDRESS_TYPES: [&str; 2] = ["shipping", "billing"];
+const STOCK_STATUSES: [&str; 4] = ["sellable", "damaged", "quarantine", "expired"];
+
+pub const PRODUCT_FIELDS: &[FieldSpec] = &[
+    (
+        "sku",
+        true,
+        "Stock keeping unit; identifies the product on re-import",
+        &["codigo", "cod", "referencia", "ref"],
+    ),
+    ("name", true, "Product name", &["nome", "produto", "title"]),
+    (
+        "type",
+        false,
+        "physical, digital, service or bundle (default physical)",
+        &["tipo"],
+    ),
+    (
+        "status",
+        false,
+        "Product status (default draft)",
+        &["situacao"],
+    ),
+    (
+        "price",
+        true,
+        "Selling price",
+        &["preco", "preco_venda", "valor"],
+    ),
+    (
+        "promotional_price",
+        false,
+        "Promotional price",
+        &["preco_promocional"],
+    ),
+    ("cost_price", false, "Cost price", &["custo", "preco_custo"]),
+    ("currency", false, "Currency code (default BRL)", &["moeda"]),
+    (
+        "gtin_ean",
+        false,
+        "GTIN/EAN barcode",
+        &["gtin", "ean", "codigo_barras", "barcode"],
+    ),
+    ("tax_ncm", false, "NCM tax code", &["ncm"]),
+    (
+        "is_shippable",
+        false,
+        "Whether the product is shipped (yes/no)",
+        &["enviavel"],
+    ),
+    ("weight_g", false, "Weight in grams", &["peso", "peso_g"]),
+    (
+        "width_mm",
+        false,
+        "Width in millimetres",
+        &["largura", "largura_mm"],
+    ),
+    (
+        "height_mm",
+        false,
+        "Height in millimetres",
+        &["altura", "altura_mm"],
+    ),
+    (
+        "depth_mm",
+        false,
+        "Depth in millimetres",
+        &["profundidade", "comprimento", "profundidade_mm"],
+    ),
+    (
+        "category",
+        false,
+        "Category path such as Clothing > T-shirts; missing categories are created",
+        &["categoria"],
+    ),
+    (
+        "brand",
+        false,
+        "Brand name; missing brands are created",
+        &["marca"],
+    ),
+    (
+        "parent_sku",
+        false,
+        "SKU of the parent product, for variants",
+        &["sku_pai", "produto_pai"],
+    ),
+    (
+        "options",
+        false,
+        "Variant option values such as Size=M; Color=Blue",
+        &["opcoes", "variacoes"],
+    ),
+    (
+        "slug",
+        false,
+        "URL slug, generated from the name when absent",
+        &[],
+    ),
+];
+
+pub const CUSTOMER_FIELDS: &[FieldSpec] = &[
+    (
+        "email",
+        false,
+        "E-mail; identifies the customer on re-import",
+        &["e_mail"],
+    ),
+    (
+        "tax_id",
+        false,
+        "CPF or CNPJ; identifies the customer when the e-mail does not",
+        &["cpf", "cnpj", "cpf_cnpj", "documento"],
+    ),
+    (
+        "type",
+        false,
+        "individual or company, from the tax ID when absent",
+        &["tipo", "tipo_pessoa"],
+    ),
+    (
+        "first_name",
+        false,
+        "First name; a first name or company name is required",
+        &["nome", "primeiro_nome"],
+    ),
+    ("last_name", false, "Last name", &["sobrenome"]),
+    (
+        "company_name",
+        false,
+        "Company name",
+        &["razao_social", "empresa"],
+    ),
+    ("phone", false, "Phone", &["telefone", "celular", "fone"]),
+    (
+        "tax_id_type",
+        false,
+        "cpf or cnpj, from the tax ID when absent",
+        &[],
+    ),
+    (
+        "state_tax_id",
+        false,
+        "State tax registration",
+        &["inscricao_estadual", "ie"],
+    ),
+    (
+        "status",
+        false,
+        "active, inactive or blocked (default active)",
+        &["situacao"],
+    ),
+    ("language", false, "Language (default pt)", &["idioma"]),
+    ("currency", false, "Currency code (default BRL)", &["moeda"]),
+    ("tags", false, "Tags", &["etiquetas"]),
+    (
+        "accepts_marketing",
+        false,
+        "Accepts marketing (yes/no)",
+        &["aceita_marketing"],
+    ),
+    ("notes", false, "Notes", &["observacoes", "obs"]),
+    (
+        "address_type",
+        false,
+        "shipping or billing (default shipping)",
+        &["tipo_endereco"],
+    ),
+    (
+        "address1",
+        false,
+        "Street and number",
+        &["endereco", "logradouro"],
+    ),
+    ("address2", false, "Address complement", &["complemento"]),
+    ("city", false, "City", &["cidade", "municipio"]),
+    (
+        "province_code",
+        false,
+        "State code such as SP",
+        &["uf", "estado"],
+    ),
+    ("postal_code", false, "Postal code", &["cep"]),
+    (
+        "country_code",
+        false,
+        "Country code (default BR)",
+        &["pais"],
+    ),
+];
+
+pub const INVENTORY_FIELDS: &[FieldSpec] = &[
+    (
+        "sku",
+        true,
+        "Product SKU",
+        &["codigo", "cod", "referencia", "ref"],
+    ),
+    (
+        "location",
+        true,
+        "Location name or ID",
+        &["local", "deposito", "loja"],
+    ),
+    (
+        "quantity",
+        true,
+        "Quantity on hand",
+        &["quantidade", "qtd", "estoque", "saldo"],
+    ),
+    (
+        "stock_status",
+        false,
+        "sellable, damaged, quarantine or expired (default sellable)",
+        &[],
+    ),
+];
+
+/// Columns an import file must provide, by entity
+fn required_columns(entity: &str) -> &'static [&'static str] {
+    match entity {
+        "products" => &["sku"],
+        "inventory" => &["sku", "location", "quantity"],
+        _ => &[],
+    }
+}
+
+pub fn fields_for(entity: &str) -> Result<&'static [FieldSpec], String> {
+    match entity {
+        "products" => Ok(PRODUCT_FIELDS),
+        "customers" => Ok(CUSTOMER_FIELDS),
+        "inventory" => Ok(INVENTORY_FIELDS),
+        other => Err(format!(
+            "Invalid entity: {} (expected {})",
+            other,
+            ENTITIES.join(", ")
+        )),
+    }
+}
+
+fn is_deleted(sync_status: Option<&str>) -> bool {
+    sync_status == Some("deleted")
+}
+
+/// "Size=M; Color=Blue" -> [("Size", "M"), ("Color", "Blue")]
+fn parse_options(raw: &str) -> Result<Vec<(String, String)>, String> {
+    let mut options: Vec<(String, String)> = Vec::new();
+    for part in raw.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+        let (name, value) = part
+            .split_once('=')
+            .or_else(|| part.split_once(':'))
+            .map(|(n, v)| (n.trim(), v.trim()))
+            .filter(|(n, v)| !n.is_empty() && !v.is_empty())
+            .ok_or_else(|| format!("Invalid option {:?}, expected Name=Value", part))?;
+        if options.iter().any(|(n, _)| n.eq_ignore_ascii_case(name)) {
+            return Err(format!("Option {} is repeated", name));
+        }
+        options.push((name.to_string(), value.to_string()));
+    }
+    if options.is_empty() {
+        return Err("No option values given".to_string());
+    }
+    Ok(options)
+}
+
+/// Set the values of `attributes.options`, keeping the other attributes
+fn with_options(attributes: Option<&str>, options: &[(String, String)]) -> Option<String> {
+    let mut value = attributes
+        .and_then(|a| serde_json::from_str::<Value>(a).ok())
+        .filter(Value::is_object)
+        .unwrap_or_else(|| json!({}));
+    let entry = &mut value["options"];
+    if !entry.is_object() {
+        *entry = json!({});
+    }
+    for (name, option_value) in options {
+        entry[name.as_str()] = json!(option_value);
+    }
+    Some(value.to_string())
+}
+
+/// `base`, or `base-2`, `base-3`... whichever is not taken yet
+fn unique_slug(base: &str, taken: &mut HashSet<String>) -> String {
+    let base = if base.is_empty() { "item" } else { base };
+    let mut slug = base.to_string();
+    let mut n = 2;
+    while taken.contains(&slug) {
+        slug = format!("{}-{}", base, n);
+        n += 1;
+    }
+    taken.insert(slug.clone());
+    slug
+}
+
+fn is_valid_email(email: &str) -> bool {
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !email.chars().any(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+/// Validation errors and counters of an import, plus progress reporting
+struct ImportRun<'a, F: Fn(ImportProgress)> {
+    report: ImportReport,
+    chunk_size: usize,
+    on_progress: &'a F,
+}
+
+impl<'a, F: Fn(ImportProgress)> ImportRun<'a, F> {
+    fn progress(&self, stage: &str, processed: usize, total: usize) {
+        (self.on_progress)(ImportProgress {
+            import_id: self.report.import_id.clone(),
+            entity: self.report.entity.clone(),
+            stage: stage.to_string(),
+            processed,
+            total,
+        });
+    }
+
+    /// Report validation progress once per chunk of rows
+    fn validated(&self, index: usize, total: usize) {
+        if (index + 1).is_multiple_of(self.chunk_size) || index + 1 == total {
+            self.progress("validating", index + 1, total);
+        }
+    }
+
+    fn reject(&mut self, errors: Vec<ImportRowError>) {
+        self.report.failed += 1;
+        self.report.errors.extend(errors);
+    }
+
+    /// A chunk could not be written: it was rolled back and the import stops
+    fn abort(&mut self, row: usize, message: String) {
+        self.report.failed += 1;
+        self.report.completed = false;
+        self.report.errors.push(ImportRowError {
+            row,
+            column: None,
+            message,
+        });
+    }
+}
+
+// ============================================================
+// Plans: validated rows ready to be written
+// ============================================================
+
+struct ProductRow {
+    row: usize,
+    id: String,
+    is_new: bool,
+    sku: String,
+    slug: Option<String>,
+    name: Option<String>,
+    r#type: Option<String>,
+    status: Option<String>,
+    price: Option<f64>,
+    promotional_price: Option<f64>,
+    cost_price: Option<f64>,
+    currency: Option<String>,
+    gtin_ean: Option<String>,
+    tax_ncm: Option<String>,
+    is_shippable: Option<bool>,
+    weight_g: Option<i64>,
+    width_mm: Option<i64>,
+    height_mm: Option<i64>,
+    depth_mm: Option<i64>,
+    category_id: Option<String>,
+    brand_id: Option<String>,
+    parent_id: Option<String>,
+    options: Option<Vec<(String, String)>>,
+}
+
+impl ProductRow {
+    fn into_new_product(self, shop_id: &str) -> Product {
+        let now = Utc::now();
+        let r#type = self.r#type.unwrap_or_else(|| "physical".to_string());
+        Product {
+            id: self.id,
+            shop_id: shop_id.to_string(),
+            sku: self.sku,
+            is_shippable: self.is_shippable.unwrap_or(r#type == "physical"),
+            r#type,
+            status: self.status.or(Some("draft".to_string())),
+            name: self.name.unwrap_or_default(),
+            slug: self.slug.unwrap_or_default(),
+            gtin_ean: self.gtin_ean,
+            price: self.price.unwrap_or_default(),
+            promotional_price: self.promotional_price,
+            cost_price: self.cost_price,
+            currency: self.currency.or(Some("BRL".to_string())),
+            tax_ncm: self.tax_ncm,
+            weight_g: self.weight_g.unwrap_or_default(),
+            width_mm: self.width_mm.unwrap_or_default(),
+            height_mm: self.height_mm.unwrap_or_default(),
+            depth_mm: self.depth_mm.unwrap_or_default(),
+            attributes: self.options.as_deref().and_then(|o| with_options(None, o)),
+            metadata: None,
+            category_id: self.category_id,
+            brand_id: self.brand_id,
+            parent_id: self.parent_id,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        }
+    }
+
+    fn merge_into(self, mut product: Product) -> Product {
+        if let Some(slug) = self.slug {
+            product.slug = slug;
+        }
+        if let Some(name) = self.name {
+            product.name = name;
+        }
+        if let Some(r#type) = self.r#type {
+            product.r#type = r#type;
+        }
+        if self.status.is_some() {
+            product.status = self.status;
+        }
+        if let Some(price) = self.price {
+            product.price = price;
+        }
+        if self.promotional_price.is_some() {
+            product.promotional_price = self.promotional_price;
+        }
+        if self.cost_price.is_some() {
+            product.cost_price = self.cost_price;
+        }
+        if self.currency.is_some() {
+            product.currency = self.currency;
+        }
+        if self.gtin_ean.is_some() {
+            product.gtin_ean = self.gtin_ean;
+        }
+        if self.tax_ncm.is_some() {
+            product.tax_ncm = self.tax_ncm;
+        }
+        if let Some(is_shippable) = self.is_shippable {
+            product.is_shippable = is_shippable;
+        }
+        if let Some(weight_g) = self.weight_g {
+            product.weight_g = weight_g;
+        }
+        if let Some(width_mm) = self.width_mm {
+            product.width_mm = width_mm;
+        }
+        if let Some(height_mm) = self.height_mm {
+            product.height_mm = height_mm;
+        }
+        if let Some(depth_mm) = self.depth_mm {
+            product.depth_mm = depth_mm;
+        }
+        if self.category_id.is_some() {
+            product.category_id = self.category_id;
+        }
+        if self.brand_id.is_some() {
+            product.brand_id = self.brand_id;
+        }
+        if self.parent_id.is_some() {
+            product.parent_id = self.parent_id;
+        }
+        if let Some(options) = &self.options {
+            product.attributes = with_options(product.attributes.as_deref(), options);
+        }
+        product.sync_status = Some("modified".to_string());
+        product.updated_at = Some(Utc::now());
+        product
+    }
+}
+
+/// A SKU known to the import: an existing product or an earlier file row
+struct SkuEntry {
+    id: String,
+    row: Option<usize>, // File row, for products added by this import
+    is_variant: bool,
+    deleted: bool,
+    r#type: String,
+    slug: String,
+}
+
+impl From<ProductKey> for SkuEntry {
+    fn from(key: ProductKey) -> Self {
+        Self {
+            deleted: is_deleted(key.sync_status.as_deref()),
+            is_variant: key.parent_id.is_some(),
+            id: key.id,
+            row: None,
+            r#type: key.r#type,
+            slug: key.slug,
+        }
+    }
+}
+
+/// Categories by parent and name, creating the missing ones on demand
+struct CategoryIndex {
+    by_parent: HashMap<(Option<String>, String), String>,
+    slugs: HashSet<String>,
+    pending: Vec<Category>,
+}
+
+impl CategoryIndex {
+    fn new(categories: Vec<Category>, slugs: Vec<String>) -> Self {
+        let by_parent = categories
+            .into_iter()
+            .map(|c| ((c.parent_id, c.name.to_lowercase()), c.id))
+            .collect();
+        Self {
+            by_parent,
+            slugs: slugs.into_iter().collect(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Id of the category at `path` ("Clothing > T-shirts")
+    fn resolve(&mut self, path: &str, shop_id: &str) -> Option<String> {
+        let mut parent_id: Option<String> = None;
+        for name in path.split('>').map(str::trim).filter(|n| !n.is_empty()) {
+            let key = (parent_id.clone(), name.to_lowercase());
+            let id = match self.by_parent.get(&key) {
+                Some(id) => id.clone(),
+                None => {
+                    let now = Utc::now();
+                    let category = Category {
+                        id: Uuid::new_v4().to_string(),
+                        shop_id: shop_id.to_string(),
+                        parent_id: parent_id.clone(),
+                        name: name.to_string(),
+                        slug: unique_slug(&slugify(name), &mut self.slugs),
+                        description: None,
+                        image_url: None,
+                        banner_url: None,
+                        r#type: Some("manual".to_string()),
+                        rules: Some("[]".to_string()),
+                        is_visible: true,
+                        sort_order: 0,
+                        seo_title: None,
+                        seo_description: None,
+                        template_suffix: None,
+                        metadata: Some("{}".to_string()),
+                        sync_status: Some("created".to_string()),
+                        created_at: now,
+                        updated_at: now,
+                    };
+                    let id = category.id.clone();
+                    self.by_parent.insert(key, id.clone());
+                    self.pending.push(category);
+                    id
+                }
+            };
+            parent_id = Some(id);
+        }
+        parent_id
+    }
+}
+
+/// Brands by name or slug, creating the missing ones on demand
+struct BrandIndex {
+    by_name: HashMap<String, String>,
+    slugs: HashSet<String>,
+    pending: Vec<Brand>,
+}
+
+impl BrandIndex {
+    fn new(brands: Vec<Brand>, slugs: Vec<String>) -> Self {
+        let mut by_name = HashMap::new();
+        for brand in brands {
+            by_name.insert(brand.slug.clone(), brand.id.clone());
+            by_name.insert(brand.name.to_lowercase(), brand.id);
+        }
+        Self {
+            by_name,
+            slugs: slugs.into_iter().collect(),
+            pending: Vec::new(),
+        }
+    }
+
+    fn resolve(&mut self, name: &str, shop_id: &str) -> String {
+        if let Some(id) = self.by_name.get(&name.to_lowercase()) {
+            return id.clone();
+        }
+        let now = Utc::now();
+        let brand = Brand {
+            id: Uuid::new_v4().to_string(),
+            shop_id: shop_id.to_string(),
+            name: name.to_string(),
+            slug: unique_slug(&slugify(name), &mut self.slugs),
+            logo_url: None,
+            banner_url: None,
+            description: None,
+            rich_description: None,
+            website_url: None,
+            status: "active".to_string(),
+            is_featured: false,
+            sort_order: 0,
+            seo_title: None,
+            seo_keywords: None,
+            metadata: None,
+            sync_status: "created".to_string(),
+            created_at: now,
+            updated_at: now,
+        };
+        let id = brand.id.clone();
+        self.by_name.insert(name.to_lowercase(), id.clone());
+        self.pending.push(brand);
+        id
+    }
+}
+
+struct CustomerRow {
+    row: usize,
+    id: String,
+    is_new: bool,
+    r#type: Option<String>,
+    email: Option<String>,
+    phone: Option<String>,
+    first_name: Option<String>,
+    last_name: Option<String>,
+    company_name: Option<String>,
+    tax_id: Option<String>,
+    tax_id_type: Option<String>,
+    state_tax_id: Option<String>,
+    status: Option<String>,
+    language: Option<String>,
+    currency: Option<String>,
+    tags: Option<String>,
+    accepts_marketing: Option<bool>,
+    notes: Option<String>,
+    address: Option<AddressRow>,
+}
+
+struct AddressRow {
+    r#type: String,
+    address1: String,
+    address2: Option<String>,
+    city: String,
+    province_code: Option<String>,
+    postal_code: String,
+    country_code: Option<String>,
+}
+
+impl CustomerRow {
+    fn into_new_customer(self, shop_id: &str) -> (Customer, Option<AddressRow>) {
+        let now = Utc::now();
+        let customer = Customer {
+            id: self.id,
+            shop_id: shop_id.to_string(),
+            r#type: self.r#type.unwrap_or_else(|| "individual".to_string()),
+            email: self.email,
+            phone: self.phone,
+            first_name: self.first_name,
+            last_name: self.last_name,
+            company_name: self.company_name,
+            tax_id: self.tax_id,
+            tax_id_type: self.tax_id_type,
+            state_tax_id: self.state_tax_id,
+            status: self.status.or(Some("active".to_string())),
+            currency: self.currency.or(Some("BRL".to_string())),
+            language: self.language.or(Some("pt".to_string())),
+            tags: self.tags,
+            accepts_marketing: self.accepts_marketing,
+            customer_group_id: None,
+            total_spent: Some(0.0),
+            orders_count: Some(0),
+            last_order_at: None,
+            notes: self.notes,
+            metadata: None,
+            custom_attributes: None,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+        (customer, self.address)
+    }
+
+    fn merge_into(self, mut customer: Customer) -> (Customer, Option<AddressRow>) {
+        if let Some(r#type) = self.r#type {
+            customer.r#type = r#type;
+        }
+        if self.email.is_some() {
+            customer.email = self.email;
+        }
+        if self.phone.is_some() {
+            customer.phone = self.phone;
+        }
+        if self.first_name.is_some() {
+            customer.first_name = self.first_name;
+        }
+        if self.last_name.is_some() {
+            customer.last_name = self.last_name;
+        }
+        if self.company_name.is_some() {
+            customer.company_name = self.company_name;
+        }
+        if self.tax_id.is_some() {
+            customer.tax_id = self.tax_id;
+        }
+        if self.tax_id_type.is_some() {
+            customer.tax_id_type = self.tax_id_type;
+        }
+        if self.state_tax_id.is_some() {
+            customer.state_tax_id = self.state_tax_id;
+        }
+        if self.status.is_some() {
+            customer.status = self.status;
+        }
+        if self.language.is_some() {
+            customer.language = self.language;
+        }
+        if self.currency.is_some() {
+            customer.currency = self.currency;
+        }
+        if self.tags.is_some() {
+            customer.tags = self.tags;
+        }
+        if self.accepts_marketing.is_some() {
+            customer.accepts_marketing = self.accepts_marketing;
+        }
+        if self.notes.is_some() {
+            customer.notes = self.notes;
+        }
+        customer.sync_status = Some("modified".to_string());
+        customer.updated_at = Some(Utc::now());
+        (customer, self.address)
+    }
+}
+
+impl AddressRow {
+    /// Whether an existing address is the one this row describes
+    fn matches(&self, address: &CustomerAddress) -> bool {
+        address.r#type.as_deref().unwrap_or("shipping") == self.r#type
+            && address.postal_code.as_deref().map(digits) == Some(digits(&self.postal_code))
+            && address
+                .address1
+                .as_deref()
+                .is_some_and(|a| a.trim().eq_ignore_ascii_case(&self.address1))
+    }
+
+    fn apply(
+        self,
+        customer: &Customer,
+        address: Option<CustomerAddress>,
+        is_default: bool,
+    ) -> CustomerAddress {
+        let now = Utc::now();
+        let mut address = address.unwrap_or_else(|| CustomerAddress {
+            id: Uuid::new_v4().to_string(),
+            customer_id: customer.id.clone(),
+            r#type: Some(self.r#type.clone()),
+            is_default: Some(is_default),
+            first_name: customer.first_name.clone(),
+            last_name: customer.last_name.clone(),
+            company: customer.company_name.clone(),
+            address1: None,
+            address2: None,
+            city: None,
+            province_code: None,
+            country_code: None,
+            postal_code: None,
+            phone: customer.phone.clone(),
+            metadata: None,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        });
+        address.address1 = Some(self.address1);
+        if self.address2.is_some() {
+            address.address2 = self.address2;
+        }
+        address.city = Some(self.city);
+        if self.province_code.is_some() {
+            address.province_code = self.province_code;
+        }
+        address.postal_code = Some(self.postal_code);
+        address.country_code = self
+            .country_code
+            .or(address.country_code)
+            .or(Some("BR".to_string()));
+        if address.sync_status.as_deref() != Some("created") {
+            address.sync_status = Some("modified".to_string());
+        }
+        address.updated_at = Some(now);
+        address
+    }
+}
+
+struct InventoryRow {
+    row: usize,
+    product_id: String,
+    location_id: String,
+    stock_status: String,
+    quantity: f64,
+}
+
+// ============================================================
+// Service
+// ============================================================
+
+pub struct ShopImportService {
+    pool: Arc<SqlitePool>,
+    shop_id: String,
+    repo: ShopDataTransferRepository,
+    product_repo: ShopProductRepository,
+    option_repo: ShopProductOptionRepository,
+    category_repo: ShopCategoryRepository,
+    brand_repo: ShopBrandRepository,
+    customer_repo: ShopCustomerRepository,
+    address_repo: ShopCustomerAddressRepository,
+    location_repo: ShopLocationRepository,
+    inventory_repo: ShopInventoryRepository,
+}
+
+impl ShopImportService {
+    pub fn new(pool: Arc<SqlitePool>, shop_id: String) -> Self {
+        Self {
+            repo: ShopDataTransferRepository::new(pool.clone()),
+            product_repo: ShopProductRepository::new(pool.clone(), shop_id.clone()),
+            option_repo: ShopProductOptionRepository::new(pool.clone()),
+            category_repo: ShopCategoryRepository::new(pool.clone(), shop_id.clone()),
+            brand_repo: ShopBrandRepository::new(pool.clone(), shop_id.clone()),
+            customer_repo: ShopCustomerRepository::new(pool.clone(), shop_id.clone()),
+            address_repo: ShopCustomerAddressRepository::new(pool.clone()),
+            location_repo: ShopLocationRepository::new(pool.clone(), shop_id.clone()),
+            inventory_repo: ShopInventoryRepository::new(pool.clone()),
+            pool,
+            shop_id,
+        }
+    }
+
+    pub fn pool(&self) -> Arc<SqlitePool> {
+        self.pool.clone()
+    }
+
+    pub fn list_fields(&self, entity: &str) -> Result<Vec<ImportField>, String> {
+        fields_for(entity).map(import_fields)
+    }
+
+    /// First rows of a file and the mapping its headers suggest
+    pub fn preview(&self, payload: PreviewImportDTO) -> Result<ImportPreview, String> {
+        let specs = fields_for(&payload.entity)?;
+        let table = self.read(
+            &payload.path,
+            payload.format.as_deref(),
+            payload.delimiter.as_deref(),
+            payload.sheet.as_deref(),
+        )?;
+
+        let limit = payload.limit.unwrap_or(DEFAULT_PREVIEW_ROWS);
+        Ok(ImportPreview {
+            suggested_mapping: suggest_mapping(&table.headers, specs)
+                .into_iter()
+                .map(|(header, field)| (header, field.to_string()))
+                .collect(),
+            total_rows: table.rows.len(),
+            rows: table
+                .rows
+                .into_iter()
+                .take(limit)
+                .map(|r| r.values)
+                .collect(),
+            headers: table.headers,
+            sheets: table.sheets,
+            fields: import_fields(specs),
+        })
+    }
+
+    fn read(
+        &self,
+        path: &str,
+        format: Option<&str>,
+        delimiter: Option<&str>,
+        sheet: Option<&str>,
+    ) -> Result<Table, String> {
+        let format = FileFormat::resolve(format, path)?;
+        read_table(path, format, parse_delimiter(delimiter)?, sheet)
+    }
+
+    /// Validate a file and, unless it is a dry run, write its valid rows
+    pub async fn import<F>(
+        &self,
+        payload: ImportFileDTO,
+        on_progress: F,
+    ) -> Result<ImportReport, String>
+    where
+        F: Fn(ImportProgress) + Send + Sync,
+    {
+        let specs = fields_for(&payload.entity)?;
+        let table = self.read(
+            &payload.path,
+            payload.format.as_deref(),
+            payload.delimiter.as_deref(),
+            payload.sheet.as_deref(),
+        )?;
+        let columns = ColumnMap::resolve(
+            &table.headers,
+            payload.mapping.as_ref(),
+            specs,
+            required_columns(&payload.entity),
+        )?;
+
+        let dry_run = payload.dry_run.unwrap_or(false);
+        let update_existing = payload.update_existing.unwrap_or(true);
+        let mut run = ImportRun {
+            report: ImportReport {
+                import_id: payload
+                    .import_id
+                    .unwrap_or_else(|| Uuid::new_v4().to_string()),
+                entity: payload.entity.clone(),
+                dry_run,
+                total_rows: table.rows.len(),
+                valid_rows: 0,
+                created: 0,
+                updated: 0,
+                skipped: 0,
+                failed: 0,
+                categories_created: 0,
+                brands_created: 0,
+                chunks_committed: 0,
+                completed: true,
+                errors: Vec::new(),
+            },
+            chunk_size: payload
+                .chunk_size
+                .unwrap_or(DEFAULT_CHUNK_SIZE)
+                .clamp(1, MAX_CHUNK_SIZE),
+            on_progress: &on_progress,
+        };
+
+        match payload.entity.as_str() {
+            "products" => {
+                self.import_products(&mut run, &table.rows, &columns, dry_run, update_existing)
+                    .await?
+            }
+            "customers" => {
+                self.import_customers(&mut run, &table.rows, &columns, dry_run, update_existing)
+                    .await?
+            }
+            _ => {
+                self.import_inventory(&mut run, &table.rows, &columns, dry_run, update_existing)
+                    .await?
+            }
+        }
+
+        let total = run.report.total_rows;
+        run.progress("finished", total, total);
+        Ok(run.report)
+    }
+
+    async fn begin(&self) -> Result<Transaction<'static, Sqlite>, String> {
+        self.pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))
+    }
+
+    // ============================================================
+    // Products
+    // ============================================================
+
+    async fn import_products<F: Fn(ImportProgress)>(
+        &self,
+        run: &mut ImportRun<'_, F>,
+        rows: &[TableRow],
+        columns: &ColumnMap,
+        dry_run: bool,
+        update_existing: bool,
+    ) -> Result<(), String> {
+        let keys = self
+            .repo
+            .product_keys()
+            .await
+            .map_err(|e| format!("Failed to load products: {}", e))?;
+        let categories = self
+            .category_repo
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list categories: {}", e))?;
+        let category_slugs = self
+            .repo
+            .category_slugs()
+            .await
+            .map_err(|e| format!("Failed to load categories: {}", e))?;
+        let brands = self
+            .brand_repo
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list brands: {}", e))?;
+        let brand_slugs = self
+            .repo
+            .brand_slugs()
+            .await
+            .map_err(|e| format!("Failed to load brands: {}", e))?;
+
+        let mut slugs: HashSet<String> = keys.iter().map(|k| k.slug.clone()).collect();
+        let mut skus: HashMap<String, SkuEntry> = keys
+            .into_iter()
+            .map(|key: ProductKey| (key.sku.to_lowercase(), SkuEntry::from(key)))
+            .collect();
+        let mut category_index = CategoryIndex::new(categories, category_slugs);
+        let mut brand_index = BrandIndex::new(brands, brand_slugs);
+
+        let mut plan: Vec<ProductRow> = Vec::new();
+        for (index, table_row) in rows.iter().enumerate() {
+            let mut reader = RowReader::new(columns, &table_row.values, table_row.number);
+            let planned = self.plan_product(
+                &mut reader,
+                &mut skus,
+                &mut slugs,
+                &mut category_index,
+                &mut brand_index,
+                update_existing,
+            );
+            match planned {
+                Some(Some(row)) if reader.is_valid() => plan.push(row),
+                Some(None) if reader.is_valid() => run.report.skipped += 1,
+                _ => run.reject(reader.errors),
+            }
+            run.validated(index, rows.len());
+        }
+
+        run.report.valid_rows = plan.len() + run.report.skipped;
+        if dry_run {
+            run.report.created = plan.iter().filter(|r| r.is_new).count();
+            run.report.updated = plan.len() - run.report.created;
+            run.report.categories_created = category_index.pending.len();
+            run.report.brands_created = brand_index.pending.len();
+            return Ok(());
+        }
+
+        let total = plan.len();
+        let mut processed = 0;
+        let mut parent_options: HashMap<String, Vec<(String, Vec<String>)>> = HashMap::new();
+        let mut chunks = Vec::new();
+        let mut remaining = plan.into_iter().peekable();
+        while remaining.peek().is_some() {
+            chunks.push(remaining.by_ref().take(run.chunk_size).collect::<Vec<_>>());
+        }
+
+        for (index, chunk) in chunks.into_iter().enumerate() {
+            let chunk_len = chunk.len();
+            let first_row = chunk.first().map(|r| r.row).unwrap_or_default();
+
+            // Reads happen before the transaction opens
+            let existing_ids: Vec<String> = chunk
+                .iter()
+                .filter(|r| !r.is_new)
+                .map(|r| r.id.clone())
+                .collect();
+            let mut existing: HashMap<String, Product> = self
+                .product_repo
+                .list_by_ids(&existing_ids)
+                .await
+                .map_err(|e| format!("Failed to fetch products: {}", e))?
+                .into_iter()
+                .map(|p| (p.id.clone(), p))
+                .collect();
+            let mut touched_parents: Vec<String> = Vec::new();
+            for row in &chunk {
+                if let (Some(parent_id), Some(options)) = (&row.parent_id, &row.options) {
+                    if !parent_options.contains_key(parent_id) {
+                        let current = self
+                            .option_repo
+                            .list_by_product(parent_id)
+                            .await
+                            .map_err(|e| format!("Failed to list product options: {}", e))?
+                            .into_iter()
+                            .map(|o| {
+                                let values = serde_json::from_str::<Vec<String>>(&o.option_values)
+                                    .unwrap_or_default();
+                                (o.name, values)
+                            })
+                            .collect();
+                        parent_options.insert(parent_id.clone(), current);
+                    }
+                    let axes = parent_options
+                        .get_mut(parent_id)
+                        .expect("options loaded above");
+                    for (name, value) in options {
+                        match axes.iter_mut().find(|(n, _)| n.eq_ignore_ascii_case(name)) {
+                            Some((_, values)) => {
+                                if !values.iter().any(|v| v.eq_ignore_ascii_case(value)) {
+                                    values.push(value.clone());
+                                }
+                            }
+                            None => axes.push((name.clone(), vec![value.clone()])),
+                        }
+                    }
+                    if !touched_parents.contains(parent_id) {
+                        touched_parents.push(parent_id.clone());
+                    }
+                }
+            }
+
+            let mut tx = self.begin().await?;
+            let mut created = 0;
+            let mut failure: Option<(usize, String)> = None;
+
+            if index == 0 {
+                for category in &category_index.pending {
+                    if let Err(e) = self.category_repo.create_in_tx(&mut tx, category).await {
+                        failure = Some((
+                            first_row,
+                            format!("Failed to create category {}: {}", category.name, e),
+                        ));
+                        break;
+                    }
+                }
+                for brand in &brand_index.pending {
+                    if failure.is_some() {
+                        break;
+                    }
+                    if let Err(e) = self.brand_repo.create_in_tx(&mut tx, brand).await {
+                        failure = Some((
+                            first_row,
+                            format!("Failed to create brand {}: {}", brand.name, e),
+                        ));
+                    }
+                }
+            }
+
+            for row in chunk {
+                if failure.is_some() {
+                    break;
+                }
+                let row_number = row.row;
+                let sku = row.sku.clone();
+                let result = if row.is_new {
+                    created += 1;
+                    let product = row.into_new_product(&self.shop_id);
+                    self.product_repo
+                        .create_in_tx(&mut tx, &product)
+                        .await
+                        .map(|_| ())
+                        .map_err(|e| format!("Failed to create product {}: {}", sku, e))
+                } else {
+                    match existing.remove(&row.id) {
+                        Some(current) => {
+                            let prices = (current.price, current.promotional_price);
+                            let product = row.merge_into(current);
+                            let repriced = prices != (product.price, product.promotional_price);
+                            self.update_product_in_tx(&mut tx, product, repriced).await
+                        }
+                        None => Err(format!("Product {} no longer exists", sku)),
+                    }
+                };
+                if let Err(e) = result {
+                    failure = Some((row_number, e));
+                }
+            }
+
+            if failure.is_none() {
+                for parent_id in &touched_parents {
+                    let options = SetProductOptionsDTO {
+                        product_id: parent_id.clone(),
+                        options: parent_options[parent_id]
+                            .iter()
+                            .map(|(name, values)| ProductOptionDTO {
+                                name: name.clone(),
+                                values: values.clone(),
+                            })
+                            .collect(),
+                    }
+                    .into_models();
+                    if let Err(e) =
+                        ShopProductOptionRepository::replace_in_tx(&mut tx, parent_id, &options)
+                            .await
+                    {
+                        failure =
+                            Some((first_row, format!("Failed to save product options: {}", e)));
+                        break;
+                    }
+                }
+            }
+
+            if let Some((row, message)) = failure {
+                let _ = tx.rollback().await;
+                run.abort(row, message);
+                return Ok(());
+            }
+            tx.commit()
+                .await
+                .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+            if index == 0 {
+                run.report.categories_created = category_index.pending.len();
+                run.report.brands_created = brand_index.pending.len();
+            }
+            run.report.created += created;
+            run.report.updated += chunk_len - created;
+            run.report.chunks_committed += 1;
+            processed += chunk_len;
+            run.progress("importing", processed, total);
+        }
+        Ok(())
+    }
+
+    /// Save an updated product, recording price changes in the price history
+    async fn update_product_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        product: Product,
+        repriced: bool,
+    ) -> Result<(), String> {
+        if repriced {
+            ShopPriceHistoryService::ensure_open_in_tx(tx, &product.id).await?;
+        }
+        self.product_repo
+            .update_in_tx(tx, &product)
+            .await
+            .map_err(|e| format!("Failed to update product {}: {}", product.sku, e))?;
+        if repriced {
+            ShopPriceHistoryService::record_in_tx(tx, &product.id, "bulk", None, None).await?;
+        }
+        Ok(())
+    }
+
+    /// Validate a product row. Returns None for a row that matches an
+    /// existing product when updates are off.
+    fn plan_product(
+        &self,
+        reader: &mut RowReader<'_>,
+        skus: &mut HashMap<String, SkuEntry>,
+        slugs: &mut HashSet<String>,
+        categories: &mut CategoryIndex,
+        brands: &mut BrandIndex,
+        update_existing: bool,
+    ) -> Option<Option<ProductRow>> {
+        let sku = match reader.text("sku") {
+            Some(sku) => sku,
+            None => {
+                reader.error(Some("sku"), "SKU is required");
+                return None;
+            }
+        };
+
+        let existing = skus.get(&sku.to_lowercase());
+        if let Some(entry) = existing {
+            if let Some(row) = entry.row {
+                reader.error(
+                    Some("sku"),
+                    format!("SKU {} already appears on row {}", sku, row),
+                );
+                return None;
+            }
+            if entry.deleted {
+                reader.error(
+                    Some("sku"),
+                    format!("SKU {} belongs to a deleted product", sku),
+                );
+                return None;
+            }
+            if !update_existing {
+                return Some(None);
+            }
+        }
+        let existing_id = existing.map(|e| e.id.clone());
+        let existing_slug = existing.map(|e| e.slug.clone());
+        let existing_is_variant = existing.is_some_and(|e| e.is_variant);
+        let is_new = existing_id.is_none();
+
+        let name = reader.text("name");
+        let price = reader.amount("price");
+        if is_new && name.is_none() {
+            reader.error(Some("name"), "Name is required for new products");
+        }
+        if is_new && price.is_none() && reader.is_valid() {
+            reader.error(Some("price"), "Price is required for new products");
+        }
+
+        let r#type = reader.choice("type", &PRODUCT_TYPES);
+        let currency = reader.text("currency").map(|c| c.to_uppercase());
+        if currency
+            .as_deref()
+            .is_some_and(|c| c.len() != 3 || !c.chars().all(|ch| ch.is_ascii_alphabetic()))
+        {
+            reader.error(Some("currency"), "Currency must be a 3-letter code");
+        }
+        let gtin_ean = reader.text("gtin_ean");
+        if let Some(code) = &gtin_ean {
+            if let Err(e) = gtin::validate_gtin(code) {
+                reader.error(Some("gtin_ean"), e);
+            }
+        }
+
+        let options = match reader.text("options") {
+            Some(raw) => match parse_options(&raw) {
+                Ok(options) => Some(options),
+                Err(e) => {
+                    reader.error(Some("options"), e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let parent_id = match reader.text("parent_sku") {
+            Some(parent_sku) if parent_sku.eq_ignore_ascii_case(&sku) => {
+                reader.error(Some("parent_sku"), "A product cannot be its own parent");
+                None
+            }
+            Some(parent_sku) => match skus.get(&parent_sku.to_lowercase()) {
+                Some(parent) if parent.deleted => {
+                    reader.error(
+                        Some("parent_sku"),
+                        format!("Parent {} is deleted", parent_sku),
+                    );
+                    None
+                }
+                Some(parent) if parent.is_variant => {
+                    reader.error(
+                        Some("parent_sku"),
+                        format!("Parent {} is itself a variant", parent_sku),
+                    );
+                    None
+                }
+                Some(parent) => Some(parent.id.clone()),
+                None => {
+                    reader.error(
+                        Some("parent_sku"),
+                        format!(
+                            "Parent {} not found in the shop or on an earlier row",
+                            parent_sku
+                        ),
+                    );
+                    None
+                }
+            },
+            None => None,
+        };
+        if options.is_some() && parent_id.is_none() && !existing_is_variant && reader.is_valid() {
+            reader.error(
+                Some("options"),
+                "Options are only allowed on variants (set parent_sku)",
+            );
+        }
+
+        let mut row = ProductRow {
+            row: reader.row,
+            id: existing_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            is_new,
+            sku: sku.clone(),
+            slug: None,
+            status: reader.text("status"),
+            promotional_price: reader.amount("promotional_price"),
+            cost_price: reader.amount("cost_price"),
+            tax_ncm: reader.text("tax_ncm"),
+            is_shippable: reader.boolean("is_shippable"),
+            weight_g: reader.count("weight_g"),
+            width_mm: reader.count("width_mm"),
+            height_mm: reader.count("height_mm"),
+            depth_mm: reader.count("depth_mm"),
+            category_id: None,
+            brand_id: None,
+            name,
+            r#type,
+            price,
+            currency,
+            gtin_ean,
+            parent_id,
+            options,
+        };
+
+        let slug = reader
+            .text("slug")
+            .map(|s| slugify(&s))
+            .filter(|s| !s.is_empty());
+        if !reader.is_valid() {
+            return None;
+        }
+
+        // Only valid rows claim slugs, SKUs, categories and brands
+        row.slug = match slug {
+            Some(slug) if existing_slug.as_deref() == Some(slug.as_str()) => None,
+            Some(slug) if slugs.contains(&slug) => {
+                reader.error(Some("slug"), format!("Slug {} is already taken", slug));
+                return None;
+            }
+            Some(slug) => {
+                slugs.insert(slug.clone());
+                Some(slug)
+            }
+            None if is_new => Some(unique_slug(
+                &slugify(row.name.as_deref().unwrap_or(&sku)),
+                slugs,
+            )),
+            None => None,
+        };
+        row.category_id = reader
+            .text("category")
+            .and_then(|path| categories.resolve(&path, &self.shop_id));
+        row.brand_id = reader
+            .text("brand")
+            .map(|name| brands.resolve(&name, &self.shop_id));
+
+        if is_new {
+            skus.insert(
+                sku.to_lowercase(),
+                SkuEntry {
+                    id: row.id.clone(),
+                    row: Some(row.row),
+                    is_variant: row.parent_id.is_some(),
+                    deleted: false,
+                    r#type: row.r#type.clone().unwrap_or_else(|| "physical".to_string()),
+                    slug: row.slug.clone().unwrap_or_default(),
+                },
+            );
+        } else if let Some(entry) = skus.get_mut(&sku.to_lowercase()) {
+            entry.row = Some(row.row);
+            entry.is_variant = entry.is_variant || row.parent_id.is_some();
+        }
+        Some(Some(row))
+    }
+
+    // ============================================================
+    // Customers
+    // ============================================================
+
+    async fn import_customers<F: Fn(ImportProgress)>(
+        &self,
+        run: &mut ImportRun<'_, F>,
+        rows: &[TableRow],
+        columns: &ColumnMap,
+        dry_run: bool,
+        update_existing: bool,
+    ) -> Result<(), String> {
+        let keys = self
+            .repo
+            .customer_keys()
+            .await
+            .map_err(|e| format!("Failed to load customers: {}", e))?;
+
+        // Keys are (id, deleted, file row)
+        let mut by_email: HashMap<String, (String, bool, Option<usize>)> = HashMap::new();
+        let mut by_tax_id: HashMap<String, (String, bool, Option<usize>)> = HashMap::new();
+        for key in keys {
+            let deleted = is_deleted(key.sync_status.as_deref());
+            if let Some(email) = key
+                .email
+                .as_deref()
+                .map(|e| e.trim().to_lowercase())
+                .filter(|e| !e.is_empty())
+            {
+                by_email.insert(email, (key.id.clone(), deleted, None));
+            }
+            if let Some(tax_id) = key.tax_id.as_deref().map(digits).filter(|t| !t.is_empty()) {
+                by_tax_id.insert(tax_id, (key.id.clone(), deleted, None));
+            }
+        }
+
+        let mut plan: Vec<CustomerRow> = Vec::new();
+        for (index, table_row) in rows.iter().enumerate() {
+            let mut reader = RowReader::new(columns, &table_row.values, table_row.number);
+            let planned =
+                plan_customer(&mut reader, &mut by_email, &mut by_tax_id, update_existing);
+            match planned {
+                Some(Some(row)) if reader.is_valid() => plan.push(row),
+                Some(None) if reader.is_valid() => run.report.skipped += 1,
+                _ => run.reject(reader.errors),
+            }
+            run.validated(index, rows.len());
+        }
+
+        run.report.valid_rows = plan.len() + run.report.skipped;
+        if dry_run {
+            run.report.created = plan.iter().filter(|r| r.is_new).count();
+            run.report.updated = plan.len() - run.report.created;
+            return Ok(());
+        }
+
+        let total = plan.len();
+        let mut processed = 0;
+        let mut remaining = plan.into_iter().peekable();
+        while remaining.peek().is_some() {
+            let chunk: Vec<CustomerRow> = remaining.by_ref().take(run.chunk_size).collect();
+            let chunk_len = chunk.len();
+
+            let existing_ids: Vec<String> = chunk
+                .iter()
+                .filter(|r| !r.is_new)
+                .map(|r| r.id.clone())
+                .collect();
+            let mut existing: HashMap<String, Customer> = self
+                .customer_repo
+                .list_by_ids(&existing_ids)
+                .await
+                .map_err(|e| format!("Failed to fetch customers: {}", e))?
+                .into_iter()
+                .map(|c| (c.id.clone(), c))
+                .collect();
+            let mut addresses: HashMap<String, Vec<CustomerAddress>> = HashMap::new();
+            for row in chunk.iter().filter(|r| !r.is_new && r.address.is_some()) {
+                let list = self
+                    .address_repo
+                    .list_by_customer(&row.id)
+                    .await
+                    .map_err(|e| format!("Failed to list customer addresses: {}", e))?;
+                addresses.insert(row.id.clone(), list);
+            }
+
+            let mut tx = self.begin().await?;
+            let mut created = 0;
+            let mut failure: Option<(usize, String)> = None;
+            for row in chunk {
+                let row_number = row.row;
+                let id = row.id.clone();
+                let result = if row.is_new {
+                    created += 1;
+                    let (customer, address) = row.into_new_customer(&self.shop_id);
+                    match self.customer_repo.create_in_tx(&mut tx, &customer).await {
+                        Ok(_) => {
+                            self.save_address_in_tx(&mut tx, &customer, address, Vec::new())
+                                .await
+                        }
+                        Err(e) => Err(format!("Failed to create customer: {}", e)),
+                    }
+                } else {
+                    match existing.remove(&id) {
+                        Some(current) => {
+                            let (customer, address) = row.merge_into(current);
+                            match self.customer_repo.update_in_tx(&mut tx, &customer).await {
+                                Ok(_) => {
+                                    let current = addresses.remove(&id).unwrap_or_default();
+                                    self.save_address_in_tx(&mut tx, &customer, address, current)
+                                        .await
+                                }
+                                Err(e) => Err(format!("Failed to update customer: {}", e)),
+                            }
+                        }
+                        None => Err(format!("Customer {} no longer exists", id)),
+                    }
+                };
+                if let Err(e) = result {
+                    failure = Some((row_number, e));
+                    break;
+                }
+            }
+
+            if let Some((row, message)) = failure {
+                let _ = tx.rollback().await;
+                run.abort(row, message);
+                return Ok(());
+            }
+            tx.commit()
+                .await
+                .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+            run.report.created += created;
+            run.report.updated += chunk_len - created;
+            run.report.chunks_committed += 1;
+            processed += chunk_len;
+            run.progress("importing", processed, total);
+        }
+        Ok(())
+    }
+
+    /// Update the matching address of the customer, or add it (as the
+    /// default when the customer has none of that type)
+    async fn save_address_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        customer: &Customer,
+        address: Option<AddressRow>,
+        current: Vec<CustomerAddress>,
+    ) -> Result<(), String> {
+        let Some(address) = address else {
+            return Ok(());
+        };
+        let has_default = current
+            .iter()
+            .any(|a| a.r#type.as_deref().unwrap_or("shipping") == address.r#type);
+        match current.into_iter().find(|a| address.matches(a)) {
+            Some(found) => {
+                let updated = address.apply(customer, Some(found), false);
+                self.address_repo
+                    .update_in_tx(tx, &updated)
+                    .await
+                    .map_err(|e| format!("Failed to update customer address: {}", e))?;
+            }
+            None => {
+                let created = address.apply(customer, None, !has_default);
+                self.address_repo
+                    .create_in_tx(tx, &created)
+                    .await
+                    .map_err(|e| format!("Failed to create customer address: {}", e))?;
+            }
+        }
+        Ok(())
+    }
+
+    // ============================================================
+    // Inventory
+    // ============================================================
+
+    async fn import_inventory<F: Fn(ImportProgress)>(
+        &self,
+        run: &mut ImportRun<'_, F>,
+        rows: &[TableRow],
+        columns: &ColumnMap,
+        dry_run: bool,
+        update_existing: bool,
+    ) -> Result<(), String> {
+        let skus: HashMap<String, SkuEntry> = self
+            .repo
+            .product_keys()
+            .await
+            .map_err(|e| format!("Failed to load products: {}", e))?
+            .into_iter()
+            .map(|key| (key.sku.to_lowercase(), SkuEntry::from(key)))
+            .collect();
+        let locations = self
+            .location_repo
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list locations: {}", e))?;
+        let levels: HashSet<(String, String, String)> = self
+            .inventory_repo
+            .list_levels()
+            .await
+            .map_err(|e| format!("Failed to list inventory levels: {}", e))?
+            .into_iter()
+            .filter(|l| l.batch_number.is_none() && l.serial_number.is_none())
+            .map(|l| {
+                let status = l.stock_status.unwrap_or_else(|| "sellable".to_string());
+                (l.product_id, l.location_id, status)
+            })
+            .collect();
+
+        let mut seen: HashMap<(String, String, String), usize> = HashMap::new();
+        let mut plan: Vec<(InventoryRow, bool)> = Vec::new();
+        for (index, table_row) in rows.iter().enumerate() {
+            let mut reader = RowReader::new(columns, &table_row.values, table_row.number);
+
+            let product_id = match reader.text("sku") {
+                Some(sku) => match skus.get(&sku.to_lowercase()) {
+                    Some(entry) if entry.deleted => {
+                        reader.error(
+                            Some("sku"),
+                            format!("SKU {} belongs to a deleted product", sku),
+                        );
+                        None
+                    }
+                    Some(entry) if entry.r#type == "bundle" => {
+                        reader.error(
+                            Some("sku"),
+                            format!("Bundle {} has no stock of its own", sku),
+                        );
+                        None
+                    }
+                    Some(entry) => Some(entry.id.clone()),
+                    None => {
+                        reader.error(Some("sku"), format!("Product not found: {}", sku));
+                        None
+                    }
+                },
+                None => {
+                    reader.error(Some("sku"), "SKU is required");
+                    None
+                }
+            };
+            let location_id = match reader.text("location") {
+                Some(location) => {
+                    let found = locations
+                        .iter()
+                        .find(|l| l.id == location || l.name.eq_ignore_ascii_case(&location));
+                    if found.is_none() {
+                        reader.error(
+                            Some("location"),
+                            format!("Location not found: {}", location),
+                        );
+                    }
+                    found.map(|l| l.id.clone())
+                }
+                None => {
+                    reader.error(Some("location"), "Location is required");
+                    None
+                }
+            };
+            let quantity = reader.amount("quantity");
+            if quantity.is_none() && reader.is_valid() {
+                reader.error(Some("quantity"), "Quantity is required");
+            }
+            let stock_status = reader
+                .choice("stock_status", &STOCK_STATUSES)
+                .unwrap_or_else(|| "sellable".to_string());
+
+            if let (Some(product_id), Some(location_id), Some(quantity)) =
+                (product_id, location_id, quantity)
+            {
+                if reader.is_valid() {
+                    let key = (product_id, location_id, stock_status);
+                    if let Some(first) = seen.get(&key) {
+                        reader.error(
+                            None,
+                            format!("Same product, location and status as row {}", first),
+                        );
+                    } else {
+                        seen.insert(key.clone(), reader.row);
+                        let exists = levels.contains(&key);
+                        if exists && !update_existing {
+                            run.report.skipped += 1;
+                        } else {
+                            let (product_id, location_id, stock_status) = key;
+                            plan.push((
+                                InventoryRow {
+                                    row: reader.row,
+                                    product_id,
+                                    location_id,
+                                    stock_status,
+                                    quantity,
+                                },
+                                !exists,
+                            ));
+                        }
+                    }
+                }
+            }
+            if !reader.is_valid() {
+                run.reject(reader.errors);
+            }
+            run.validated(index, rows.len());
+        }
+
+        run.report.valid_rows = plan.len() + run.report.skipped;
+        if dry_run {
+            run.report.created = plan.iter().filter(|(_, is_new)| *is_new).count();
+            run.report.updated = plan.len() - run.report.created;
+            return Ok(());
+        }
+
+        let total = plan.len();
+        let mut processed = 0;
+        for chunk in plan.chunks(run.chunk_size) {
+            let mut tx = self.begin().await?;
+            let mut created = 0;
+            let mut failure: Option<(usize, String)> = None;
+            for (row, _) in chunk {
+                match set_stock_in_tx(&mut tx, row).await {
+                    Ok(was_created) => created += usize::from(was_created),
+                    Err(e) => {
+                        failure = Some((row.row, e));
+                        break;
+                    }
+                }
+            }
+
+            if let Some((row, message)) = failure {
+                let _ = tx.rollback().await;
+                run.abort(row, message);
+                return Ok(());
+            }
+            tx.commit()
+                .await
+                .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+            run.report.created += created;
+            run.report.updated += chunk.len() - created;
+            run.report.chunks_committed += 1;
+            processed += chunk.len();
+            run.progress("importing", processed, total);
+        }
+        Ok(())
+    }
+}
+
+/// Validate a customer row. Returns None for a row that matches an existing
+/// customer when updates are off.
+fn plan_customer(
+    reader: &mut RowReader<'_>,
+    by_email: &mut HashMap<String, (String, bool, Option<usize>)>,
+    by_tax_id: &mut HashMap<String, (String, bool, Option<usize>)>,
+    update_existing: bool,
+) -> Option<Option<CustomerRow>> {
+    let email = reader.text("email").map(|e| e.to_lowercase());
+    if email.as_deref().is_some_and(|e| !is_valid_email(e)) {
+        reader.error(Some("email"), "Invalid e-mail");
+    }
+    let tax_id = reader.text("tax_id");
+    let tax_digits = tax_id.as_deref().map(digits).filter(|d| !d.is_empty());
+    if tax_id.is_some() && tax_digits.is_none() {
+        reader.error(Some("tax_id"), "Tax ID must contain digits");
+    }
+
+    let email_match = email.as_ref().and_then(|e| by_email.get(e)).cloned();
+    let tax_match = tax_digits.as_ref().and_then(|t| by_tax_id.get(t)).cloned();
+    for (column, found) in [("email", &email_match), ("tax_id", &tax_match)] {
+        if let Some((_, deleted, row)) = found {
+            if let Some(row) = row {
+                reader.error(Some(column), format!("Same {} as row {}", column, row));
+            } else if *deleted {
+                reader.error(
+                    Some(column),
+                    format!("The {} belongs to a deleted customer", column),
+                );
+            }
+        }
+    }
+    if let (Some((email_id, ..)), Some((tax_id_id, ..))) = (&email_match, &tax_match) {
+        if email_id != tax_id_id {
+            reader.error(
+                None,
+                "The e-mail and the tax ID belong to different customers",
+            );
+        }
+    }
+    if !reader.is_valid() {
+        return None;
+    }
+
+    let existing_id = email_match.or(tax_match).map(|(id, ..)| id);
+    if existing_id.is_some() && !update_existing {
+        return Some(None);
+    }
+    let is_new = existing_id.is_none();
+
+    let first_name = reader.text("first_name");
+    let company_name = reader.text("company_name");
+    if is_new && first_name.is_none() && company_name.is_none() {
+        reader.error(
+            Some("first_name"),
+            "A first name or company name is required for new customers",
+        );
+    }
+
+    let mut r#type = reader.choice("type", &CUSTOMER_TYPES);
+    let mut tax_id_type = reader.text("tax_id_type").map(|t| t.to_lowercase());
+    match tax_digits.as_deref().map(str::len) {
+        Some(11) => {
+            tax_id_type = tax_id_type.or(Some("cpf".to_string()));
+        }
+        Some(14) => {
+            tax_id_type = tax_id_type.or(Some("cnpj".to_string()));
+            if is_new {
+                r#type = r#type.or(Some("company".to_string()));
+            }
+        }
+        _ => {}
+    }
+
+    let address = if ["address1", "city", "postal_code"]
+        .iter()
+        .any(|f| reader.text(f).is_some())
+    {
+        let address1 = reader.text("address1");
+        let city = reader.text("city");
+        let postal_code = reader.text("postal_code");
+        let r#type = reader
+            .choice("address_type", &ADDRESS_TYPES)
+            .unwrap_or_else(|| "shipping".to_string());
+        for (field, value) in [
+            ("address1", &address1),
+            ("city", &city),
+            ("postal_code", &postal_code),
+        ] {
+            if value.is_none() {
+                reader.error(Some(field), format!("{} is required for an address", field));
+            }
+        }
+        match (address1, city, postal_code) {
+            (Some(address1), Some(city), Some(postal_code)) => Some(AddressRow {
+                r#type,
+                address1,
+                address2: reader.text("address2"),
+                city,
+                province_code: reader.text("province_code").map(|p| p.to_uppercase()),
+                postal_code,
+                country_code: reader.text("country_code").map(|c| c.to_uppercase()),
+            }),
+            _ => None,
+        }
+    } else {
+        None
+    };
+
+    let row = CustomerRow {
+        row: reader.row,
+        id: existing_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+        is_new,
+        r#type,
+        phone: reader.text("phone"),
+        last_name: reader.text("last_name"),
+        state_tax_id: reader.text("state_tax_id"),
+        status: reader.choice("status", &CUSTOMER_STATUSES),
+        language: reader.text("language"),
+        currency: reader.text("currency").map(|c| c.to_uppercase()),
+        tags: reader.text("tags"),
+        accepts_marketing: reader.boolean("accepts_marketing"),
+        notes: reader.text("notes"),
+        email,
+        first_name,
+        company_name,
+        tax_id,
+        tax_id_type,
+        address,
+    };
+    if !reader.is_valid() {
+        return None;
+    }
+
+    if let Some(email) = &row.email {
+        by_email.insert(email.clone(), (row.id.clone(), false, Some(row.row)));
+    }
+    if let Some(tax_digits) = tax_digits {
+        by_tax_id.insert(tax_digits, (row.id.clone(), false, Some(row.row)));
+    }
+    Some(Some(row))
+}
+
+/// Set the quantity on hand of a level, creating the level when missing.
+/// The difference is recorded as an adjustment movement, whose trigger
+/// updates the level. Returns whether the level was created.
+async fn set_stock_in_tx(
+    tx: &mut Transaction<'_, Sqlite>,
+    row: &InventoryRow,
+) -> Result<bool, String> {
+    let now = Utc::now();
+    let existing = ShopInventoryRepository::find_level_by_stock_status_in_tx(
+        tx,
+        &row.product_id,
+        &row.location_id,
+        &row.stock_status,
+    )
+    .await
+    .map_err(|e| format!("Failed to fetch inventory level: {}", e))?;
+
+    let created = existing.is_none();
+    let level = match existing {
+        Some(level) => level,
+        None => {
+            let level = InventoryLevel {
+                id: Uuid::new_v4().to_string(),
+                product_id: row.product_id.clone(),
+                location_id: row.location_id.clone(),
+                batch_number: None,
+                serial_number: None,
+                expiry_date: None,
+                quantity_on_hand: 0.0,
+                quantity_reserved: 0.0,
+                stock_status: Some(row.stock_status.clone()),
+                aisle_bin_slot: None,
+                last_counted_at: None,
+                sync_status: Some("created".to_string()),
+                created_at: Some(now),
+                updated_at: Some(now),
+            };
+            ShopInventoryRepository::create_level_in_tx(tx, &level)
+                .await
+                .map_err(|e| format!("Failed to create inventory level: {}", e))?
+        }
+    };
+
+    let difference = row.quantity - level.quantity_on_hand;
+    if difference.abs() >= 0.001 {
+        let (movement_type, quantity) = if difference > 0.0 {
+            ("in", difference)
+        } else {
+            ("out", difference.abs())
+        };
+        let movement = InventoryMovement {
+            id: Uuid::new_v4().to_string(),
+            transaction_id: None, // Adjustment doesn't have a transaction
+            inventory_level_id: Some(level.id.clone()),
+            movement_type: Some(movement_type.to_string()),
+            quantity,
+            previous_balance: Some(level.quantity_on_hand),
+            new_balance: Some(row.quantity),
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+
+        // The trigger 'trg_inventory_movement_update_level' updates quantity_on_hand
+        InventoryMovementsRepository::create_with_tx(tx, movement)
+            .await
+            .map_err(|e| format!("Failed to create inventory movement: {}", e))?;
+    }
+    Ok(created)
+}
@@ -0,0 +1,305 @@
+//! Reading and writing tabular files (CSV and spreadsheets)
+
+use calamine::{open_workbook_auto_from_rs, Data, Reader};
+use rust_xlsxwriter::{Format, Workbook};
+use std::io::Cursor;
+use std::path::Path;
+
+/// Delimiters considered when detecting the delimiter of a CSV file
+const CANDIDATE_DELIMITERS: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Csv,
+    Xlsx, // Any spreadsheet on import (xlsx, xls, ods)
+}
+
+impl FileFormat {
+    /// The explicit format, or the one implied by the file extension
+    pub fn resolve(format: Option<&str>, path: &str) -> Result<Self, String> {
+        let format = match format {
+            Some(format) => format.to_lowercase(),
+            None => Path::new(path)
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(str::to_lowercase)
+                .ok_or_else(|| format!("Cannot tell the format of {}", path))?,
+        };
+        match format.as_str() {
+            "csv" | "txt" => Ok(Self::Csv),
+            "xlsx" | "xlsm" | "xls" | "ods" => Ok(Self::Xlsx),
+            other => Err(format!("Unsupported file format: {}", other)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Xlsx => "xlsx",
+        }
+    }
+}
+
+/// Parse a delimiter option: a single character, or "tab"
+pub fn parse_delimiter(value: Option<&str>) -> Result<Option<u8>, String> {
+    match value {
+        None | Some("") => Ok(None),
+        Some("tab") | Some("\\t") | Some("\t") => Ok(Some(b'\t')),
+        Some(value) if value.len() == 1 && value.is_ascii() => Ok(Some(value.as_bytes()[0])),
+        Some(value) => Err(format!("Invalid delimiter: {}", value)),
+    }
+}
+
+/// A record of the file with its spreadsheet row number
+#[derive(Debug, Clone)]
+pub struct TableRow {
+    pub number: usize,
+    pub values: Vec<String>,
+}
+
+/// Headers and records of a file; blank records are dropped
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<TableRow>,
+    pub sheets: Vec<String>,
+}
+
+impl Table {
+    fn from_records(records: Vec<Vec<String>>, sheets: Vec<String>) -> Result<Self, String> {
+        let mut records = records.into_iter().enumerate();
+        let headers = records
+            .next()
+            .map(|(_, headers)| headers.into_iter().map(|h| h.trim().to_string()).collect())
+            .ok_or_else(|| "The file is empty".to_string())?;
+
+        let rows = records
+            .filter(|(_, values)| values.iter().any(|v| !v.trim().is_empty()))
+            .map(|(index, values)| TableRow {
+                number: index + 1,
+                values,
+            })
+            .collect();
+
+        Ok(Self {
+            headers,
+            rows,
+            sheets,
+        })
+    }
+}
+
+pub fn read_table(
+    path: &str,
+    format: FileFormat,
+    delimiter: Option<u8>,
+    sheet: Option<&str>,
+) -> Result<Table, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    match format {
+        FileFormat::Csv => read_csv(&decode_text(&bytes), delimiter),
+        FileFormat::Xlsx => read_spreadsheet(bytes, sheet),
+    }
+}
+
+/// UTF-8 without its BOM; anything else is taken as Latin-1, the encoding
+/// spreadsheet programs commonly use when saving CSV on Windows
+fn decode_text(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes.iter().map(|b| *b as char).collect(),
+    }
+}
+
+/// The candidate appearing most often, outside quotes, on the header line
+fn detect_delimiter(text: &str) -> u8 {
+    let header = text.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+    let mut counts = [0usize; CANDIDATE_DELIMITERS.len()];
+    let mut quoted = false;
+    for byte in header.bytes() {
+        if byte == b'"' {
+            quoted = !quoted;
+        } else if !quoted {
+            if let Some(i) = CANDIDATE_DELIMITERS.iter().position(|d| *d == byte) {
+                counts[i] += 1;
+            }
+        }
+    }
+    counts
+        .iter()
+        .enumerate()
+        .max_by_key(|(i, count)| (**count, usize::MAX - i))
+        .filter(|(_, count)| **count > 0)
+        .map(|(i, _)| CANDIDATE_DELIMITERS[i])
+        .unwrap_or(b',')
+}
+
+fn read_csv(text: &str, delimiter: Option<u8>) -> Result<Table, String> {
+    let delimiter = delimiter.unwrap_or_else(|| detect_delimiter(text));
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+
+    let mut records = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| format!("Invalid CSV at row {}: {}", index + 1, e))?;
+        records.push(record.iter().map(str::to_string).collect());
+    }
+    Table::from_records(records, Vec::new())
+}
+
+fn read_spreadsheet(bytes: Vec<u8>, sheet: Option<&str>) -> Result<Table, String> {
+    let mut workbook = open_workbook_auto_from_rs(Cursor::new(bytes))
+        .map_err(|e| format!("Failed to open spreadsheet: {}", e))?;
+    let sheets = workbook.sheet_names();
+
+    let name = match sheet {
+        Some(sheet) => sheets
+            .iter()
+            .find(|s| s.eq_ignore_ascii_case(sheet))
+            .cloned()
+            .ok_or_else(|| format!("Sheet not found: {}", sheet))?,
+        None => sheets
+            .first()
+            .cloned()
+            .ok_or_else(|| "The spreadsheet has no sheets".to_string())?,
+    };
+    let range = workbook
+        .worksheet_range(&name)
+        .map_err(|e| format!("Failed to read sheet {}: {}", name, e))?;
+
+    // The range starts at the first used cell; pad so row numbers match
+    let first_row = range.start().map(|(row, _)| row as usize).unwrap_or(0);
+    let first_column = range
+        .start()
+        .map(|(_, column)| column as usize)
+        .unwrap_or(0);
+    let mut records: Vec<Vec<String>> = vec![Vec::new(); first_row];
+    for row in range.rows() {
+        let mut values = vec![String::new(); first_column];
+        values.extend(row.iter().map(cell_text));
+        records.push(values);
+    }
+    Table::from_records(records, sheets)
+}
+
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty | Data::Error(_) => String::new(),
+        Data::String(value) => value.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// A value written to an export file
+#[derive(Debug, Clone)]
+pub enum ExportCell {
+    Empty,
+    Text(String),
+    Number(f64),
+}
+
+impl ExportCell {
+    pub fn text(value: Option<&str>) -> Self {
+        match value {
+            Some(value) if !value.is_empty() => Self::Text(value.to_string()),
+            _ => Self::Empty,
+        }
+    }
+
+    pub fn number(value: Option<f64>) -> Self {
+        value.map(Self::Number).unwrap_or(Self::Empty)
+    }
+
+    fn to_text(&self) -> String {
+        match self {
+            Self::Empty => String::new(),
+            Self::Text(value) => value.clone(),
+            Self::Number(value) => value.to_string(),
+        }
+    }
+}
+
+impl From<String> for ExportCell {
+    fn from(value: String) -> Self {
+        Self::text(Some(&value))
+    }
+}
+
+impl From<bool> for ExportCell {
+    fn from(value: bool) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+/// Write a CSV file, with a BOM so spreadsheet programs read it as UTF-8
+pub fn write_csv(
+    path: &str,
+    headers: &[&str],
+    rows: &[Vec<ExportCell>],
+    delimiter: u8,
+) -> Result<(), String> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(Vec::new());
+    writer
+        .write_record(headers)
+        .map_err(|e| format!("Failed to write CSV: {}", e))?;
+    for row in rows {
+        writer
+            .write_record(row.iter().map(ExportCell::to_text))
+            .map_err(|e| format!("Failed to write CSV: {}", e))?;
+    }
+    let body = writer
+        .into_inner()
+        .map_err(|e| format!("Failed to write CSV: {}", e))?;
+
+    let mut bytes = b"\xEF\xBB\xBF".to_vec();
+    bytes.extend(body);
+    std::fs::write(path, bytes).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+pub fn write_xlsx(
+    path: &str,
+    sheet_name: &str,
+    headers: &[&str],
+    rows: &[Vec<ExportCell>],
+) -> Result<(), String> {
+    let xlsx_error = |e: rust_xlsxwriter::XlsxError| format!("Failed to write spreadsheet: {}", e);
+
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(sheet_name).map_err(xlsx_error)?;
+
+    for (column, header) in headers.iter().enumerate() {
+        worksheet
+            .write_string_with_format(0, column as u16, *header, &bold)
+            .map_err(xlsx_error)?;
+    }
+    for (index, row) in rows.iter().enumerate() {
+        let row_number = index as u32 + 1;
+        for (column, cell) in row.iter().enumerate() {
+            match cell {
+                ExportCell::Empty => {}
+                ExportCell::Text(value) => {
+                    worksheet
+                        .write_string(row_number, column as u16, value)
+                        .map_err(xlsx_error)?;
+                }
+                ExportCell::Number(value) => {
+                    worksheet
+                        .write_number(row_number, column as u16, *value)
+                        .map_err(xlsx_error)?;
+                }
+            }
+        }
+    }
+    worksheet.set_freeze_panes(1, 0).map_err(xlsx_error)?;
+    worksheet.autofit();
+
+    workbook.save(path).map_err(xlsx_error)
+}
@@ -0,0 +1,83 @@
+use crate::db::{DatabaseConfig, DatabaseType, PoolManager, PoolStats, RepositoryFactory};
+use crate::features::database::dtos::database_dto::{
+    RunDatabaseMaintenanceDTO, SetSqliteTuningDTO,
+};
+use crate::features::database::models::database_maintenance_model::DatabaseMaintenanceRun;
+use crate::features::database::services::sqlite_maintenance_service::SqliteMaintenanceService;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn pool_stats(pool_manager: State<'_, Arc<PoolManager>>) -> Result<PoolStats, String> {
+    Ok(pool_manager.pool_stats())
+}
+
+#[tauri::command]
+pub async fn get_shop_database_config(
+    pool_manager: State<'_, Arc<PoolManager>>,
+    shop_id: String,
+) -> Result<DatabaseConfig, String> {
+    pool_manager
+        .get_shop_database_config(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to load shop database config: {}", e))
+}
+
+/// Store a shop's SQLite tuning and reopen its pool with it
+#[tauri::command]
+pub async fn set_shop_sqlite_tuning(
+    pool: State<'_, SqlitePool>,
+    pool_manager: State<'_, Arc<PoolManager>>,
+    payload: SetSqliteTuningDTO,
+) -> Result<DatabaseConfig, String> {
+    let mut config = pool_manager
+        .get_shop_database_config(&payload.shop_id)
+        .await
+        .map_err(|e| format!("Failed to load shop database config: {}", e))?;
+    if config.database_type != DatabaseType::Sqlite {
+        return Err("SQLite tuning only applies to SQLite shops".to_string());
+    }
+    if payload.tuning.busy_timeout_ms == 0 {
+        return Err("busy_timeout_ms must be greater than zero".to_string());
+    }
+    config.sqlite = payload.tuning;
+
+    let config_json = config
+        .to_json()
+        .map_err(|e| format!("Failed to serialize database config: {}", e))?;
+    sqlx::query("UPDATE shops SET database_config = ?, updated_at = datetime('now') WHERE id = ?")
+        .bind(config_json)
+        .bind(&payload.shop_id)
+        .execute(pool.inner())
+        .await
+        .map_err(|e| format!("Failed to update shop database config: {}", e))?;
+
+    // Connections only pick the pragmas up when they are opened
+    pool_manager.invalidate_shop_pool(&payload.shop_id).await;
+    Ok(config)
+}
+
+#[tauri::command]
+pub async fn run_database_maintenance(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: Option<RunDatabaseMaintenanceDTO>,
+) -> Result<DatabaseMaintenanceRun, String> {
+    let service = SqliteMaintenanceService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service
+        .run_maintenance(&shop_id, payload.unwrap_or_default())
+        .await
+}
+
+#[tauri::command]
+pub async fn list_database_maintenance_runs(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<DatabaseMaintenanceRun>, String> {
+    let service = SqliteMaintenanceService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.list_runs(shop_id, limit).await
+}
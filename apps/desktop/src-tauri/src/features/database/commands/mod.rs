@@ -0,0 +1,2 @@
+pub mod database_commands;
+pub mod shop_database_migration_commands;
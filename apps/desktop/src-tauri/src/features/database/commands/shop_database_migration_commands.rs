@@ -0,0 +1,52 @@
+use crate::db::RepositoryFactory;
+use crate::features::database::dtos::database_dto::MigrateShopDatabaseDTO;
+use crate::features::database::models::shop_database_migration_model::ShopDatabaseMigration;
+use crate::features::database::services::shop_database_migration_service::ShopDatabaseMigrationService;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn migrate_shop_database(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    payload: MigrateShopDatabaseDTO,
+) -> Result<ShopDatabaseMigration, String> {
+    let service =
+        ShopDatabaseMigrationService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.migrate(payload).await
+}
+
+#[tauri::command]
+pub async fn rollback_shop_database_migration(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    id: String,
+) -> Result<ShopDatabaseMigration, String> {
+    let service =
+        ShopDatabaseMigrationService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.rollback(&id).await
+}
+
+#[tauri::command]
+pub async fn get_shop_database_migration(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    id: String,
+) -> Result<Option<ShopDatabaseMigration>, String> {
+    let service =
+        ShopDatabaseMigrationService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.get_migration(&id).await
+}
+
+#[tauri::command]
+pub async fn list_shop_database_migrations(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<ShopDatabaseMigration>, String> {
+    let service =
+        ShopDatabaseMigrationService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.list_migrations(shop_id, limit).await
+}
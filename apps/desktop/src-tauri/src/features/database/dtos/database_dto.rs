@@ -0,0 +1,26 @@
+use crate::db::{DatabaseType, SqliteTuning};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RunDatabaseMaintenanceDTO {
+    /// Run `PRAGMA integrity_check` (default: true)
+    pub integrity_check: Option<bool>,
+    /// Rebuild the whole file with VACUUM instead of an incremental vacuum.
+    /// Blocks every other connection to the shop while it runs.
+    pub full_vacuum: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetSqliteTuningDTO {
+    pub shop_id: String,
+    pub tuning: SqliteTuning,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MigrateShopDatabaseDTO {
+    pub shop_id: String,
+    /// Only SQLite is accepted: moving a shop to Postgres is blocked until
+    /// shop commands can run on Postgres pools
+    pub target_type: DatabaseType,
+    pub created_by: Option<String>,
+}
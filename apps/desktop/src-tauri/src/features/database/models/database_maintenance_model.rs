@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Outcome of one maintenance pass over a shop's SQLite database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DatabaseMaintenanceRun {
+    pub id: String,
+    pub shop_id: String,
+    pub triggered_by: String, // schedule, manual
+    pub status: String,       // ok, warning, failed
+    /// "ok" or the problems found; None when the check was skipped
+    pub integrity_check: Option<String>,
+    pub optimized: bool,
+    pub checkpoint_mode: Option<String>,
+    /// The checkpoint could not finish because of active readers or writers
+    pub checkpoint_busy: Option<bool>,
+    pub checkpoint_log_frames: Option<i64>,
+    pub checkpointed_frames: Option<i64>,
+    pub vacuum_mode: Option<String>, // incremental, full
+    pub pages_freed: i64,
+    pub page_count: Option<i64>,
+    pub freelist_count: Option<i64>,
+    pub size_bytes: Option<i64>,
+    pub error: Option<String>,
+    pub duration_ms: i64,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+}
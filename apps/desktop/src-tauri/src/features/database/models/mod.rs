@@ -0,0 +1,2 @@
+pub mod database_maintenance_model;
+pub mod shop_database_migration_model;
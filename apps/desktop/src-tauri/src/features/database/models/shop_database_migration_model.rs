@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// One move of a shop's database between SQLite and Postgres
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ShopDatabaseMigration {
+    pub id: String,
+    pub shop_id: String,
+    pub source_type: String, // sqlite, postgres
+    pub target_type: String, // sqlite, postgres
+    /// The shop's database_config before the move (JSON), restored on rollback
+    pub source_config: String,
+    /// The database_config the shop was switched to (JSON)
+    pub target_config: String,
+    pub status: String, // running, completed, failed, rolled_back
+    pub tables_copied: i64,
+    pub rows_copied: i64,
+    /// Per-table verification report (JSON array of `TableCopyReport`)
+    pub tables: Option<String>,
+    /// Where the SQLite file replaced by the target was kept
+    pub archived_path: Option<String>,
+    pub error: Option<String>,
+    pub created_by: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub rolled_back_at: Option<DateTime<Utc>>,
+}
+
+/// Row count and checksum of one table on both sides of a move
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableCopyReport {
+    pub table: String,
+    pub source_rows: i64,
+    pub target_rows: i64,
+    pub source_checksum: String,
+    pub target_checksum: String,
+    pub matches: bool,
+}
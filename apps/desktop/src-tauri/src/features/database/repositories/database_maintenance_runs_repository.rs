@@ -0,0 +1,79 @@
+use crate::features::database::models::database_maintenance_model::DatabaseMaintenanceRun;
+use chrono::{DateTime, Utc};
+use sqlx::{Result, SqlitePool};
+
+pub struct DatabaseMaintenanceRunsRepository {
+    pool: SqlitePool,
+}
+
+impl DatabaseMaintenanceRunsRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, run: &DatabaseMaintenanceRun) -> Result<DatabaseMaintenanceRun> {
+        let sql = r#"
+            INSERT INTO database_maintenance_runs (
+                id, shop_id, triggered_by, status, integrity_check, optimized,
+                checkpoint_mode, checkpoint_busy, checkpoint_log_frames, checkpointed_frames,
+                vacuum_mode, pages_freed, page_count, freelist_count, size_bytes,
+                error, duration_ms, started_at, finished_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, DatabaseMaintenanceRun>(sql)
+            .bind(&run.id)
+            .bind(&run.shop_id)
+            .bind(&run.triggered_by)
+            .bind(&run.status)
+            .bind(&run.integrity_check)
+            .bind(run.optimized)
+            .bind(&run.checkpoint_mode)
+            .bind(run.checkpoint_busy)
+            .bind(run.checkpoint_log_frames)
+            .bind(run.checkpointed_frames)
+            .bind(&run.vacuum_mode)
+            .bind(run.pages_freed)
+            .bind(run.page_count)
+            .bind(run.freelist_count)
+            .bind(run.size_bytes)
+            .bind(&run.error)
+            .bind(run.duration_ms)
+            .bind(run.started_at)
+            .bind(run.finished_at)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    pub async fn list(
+        &self,
+        shop_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<DatabaseMaintenanceRun>> {
+        let sql = r#"
+            SELECT * FROM database_maintenance_runs
+            WHERE (?1 IS NULL OR shop_id = ?1)
+            ORDER BY started_at DESC
+            LIMIT ?2
+        "#;
+        sqlx::query_as::<_, DatabaseMaintenanceRun>(sql)
+            .bind(shop_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// When the shop's database last passed or failed an integrity check
+    pub async fn last_integrity_check_at(&self, shop_id: &str) -> Result<Option<DateTime<Utc>>> {
+        let sql = r#"
+            SELECT started_at FROM database_maintenance_runs
+            WHERE shop_id = ? AND integrity_check IS NOT NULL
+            ORDER BY started_at DESC
+            LIMIT 1
+        "#;
+        sqlx::query_scalar::<_, DateTime<Utc>>(sql)
+            .bind(shop_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+}
@@ -0,0 +1,2 @@
+pub mod database_maintenance_runs_repository;
+pub mod shop_database_migrations_repository;
@@ -0,0 +1,142 @@
+use crate::features::database::models::shop_database_migration_model::ShopDatabaseMigration;
+use sqlx::{Result, Sqlite, SqlitePool, Transaction};
+
+pub struct ShopDatabaseMigrationsRepository {
+    pool: SqlitePool,
+}
+
+impl ShopDatabaseMigrationsRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, migration: &ShopDatabaseMigration) -> Result<ShopDatabaseMigration> {
+        let sql = r#"
+            INSERT INTO shop_database_migrations (
+                id, shop_id, source_type, target_type, source_config, target_config,
+                status, tables_copied, rows_copied, tables, archived_path, error,
+                created_by, started_at, finished_at, rolled_back_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, ShopDatabaseMigration>(sql)
+            .bind(&migration.id)
+            .bind(&migration.shop_id)
+            .bind(&migration.source_type)
+            .bind(&migration.target_type)
+            .bind(&migration.source_config)
+            .bind(&migration.target_config)
+            .bind(&migration.status)
+            .bind(migration.tables_copied)
+            .bind(migration.rows_copied)
+            .bind(&migration.tables)
+            .bind(&migration.archived_path)
+            .bind(&migration.error)
+            .bind(&migration.created_by)
+            .bind(migration.started_at)
+            .bind(migration.finished_at)
+            .bind(migration.rolled_back_at)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<ShopDatabaseMigration>> {
+        sqlx::query_as::<_, ShopDatabaseMigration>(
+            "SELECT * FROM shop_database_migrations WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn list(
+        &self,
+        shop_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<ShopDatabaseMigration>> {
+        let sql = r#"
+            SELECT * FROM shop_database_migrations
+            WHERE (?1 IS NULL OR shop_id = ?1)
+            ORDER BY started_at DESC
+            LIMIT ?2
+        "#;
+        sqlx::query_as::<_, ShopDatabaseMigration>(sql)
+            .bind(shop_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// The shop's last move that got applied, rolled back or not
+    pub async fn find_latest_applied(
+        &self,
+        shop_id: &str,
+    ) -> Result<Option<ShopDatabaseMigration>> {
+        let sql = r#"
+            SELECT * FROM shop_database_migrations
+            WHERE shop_id = ? AND status IN ('completed', 'rolled_back')
+            ORDER BY started_at DESC
+            LIMIT 1
+        "#;
+        sqlx::query_as::<_, ShopDatabaseMigration>(sql)
+            .bind(shop_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    /// Fail moves left running by a crash or a closed app
+    pub async fn fail_running(&self, shop_id: &str, error: &str) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE shop_database_migrations SET status = 'failed', error = ?, finished_at = datetime('now') WHERE shop_id = ? AND status = 'running'",
+        )
+        .bind(error)
+        .bind(shop_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn mark_failed(&self, id: &str, error: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE shop_database_migrations SET status = 'failed', error = ?, finished_at = datetime('now') WHERE id = ?",
+        )
+        .bind(error)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_completed_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        migration: &ShopDatabaseMigration,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE shop_database_migrations
+            SET status = 'completed', tables_copied = ?, rows_copied = ?, tables = ?,
+                archived_path = ?, error = NULL, finished_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(migration.tables_copied)
+        .bind(migration.rows_copied)
+        .bind(&migration.tables)
+        .bind(&migration.archived_path)
+        .bind(migration.finished_at)
+        .bind(&migration.id)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_rolled_back_in_tx(tx: &mut Transaction<'_, Sqlite>, id: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE shop_database_migrations SET status = 'rolled_back', rolled_back_at = datetime('now') WHERE id = ?",
+        )
+        .bind(id)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+}
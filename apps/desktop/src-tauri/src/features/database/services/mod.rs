@@ -0,0 +1,4 @@
+pub mod shop_database_copy;
+pub mod shop_database_migration_service;
+pub mod sqlite_maintenance_scheduler;
+pub mod sqlite_maintenance_service;
@@ -0,0 +1,897 @@
+//! Table-by-table copy of a Postgres shop database into SQLite
+//!
+//! Only this direction exists because shop commands only run on SQLite
+//! pools, so no shop is ever moved onto Postgres.
+//!
+//! The copy runs inside one transaction on each side: a read-only snapshot
+//! of the Postgres source and a single write transaction on the SQLite
+//! target, which is only committed once every table has been read back and
+//! verified.
+//!
+//! Both sides are compared on canonical values, so the storage quirks of
+//! each engine don't count as differences:
+//! - decimals are rounded to the Postgres column's scale (SQLite keeps REAL)
+//! - timestamps are normalized to UTC with microsecond precision
+//! - booleans are 1/0
+//!
+//! A table's checksum is the wrapping sum of per-row SHA-256 digests, so it
+//! doesn't depend on the order rows are read in.
+
+use crate::db::ShopPool;
+use crate::features::database::models::shop_database_migration_model::TableCopyReport;
+use crate::features::search::services::shop_search_service::ShopSearchService;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SubsecRound, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::postgres::{PgArguments, PgConnection};
+use sqlx::query::Query;
+use sqlx::sqlite::{SqliteArguments, SqliteConnection, SqliteRow};
+use sqlx::{Postgres, Row, Sqlite, TypeInfo, ValueRef};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Tables owned by the migration runner or rebuilt by the search index
+const SKIPPED_TABLES: &[&str] = &[
+    "_migrations",
+    "search_index_keys",
+    "products_fts",
+    "customers_fts",
+];
+/// Rows read from the source per round trip
+const PAGE_SIZE: i64 = 500;
+/// Bound parameters per INSERT statement
+const MAX_PARAMS_PER_INSERT: usize = 1_000;
+
+/// A transaction-bound connection to either side of the copy, for reading
+enum ShopConn<'c> {
+    Sqlite(&'c mut SqliteConnection),
+    Postgres(&'c mut PgConnection),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnKind {
+    Integer,
+    Decimal { scale: Option<usize> },
+    Boolean,
+    Timestamp,
+    Date,
+    Text,
+}
+
+impl ColumnKind {
+    /// Map a Postgres `format_type` name to the kind values are compared as
+    fn from_pg_type(pg_type: &str) -> Self {
+        match pg_type {
+            "smallint" | "integer" | "bigint" => Self::Integer,
+            "boolean" => Self::Boolean,
+            "date" => Self::Date,
+            "real" | "double precision" => Self::Decimal { scale: None },
+            t if t.starts_with("timestamp") => Self::Timestamp,
+            t if t.starts_with("numeric") => Self::Decimal {
+                scale: t
+                    .split_once(',')
+                    .and_then(|(_, s)| s.trim_end_matches(')').trim().parse().ok()),
+            },
+            _ => Self::Text,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ColumnSpec {
+    name: String,
+    kind: ColumnKind,
+    /// Postgres type, used to cast the text parameters sent to Postgres
+    pg_type: String,
+}
+
+#[derive(Debug, Clone)]
+struct TableSpec {
+    name: String,
+    columns: Vec<ColumnSpec>,
+    /// Indexes into `columns`, in primary key order
+    primary_key: Vec<usize>,
+    /// Columns referencing the table itself, filled in after all its rows exist
+    self_refs: Vec<usize>,
+    /// Tables this one references
+    depends_on: BTreeSet<String>,
+}
+
+/// A value as stored, before canonicalization
+enum RawValue {
+    Null,
+    Int(i64),
+    Real(f64),
+    Text(String),
+}
+
+/// A row as canonical strings, `None` for NULL
+type CanonicalRow = Vec<Option<String>>;
+
+#[derive(Default)]
+struct TableDigest {
+    rows: i64,
+    sum: u128,
+}
+
+impl TableDigest {
+    fn add(&mut self, row: &[Option<String>]) {
+        let mut hasher = Sha256::new();
+        for value in row {
+            match value {
+                None => hasher.update([0u8]),
+                Some(v) => {
+                    hasher.update([1u8]);
+                    hasher.update((v.len() as u64).to_le_bytes());
+                    hasher.update(v.as_bytes());
+                }
+            }
+        }
+        let digest = hasher.finalize();
+        let mut head = [0u8; 16];
+        head.copy_from_slice(&digest[..16]);
+        self.sum = self.sum.wrapping_add(u128::from_le_bytes(head));
+        self.rows += 1;
+    }
+
+    fn checksum(&self) -> String {
+        format!("{:032x}", self.sum)
+    }
+}
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn is_skipped(table: &str) -> bool {
+    SKIPPED_TABLES.contains(&table)
+        || table.starts_with("products_fts_")
+        || table.starts_with("customers_fts_")
+}
+
+/// Copy every shop table from a Postgres `source` to a SQLite `target` and
+/// verify the result.
+///
+/// `target` must hold a freshly migrated, empty shop schema. Nothing is
+/// committed on the target unless every table's row count and checksum
+/// match the source.
+pub async fn copy_shop_database(
+    shop_id: &str,
+    source: &ShopPool,
+    target: &ShopPool,
+) -> Result<Vec<TableCopyReport>, String> {
+    let (ShopPool::Postgres(source), ShopPool::Sqlite(target)) = (source, target) else {
+        return Err("Shop databases can only be copied from Postgres to SQLite".to_string());
+    };
+
+    let begin_err = |e: sqlx::Error| format!("Failed to begin transaction: {}", e);
+    let mut source_tx = source.begin().await.map_err(begin_err)?;
+    let mut target_tx = target.begin().await.map_err(begin_err)?;
+    let reports = copy_tables(&mut source_tx, &mut target_tx).await?;
+    target_tx
+        .commit()
+        .await
+        .map_err(|e| format!("Failed to commit target database: {}", e))?;
+
+    // The FTS5 index isn't copied; rebuild it from the copied rows
+    ShopSearchService::new(target.clone(), shop_id.to_string())
+        .rebuild_index()
+        .await?;
+    Ok(reports)
+}
+
+async fn copy_tables(
+    source: &mut PgConnection,
+    target: &mut SqliteConnection,
+) -> Result<Vec<TableCopyReport>, String> {
+    prepare_source(source).await?;
+    let tables = load_table_specs(target, source).await?;
+    let tables = dependency_order(tables)?;
+    let triggers = prepare_target(target).await?;
+
+    let mut source_digests = Vec::with_capacity(tables.len());
+    for table in &tables {
+        let digest = copy_table(source, target, table).await?;
+        println!(
+            "[ShopDatabaseCopy] Copied {} rows of {}",
+            digest.rows, table.name
+        );
+        source_digests.push(digest);
+    }
+
+    finish_target(target, &triggers).await?;
+
+    let mut reports = Vec::with_capacity(tables.len());
+    for (table, source_digest) in tables.iter().zip(source_digests) {
+        let target_digest = digest_table(&mut ShopConn::Sqlite(&mut *target), table).await?;
+        let source_checksum = source_digest.checksum();
+        let target_checksum = target_digest.checksum();
+        reports.push(TableCopyReport {
+            table: table.name.clone(),
+            source_rows: source_digest.rows,
+            target_rows: target_digest.rows,
+            matches: source_digest.rows == target_digest.rows && source_checksum == target_checksum,
+            source_checksum,
+            target_checksum,
+        });
+    }
+
+    let mismatched: Vec<String> = reports
+        .iter()
+        .filter(|r| !r.matches)
+        .map(|r| {
+            format!(
+                "{} ({} rows, {} copied)",
+                r.table, r.source_rows, r.target_rows
+            )
+        })
+        .collect();
+    if !mismatched.is_empty() {
+        return Err(format!("Verification failed for {}", mismatched.join(", ")));
+    }
+    Ok(reports)
+}
+
+/// Read both sides' catalogs and match every table and column.
+///
+/// Tables, primary keys and foreign keys come from SQLite; column types
+/// come from Postgres, which is the stricter of the two.
+async fn load_table_specs(
+    sqlite: &mut SqliteConnection,
+    postgres: &mut PgConnection,
+) -> Result<Vec<TableSpec>, String> {
+    let sqlite_tables: Vec<String> = sqlx::query_scalar(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+    )
+    .fetch_all(&mut *sqlite)
+    .await
+    .map_err(|e| format!("Failed to list SQLite tables: {}", e))?;
+    let sqlite_tables: Vec<String> = sqlite_tables
+        .into_iter()
+        .filter(|t| !is_skipped(t))
+        .collect();
+
+    let postgres_tables: Vec<String> = sqlx::query_scalar(
+        "SELECT tablename::text FROM pg_tables WHERE schemaname = current_schema() ORDER BY tablename",
+    )
+    .fetch_all(&mut *postgres)
+    .await
+    .map_err(|e| format!("Failed to list Postgres tables: {}", e))?;
+    if let Some(extra) = postgres_tables
+        .iter()
+        .find(|t| !is_skipped(t) && !sqlite_tables.contains(t))
+    {
+        return Err(format!("Table {} only exists in Postgres", extra));
+    }
+
+    let mut specs = Vec::with_capacity(sqlite_tables.len());
+    for table in sqlite_tables {
+        // hidden: 1 for virtual table columns, 2 and 3 for generated columns
+        let sqlite_columns: Vec<(String, i64, i64)> =
+            sqlx::query_as("SELECT name, pk, hidden FROM pragma_table_xinfo(?) ORDER BY cid")
+                .bind(&table)
+                .fetch_all(&mut *sqlite)
+                .await
+                .map_err(|e| format!("Failed to read SQLite columns of {}: {}", table, e))?;
+
+        let postgres_columns: Vec<(String, String, bool)> = sqlx::query_as(
+            r#"
+            SELECT a.attname::text, format_type(a.atttypid, a.atttypmod), a.attgenerated <> ''
+            FROM pg_attribute a
+            JOIN pg_class c ON c.oid = a.attrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = current_schema() AND c.relname = $1
+              AND a.attnum > 0 AND NOT a.attisdropped
+            ORDER BY a.attnum
+            "#,
+        )
+        .bind(&table)
+        .fetch_all(&mut *postgres)
+        .await
+        .map_err(|e| format!("Failed to read Postgres columns of {}: {}", table, e))?;
+        if postgres_columns.is_empty() {
+            return Err(format!("Table {} only exists in SQLite", table));
+        }
+
+        let pg_types: HashMap<&str, &str> = postgres_columns
+            .iter()
+            .filter(|(_, _, generated)| !generated)
+            .map(|(name, pg_type, _)| (name.as_str(), pg_type.as_str()))
+            .collect();
+        let pg_generated: BTreeSet<&str> = postgres_columns
+            .iter()
+            .filter(|(_, _, generated)| *generated)
+            .map(|(name, _, _)| name.as_str())
+            .collect();
+
+        let mut columns = Vec::new();
+        let mut pk_positions = Vec::new();
+        for (name, pk, hidden) in &sqlite_columns {
+            if *hidden != 0 || pg_generated.contains(name.as_str()) {
+                continue;
+            }
+            let pg_type = pg_types
+                .get(name.as_str())
+                .ok_or_else(|| format!("Column {}.{} only exists in SQLite", table, name))?;
+            if *pk > 0 {
+                pk_positions.push((*pk, columns.len()));
+            }
+            columns.push(ColumnSpec {
+                name: name.clone(),
+                kind: ColumnKind::from_pg_type(pg_type),
+                pg_type: pg_type.to_string(),
+            });
+        }
+        if let Some(missing) = pg_types
+            .keys()
+            .find(|name| !columns.iter().any(|c| c.name == **name))
+        {
+            return Err(format!(
+                "Column {}.{} only exists in Postgres",
+                table, missing
+            ));
+        }
+        if pk_positions.is_empty() {
+            return Err(format!("Table {} has no primary key", table));
+        }
+        pk_positions.sort();
+
+        let foreign_keys: Vec<(String, String)> =
+            sqlx::query_as(r#"SELECT "table", "from" FROM pragma_foreign_key_list(?)"#)
+                .bind(&table)
+                .fetch_all(&mut *sqlite)
+                .await
+                .map_err(|e| format!("Failed to read foreign keys of {}: {}", table, e))?;
+
+        let mut depends_on = BTreeSet::new();
+        let mut self_refs = Vec::new();
+        for (referenced, column) in foreign_keys {
+            if referenced == table {
+                if let Some(idx) = columns.iter().position(|c| c.name == column) {
+                    self_refs.push(idx);
+                }
+            } else {
+                depends_on.insert(referenced);
+            }
+        }
+
+        specs.push(TableSpec {
+            name: table,
+            columns,
+            primary_key: pk_positions.into_iter().map(|(_, idx)| idx).collect(),
+            self_refs,
+            depends_on,
+        });
+    }
+    Ok(specs)
+}
+
+/// Order tables so every table comes after the ones it references
+fn dependency_order(tables: Vec<TableSpec>) -> Result<Vec<TableSpec>, String> {
+    let names: BTreeSet<String> = tables.iter().map(|t| t.name.clone()).collect();
+    let mut pending: BTreeMap<String, TableSpec> =
+        tables.into_iter().map(|t| (t.name.clone(), t)).collect();
+    let mut ordered: Vec<TableSpec> = Vec::with_capacity(pending.len());
+    let mut placed: BTreeSet<String> = BTreeSet::new();
+
+    while !pending.is_empty() {
+        let ready: Vec<String> = pending
+            .values()
+            .filter(|t| {
+                t.depends_on
+                    .iter()
+                    .all(|d| placed.contains(d) || !names.contains(d))
+            })
+            .map(|t| t.name.clone())
+            .collect();
+        if ready.is_empty() {
+            let cycle: Vec<&str> = pending.keys().map(String::as_str).collect();
+            return Err(format!(
+                "Circular foreign keys between {}",
+                cycle.join(", ")
+            ));
+        }
+        for name in ready {
+            if let Some(table) = pending.remove(&name) {
+                placed.insert(name);
+                ordered.push(table);
+            }
+        }
+    }
+    Ok(ordered)
+}
+
+async fn prepare_source(source: &mut PgConnection) -> Result<(), String> {
+    // A consistent snapshot, whatever other clients of the server do
+    for sql in [
+        "SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY",
+        "SET LOCAL TIME ZONE 'UTC'",
+        "SET LOCAL DateStyle = 'ISO, YMD'",
+    ] {
+        sqlx::query(sql)
+            .execute(&mut *source)
+            .await
+            .map_err(|e| format!("Failed to prepare source transaction: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Turn off foreign key ordering and triggers on the target.
+///
+/// Triggers would otherwise re-apply stock movements and write audit rows
+/// for data that already carries their effects. Returns the triggers to
+/// recreate.
+async fn prepare_target(target: &mut SqliteConnection) -> Result<Vec<String>, String> {
+    sqlx::query("PRAGMA defer_foreign_keys = ON")
+        .execute(&mut *target)
+        .await
+        .map_err(|e| format!("Failed to defer foreign keys: {}", e))?;
+
+    let triggers: Vec<(String, String)> = sqlx::query_as(
+        "SELECT name, sql FROM sqlite_master WHERE type = 'trigger' AND sql IS NOT NULL",
+    )
+    .fetch_all(&mut *target)
+    .await
+    .map_err(|e| format!("Failed to list triggers: {}", e))?;
+    for (name, _) in &triggers {
+        sqlx::query(&format!("DROP TRIGGER IF EXISTS {}", quote(name)))
+            .execute(&mut *target)
+            .await
+            .map_err(|e| format!("Failed to drop trigger {}: {}", name, e))?;
+    }
+    Ok(triggers.into_iter().map(|(_, sql)| sql).collect())
+}
+
+/// Restore the target's triggers
+async fn finish_target(target: &mut SqliteConnection, triggers: &[String]) -> Result<(), String> {
+    for sql in triggers {
+        sqlx::query(sql)
+            .execute(&mut *target)
+            .await
+            .map_err(|e| format!("Failed to recreate trigger: {}", e))?;
+    }
+    Ok(())
+}
+
+async fn copy_table(
+    source: &mut PgConnection,
+    target: &mut SqliteConnection,
+    table: &TableSpec,
+) -> Result<TableDigest, String> {
+    let mut digest = TableDigest::default();
+    let mut deferred: Vec<(CanonicalRow, usize, String)> = Vec::new();
+    let mut after: Option<CanonicalRow> = None;
+
+    loop {
+        let rows = read_page(&mut ShopConn::Postgres(&mut *source), table, after.as_ref()).await?;
+        let Some(last) = rows.last() else {
+            break;
+        };
+        after = Some(table.primary_key.iter().map(|&i| last[i].clone()).collect());
+
+        let mut batch = Vec::with_capacity(rows.len());
+        for row in &rows {
+            digest.add(row);
+            let mut row = row.clone();
+            // Parents may come later in key order: link them once all rows exist
+            for &idx in &table.self_refs {
+                if let Some(value) = row[idx].take() {
+                    let key = table.primary_key.iter().map(|&i| row[i].clone()).collect();
+                    deferred.push((key, idx, value));
+                }
+            }
+            batch.push(row);
+        }
+        insert_rows(target, table, &batch).await?;
+
+        if (rows.len() as i64) < PAGE_SIZE {
+            break;
+        }
+    }
+
+    for (key, idx, value) in deferred {
+        update_self_ref(target, table, &key, idx, value).await?;
+    }
+    Ok(digest)
+}
+
+async fn digest_table(conn: &mut ShopConn<'_>, table: &TableSpec) -> Result<TableDigest, String> {
+    let mut digest = TableDigest::default();
+    let mut after: Option<CanonicalRow> = None;
+    loop {
+        let rows = read_page(conn, table, after.as_ref()).await?;
+        let Some(last) = rows.last() else {
+            break;
+        };
+        after = Some(table.primary_key.iter().map(|&i| last[i].clone()).collect());
+        for row in &rows {
+            digest.add(row);
+        }
+        if (rows.len() as i64) < PAGE_SIZE {
+            break;
+        }
+    }
+    Ok(digest)
+}
+
+fn key_columns(table: &TableSpec) -> String {
+    table
+        .primary_key
+        .iter()
+        .map(|&i| quote(&table.columns[i].name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Read the next page of rows after `after` in primary key order
+async fn read_page(
+    conn: &mut ShopConn<'_>,
+    table: &TableSpec,
+    after: Option<&CanonicalRow>,
+) -> Result<Vec<CanonicalRow>, String> {
+    let keys = key_columns(table);
+    let read_err = |e: sqlx::Error| format!("Failed to read {}: {}", table.name, e);
+
+    let raw_rows: Vec<Vec<RawValue>> = match conn {
+        ShopConn::Sqlite(conn) => {
+            let columns: Vec<String> = table.columns.iter().map(|c| quote(&c.name)).collect();
+            let filter = match after {
+                Some(_) => format!(
+                    "WHERE ({}) > ({})",
+                    keys,
+                    vec!["?"; table.primary_key.len()].join(", ")
+                ),
+                None => String::new(),
+            };
+            let sql = format!(
+                "SELECT {} FROM {} {} ORDER BY {} LIMIT {}",
+                columns.join(", "),
+                quote(&table.name),
+                filter,
+                keys,
+                PAGE_SIZE
+            );
+            let mut query = sqlx::query(&sql);
+            if let Some(after) = after {
+                for (&idx, value) in table.primary_key.iter().zip(after) {
+                    query = bind_sqlite(query, table.columns[idx].kind, value.as_deref())?;
+                }
+            }
+            let rows = query.fetch_all(&mut **conn).await.map_err(read_err)?;
+            rows.iter()
+                .map(|row| {
+                    (0..table.columns.len())
+                        .map(|i| sqlite_raw(row, i))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(read_err)?
+        }
+        ShopConn::Postgres(conn) => {
+            let columns: Vec<String> = table
+                .columns
+                .iter()
+                .map(|c| format!("{}::text", quote(&c.name)))
+                .collect();
+            let filter = match after {
+                Some(_) => {
+                    let params: Vec<String> = table
+                        .primary_key
+                        .iter()
+                        .enumerate()
+                        .map(|(n, &idx)| format!("${}::{}", n + 1, table.columns[idx].pg_type))
+                        .collect();
+                    format!("WHERE ({}) > ({})", keys, params.join(", "))
+                }
+                None => String::new(),
+            };
+            let sql = format!(
+                "SELECT {} FROM {} {} ORDER BY {} LIMIT {}",
+                columns.join(", "),
+                quote(&table.name),
+                filter,
+                keys,
+                PAGE_SIZE
+            );
+            let mut query = sqlx::query(&sql);
+            if let Some(after) = after {
+                for (&idx, value) in table.primary_key.iter().zip(after) {
+                    query = bind_postgres(query, table.columns[idx].kind, value.as_deref());
+                }
+            }
+            let rows = query.fetch_all(&mut **conn).await.map_err(read_err)?;
+            rows.iter()
+                .map(|row| {
+                    (0..table.columns.len())
+                        .map(|i| {
+                            row.try_get::<Option<String>, _>(i)
+                                .map(|v| v.map_or(RawValue::Null, RawValue::Text))
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(read_err)?
+        }
+    };
+
+    raw_rows
+        .into_iter()
+        .map(|raw| {
+            raw.into_iter()
+                .zip(&table.columns)
+                .map(|(value, column)| {
+                    canonical(column.kind, value).map_err(|e| {
+                        format!("Invalid value in {}.{}: {}", table.name, column.name, e)
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn sqlite_raw(row: &SqliteRow, idx: usize) -> Result<RawValue, sqlx::Error> {
+    let value = row.try_get_raw(idx)?;
+    if value.is_null() {
+        return Ok(RawValue::Null);
+    }
+    // The storage class of this value, not the column's declared type
+    let storage_class = value.type_info().name().to_string();
+    match storage_class.as_str() {
+        "INTEGER" | "BOOLEAN" => Ok(RawValue::Int(row.try_get_unchecked(idx)?)),
+        "REAL" => Ok(RawValue::Real(row.try_get_unchecked(idx)?)),
+        "TEXT" => Ok(RawValue::Text(row.try_get_unchecked(idx)?)),
+        other => Err(sqlx::Error::Decode(
+            format!("unsupported {} value in column {}", other, idx).into(),
+        )),
+    }
+}
+
+async fn insert_rows(
+    target: &mut SqliteConnection,
+    table: &TableSpec,
+    rows: &[CanonicalRow],
+) -> Result<(), String> {
+    let column_count = table.columns.len();
+    let rows_per_insert = (MAX_PARAMS_PER_INSERT / column_count).max(1);
+    let columns: Vec<String> = table.columns.iter().map(|c| quote(&c.name)).collect();
+    let row_params = format!("({})", vec!["?"; column_count].join(", "));
+    let insert_err = |e: sqlx::Error| format!("Failed to insert into {}: {}", table.name, e);
+
+    for chunk in rows.chunks(rows_per_insert) {
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES {}",
+            quote(&table.name),
+            columns.join(", "),
+            vec![row_params.clone(); chunk.len()].join(", ")
+        );
+        let mut query = sqlx::query(&sql);
+        for row in chunk {
+            for (column, value) in table.columns.iter().zip(row) {
+                query = bind_sqlite(query, column.kind, value.as_deref())?;
+            }
+        }
+        query.execute(&mut *target).await.map_err(insert_err)?;
+    }
+    Ok(())
+}
+
+async fn update_self_ref(
+    target: &mut SqliteConnection,
+    table: &TableSpec,
+    key: &CanonicalRow,
+    idx: usize,
+    value: String,
+) -> Result<(), String> {
+    let column = &table.columns[idx];
+    let sql = format!(
+        "UPDATE {} SET {} = ? WHERE ({}) = ({})",
+        quote(&table.name),
+        quote(&column.name),
+        key_columns(table),
+        vec!["?"; key.len()].join(", ")
+    );
+    let mut query = bind_sqlite(sqlx::query(&sql), column.kind, Some(&value))?;
+    for (&key_idx, key_value) in table.primary_key.iter().zip(key) {
+        query = bind_sqlite(query, table.columns[key_idx].kind, key_value.as_deref())?;
+    }
+    query
+        .execute(&mut *target)
+        .await
+        .map_err(|e| format!("Failed to link {}.{}: {}", table.name, column.name, e))?;
+    Ok(())
+}
+
+/// Bind a canonical value with the storage class SQLite expects for its kind
+fn bind_sqlite<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    kind: ColumnKind,
+    value: Option<&str>,
+) -> Result<Query<'q, Sqlite, SqliteArguments<'q>>, String> {
+    let Some(value) = value else {
+        return Ok(query.bind(None::<String>));
+    };
+    Ok(match kind {
+        ColumnKind::Integer | ColumnKind::Boolean => query.bind(
+            value
+                .parse::<i64>()
+                .map_err(|e| format!("Invalid integer '{}': {}", value, e))?,
+        ),
+        ColumnKind::Decimal { .. } => query.bind(
+            value
+                .parse::<f64>()
+                .map_err(|e| format!("Invalid decimal '{}': {}", value, e))?,
+        ),
+        // Same layout as CURRENT_TIMESTAMP, so text comparisons keep working
+        ColumnKind::Timestamp => {
+            query.bind(value.strip_suffix(".000000").unwrap_or(value).to_string())
+        }
+        ColumnKind::Date | ColumnKind::Text => query.bind(value.to_string()),
+    })
+}
+
+/// Bind a canonical value as text; the statement casts it to the column type
+fn bind_postgres<'q>(
+    query: Query<'q, Postgres, PgArguments>,
+    kind: ColumnKind,
+    value: Option<&str>,
+) -> Query<'q, Postgres, PgArguments> {
+    let value = value.map(|v| match kind {
+        ColumnKind::Timestamp => format!("{}+00", v),
+        _ => v.to_string(),
+    });
+    query.bind(value)
+}
+
+/// Render a stored value in the form both engines agree on for its kind
+fn canonical(kind: ColumnKind, value: RawValue) -> Result<Option<String>, String> {
+    let text = match (kind, value) {
+        (_, RawValue::Null) => return Ok(None),
+
+        (ColumnKind::Integer, RawValue::Int(v)) => v.to_string(),
+        (ColumnKind::Integer, RawValue::Real(v)) if v.fract() == 0.0 && v.is_finite() => {
+            (v as i64).to_string()
+        }
+        (ColumnKind::Integer, RawValue::Text(v)) => v
+            .trim()
+            .parse::<i64>()
+            .map_err(|e| format!("'{}' is not an integer: {}", v, e))?
+            .to_string(),
+        (ColumnKind::Integer, RawValue::Real(v)) => return Err(format!("{} is not an integer", v)),
+
+        (ColumnKind::Decimal { scale }, RawValue::Int(v)) => round_decimal(&v.to_string(), scale)?,
+        (ColumnKind::Decimal { scale }, RawValue::Real(v)) => round_decimal(&v.to_string(), scale)?,
+        (ColumnKind::Decimal { scale }, RawValue::Text(v)) => round_decimal(v.trim(), scale)?,
+
+        (ColumnKind::Boolean, RawValue::Int(v)) => (if v != 0 { "1" } else { "0" }).to_string(),
+        (ColumnKind::Boolean, RawValue::Real(v)) => (if v != 0.0 { "1" } else { "0" }).to_string(),
+        (ColumnKind::Boolean, RawValue::Text(v)) => match v.trim().to_lowercase().as_str() {
+            "1" | "t" | "true" | "yes" | "on" => "1".to_string(),
+            "0" | "f" | "false" | "no" | "off" => "0".to_string(),
+            _ => return Err(format!("'{}' is not a boolean", v)),
+        },
+
+        (ColumnKind::Timestamp, RawValue::Int(v)) => DateTime::from_timestamp(v, 0)
+            .ok_or_else(|| format!("{} is not a valid timestamp", v))?
+            .format("%Y-%m-%d %H:%M:%S%.6f")
+            .to_string(),
+        (ColumnKind::Timestamp, RawValue::Text(v)) => parse_timestamp(&v)?
+            .round_subsecs(6)
+            .format("%Y-%m-%d %H:%M:%S%.6f")
+            .to_string(),
+        (ColumnKind::Timestamp, RawValue::Real(v)) => {
+            return Err(format!("{} is not a timestamp", v))
+        }
+
+        (ColumnKind::Date, RawValue::Text(v)) => {
+            match NaiveDate::parse_from_str(v.trim(), "%Y-%m-%d") {
+                Ok(date) => date.format("%Y-%m-%d").to_string(),
+                Err(_) => parse_timestamp(&v)?
+                    .date_naive()
+                    .format("%Y-%m-%d")
+                    .to_string(),
+            }
+        }
+        (ColumnKind::Date, RawValue::Int(v)) => return Err(format!("{} is not a date", v)),
+        (ColumnKind::Date, RawValue::Real(v)) => return Err(format!("{} is not a date", v)),
+
+        (ColumnKind::Text, RawValue::Int(v)) => v.to_string(),
+        (ColumnKind::Text, RawValue::Real(v)) => v.to_string(),
+        (ColumnKind::Text, RawValue::Text(v)) => v,
+    };
+    Ok(Some(text))
+}
+
+/// Parse the timestamp layouts found in shop databases: SQLite's
+/// CURRENT_TIMESTAMP, RFC 3339 as written by sqlx, Postgres' ISO output
+/// and bare dates. Values without an offset are UTC.
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, String> {
+    let value = value.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S%.f%#z", "%Y-%m-%dT%H:%M:%S%.f%#z"] {
+        if let Ok(dt) = DateTime::parse_from_str(value, format) {
+            return Ok(dt.with_timezone(&Utc));
+        }
+    }
+    for format in [
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+    ] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok(dt.and_utc());
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        if let Some(dt) = date.and_hms_opt(0, 0, 0) {
+            return Ok(dt.and_utc());
+        }
+    }
+    Err(format!("'{}' is not a timestamp", value))
+}
+
+/// Round a decimal string half away from zero to `scale` places and drop
+/// trailing zeros, so 19.9 (REAL) and 19.90 (NUMERIC) compare equal
+fn round_decimal(value: &str, scale: Option<usize>) -> Result<String, String> {
+    if value.contains(['e', 'E']) {
+        let parsed: f64 = value
+            .parse()
+            .map_err(|e| format!("'{}' is not a number: {}", value, e))?;
+        return round_decimal(&parsed.to_string(), scale);
+    }
+
+    let (negative, unsigned) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let (int_part, frac_part) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let is_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if (int_part.is_empty() && frac_part.is_empty())
+        || !is_digits(int_part)
+        || !is_digits(frac_part)
+    {
+        return Err(format!("'{}' is not a number", value));
+    }
+
+    let mut digits: Vec<u8> = int_part.bytes().map(|b| b - b'0').collect();
+    let mut frac: Vec<u8> = frac_part.bytes().map(|b| b - b'0').collect();
+    if let Some(scale) = scale {
+        if frac.len() > scale {
+            let round_up = frac[scale] >= 5;
+            frac.truncate(scale);
+            digits.extend(&frac);
+            if round_up {
+                let mut i = digits.len();
+                loop {
+                    if i == 0 {
+                        digits.insert(0, 1);
+                        break;
+                    }
+                    i -= 1;
+                    if digits[i] == 9 {
+                        digits[i] = 0;
+                    } else {
+                        digits[i] += 1;
+                        break;
+                    }
+                }
+            }
+            frac = digits.split_off(digits.len() - scale);
+        }
+    }
+
+    while frac.last() == Some(&0) {
+        frac.pop();
+    }
+    let int_str: String = digits.iter().map(|d| char::from(b'0' + d)).collect();
+    let int_str = int_str.trim_start_matches('0');
+    let int_str = if int_str.is_empty() { "0" } else { int_str };
+    let frac_str: String = frac.iter().map(|d| char::from(b'0' + d)).collect();
+
+    let is_zero = int_str == "0" && frac_str.is_empty();
+    let sign = if negative && !is_zero { "-" } else { "" };
+    if frac_str.is_empty() {
+        Ok(format!("{}{}", sign, int_str))
+    } else {
+        Ok(format!("{}{}.{}", sign, int_str, frac_str))
+    }
+}
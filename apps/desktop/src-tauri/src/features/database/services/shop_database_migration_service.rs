@@ -0,0 +1,415 @@
+//! Moves a shop's database from Postgres to SQLite
+//!
+//! Moving a shop to Postgres is blocked: every shop command runs on a
+//! SQLite pool (`RepositoryFactory::shop_pool`) with SQLite-specific SQL, so
+//! a shop on Postgres could not be used. Moves to Postgres, and rollbacks
+//! that would return a shop to Postgres, are refused until that layer
+//! supports Postgres.
+//!
+//! A move:
+//! 1. blocks the shop's pool, so nothing writes to the source meanwhile
+//! 2. creates the shop schema in a staging file next to the shop's database
+//! 3. copies and verifies every table (see `shop_database_copy`)
+//! 4. swaps the staging file in, keeping the file it replaces
+//! 5. switches `shops.database_config` and records the move in one registry
+//!    transaction
+//!
+//! The source database is left untouched as the rollback point. Rolling
+//! back only switches the configuration back: data written to the target
+//! after the move stays there.
+
+use crate::db::{DatabaseConfig, DatabaseType, PoolManager, RepositoryFactory, ShopPool};
+use crate::features::database::dtos::database_dto::MigrateShopDatabaseDTO;
+use crate::features::database::models::shop_database_migration_model::{
+    ShopDatabaseMigration, TableCopyReport,
+};
+use crate::features::database::repositories::shop_database_migrations_repository::ShopDatabaseMigrationsRepository;
+use crate::features::database::services::shop_database_copy::copy_shop_database;
+use crate::features::shop::repositories::shop_repository::ShopsRepository;
+use chrono::Utc;
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const DEFAULT_MIGRATION_LIMIT: i64 = 50;
+const POSTGRES_UNSUPPORTED: &str =
+    "Shop commands only run on SQLite, so a shop's database can only move to SQLite";
+/// Files SQLite keeps next to a database in WAL mode
+const SQLITE_SIDECAR_SUFFIXES: [&str; 3] = ["", "-wal", "-shm"];
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn remove_sqlite_files(path: &Path) -> Result<(), String> {
+    for suffix in SQLITE_SIDECAR_SUFFIXES {
+        let file = with_suffix(path, suffix);
+        if file.exists() {
+            std::fs::remove_file(&file)
+                .map_err(|e| format!("Failed to remove {}: {}", file.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+fn move_sqlite_files(from: &Path, to: &Path) -> Result<(), String> {
+    for suffix in SQLITE_SIDECAR_SUFFIXES {
+        let file = with_suffix(from, suffix);
+        if file.exists() {
+            std::fs::rename(&file, with_suffix(to, suffix))
+                .map_err(|e| format!("Failed to move {}: {}", file.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+async fn close_shop_pool(pool: &ShopPool) {
+    match pool {
+        ShopPool::Sqlite(pool) => pool.close().await,
+        ShopPool::Postgres(pool) => pool.close().await,
+    }
+}
+
+pub struct ShopDatabaseMigrationService {
+    pool: SqlitePool,
+    repo_factory: Arc<RepositoryFactory>,
+    repo: ShopDatabaseMigrationsRepository,
+}
+
+impl ShopDatabaseMigrationService {
+    pub fn new(pool: SqlitePool, repo_factory: Arc<RepositoryFactory>) -> Self {
+        let repo = ShopDatabaseMigrationsRepository::new(pool.clone());
+        Self {
+            pool,
+            repo_factory,
+            repo,
+        }
+    }
+
+    fn pool_manager(&self) -> &Arc<PoolManager> {
+        self.repo_factory.pool_manager()
+    }
+
+    /// Move a shop from Postgres to SQLite.
+    ///
+    /// The shop is unavailable while its data is copied.
+    pub async fn migrate(
+        &self,
+        payload: MigrateShopDatabaseDTO,
+    ) -> Result<ShopDatabaseMigration, String> {
+        let shop_id = payload.shop_id.clone();
+        let source_config = self
+            .pool_manager()
+            .get_shop_database_config(&shop_id)
+            .await
+            .map_err(|e| format!("Failed to load shop database config: {}", e))?;
+        if source_config.database_type == payload.target_type {
+            return Err(format!("Shop already uses {}", payload.target_type));
+        }
+        if payload.target_type != DatabaseType::Sqlite {
+            return Err(POSTGRES_UNSUPPORTED.to_string());
+        }
+
+        // Pool sizes and SQLite tuning carry over; the target is always the
+        // shop's default file
+        let target_config = DatabaseConfig {
+            database_type: DatabaseType::Sqlite,
+            connection_string: None,
+            ..source_config.clone()
+        };
+
+        if !self.pool_manager().block_shop(&shop_id) {
+            return Err("The shop's database is already being migrated".to_string());
+        }
+
+        let result = self
+            .run_migration(&shop_id, &source_config, &target_config, payload.created_by)
+            .await;
+        self.pool_manager().unblock_shop(&shop_id);
+        result
+    }
+
+    async fn run_migration(
+        &self,
+        shop_id: &str,
+        source_config: &DatabaseConfig,
+        target_config: &DatabaseConfig,
+        created_by: Option<String>,
+    ) -> Result<ShopDatabaseMigration, String> {
+        let to_json = |config: &DatabaseConfig| {
+            config
+                .to_json()
+                .map_err(|e| format!("Failed to serialize database config: {}", e))
+        };
+
+        // Only one move runs per shop, so a running one was interrupted
+        self.repo
+            .fail_running(shop_id, "Interrupted before completion")
+            .await
+            .map_err(|e| format!("Failed to update previous migrations: {}", e))?;
+
+        let mut migration = self
+            .repo
+            .create(&ShopDatabaseMigration {
+                id: Uuid::new_v4().to_string(),
+                shop_id: shop_id.to_string(),
+                source_type: source_config.database_type.as_str().to_string(),
+                target_type: target_config.database_type.as_str().to_string(),
+                source_config: to_json(source_config)?,
+                target_config: to_json(target_config)?,
+                status: "running".to_string(),
+                tables_copied: 0,
+                rows_copied: 0,
+                tables: None,
+                archived_path: None,
+                error: None,
+                created_by,
+                started_at: Utc::now(),
+                finished_at: None,
+                rolled_back_at: None,
+            })
+            .await
+            .map_err(|e| format!("Failed to create migration: {}", e))?;
+
+        let staging_path = self.pool_manager().get_shop_staging_db_path(shop_id);
+        match self
+            .copy_and_switch(&mut migration, source_config, target_config, &staging_path)
+            .await
+        {
+            Ok(()) => Ok(migration),
+            Err(e) => {
+                eprintln!(
+                    "[ShopDatabaseMigration] Shop {} migration failed: {}",
+                    shop_id, e
+                );
+                if let Err(cleanup) = remove_sqlite_files(&staging_path) {
+                    eprintln!("[ShopDatabaseMigration] {}", cleanup);
+                }
+                self.repo
+                    .mark_failed(&migration.id, &e)
+                    .await
+                    .map_err(|db| format!("{} (and failed to record it: {})", e, db))?;
+                Err(e)
+            }
+        }
+    }
+
+    async fn copy_and_switch(
+        &self,
+        migration: &mut ShopDatabaseMigration,
+        source_config: &DatabaseConfig,
+        target_config: &DatabaseConfig,
+        staging_path: &Path,
+    ) -> Result<(), String> {
+        let pool_manager = self.pool_manager();
+        let shop_id = migration.shop_id.clone();
+
+        // Waits for in-flight queries; new ones are refused while blocked
+        pool_manager.invalidate_shop_pool(&shop_id).await;
+
+        let source = pool_manager
+            .connect_shop_database(&shop_id, source_config)
+            .await
+            .map_err(|e| format!("Failed to open source database: {}", e))?;
+
+        if let Err(e) = remove_sqlite_files(staging_path) {
+            close_shop_pool(&source).await;
+            return Err(e);
+        }
+        let staging_config = DatabaseConfig {
+            connection_string: Some(staging_path.to_string_lossy().to_string()),
+            ..target_config.clone()
+        };
+        let target = match pool_manager
+            .connect_shop_database(&shop_id, &staging_config)
+            .await
+        {
+            Ok(target) => target,
+            Err(e) => {
+                close_shop_pool(&source).await;
+                return Err(format!("Failed to open target database: {}", e));
+            }
+        };
+
+        let copied = self.prepare_and_copy(&shop_id, &source, &target).await;
+        close_shop_pool(&source).await;
+        close_shop_pool(&target).await;
+        let reports = copied?;
+
+        // Keep whatever sits at the shop's default path, e.g. a file left
+        // behind before the shop was set up on Postgres
+        let db_path = pool_manager.get_shop_db_path(&shop_id);
+        if db_path.exists() {
+            let archived = db_path.with_file_name(format!(
+                "shop_{}.{}.bak.db",
+                shop_id,
+                Utc::now().format("%Y%m%d%H%M%S")
+            ));
+            move_sqlite_files(&db_path, &archived)?;
+            migration.archived_path = Some(archived.to_string_lossy().to_string());
+        }
+        move_sqlite_files(staging_path, &db_path)?;
+
+        migration.status = "completed".to_string();
+        migration.tables_copied = reports.len() as i64;
+        migration.rows_copied = reports.iter().map(|r| r.target_rows).sum();
+        migration.tables = Some(
+            serde_json::to_string(&reports)
+                .map_err(|e| format!("Failed to serialize copy report: {}", e))?,
+        );
+        migration.finished_at = Some(Utc::now());
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+        ShopsRepository::update_database_config_in_tx(
+            &mut tx,
+            &shop_id,
+            target_config.database_type.as_str(),
+            &migration.target_config,
+        )
+        .await
+        .map_err(|e| format!("Failed to switch shop database config: {}", e))?;
+        ShopDatabaseMigrationsRepository::mark_completed_in_tx(&mut tx, migration)
+            .await
+            .map_err(|e| format!("Failed to complete migration: {}", e))?;
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit migration: {}", e))?;
+
+        println!(
+            "[ShopDatabaseMigration] Shop {} moved to {}: {} tables, {} rows",
+            shop_id, migration.target_type, migration.tables_copied, migration.rows_copied
+        );
+        Ok(())
+    }
+
+    async fn prepare_and_copy(
+        &self,
+        shop_id: &str,
+        source: &ShopPool,
+        target: &ShopPool,
+    ) -> Result<Vec<TableCopyReport>, String> {
+        self.repo_factory
+            .migration_service()
+            .migrate_shop_pool(target, &format!("shop_{}", shop_id))
+            .await
+            .map_err(|e| format!("Failed to create target schema: {}", e))?;
+
+        copy_shop_database(shop_id, source, target).await
+    }
+
+    /// Switch a shop back to the database it was moved from.
+    ///
+    /// Only the shop's latest move can be rolled back, and only once. Moves
+    /// only leave Postgres, whose rollback would put the shop back there, so
+    /// this is refused for every move until shops can run on Postgres.
+    pub async fn rollback(&self, migration_id: &str) -> Result<ShopDatabaseMigration, String> {
+        let migration = self
+            .repo
+            .find_by_id(migration_id)
+            .await
+            .map_err(|e| format!("Failed to fetch migration: {}", e))?
+            .ok_or("Migration not found")?;
+        if migration.status != "completed" {
+            return Err(format!("Cannot roll back a {} migration", migration.status));
+        }
+        // Earlier moves' sources may have been replaced since
+        let latest = self
+            .repo
+            .find_latest_applied(&migration.shop_id)
+            .await
+            .map_err(|e| format!("Failed to fetch migrations: {}", e))?;
+        if latest.as_ref().map(|m| m.id.as_str()) != Some(migration.id.as_str()) {
+            return Err("Only the shop's latest migration can be rolled back".to_string());
+        }
+
+        let shop_id = migration.shop_id.clone();
+        let source_config = DatabaseConfig::from_json(&migration.source_config)
+            .map_err(|e| format!("Invalid source database config: {}", e))?;
+        if source_config.database_type == DatabaseType::Postgres {
+            return Err(POSTGRES_UNSUPPORTED.to_string());
+        }
+
+        if !self.pool_manager().block_shop(&shop_id) {
+            return Err("The shop's database is already being migrated".to_string());
+        }
+        let result = self.switch_back(&migration, &source_config).await;
+        self.pool_manager().unblock_shop(&shop_id);
+        result?;
+
+        self.repo
+            .find_by_id(migration_id)
+            .await
+            .map_err(|e| format!("Failed to fetch migration: {}", e))?
+            .ok_or_else(|| "Migration not found".to_string())
+    }
+
+    async fn switch_back(
+        &self,
+        migration: &ShopDatabaseMigration,
+        source_config: &DatabaseConfig,
+    ) -> Result<(), String> {
+        let pool_manager = self.pool_manager();
+        let shop_id = &migration.shop_id;
+
+        // The rollback point must still be there
+        let path = source_config
+            .connection_string
+            .as_ref()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| pool_manager.get_shop_db_path(shop_id));
+        if !path.exists() {
+            return Err(format!(
+                "Source database {} no longer exists",
+                path.display()
+            ));
+        }
+
+        pool_manager.invalidate_shop_pool(shop_id).await;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+        ShopsRepository::update_database_config_in_tx(
+            &mut tx,
+            shop_id,
+            &migration.source_type,
+            &migration.source_config,
+        )
+        .await
+        .map_err(|e| format!("Failed to switch shop database config: {}", e))?;
+        ShopDatabaseMigrationsRepository::mark_rolled_back_in_tx(&mut tx, &migration.id)
+            .await
+            .map_err(|e| format!("Failed to roll back migration: {}", e))?;
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit rollback: {}", e))?;
+        Ok(())
+    }
+
+    pub async fn get_migration(&self, id: &str) -> Result<Option<ShopDatabaseMigration>, String> {
+        self.repo
+            .find_by_id(id)
+            .await
+            .map_err(|e| format!("Failed to fetch migration: {}", e))
+    }
+
+    pub async fn list_migrations(
+        &self,
+        shop_id: Option<String>,
+        limit: Option<i64>,
+    ) -> Result<Vec<ShopDatabaseMigration>, String> {
+        self.repo
+            .list(shop_id.as_deref(), limit.unwrap_or(DEFAULT_MIGRATION_LIMIT))
+            .await
+            .map_err(|e| format!("Failed to list migrations: {}", e))
+    }
+}
@@ -0,0 +1,44 @@
+//! Background job running maintenance on the shop SQLite databases
+//!
+//! Started from the app setup; every hour it runs
+//! `SqliteMaintenanceService::run_scheduled` for each SQLite shop whose
+//! database is open (see `run_for_open_shops`). Closed shops are left alone:
+//! their WAL is checkpointed and `PRAGMA optimize` run when the pool's
+//! connections close.
+
+use crate::db::RepositoryFactory;
+use crate::features::database::services::sqlite_maintenance_service::SqliteMaintenanceService;
+use crate::features::shop::services::shop_scheduler::run_for_open_shops;
+use std::sync::Arc;
+use std::time::Duration;
+
+const SQLITE_MAINTENANCE_INTERVAL: Duration = Duration::from_secs(3600);
+
+pub async fn run_sqlite_maintenance(repo_factory: Arc<RepositoryFactory>) {
+    let service = Arc::new(SqliteMaintenanceService::new(
+        repo_factory.registry_pool().clone(),
+        repo_factory.clone(),
+    ));
+    run_for_open_shops(
+        repo_factory,
+        SQLITE_MAINTENANCE_INTERVAL,
+        "sqlite_maintenance",
+        |shop_id, pool| {
+            let service = service.clone();
+            async move {
+                let run = service.run_scheduled(&shop_id, &pool).await?;
+                if run.status == "ok" {
+                    return Ok(());
+                }
+                Err(format!(
+                    "{} ({})",
+                    run.status,
+                    run.error
+                        .or(run.integrity_check)
+                        .unwrap_or_else(|| "WAL checkpoint busy".to_string())
+                ))
+            }
+        },
+    )
+    .await
+}
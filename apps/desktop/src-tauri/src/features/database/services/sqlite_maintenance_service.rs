@@ -0,0 +1,278 @@
+//! Maintenance of shop SQLite databases
+//!
+//! A pass runs on a single connection of the shop's pool:
+//! - `PRAGMA integrity_check` (optional)
+//! - `PRAGMA optimize`
+//! - `PRAGMA incremental_vacuum` when the file uses incremental auto_vacuum,
+//!   or a full VACUUM on request
+//! - a WAL checkpoint: PASSIVE when scheduled, so it never waits on the POS,
+//!   TRUNCATE when run by hand
+//!
+//! Every pass is recorded in the registry, failed ones included.
+
+use crate::db::{DatabaseType, RepositoryFactory};
+use crate::features::database::dtos::database_dto::RunDatabaseMaintenanceDTO;
+use crate::features::database::models::database_maintenance_model::DatabaseMaintenanceRun;
+use crate::features::database::repositories::database_maintenance_runs_repository::DatabaseMaintenanceRunsRepository;
+use chrono::Utc;
+use sqlx::sqlite::SqliteConnection;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use std::time::Instant;
+use uuid::Uuid;
+
+/// Problems reported by one integrity check at most
+const INTEGRITY_CHECK_MAX_ERRORS: u32 = 100;
+/// Free pages released per scheduled pass, bounding how long the write lock is held
+const SCHEDULED_VACUUM_PAGES: u32 = 2_000;
+/// Minimum time between two scheduled integrity checks of a shop
+const INTEGRITY_CHECK_INTERVAL_HOURS: i64 = 24;
+const DEFAULT_RUN_LIMIT: i64 = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaintenanceTrigger {
+    Schedule,
+    Manual,
+}
+
+impl MaintenanceTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Schedule => "schedule",
+            Self::Manual => "manual",
+        }
+    }
+
+    fn checkpoint_mode(&self) -> &'static str {
+        match self {
+            Self::Schedule => "PASSIVE",
+            Self::Manual => "TRUNCATE",
+        }
+    }
+}
+
+struct MaintenancePlan {
+    trigger: MaintenanceTrigger,
+    integrity_check: bool,
+    full_vacuum: bool,
+    /// Switch the file to incremental auto_vacuum when fully vacuuming it
+    incremental_auto_vacuum: bool,
+}
+
+async fn pragma_i64(conn: &mut SqliteConnection, name: &str) -> Result<i64, String> {
+    sqlx::query_scalar::<_, i64>(&format!("PRAGMA {}", name))
+        .fetch_one(conn)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", name, e))
+}
+
+pub struct SqliteMaintenanceService {
+    repo_factory: Arc<RepositoryFactory>,
+    repo: DatabaseMaintenanceRunsRepository,
+}
+
+impl SqliteMaintenanceService {
+    pub fn new(pool: SqlitePool, repo_factory: Arc<RepositoryFactory>) -> Self {
+        let repo = DatabaseMaintenanceRunsRepository::new(pool);
+        Self { repo_factory, repo }
+    }
+
+    /// Run a full pass on a shop's database now
+    pub async fn run_maintenance(
+        &self,
+        shop_id: &str,
+        payload: RunDatabaseMaintenanceDTO,
+    ) -> Result<DatabaseMaintenanceRun, String> {
+        let config = self
+            .repo_factory
+            .pool_manager()
+            .get_shop_database_config(shop_id)
+            .await
+            .map_err(|e| format!("Failed to load shop database config: {}", e))?;
+        if config.database_type != DatabaseType::Sqlite {
+            return Err("Database maintenance is only available for SQLite shops".to_string());
+        }
+
+        let pool = self
+            .repo_factory
+            .shop_pool(shop_id)
+            .await
+            .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+        let plan = MaintenancePlan {
+            trigger: MaintenanceTrigger::Manual,
+            integrity_check: payload.integrity_check.unwrap_or(true),
+            full_vacuum: payload.full_vacuum.unwrap_or(false),
+            incremental_auto_vacuum: config.sqlite.incremental_vacuum,
+        };
+        self.run_on_pool(shop_id, &pool, plan).await
+    }
+
+    /// Run the scheduled pass on an open shop pool.
+    ///
+    /// The integrity check only runs when the last one is older than a day.
+    pub async fn run_scheduled(
+        &self,
+        shop_id: &str,
+        pool: &SqlitePool,
+    ) -> Result<DatabaseMaintenanceRun, String> {
+        let last_check = self
+            .repo
+            .last_integrity_check_at(shop_id)
+            .await
+            .map_err(|e| format!("Failed to fetch last integrity check: {}", e))?;
+        let integrity_check = match last_check {
+            Some(at) => Utc::now() - at >= chrono::Duration::hours(INTEGRITY_CHECK_INTERVAL_HOURS),
+            None => true,
+        };
+
+        let plan = MaintenancePlan {
+            trigger: MaintenanceTrigger::Schedule,
+            integrity_check,
+            full_vacuum: false,
+            incremental_auto_vacuum: false,
+        };
+        self.run_on_pool(shop_id, pool, plan).await
+    }
+
+    pub async fn list_runs(
+        &self,
+        shop_id: Option<String>,
+        limit: Option<i64>,
+    ) -> Result<Vec<DatabaseMaintenanceRun>, String> {
+        self.repo
+            .list(shop_id.as_deref(), limit.unwrap_or(DEFAULT_RUN_LIMIT))
+            .await
+            .map_err(|e| format!("Failed to list maintenance runs: {}", e))
+    }
+
+    async fn run_on_pool(
+        &self,
+        shop_id: &str,
+        pool: &SqlitePool,
+        plan: MaintenancePlan,
+    ) -> Result<DatabaseMaintenanceRun, String> {
+        let started_at = Utc::now();
+        let started = Instant::now();
+        let mut run = DatabaseMaintenanceRun {
+            id: Uuid::new_v4().to_string(),
+            shop_id: shop_id.to_string(),
+            triggered_by: plan.trigger.as_str().to_string(),
+            status: "ok".to_string(),
+            integrity_check: None,
+            optimized: false,
+            checkpoint_mode: None,
+            checkpoint_busy: None,
+            checkpoint_log_frames: None,
+            checkpointed_frames: None,
+            vacuum_mode: None,
+            pages_freed: 0,
+            page_count: None,
+            freelist_count: None,
+            size_bytes: None,
+            error: None,
+            duration_ms: 0,
+            started_at,
+            finished_at: started_at,
+        };
+
+        if let Err(e) = Self::maintain(pool, &plan, &mut run).await {
+            run.error = Some(e);
+        }
+
+        run.status = if run.error.is_some() {
+            "failed"
+        } else if run.integrity_check.as_deref().is_some_and(|r| r != "ok")
+            || run.checkpoint_busy == Some(true)
+        {
+            "warning"
+        } else {
+            "ok"
+        }
+        .to_string();
+        run.duration_ms = started.elapsed().as_millis() as i64;
+        run.finished_at = Utc::now();
+
+        self.repo
+            .create(&run)
+            .await
+            .map_err(|e| format!("Failed to record maintenance run: {}", e))
+    }
+
+    async fn maintain(
+        pool: &SqlitePool,
+        plan: &MaintenancePlan,
+        run: &mut DatabaseMaintenanceRun,
+    ) -> Result<(), String> {
+        let mut conn = pool
+            .acquire()
+            .await
+            .map_err(|e| format!("Failed to acquire connection: {}", e))?;
+
+        if plan.integrity_check {
+            let sql = format!("PRAGMA integrity_check({})", INTEGRITY_CHECK_MAX_ERRORS);
+            let problems = sqlx::query_scalar::<_, String>(&sql)
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to check integrity: {}", e))?;
+            run.integrity_check = Some(problems.join("\n"));
+        }
+
+        sqlx::query("PRAGMA optimize")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to optimize: {}", e))?;
+        run.optimized = true;
+
+        let freelist_before = pragma_i64(&mut conn, "freelist_count").await?;
+        if plan.full_vacuum {
+            // VACUUM rewrites the file, so it also applies a new auto_vacuum mode
+            if plan.incremental_auto_vacuum {
+                sqlx::query("PRAGMA auto_vacuum = INCREMENTAL")
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| format!("Failed to set auto_vacuum: {}", e))?;
+            }
+            sqlx::query("VACUUM")
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to vacuum: {}", e))?;
+            run.vacuum_mode = Some("full".to_string());
+        } else if freelist_before > 0 && pragma_i64(&mut conn, "auto_vacuum").await? == 2 {
+            let sql = match plan.trigger {
+                MaintenanceTrigger::Schedule => {
+                    format!("PRAGMA incremental_vacuum({})", SCHEDULED_VACUUM_PAGES)
+                }
+                MaintenanceTrigger::Manual => "PRAGMA incremental_vacuum".to_string(),
+            };
+            sqlx::query(&sql)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to run incremental vacuum: {}", e))?;
+            run.vacuum_mode = Some("incremental".to_string());
+        }
+
+        // Checkpoint last, so pages rewritten by the vacuum leave the WAL too
+        let mode = plan.trigger.checkpoint_mode();
+        let (busy, log_frames, checkpointed_frames) =
+            sqlx::query_as::<_, (i64, i64, i64)>(&format!("PRAGMA wal_checkpoint({})", mode))
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to checkpoint WAL: {}", e))?;
+        run.checkpoint_mode = Some(mode.to_string());
+        run.checkpoint_busy = Some(busy != 0);
+        // -1 when the database is not in WAL mode
+        run.checkpoint_log_frames = (log_frames >= 0).then_some(log_frames);
+        run.checkpointed_frames = (checkpointed_frames >= 0).then_some(checkpointed_frames);
+
+        let freelist_after = pragma_i64(&mut conn, "freelist_count").await?;
+        let page_count = pragma_i64(&mut conn, "page_count").await?;
+        let page_size = pragma_i64(&mut conn, "page_size").await?;
+        run.pages_freed = (freelist_before - freelist_after).max(0);
+        run.freelist_count = Some(freelist_after);
+        run.page_count = Some(page_count);
+        run.size_bytes = Some(page_count * page_size);
+
+        Ok(())
+    }
+}
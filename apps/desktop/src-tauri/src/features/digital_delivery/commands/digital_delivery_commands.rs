@@ -0,0 +1,164 @@
+use crate::db::RepositoryFactory;
+use crate::features::digital_delivery::dtos::digital_delivery_dto::{
+    AddLicenseKeysDTO, UpsertDigitalProductDTO,
+};
+use crate::features::digital_delivery::models::digital_delivery_model::{
+    DigitalDelivery, DigitalProduct, DownloadGrant, LicenseKey, LicenseKeyImportResult,
+    LicenseKeyStock,
+};
+use crate::features::digital_delivery::services::shop_digital_delivery_service::ShopDigitalDeliveryService;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn upsert_digital_product(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: UpsertDigitalProductDTO,
+) -> Result<DigitalProduct, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopDigitalDeliveryService::new(pool, shop_id);
+    service.upsert_digital_product(payload).await
+}
+
+#[tauri::command]
+pub async fn get_digital_product(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    product_id: String,
+) -> Result<Option<DigitalProduct>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopDigitalDeliveryService::new(pool, shop_id);
+    service.get_digital_product(&product_id).await
+}
+
+#[tauri::command]
+pub async fn add_license_keys(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: AddLicenseKeysDTO,
+) -> Result<LicenseKeyImportResult, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopDigitalDeliveryService::new(pool, shop_id);
+    service.add_license_keys(payload).await
+}
+
+#[tauri::command]
+pub async fn list_license_keys(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    product_id: String,
+    status: Option<String>,
+) -> Result<Vec<LicenseKey>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopDigitalDeliveryService::new(pool, shop_id);
+    service
+        .list_license_keys(&product_id, status.as_deref())
+        .await
+}
+
+#[tauri::command]
+pub async fn get_license_key_stock(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    product_id: String,
+) -> Result<LicenseKeyStock, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopDigitalDeliveryService::new(pool, shop_id);
+    service.get_license_key_stock(&product_id).await
+}
+
+#[tauri::command]
+pub async fn revoke_license_key(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+) -> Result<LicenseKey, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopDigitalDeliveryService::new(pool, shop_id);
+    service.revoke_license_key(&id).await
+}
+
+#[tauri::command]
+pub async fn list_digital_deliveries(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    transaction_id: String,
+) -> Result<Vec<DigitalDelivery>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopDigitalDeliveryService::new(pool, shop_id);
+    service.list_transaction_deliveries(&transaction_id).await
+}
+
+#[tauri::command]
+pub async fn list_customer_digital_deliveries(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    customer_id: String,
+) -> Result<Vec<DigitalDelivery>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopDigitalDeliveryService::new(pool, shop_id);
+    service.list_customer_deliveries(&customer_id).await
+}
+
+#[tauri::command]
+pub async fn redeem_download(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    token: String,
+) -> Result<DownloadGrant, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopDigitalDeliveryService::new(pool, shop_id);
+    service.redeem_download(&token).await
+}
+
+#[tauri::command]
+pub async fn revoke_digital_delivery(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+) -> Result<DigitalDelivery, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopDigitalDeliveryService::new(pool, shop_id);
+    service.revoke_delivery(&id).await
+}
@@ -0,0 +1,36 @@
+use crate::features::digital_delivery::models::digital_delivery_model::DigitalProduct;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpsertDigitalProductDTO {
+    pub product_id: String,
+    pub delivery_method: String, // 'license_key', 'download_link'
+    pub download_url: Option<String>,
+    pub download_limit: Option<i64>,
+    pub link_expiry_days: Option<i64>,
+    pub instructions: Option<String>,
+}
+
+impl UpsertDigitalProductDTO {
+    pub fn into_model(self) -> DigitalProduct {
+        let now = Utc::now();
+        DigitalProduct {
+            product_id: self.product_id,
+            delivery_method: self.delivery_method,
+            download_url: self.download_url,
+            download_limit: self.download_limit,
+            link_expiry_days: self.link_expiry_days,
+            instructions: self.instructions,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddLicenseKeysDTO {
+    pub product_id: String,
+    pub keys: Vec<String>,
+}
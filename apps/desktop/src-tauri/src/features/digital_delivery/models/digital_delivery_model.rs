@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Fulfillment settings of a digital product
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct DigitalProduct {
+    pub product_id: String,
+    pub delivery_method: String, // 'license_key', 'download_link'
+    pub download_url: Option<String>,
+    pub download_limit: Option<i64>, // Downloads per link, unlimited when None
+    pub link_expiry_days: Option<i64>, // Links never expire when None
+    pub instructions: Option<String>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct LicenseKey {
+    pub id: String,
+    pub product_id: String,
+    pub license_key: String,
+    pub status: String, // 'available', 'assigned', 'revoked'
+    pub assigned_at: Option<DateTime<Utc>>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A license key or download link handed to a customer for a sold item
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct DigitalDelivery {
+    pub id: String,
+    pub transaction_id: String,
+    pub transaction_item_id: String,
+    pub product_id: Option<String>,
+    pub customer_id: Option<String>,
+    pub delivery_method: String, // 'license_key', 'download_link'
+    pub status: String,          // 'pending' (waiting for keys), 'delivered', 'revoked'
+    pub license_key_id: Option<String>,
+    pub license_key: Option<String>,
+    pub download_token: Option<String>,
+    pub download_limit: Option<i64>,
+    pub download_count: i64,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub last_downloaded_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct LicenseKeyStock {
+    pub product_id: String,
+    pub available: i64,
+    pub assigned: i64,
+    pub revoked: i64,
+    pub pending_deliveries: i64, // Sold units waiting for a key
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LicenseKeyImportResult {
+    pub added: usize,
+    pub duplicates: usize,           // Already in the pool, skipped
+    pub deliveries_fulfilled: usize, // Pending deliveries given one of the new keys
+}
+
+/// Access to a download granted by a link
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DownloadGrant {
+    pub delivery_id: String,
+    pub product_id: Option<String>,
+    pub download_url: String,
+    pub downloads_remaining: Option<i64>, // None when unlimited
+    pub expires_at: Option<DateTime<Utc>>,
+}
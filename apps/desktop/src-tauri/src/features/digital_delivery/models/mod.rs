@@ -0,0 +1 @@
+pub mod digital_delivery_model;
@@ -0,0 +1,344 @@
+//! Shop-scoped Digital Delivery Repository for Multi-Database Architecture
+
+use crate::features::digital_delivery::models::digital_delivery_model::{
+    DigitalDelivery, DigitalProduct, LicenseKey, LicenseKeyStock,
+};
+use sqlx::{Result, Sqlite, SqlitePool, Transaction};
+use std::sync::Arc;
+
+pub struct ShopDigitalDeliveryRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopDigitalDeliveryRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    // ============================================================
+    // Digital products
+    // ============================================================
+
+    pub async fn get_product(&self, product_id: &str) -> Result<Option<DigitalProduct>> {
+        let sql = "SELECT * FROM digital_products WHERE product_id = $1";
+        sqlx::query_as::<_, DigitalProduct>(sql)
+            .bind(product_id)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    /// Settings of a product, if it is still a digital product
+    pub async fn get_digital_product_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        product_id: &str,
+    ) -> Result<Option<DigitalProduct>> {
+        let sql = r#"
+            SELECT dp.* FROM digital_products dp
+            JOIN products p ON p.id = dp.product_id
+            WHERE dp.product_id = $1 AND p.type = 'digital'
+        "#;
+        sqlx::query_as::<_, DigitalProduct>(sql)
+            .bind(product_id)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    pub async fn upsert_product(&self, product: &DigitalProduct) -> Result<DigitalProduct> {
+        let sql = r#"
+            INSERT INTO digital_products (
+                product_id, delivery_method, download_url, download_limit, link_expiry_days,
+                instructions, _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (product_id) DO UPDATE SET
+                delivery_method = excluded.delivery_method,
+                download_url = excluded.download_url,
+                download_limit = excluded.download_limit,
+                link_expiry_days = excluded.link_expiry_days,
+                instructions = excluded.instructions,
+                _status = 'modified',
+                updated_at = excluded.updated_at
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, DigitalProduct>(sql)
+            .bind(&product.product_id)
+            .bind(&product.delivery_method)
+            .bind(&product.download_url)
+            .bind(product.download_limit)
+            .bind(product.link_expiry_days)
+            .bind(&product.instructions)
+            .bind(&product.sync_status)
+            .bind(product.created_at)
+            .bind(product.updated_at)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    // ============================================================
+    // License keys
+    // ============================================================
+
+    /// Add a key to the pool. Returns false when the product already has it.
+    pub async fn add_key_in_tx(tx: &mut Transaction<'_, Sqlite>, key: &LicenseKey) -> Result<bool> {
+        let sql = r#"
+            INSERT INTO license_keys (
+                id, product_id, license_key, status, assigned_at, _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (product_id, license_key) DO NOTHING
+        "#;
+        let result = sqlx::query(sql)
+            .bind(&key.id)
+            .bind(&key.product_id)
+            .bind(&key.license_key)
+            .bind(&key.status)
+            .bind(key.assigned_at)
+            .bind(&key.sync_status)
+            .bind(key.created_at)
+            .bind(key.updated_at)
+            .execute(&mut **tx)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_key(&self, id: &str) -> Result<Option<LicenseKey>> {
+        let sql = "SELECT * FROM license_keys WHERE id = $1";
+        sqlx::query_as::<_, LicenseKey>(sql)
+            .bind(id)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn list_keys(
+        &self,
+        product_id: &str,
+        status: Option<&str>,
+    ) -> Result<Vec<LicenseKey>> {
+        let sql = r#"
+            SELECT * FROM license_keys
+            WHERE product_id = $1 AND ($2 IS NULL OR status = $2)
+            ORDER BY created_at ASC, id ASC
+        "#;
+        sqlx::query_as::<_, LicenseKey>(sql)
+            .bind(product_id)
+            .bind(status)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn key_stock(&self, product_id: &str) -> Result<LicenseKeyStock> {
+        let sql = r#"
+            SELECT
+                $1 AS product_id,
+                COALESCE(SUM(CASE WHEN status = 'available' THEN 1 ELSE 0 END), 0) AS available,
+                COALESCE(SUM(CASE WHEN status = 'assigned' THEN 1 ELSE 0 END), 0) AS assigned,
+                COALESCE(SUM(CASE WHEN status = 'revoked' THEN 1 ELSE 0 END), 0) AS revoked,
+                (SELECT COUNT(*) FROM digital_deliveries
+                 WHERE product_id = $1 AND status = 'pending') AS pending_deliveries
+            FROM license_keys
+            WHERE product_id = $1
+        "#;
+        sqlx::query_as::<_, LicenseKeyStock>(sql)
+            .bind(product_id)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    /// Oldest key still available
+    pub async fn next_available_key_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        product_id: &str,
+    ) -> Result<Option<LicenseKey>> {
+        let sql = r#"
+            SELECT * FROM license_keys
+            WHERE product_id = $1 AND status = 'available'
+            ORDER BY created_at ASC, id ASC
+            LIMIT 1
+        "#;
+        sqlx::query_as::<_, LicenseKey>(sql)
+            .bind(product_id)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    /// Move a key from `from_status` to `to_status`. Returns false when the
+    /// key was not in `from_status`.
+    pub async fn set_key_status_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+        from_status: &str,
+        to_status: &str,
+    ) -> Result<bool> {
+        let sql = r#"
+            UPDATE license_keys
+            SET status = $3,
+                assigned_at = CASE WHEN $3 = 'assigned' THEN datetime('now') ELSE assigned_at END,
+                _status = 'modified', updated_at = datetime('now')
+            WHERE id = $1 AND status = $2
+        "#;
+        let result = sqlx::query(sql)
+            .bind(id)
+            .bind(from_status)
+            .bind(to_status)
+            .execute(&mut **tx)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ============================================================
+    // Deliveries
+    // ============================================================
+
+    pub async fn create_delivery_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        delivery: &DigitalDelivery,
+    ) -> Result<DigitalDelivery> {
+        let sql = r#"
+            INSERT INTO digital_deliveries (
+                id, transaction_id, transaction_item_id, product_id, customer_id, delivery_method,
+                status, license_key_id, license_key, download_token, download_limit, download_count,
+                expires_at, delivered_at, last_downloaded_at, revoked_at, _status, created_at, updated_at
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19
+            )
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, DigitalDelivery>(sql)
+            .bind(&delivery.id)
+            .bind(&delivery.transaction_id)
+            .bind(&delivery.transaction_item_id)
+            .bind(&delivery.product_id)
+            .bind(&delivery.customer_id)
+            .bind(&delivery.delivery_method)
+            .bind(&delivery.status)
+            .bind(&delivery.license_key_id)
+            .bind(&delivery.license_key)
+            .bind(&delivery.download_token)
+            .bind(delivery.download_limit)
+            .bind(delivery.download_count)
+            .bind(delivery.expires_at)
+            .bind(delivery.delivered_at)
+            .bind(delivery.last_downloaded_at)
+            .bind(delivery.revoked_at)
+            .bind(&delivery.sync_status)
+            .bind(delivery.created_at)
+            .bind(delivery.updated_at)
+            .fetch_one(&mut **tx)
+            .await
+    }
+
+    pub async fn get_delivery(&self, id: &str) -> Result<Option<DigitalDelivery>> {
+        let sql = "SELECT * FROM digital_deliveries WHERE id = $1";
+        sqlx::query_as::<_, DigitalDelivery>(sql)
+            .bind(id)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn find_by_token(&self, token: &str) -> Result<Option<DigitalDelivery>> {
+        let sql = "SELECT * FROM digital_deliveries WHERE download_token = $1";
+        sqlx::query_as::<_, DigitalDelivery>(sql)
+            .bind(token)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn list_by_transaction(&self, transaction_id: &str) -> Result<Vec<DigitalDelivery>> {
+        let sql = r#"
+            SELECT * FROM digital_deliveries
+            WHERE transaction_id = $1
+            ORDER BY created_at ASC, id ASC
+        "#;
+        sqlx::query_as::<_, DigitalDelivery>(sql)
+            .bind(transaction_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn list_by_transaction_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        transaction_id: &str,
+    ) -> Result<Vec<DigitalDelivery>> {
+        let sql = r#"
+            SELECT * FROM digital_deliveries
+            WHERE transaction_id = $1
+            ORDER BY created_at ASC, id ASC
+        "#;
+        sqlx::query_as::<_, DigitalDelivery>(sql)
+            .bind(transaction_id)
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    pub async fn list_by_customer(&self, customer_id: &str) -> Result<Vec<DigitalDelivery>> {
+        let sql = r#"
+            SELECT * FROM digital_deliveries
+            WHERE customer_id = $1
+            ORDER BY created_at DESC
+        "#;
+        sqlx::query_as::<_, DigitalDelivery>(sql)
+            .bind(customer_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// License-key deliveries of a product still waiting for a key, oldest first
+    pub async fn list_pending_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        product_id: &str,
+    ) -> Result<Vec<DigitalDelivery>> {
+        let sql = r#"
+            SELECT * FROM digital_deliveries
+            WHERE product_id = $1 AND status = 'pending' AND delivery_method = 'license_key'
+            ORDER BY created_at ASC, id ASC
+        "#;
+        sqlx::query_as::<_, DigitalDelivery>(sql)
+            .bind(product_id)
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    /// Hand a key to a pending delivery
+    pub async fn fulfil_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+        key: &LicenseKey,
+    ) -> Result<()> {
+        let sql = r#"
+            UPDATE digital_deliveries
+            SET status = 'delivered', license_key_id = $2, license_key = $3,
+                delivered_at = datetime('now'), _status = 'modified', updated_at = datetime('now')
+            WHERE id = $1 AND status = 'pending'
+        "#;
+        sqlx::query(sql)
+            .bind(id)
+            .bind(&key.id)
+            .bind(&key.license_key)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    /// Count a download, unless the link ran out. Returns whether it was
+    /// counted.
+    pub async fn record_download(&self, id: &str) -> Result<bool> {
+        let sql = r#"
+            UPDATE digital_deliveries
+            SET download_count = download_count + 1, last_downloaded_at = datetime('now'),
+                _status = 'modified', updated_at = datetime('now')
+            WHERE id = $1 AND status = 'delivered'
+              AND (download_limit IS NULL OR download_count < download_limit)
+        "#;
+        let result = sqlx::query(sql).bind(id).execute(&*self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn revoke_in_tx(tx: &mut Transaction<'_, Sqlite>, id: &str) -> Result<()> {
+        let sql = r#"
+            UPDATE digital_deliveries
+            SET status = 'revoked', revoked_at = datetime('now'),
+                _status = 'modified', updated_at = datetime('now')
+            WHERE id = $1
+        "#;
+        sqlx::query(sql).bind(id).execute(&mut **tx).await?;
+        Ok(())
+    }
+}
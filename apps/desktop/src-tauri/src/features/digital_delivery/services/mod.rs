@@ -0,0 +1 @@
+pub mod shop_digital_delivery_service;
@@ -0,0 +1,507 @@
+//! Shop-scoped Digital Delivery Service for Multi-Database Architecture
+//!
+//! Digital products are fulfilled when the sale completes: each unit of a
+//! license-key product takes the oldest available key from the pool, and a
+//! download-link product gets one link per sold line. Units sold while the
+//! pool is empty wait as 'pending' deliveries and are fulfilled, oldest
+//! first, as soon as keys are added.
+
+use crate::db::DbTransaction;
+use crate::features::bundle::repositories::shop_bundle_repository::ShopBundleRepository;
+use crate::features::digital_delivery::dtos::digital_delivery_dto::{
+    AddLicenseKeysDTO, UpsertDigitalProductDTO,
+};
+use crate::features::digital_delivery::models::digital_delivery_model::{
+    DigitalDelivery, DigitalProduct, DownloadGrant, LicenseKey, LicenseKeyImportResult,
+    LicenseKeyStock,
+};
+use crate::features::digital_delivery::repositories::shop_digital_delivery_repository::ShopDigitalDeliveryRepository;
+use crate::features::product::repositories::shop_product_repository::ShopProductRepository;
+use crate::features::transaction::models::transaction_model::{Transaction, TransactionItem};
+use chrono::{Duration, Utc};
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const DELIVERY_METHODS: [&str; 2] = ["license_key", "download_link"];
+
+pub struct ShopDigitalDeliveryService {
+    pool: Arc<SqlitePool>,
+    shop_id: String,
+    repo: ShopDigitalDeliveryRepository,
+}
+
+impl ShopDigitalDeliveryService {
+    pub fn new(pool: Arc<SqlitePool>, shop_id: String) -> Self {
+        let repo = ShopDigitalDeliveryRepository::new(pool.clone());
+        Self {
+            pool,
+            shop_id,
+            repo,
+        }
+    }
+
+    pub fn shop_id(&self) -> &str {
+        &self.shop_id
+    }
+
+    /// Fail unless the product exists and is a digital product
+    async fn require_digital_product(&self, product_id: &str) -> Result<(), String> {
+        let product = ShopProductRepository::new(self.pool.clone(), self.shop_id.clone())
+            .get_by_id(product_id)
+            .await
+            .map_err(|e| format!("Failed to fetch product: {}", e))?
+            .ok_or_else(|| format!("Product not found: {}", product_id))?;
+        if product.r#type != "digital" {
+            return Err(format!("Product {} is not a digital product", product.sku));
+        }
+        Ok(())
+    }
+
+    // ============================================================
+    // Settings and key pool
+    // ============================================================
+
+    pub async fn upsert_digital_product(
+        &self,
+        payload: UpsertDigitalProductDTO,
+    ) -> Result<DigitalProduct, String> {
+        self.require_digital_product(&payload.product_id).await?;
+        if !DELIVERY_METHODS.contains(&payload.delivery_method.as_str()) {
+            return Err(format!(
+                "Invalid delivery method: {} (expected license_key or download_link)",
+                payload.delivery_method
+            ));
+        }
+        let has_url = payload
+            .download_url
+            .as_deref()
+            .is_some_and(|url| !url.trim().is_empty());
+        if payload.delivery_method == "download_link" && !has_url {
+            return Err("download_url is required for download links".to_string());
+        }
+        if payload.download_limit.is_some_and(|limit| limit <= 0) {
+            return Err("download_limit must be greater than zero".to_string());
+        }
+        if payload.link_expiry_days.is_some_and(|days| days <= 0) {
+            return Err("link_expiry_days must be greater than zero".to_string());
+        }
+
+        self.repo
+            .upsert_product(&payload.into_model())
+            .await
+            .map_err(|e| format!("Failed to save digital product: {}", e))
+    }
+
+    pub async fn get_digital_product(
+        &self,
+        product_id: &str,
+    ) -> Result<Option<DigitalProduct>, String> {
+        self.repo
+            .get_product(product_id)
+            .await
+            .map_err(|e| format!("Failed to fetch digital product: {}", e))
+    }
+
+    /// Add keys to a product's pool and hand them to deliveries waiting for one
+    pub async fn add_license_keys(
+        &self,
+        payload: AddLicenseKeysDTO,
+    ) -> Result<LicenseKeyImportResult, String> {
+        self.require_digital_product(&payload.product_id).await?;
+
+        let mut seen = HashSet::new();
+        let keys: Vec<&str> = payload
+            .keys
+            .iter()
+            .map(|key| key.trim())
+            .filter(|key| !key.is_empty())
+            .collect();
+        if keys.is_empty() {
+            return Err("No license keys given".to_string());
+        }
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let mut result = LicenseKeyImportResult {
+            added: 0,
+            duplicates: 0,
+            deliveries_fulfilled: 0,
+        };
+        for key in keys {
+            if !seen.insert(key) {
+                result.duplicates += 1;
+                continue;
+            }
+            let now = Utc::now();
+            let license_key = LicenseKey {
+                id: Uuid::new_v4().to_string(),
+                product_id: payload.product_id.clone(),
+                license_key: key.to_string(),
+                status: "available".to_string(),
+                assigned_at: None,
+                sync_status: Some("created".to_string()),
+                created_at: Some(now),
+                updated_at: Some(now),
+            };
+            let added = ShopDigitalDeliveryRepository::add_key_in_tx(&mut tx, &license_key)
+                .await
+                .map_err(|e| format!("Failed to add license key: {}", e))?;
+            if added {
+                result.added += 1;
+            } else {
+                result.duplicates += 1;
+            }
+        }
+
+        result.deliveries_fulfilled =
+            Self::fulfil_pending_in_tx(&mut tx, &payload.product_id).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(result)
+    }
+
+    pub async fn list_license_keys(
+        &self,
+        product_id: &str,
+        status: Option<&str>,
+    ) -> Result<Vec<LicenseKey>, String> {
+        self.repo
+            .list_keys(product_id, status)
+            .await
+            .map_err(|e| format!("Failed to list license keys: {}", e))
+    }
+
+    pub async fn get_license_key_stock(&self, product_id: &str) -> Result<LicenseKeyStock, String> {
+        self.repo
+            .key_stock(product_id)
+            .await
+            .map_err(|e| format!("Failed to count license keys: {}", e))
+    }
+
+    /// Withdraw an unused key from the pool. Delivered keys are revoked
+    /// through their delivery.
+    pub async fn revoke_license_key(&self, id: &str) -> Result<LicenseKey, String> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let revoked = ShopDigitalDeliveryRepository::set_key_status_in_tx(
+            &mut tx,
+            id,
+            "available",
+            "revoked",
+        )
+        .await
+        .map_err(|e| format!("Failed to revoke license key: {}", e))?;
+        if !revoked {
+            return Err(format!("License key {} is not available", id));
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        self.repo
+            .get_key(id)
+            .await
+            .map_err(|e| format!("Failed to fetch license key: {}", e))?
+            .ok_or_else(|| format!("License key not found: {}", id))
+    }
+
+    // ============================================================
+    // Deliveries
+    // ============================================================
+
+    pub async fn list_transaction_deliveries(
+        &self,
+        transaction_id: &str,
+    ) -> Result<Vec<DigitalDelivery>, String> {
+        self.repo
+            .list_by_transaction(transaction_id)
+            .await
+            .map_err(|e| format!("Failed to list digital deliveries: {}", e))
+    }
+
+    pub async fn list_customer_deliveries(
+        &self,
+        customer_id: &str,
+    ) -> Result<Vec<DigitalDelivery>, String> {
+        self.repo
+            .list_by_customer(customer_id)
+            .await
+            .map_err(|e| format!("Failed to list digital deliveries: {}", e))
+    }
+
+    /// Count a download on a link and return where to fetch the file
+    pub async fn redeem_download(&self, token: &str) -> Result<DownloadGrant, String> {
+        let delivery = self
+            .repo
+            .find_by_token(token)
+            .await
+            .map_err(|e| format!("Failed to fetch digital delivery: {}", e))?
+            .ok_or_else(|| "Download link not found".to_string())?;
+
+        if delivery.status != "delivered" {
+            return Err("Download link has been revoked".to_string());
+        }
+        if delivery
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+        {
+            return Err("Download link has expired".to_string());
+        }
+
+        let download_url = match &delivery.product_id {
+            Some(product_id) => self
+                .get_digital_product(product_id)
+                .await?
+                .and_then(|settings| settings.download_url),
+            None => None,
+        }
+        .ok_or_else(|| "The product has no file to download".to_string())?;
+
+        let counted = self
+            .repo
+            .record_download(&delivery.id)
+            .await
+            .map_err(|e| format!("Failed to record download: {}", e))?;
+        if !counted {
+            return Err("Download limit reached".to_string());
+        }
+
+        Ok(DownloadGrant {
+            downloads_remaining: delivery
+                .download_limit
+                .map(|limit| (limit - delivery.download_count - 1).max(0)),
+            delivery_id: delivery.id,
+            product_id: delivery.product_id,
+            download_url,
+            expires_at: delivery.expires_at,
+        })
+    }
+
+    /// Revoke a delivery, e.g. after a refund. Its license key is revoked
+    /// too rather than returned to the pool, since the customer has seen it.
+    pub async fn revoke_delivery(&self, id: &str) -> Result<DigitalDelivery, String> {
+        let delivery = self
+            .repo
+            .get_delivery(id)
+            .await
+            .map_err(|e| format!("Failed to fetch digital delivery: {}", e))?
+            .ok_or_else(|| format!("Digital delivery not found: {}", id))?;
+        if delivery.status == "revoked" {
+            return Err("Digital delivery is already revoked".to_string());
+        }
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        Self::revoke_in_tx(&mut tx, &delivery).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        self.repo
+            .get_delivery(id)
+            .await
+            .map_err(|e| format!("Failed to fetch digital delivery: {}", e))?
+            .ok_or_else(|| format!("Digital delivery not found: {}", id))
+    }
+
+    // ============================================================
+    // Fulfillment helpers (run inside the caller's transaction)
+    // ============================================================
+
+    async fn revoke_in_tx(
+        tx: &mut DbTransaction<'_>,
+        delivery: &DigitalDelivery,
+    ) -> Result<(), String> {
+        ShopDigitalDeliveryRepository::revoke_in_tx(tx, &delivery.id)
+            .await
+            .map_err(|e| format!("Failed to revoke digital delivery: {}", e))?;
+        if let Some(key_id) = &delivery.license_key_id {
+            ShopDigitalDeliveryRepository::set_key_status_in_tx(tx, key_id, "assigned", "revoked")
+                .await
+                .map_err(|e| format!("Failed to revoke license key: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Revoke every delivery of a transaction, e.g. when the sale is
+    /// cancelled. Pending deliveries are revoked too so they never get a key.
+    pub async fn revoke_transaction_in_tx(
+        tx: &mut DbTransaction<'_>,
+        transaction_id: &str,
+    ) -> Result<(), String> {
+        let deliveries =
+            ShopDigitalDeliveryRepository::list_by_transaction_in_tx(tx, transaction_id)
+                .await
+                .map_err(|e| format!("Failed to list digital deliveries: {}", e))?;
+        for delivery in deliveries.iter().filter(|d| d.status != "revoked") {
+            Self::revoke_in_tx(tx, delivery).await?;
+        }
+        Ok(())
+    }
+
+    /// Give the oldest available keys to the oldest pending deliveries of a
+    /// product. Returns how many deliveries were fulfilled.
+    async fn fulfil_pending_in_tx(
+        tx: &mut DbTransaction<'_>,
+        product_id: &str,
+    ) -> Result<usize, String> {
+        let pending = ShopDigitalDeliveryRepository::list_pending_in_tx(tx, product_id)
+            .await
+            .map_err(|e| format!("Failed to list pending deliveries: {}", e))?;
+
+        let mut fulfilled = 0;
+        for delivery in pending {
+            let key = match Self::take_key_in_tx(tx, product_id).await? {
+                Some(key) => key,
+                None => break,
+            };
+            ShopDigitalDeliveryRepository::fulfil_in_tx(tx, &delivery.id, &key)
+                .await
+                .map_err(|e| format!("Failed to fulfil digital delivery: {}", e))?;
+            fulfilled += 1;
+        }
+        Ok(fulfilled)
+    }
+
+    /// Assign the oldest available key of a product, if any
+    async fn take_key_in_tx(
+        tx: &mut DbTransaction<'_>,
+        product_id: &str,
+    ) -> Result<Option<LicenseKey>, String> {
+        let key = ShopDigitalDeliveryRepository::next_available_key_in_tx(tx, product_id)
+            .await
+            .map_err(|e| format!("Failed to fetch license key: {}", e))?;
+        if let Some(key) = &key {
+            ShopDigitalDeliveryRepository::set_key_status_in_tx(
+                tx,
+                &key.id,
+                "available",
+                "assigned",
+            )
+            .await
+            .map_err(|e| format!("Failed to assign license key: {}", e))?;
+        }
+        Ok(key)
+    }
+
+    /// Create the deliveries of a sold line: for the product itself when it
+    /// is digital, or for the digital components of a bundle. Digital
+    /// products without fulfillment settings are left to manual delivery.
+    pub async fn deliver_in_tx(
+        tx: &mut DbTransaction<'_>,
+        transaction: &Transaction,
+        item: &TransactionItem,
+    ) -> Result<Vec<DigitalDelivery>, String> {
+        let product_id = match &item.product_id {
+            Some(product_id) => product_id,
+            None => return Ok(Vec::new()),
+        };
+
+        let components = ShopBundleRepository::list_components_in_tx(tx, product_id)
+            .await
+            .map_err(|e| format!("Failed to list bundle components: {}", e))?;
+        let lines: Vec<(String, f64)> = if components.is_empty() {
+            vec![(product_id.clone(), item.quantity)]
+        } else {
+            components
+                .into_iter()
+                .filter(|c| c.component_type.as_deref() == Some("digital"))
+                .map(|c| (c.component_id, c.quantity * item.quantity))
+                .collect()
+        };
+
+        let mut deliveries = Vec::new();
+        for (digital_id, quantity) in lines {
+            let settings =
+                match ShopDigitalDeliveryRepository::get_digital_product_in_tx(tx, &digital_id)
+                    .await
+                    .map_err(|e| format!("Failed to fetch digital product: {}", e))?
+                {
+                    Some(settings) => settings,
+                    None => continue,
+                };
+
+            let now = Utc::now();
+            let mut delivery = DigitalDelivery {
+                id: Uuid::new_v4().to_string(),
+                transaction_id: transaction.id.clone(),
+                transaction_item_id: item.id.clone(),
+                product_id: Some(digital_id.clone()),
+                customer_id: transaction.customer_id.clone(),
+                delivery_method: settings.delivery_method.clone(),
+                status: "delivered".to_string(),
+                license_key_id: None,
+                license_key: None,
+                download_token: None,
+                download_limit: None,
+                download_count: 0,
+                expires_at: None,
+                delivered_at: Some(now),
+                last_downloaded_at: None,
+                revoked_at: None,
+                sync_status: Some("created".to_string()),
+                created_at: Some(now),
+                updated_at: Some(now),
+            };
+
+            if settings.delivery_method == "download_link" {
+                delivery.download_token = Some(Uuid::new_v4().simple().to_string());
+                delivery.download_limit = settings.download_limit;
+                delivery.expires_at = settings
+                    .link_expiry_days
+                    .map(|days| now + Duration::days(days));
+                let created = ShopDigitalDeliveryRepository::create_delivery_in_tx(tx, &delivery)
+                    .await
+                    .map_err(|e| format!("Failed to create digital delivery: {}", e))?;
+                deliveries.push(created);
+                continue;
+            }
+
+            if quantity.fract() != 0.0 || quantity <= 0.0 {
+                return Err(format!(
+                    "License keys are sold in whole units (quantity {})",
+                    quantity
+                ));
+            }
+            for _ in 0..quantity as usize {
+                let mut unit = delivery.clone();
+                unit.id = Uuid::new_v4().to_string();
+                match Self::take_key_in_tx(tx, &digital_id).await? {
+                    Some(key) => {
+                        unit.license_key_id = Some(key.id);
+                        unit.license_key = Some(key.license_key);
+                    }
+                    None => {
+                        unit.status = "pending".to_string();
+                        unit.delivered_at = None;
+                    }
+                }
+                let created = ShopDigitalDeliveryRepository::create_delivery_in_tx(tx, &unit)
+                    .await
+                    .map_err(|e| format!("Failed to create digital delivery: {}", e))?;
+                deliveries.push(created);
+            }
+        }
+
+        Ok(deliveries)
+    }
+}
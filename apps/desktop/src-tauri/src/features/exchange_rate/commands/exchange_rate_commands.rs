@@ -0,0 +1,30 @@
+use crate::features::exchange_rate::dtos::exchange_rate_dto::SetExchangeRateDTO;
+use crate::features::exchange_rate::models::exchange_rate_model::ExchangeRate;
+use crate::features::exchange_rate::services::exchange_rate_service::ExchangeRateService;
+use sqlx::SqlitePool;
+use tauri::State;
+
+#[tauri::command]
+pub async fn set_exchange_rate(
+    pool: State<'_, SqlitePool>,
+    payload: SetExchangeRateDTO,
+) -> Result<ExchangeRate, String> {
+    let service = ExchangeRateService::new(pool.inner().clone());
+    service.set_rate(payload).await
+}
+
+#[tauri::command]
+pub async fn list_exchange_rates(pool: State<'_, SqlitePool>) -> Result<Vec<ExchangeRate>, String> {
+    let service = ExchangeRateService::new(pool.inner().clone());
+    service.list_rates().await
+}
+
+#[tauri::command]
+pub async fn delete_exchange_rate(
+    pool: State<'_, SqlitePool>,
+    base_currency: String,
+    quote_currency: String,
+) -> Result<(), String> {
+    let service = ExchangeRateService::new(pool.inner().clone());
+    service.delete_rate(&base_currency, &quote_currency).await
+}
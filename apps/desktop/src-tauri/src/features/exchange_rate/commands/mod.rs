@@ -0,0 +1 @@
+pub mod exchange_rate_commands;
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetExchangeRateDTO {
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub rate: f64,
+}
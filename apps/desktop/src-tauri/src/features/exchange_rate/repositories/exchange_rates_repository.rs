@@ -0,0 +1,55 @@
+use crate::features::exchange_rate::models::exchange_rate_model::ExchangeRate;
+use sqlx::{Result, SqlitePool};
+
+pub struct ExchangeRatesRepository {
+    pool: SqlitePool,
+}
+
+impl ExchangeRatesRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn upsert(&self, item: &ExchangeRate) -> Result<ExchangeRate> {
+        let sql = r#"
+            INSERT INTO exchange_rates (
+                base_currency, quote_currency, rate, _status, created_at, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT (base_currency, quote_currency) DO UPDATE SET
+                rate = excluded.rate,
+                _status = 'modified',
+                updated_at = excluded.updated_at
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, ExchangeRate>(sql)
+            .bind(&item.base_currency)
+            .bind(&item.quote_currency)
+            .bind(item.rate)
+            .bind(&item.sync_status)
+            .bind(item.created_at)
+            .bind(item.updated_at)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    pub async fn list_all(&self) -> Result<Vec<ExchangeRate>> {
+        let sql = r#"
+            SELECT * FROM exchange_rates
+            WHERE _status != 'deleted'
+            ORDER BY base_currency ASC, quote_currency ASC
+        "#;
+        sqlx::query_as::<_, ExchangeRate>(sql)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    pub async fn delete(&self, base_currency: &str, quote_currency: &str) -> Result<()> {
+        let sql = "DELETE FROM exchange_rates WHERE base_currency = ? AND quote_currency = ?";
+        sqlx::query(sql)
+            .bind(base_currency)
+            .bind(quote_currency)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1 @@
+pub mod exchange_rates_repository;
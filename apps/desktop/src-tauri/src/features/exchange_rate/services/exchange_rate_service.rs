@@ -0,0 +1,106 @@
+use crate::features::exchange_rate::dtos::exchange_rate_dto::SetExchangeRateDTO;
+use crate::features::exchange_rate::models::exchange_rate_model::ExchangeRate;
+use crate::features::exchange_rate::repositories::exchange_rates_repository::ExchangeRatesRepository;
+use chrono::Utc;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+fn normalize_currency(code: &str) -> Result<String, String> {
+    let code = code.trim().to_uppercase();
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(format!("Invalid currency code: {}", code));
+    }
+    Ok(code)
+}
+
+/// Exchange rates loaded once, resolving direct and inverse pairs
+pub struct RateTable {
+    rates: HashMap<(String, String), f64>,
+}
+
+impl RateTable {
+    pub fn new(rates: Vec<ExchangeRate>) -> Self {
+        let mut table = HashMap::new();
+        for rate in rates {
+            let base = rate.base_currency.to_uppercase();
+            let quote = rate.quote_currency.to_uppercase();
+            // A rate set explicitly wins over the inverse of the opposite pair
+            table
+                .entry((quote.clone(), base.clone()))
+                .or_insert(1.0 / rate.rate);
+            table.insert((base, quote), rate.rate);
+        }
+        Self { rates: table }
+    }
+
+    /// Rate converting an amount in `from` to `to`, if known
+    pub fn rate(&self, from: &str, to: &str) -> Option<f64> {
+        let from = from.to_uppercase();
+        let to = to.to_uppercase();
+        if from == to {
+            return Some(1.0);
+        }
+        self.rates.get(&(from, to)).copied()
+    }
+}
+
+pub struct ExchangeRateService {
+    repo: ExchangeRatesRepository,
+}
+
+impl ExchangeRateService {
+    pub fn new(pool: SqlitePool) -> Self {
+        let repo = ExchangeRatesRepository::new(pool);
+        Self { repo }
+    }
+
+    pub async fn set_rate(&self, payload: SetExchangeRateDTO) -> Result<ExchangeRate, String> {
+        let base_currency = normalize_currency(&payload.base_currency)?;
+        let quote_currency = normalize_currency(&payload.quote_currency)?;
+        if base_currency == quote_currency {
+            return Err("Base and quote currency must differ".to_string());
+        }
+        if !payload.rate.is_finite() || payload.rate <= 0.0 {
+            return Err("Exchange rate must be greater than zero".to_string());
+        }
+
+        let now = Utc::now();
+        let rate = ExchangeRate {
+            base_currency,
+            quote_currency,
+            rate: payload.rate,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+        self.repo
+            .upsert(&rate)
+            .await
+            .map_err(|e| format!("Failed to save exchange rate: {}", e))
+    }
+
+    pub async fn list_rates(&self) -> Result<Vec<ExchangeRate>, String> {
+        self.repo
+            .list_all()
+            .await
+            .map_err(|e| format!("Failed to list exchange rates: {}", e))
+    }
+
+    pub async fn delete_rate(
+        &self,
+        base_currency: &str,
+        quote_currency: &str,
+    ) -> Result<(), String> {
+        self.repo
+            .delete(
+                &normalize_currency(base_currency)?,
+                &normalize_currency(quote_currency)?,
+            )
+            .await
+            .map_err(|e| format!("Failed to delete exchange rate: {}", e))
+    }
+
+    pub async fn rate_table(&self) -> Result<RateTable, String> {
+        Ok(RateTable::new(self.list_rates().await?))
+    }
+}
@@ -0,0 +1,118 @@
+use crate::db::RepositoryFactory;
+use crate::features::gift_card::dtos::gift_card_dto::{AdjustGiftCardDTO, IssueGiftCardDTO};
+use crate::features::gift_card::models::gift_card_model::{GiftCard, GiftCardLedgerEntry};
+use crate::features::gift_card::services::shop_gift_card_service::ShopGiftCardService;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn issue_gift_card(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: IssueGiftCardDTO,
+) -> Result<GiftCard, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopGiftCardService::new(pool);
+    service.issue_gift_card(payload).await
+}
+
+#[tauri::command]
+pub async fn adjust_gift_card_balance(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: AdjustGiftCardDTO,
+) -> Result<GiftCard, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopGiftCardService::new(pool);
+    service.adjust_balance(payload).await
+}
+
+#[tauri::command]
+pub async fn update_gift_card_status(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+    status: String,
+) -> Result<GiftCard, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopGiftCardService::new(pool);
+    service.set_status(&id, &status).await
+}
+
+#[tauri::command]
+pub async fn get_gift_card(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+) -> Result<Option<GiftCard>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopGiftCardService::new(pool);
+    service.get_gift_card(&id).await
+}
+
+#[tauri::command]
+pub async fn get_gift_card_by_code(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    code: String,
+) -> Result<Option<GiftCard>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopGiftCardService::new(pool);
+    service.get_gift_card_by_code(&code).await
+}
+
+#[tauri::command]
+pub async fn list_gift_cards(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+) -> Result<Vec<GiftCard>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopGiftCardService::new(pool);
+    service.list_gift_cards().await
+}
+
+#[tauri::command]
+pub async fn list_gift_cards_by_customer(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    customer_id: String,
+) -> Result<Vec<GiftCard>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopGiftCardService::new(pool);
+    service.list_gift_cards_by_customer(&customer_id).await
+}
+
+#[tauri::command]
+pub async fn list_gift_card_ledger(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    gift_card_id: String,
+) -> Result<Vec<GiftCardLedgerEntry>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopGiftCardService::new(pool);
+    service.list_ledger(&gift_card_id).await
+}
@@ -0,0 +1,66 @@
+use crate::features::gift_card::models::gift_card_model::GiftCard;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+fn generate_code() -> String {
+    // 16 uppercase hex characters grouped as XXXX-XXXX-XXXX-XXXX
+    let raw = Uuid::new_v4().simple().to_string().to_uppercase();
+    format!("{}-{}-{}-{}", &raw[0..4], &raw[4..8], &raw[8..12], &raw[12..16])
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssueGiftCardDTO {
+    pub code: Option<String>,
+    pub initial_balance: f64,
+    pub currency: Option<String>,
+    pub customer_id: Option<String>,
+    pub source: Option<String>,
+    pub source_transaction_id: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+    pub created_by: Option<String>,
+}
+
+impl IssueGiftCardDTO {
+    pub fn into_model(self) -> GiftCard {
+        let now = Utc::now();
+        let source = self.source.unwrap_or_else(|| {
+            if self.source_transaction_id.is_some() {
+                "sale".to_string()
+            } else {
+                "manual".to_string()
+            }
+        });
+
+        GiftCard {
+            id: Uuid::new_v4().to_string(),
+            code: self
+                .code
+                .map(|c| c.trim().to_uppercase())
+                .unwrap_or_else(generate_code),
+            initial_balance: self.initial_balance,
+            currency: self.currency.or(Some("BRL".to_string())),
+            customer_id: self.customer_id,
+            source,
+            source_transaction_id: self.source_transaction_id,
+            status: "active".to_string(),
+            expires_at: self.expires_at,
+            notes: self.notes,
+            created_by: self.created_by,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+            balance: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdjustGiftCardDTO {
+    pub gift_card_id: String,
+    pub r#type: String, // 'credit', 'debit'
+    pub amount: f64,
+    pub notes: Option<String>,
+    pub created_by: Option<String>,
+}
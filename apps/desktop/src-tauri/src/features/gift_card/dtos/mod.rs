@@ -0,0 +1 @@
+pub mod gift_card_dto;
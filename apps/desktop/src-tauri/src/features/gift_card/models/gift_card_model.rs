@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct GiftCard {
+    pub id: String,
+    pub code: String,
+    pub initial_balance: f64,
+    pub currency: Option<String>, // DEFAULT 'BRL'
+    pub customer_id: Option<String>,
+    pub source: String, // 'sale', 'manual'
+    pub source_transaction_id: Option<String>,
+    pub status: String, // 'active', 'disabled'
+    pub expires_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+    pub created_by: Option<String>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    #[sqlx(default)]
+    pub balance: f64, // Sum of gift_card_ledger, not a column
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct GiftCardLedgerEntry {
+    pub id: String,
+    pub gift_card_id: String,
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub entry_type: String, // 'credit', 'debit'
+    pub amount: f64,
+    pub reason: String, // 'issue', 'redeem', 'refund', 'adjustment'
+    pub payment_id: Option<String>,
+    pub transaction_id: Option<String>,
+    pub notes: Option<String>,
+    pub created_by: Option<String>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
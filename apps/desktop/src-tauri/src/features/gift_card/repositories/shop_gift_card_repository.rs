@@ -0,0 +1,193 @@
+//! Shop-scoped Gift Card Repository for Multi-Database Architecture
+//!
+//! The ledger is append-only: entries are inserted, never updated or deleted,
+//! and a card's balance is always computed from it.
+
+use crate::features::gift_card::models::gift_card_model::{GiftCard, GiftCardLedgerEntry};
+use sqlx::{Result, Sqlite, SqlitePool, Transaction};
+use std::sync::Arc;
+
+const SELECT_WITH_BALANCE: &str = r#"
+    SELECT g.*,
+        (SELECT COALESCE(SUM(CASE WHEN l.type = 'credit' THEN l.amount ELSE -l.amount END), 0.0)
+         FROM gift_card_ledger l WHERE l.gift_card_id = g.id) AS balance
+    FROM gift_cards g
+"#;
+
+pub struct ShopGiftCardRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopGiftCardRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_by_id(&self, id: &str) -> Result<Option<GiftCard>> {
+        let sql = format!(
+            "{} WHERE g.id = $1 AND (g._status IS NULL OR g._status != 'deleted')",
+            SELECT_WITH_BALANCE
+        );
+        sqlx::query_as::<_, GiftCard>(&sql)
+            .bind(id)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn get_by_code(&self, code: &str) -> Result<Option<GiftCard>> {
+        let sql = format!(
+            "{} WHERE g.code = $1 AND (g._status IS NULL OR g._status != 'deleted')",
+            SELECT_WITH_BALANCE
+        );
+        sqlx::query_as::<_, GiftCard>(&sql)
+            .bind(code)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn list(&self) -> Result<Vec<GiftCard>> {
+        let sql = format!(
+            "{} WHERE g._status IS NULL OR g._status != 'deleted' ORDER BY g.created_at DESC",
+            SELECT_WITH_BALANCE
+        );
+        sqlx::query_as::<_, GiftCard>(&sql)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn list_by_customer(&self, customer_id: &str) -> Result<Vec<GiftCard>> {
+        let sql = format!(
+            "{} WHERE g.customer_id = $1 AND (g._status IS NULL OR g._status != 'deleted') ORDER BY g.created_at DESC",
+            SELECT_WITH_BALANCE
+        );
+        sqlx::query_as::<_, GiftCard>(&sql)
+            .bind(customer_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn update_status(&self, id: &str, status: &str) -> Result<()> {
+        let sql = r#"
+            UPDATE gift_cards SET status = $2, _status = 'modified', updated_at = datetime('now')
+            WHERE id = $1
+        "#;
+        sqlx::query(sql)
+            .bind(id)
+            .bind(status)
+            .execute(&*self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn list_entries(&self, gift_card_id: &str) -> Result<Vec<GiftCardLedgerEntry>> {
+        let sql = "SELECT * FROM gift_card_ledger WHERE gift_card_id = $1 ORDER BY created_at ASC";
+        sqlx::query_as::<_, GiftCardLedgerEntry>(sql)
+            .bind(gift_card_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    // ============================================================
+    // Transaction-aware methods for atomic operations
+    // ============================================================
+
+    pub async fn create_in_tx(tx: &mut Transaction<'_, Sqlite>, gift_card: &GiftCard) -> Result<()> {
+        let sql = r#"
+            INSERT INTO gift_cards (
+                id, code, initial_balance, currency, customer_id, source,
+                source_transaction_id, status, expires_at, notes, created_by,
+                _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+        "#;
+
+        sqlx::query(sql)
+            .bind(&gift_card.id)
+            .bind(&gift_card.code)
+            .bind(gift_card.initial_balance)
+            .bind(&gift_card.currency)
+            .bind(&gift_card.customer_id)
+            .bind(&gift_card.source)
+            .bind(&gift_card.source_transaction_id)
+            .bind(&gift_card.status)
+            .bind(gift_card.expires_at)
+            .bind(&gift_card.notes)
+            .bind(&gift_card.created_by)
+            .bind(&gift_card.sync_status)
+            .bind(gift_card.created_at)
+            .bind(gift_card.updated_at)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_by_code_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        code: &str,
+    ) -> Result<Option<GiftCard>> {
+        let sql = format!(
+            "{} WHERE g.code = $1 AND (g._status IS NULL OR g._status != 'deleted')",
+            SELECT_WITH_BALANCE
+        );
+        sqlx::query_as::<_, GiftCard>(&sql)
+            .bind(code)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    pub async fn get_by_id_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+    ) -> Result<Option<GiftCard>> {
+        let sql = format!(
+            "{} WHERE g.id = $1 AND (g._status IS NULL OR g._status != 'deleted')",
+            SELECT_WITH_BALANCE
+        );
+        sqlx::query_as::<_, GiftCard>(&sql)
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    pub async fn get_payment_debit_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        payment_id: &str,
+    ) -> Result<Option<GiftCardLedgerEntry>> {
+        let sql = r#"
+            SELECT * FROM gift_card_ledger
+            WHERE payment_id = $1 AND type = 'debit' AND reason = 'redeem'
+            LIMIT 1
+        "#;
+        sqlx::query_as::<_, GiftCardLedgerEntry>(sql)
+            .bind(payment_id)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    pub async fn append_entry_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        entry: &GiftCardLedgerEntry,
+    ) -> Result<GiftCardLedgerEntry> {
+        let sql = r#"
+            INSERT INTO gift_card_ledger (
+                id, gift_card_id, type, amount, reason, payment_id, transaction_id,
+                notes, created_by, _status, created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING *
+        "#;
+
+        sqlx::query_as::<_, GiftCardLedgerEntry>(sql)
+            .bind(&entry.id)
+            .bind(&entry.gift_card_id)
+            .bind(&entry.entry_type)
+            .bind(entry.amount)
+            .bind(&entry.reason)
+            .bind(&entry.payment_id)
+            .bind(&entry.transaction_id)
+            .bind(&entry.notes)
+            .bind(&entry.created_by)
+            .bind(&entry.sync_status)
+            .bind(entry.created_at)
+            .fetch_one(&mut **tx)
+            .await
+    }
+}
@@ -0,0 +1 @@
+pub mod shop_gift_card_service;
@@ -0,0 +1,271 @@
+//! Shop-scoped Gift Card Service for Multi-Database Architecture
+
+use crate::features::gift_card::dtos::gift_card_dto::{AdjustGiftCardDTO, IssueGiftCardDTO};
+use crate::features::gift_card::models::gift_card_model::{GiftCard, GiftCardLedgerEntry};
+use crate::features::gift_card::repositories::shop_gift_card_repository::ShopGiftCardRepository;
+use chrono::Utc;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct ShopGiftCardService {
+    pool: Arc<SqlitePool>,
+    repo: ShopGiftCardRepository,
+}
+
+impl ShopGiftCardService {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        let repo = ShopGiftCardRepository::new(pool.clone());
+        Self { pool, repo }
+    }
+
+    pub fn pool(&self) -> Arc<SqlitePool> {
+        self.pool.clone()
+    }
+
+    /// Issue a gift card, recording its initial balance as the first ledger credit
+    pub async fn issue_gift_card(&self, payload: IssueGiftCardDTO) -> Result<GiftCard, String> {
+        if payload.initial_balance <= 0.0 {
+            return Err("Gift card balance must be greater than zero".to_string());
+        }
+
+        let gift_card = payload.into_model();
+        if gift_card.source != "sale" && gift_card.source != "manual" {
+            return Err(format!("Invalid gift card source: {}", gift_card.source));
+        }
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        ShopGiftCardRepository::create_in_tx(&mut tx, &gift_card)
+            .await
+            .map_err(|e| format!("Failed to create gift card: {}", e))?;
+
+        let entry = Self::new_entry(
+            &gift_card.id,
+            "credit",
+            gift_card.initial_balance,
+            "issue",
+            None,
+            gift_card.source_transaction_id.clone(),
+            gift_card.created_by.clone(),
+        );
+        ShopGiftCardRepository::append_entry_in_tx(&mut tx, &entry)
+            .await
+            .map_err(|e| format!("Failed to record gift card issue: {}", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        self.repo
+            .get_by_id(&gift_card.id)
+            .await
+            .map_err(|e| format!("Failed to fetch gift card: {}", e))?
+            .ok_or_else(|| "Created gift card not found".to_string())
+    }
+
+    /// Manual credit or debit, e.g. for goodwill or corrections
+    pub async fn adjust_balance(&self, payload: AdjustGiftCardDTO) -> Result<GiftCard, String> {
+        if payload.amount <= 0.0 {
+            return Err("Adjustment amount must be greater than zero".to_string());
+        }
+        if payload.r#type != "credit" && payload.r#type != "debit" {
+            return Err(format!("Invalid ledger entry type: {}", payload.r#type));
+        }
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let gift_card = ShopGiftCardRepository::get_by_id_in_tx(&mut tx, &payload.gift_card_id)
+            .await
+            .map_err(|e| format!("Failed to fetch gift card: {}", e))?
+            .ok_or_else(|| format!("Gift card not found: {}", payload.gift_card_id))?;
+
+        if payload.r#type == "debit" && payload.amount > gift_card.balance + 0.001 {
+            return Err(format!(
+                "Insufficient gift card balance. Available: {}, Requested: {}",
+                gift_card.balance, payload.amount
+            ));
+        }
+
+        let mut entry = Self::new_entry(
+            &gift_card.id,
+            &payload.r#type,
+            payload.amount,
+            "adjustment",
+            None,
+            None,
+            payload.created_by,
+        );
+        entry.notes = payload.notes;
+        ShopGiftCardRepository::append_entry_in_tx(&mut tx, &entry)
+            .await
+            .map_err(|e| format!("Failed to record gift card adjustment: {}", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        self.repo
+            .get_by_id(&gift_card.id)
+            .await
+            .map_err(|e| format!("Failed to fetch gift card: {}", e))?
+            .ok_or_else(|| format!("Gift card not found: {}", gift_card.id))
+    }
+
+    pub async fn set_status(&self, id: &str, status: &str) -> Result<GiftCard, String> {
+        if status != "active" && status != "disabled" {
+            return Err(format!("Invalid gift card status: {}", status));
+        }
+        self.repo
+            .update_status(id, status)
+            .await
+            .map_err(|e| format!("Failed to update gift card status: {}", e))?;
+        self.repo
+            .get_by_id(id)
+            .await
+            .map_err(|e| format!("Failed to fetch gift card: {}", e))?
+            .ok_or_else(|| format!("Gift card not found: {}", id))
+    }
+
+    pub async fn get_gift_card(&self, id: &str) -> Result<Option<GiftCard>, String> {
+        self.repo
+            .get_by_id(id)
+            .await
+            .map_err(|e| format!("Failed to fetch gift card: {}", e))
+    }
+
+    pub async fn get_gift_card_by_code(&self, code: &str) -> Result<Option<GiftCard>, String> {
+        self.repo
+            .get_by_code(&code.trim().to_uppercase())
+            .await
+            .map_err(|e| format!("Failed to fetch gift card: {}", e))
+    }
+
+    pub async fn list_gift_cards(&self) -> Result<Vec<GiftCard>, String> {
+        self.repo
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list gift cards: {}", e))
+    }
+
+    pub async fn list_gift_cards_by_customer(&self, customer_id: &str) -> Result<Vec<GiftCard>, String> {
+        self.repo
+            .list_by_customer(customer_id)
+            .await
+            .map_err(|e| format!("Failed to list gift cards: {}", e))
+    }
+
+    pub async fn list_ledger(&self, gift_card_id: &str) -> Result<Vec<GiftCardLedgerEntry>, String> {
+        self.repo
+            .list_entries(gift_card_id)
+            .await
+            .map_err(|e| format!("Failed to list gift card ledger: {}", e))
+    }
+
+    // ============================================================
+    // Transaction-aware methods used by payments
+    // ============================================================
+
+    /// Debit a gift card to pay for a transaction
+    pub async fn redeem_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        code: &str,
+        amount: f64,
+        payment_id: &str,
+        transaction_id: &str,
+        created_by: Option<String>,
+    ) -> Result<GiftCard, String> {
+        let gift_card = ShopGiftCardRepository::get_by_code_in_tx(tx, &code.trim().to_uppercase())
+            .await
+            .map_err(|e| format!("Failed to fetch gift card: {}", e))?
+            .ok_or_else(|| format!("Gift card not found: {}", code))?;
+
+        if gift_card.status != "active" {
+            return Err("Gift card is disabled".to_string());
+        }
+        if let Some(expires_at) = gift_card.expires_at {
+            if expires_at < Utc::now() {
+                return Err("Gift card has expired".to_string());
+            }
+        }
+        if amount > gift_card.balance + 0.001 {
+            return Err(format!(
+                "Insufficient gift card balance. Available: {}, Requested: {}",
+                gift_card.balance, amount
+            ));
+        }
+
+        let entry = Self::new_entry(
+            &gift_card.id,
+            "debit",
+            amount,
+            "redeem",
+            Some(payment_id.to_string()),
+            Some(transaction_id.to_string()),
+            created_by,
+        );
+        ShopGiftCardRepository::append_entry_in_tx(tx, &entry)
+            .await
+            .map_err(|e| format!("Failed to record gift card redemption: {}", e))?;
+
+        Ok(gift_card)
+    }
+
+    /// Credit back the card that paid for a refunded payment
+    pub async fn restore_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        payment_id: &str,
+        amount: f64,
+        created_by: Option<String>,
+    ) -> Result<GiftCardLedgerEntry, String> {
+        let debit = ShopGiftCardRepository::get_payment_debit_in_tx(tx, payment_id)
+            .await
+            .map_err(|e| format!("Failed to fetch gift card redemption: {}", e))?
+            .ok_or_else(|| format!("No gift card redemption found for payment {}", payment_id))?;
+
+        let entry = Self::new_entry(
+            &debit.gift_card_id,
+            "credit",
+            amount,
+            "refund",
+            Some(payment_id.to_string()),
+            debit.transaction_id.clone(),
+            created_by,
+        );
+        ShopGiftCardRepository::append_entry_in_tx(tx, &entry)
+            .await
+            .map_err(|e| format!("Failed to record gift card refund: {}", e))
+    }
+
+    fn new_entry(
+        gift_card_id: &str,
+        entry_type: &str,
+        amount: f64,
+        reason: &str,
+        payment_id: Option<String>,
+        transaction_id: Option<String>,
+        created_by: Option<String>,
+    ) -> GiftCardLedgerEntry {
+        GiftCardLedgerEntry {
+            id: Uuid::new_v4().to_string(),
+            gift_card_id: gift_card_id.to_string(),
+            entry_type: entry_type.to_string(),
+            amount,
+            reason: reason.to_string(),
+            payment_id,
+            transaction_id,
+            notes: None,
+            created_by,
+            sync_status: Some("created".to_string()),
+            created_at: Some(Utc::now()),
+        }
+    }
+}
@@ -2,7 +2,7 @@
 
 use crate::features::inventory::models::inventory_level_model::InventoryLevel;
 use crate::features::transaction::models::transaction_model::InventoryMovement;
-use sqlx::{Result, SqlitePool};
+use sqlx::{Result, Sqlite, SqlitePool, Transaction};
 use std::sync::Arc;
 
 pub struct ShopInventoryRepository {
@@ -93,6 +93,17 @@ impl ShopInventoryRepository {
             .await
     }
 
+    pub async fn get_level_by_id_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+    ) -> Result<Option<InventoryLevel>> {
+        let sql = "SELECT * FROM inventory_levels WHERE id = $1 AND (_status IS NULL OR _status != 'deleted')";
+        sqlx::query_as::<_, InventoryLevel>(sql)
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
     pub async fn list_levels(&self) -> Result<Vec<InventoryLevel>> {
         let sql = "SELECT * FROM inventory_levels WHERE _status IS NULL OR _status != 'deleted'";
         sqlx::query_as::<_, InventoryLevel>(sql)
@@ -133,6 +144,62 @@ impl ShopInventoryRepository {
             .await
     }
 
+    /// Find the level holding a product at a location for a given stock status
+    /// (sellable, damaged, quarantine, expired) within a transaction
+    pub async fn find_level_by_stock_status_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        product_id: &str,
+        location_id: &str,
+        stock_status: &str,
+    ) -> Result<Option<InventoryLevel>> {
+        let sql = r#"
+            SELECT * FROM inventory_levels
+            WHERE product_id = $1 AND location_id = $2 AND stock_status = $3
+              AND batch_number IS NULL AND serial_number IS NULL
+              AND (_status IS NULL OR _status != 'deleted')
+            LIMIT 1
+        "#;
+        sqlx::query_as::<_, InventoryLevel>(sql)
+            .bind(product_id)
+            .bind(location_id)
+            .bind(stock_status)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    pub async fn create_level_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        level: &InventoryLevel,
+    ) -> Result<InventoryLevel> {
+        let sql = r#"
+            INSERT INTO inventory_levels (
+                id, product_id, location_id, batch_number, serial_number, expiry_date,
+                quantity_on_hand, quantity_reserved, stock_status, aisle_bin_slot,
+                last_counted_at, _status, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            RETURNING *
+        "#;
+
+        sqlx::query_as::<_, InventoryLevel>(sql)
+            .bind(&level.id)
+            .bind(&level.product_id)
+            .bind(&level.location_id)
+            .bind(&level.batch_number)
+            .bind(&level.serial_number)
+            .bind(level.expiry_date)
+            .bind(level.quantity_on_hand)
+            .bind(level.quantity_reserved)
+            .bind(&level.stock_status)
+            .bind(&level.aisle_bin_slot)
+            .bind(level.last_counted_at)
+            .bind(&level.sync_status)
+            .bind(level.created_at)
+            .bind(level.updated_at)
+            .fetch_one(&mut **tx)
+            .await
+    }
+
     pub async fn delete_level(&self, id: &str) -> Result<()> {
         let sql = "UPDATE inventory_levels SET _status = 'deleted', updated_at = datetime('now') WHERE id = $1";
         sqlx::query(sql).bind(id).execute(&*self.pool).await?;
@@ -276,6 +343,30 @@ impl ShopInventoryRepository {
             .await
     }
 
+    pub async fn list_movements_by_transaction_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        transaction_id: &str,
+    ) -> Result<Vec<InventoryMovement>> {
+        let sql = "SELECT * FROM inventory_movements WHERE transaction_id = $1 AND (_status IS NULL OR _status != 'deleted')";
+        sqlx::query_as::<_, InventoryMovement>(sql)
+            .bind(transaction_id)
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    /// Whether a transaction already moved stock
+    pub async fn has_transaction_movements_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        transaction_id: &str,
+    ) -> Result<bool> {
+        let sql = "SELECT COUNT(*) FROM inventory_movements WHERE transaction_id = $1 AND (_status IS NULL OR _status != 'deleted')";
+        let (count,): (i64,) = sqlx::query_as(sql)
+            .bind(transaction_id)
+            .fetch_one(&mut **tx)
+            .await?;
+        Ok(count > 0)
+    }
+
     pub async fn delete_movements_by_transaction(&self, transaction_id: &str) -> Result<()> {
         let sql = "DELETE FROM inventory_movements WHERE transaction_id = $1";
         sqlx::query(sql)
@@ -12,7 +12,7 @@ pub async fn create_location(
 ) -> Result<Location, String> {
     let shop_id = payload.shop_id.clone();
     let pool = repo_factory
-        .shop_pool(&shop_id)
+        .shop_module_pool(&shop_id, "locations")
         .await
         .map_err(|e| format!("Failed to get shop pool: {}", e))?;
 
@@ -27,7 +27,7 @@ pub async fn update_location(
 ) -> Result<Location, String> {
     let shop_id = payload.shop_id.clone();
     let pool = repo_factory
-        .shop_pool(&shop_id)
+        .shop_module_pool(&shop_id, "locations")
         .await
         .map_err(|e| format!("Failed to get shop pool: {}", e))?;
 
@@ -42,7 +42,7 @@ pub async fn delete_location(
     id: String,
 ) -> Result<(), String> {
     let pool = repo_factory
-        .shop_pool(&shop_id)
+        .shop_module_pool(&shop_id, "locations")
         .await
         .map_err(|e| format!("Failed to get shop pool: {}", e))?;
 
@@ -57,7 +57,7 @@ pub async fn get_location(
     id: String,
 ) -> Result<Option<Location>, String> {
     let pool = repo_factory
-        .shop_pool(&shop_id)
+        .shop_module_pool(&shop_id, "locations")
         .await
         .map_err(|e| format!("Failed to get shop pool: {}", e))?;
 
@@ -71,7 +71,7 @@ pub async fn list_locations(
     shop_id: String,
 ) -> Result<Vec<Location>, String> {
     let pool = repo_factory
-        .shop_pool(&shop_id)
+        .shop_module_pool(&shop_id, "locations")
         .await
         .map_err(|e| format!("Failed to get shop pool: {}", e))?;
 
@@ -86,7 +86,7 @@ pub async fn list_locations_by_type(
     location_type: String,
 ) -> Result<Vec<Location>, String> {
     let pool = repo_factory
-        .shop_pool(&shop_id)
+        .shop_module_pool(&shop_id, "locations")
         .await
         .map_err(|e| format!("Failed to get shop pool: {}", e))?;
 
@@ -100,7 +100,7 @@ pub async fn list_sellable_locations(
     shop_id: String,
 ) -> Result<Vec<Location>, String> {
     let pool = repo_factory
-        .shop_pool(&shop_id)
+        .shop_module_pool(&shop_id, "locations")
         .await
         .map_err(|e| format!("Failed to get shop pool: {}", e))?;
 
@@ -1,26 +1,43 @@
 pub mod analytics;
 pub mod audit_log;
+pub mod barcode;
 pub mod brand;
+pub mod bundle;
+pub mod carrier;
 pub mod category;
 pub mod checkout;
 pub mod customer;
 pub mod customer_address;
 pub mod customer_group;
 pub mod customer_group_membership;
+pub mod data_transfer;
+pub mod database;
+pub mod digital_delivery;
+pub mod exchange_rate;
+pub mod gift_card;
 pub mod inquiry;
 pub mod inventory;
 pub mod location;
 pub mod module;
 pub mod order;
+pub mod packing;
 pub mod payment;
 pub mod pos_session;
+pub mod price_history;
+pub mod price_list;
 pub mod product;
+pub mod product_media;
 pub mod refund;
+pub mod return_request;
 pub mod review;
 pub mod role;
+pub mod search;
+pub mod service_booking;
 pub mod shipment;
 pub mod shop;
 pub mod shop_template;
+pub mod stock_transfer;
+pub mod store_credit;
 pub mod transaction;
 pub mod user;
 pub mod user_identity;
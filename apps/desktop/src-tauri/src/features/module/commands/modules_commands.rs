@@ -1,4 +1,7 @@
-use crate::features::module::dtos::module_dto::ModuleDto;
+use crate::features::module::dtos::module_dto::{
+    ModuleDto, SetShopModulesDTO, SetShopModulesResult, ShopModuleStatus,
+};
+use crate::features::module::models::module_model::ShopModuleChange;
 use crate::features::module::services::modules_service::ModulesService;
 use sqlx::SqlitePool;
 use tauri::State;
@@ -41,3 +44,30 @@ pub async fn list_core_modules(pool: State<'_, SqlitePool>) -> Result<Vec<Module
     let service = ModulesService::new(pool.inner().clone());
     service.list_core_modules().await
 }
+
+#[tauri::command]
+pub async fn get_shop_modules(
+    pool: State<'_, SqlitePool>,
+    shop_id: String,
+) -> Result<Vec<ShopModuleStatus>, String> {
+    let service = ModulesService::new(pool.inner().clone());
+    service.get_shop_modules(&shop_id).await
+}
+
+#[tauri::command]
+pub async fn set_shop_modules(
+    pool: State<'_, SqlitePool>,
+    payload: SetShopModulesDTO,
+) -> Result<SetShopModulesResult, String> {
+    let service = ModulesService::new(pool.inner().clone());
+    service.set_shop_modules(payload).await
+}
+
+#[tauri::command]
+pub async fn list_shop_module_changes(
+    pool: State<'_, SqlitePool>,
+    shop_id: String,
+) -> Result<Vec<ShopModuleChange>, String> {
+    let service = ModulesService::new(pool.inner().clone());
+    service.list_shop_module_changes(&shop_id).await
+}
@@ -1,5 +1,6 @@
 use crate::features::module::models::module_model::Module;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ModuleDto {
@@ -35,3 +36,33 @@ impl From<Module> for ModuleDto {
         }
     }
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetShopModulesDTO {
+    pub shop_id: String,
+    pub modules: HashMap<String, bool>, // Module code -> enabled; others keep their state
+    pub changed_by: Option<String>,
+    pub reason: Option<String>,
+}
+
+/// A module as seen by one shop
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShopModuleStatus {
+    pub code: String,
+    pub name: String,
+    pub category: Option<String>,
+    pub is_core: bool,
+    pub enabled: bool, // Switched on in features_config
+    pub active: bool,  // Enabled and every required module active
+    pub required_modules: Vec<String>,
+    pub conflicts_with: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SetShopModulesResult {
+    pub features_config: String,
+    pub enabled: Vec<String>,
+    pub disabled: Vec<String>,
+    pub auto_enabled: Vec<String>, // Enabled because a requested module requires them
+    pub modules: Vec<ShopModuleStatus>,
+}
@@ -3,3 +3,4 @@ pub mod dtos;
 pub mod models;
 pub mod repositories;
 pub mod services;
+pub mod utils;
@@ -22,3 +22,17 @@ pub struct Module {
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
 }
+
+/// A change of the modules enabled for a shop
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ShopModuleChange {
+    pub id: String,
+    pub shop_id: String,
+    pub changed_by: Option<String>,
+    pub enabled_modules: Option<String>, // JSON array stored as TEXT
+    pub disabled_modules: Option<String>, // JSON array stored as TEXT
+    pub previous_config: Option<String>, // features_config before the change
+    pub new_config: String,
+    pub reason: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
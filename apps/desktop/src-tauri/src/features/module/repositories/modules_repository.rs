@@ -1,5 +1,5 @@
-use crate::features::module::models::module_model::Module;
-use sqlx::{Result, SqlitePool};
+use crate::features::module::models::module_model::{Module, ShopModuleChange};
+use sqlx::{Result, Sqlite, SqlitePool, Transaction};
 
 pub struct ModulesRepository {
     pool: SqlitePool,
@@ -46,4 +46,39 @@ impl ModulesRepository {
             .fetch_all(&self.pool)
             .await
     }
+
+    pub async fn create_change_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        change: &ShopModuleChange,
+    ) -> Result<ShopModuleChange> {
+        sqlx::query_as::<_, ShopModuleChange>(
+            r#"
+            INSERT INTO shop_module_changes (
+                id, shop_id, changed_by, enabled_modules, disabled_modules,
+                previous_config, new_config, reason, created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING *
+            "#,
+        )
+        .bind(&change.id)
+        .bind(&change.shop_id)
+        .bind(&change.changed_by)
+        .bind(&change.enabled_modules)
+        .bind(&change.disabled_modules)
+        .bind(&change.previous_config)
+        .bind(&change.new_config)
+        .bind(&change.reason)
+        .bind(change.created_at)
+        .fetch_one(&mut **tx)
+        .await
+    }
+
+    pub async fn list_changes(&self, shop_id: &str) -> Result<Vec<ShopModuleChange>> {
+        sqlx::query_as::<_, ShopModuleChange>(
+            "SELECT * FROM shop_module_changes WHERE shop_id = ? ORDER BY created_at DESC",
+        )
+        .bind(shop_id)
+        .fetch_all(&self.pool)
+        .await
+    }
 }
@@ -1,15 +1,66 @@
-use crate::features::module::dtos::module_dto::ModuleDto;
+use crate::features::module::dtos::module_dto::{
+    ModuleDto, SetShopModulesDTO, SetShopModulesResult, ShopModuleStatus,
+};
+use crate::features::module::models::module_model::{Module, ShopModuleChange};
 use crate::features::module::repositories::modules_repository::ModulesRepository;
+use crate::features::module::utils::module_checker;
+use crate::features::shop::repositories::shop_repository::ShopsRepository;
+use chrono::Utc;
+use serde_json::{Map, Value};
 use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Module codes listed in a JSON array column
+fn parse_codes(json: Option<&str>) -> Vec<String> {
+    json.and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default()
+}
+
+/// Whether a module is switched on for a shop; core modules always are
+fn is_enabled(module: &Module, features_config: Option<&str>) -> bool {
+    module.is_core || module_checker::is_module_enabled(features_config, &module.code)
+}
+
+/// Modules that are enabled and whose required modules are all active
+fn resolve_active(modules: &[Module], features_config: Option<&str>) -> HashSet<String> {
+    let mut active: HashSet<String> = modules
+        .iter()
+        .filter(|m| is_enabled(m, features_config))
+        .map(|m| m.code.clone())
+        .collect();
+
+    // Drop modules with a missing requirement until nothing changes, so a
+    // disabled module also switches off everything that builds on it
+    loop {
+        let blocked: Vec<String> = modules
+            .iter()
+            .filter(|m| !m.is_core && active.contains(&m.code))
+            .filter(|m| {
+                parse_codes(m.required_modules.as_deref())
+                    .iter()
+                    .any(|required| !active.contains(required))
+            })
+            .map(|m| m.code.clone())
+            .collect();
+        if blocked.is_empty() {
+            return active;
+        }
+        for code in blocked {
+            active.remove(&code);
+        }
+    }
+}
 
 pub struct ModulesService {
+    pool: SqlitePool,
     repo: ModulesRepository,
 }
 
 impl ModulesService {
     pub fn new(pool: SqlitePool) -> Self {
-        let repo = ModulesRepository::new(pool);
-        Self { repo }
+        let repo = ModulesRepository::new(pool.clone());
+        Self { pool, repo }
     }
 
     pub async fn get_module(&self, id: &str) -> Result<Option<ModuleDto>, String> {
@@ -51,4 +102,228 @@ impl ModulesService {
             .map_err(|e| format!("Failed to list core modules: {}", e))
             .map(|modules| modules.into_iter().map(ModuleDto::from).collect())
     }
+
+    // ============================================================
+    // Per-shop enablement
+    // ============================================================
+
+    async fn shop_features_config(&self, shop_id: &str) -> Result<Option<String>, String> {
+        ShopsRepository::new(self.pool.clone())
+            .find_by_id(shop_id)
+            .await
+            .map_err(|e| format!("Failed to fetch shop: {}", e))?
+            .map(|shop| shop.features_config)
+            .ok_or_else(|| format!("Shop not found: {}", shop_id))
+    }
+
+    /// Whether a module is usable by a shop: enabled in its features_config
+    /// with every required module active. Codes missing from the registry
+    /// fall back to the features_config alone.
+    pub async fn is_module_active(&self, shop_id: &str, code: &str) -> Result<bool, String> {
+        let features_config = self.shop_features_config(shop_id).await?;
+        let modules = self
+            .repo
+            .list_all()
+            .await
+            .map_err(|e| format!("Failed to list modules: {}", e))?;
+
+        if !modules.iter().any(|m| m.code == code) {
+            return Ok(module_checker::is_module_enabled_or_core(
+                features_config.as_deref(),
+                code,
+            ));
+        }
+        Ok(resolve_active(&modules, features_config.as_deref()).contains(code))
+    }
+
+    fn module_statuses(modules: &[Module], features_config: Option<&str>) -> Vec<ShopModuleStatus> {
+        let active = resolve_active(modules, features_config);
+        modules
+            .iter()
+            .map(|m| ShopModuleStatus {
+                code: m.code.clone(),
+                name: m.name.clone(),
+                category: m.category.clone(),
+                is_core: m.is_core,
+                enabled: is_enabled(m, features_config),
+                active: active.contains(&m.code),
+                required_modules: parse_codes(m.required_modules.as_deref()),
+                conflicts_with: parse_codes(m.conflicts_with.as_deref()),
+            })
+            .collect()
+    }
+
+    pub async fn get_shop_modules(&self, shop_id: &str) -> Result<Vec<ShopModuleStatus>, String> {
+        let features_config = self.shop_features_config(shop_id).await?;
+        let modules = self
+            .repo
+            .list_all()
+            .await
+            .map_err(|e| format!("Failed to list modules: {}", e))?;
+        Ok(Self::module_statuses(&modules, features_config.as_deref()))
+    }
+
+    /// Enable or disable modules for a shop. Requirements of enabled modules
+    /// are switched on too; disabling a module others still need, enabling
+    /// conflicting modules, or disabling a core module is refused.
+    pub async fn set_shop_modules(
+        &self,
+        payload: SetShopModulesDTO,
+    ) -> Result<SetShopModulesResult, String> {
+        let previous_config = self.shop_features_config(&payload.shop_id).await?;
+        let modules = self
+            .repo
+            .list_all()
+            .await
+            .map_err(|e| format!("Failed to list modules: {}", e))?;
+        let by_code: HashMap<&str, &Module> =
+            modules.iter().map(|m| (m.code.as_str(), m)).collect();
+
+        for (code, enabled) in &payload.modules {
+            let module = by_code
+                .get(code.as_str())
+                .ok_or_else(|| format!("Unknown module: {}", code))?;
+            if module.is_core && !enabled {
+                return Err(format!("Module '{}' is core and cannot be disabled", code));
+            }
+        }
+
+        let before: HashMap<String, bool> = modules
+            .iter()
+            .map(|m| (m.code.clone(), is_enabled(m, previous_config.as_deref())))
+            .collect();
+        let mut state = before.clone();
+        for (code, enabled) in &payload.modules {
+            state.insert(code.clone(), *enabled);
+        }
+
+        // Switch on what the requested modules need, transitively
+        let mut auto_enabled = Vec::new();
+        let mut queue: Vec<String> = payload
+            .modules
+            .iter()
+            .filter(|(_, enabled)| **enabled)
+            .map(|(code, _)| code.clone())
+            .collect();
+        while let Some(code) = queue.pop() {
+            for required in parse_codes(by_code[code.as_str()].required_modules.as_deref()) {
+                if !by_code.contains_key(required.as_str()) {
+                    return Err(format!(
+                        "Module '{}' requires unknown module '{}'",
+                        code, required
+                    ));
+                }
+                if payload.modules.get(&required) == Some(&false) {
+                    return Err(format!(
+                        "Module '{}' requires '{}', which is being disabled",
+                        code, required
+                    ));
+                }
+                if !state[&required] {
+                    state.insert(required.clone(), true);
+                    auto_enabled.push(required.clone());
+                    queue.push(required);
+                }
+            }
+        }
+
+        for module in &modules {
+            if !state[&module.code] {
+                continue;
+            }
+            for required in parse_codes(module.required_modules.as_deref()) {
+                if !state.get(&required).copied().unwrap_or(false) {
+                    return Err(format!(
+                        "Module '{}' requires '{}'; disable '{}' first",
+                        module.code, required, module.code
+                    ));
+                }
+            }
+            for conflict in parse_codes(module.conflicts_with.as_deref()) {
+                if state.get(&conflict).copied().unwrap_or(false) {
+                    return Err(format!(
+                        "Modules '{}' and '{}' cannot be enabled together",
+                        module.code, conflict
+                    ));
+                }
+            }
+        }
+
+        let mut enabled: Vec<String> = modules
+            .iter()
+            .filter(|m| state[&m.code] && !before[&m.code])
+            .map(|m| m.code.clone())
+            .collect();
+        let mut disabled: Vec<String> = modules
+            .iter()
+            .filter(|m| !state[&m.code] && before[&m.code])
+            .map(|m| m.code.clone())
+            .collect();
+        enabled.sort();
+        disabled.sort();
+        auto_enabled.sort();
+
+        // Keep unrelated keys of the existing config
+        let mut config: Map<String, Value> = previous_config
+            .as_deref()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default();
+        for module in &modules {
+            config.insert(module.code.clone(), Value::Bool(state[&module.code]));
+        }
+        let features_config = Value::Object(config).to_string();
+
+        if !enabled.is_empty() || !disabled.is_empty() {
+            let mut tx = self
+                .pool
+                .begin()
+                .await
+                .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+            ShopsRepository::update_features_config_in_tx(
+                &mut tx,
+                &payload.shop_id,
+                &features_config,
+            )
+            .await
+            .map_err(|e| format!("Failed to update shop modules: {}", e))?;
+
+            let change = ShopModuleChange {
+                id: Uuid::new_v4().to_string(),
+                shop_id: payload.shop_id.clone(),
+                changed_by: payload.changed_by,
+                enabled_modules: Some(Value::from(enabled.clone()).to_string()),
+                disabled_modules: Some(Value::from(disabled.clone()).to_string()),
+                previous_config,
+                new_config: features_config.clone(),
+                reason: payload.reason,
+                created_at: Some(Utc::now()),
+            };
+            ModulesRepository::create_change_in_tx(&mut tx, &change)
+                .await
+                .map_err(|e| format!("Failed to record module change: {}", e))?;
+
+            tx.commit()
+                .await
+                .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        }
+
+        Ok(SetShopModulesResult {
+            modules: Self::module_statuses(&modules, Some(&features_config)),
+            features_config,
+            enabled,
+            disabled,
+            auto_enabled,
+        })
+    }
+
+    pub async fn list_shop_module_changes(
+        &self,
+        shop_id: &str,
+    ) -> Result<Vec<ShopModuleChange>, String> {
+        self.repo
+            .list_changes(shop_id)
+            .await
+            .map_err(|e| format!("Failed to list module changes: {}", e))
+    }
 }
@@ -181,6 +181,26 @@ impl ShopOrderRepository {
         Ok(shop_order.into_order(self.shop_id.clone()))
     }
 
+    pub async fn update_payment_status_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+        status: &str,
+        shop_id: String,
+    ) -> Result<Order> {
+        let sql = r#"
+            UPDATE orders SET payment_status = $2, _status = 'modified', updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+        "#;
+        let shop_order = sqlx::query_as::<_, ShopOrder>(sql)
+            .bind(id)
+            .bind(status)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(shop_order.into_order(shop_id))
+    }
+
     pub async fn update_fulfillment_status(&self, id: &str, status: &str) -> Result<Order> {
         let sql = r#"
             UPDATE orders SET fulfillment_status = $2, _status = 'modified', updated_at = datetime('now')
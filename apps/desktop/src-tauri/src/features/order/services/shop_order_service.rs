@@ -1,9 +1,11 @@
 //! Shop-scoped Order Service for Multi-Database Architecture
 
+use crate::features::customer::repositories::shop_customer_repository::ShopCustomerRepository;
+use crate::features::customer_group::services::shop_customer_segment_service::ShopCustomerSegmentService;
 use crate::features::order::dtos::order_dto::{CreateOrderDTO, UpdateOrderDTO};
 use crate::features::order::models::order_model::Order;
 use crate::features::order::repositories::shop_order_repository::ShopOrderRepository;
-use sqlx::SqlitePool;
+use sqlx::{Sqlite, SqlitePool, Transaction};
 use std::sync::Arc;
 
 pub struct ShopOrderService {
@@ -74,11 +76,28 @@ impl ShopOrderService {
             .map_err(|e| format!("Failed to list orders: {}", e))
     }
 
+    /// Paying an order adds it to the customer's spend
     pub async fn update_payment_status(&self, id: &str, status: &str) -> Result<Order, String> {
-        self.repo
-            .update_payment_status(id, status)
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let order = ShopOrderRepository::update_payment_status_in_tx(
+            &mut tx,
+            id,
+            status,
+            self.shop_id.clone(),
+        )
+        .await
+        .map_err(|e| format!("Failed to update payment status: {}", e))?;
+        self.refresh_customer_stats_in_tx(&mut tx, &order).await?;
+
+        tx.commit()
             .await
-            .map_err(|e| format!("Failed to update payment status: {}", e))
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        Ok(order)
     }
 
     pub async fn update_fulfillment_status(&self, id: &str, status: &str) -> Result<Order, String> {
@@ -88,10 +107,38 @@ impl ShopOrderService {
             .map_err(|e| format!("Failed to update fulfillment status: {}", e))
     }
 
+    /// A cancelled order no longer counts towards the customer's spend
     pub async fn cancel_order(&self, id: &str) -> Result<Order, String> {
-        self.repo
-            .cancel(id)
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let order = ShopOrderRepository::cancel_in_tx(&mut tx, id, self.shop_id.clone())
+            .await
+            .map_err(|e| format!("Failed to cancel order: {}", e))?;
+        self.refresh_customer_stats_in_tx(&mut tx, &order).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        Ok(order)
+    }
+
+    /// Recount the stats of the order's customer and re-evaluate its dynamic
+    /// groups
+    async fn refresh_customer_stats_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        order: &Order,
+    ) -> Result<(), String> {
+        let Some(customer_id) = &order.customer_id else {
+            return Ok(());
+        };
+        ShopCustomerRepository::refresh_stats_in_tx(tx, customer_id, self.shop_id.clone())
             .await
-            .map_err(|e| format!("Failed to cancel order: {}", e))
+            .map_err(|e| format!("Failed to update customer stats: {}", e))?;
+        ShopCustomerSegmentService::refresh_customer_in_tx(tx, customer_id).await
     }
 }
@@ -0,0 +1,98 @@
+use crate::db::RepositoryFactory;
+use crate::features::packing::dtos::packing_dto::{
+    CreatePackedShipmentsDTO, CreateShippingBoxDTO, PackItemDTO, UpdateShippingBoxDTO,
+};
+use crate::features::packing::models::packing_model::{PackingPlan, ShippingBox};
+use crate::features::packing::services::shop_packing_service::ShopPackingService;
+use crate::features::shipment::models::shipment_model::Shipment;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn list_shipping_boxes(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+) -> Result<Vec<ShippingBox>, String> {
+    let pool = repo_factory
+        .shop_module_pool(&shop_id, "shipping")
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPackingService::new(pool, shop_id);
+    service.list_boxes().await
+}
+
+#[tauri::command]
+pub async fn create_shipping_box(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: CreateShippingBoxDTO,
+) -> Result<ShippingBox, String> {
+    let pool = repo_factory
+        .shop_module_pool(&shop_id, "shipping")
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPackingService::new(pool, shop_id);
+    service.create_box(payload).await
+}
+
+#[tauri::command]
+pub async fn update_shipping_box(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: UpdateShippingBoxDTO,
+) -> Result<ShippingBox, String> {
+    let pool = repo_factory
+        .shop_module_pool(&shop_id, "shipping")
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPackingService::new(pool, shop_id);
+    service.update_box(payload).await
+}
+
+#[tauri::command]
+pub async fn delete_shipping_box(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+) -> Result<(), String> {
+    let pool = repo_factory
+        .shop_module_pool(&shop_id, "shipping")
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPackingService::new(pool, shop_id);
+    service.delete_box(&id).await
+}
+
+#[tauri::command]
+pub async fn compute_packing_plan(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    items: Vec<PackItemDTO>,
+) -> Result<PackingPlan, String> {
+    let pool = repo_factory
+        .shop_module_pool(&shop_id, "shipping")
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPackingService::new(pool, shop_id);
+    service.plan_packing(&items).await
+}
+
+#[tauri::command]
+pub async fn create_packed_shipments(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: CreatePackedShipmentsDTO,
+) -> Result<Vec<Shipment>, String> {
+    let pool = repo_factory
+        .shop_module_pool(&shop_id, "shipping")
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPackingService::new(pool, shop_id);
+    service.create_packed_shipments(payload).await
+}
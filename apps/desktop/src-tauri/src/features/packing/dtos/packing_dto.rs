@@ -0,0 +1,95 @@
+use crate::features::packing::models::packing_model::ShippingBox;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateShippingBoxDTO {
+    pub name: String,
+    pub package_type: Option<String>,
+    pub width_mm: i64,
+    pub height_mm: i64,
+    pub depth_mm: i64,
+    pub empty_weight_g: Option<i64>,
+    pub max_weight_g: Option<i64>,
+    pub is_active: Option<bool>,
+}
+
+impl CreateShippingBoxDTO {
+    pub fn into_model(self) -> ShippingBox {
+        let now = Utc::now();
+        ShippingBox {
+            id: Uuid::new_v4().to_string(),
+            name: self.name,
+            package_type: self.package_type.or(Some("box".to_string())),
+            width_mm: self.width_mm,
+            height_mm: self.height_mm,
+            depth_mm: self.depth_mm,
+            empty_weight_g: self.empty_weight_g.or(Some(0)),
+            max_weight_g: self.max_weight_g,
+            is_active: self.is_active.or(Some(true)),
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateShippingBoxDTO {
+    pub id: String,
+    pub name: Option<String>,
+    pub package_type: Option<String>,
+    pub width_mm: Option<i64>,
+    pub height_mm: Option<i64>,
+    pub depth_mm: Option<i64>,
+    pub empty_weight_g: Option<i64>,
+    pub max_weight_g: Option<i64>,
+    pub is_active: Option<bool>,
+}
+
+impl UpdateShippingBoxDTO {
+    pub fn apply_to_model(self, mut existing: ShippingBox) -> ShippingBox {
+        if let Some(name) = self.name {
+            existing.name = name;
+        }
+        if let Some(package_type) = self.package_type {
+            existing.package_type = Some(package_type);
+        }
+        if let Some(width_mm) = self.width_mm {
+            existing.width_mm = width_mm;
+        }
+        if let Some(height_mm) = self.height_mm {
+            existing.height_mm = height_mm;
+        }
+        if let Some(depth_mm) = self.depth_mm {
+            existing.depth_mm = depth_mm;
+        }
+        if let Some(empty_weight_g) = self.empty_weight_g {
+            existing.empty_weight_g = Some(empty_weight_g);
+        }
+        if let Some(max_weight_g) = self.max_weight_g {
+            existing.max_weight_g = Some(max_weight_g);
+        }
+        if let Some(is_active) = self.is_active {
+            existing.is_active = Some(is_active);
+        }
+        existing.sync_status = Some("modified".to_string());
+        existing.updated_at = Some(Utc::now());
+        existing
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PackItemDTO {
+    pub order_item_id: Option<String>,
+    pub product_id: String,
+    pub quantity: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatePackedShipmentsDTO {
+    pub order_id: String,
+    pub location_id: Option<String>,
+    pub items: Vec<PackItemDTO>,
+}
@@ -0,0 +1 @@
+pub mod packing_model;
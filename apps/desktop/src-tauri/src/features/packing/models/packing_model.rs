@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ShippingBox {
+    pub id: String,
+    pub name: String,
+    pub package_type: Option<String>, // 'box', 'envelope', 'tube'
+    pub width_mm: i64,
+    pub height_mm: i64,
+    pub depth_mm: i64,
+    pub empty_weight_g: Option<i64>,
+    pub max_weight_g: Option<i64>,
+    pub is_active: Option<bool>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PackedItem {
+    pub order_item_id: Option<String>,
+    pub product_id: String,
+    pub quantity: i64,
+}
+
+/// One physical package of a packing plan
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PackedPackage {
+    pub box_id: Option<String>, // None when the item ships in its own packaging
+    pub box_name: Option<String>,
+    pub package_type: String,
+    pub weight_g: i64, // Items plus the empty box
+    pub width_mm: i64,
+    pub height_mm: i64,
+    pub depth_mm: i64,
+    pub items: Vec<PackedItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PackingPlan {
+    pub packages: Vec<PackedPackage>,
+    pub total_weight_g: i64,
+}
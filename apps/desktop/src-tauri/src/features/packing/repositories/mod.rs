@@ -0,0 +1 @@
+pub mod shop_shipping_box_repository;
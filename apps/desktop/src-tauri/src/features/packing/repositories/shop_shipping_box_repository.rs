@@ -0,0 +1,110 @@
+//! Shop-scoped Shipping Box Repository for Multi-Database Architecture
+
+use crate::features::packing::models::packing_model::ShippingBox;
+use sqlx::{Result, SqlitePool};
+use std::sync::Arc;
+
+pub struct ShopShippingBoxRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopShippingBoxRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, shipping_box: &ShippingBox) -> Result<ShippingBox> {
+        let sql = r#"
+            INSERT INTO shipping_boxes (
+                id, name, package_type, width_mm, height_mm, depth_mm,
+                empty_weight_g, max_weight_g, is_active, _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING *
+        "#;
+
+        sqlx::query_as::<_, ShippingBox>(sql)
+            .bind(&shipping_box.id)
+            .bind(&shipping_box.name)
+            .bind(&shipping_box.package_type)
+            .bind(shipping_box.width_mm)
+            .bind(shipping_box.height_mm)
+            .bind(shipping_box.depth_mm)
+            .bind(shipping_box.empty_weight_g)
+            .bind(shipping_box.max_weight_g)
+            .bind(shipping_box.is_active)
+            .bind(&shipping_box.sync_status)
+            .bind(shipping_box.created_at)
+            .bind(shipping_box.updated_at)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn update(&self, shipping_box: &ShippingBox) -> Result<ShippingBox> {
+        let sql = r#"
+            UPDATE shipping_boxes SET
+                name = $2,
+                package_type = $3,
+                width_mm = $4,
+                height_mm = $5,
+                depth_mm = $6,
+                empty_weight_g = $7,
+                max_weight_g = $8,
+                is_active = $9,
+                _status = $10,
+                updated_at = $11
+            WHERE id = $1
+            RETURNING *
+        "#;
+
+        sqlx::query_as::<_, ShippingBox>(sql)
+            .bind(&shipping_box.id)
+            .bind(&shipping_box.name)
+            .bind(&shipping_box.package_type)
+            .bind(shipping_box.width_mm)
+            .bind(shipping_box.height_mm)
+            .bind(shipping_box.depth_mm)
+            .bind(shipping_box.empty_weight_g)
+            .bind(shipping_box.max_weight_g)
+            .bind(shipping_box.is_active)
+            .bind(&shipping_box.sync_status)
+            .bind(shipping_box.updated_at)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn get_by_id(&self, id: &str) -> Result<Option<ShippingBox>> {
+        let sql = "SELECT * FROM shipping_boxes WHERE id = $1 AND (_status IS NULL OR _status != 'deleted')";
+        sqlx::query_as::<_, ShippingBox>(sql)
+            .bind(id)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn list(&self) -> Result<Vec<ShippingBox>> {
+        let sql = r#"
+            SELECT * FROM shipping_boxes
+            WHERE _status IS NULL OR _status != 'deleted'
+            ORDER BY width_mm * height_mm * depth_mm ASC
+        "#;
+        sqlx::query_as::<_, ShippingBox>(sql)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn list_active(&self) -> Result<Vec<ShippingBox>> {
+        let sql = r#"
+            SELECT * FROM shipping_boxes
+            WHERE is_active = true AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY width_mm * height_mm * depth_mm ASC
+        "#;
+        sqlx::query_as::<_, ShippingBox>(sql)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        let sql = "UPDATE shipping_boxes SET _status = 'deleted', updated_at = datetime('now') WHERE id = $1";
+        sqlx::query(sql).bind(id).execute(&*self.pool).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1 @@
+pub mod shop_packing_service;
@@ -0,0 +1,410 @@
+//! Shop-scoped Packing Service for Multi-Database Architecture
+//!
+//! Packs order items into the shop's box catalog with a first-fit decreasing
+//! heuristic: units are placed largest first into the first open box with room,
+//! new boxes are opened at the largest size the unit fits, and every box is
+//! finally shrunk to the smallest size that still holds its contents.
+//! Units that fit no box ship in their own packaging.
+
+use crate::features::packing::dtos::packing_dto::{
+    CreatePackedShipmentsDTO, CreateShippingBoxDTO, PackItemDTO, UpdateShippingBoxDTO,
+};
+use crate::features::packing::models::packing_model::{
+    PackedItem, PackedPackage, PackingPlan, ShippingBox,
+};
+use crate::features::packing::repositories::shop_shipping_box_repository::ShopShippingBoxRepository;
+use crate::features::product::repositories::shop_product_repository::ShopProductRepository;
+use crate::features::shipment::models::shipment_model::{Shipment, ShipmentItem};
+use crate::features::shipment::services::shipment_service::ShipmentService;
+use chrono::Utc;
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Share of a box's volume that can actually be filled: items rarely tessellate
+const FILL_RATIO: f64 = 0.85;
+const PACKAGE_TYPES: [&str; 3] = ["box", "envelope", "tube"];
+/// Units are packed one by one, so a single plan is kept to a sane size
+const MAX_PACKED_UNITS: usize = 10_000;
+
+#[derive(Clone)]
+struct Unit {
+    order_item_id: Option<String>,
+    product_id: String,
+    dims: [i64; 3], // Sorted largest first, so any rotation can be checked
+    volume: i64,
+    weight_g: i64,
+}
+
+struct OpenPackage<'a> {
+    shipping_box: &'a ShippingBox,
+    units: Vec<Unit>,
+}
+
+fn sorted_dims(width_mm: i64, height_mm: i64, depth_mm: i64) -> [i64; 3] {
+    let mut dims = [width_mm.max(0), height_mm.max(0), depth_mm.max(0)];
+    dims.sort_unstable_by(|a, b| b.cmp(a));
+    dims
+}
+
+fn box_dims(shipping_box: &ShippingBox) -> [i64; 3] {
+    sorted_dims(
+        shipping_box.width_mm,
+        shipping_box.height_mm,
+        shipping_box.depth_mm,
+    )
+}
+
+/// Whether a box can hold a set of units
+fn box_holds(shipping_box: &ShippingBox, units: &[&Unit]) -> bool {
+    let dims = box_dims(shipping_box);
+    if !units
+        .iter()
+        .all(|u| u.dims.iter().zip(dims.iter()).all(|(a, b)| a <= b))
+    {
+        return false;
+    }
+
+    // A single unit that fits dimensionally always fits alone
+    let volume: i64 = units.iter().map(|u| u.volume).sum();
+    if units.len() > 1 && volume as f64 > (dims[0] * dims[1] * dims[2]) as f64 * FILL_RATIO {
+        return false;
+    }
+
+    let weight: i64 =
+        units.iter().map(|u| u.weight_g).sum::<i64>() + shipping_box.empty_weight_g.unwrap_or(0);
+    shipping_box.max_weight_g.is_none_or(|max| weight <= max)
+}
+
+/// Group a package's units back into item lines
+fn packed_items(units: &[Unit]) -> Vec<PackedItem> {
+    let mut items: Vec<PackedItem> = Vec::new();
+    for unit in units {
+        match items
+            .iter_mut()
+            .find(|i| i.product_id == unit.product_id && i.order_item_id == unit.order_item_id)
+        {
+            Some(item) => item.quantity += 1,
+            None => items.push(PackedItem {
+                order_item_id: unit.order_item_id.clone(),
+                product_id: unit.product_id.clone(),
+                quantity: 1,
+            }),
+        }
+    }
+    items
+}
+
+/// Compute the packages for a set of units; `boxes` must be sorted smallest first
+fn pack_units(mut units: Vec<Unit>, boxes: &[ShippingBox]) -> Vec<PackedPackage> {
+    units.sort_by(|a, b| b.volume.cmp(&a.volume).then(b.weight_g.cmp(&a.weight_g)));
+
+    let mut open: Vec<OpenPackage> = Vec::new();
+    let mut own_packaging: Vec<Unit> = Vec::new();
+
+    for unit in units {
+        let placed = open.iter_mut().find(|package| {
+            let mut candidate: Vec<&Unit> = package.units.iter().collect();
+            candidate.push(&unit);
+            box_holds(package.shipping_box, &candidate)
+        });
+
+        if let Some(package) = placed {
+            package.units.push(unit);
+            continue;
+        }
+
+        match boxes.iter().rev().find(|b| box_holds(b, &[&unit])) {
+            Some(shipping_box) => open.push(OpenPackage {
+                shipping_box,
+                units: vec![unit],
+            }),
+            None => own_packaging.push(unit),
+        }
+    }
+
+    let mut packages = Vec::with_capacity(open.len() + own_packaging.len());
+    for package in open {
+        let units: Vec<&Unit> = package.units.iter().collect();
+        let shipping_box = boxes
+            .iter()
+            .find(|b| box_holds(b, &units))
+            .unwrap_or(package.shipping_box);
+
+        packages.push(PackedPackage {
+            box_id: Some(shipping_box.id.clone()),
+            box_name: Some(shipping_box.name.clone()),
+            package_type: shipping_box
+                .package_type
+                .clone()
+                .unwrap_or_else(|| "box".to_string()),
+            weight_g: package.units.iter().map(|u| u.weight_g).sum::<i64>()
+                + shipping_box.empty_weight_g.unwrap_or(0),
+            width_mm: shipping_box.width_mm,
+            height_mm: shipping_box.height_mm,
+            depth_mm: shipping_box.depth_mm,
+            items: packed_items(&package.units),
+        });
+    }
+
+    for unit in own_packaging {
+        packages.push(PackedPackage {
+            box_id: None,
+            box_name: None,
+            package_type: "own_packaging".to_string(),
+            weight_g: unit.weight_g,
+            width_mm: unit.dims[1],
+            height_mm: unit.dims[2],
+            depth_mm: unit.dims[0],
+            items: packed_items(std::slice::from_ref(&unit)),
+        });
+    }
+
+    packages
+}
+
+/// Without a box catalog everything goes in one package sized by stacking the units
+fn stack_units(units: Vec<Unit>) -> Vec<PackedPackage> {
+    if units.is_empty() {
+        return Vec::new();
+    }
+    vec![PackedPackage {
+        box_id: None,
+        box_name: None,
+        package_type: "box".to_string(),
+        weight_g: units.iter().map(|u| u.weight_g).sum(),
+        width_mm: units.iter().map(|u| u.dims[1]).max().unwrap_or(0),
+        height_mm: units.iter().map(|u| u.dims[2]).sum(),
+        depth_mm: units.iter().map(|u| u.dims[0]).max().unwrap_or(0),
+        items: packed_items(&units),
+    }]
+}
+
+pub struct ShopPackingService {
+    pool: Arc<SqlitePool>,
+    shop_id: String,
+    repo: ShopShippingBoxRepository,
+}
+
+impl ShopPackingService {
+    pub fn new(pool: Arc<SqlitePool>, shop_id: String) -> Self {
+        let repo = ShopShippingBoxRepository::new(pool.clone());
+        Self {
+            pool,
+            shop_id,
+            repo,
+        }
+    }
+
+    pub fn pool(&self) -> Arc<SqlitePool> {
+        self.pool.clone()
+    }
+
+    // ============================================================
+    // Box catalog
+    // ============================================================
+
+    fn validate_box(shipping_box: &ShippingBox) -> Result<(), String> {
+        if shipping_box.width_mm <= 0 || shipping_box.height_mm <= 0 || shipping_box.depth_mm <= 0 {
+            return Err("Box dimensions must be greater than zero".to_string());
+        }
+        if let Some(package_type) = &shipping_box.package_type {
+            if !PACKAGE_TYPES.contains(&package_type.as_str()) {
+                return Err(format!("Invalid package type: {}", package_type));
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn list_boxes(&self) -> Result<Vec<ShippingBox>, String> {
+        self.repo
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list shipping boxes: {}", e))
+    }
+
+    pub async fn create_box(&self, payload: CreateShippingBoxDTO) -> Result<ShippingBox, String> {
+        let shipping_box = payload.into_model();
+        Self::validate_box(&shipping_box)?;
+        self.repo
+            .create(&shipping_box)
+            .await
+            .map_err(|e| format!("Failed to create shipping box: {}", e))
+    }
+
+    pub async fn update_box(&self, payload: UpdateShippingBoxDTO) -> Result<ShippingBox, String> {
+        let existing = self
+            .repo
+            .get_by_id(&payload.id)
+            .await
+            .map_err(|e| format!("Failed to fetch shipping box: {}", e))?
+            .ok_or_else(|| format!("Shipping box not found: {}", payload.id))?;
+
+        let shipping_box = payload.apply_to_model(existing);
+        Self::validate_box(&shipping_box)?;
+        self.repo
+            .update(&shipping_box)
+            .await
+            .map_err(|e| format!("Failed to update shipping box: {}", e))
+    }
+
+    pub async fn delete_box(&self, id: &str) -> Result<(), String> {
+        self.repo
+            .delete(id)
+            .await
+            .map_err(|e| format!("Failed to delete shipping box: {}", e))
+    }
+
+    // ============================================================
+    // Packing
+    // ============================================================
+
+    /// Compute which items go in which box. Non-shippable products, digital
+    /// goods and services are left out.
+    pub async fn plan_packing(&self, items: &[PackItemDTO]) -> Result<PackingPlan, String> {
+        let product_repo = ShopProductRepository::new(self.pool.clone(), self.shop_id.clone());
+
+        let mut units = Vec::new();
+        for item in items {
+            if item.quantity <= 0 {
+                return Err(format!("Invalid quantity for product {}", item.product_id));
+            }
+            if units.len() + item.quantity as usize > MAX_PACKED_UNITS {
+                return Err(format!(
+                    "Too many units to pack at once (at most {})",
+                    MAX_PACKED_UNITS
+                ));
+            }
+            let product = product_repo
+                .get_by_id(&item.product_id)
+                .await
+                .map_err(|e| format!("Failed to fetch product: {}", e))?
+                .ok_or_else(|| format!("Product not found: {}", item.product_id))?;
+
+            if !product.needs_shipping() {
+                continue;
+            }
+
+            let dims = sorted_dims(product.width_mm, product.height_mm, product.depth_mm);
+            let unit = Unit {
+                order_item_id: item.order_item_id.clone(),
+                product_id: product.id.clone(),
+                dims,
+                volume: dims[0] * dims[1] * dims[2],
+                weight_g: product.weight_g.max(0),
+            };
+            units.extend(std::iter::repeat_n(unit, item.quantity as usize));
+        }
+
+        let boxes = self
+            .repo
+            .list_active()
+            .await
+            .map_err(|e| format!("Failed to list shipping boxes: {}", e))?;
+
+        let packages = if boxes.is_empty() {
+            stack_units(units)
+        } else {
+            pack_units(units, &boxes)
+        };
+
+        Ok(PackingPlan {
+            total_weight_g: packages.iter().map(|p| p.weight_g).sum(),
+            packages,
+        })
+    }
+
+    /// Pack an order's items and create one pre-filled shipment per package
+    pub async fn create_packed_shipments(
+        &self,
+        payload: CreatePackedShipmentsDTO,
+    ) -> Result<Vec<Shipment>, String> {
+        if payload.items.iter().any(|i| i.order_item_id.is_none()) {
+            return Err("Every item needs an order_item_id to be shipped".to_string());
+        }
+
+        let plan = self.plan_packing(&payload.items).await?;
+        if plan.packages.is_empty() {
+            return Err("None of the items needs shipping".to_string());
+        }
+
+        let package_count = plan.packages.len();
+        let mut shipments = Vec::with_capacity(package_count);
+
+        // All packages are created or none, so a retry never duplicates boxes
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        for (index, package) in plan.packages.into_iter().enumerate() {
+            let now = Utc::now();
+            let shipment_id = Uuid::new_v4().to_string();
+
+            let shipment = Shipment {
+                id: shipment_id.clone(),
+                order_id: payload.order_id.clone(),
+                location_id: payload.location_id.clone(),
+                status: Some("pending".to_string()),
+                carrier_company: None,
+                carrier_service: None,
+                tracking_number: None,
+                tracking_url: None,
+                weight_g: Some(package.weight_g as i32),
+                height_mm: Some(package.height_mm as i32),
+                width_mm: Some(package.width_mm as i32),
+                depth_mm: Some(package.depth_mm as i32),
+                package_type: Some(package.package_type.clone()),
+                shipping_label_url: None,
+                invoice_url: None,
+                invoice_key: None,
+                cost_amount: None,
+                insurance_amount: None,
+                estimated_delivery_at: None,
+                shipped_at: None,
+                delivered_at: None,
+                metadata: Some(
+                    json!({
+                        "box_id": package.box_id,
+                        "box_name": package.box_name,
+                        "package": index + 1,
+                        "package_count": package_count,
+                    })
+                    .to_string(),
+                ),
+                customs_info: None,
+                sync_status: Some("created".to_string()),
+                created_at: Some(now),
+                updated_at: Some(now),
+            };
+
+            let items = package
+                .items
+                .iter()
+                .map(|item| ShipmentItem {
+                    id: Uuid::new_v4().to_string(),
+                    shipment_id: shipment_id.clone(),
+                    order_item_id: item.order_item_id.clone().unwrap_or_default(),
+                    quantity: item.quantity as i32,
+                    batch_number: None,
+                    serial_numbers: None,
+                    sync_status: Some("created".to_string()),
+                    created_at: Some(now),
+                    updated_at: Some(now),
+                })
+                .collect();
+
+            let created =
+                ShipmentService::create_full_shipment_in_tx(&mut tx, shipment, items).await?;
+            shipments.push(created);
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(shipments)
+    }
+}
@@ -0,0 +1,83 @@
+use crate::db::RepositoryFactory;
+use crate::features::payment::dtos::payment_dto::UpsertInstallmentRateDTO;
+use crate::features::payment::models::installment_model::{
+    InstallmentOption, InstallmentRate, PaymentInstallment,
+};
+use crate::features::payment::services::shop_installment_service::ShopInstallmentService;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn list_installment_rates(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+) -> Result<Vec<InstallmentRate>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopInstallmentService::new(pool);
+    service.list_rates().await
+}
+
+#[tauri::command]
+pub async fn upsert_installment_rate(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: UpsertInstallmentRateDTO,
+) -> Result<InstallmentRate, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopInstallmentService::new(pool);
+    service.upsert_rate(payload).await
+}
+
+#[tauri::command]
+pub async fn delete_installment_rate(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+) -> Result<(), String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopInstallmentService::new(pool);
+    service.delete_rate(&id).await
+}
+
+#[tauri::command]
+pub async fn quote_installments(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    method: String,
+    amount: f64,
+) -> Result<Vec<InstallmentOption>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopInstallmentService::new(pool);
+    service.quote(&method, amount).await
+}
+
+#[tauri::command]
+pub async fn list_payment_installments(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payment_id: String,
+) -> Result<Vec<PaymentInstallment>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopInstallmentService::new(pool);
+    service.list_installments(&payment_id).await
+}
@@ -1 +1,2 @@
+pub mod installment_commands;
 pub mod payment_commands;
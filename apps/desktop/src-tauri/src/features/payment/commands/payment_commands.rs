@@ -1,6 +1,8 @@
 use crate::db::RepositoryFactory;
-use crate::features::payment::models::payment_model::Payment;
+use crate::features::payment::dtos::payment_dto::CreatePaymentDTO;
+use crate::features::payment::models::payment_model::{Payment, PaymentSummary};
 use crate::features::payment::services::shop_payment_service::ShopPaymentService;
+use crate::features::refund::models::refund_model::Refund;
 use std::sync::Arc;
 use tauri::State;
 
@@ -62,3 +64,53 @@ pub async fn update_payment_status(
     let service = ShopPaymentService::new(pool);
     service.update_payment_status(&id, &status).await
 }
+
+#[tauri::command]
+pub async fn create_payment(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: CreatePaymentDTO,
+) -> Result<Payment, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPaymentService::new(pool);
+    service.create_payment(payload).await
+}
+
+#[tauri::command]
+pub async fn process_refund(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payment_id: String,
+    amount: f64,
+    reason: Option<String>,
+    created_by: Option<String>,
+) -> Result<Refund, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPaymentService::new(pool);
+    service
+        .process_refund(&payment_id, amount, reason.as_deref(), created_by.as_deref())
+        .await
+}
+
+#[tauri::command]
+pub async fn get_payment_summary(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    transaction_id: String,
+) -> Result<PaymentSummary, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPaymentService::new(pool);
+    service.get_payment_summary(&transaction_id).await
+}
@@ -0,0 +1,73 @@
+use crate::features::payment::models::installment_model::InstallmentRate;
+use crate::features::payment::models::payment_model::Payment;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatePaymentDTO {
+    pub transaction_id: String,
+    pub amount: f64,
+    pub currency: Option<String>,
+    pub provider: Option<String>,
+    pub method: String, // 'cash', 'pix', 'credit_card', 'gift_card', 'store_credit', ...
+    pub installments: Option<i64>,
+    pub status: Option<String>,
+    pub provider_transaction_id: Option<String>,
+    pub authorization_code: Option<String>,
+    pub payment_details: Option<String>, // JSONB
+    pub gift_card_code: Option<String>,  // Required when method is 'gift_card'
+    pub created_by: Option<String>,
+}
+
+impl CreatePaymentDTO {
+    pub fn into_model(self) -> Payment {
+        let now = Utc::now();
+        Payment {
+            id: Uuid::new_v4().to_string(),
+            transaction_id: self.transaction_id,
+            amount: self.amount,
+            currency: self.currency.or(Some("BRL".to_string())),
+            provider: self.provider.unwrap_or_else(|| "internal".to_string()),
+            method: self.method,
+            installments: self.installments.or(Some(1)),
+            status: self.status.unwrap_or_else(|| "pending".to_string()),
+            provider_transaction_id: self.provider_transaction_id,
+            authorization_code: self.authorization_code,
+            payment_details: self.payment_details,
+            risk_level: None,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+            authorized_at: None,
+            captured_at: None,
+            voided_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpsertInstallmentRateDTO {
+    pub method: Option<String>, // Defaults to 'credit_card'
+    pub installments: i64,
+    pub interest_rate: f64, // % per month
+    pub min_installment_amount: Option<f64>,
+    pub is_active: Option<bool>,
+}
+
+impl UpsertInstallmentRateDTO {
+    pub fn into_model(self) -> InstallmentRate {
+        let now = Utc::now();
+        InstallmentRate {
+            id: Uuid::new_v4().to_string(),
+            method: self.method.unwrap_or_else(|| "credit_card".to_string()),
+            installments: self.installments,
+            interest_rate: self.interest_rate,
+            min_installment_amount: self.min_installment_amount,
+            is_active: self.is_active.or(Some(true)),
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        }
+    }
+}
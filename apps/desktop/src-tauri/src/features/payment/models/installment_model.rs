@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct InstallmentRate {
+    pub id: String,
+    pub method: String, // DEFAULT 'credit_card'
+    pub installments: i64,
+    pub interest_rate: f64, // % per month, 0 = interest-free
+    pub min_installment_amount: Option<f64>,
+    pub is_active: Option<bool>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PaymentInstallment {
+    pub id: String,
+    pub payment_id: String,
+    pub number: i64,
+    pub due_date: DateTime<Utc>,
+    pub principal: f64,
+    pub interest: f64,
+    pub amount: f64,    // principal + interest
+    pub status: String, // 'pending', 'paid', 'cancelled'
+    pub paid_at: Option<DateTime<Utc>>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Installment plan quoted for an amount, shown at checkout before paying
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstallmentOption {
+    pub installments: i64,
+    pub interest_rate: f64,
+    pub installment_amount: f64,
+    pub total_interest: f64,
+    pub total_amount: f64,
+}
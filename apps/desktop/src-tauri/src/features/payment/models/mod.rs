@@ -1 +1,2 @@
+pub mod installment_model;
 pub mod payment_model;
@@ -25,3 +25,15 @@ pub struct Payment {
     pub captured_at: Option<DateTime<Utc>>,
     pub voided_at: Option<DateTime<Utc>>,
 }
+
+/// Tender state of a transaction paid with one or more payments
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PaymentSummary {
+    pub transaction_id: String,
+    pub total_due: f64,
+    pub total_paid: f64,
+    pub remaining_balance: f64,
+    pub change_given: f64,
+    pub is_fully_paid: bool,
+    pub payments: Vec<Payment>,
+}
@@ -1,2 +1,3 @@
 pub mod payments_repository;
+pub mod shop_installment_repository;
 pub mod shop_payment_repository;
@@ -210,7 +210,7 @@ impl<'a> PaymentsRepository<'a> {
         payment_id: &str,
     ) -> Result<f64> {
         let sql = r#"
-            SELECT COALESCE(SUM(amount), 0) as total
+            SELECT COALESCE(SUM(amount), 0.0) as total
             FROM refunds
             WHERE payment_id = $1 AND status = 'completed'
         "#;
@@ -0,0 +1,137 @@
+//! Shop-scoped Installment Repository for Multi-Database Architecture
+
+use crate::features::payment::models::installment_model::{InstallmentRate, PaymentInstallment};
+use sqlx::{Result, Sqlite, SqlitePool, Transaction};
+use std::sync::Arc;
+
+pub struct ShopInstallmentRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopInstallmentRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_rates(&self) -> Result<Vec<InstallmentRate>> {
+        let sql = r#"
+            SELECT * FROM installment_rates
+            WHERE _status IS NULL OR _status != 'deleted'
+            ORDER BY method, installments
+        "#;
+        sqlx::query_as::<_, InstallmentRate>(sql)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn list_active_rates_by_method(&self, method: &str) -> Result<Vec<InstallmentRate>> {
+        let sql = r#"
+            SELECT * FROM installment_rates
+            WHERE method = $1 AND is_active = true
+              AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY installments
+        "#;
+        sqlx::query_as::<_, InstallmentRate>(sql)
+            .bind(method)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// Insert a rate, or replace the one already configured for the same method and installments
+    pub async fn upsert_rate(&self, rate: &InstallmentRate) -> Result<InstallmentRate> {
+        let sql = r#"
+            INSERT INTO installment_rates (
+                id, method, installments, interest_rate, min_installment_amount,
+                is_active, _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (method, installments) DO UPDATE SET
+                interest_rate = excluded.interest_rate,
+                min_installment_amount = excluded.min_installment_amount,
+                is_active = excluded.is_active,
+                _status = 'modified',
+                updated_at = excluded.updated_at
+            RETURNING *
+        "#;
+
+        sqlx::query_as::<_, InstallmentRate>(sql)
+            .bind(&rate.id)
+            .bind(&rate.method)
+            .bind(rate.installments)
+            .bind(rate.interest_rate)
+            .bind(rate.min_installment_amount)
+            .bind(rate.is_active)
+            .bind(&rate.sync_status)
+            .bind(rate.created_at)
+            .bind(rate.updated_at)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn delete_rate(&self, id: &str) -> Result<()> {
+        let sql = "UPDATE installment_rates SET _status = 'deleted', updated_at = datetime('now') WHERE id = $1";
+        sqlx::query(sql).bind(id).execute(&*self.pool).await?;
+        Ok(())
+    }
+
+    pub async fn list_by_payment(&self, payment_id: &str) -> Result<Vec<PaymentInstallment>> {
+        let sql = r#"
+            SELECT * FROM payment_installments
+            WHERE payment_id = $1 AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY number
+        "#;
+        sqlx::query_as::<_, PaymentInstallment>(sql)
+            .bind(payment_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    // ============================================================
+    // Transaction-aware methods for atomic operations
+    // ============================================================
+
+    pub async fn get_active_rate_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        method: &str,
+        installments: i64,
+    ) -> Result<Option<InstallmentRate>> {
+        let sql = r#"
+            SELECT * FROM installment_rates
+            WHERE method = $1 AND installments = $2 AND is_active = true
+              AND (_status IS NULL OR _status != 'deleted')
+        "#;
+        sqlx::query_as::<_, InstallmentRate>(sql)
+            .bind(method)
+            .bind(installments)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    pub async fn create_installment_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        installment: &PaymentInstallment,
+    ) -> Result<PaymentInstallment> {
+        let sql = r#"
+            INSERT INTO payment_installments (
+                id, payment_id, number, due_date, principal, interest, amount,
+                status, paid_at, _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING *
+        "#;
+
+        sqlx::query_as::<_, PaymentInstallment>(sql)
+            .bind(&installment.id)
+            .bind(&installment.payment_id)
+            .bind(installment.number)
+            .bind(installment.due_date)
+            .bind(installment.principal)
+            .bind(installment.interest)
+            .bind(installment.amount)
+            .bind(&installment.status)
+            .bind(installment.paid_at)
+            .bind(&installment.sync_status)
+            .bind(installment.created_at)
+            .bind(installment.updated_at)
+            .fetch_one(&mut **tx)
+            .await
+    }
+}
@@ -1,7 +1,7 @@
 //! Shop-scoped Payment Repository for Multi-Database Architecture
 
 use crate::features::payment::models::payment_model::Payment;
-use sqlx::{Result, SqlitePool};
+use sqlx::{Result, Sqlite, SqlitePool, Transaction};
 use std::sync::Arc;
 
 pub struct ShopPaymentRepository {
@@ -171,7 +171,7 @@ impl ShopPaymentRepository {
 
     pub async fn get_refunded_amount(&self, payment_id: &str) -> Result<f64> {
         let sql = r#"
-            SELECT COALESCE(SUM(amount), 0) as total
+            SELECT COALESCE(SUM(amount), 0.0) as total
             FROM refunds
             WHERE payment_id = $1 AND status = 'completed'
         "#;
@@ -181,4 +181,79 @@ impl ShopPaymentRepository {
             .await?;
         Ok(result.0)
     }
+
+    pub async fn create_in_tx(tx: &mut Transaction<'_, Sqlite>, payment: &Payment) -> Result<Payment> {
+        let sql = r#"
+            INSERT INTO payments (
+                id, transaction_id, amount, currency, provider, method,
+                installments, status, provider_transaction_id, authorization_code,
+                payment_details, risk_level, _status, created_at, updated_at,
+                authorized_at, captured_at, voided_at
+            )
+            VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18
+            )
+            RETURNING *
+        "#;
+
+        sqlx::query_as::<_, Payment>(sql)
+            .bind(&payment.id)
+            .bind(&payment.transaction_id)
+            .bind(payment.amount)
+            .bind(&payment.currency)
+            .bind(&payment.provider)
+            .bind(&payment.method)
+            .bind(payment.installments)
+            .bind(&payment.status)
+            .bind(&payment.provider_transaction_id)
+            .bind(&payment.authorization_code)
+            .bind(&payment.payment_details)
+            .bind(&payment.risk_level)
+            .bind(&payment.sync_status)
+            .bind(payment.created_at)
+            .bind(payment.updated_at)
+            .bind(payment.authorized_at)
+            .bind(payment.captured_at)
+            .bind(payment.voided_at)
+            .fetch_one(&mut **tx)
+            .await
+    }
+
+    pub async fn list_by_transaction_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        transaction_id: &str,
+    ) -> Result<Vec<Payment>> {
+        let sql = r#"
+            SELECT * FROM payments
+            WHERE transaction_id = $1 AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY created_at ASC
+        "#;
+        sqlx::query_as::<_, Payment>(sql)
+            .bind(transaction_id)
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    /// Amount actually paid towards a transaction: authorized or captured payments,
+    /// net of completed refunds
+    pub async fn get_paid_amount_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        transaction_id: &str,
+    ) -> Result<f64> {
+        let sql = r#"
+            SELECT COALESCE(SUM(p.amount - COALESCE((
+                SELECT SUM(r.amount) FROM refunds r
+                WHERE r.payment_id = p.id AND r.status = 'completed'
+            ), 0)), 0.0) AS total
+            FROM payments p
+            WHERE p.transaction_id = $1
+              AND p.status IN ('authorized', 'captured', 'partially_refunded')
+              AND (p._status IS NULL OR p._status != 'deleted')
+        "#;
+        let result: (f64,) = sqlx::query_as(sql)
+            .bind(transaction_id)
+            .fetch_one(&mut **tx)
+            .await?;
+        Ok(result.0)
+    }
 }
@@ -1,2 +1,3 @@
 pub mod payment_service;
+pub mod shop_installment_service;
 pub mod shop_payment_service;
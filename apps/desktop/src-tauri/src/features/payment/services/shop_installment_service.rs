@@ -0,0 +1,199 @@
+//! Shop-scoped Installment Service for Multi-Database Architecture
+//!
+//! Each shop configures which installment plans it offers per payment method
+//! and the monthly interest charged on them. Installments with interest follow
+//! the Price table: equal installments, interest charged on the outstanding balance.
+
+use crate::features::payment::dtos::payment_dto::UpsertInstallmentRateDTO;
+use crate::features::payment::models::installment_model::{
+    InstallmentOption, InstallmentRate, PaymentInstallment,
+};
+use crate::features::payment::models::payment_model::Payment;
+use crate::features::payment::repositories::shop_installment_repository::ShopInstallmentRepository;
+use chrono::{Months, Utc};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn round_cents(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Split a principal into `installments` parts of (principal, interest)
+fn build_schedule(principal: f64, installments: i64, interest_rate: f64) -> Vec<(f64, f64)> {
+    let n = installments.max(1);
+    let rate = interest_rate / 100.0;
+    let installment_amount = if rate > 0.0 {
+        round_cents(principal * rate / (1.0 - (1.0 + rate).powi(-(n as i32))))
+    } else {
+        round_cents(principal / n as f64)
+    };
+
+    let mut balance = principal;
+    let mut schedule = Vec::with_capacity(n as usize);
+    for number in 1..=n {
+        let interest = round_cents(balance * rate);
+        // The last installment absorbs rounding differences
+        let part = if number == n {
+            round_cents(balance)
+        } else {
+            round_cents(installment_amount - interest)
+        };
+        balance -= part;
+        schedule.push((part, interest));
+    }
+    schedule
+}
+
+pub struct ShopInstallmentService {
+    pool: Arc<SqlitePool>,
+    repo: ShopInstallmentRepository,
+}
+
+impl ShopInstallmentService {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        let repo = ShopInstallmentRepository::new(pool.clone());
+        Self { pool, repo }
+    }
+
+    pub fn pool(&self) -> Arc<SqlitePool> {
+        self.pool.clone()
+    }
+
+    pub async fn list_rates(&self) -> Result<Vec<InstallmentRate>, String> {
+        self.repo
+            .list_rates()
+            .await
+            .map_err(|e| format!("Failed to list installment rates: {}", e))
+    }
+
+    pub async fn upsert_rate(&self, payload: UpsertInstallmentRateDTO) -> Result<InstallmentRate, String> {
+        if payload.installments < 1 {
+            return Err("Installments must be at least 1".to_string());
+        }
+        if payload.interest_rate < 0.0 {
+            return Err("Interest rate cannot be negative".to_string());
+        }
+
+        let rate = payload.into_model();
+        self.repo
+            .upsert_rate(&rate)
+            .await
+            .map_err(|e| format!("Failed to save installment rate: {}", e))
+    }
+
+    pub async fn delete_rate(&self, id: &str) -> Result<(), String> {
+        self.repo
+            .delete_rate(id)
+            .await
+            .map_err(|e| format!("Failed to delete installment rate: {}", e))
+    }
+
+    /// Installment plans available for an amount, with the interest each one adds
+    pub async fn quote(&self, method: &str, amount: f64) -> Result<Vec<InstallmentOption>, String> {
+        if amount <= 0.0 {
+            return Err("Amount must be greater than zero".to_string());
+        }
+
+        let rates = self
+            .repo
+            .list_active_rates_by_method(method)
+            .await
+            .map_err(|e| format!("Failed to list installment rates: {}", e))?;
+
+        let options = rates
+            .into_iter()
+            .filter_map(|rate| {
+                let schedule = build_schedule(amount, rate.installments, rate.interest_rate);
+                let installment_amount = schedule.first().map(|(p, i)| p + i).unwrap_or(amount);
+                if let Some(min) = rate.min_installment_amount {
+                    if installment_amount + 0.001 < min {
+                        return None;
+                    }
+                }
+                let total_interest = round_cents(schedule.iter().map(|(_, i)| i).sum());
+                Some(InstallmentOption {
+                    installments: rate.installments,
+                    interest_rate: rate.interest_rate,
+                    installment_amount: round_cents(installment_amount),
+                    total_interest,
+                    total_amount: round_cents(amount + total_interest),
+                })
+            })
+            .collect();
+
+        Ok(options)
+    }
+
+    pub async fn list_installments(&self, payment_id: &str) -> Result<Vec<PaymentInstallment>, String> {
+        self.repo
+            .list_by_payment(payment_id)
+            .await
+            .map_err(|e| format!("Failed to list payment installments: {}", e))
+    }
+
+    // ============================================================
+    // Transaction-aware methods used by payments
+    // ============================================================
+
+    /// Create the installment schedule of a payment split in more than one installment.
+    /// The plan must be offered by the shop for the payment method.
+    pub async fn create_schedule_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        payment: &Payment,
+    ) -> Result<Vec<PaymentInstallment>, String> {
+        let installments = payment.installments.unwrap_or(1);
+        if installments <= 1 {
+            return Ok(Vec::new());
+        }
+
+        let rate = ShopInstallmentRepository::get_active_rate_in_tx(tx, &payment.method, installments)
+            .await
+            .map_err(|e| format!("Failed to fetch installment rate: {}", e))?
+            .ok_or_else(|| {
+                format!(
+                    "{} installments are not offered for {} payments",
+                    installments, payment.method
+                )
+            })?;
+
+        let schedule = build_schedule(payment.amount, installments, rate.interest_rate);
+        if let (Some(min), Some((principal, interest))) = (rate.min_installment_amount, schedule.first()) {
+            if principal + interest + 0.001 < min {
+                return Err(format!(
+                    "Installment amount is below the minimum of {} for {} installments",
+                    min, installments
+                ));
+            }
+        }
+
+        let start = payment.created_at.unwrap_or_else(Utc::now);
+        let mut created = Vec::with_capacity(schedule.len());
+        for (index, (principal, interest)) in schedule.into_iter().enumerate() {
+            let number = index as i64 + 1;
+            let installment = PaymentInstallment {
+                id: Uuid::new_v4().to_string(),
+                payment_id: payment.id.clone(),
+                number,
+                due_date: start
+                    .checked_add_months(Months::new(number as u32))
+                    .unwrap_or(start),
+                principal,
+                interest,
+                amount: round_cents(principal + interest),
+                status: "pending".to_string(),
+                paid_at: None,
+                sync_status: Some("created".to_string()),
+                created_at: Some(Utc::now()),
+                updated_at: Some(Utc::now()),
+            };
+
+            let row = ShopInstallmentRepository::create_installment_in_tx(tx, &installment)
+                .await
+                .map_err(|e| format!("Failed to create payment installment: {}", e))?;
+            created.push(row);
+        }
+
+        Ok(created)
+    }
+}
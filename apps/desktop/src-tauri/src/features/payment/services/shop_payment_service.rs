@@ -1,11 +1,16 @@
 //! Shop-scoped Payment Service for Multi-Database Architecture
 
-use crate::features::payment::models::payment_model::Payment;
+use crate::features::gift_card::services::shop_gift_card_service::ShopGiftCardService;
+use crate::features::payment::dtos::payment_dto::CreatePaymentDTO;
+use crate::features::payment::models::payment_model::{Payment, PaymentSummary};
+use crate::features::payment::repositories::payments_repository::PaymentsRepository;
 use crate::features::payment::repositories::shop_payment_repository::ShopPaymentRepository;
+use crate::features::payment::services::shop_installment_service::ShopInstallmentService;
 use crate::features::refund::models::refund_model::Refund;
-use crate::features::refund::repositories::shop_refund_repository::ShopRefundRepository;
+use crate::features::refund::repositories::refunds_repository::RefundsRepository;
+use crate::features::store_credit::services::shop_store_credit_service::ShopStoreCreditService;
 use chrono::Utc;
-use sqlx::SqlitePool;
+use sqlx::{Sqlite, SqlitePool, Transaction};
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -100,6 +105,183 @@ impl ShopPaymentService {
             .map_err(|e| format!("Failed to void payment: {}", e))
     }
 
+    /// Record one tender of a transaction. A sale can be paid with several
+    /// payments until its balance is covered.
+    /// - cash: an amount above the remaining balance is returned as change
+    /// - gift_card / store_credit: the ledger is debited in the same database transaction
+    /// - installments > 1: the schedule follows the shop's installment rate for the method
+    pub async fn create_payment(&self, payload: CreatePaymentDTO) -> Result<Payment, String> {
+        if payload.amount <= 0.0 {
+            return Err("Payment amount must be greater than zero".to_string());
+        }
+        if payload.installments.is_some_and(|n| n < 1) {
+            return Err("Installments must be at least 1".to_string());
+        }
+
+        let gift_card_code = payload.gift_card_code.clone();
+        let created_by = payload.created_by.clone();
+        let mut payment = payload.into_model();
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let sql = r#"
+            SELECT customer_id, total_net FROM transactions
+            WHERE id = $1 AND (_status IS NULL OR _status != 'deleted')
+        "#;
+        let (customer_id, total_net): (Option<String>, Option<f64>) = sqlx::query_as(sql)
+            .bind(&payment.transaction_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to fetch transaction: {}", e))?
+            .ok_or_else(|| format!("Transaction not found: {}", payment.transaction_id))?;
+
+        // Without a net total there is no balance to hold the payment to
+        let remaining = match total_net {
+            Some(total_net) => {
+                let paid =
+                    ShopPaymentRepository::get_paid_amount_in_tx(&mut tx, &payment.transaction_id)
+                        .await
+                        .map_err(|e| format!("Failed to get paid amount: {}", e))?;
+                let remaining = total_net - paid;
+                if remaining < 0.01 {
+                    return Err("Transaction is already fully paid".to_string());
+                }
+                Some(remaining)
+            }
+            None => None,
+        };
+
+        if payment.method == "cash" {
+            let tendered = payment.amount;
+            if let Some(remaining) = remaining.filter(|r| tendered > *r) {
+                payment.amount = (remaining * 100.0).round() / 100.0;
+            }
+            let change = ((tendered - payment.amount) * 100.0).round() / 100.0;
+            let mut details = payment
+                .payment_details
+                .as_deref()
+                .and_then(|d| serde_json::from_str::<serde_json::Value>(d).ok())
+                .filter(|d| d.is_object())
+                .unwrap_or_else(|| serde_json::json!({}));
+            details["tendered"] = serde_json::json!(tendered);
+            details["change"] = serde_json::json!(change);
+            payment.payment_details = Some(details.to_string());
+        } else if let Some(remaining) = remaining.filter(|r| payment.amount > r + 0.001) {
+            return Err(format!(
+                "Payment exceeds the remaining balance. Remaining: {:.2}, Requested: {:.2}",
+                remaining, payment.amount
+            ));
+        }
+
+        let captured_on_create = matches!(payment.method.as_str(), "cash" | "gift_card" | "store_credit");
+        if captured_on_create {
+            payment.status = "captured".to_string();
+            payment.captured_at = payment.created_at;
+        }
+        if payment.method == "gift_card" || payment.method == "store_credit" {
+            payment.provider = "internal".to_string();
+        }
+
+        let created = ShopPaymentRepository::create_in_tx(&mut tx, &payment)
+            .await
+            .map_err(|e| format!("Failed to create payment: {}", e))?;
+
+        ShopInstallmentService::create_schedule_in_tx(&mut tx, &created).await?;
+
+        match payment.method.as_str() {
+            "gift_card" => {
+                let code = gift_card_code
+                    .ok_or_else(|| "Gift card code is required for gift card payments".to_string())?;
+                ShopGiftCardService::redeem_in_tx(
+                    &mut tx,
+                    &code,
+                    payment.amount,
+                    &payment.id,
+                    &payment.transaction_id,
+                    created_by,
+                )
+                .await?;
+            }
+            "store_credit" => {
+                let customer_id = customer_id.ok_or_else(|| {
+                    "Store credit payments require a transaction with a customer".to_string()
+                })?;
+                ShopStoreCreditService::redeem_in_tx(
+                    &mut tx,
+                    &customer_id,
+                    payment.amount,
+                    &payment.id,
+                    &payment.transaction_id,
+                    created_by,
+                )
+                .await?;
+            }
+            _ => {}
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(created)
+    }
+
+    /// Paid and remaining amounts of a transaction, for split-tender checkouts
+    pub async fn get_payment_summary(&self, transaction_id: &str) -> Result<PaymentSummary, String> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let sql = r#"
+            SELECT total_net FROM transactions
+            WHERE id = $1 AND (_status IS NULL OR _status != 'deleted')
+        "#;
+        let (total_net,): (Option<f64>,) = sqlx::query_as(sql)
+            .bind(transaction_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to fetch transaction: {}", e))?
+            .ok_or_else(|| format!("Transaction not found: {}", transaction_id))?;
+
+        let total_paid = ShopPaymentRepository::get_paid_amount_in_tx(&mut tx, transaction_id)
+            .await
+            .map_err(|e| format!("Failed to get paid amount: {}", e))?;
+        let payments = ShopPaymentRepository::list_by_transaction_in_tx(&mut tx, transaction_id)
+            .await
+            .map_err(|e| format!("Failed to list payments: {}", e))?;
+
+        let change_given = payments
+            .iter()
+            .filter(|p| p.method == "cash")
+            .filter_map(|p| p.payment_details.as_deref())
+            .filter_map(|d| serde_json::from_str::<serde_json::Value>(d).ok())
+            .filter_map(|d| d["change"].as_f64())
+            .sum();
+
+        // A transaction without a net total is never reported as fully paid
+        let total_due = total_net.unwrap_or(total_paid);
+        let remaining_balance = ((total_due - total_paid).max(0.0) * 100.0).round() / 100.0;
+
+        Ok(PaymentSummary {
+            transaction_id: transaction_id.to_string(),
+            total_due,
+            total_paid,
+            remaining_balance,
+            change_given,
+            is_fully_paid: total_net.is_some() && remaining_balance < 0.01,
+            payments,
+        })
+    }
+
+    /// Refund a captured payment.
+    /// Refunds of gift card and store credit payments are credited back to
+    /// the balance they were paid from.
     pub async fn process_refund(
         &self,
         payment_id: &str,
@@ -111,13 +293,102 @@ impl ShopPaymentService {
             return Err("Refund amount must be greater than zero".to_string());
         }
 
-        let payment = self
-            .repo
-            .get_by_id(payment_id)
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let payment = PaymentsRepository::get_by_id_with_tx(&mut tx, payment_id)
             .await
             .map_err(|e| format!("Failed to get payment: {}", e))?
             .ok_or_else(|| format!("Payment not found: {}", payment_id))?;
 
+        let created_refund = Self::refund_in_tx(
+            &mut tx,
+            &payment,
+            amount,
+            reason.map(|s| s.to_string()),
+            created_by,
+        )
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(created_refund)
+    }
+
+    /// Refund what is left of the gift card and store credit payments of a
+    /// transaction, e.g. when the sale is cancelled
+    pub async fn reverse_stored_value_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        transaction_id: &str,
+        reason: &str,
+        created_by: Option<&str>,
+    ) -> Result<Vec<Refund>, String> {
+        let payments = ShopPaymentRepository::list_by_transaction_in_tx(tx, transaction_id)
+            .await
+            .map_err(|e| format!("Failed to list payments: {}", e))?;
+
+        let mut refunds = Vec::new();
+        for payment in payments {
+            let stored_value = payment.method == "gift_card" || payment.method == "store_credit";
+            let refundable = payment.status == "captured" || payment.status == "partially_refunded";
+            if !stored_value || !refundable {
+                continue;
+            }
+
+            let refunded = PaymentsRepository::get_refunded_amount_with_tx(tx, &payment.id)
+                .await
+                .map_err(|e| format!("Failed to get refunded amount: {}", e))?;
+            let amount = payment.amount - refunded;
+            if amount >= 0.01 {
+                let refund =
+                    Self::refund_in_tx(tx, &payment, amount, Some(reason.to_string()), created_by)
+                        .await?;
+                refunds.push(refund);
+            }
+        }
+        Ok(refunds)
+    }
+
+    /// What is left to refund of the payments of a transaction other than gift
+    /// card and store credit, which go back through their provider
+    pub async fn unrefunded_external_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        transaction_id: &str,
+    ) -> Result<f64, String> {
+        let payments = ShopPaymentRepository::list_by_transaction_in_tx(tx, transaction_id)
+            .await
+            .map_err(|e| format!("Failed to list payments: {}", e))?;
+
+        let mut unrefunded = 0.0;
+        for payment in payments {
+            let stored_value = payment.method == "gift_card" || payment.method == "store_credit";
+            let refundable = payment.status == "captured" || payment.status == "partially_refunded";
+            if stored_value || !refundable {
+                continue;
+            }
+
+            let refunded = PaymentsRepository::get_refunded_amount_with_tx(tx, &payment.id)
+                .await
+                .map_err(|e| format!("Failed to get refunded amount: {}", e))?;
+            unrefunded += payment.amount - refunded;
+        }
+        Ok(unrefunded)
+    }
+
+    /// Refund a captured payment within `tx`, crediting gift card and store
+    /// credit payments back to their balance
+    pub async fn refund_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        payment: &Payment,
+        amount: f64,
+        reason: Option<String>,
+        created_by: Option<&str>,
+    ) -> Result<Refund, String> {
         if payment.status != "captured" && payment.status != "partially_refunded" {
             return Err(format!(
                 "Payment with status '{}' cannot be refunded",
@@ -125,9 +396,7 @@ impl ShopPaymentService {
             ));
         }
 
-        let already_refunded = self
-            .repo
-            .get_refunded_amount(payment_id)
+        let already_refunded = PaymentsRepository::get_refunded_amount_with_tx(tx, &payment.id)
             .await
             .map_err(|e| format!("Failed to get refunded amount: {}", e))?;
 
@@ -144,10 +413,10 @@ impl ShopPaymentService {
         let now = Some(Utc::now());
         let refund = Refund {
             id: Uuid::new_v4().to_string(),
-            payment_id: payment_id.to_string(),
+            payment_id: payment.id.clone(),
             amount,
             status: "completed".to_string(),
-            reason: reason.map(|s| s.to_string()),
+            reason,
             provider_refund_id: None,
             sync_status: Some("created".to_string()),
             created_at: now,
@@ -155,12 +424,32 @@ impl ShopPaymentService {
             created_by: created_by.map(|s| s.to_string()),
         };
 
-        let refund_repo = ShopRefundRepository::new(self.pool.clone());
-        let created_refund = refund_repo
-            .create(&refund)
+        let created_refund = RefundsRepository::create_with_tx(tx, refund)
             .await
             .map_err(|e| format!("Failed to create refund: {}", e))?;
 
+        match payment.method.as_str() {
+            "gift_card" => {
+                ShopGiftCardService::restore_in_tx(
+                    tx,
+                    &payment.id,
+                    amount,
+                    created_by.map(|s| s.to_string()),
+                )
+                .await?;
+            }
+            "store_credit" => {
+                ShopStoreCreditService::restore_in_tx(
+                    tx,
+                    &payment.id,
+                    amount,
+                    created_by.map(|s| s.to_string()),
+                )
+                .await?;
+            }
+            _ => {}
+        }
+
         // Update payment status
         let total_refunded = already_refunded + amount;
         let new_status = if (payment.amount - total_refunded).abs() < 0.01 {
@@ -169,8 +458,7 @@ impl ShopPaymentService {
             "partially_refunded"
         };
 
-        self.repo
-            .update_status(payment_id, new_status)
+        PaymentsRepository::update_status_with_tx(tx, &payment.id, new_status)
             .await
             .map_err(|e| format!("Failed to update payment status: {}", e))?;
 
@@ -0,0 +1,117 @@
+use crate::db::RepositoryFactory;
+use crate::features::price_history::dtos::price_history_dto::{
+    CreatePriceScheduleDTO, PriceScheduleFilterDTO,
+};
+use crate::features::price_history::models::price_history_model::{
+    PriceHistory, PriceOnDate, PriceSchedule, PriceScheduleRun, PromoDisclosure,
+};
+use crate::features::price_history::services::shop_price_history_service::ShopPriceHistoryService;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn list_price_history(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    product_id: String,
+) -> Result<Vec<PriceHistory>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPriceHistoryService::new(pool, shop_id);
+    service.list_history(&product_id).await
+}
+
+#[tauri::command]
+pub async fn get_price_on_date(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    product_id: String,
+    at: DateTime<Utc>,
+) -> Result<PriceOnDate, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPriceHistoryService::new(pool, shop_id);
+    service.get_price_on_date(&product_id, at).await
+}
+
+#[tauri::command]
+pub async fn get_promo_disclosure(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    product_id: String,
+    window_days: Option<i64>,
+) -> Result<PromoDisclosure, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPriceHistoryService::new(pool, shop_id);
+    service.get_promo_disclosure(&product_id, window_days).await
+}
+
+#[tauri::command]
+pub async fn create_price_schedule(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: CreatePriceScheduleDTO,
+) -> Result<PriceSchedule, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPriceHistoryService::new(pool, shop_id);
+    service.create_schedule(payload).await
+}
+
+#[tauri::command]
+pub async fn list_price_schedules(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    filters: PriceScheduleFilterDTO,
+) -> Result<Vec<PriceSchedule>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPriceHistoryService::new(pool, shop_id);
+    service.list_schedules(filters).await
+}
+
+#[tauri::command]
+pub async fn cancel_price_schedule(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+) -> Result<PriceSchedule, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPriceHistoryService::new(pool, shop_id);
+    service.cancel_schedule(&id).await
+}
+
+#[tauri::command]
+pub async fn apply_due_price_schedules(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+) -> Result<PriceScheduleRun, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPriceHistoryService::new(pool, shop_id);
+    service.apply_due_schedules().await
+}
@@ -0,0 +1,44 @@
+use crate::features::price_history::models::price_history_model::PriceSchedule;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatePriceScheduleDTO {
+    pub product_id: String,
+    pub kind: String, // 'price_change', 'promotion'
+    pub price: Option<f64>,
+    pub promotional_price: Option<f64>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub created_by: Option<String>,
+}
+
+impl CreatePriceScheduleDTO {
+    pub fn into_model(self) -> PriceSchedule {
+        let now = Utc::now();
+        PriceSchedule {
+            id: Uuid::new_v4().to_string(),
+            product_id: self.product_id,
+            kind: self.kind,
+            price: self.price,
+            promotional_price: self.promotional_price,
+            starts_at: self.starts_at,
+            ends_at: self.ends_at,
+            status: "scheduled".to_string(),
+            previous_promotional_price: None,
+            created_by: self.created_by,
+            applied_at: None,
+            reverted_at: None,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PriceScheduleFilterDTO {
+    pub product_id: Option<String>,
+    pub status: Option<String>,
+}
@@ -0,0 +1 @@
+pub mod price_history_model;
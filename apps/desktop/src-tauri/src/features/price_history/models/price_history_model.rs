@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PriceHistory {
+    pub id: String,
+    pub product_id: String,
+    pub price: f64,
+    pub promotional_price: Option<f64>,
+    pub effective_from: DateTime<Utc>,
+    pub effective_to: Option<DateTime<Utc>>, // NULL while current
+    pub source: String, // 'baseline', 'manual', 'bulk', 'schedule', 'promotion'
+    pub schedule_id: Option<String>,
+    pub changed_by: Option<String>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl PriceHistory {
+    /// Price a customer actually paid while this state was in effect
+    pub fn selling_price(&self) -> f64 {
+        match self.promotional_price {
+            Some(promotional_price)
+                if promotional_price > 0.0 && promotional_price < self.price =>
+            {
+                promotional_price
+            }
+            _ => self.price,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PriceSchedule {
+    pub id: String,
+    pub product_id: String,
+    pub kind: String, // 'price_change', 'promotion'
+    pub price: Option<f64>,
+    pub promotional_price: Option<f64>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub status: String, // 'scheduled', 'active', 'completed', 'cancelled'
+    pub previous_promotional_price: Option<f64>,
+    pub created_by: Option<String>,
+    pub applied_at: Option<DateTime<Utc>>,
+    pub reverted_at: Option<DateTime<Utc>>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Current regular and promotional price of a product
+#[derive(Debug, FromRow)]
+pub struct ProductPrices {
+    pub price: f64,
+    pub promotional_price: Option<f64>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Prices of a product at a point in time
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PriceOnDate {
+    pub product_id: String,
+    pub at: DateTime<Utc>,
+    pub price: f64,
+    pub promotional_price: Option<f64>,
+    pub selling_price: f64,
+    pub history_id: Option<String>, // None when no recorded state covers the date
+}
+
+/// Evidence for a promotion disclosure: the regular price before the
+/// promotion and the lowest price charged in the preceding window
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromoDisclosure {
+    pub product_id: String,
+    pub price: f64,
+    pub promotional_price: Option<f64>,
+    pub promotion_started_at: Option<DateTime<Utc>>,
+    pub previous_price: Option<f64>,
+    pub lowest_price: Option<f64>,
+    pub window_days: i64,
+    pub evidence: Vec<PriceHistory>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PriceScheduleRun {
+    pub applied: u32,
+    pub reverted: u32,
+    pub failed: u32,
+}
@@ -0,0 +1 @@
+pub mod shop_price_history_repository;
@@ -0,0 +1,298 @@
+//! Shop-scoped Price History Repository for Multi-Database Architecture
+
+use crate::features::price_history::models::price_history_model::{
+    PriceHistory, PriceSchedule, ProductPrices,
+};
+use chrono::{DateTime, Utc};
+use sqlx::{QueryBuilder, Result, Sqlite, SqlitePool, Transaction};
+use std::sync::Arc;
+
+const ON_DATE_SQL: &str = r#"
+    SELECT * FROM price_history
+    WHERE product_id = $1
+      AND (_status IS NULL OR _status != 'deleted')
+      AND effective_from <= $2
+      AND (effective_to IS NULL OR effective_to > $2)
+    ORDER BY effective_from DESC
+    LIMIT 1
+"#;
+
+pub struct ShopPriceHistoryRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopPriceHistoryRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    // ============================================================
+    // History
+    // ============================================================
+
+    pub async fn get_product_prices_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        product_id: &str,
+    ) -> Result<Option<ProductPrices>> {
+        let sql = "SELECT price, promotional_price, created_at FROM products WHERE id = $1";
+        sqlx::query_as::<_, ProductPrices>(sql)
+            .bind(product_id)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    pub async fn set_product_prices_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        product_id: &str,
+        price: f64,
+        promotional_price: Option<f64>,
+    ) -> Result<()> {
+        let sql = r#"
+            UPDATE products SET
+                price = $2,
+                promotional_price = $3,
+                _status = 'modified',
+                updated_at = datetime('now')
+            WHERE id = $1
+        "#;
+        sqlx::query(sql)
+            .bind(product_id)
+            .bind(price)
+            .bind(promotional_price)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    /// The open (current) history row of a product
+    pub async fn get_current_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        product_id: &str,
+    ) -> Result<Option<PriceHistory>> {
+        let sql = r#"
+            SELECT * FROM price_history
+            WHERE product_id = $1 AND effective_to IS NULL
+              AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY effective_from DESC
+            LIMIT 1
+        "#;
+        sqlx::query_as::<_, PriceHistory>(sql)
+            .bind(product_id)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    pub async fn close_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+        effective_to: DateTime<Utc>,
+    ) -> Result<()> {
+        let sql = r#"
+            UPDATE price_history SET
+                effective_to = $2,
+                _status = 'modified',
+                updated_at = datetime('now')
+            WHERE id = $1
+        "#;
+        sqlx::query(sql)
+            .bind(id)
+            .bind(effective_to)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn create_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        entry: &PriceHistory,
+    ) -> Result<PriceHistory> {
+        let sql = r#"
+            INSERT INTO price_history (
+                id, product_id, price, promotional_price, effective_from, effective_to,
+                source, schedule_id, changed_by, _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, PriceHistory>(sql)
+            .bind(&entry.id)
+            .bind(&entry.product_id)
+            .bind(entry.price)
+            .bind(entry.promotional_price)
+            .bind(entry.effective_from)
+            .bind(entry.effective_to)
+            .bind(&entry.source)
+            .bind(&entry.schedule_id)
+            .bind(&entry.changed_by)
+            .bind(&entry.sync_status)
+            .bind(entry.created_at)
+            .bind(entry.updated_at)
+            .fetch_one(&mut **tx)
+            .await
+    }
+
+    pub async fn list_by_product(&self, product_id: &str) -> Result<Vec<PriceHistory>> {
+        let sql = r#"
+            SELECT * FROM price_history
+            WHERE product_id = $1 AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY effective_from DESC
+        "#;
+        sqlx::query_as::<_, PriceHistory>(sql)
+            .bind(product_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// The history row in effect at `at`
+    pub async fn get_on_date(
+        &self,
+        product_id: &str,
+        at: DateTime<Utc>,
+    ) -> Result<Option<PriceHistory>> {
+        sqlx::query_as::<_, PriceHistory>(ON_DATE_SQL)
+            .bind(product_id)
+            .bind(at)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    /// History rows in effect at any time between `from` and `to`
+    pub async fn list_between(
+        &self,
+        product_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<PriceHistory>> {
+        let sql = r#"
+            SELECT * FROM price_history
+            WHERE product_id = $1
+              AND (_status IS NULL OR _status != 'deleted')
+              AND effective_from < $3
+              AND (effective_to IS NULL OR effective_to > $2)
+            ORDER BY effective_from ASC
+        "#;
+        sqlx::query_as::<_, PriceHistory>(sql)
+            .bind(product_id)
+            .bind(from)
+            .bind(to)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    // ============================================================
+    // Schedules
+    // ============================================================
+
+    pub async fn create_schedule(&self, schedule: &PriceSchedule) -> Result<PriceSchedule> {
+        let sql = r#"
+            INSERT INTO price_schedules (
+                id, product_id, kind, price, promotional_price, starts_at, ends_at, status,
+                previous_promotional_price, created_by, applied_at, reverted_at,
+                _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, PriceSchedule>(sql)
+            .bind(&schedule.id)
+            .bind(&schedule.product_id)
+            .bind(&schedule.kind)
+            .bind(schedule.price)
+            .bind(schedule.promotional_price)
+            .bind(schedule.starts_at)
+            .bind(schedule.ends_at)
+            .bind(&schedule.status)
+            .bind(schedule.previous_promotional_price)
+            .bind(&schedule.created_by)
+            .bind(schedule.applied_at)
+            .bind(schedule.reverted_at)
+            .bind(&schedule.sync_status)
+            .bind(schedule.created_at)
+            .bind(schedule.updated_at)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn update_schedule_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        schedule: &PriceSchedule,
+    ) -> Result<PriceSchedule> {
+        let sql = r#"
+            UPDATE price_schedules SET
+                status = $2,
+                previous_promotional_price = $3,
+                applied_at = $4,
+                reverted_at = $5,
+                _status = 'modified',
+                updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, PriceSchedule>(sql)
+            .bind(&schedule.id)
+            .bind(&schedule.status)
+            .bind(schedule.previous_promotional_price)
+            .bind(schedule.applied_at)
+            .bind(schedule.reverted_at)
+            .fetch_one(&mut **tx)
+            .await
+    }
+
+    pub async fn get_schedule(&self, id: &str) -> Result<Option<PriceSchedule>> {
+        let sql = "SELECT * FROM price_schedules WHERE id = $1 AND (_status IS NULL OR _status != 'deleted')";
+        sqlx::query_as::<_, PriceSchedule>(sql)
+            .bind(id)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn list_schedules(
+        &self,
+        product_id: Option<&str>,
+        status: Option<&str>,
+    ) -> Result<Vec<PriceSchedule>> {
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT * FROM price_schedules WHERE (_status IS NULL OR _status != 'deleted')",
+        );
+        if let Some(product_id) = product_id {
+            builder.push(" AND product_id = ");
+            builder.push_bind(product_id);
+        }
+        if let Some(status) = status {
+            builder.push(" AND status = ");
+            builder.push_bind(status);
+        }
+        builder.push(" ORDER BY starts_at ASC");
+
+        builder
+            .build_query_as::<PriceSchedule>()
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// Scheduled entries whose start time has come
+    pub async fn list_due_to_start(&self, now: DateTime<Utc>) -> Result<Vec<PriceSchedule>> {
+        let sql = r#"
+            SELECT * FROM price_schedules
+            WHERE status = 'scheduled' AND starts_at <= $1
+              AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY starts_at ASC
+        "#;
+        sqlx::query_as::<_, PriceSchedule>(sql)
+            .bind(now)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// Running promotions whose window has closed
+    pub async fn list_due_to_end(&self, now: DateTime<Utc>) -> Result<Vec<PriceSchedule>> {
+        let sql = r#"
+            SELECT * FROM price_schedules
+            WHERE status = 'active' AND ends_at IS NOT NULL AND ends_at <= $1
+              AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY ends_at ASC
+        "#;
+        sqlx::query_as::<_, PriceSchedule>(sql)
+            .bind(now)
+            .fetch_all(&*self.pool)
+            .await
+    }
+}
@@ -0,0 +1,2 @@
+pub mod price_scheduler;
+pub mod shop_price_history_service;
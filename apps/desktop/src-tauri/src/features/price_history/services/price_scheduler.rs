@@ -0,0 +1,28 @@
+//! Background job applying scheduled price changes and promotional windows
+//!
+//! Started from the app setup; every minute it runs
+//! `ShopPriceHistoryService::apply_due_schedules` for each SQLite shop whose
+//! database is open (see `run_for_open_shops`).
+
+use crate::db::RepositoryFactory;
+use crate::features::price_history::services::shop_price_history_service::ShopPriceHistoryService;
+use crate::features::shop::services::shop_scheduler::run_for_open_shops;
+use std::sync::Arc;
+use std::time::Duration;
+
+const PRICE_SCHEDULER_INTERVAL: Duration = Duration::from_secs(60);
+
+pub async fn run_price_scheduler(repo_factory: Arc<RepositoryFactory>) {
+    run_for_open_shops(
+        repo_factory,
+        PRICE_SCHEDULER_INTERVAL,
+        "price_scheduler",
+        |shop_id, pool| async move {
+            ShopPriceHistoryService::new(pool, shop_id)
+                .apply_due_schedules()
+                .await
+                .map(|_| ())
+        },
+    )
+    .await
+}
@@ -0,0 +1,528 @@
+//! Shop-scoped Price History Service for Multi-Database Architecture
+//!
+//! Every change of a product's regular or promotional price closes the
+//! current price_history row and opens a new one, so the price in effect at
+//! any date can be looked up. Products priced before history existed get a
+//! 'baseline' row (effective from their creation) on their first change.
+//!
+//! Scheduled price changes and promotional windows live in price_schedules
+//! and are applied and reverted by `apply_due_schedules`, which the price
+//! scheduler runs periodically for every shop.
+
+use crate::db::DbTransaction;
+use crate::features::price_history::dtos::price_history_dto::{
+    CreatePriceScheduleDTO, PriceScheduleFilterDTO,
+};
+use crate::features::price_history::models::price_history_model::{
+    PriceHistory, PriceOnDate, PriceSchedule, PriceScheduleRun, PromoDisclosure,
+};
+use crate::features::price_history::repositories::shop_price_history_repository::ShopPriceHistoryRepository;
+use crate::features::product::repositories::shop_product_repository::ShopProductRepository;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const PRICE_SCHEDULE_KINDS: &[&str] = &["price_change", "promotion"];
+
+/// Default look-back window of a promotion disclosure
+pub const DISCLOSURE_WINDOW_DAYS: i64 = 30;
+
+fn same_price(a: Option<f64>, b: Option<f64>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => (a - b).abs() < 0.005,
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+pub struct ShopPriceHistoryService {
+    pool: Arc<SqlitePool>,
+    shop_id: String,
+    repo: ShopPriceHistoryRepository,
+}
+
+impl ShopPriceHistoryService {
+    pub fn new(pool: Arc<SqlitePool>, shop_id: String) -> Self {
+        let repo = ShopPriceHistoryRepository::new(pool.clone());
+        Self {
+            pool,
+            shop_id,
+            repo,
+        }
+    }
+
+    pub fn pool(&self) -> Arc<SqlitePool> {
+        self.pool.clone()
+    }
+
+    // ============================================================
+    // Recording (run inside the caller's transaction)
+    // ============================================================
+
+    /// Make sure the product has an open history row holding its current
+    /// prices. Call before changing the prices of a product.
+    pub async fn ensure_open_in_tx(
+        tx: &mut DbTransaction<'_>,
+        product_id: &str,
+    ) -> Result<(), String> {
+        let current = ShopPriceHistoryRepository::get_current_in_tx(tx, product_id)
+            .await
+            .map_err(|e| format!("Failed to fetch price history: {}", e))?;
+        if current.is_some() {
+            return Ok(());
+        }
+
+        let prices = ShopPriceHistoryRepository::get_product_prices_in_tx(tx, product_id)
+            .await
+            .map_err(|e| format!("Failed to fetch product prices: {}", e))?
+            .ok_or_else(|| format!("Product not found: {}", product_id))?;
+
+        let now = Utc::now();
+        let baseline = PriceHistory {
+            id: Uuid::new_v4().to_string(),
+            product_id: product_id.to_string(),
+            price: prices.price,
+            promotional_price: prices.promotional_price,
+            effective_from: prices.created_at.unwrap_or(now),
+            effective_to: None,
+            source: "baseline".to_string(),
+            schedule_id: None,
+            changed_by: None,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+        ShopPriceHistoryRepository::create_in_tx(tx, &baseline)
+            .await
+            .map_err(|e| format!("Failed to record price history: {}", e))?;
+        Ok(())
+    }
+
+    /// Record the product's prices if they differ from the open history row.
+    /// Call after changing the prices of a product.
+    pub async fn record_in_tx(
+        tx: &mut DbTransaction<'_>,
+        product_id: &str,
+        source: &str,
+        schedule_id: Option<&str>,
+        changed_by: Option<&str>,
+    ) -> Result<Option<PriceHistory>, String> {
+        let prices = ShopPriceHistoryRepository::get_product_prices_in_tx(tx, product_id)
+            .await
+            .map_err(|e| format!("Failed to fetch product prices: {}", e))?
+            .ok_or_else(|| format!("Product not found: {}", product_id))?;
+        let current = ShopPriceHistoryRepository::get_current_in_tx(tx, product_id)
+            .await
+            .map_err(|e| format!("Failed to fetch price history: {}", e))?;
+
+        let now = Utc::now();
+        if let Some(current) = current {
+            if same_price(Some(current.price), Some(prices.price))
+                && same_price(current.promotional_price, prices.promotional_price)
+            {
+                return Ok(None);
+            }
+            ShopPriceHistoryRepository::close_in_tx(tx, &current.id, now)
+                .await
+                .map_err(|e| format!("Failed to close price history: {}", e))?;
+        }
+
+        let entry = PriceHistory {
+            id: Uuid::new_v4().to_string(),
+            product_id: product_id.to_string(),
+            price: prices.price,
+            promotional_price: prices.promotional_price,
+            effective_from: now,
+            effective_to: None,
+            source: source.to_string(),
+            schedule_id: schedule_id.map(str::to_string),
+            changed_by: changed_by.map(str::to_string),
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+        let created = ShopPriceHistoryRepository::create_in_tx(tx, &entry)
+            .await
+            .map_err(|e| format!("Failed to record price history: {}", e))?;
+        Ok(Some(created))
+    }
+
+    /// Set the prices of a product and record the change
+    pub async fn set_prices_in_tx(
+        tx: &mut DbTransaction<'_>,
+        product_id: &str,
+        price: f64,
+        promotional_price: Option<f64>,
+        source: &str,
+        schedule_id: Option<&str>,
+        changed_by: Option<&str>,
+    ) -> Result<(), String> {
+        Self::ensure_open_in_tx(tx, product_id).await?;
+        ShopPriceHistoryRepository::set_product_prices_in_tx(
+            tx,
+            product_id,
+            price,
+            promotional_price,
+        )
+        .await
+        .map_err(|e| format!("Failed to update product prices: {}", e))?;
+        Self::record_in_tx(tx, product_id, source, schedule_id, changed_by).await?;
+        Ok(())
+    }
+
+    // ============================================================
+    // Queries
+    // ============================================================
+
+    pub async fn list_history(&self, product_id: &str) -> Result<Vec<PriceHistory>, String> {
+        self.repo
+            .list_by_product(product_id)
+            .await
+            .map_err(|e| format!("Failed to list price history: {}", e))
+    }
+
+    /// Prices in effect at `at`. Dates before any recorded change fall back
+    /// to the product's current prices.
+    pub async fn get_price_on_date(
+        &self,
+        product_id: &str,
+        at: DateTime<Utc>,
+    ) -> Result<PriceOnDate, String> {
+        let entry = self
+            .repo
+            .get_on_date(product_id, at)
+            .await
+            .map_err(|e| format!("Failed to fetch price history: {}", e))?;
+
+        if let Some(entry) = entry {
+            return Ok(PriceOnDate {
+                product_id: product_id.to_string(),
+                at,
+                price: entry.price,
+                promotional_price: entry.promotional_price,
+                selling_price: entry.selling_price(),
+                history_id: Some(entry.id),
+            });
+        }
+
+        let product = ShopProductRepository::new(self.pool.clone(), self.shop_id.clone())
+            .get_by_id(product_id)
+            .await
+            .map_err(|e| format!("Failed to fetch product: {}", e))?
+            .ok_or_else(|| format!("Product not found: {}", product_id))?;
+        let selling_price = match product.promotional_price {
+            Some(promotional_price)
+                if promotional_price > 0.0 && promotional_price < product.price =>
+            {
+                promotional_price
+            }
+            _ => product.price,
+        };
+
+        Ok(PriceOnDate {
+            product_id: product_id.to_string(),
+            at,
+            price: product.price,
+            promotional_price: product.promotional_price,
+            selling_price,
+            history_id: None,
+        })
+    }
+
+    /// Evidence for advertising a promotion: when the running promotion
+    /// started, the price charged right before it and the lowest price
+    /// charged in the `window_days` before it, with the history rows used
+    pub async fn get_promo_disclosure(
+        &self,
+        product_id: &str,
+        window_days: Option<i64>,
+    ) -> Result<PromoDisclosure, String> {
+        let window_days = window_days.unwrap_or(DISCLOSURE_WINDOW_DAYS).max(1);
+        let current = self.get_price_on_date(product_id, Utc::now()).await?;
+        let history = self.list_history(product_id).await?;
+
+        // Newest first: the leading rows with a promotional price are the running promotion
+        let on_promotion = history
+            .iter()
+            .take_while(|h| h.selling_price() < h.price)
+            .count();
+        let promotion_started_at = if current.selling_price < current.price && on_promotion > 0 {
+            Some(history[on_promotion - 1].effective_from)
+        } else {
+            None
+        };
+        let previous_price = promotion_started_at
+            .and_then(|_| history.get(on_promotion))
+            .map(|h| h.selling_price());
+
+        let window_end = promotion_started_at.unwrap_or_else(Utc::now);
+        let evidence = self
+            .repo
+            .list_between(
+                product_id,
+                window_end - Duration::days(window_days),
+                window_end,
+            )
+            .await
+            .map_err(|e| format!("Failed to list price history: {}", e))?;
+        let lowest_price = evidence
+            .iter()
+            .map(|h| h.selling_price())
+            .fold(None, |lowest: Option<f64>, p| {
+                Some(lowest.map_or(p, |l| l.min(p)))
+            });
+
+        Ok(PromoDisclosure {
+            product_id: product_id.to_string(),
+            price: current.price,
+            promotional_price: current.promotional_price,
+            promotion_started_at,
+            previous_price,
+            lowest_price,
+            window_days,
+            evidence,
+        })
+    }
+
+    // ============================================================
+    // Schedules
+    // ============================================================
+
+    pub async fn create_schedule(
+        &self,
+        payload: CreatePriceScheduleDTO,
+    ) -> Result<PriceSchedule, String> {
+        let schedule = payload.into_model();
+
+        if !PRICE_SCHEDULE_KINDS.contains(&schedule.kind.as_str()) {
+            return Err(format!("Invalid price schedule kind: {}", schedule.kind));
+        }
+        if [schedule.price, schedule.promotional_price]
+            .into_iter()
+            .flatten()
+            .any(|p| p < 0.0)
+        {
+            return Err("Prices cannot be negative".to_string());
+        }
+        match schedule.kind.as_str() {
+            "price_change" => {
+                if schedule.price.is_none() && schedule.promotional_price.is_none() {
+                    return Err("A price change needs a price or a promotional price".to_string());
+                }
+            }
+            _ => {
+                if schedule.promotional_price.is_none() {
+                    return Err("A promotion needs a promotional price".to_string());
+                }
+                match schedule.ends_at {
+                    Some(ends_at) if ends_at > schedule.starts_at => {}
+                    _ => return Err("A promotion needs an end after its start".to_string()),
+                }
+            }
+        }
+
+        ShopProductRepository::new(self.pool.clone(), self.shop_id.clone())
+            .get_by_id(&schedule.product_id)
+            .await
+            .map_err(|e| format!("Failed to fetch product: {}", e))?
+            .ok_or_else(|| format!("Product not found: {}", schedule.product_id))?;
+
+        if schedule.kind == "promotion" {
+            let existing = self
+                .repo
+                .list_schedules(Some(&schedule.product_id), None)
+                .await
+                .map_err(|e| format!("Failed to list price schedules: {}", e))?;
+            let overlaps = existing.iter().any(|other| {
+                other.kind == "promotion"
+                    && (other.status == "scheduled" || other.status == "active")
+                    && other.starts_at < schedule.ends_at.unwrap_or(schedule.starts_at)
+                    && other
+                        .ends_at
+                        .is_none_or(|ends_at| ends_at > schedule.starts_at)
+            });
+            if overlaps {
+                return Err("Another promotion overlaps this window".to_string());
+            }
+        }
+
+        self.repo
+            .create_schedule(&schedule)
+            .await
+            .map_err(|e| format!("Failed to create price schedule: {}", e))
+    }
+
+    pub async fn list_schedules(
+        &self,
+        filters: PriceScheduleFilterDTO,
+    ) -> Result<Vec<PriceSchedule>, String> {
+        self.repo
+            .list_schedules(filters.product_id.as_deref(), filters.status.as_deref())
+            .await
+            .map_err(|e| format!("Failed to list price schedules: {}", e))
+    }
+
+    /// Cancel a pending schedule, or end a running promotion early
+    pub async fn cancel_schedule(&self, id: &str) -> Result<PriceSchedule, String> {
+        let mut schedule = self
+            .repo
+            .get_schedule(id)
+            .await
+            .map_err(|e| format!("Failed to fetch price schedule: {}", e))?
+            .ok_or_else(|| format!("Price schedule not found: {}", id))?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        match schedule.status.as_str() {
+            "scheduled" => {}
+            "active" => Self::revert_promotion_in_tx(&mut tx, &mut schedule).await?,
+            other => {
+                return Err(format!(
+                    "Price schedule with status '{}' cannot be cancelled",
+                    other
+                ))
+            }
+        }
+        schedule.status = "cancelled".to_string();
+
+        let updated = ShopPriceHistoryRepository::update_schedule_in_tx(&mut tx, &schedule)
+            .await
+            .map_err(|e| format!("Failed to update price schedule: {}", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(updated)
+    }
+
+    /// Apply schedules whose start has come and revert promotions whose
+    /// window has closed. Each schedule runs in its own transaction so one
+    /// failure does not hold back the others.
+    pub async fn apply_due_schedules(&self) -> Result<PriceScheduleRun, String> {
+        let now = Utc::now();
+        let mut run = PriceScheduleRun::default();
+
+        let due = self
+            .repo
+            .list_due_to_start(now)
+            .await
+            .map_err(|e| format!("Failed to list due price schedules: {}", e))?;
+        for schedule in due {
+            match self.apply_schedule(schedule).await {
+                Ok(()) => run.applied += 1,
+                Err(e) => {
+                    eprintln!("[price_scheduler] shop {}: {}", self.shop_id, e);
+                    run.failed += 1;
+                }
+            }
+        }
+
+        let ended = self
+            .repo
+            .list_due_to_end(now)
+            .await
+            .map_err(|e| format!("Failed to list ended promotions: {}", e))?;
+        for mut schedule in ended {
+            let result = async {
+                let mut tx = self
+                    .pool
+                    .begin()
+                    .await
+                    .map_err(|e| format!("Failed to start transaction: {}", e))?;
+                Self::revert_promotion_in_tx(&mut tx, &mut schedule).await?;
+                schedule.status = "completed".to_string();
+                ShopPriceHistoryRepository::update_schedule_in_tx(&mut tx, &schedule)
+                    .await
+                    .map_err(|e| format!("Failed to update price schedule: {}", e))?;
+                tx.commit()
+                    .await
+                    .map_err(|e| format!("Failed to commit transaction: {}", e))
+            }
+            .await;
+
+            match result {
+                Ok(()) => run.reverted += 1,
+                Err(e) => {
+                    eprintln!("[price_scheduler] shop {}: {}", self.shop_id, e);
+                    run.failed += 1;
+                }
+            }
+        }
+
+        Ok(run)
+    }
+
+    async fn apply_schedule(&self, mut schedule: PriceSchedule) -> Result<(), String> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let prices =
+            ShopPriceHistoryRepository::get_product_prices_in_tx(&mut tx, &schedule.product_id)
+                .await
+                .map_err(|e| format!("Failed to fetch product prices: {}", e))?
+                .ok_or_else(|| format!("Product not found: {}", schedule.product_id))?;
+
+        let source = if schedule.kind == "promotion" {
+            schedule.previous_promotional_price = prices.promotional_price;
+            schedule.status = "active".to_string();
+            "promotion"
+        } else {
+            schedule.status = "completed".to_string();
+            "schedule"
+        };
+
+        Self::set_prices_in_tx(
+            &mut tx,
+            &schedule.product_id,
+            schedule.price.unwrap_or(prices.price),
+            schedule.promotional_price.or(prices.promotional_price),
+            source,
+            Some(&schedule.id),
+            schedule.created_by.as_deref(),
+        )
+        .await?;
+
+        schedule.applied_at = Some(Utc::now());
+        ShopPriceHistoryRepository::update_schedule_in_tx(&mut tx, &schedule)
+            .await
+            .map_err(|e| format!("Failed to update price schedule: {}", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))
+    }
+
+    /// Restore the promotional price a promotion replaced
+    async fn revert_promotion_in_tx(
+        tx: &mut DbTransaction<'_>,
+        schedule: &mut PriceSchedule,
+    ) -> Result<(), String> {
+        let prices = ShopPriceHistoryRepository::get_product_prices_in_tx(tx, &schedule.product_id)
+            .await
+            .map_err(|e| format!("Failed to fetch product prices: {}", e))?
+            .ok_or_else(|| format!("Product not found: {}", schedule.product_id))?;
+
+        Self::set_prices_in_tx(
+            tx,
+            &schedule.product_id,
+            prices.price,
+            schedule.previous_promotional_price,
+            "promotion",
+            Some(&schedule.id),
+            schedule.created_by.as_deref(),
+        )
+        .await?;
+
+        schedule.reverted_at = Some(Utc::now());
+        Ok(())
+    }
+}
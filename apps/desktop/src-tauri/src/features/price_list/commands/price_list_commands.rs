@@ -0,0 +1,168 @@
+use crate::db::RepositoryFactory;
+use crate::features::price_list::dtos::price_list_dto::{
+    CreatePriceListDTO, CreatePriceListRuleDTO, ResolvePriceDTO, UpdatePriceListDTO,
+    UpdatePriceListRuleDTO,
+};
+use crate::features::price_list::models::price_list_model::{
+    PriceList, PriceListRule, ResolvedPrice,
+};
+use crate::features::price_list::services::shop_price_list_service::ShopPriceListService;
+use crate::features::price_list::services::shop_pricing_service::ShopPricingService;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn create_price_list(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: CreatePriceListDTO,
+) -> Result<PriceList, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPriceListService::new(pool);
+    service.create_price_list(payload).await
+}
+
+#[tauri::command]
+pub async fn update_price_list(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: UpdatePriceListDTO,
+) -> Result<PriceList, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPriceListService::new(pool);
+    service.update_price_list(payload).await
+}
+
+#[tauri::command]
+pub async fn delete_price_list(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+) -> Result<(), String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPriceListService::new(pool);
+    service.delete_price_list(&id).await
+}
+
+#[tauri::command]
+pub async fn get_price_list(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+) -> Result<PriceList, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPriceListService::new(pool);
+    service.get_price_list(&id).await
+}
+
+#[tauri::command]
+pub async fn list_price_lists(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+) -> Result<Vec<PriceList>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPriceListService::new(pool);
+    service.list_price_lists().await
+}
+
+#[tauri::command]
+pub async fn create_price_list_rule(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: CreatePriceListRuleDTO,
+) -> Result<PriceListRule, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPriceListService::new(pool);
+    service.create_rule(payload).await
+}
+
+#[tauri::command]
+pub async fn update_price_list_rule(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: UpdatePriceListRuleDTO,
+) -> Result<PriceListRule, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPriceListService::new(pool);
+    service.update_rule(payload).await
+}
+
+#[tauri::command]
+pub async fn delete_price_list_rule(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+) -> Result<(), String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPriceListService::new(pool);
+    service.delete_rule(&id).await
+}
+
+#[tauri::command]
+pub async fn list_price_list_rules(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    price_list_id: String,
+) -> Result<Vec<PriceListRule>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPriceListService::new(pool);
+    service.list_rules(&price_list_id).await
+}
+
+#[tauri::command]
+pub async fn resolve_price(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: ResolvePriceDTO,
+) -> Result<ResolvedPrice, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopPricingService::new(pool, shop_id);
+    service
+        .resolve(
+            &payload.product_id,
+            payload.customer_id.as_deref(),
+            payload.quantity.unwrap_or(1.0),
+            payload.at,
+        )
+        .await
+}
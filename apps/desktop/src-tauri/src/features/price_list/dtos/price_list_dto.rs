@@ -0,0 +1,150 @@
+use crate::features::price_list::models::price_list_model::{PriceList, PriceListRule};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatePriceListDTO {
+    pub name: String,
+    pub code: Option<String>,
+    pub currency: Option<String>,
+    pub priority: Option<i64>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub is_active: Option<bool>,
+}
+
+impl CreatePriceListDTO {
+    pub fn into_model(self) -> PriceList {
+        let now = Utc::now();
+        PriceList {
+            id: Uuid::new_v4().to_string(),
+            name: self.name,
+            code: self.code,
+            currency: self.currency.or_else(|| Some("BRL".to_string())),
+            priority: self.priority.or(Some(0)),
+            starts_at: self.starts_at,
+            ends_at: self.ends_at,
+            is_active: self.is_active.or(Some(true)),
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdatePriceListDTO {
+    pub id: String,
+    pub name: Option<String>,
+    pub code: Option<String>,
+    pub currency: Option<String>,
+    pub priority: Option<i64>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub is_active: Option<bool>,
+}
+
+impl UpdatePriceListDTO {
+    pub fn apply_to_model(self, mut existing: PriceList) -> PriceList {
+        if let Some(name) = self.name {
+            existing.name = name;
+        }
+        if let Some(code) = self.code {
+            existing.code = Some(code);
+        }
+        if let Some(currency) = self.currency {
+            existing.currency = Some(currency);
+        }
+        if let Some(priority) = self.priority {
+            existing.priority = Some(priority);
+        }
+        if let Some(starts_at) = self.starts_at {
+            existing.starts_at = Some(starts_at);
+        }
+        if let Some(ends_at) = self.ends_at {
+            existing.ends_at = Some(ends_at);
+        }
+        if let Some(is_active) = self.is_active {
+            existing.is_active = Some(is_active);
+        }
+        existing.sync_status = Some("modified".to_string());
+        existing.updated_at = Some(Utc::now());
+        existing
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatePriceListRuleDTO {
+    pub price_list_id: String,
+    pub product_id: Option<String>,
+    pub category_id: Option<String>,
+    pub brand_id: Option<String>,
+    pub adjustment_type: String,
+    pub value: f64,
+    pub min_quantity: Option<f64>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+impl CreatePriceListRuleDTO {
+    pub fn into_model(self) -> PriceListRule {
+        let now = Utc::now();
+        PriceListRule {
+            id: Uuid::new_v4().to_string(),
+            price_list_id: self.price_list_id,
+            product_id: self.product_id,
+            category_id: self.category_id,
+            brand_id: self.brand_id,
+            adjustment_type: self.adjustment_type,
+            value: self.value,
+            min_quantity: self.min_quantity.or(Some(1.0)),
+            starts_at: self.starts_at,
+            ends_at: self.ends_at,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdatePriceListRuleDTO {
+    pub id: String,
+    pub adjustment_type: Option<String>,
+    pub value: Option<f64>,
+    pub min_quantity: Option<f64>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+impl UpdatePriceListRuleDTO {
+    pub fn apply_to_model(self, mut existing: PriceListRule) -> PriceListRule {
+        if let Some(adjustment_type) = self.adjustment_type {
+            existing.adjustment_type = adjustment_type;
+        }
+        if let Some(value) = self.value {
+            existing.value = value;
+        }
+        if let Some(min_quantity) = self.min_quantity {
+            existing.min_quantity = Some(min_quantity);
+        }
+        if let Some(starts_at) = self.starts_at {
+            existing.starts_at = Some(starts_at);
+        }
+        if let Some(ends_at) = self.ends_at {
+            existing.ends_at = Some(ends_at);
+        }
+        existing.sync_status = Some("modified".to_string());
+        existing.updated_at = Some(Utc::now());
+        existing
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResolvePriceDTO {
+    pub product_id: String,
+    pub customer_id: Option<String>,
+    pub quantity: Option<f64>,     // Defaults to 1
+    pub at: Option<DateTime<Utc>>, // Defaults to now
+}
@@ -0,0 +1,60 @@
+use crate::features::product::models::product_model::Product;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PriceList {
+    pub id: String,
+    pub name: String,
+    pub code: Option<String>,
+    pub currency: Option<String>, // DEFAULT 'BRL'
+    pub priority: Option<i64>,    // Breaks ties between lists offering the same price
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub is_active: Option<bool>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct PriceListRule {
+    pub id: String,
+    pub price_list_id: String,
+    pub product_id: Option<String>, // At most one scope; none applies to every product
+    pub category_id: Option<String>,
+    pub brand_id: Option<String>,
+    pub adjustment_type: String, // 'fixed', 'percentage'
+    pub value: f64,
+    pub min_quantity: Option<f64>, // Quantity tier, DEFAULT 1
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Effective unit price of a product for a customer, quantity and date
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResolvedPrice {
+    pub product_id: String,
+    pub quantity: f64,
+    pub currency: String,
+    pub base_price: f64,
+    pub unit_price: f64,
+    pub source: String, // 'base', 'promotional', 'price_list', 'group_discount'
+    pub price_list_id: Option<String>,
+    pub rule_id: Option<String>,
+    pub customer_group_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PricedProduct {
+    pub product: Product,
+    pub price: ResolvedPrice,
+}
@@ -0,0 +1,277 @@
+//! Shop-scoped Price List Repository for Multi-Database Architecture
+
+use crate::features::price_list::models::price_list_model::{PriceList, PriceListRule};
+use chrono::{DateTime, Utc};
+use sqlx::{QueryBuilder, Result, Sqlite, SqlitePool};
+use std::sync::Arc;
+
+/// Pricing settings of a customer group the customer belongs to
+#[derive(Debug, sqlx::FromRow)]
+pub struct GroupPricing {
+    pub id: String,
+    pub default_discount_percentage: Option<f64>,
+    pub price_list_id: Option<String>,
+}
+
+pub struct ShopPriceListRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopPriceListRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    // ============================================================
+    // Price lists
+    // ============================================================
+
+    pub async fn create(&self, list: &PriceList) -> Result<PriceList> {
+        let sql = r#"
+            INSERT INTO price_lists (
+                id, name, code, currency, priority, starts_at, ends_at, is_active,
+                _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, PriceList>(sql)
+            .bind(&list.id)
+            .bind(&list.name)
+            .bind(&list.code)
+            .bind(&list.currency)
+            .bind(list.priority)
+            .bind(list.starts_at)
+            .bind(list.ends_at)
+            .bind(list.is_active)
+            .bind(&list.sync_status)
+            .bind(list.created_at)
+            .bind(list.updated_at)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn update(&self, list: &PriceList) -> Result<PriceList> {
+        let sql = r#"
+            UPDATE price_lists SET
+                name = $2,
+                code = $3,
+                currency = $4,
+                priority = $5,
+                starts_at = $6,
+                ends_at = $7,
+                is_active = $8,
+                _status = $9,
+                updated_at = $10
+            WHERE id = $1
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, PriceList>(sql)
+            .bind(&list.id)
+            .bind(&list.name)
+            .bind(&list.code)
+            .bind(&list.currency)
+            .bind(list.priority)
+            .bind(list.starts_at)
+            .bind(list.ends_at)
+            .bind(list.is_active)
+            .bind(&list.sync_status)
+            .bind(list.updated_at)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn get_by_id(&self, id: &str) -> Result<Option<PriceList>> {
+        let sql =
+            "SELECT * FROM price_lists WHERE id = $1 AND (_status IS NULL OR _status != 'deleted')";
+        sqlx::query_as::<_, PriceList>(sql)
+            .bind(id)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn list(&self) -> Result<Vec<PriceList>> {
+        let sql = r#"
+            SELECT * FROM price_lists
+            WHERE (_status IS NULL OR _status != 'deleted')
+            ORDER BY priority DESC, name ASC
+        "#;
+        sqlx::query_as::<_, PriceList>(sql)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        let sql = "UPDATE price_lists SET _status = 'deleted', updated_at = datetime('now') WHERE id = $1";
+        sqlx::query(sql).bind(id).execute(&*self.pool).await?;
+        Ok(())
+    }
+
+    // ============================================================
+    // Rules
+    // ============================================================
+
+    pub async fn create_rule(&self, rule: &PriceListRule) -> Result<PriceListRule> {
+        let sql = r#"
+            INSERT INTO price_list_rules (
+                id, price_list_id, product_id, category_id, brand_id, adjustment_type,
+                value, min_quantity, starts_at, ends_at, _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, PriceListRule>(sql)
+            .bind(&rule.id)
+            .bind(&rule.price_list_id)
+            .bind(&rule.product_id)
+            .bind(&rule.category_id)
+            .bind(&rule.brand_id)
+            .bind(&rule.adjustment_type)
+            .bind(rule.value)
+            .bind(rule.min_quantity)
+            .bind(rule.starts_at)
+            .bind(rule.ends_at)
+            .bind(&rule.sync_status)
+            .bind(rule.created_at)
+            .bind(rule.updated_at)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn update_rule(&self, rule: &PriceListRule) -> Result<PriceListRule> {
+        let sql = r#"
+            UPDATE price_list_rules SET
+                adjustment_type = $2,
+                value = $3,
+                min_quantity = $4,
+                starts_at = $5,
+                ends_at = $6,
+                _status = $7,
+                updated_at = $8
+            WHERE id = $1
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, PriceListRule>(sql)
+            .bind(&rule.id)
+            .bind(&rule.adjustment_type)
+            .bind(rule.value)
+            .bind(rule.min_quantity)
+            .bind(rule.starts_at)
+            .bind(rule.ends_at)
+            .bind(&rule.sync_status)
+            .bind(rule.updated_at)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn get_rule_by_id(&self, id: &str) -> Result<Option<PriceListRule>> {
+        let sql = "SELECT * FROM price_list_rules WHERE id = $1 AND (_status IS NULL OR _status != 'deleted')";
+        sqlx::query_as::<_, PriceListRule>(sql)
+            .bind(id)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn list_rules(&self, price_list_id: &str) -> Result<Vec<PriceListRule>> {
+        let sql = r#"
+            SELECT * FROM price_list_rules
+            WHERE price_list_id = $1 AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY product_id, category_id, brand_id, min_quantity ASC
+        "#;
+        sqlx::query_as::<_, PriceListRule>(sql)
+            .bind(price_list_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn delete_rule(&self, id: &str) -> Result<()> {
+        let sql = "UPDATE price_list_rules SET _status = 'deleted', updated_at = datetime('now') WHERE id = $1";
+        sqlx::query(sql).bind(id).execute(&*self.pool).await?;
+        Ok(())
+    }
+
+    // ============================================================
+    // Resolution queries
+    // ============================================================
+
+    /// Groups of a customer: its primary group plus its memberships
+    pub async fn list_customer_groups(&self, customer_id: &str) -> Result<Vec<GroupPricing>> {
+        let sql = r#"
+            SELECT id, default_discount_percentage, price_list_id
+            FROM customer_groups
+            WHERE (_status IS NULL OR _status != 'deleted')
+              AND (
+                id = (SELECT customer_group_id FROM customers WHERE id = $1)
+                OR id IN (
+                    SELECT customer_group_id FROM customer_group_memberships
+                    WHERE customer_id = $1 AND (_status IS NULL OR _status != 'deleted')
+                )
+              )
+        "#;
+        sqlx::query_as::<_, GroupPricing>(sql)
+            .bind(customer_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// A price list if it is active at `at`
+    pub async fn get_active(&self, id: &str, at: DateTime<Utc>) -> Result<Option<PriceList>> {
+        let sql = r#"
+            SELECT * FROM price_lists
+            WHERE id = $1
+              AND (_status IS NULL OR _status != 'deleted')
+              AND (is_active IS NULL OR is_active = 1)
+              AND (starts_at IS NULL OR starts_at <= $2)
+              AND (ends_at IS NULL OR ends_at >= $2)
+        "#;
+        sqlx::query_as::<_, PriceList>(sql)
+            .bind(id)
+            .bind(at)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    /// Rules of a list valid at `at`, whatever product they cover
+    pub async fn list_active_rules(
+        &self,
+        price_list_id: &str,
+        at: DateTime<Utc>,
+    ) -> Result<Vec<PriceListRule>> {
+        let sql = r#"
+            SELECT * FROM price_list_rules
+            WHERE price_list_id = $1
+              AND (_status IS NULL OR _status != 'deleted')
+              AND (starts_at IS NULL OR starts_at <= $2)
+              AND (ends_at IS NULL OR ends_at >= $2)
+        "#;
+        sqlx::query_as::<_, PriceListRule>(sql)
+            .bind(price_list_id)
+            .bind(at)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// (product_id, category_id) links of the given products
+    pub async fn list_product_categories(
+        &self,
+        product_ids: &[String],
+    ) -> Result<Vec<(String, String)>> {
+        if product_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT product_id, category_id FROM product_categories \
+             WHERE (_status IS NULL OR _status != 'deleted') AND category_id IS NOT NULL \
+             AND product_id IN (",
+        );
+        let mut separated = builder.separated(", ");
+        for id in product_ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(")");
+
+        builder
+            .build_query_as::<(String, String)>()
+            .fetch_all(&*self.pool)
+            .await
+    }
+}
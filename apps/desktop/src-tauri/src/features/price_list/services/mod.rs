@@ -0,0 +1,2 @@
+pub mod shop_price_list_service;
+pub mod shop_pricing_service;
@@ -0,0 +1,150 @@
+//! Shop-scoped Price List Service for Multi-Database Architecture
+
+use crate::features::price_list::dtos::price_list_dto::{
+    CreatePriceListDTO, CreatePriceListRuleDTO, UpdatePriceListDTO, UpdatePriceListRuleDTO,
+};
+use crate::features::price_list::models::price_list_model::{PriceList, PriceListRule};
+use crate::features::price_list::repositories::shop_price_list_repository::ShopPriceListRepository;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+fn validate_rule(rule: &PriceListRule) -> Result<(), String> {
+    let scopes = [&rule.product_id, &rule.category_id, &rule.brand_id]
+        .iter()
+        .filter(|scope| scope.is_some())
+        .count();
+    if scopes > 1 {
+        return Err("A rule can target a product, a category or a brand, not several".to_string());
+    }
+
+    match rule.adjustment_type.as_str() {
+        "fixed" if rule.value < 0.0 => Err("Fixed price cannot be negative".to_string()),
+        "percentage" if rule.value <= -100.0 => {
+            Err("Percentage adjustment must be greater than -100".to_string())
+        }
+        "fixed" | "percentage" => {
+            if rule.min_quantity.is_some_and(|q| q <= 0.0) {
+                return Err("Minimum quantity must be greater than zero".to_string());
+            }
+            Ok(())
+        }
+        other => Err(format!("Invalid adjustment type: {}", other)),
+    }
+}
+
+pub struct ShopPriceListService {
+    pool: Arc<SqlitePool>,
+    repo: ShopPriceListRepository,
+}
+
+impl ShopPriceListService {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        let repo = ShopPriceListRepository::new(pool.clone());
+        Self { pool, repo }
+    }
+
+    pub fn pool(&self) -> Arc<SqlitePool> {
+        self.pool.clone()
+    }
+
+    // ============================================================
+    // Price lists
+    // ============================================================
+
+    pub async fn create_price_list(
+        &self,
+        payload: CreatePriceListDTO,
+    ) -> Result<PriceList, String> {
+        let list = payload.into_model();
+        if list.name.trim().is_empty() {
+            return Err("Price list name is required".to_string());
+        }
+        self.repo
+            .create(&list)
+            .await
+            .map_err(|e| format!("Failed to create price list: {}", e))
+    }
+
+    pub async fn update_price_list(
+        &self,
+        payload: UpdatePriceListDTO,
+    ) -> Result<PriceList, String> {
+        let existing = self.get_price_list(&payload.id).await?;
+        let list = payload.apply_to_model(existing);
+        self.repo
+            .update(&list)
+            .await
+            .map_err(|e| format!("Failed to update price list: {}", e))
+    }
+
+    pub async fn get_price_list(&self, id: &str) -> Result<PriceList, String> {
+        self.repo
+            .get_by_id(id)
+            .await
+            .map_err(|e| format!("Failed to fetch price list: {}", e))?
+            .ok_or_else(|| format!("Price list not found: {}", id))
+    }
+
+    pub async fn list_price_lists(&self) -> Result<Vec<PriceList>, String> {
+        self.repo
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list price lists: {}", e))
+    }
+
+    pub async fn delete_price_list(&self, id: &str) -> Result<(), String> {
+        self.repo
+            .delete(id)
+            .await
+            .map_err(|e| format!("Failed to delete price list: {}", e))
+    }
+
+    // ============================================================
+    // Rules
+    // ============================================================
+
+    pub async fn create_rule(
+        &self,
+        payload: CreatePriceListRuleDTO,
+    ) -> Result<PriceListRule, String> {
+        self.get_price_list(&payload.price_list_id).await?;
+        let rule = payload.into_model();
+        validate_rule(&rule)?;
+        self.repo
+            .create_rule(&rule)
+            .await
+            .map_err(|e| format!("Failed to create price list rule: {}", e))
+    }
+
+    pub async fn update_rule(
+        &self,
+        payload: UpdatePriceListRuleDTO,
+    ) -> Result<PriceListRule, String> {
+        let existing = self
+            .repo
+            .get_rule_by_id(&payload.id)
+            .await
+            .map_err(|e| format!("Failed to fetch price list rule: {}", e))?
+            .ok_or_else(|| format!("Price list rule not found: {}", payload.id))?;
+        let rule = payload.apply_to_model(existing);
+        validate_rule(&rule)?;
+        self.repo
+            .update_rule(&rule)
+            .await
+            .map_err(|e| format!("Failed to update price list rule: {}", e))
+    }
+
+    pub async fn list_rules(&self, price_list_id: &str) -> Result<Vec<PriceListRule>, String> {
+        self.repo
+            .list_rules(price_list_id)
+            .await
+            .map_err(|e| format!("Failed to list price list rules: {}", e))
+    }
+
+    pub async fn delete_rule(&self, id: &str) -> Result<(), String> {
+        self.repo
+            .delete_rule(id)
+            .await
+            .map_err(|e| format!("Failed to delete price list rule: {}", e))
+    }
+}
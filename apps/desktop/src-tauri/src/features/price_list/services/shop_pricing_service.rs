@@ -0,0 +1,298 @@
+//! Shop-scoped Price Resolution Service for Multi-Database Architecture
+//!
+//! Picks the effective unit price of a product for a customer, quantity and
+//! date. Candidates are the base price, the promotional price, the best rule
+//! of each active price list linked to the customer's groups and, for groups
+//! whose list has no matching rule, the group's default discount. The lowest
+//! candidate wins; list priority breaks ties.
+
+use crate::features::price_list::models::price_list_model::{
+    PriceList, PriceListRule, PricedProduct, ResolvedPrice,
+};
+use crate::features::price_list::repositories::shop_price_list_repository::{
+    GroupPricing, ShopPriceListRepository,
+};
+use crate::features::product::models::product_model::Product;
+use crate::features::product::repositories::shop_product_repository::ShopProductRepository;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn round_cents(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Product rules beat category rules, which beat brand and list-wide rules
+fn specificity(rule: &PriceListRule) -> u8 {
+    if rule.product_id.is_some() {
+        3
+    } else if rule.category_id.is_some() {
+        2
+    } else if rule.brand_id.is_some() {
+        1
+    } else {
+        0
+    }
+}
+
+/// Whether a rule covers a product (directly, through one of its categories
+/// or its brand, or list-wide) for a quantity
+fn covers(rule: &PriceListRule, product: &Product, categories: &[String], quantity: f64) -> bool {
+    if rule.min_quantity.unwrap_or(1.0) > quantity {
+        return false;
+    }
+    match (&rule.product_id, &rule.category_id, &rule.brand_id) {
+        (None, None, None) => true,
+        (product_id, category_id, brand_id) => {
+            product_id.as_deref() == Some(product.id.as_str())
+                || category_id.as_ref().is_some_and(|category_id| {
+                    product.category_id.as_ref() == Some(category_id)
+                        || categories.contains(category_id)
+                })
+                || (brand_id.is_some() && *brand_id == product.brand_id)
+        }
+    }
+}
+
+fn apply_rule(rule: &PriceListRule, base_price: f64) -> f64 {
+    match rule.adjustment_type.as_str() {
+        "fixed" => rule.value,
+        _ => base_price * (1.0 + rule.value / 100.0),
+    }
+}
+
+/// A customer group with its active price list and the list's rules valid
+/// at the pricing date
+struct GroupRules {
+    group: GroupPricing,
+    list: Option<(PriceList, Vec<PriceListRule>)>,
+}
+
+struct Candidate {
+    price: ResolvedPrice,
+    priority: i64,
+}
+
+pub struct ShopPricingService {
+    pool: Arc<SqlitePool>,
+    shop_id: String,
+    repo: ShopPriceListRepository,
+}
+
+impl ShopPricingService {
+    pub fn new(pool: Arc<SqlitePool>, shop_id: String) -> Self {
+        let repo = ShopPriceListRepository::new(pool.clone());
+        Self {
+            pool,
+            shop_id,
+            repo,
+        }
+    }
+
+    pub fn pool(&self) -> Arc<SqlitePool> {
+        self.pool.clone()
+    }
+
+    /// Effective price of a product
+    pub async fn resolve(
+        &self,
+        product_id: &str,
+        customer_id: Option<&str>,
+        quantity: f64,
+        at: Option<DateTime<Utc>>,
+    ) -> Result<ResolvedPrice, String> {
+        let product = ShopProductRepository::new(self.pool.clone(), self.shop_id.clone())
+            .get_by_id(product_id)
+            .await
+            .map_err(|e| format!("Failed to fetch product: {}", e))?
+            .ok_or_else(|| format!("Product not found: {}", product_id))?;
+
+        self.resolve_product(&product, customer_id, quantity, at)
+            .await
+    }
+
+    /// Effective price of an already loaded product
+    pub async fn resolve_product(
+        &self,
+        product: &Product,
+        customer_id: Option<&str>,
+        quantity: f64,
+        at: Option<DateTime<Utc>>,
+    ) -> Result<ResolvedPrice, String> {
+        let groups = self
+            .customer_groups(customer_id, at.unwrap_or_else(Utc::now))
+            .await?;
+        let categories = self
+            .product_categories(std::slice::from_ref(&product.id))
+            .await?;
+        let categories = categories.get(&product.id).map(Vec::as_slice).unwrap_or(&[]);
+        Self::resolve_for_product(product, categories, &groups, quantity)
+    }
+
+    /// Unit price of each product for one unit, as seen by the customer. Lists,
+    /// rules and category links are loaded once for the whole batch
+    pub async fn price_products(
+        &self,
+        products: Vec<Product>,
+        customer_id: Option<&str>,
+    ) -> Result<Vec<PricedProduct>, String> {
+        let groups = self.customer_groups(customer_id, Utc::now()).await?;
+        let product_ids: Vec<String> = products.iter().map(|p| p.id.clone()).collect();
+        let categories = self.product_categories(&product_ids).await?;
+
+        let mut priced = Vec::with_capacity(products.len());
+        for product in products {
+            let product_categories = categories.get(&product.id).map(Vec::as_slice).unwrap_or(&[]);
+            let price = Self::resolve_for_product(&product, product_categories, &groups, 1.0)?;
+            priced.push(PricedProduct { product, price });
+        }
+        Ok(priced)
+    }
+
+    async fn customer_groups(
+        &self,
+        customer_id: Option<&str>,
+        at: DateTime<Utc>,
+    ) -> Result<Vec<GroupRules>, String> {
+        let groups = match customer_id {
+            Some(customer_id) => self
+                .repo
+                .list_customer_groups(customer_id)
+                .await
+                .map_err(|e| format!("Failed to fetch customer groups: {}", e))?,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut group_rules = Vec::with_capacity(groups.len());
+        for group in groups {
+            let mut list = None;
+            if let Some(price_list_id) = &group.price_list_id {
+                let active = self
+                    .repo
+                    .get_active(price_list_id, at)
+                    .await
+                    .map_err(|e| format!("Failed to fetch price list: {}", e))?;
+                if let Some(active) = active {
+                    let rules = self
+                        .repo
+                        .list_active_rules(&active.id, at)
+                        .await
+                        .map_err(|e| format!("Failed to fetch price list rules: {}", e))?;
+                    list = Some((active, rules));
+                }
+            }
+            group_rules.push(GroupRules { group, list });
+        }
+        Ok(group_rules)
+    }
+
+    async fn product_categories(
+        &self,
+        product_ids: &[String],
+    ) -> Result<HashMap<String, Vec<String>>, String> {
+        let links = self
+            .repo
+            .list_product_categories(product_ids)
+            .await
+            .map_err(|e| format!("Failed to fetch product categories: {}", e))?;
+
+        let mut categories: HashMap<String, Vec<String>> = HashMap::new();
+        for (product_id, category_id) in links {
+            categories.entry(product_id).or_default().push(category_id);
+        }
+        Ok(categories)
+    }
+
+    fn resolve_for_product(
+        product: &Product,
+        categories: &[String],
+        groups: &[GroupRules],
+        quantity: f64,
+    ) -> Result<ResolvedPrice, String> {
+        let currency = product
+            .currency
+            .clone()
+            .unwrap_or_else(|| "BRL".to_string());
+        let base_price = product.price;
+        let candidate = |unit_price: f64, source: &str, priority: i64| Candidate {
+            price: ResolvedPrice {
+                product_id: product.id.clone(),
+                quantity,
+                currency: currency.clone(),
+                base_price,
+                unit_price: round_cents(unit_price.max(0.0)),
+                source: source.to_string(),
+                price_list_id: None,
+                rule_id: None,
+                customer_group_id: None,
+            },
+            priority,
+        };
+
+        let mut candidates = vec![candidate(base_price, "base", i64::MIN)];
+        if let Some(promotional_price) = product.promotional_price {
+            if promotional_price > 0.0 && promotional_price < base_price {
+                candidates.push(candidate(promotional_price, "promotional", i64::MIN));
+            }
+        }
+
+        for GroupRules { group, list } in groups {
+            // Most specific rule first, then the highest quantity tier reached
+            let list_rule = list
+                .as_ref()
+                .filter(|(list, _)| list.currency.as_deref().unwrap_or("BRL") == currency)
+                .and_then(|(list, rules)| {
+                    rules
+                        .iter()
+                        .filter(|rule| covers(rule, product, categories, quantity))
+                        .max_by(|a, b| {
+                            specificity(a).cmp(&specificity(b)).then(
+                                a.min_quantity
+                                    .unwrap_or(1.0)
+                                    .total_cmp(&b.min_quantity.unwrap_or(1.0)),
+                            )
+                        })
+                        .map(|rule| (list, rule))
+                });
+
+            match list_rule {
+                Some((list, rule)) => {
+                    let mut c = candidate(
+                        apply_rule(rule, base_price),
+                        "price_list",
+                        list.priority.unwrap_or(0),
+                    );
+                    c.price.price_list_id = Some(list.id.clone());
+                    c.price.rule_id = Some(rule.id.clone());
+                    c.price.customer_group_id = Some(group.id.clone());
+                    candidates.push(c);
+                }
+                None => {
+                    let discount = group.default_discount_percentage.unwrap_or(0.0);
+                    if discount > 0.0 {
+                        let mut c = candidate(
+                            base_price * (1.0 - discount.min(100.0) / 100.0),
+                            "group_discount",
+                            0,
+                        );
+                        c.price.customer_group_id = Some(group.id.clone());
+                        candidates.push(c);
+                    }
+                }
+            }
+        }
+
+        let best = candidates
+            .into_iter()
+            .min_by(|a, b| {
+                a.price
+                    .unit_price
+                    .total_cmp(&b.price.unit_price)
+                    .then(b.priority.cmp(&a.priority))
+            })
+            .map(|c| c.price)
+            .ok_or_else(|| "No price candidate".to_string())?;
+        Ok(best)
+    }
+}
@@ -1 +1,2 @@
 pub mod product_commands;
+pub mod product_variant_commands;
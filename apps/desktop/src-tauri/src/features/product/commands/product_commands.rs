@@ -1,9 +1,12 @@
 use crate::db::RepositoryFactory;
+use crate::features::price_list::models::price_list_model::PricedProduct;
+use crate::features::price_list::services::shop_pricing_service::ShopPricingService;
 use crate::features::product::dtos::product_dto::{
     CreateProductDTO, ProductListFilterDTO, UpdateProductDTO,
 };
 use crate::features::product::models::product_model::Product;
 use crate::features::product::services::shop_product_service::ShopProductService;
+use crate::features::product_media::commands::product_media_commands::media_service;
 use std::sync::Arc;
 use tauri::State;
 
@@ -48,8 +51,18 @@ pub async fn delete_product(
         .await
         .map_err(|e| format!("Failed to get shop pool: {}", e))?;
 
-    let service = ShopProductService::new(pool, shop_id);
-    service.delete_product(&id).await
+    let service = ShopProductService::new(pool, shop_id.clone());
+    service.delete_product(&id).await?;
+
+    // Files of media no other product uses; the product is gone either way
+    let cleanup = match media_service(&repo_factory, shop_id).await {
+        Ok(media) => media.purge_orphan_media().await.map(|_| ()),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = cleanup {
+        eprintln!("[delete_product] Media cleanup failed: {}", e);
+    }
+    Ok(())
 }
 
 #[tauri::command]
@@ -95,3 +108,23 @@ pub async fn list_products_filtered(
     let service = ShopProductService::new(pool, shop_id);
     service.list_products_filtered(filters).await
 }
+
+#[tauri::command]
+pub async fn list_products_priced(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    filters: ProductListFilterDTO,
+) -> Result<Vec<PricedProduct>, String> {
+    let shop_id = filters.shop_id.clone();
+    let customer_id = filters.customer_id.clone();
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let products = ShopProductService::new(pool.clone(), shop_id.clone())
+        .list_products_filtered(filters)
+        .await?;
+    ShopPricingService::new(pool, shop_id)
+        .price_products(products, customer_id.as_deref())
+        .await
+}
@@ -0,0 +1,145 @@
+use crate::db::RepositoryFactory;
+use crate::features::inventory::models::inventory_level_model::InventoryLevel;
+use crate::features::product::dtos::product_dto::{
+    BulkSetVariantStockDTO, BulkUpdateVariantsDTO, GenerateVariantsDTO, ProductListFilterDTO,
+    SetProductOptionsDTO,
+};
+use crate::features::product::models::product_model::{Product, ProductGroup, ProductOption};
+use crate::features::product::services::shop_product_variant_service::ShopProductVariantService;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn list_product_options(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    product_id: String,
+) -> Result<Vec<ProductOption>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopProductVariantService::new(pool, shop_id);
+    service.list_options(&product_id).await
+}
+
+#[tauri::command]
+pub async fn set_product_options(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: SetProductOptionsDTO,
+) -> Result<Vec<ProductOption>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopProductVariantService::new(pool, shop_id);
+    service.set_options(payload).await
+}
+
+#[tauri::command]
+pub async fn list_product_variants(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    product_id: String,
+) -> Result<Vec<Product>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopProductVariantService::new(pool, shop_id);
+    service.list_variants(&product_id).await
+}
+
+#[tauri::command]
+pub async fn generate_product_variants(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: GenerateVariantsDTO,
+) -> Result<Vec<Product>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopProductVariantService::new(pool, shop_id);
+    service.generate_variants(payload).await
+}
+
+#[tauri::command]
+pub async fn bulk_update_product_variants(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: BulkUpdateVariantsDTO,
+) -> Result<Vec<Product>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopProductVariantService::new(pool, shop_id);
+    service.bulk_update_variants(payload).await
+}
+
+#[tauri::command]
+pub async fn bulk_set_variant_stock(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: BulkSetVariantStockDTO,
+) -> Result<Vec<InventoryLevel>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopProductVariantService::new(pool, shop_id);
+    service.bulk_set_variant_stock(payload).await
+}
+
+#[tauri::command]
+pub async fn get_product_group(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+) -> Result<Option<ProductGroup>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopProductVariantService::new(pool, shop_id);
+    service.get_product_group(&id).await
+}
+
+#[tauri::command]
+pub async fn search_products_grouped(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    query: String,
+) -> Result<Vec<ProductGroup>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopProductVariantService::new(pool, shop_id);
+    service.search_products_grouped(&query).await
+}
+
+#[tauri::command]
+pub async fn list_products_grouped(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    filters: ProductListFilterDTO,
+) -> Result<Vec<ProductGroup>, String> {
+    let shop_id = filters.shop_id.clone();
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopProductVariantService::new(pool, shop_id);
+    service.list_products_grouped(filters).await
+}
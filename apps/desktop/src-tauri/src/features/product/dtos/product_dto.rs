@@ -1,4 +1,4 @@
-use crate::features::product::models::product_model::{Product, ProductCategory};
+use crate::features::product::models::product_model::{Product, ProductCategory, ProductOption};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -61,6 +61,7 @@ pub struct UpdateProductDTO {
     pub category_id: Option<String>,
     pub brand_id: Option<String>,
     pub parent_id: Option<String>,
+    pub changed_by: Option<String>, // Author recorded in the price history
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -75,6 +76,7 @@ pub struct ProductListFilterDTO {
     pub max_price: Option<f64>,
     pub page: Option<u32>,
     pub per_page: Option<u32>,
+    pub customer_id: Option<String>, // Prices are resolved for this customer's groups
 }
 
 impl CreateProductDTO {
@@ -163,3 +165,66 @@ impl UpdateProductDTO {
         (product, Vec::new())
     }
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductOptionDTO {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetProductOptionsDTO {
+    pub product_id: String,
+    pub options: Vec<ProductOptionDTO>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GenerateVariantsDTO {
+    pub product_id: String,
+    pub price: Option<f64>, // Defaults to the parent price
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkUpdateVariantsDTO {
+    pub product_id: String,
+    pub variant_ids: Option<Vec<String>>, // None applies to every variant
+    pub price: Option<f64>,
+    pub promotional_price: Option<f64>,
+    pub cost_price: Option<f64>,
+    pub status: Option<String>,
+    pub changed_by: Option<String>, // Author recorded in the price history
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VariantStockDTO {
+    pub variant_id: String,
+    pub quantity: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkSetVariantStockDTO {
+    pub product_id: String,
+    pub location_id: String,
+    pub items: Vec<VariantStockDTO>,
+}
+
+impl SetProductOptionsDTO {
+    pub fn into_models(self) -> Vec<ProductOption> {
+        let now = Utc::now();
+        self.options
+            .into_iter()
+            .enumerate()
+            .map(|(position, option)| ProductOption {
+                id: Uuid::new_v4().to_string(),
+                product_id: self.product_id.clone(),
+                name: option.name.trim().to_string(),
+                position: Some(position as i64),
+                option_values: serde_json::to_string(&option.values).unwrap_or_default(),
+                sync_status: Some("created".to_string()),
+                created_at: Some(now),
+                updated_at: Some(now),
+            })
+            .collect()
+    }
+}
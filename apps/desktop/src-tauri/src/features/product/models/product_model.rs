@@ -36,6 +36,25 @@ pub struct Product {
     pub updated_at: Option<DateTime<Utc>>, // DEFAULT CURRENT_TIMESTAMP
 }
 
+/// Product types that have no stock and are never shipped
+pub const NON_STOCK_TYPES: [&str; 2] = ["digital", "service"];
+
+/// Whether products of a type move inventory when sold, shipped or returned
+pub fn type_tracks_inventory(product_type: &str) -> bool {
+    !NON_STOCK_TYPES.contains(&product_type)
+}
+
+impl Product {
+    pub fn tracks_inventory(&self) -> bool {
+        type_tracks_inventory(&self.r#type)
+    }
+
+    /// Physical goods flagged as shippable; digital goods and services never ship
+    pub fn needs_shipping(&self) -> bool {
+        self.tracks_inventory() && self.is_shippable
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct ProductCategory {
     pub product_id: String,
@@ -56,3 +75,25 @@ pub struct ProductMetrics {
     pub average_rating: Option<f64>,
     pub updated_at: Option<DateTime<Utc>>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ProductOption {
+    pub id: String,
+    pub product_id: String, // Parent product
+    pub name: String,
+    pub position: Option<i64>,
+    pub option_values: String, // JSON array of strings
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A parent product with its option axes and variants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductGroup {
+    pub product: Product,
+    pub options: Vec<ProductOption>,
+    pub variants: Vec<Product>,
+}
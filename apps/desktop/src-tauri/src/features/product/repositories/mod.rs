@@ -2,4 +2,5 @@ pub mod product_categories_repository;
 pub mod product_metrics_repository;
 pub mod product_repository;
 pub mod shop_product_categories_repository;
+pub mod shop_product_option_repository;
 pub mod shop_product_repository;
@@ -0,0 +1,84 @@
+//! Shop-scoped Product Option Repository for Multi-Database Architecture
+
+use crate::features::product::models::product_model::ProductOption;
+use sqlx::{QueryBuilder, Result, Sqlite, SqlitePool, Transaction};
+use std::sync::Arc;
+
+pub struct ShopProductOptionRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopProductOptionRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_by_product(&self, product_id: &str) -> Result<Vec<ProductOption>> {
+        let sql = r#"
+            SELECT * FROM product_options
+            WHERE product_id = $1 AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY position ASC
+        "#;
+        sqlx::query_as::<_, ProductOption>(sql)
+            .bind(product_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// Options of several products, in position order per product
+    pub async fn list_by_products(&self, product_ids: &[String]) -> Result<Vec<ProductOption>> {
+        if product_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT * FROM product_options WHERE (_status IS NULL OR _status != 'deleted') AND product_id IN (",
+        );
+        let mut separated = builder.separated(", ");
+        for id in product_ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(") ORDER BY position ASC");
+
+        builder
+            .build_query_as::<ProductOption>()
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// Replace every option axis of a product
+    pub async fn replace_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        product_id: &str,
+        options: &[ProductOption],
+    ) -> Result<Vec<ProductOption>> {
+        sqlx::query("DELETE FROM product_options WHERE product_id = $1")
+            .bind(product_id)
+            .execute(&mut **tx)
+            .await?;
+
+        let sql = r#"
+            INSERT INTO product_options (
+                id, product_id, name, position, option_values, _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+        "#;
+
+        let mut created = Vec::with_capacity(options.len());
+        for option in options {
+            let row = sqlx::query_as::<_, ProductOption>(sql)
+                .bind(&option.id)
+                .bind(&option.product_id)
+                .bind(&option.name)
+                .bind(option.position)
+                .bind(&option.option_values)
+                .bind(&option.sync_status)
+                .bind(option.created_at)
+                .bind(option.updated_at)
+                .fetch_one(&mut **tx)
+                .await?;
+            created.push(row);
+        }
+        Ok(created)
+    }
+}
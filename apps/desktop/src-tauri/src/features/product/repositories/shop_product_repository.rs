@@ -3,8 +3,11 @@
 //! This repository operates on a shop-specific database where products
 //! don't have a shop_id column (it's implicit from the database context).
 
-use crate::features::product::models::product_model::Product;
-use sqlx::{QueryBuilder, Result, Sqlite, SqlitePool, Transaction};
+use crate::features::product::models::product_model::{Product, NON_STOCK_TYPES};
+use crate::features::search::repositories::shop_search_repository::{
+    build_match_query, PRODUCT_MATCH_IDS, PRODUCT_RANK,
+};
+use sqlx::{Executor, QueryBuilder, Result, Sqlite, SqlitePool, Transaction};
 use std::sync::Arc;
 
 /// Product repository that operates on a shop-specific database.
@@ -132,6 +135,21 @@ impl ShopProductRepository {
     }
 
     pub async fn update(&self, product: &Product) -> Result<Product> {
+        self.update_with(&*self.pool, product).await
+    }
+
+    pub async fn update_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+        product: &Product,
+    ) -> Result<Product> {
+        self.update_with(&mut **tx, product).await
+    }
+
+    async fn update_with<'e, E>(&self, executor: E, product: &Product) -> Result<Product>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
         let sql = r#"
             UPDATE products SET
                 sku = $2,
@@ -188,7 +206,7 @@ impl ShopProductRepository {
             .bind(&product.parent_id)
             .bind(&product.sync_status)
             .bind(&product.updated_at)
-            .fetch_one(&*self.pool)
+            .fetch_one(executor)
             .await?;
 
         Ok(self.with_shop_id(row.into_product()))
@@ -250,46 +268,16 @@ impl ShopProductRepository {
             FROM products WHERE _status != 'deleted'"
         );
 
-        if let Some(status) = status {
-            builder.push(" AND status = ");
-            builder.push_bind(status);
-        }
-
-        if let Some(category_id) = category_id {
-            builder.push(" AND category_id = ");
-            builder.push_bind(category_id);
-        }
-
-        if let Some(brand_id) = brand_id {
-            builder.push(" AND brand_id = ");
-            builder.push_bind(brand_id);
-        }
-
-        if let Some(query) = query {
-            let pattern = format!("%{}%", query);
-            builder.push(" AND (name LIKE ");
-            builder.push_bind(pattern.clone());
-            builder.push(" OR sku LIKE ");
-            builder.push_bind(pattern.clone());
-            builder.push(" OR gtin_ean LIKE ");
-            builder.push_bind(pattern);
-            builder.push(")");
-        }
-
-        if let Some(is_shippable) = is_shippable {
-            builder.push(" AND is_shippable = ");
-            builder.push_bind(is_shippable);
-        }
-
-        if let Some(min_price) = min_price {
-            builder.push(" AND price >= ");
-            builder.push_bind(min_price);
-        }
-
-        if let Some(max_price) = max_price {
-            builder.push(" AND price <= ");
-            builder.push_bind(max_price);
-        }
+        push_filters(
+            &mut builder,
+            status,
+            category_id,
+            brand_id,
+            query,
+            is_shippable,
+            min_price,
+            max_price,
+        );
 
         builder.push(" ORDER BY created_at DESC");
         builder.push(" LIMIT ");
@@ -306,19 +294,105 @@ impl ShopProductRepository {
             .collect())
     }
 
+    /// Full-text search over name, SKU, GTIN, brand, category and attributes,
+    /// most relevant first
     pub async fn search(&self, query_str: &str) -> Result<Vec<Product>> {
-        let sql = r#"
-            SELECT id, sku, type, status, name, slug, gtin_ean, price, promotional_price, cost_price,
-                currency, tax_ncm, is_shippable, weight_g, width_mm, height_mm, depth_mm,
-                attributes, metadata, category_id, brand_id, parent_id, _status, created_at, updated_at
-            FROM products
-            WHERE _status != 'deleted'
-              AND (name LIKE $1 OR sku LIKE $1 OR gtin_ean LIKE $1)
-            ORDER BY created_at DESC
-        "#;
-        let search_pattern = format!("%{}%", query_str);
+        let Some(match_query) = build_match_query(query_str) else {
+            return self.list().await;
+        };
+
+        let sql = format!(
+            r#"
+            SELECT p.id, p.sku, p.type, p.status, p.name, p.slug, p.gtin_ean, p.price,
+                p.promotional_price, p.cost_price, p.currency, p.tax_ncm, p.is_shippable,
+                p.weight_g, p.width_mm, p.height_mm, p.depth_mm, p.attributes, p.metadata,
+                p.category_id, p.brand_id, p.parent_id, p._status, p.created_at, p.updated_at
+            FROM products_fts
+            JOIN search_index_keys k ON k.rowid = products_fts.rowid
+            JOIN products p ON p.id = k.entity_id
+            WHERE products_fts MATCH $1 AND p._status != 'deleted'
+            ORDER BY {}
+            "#,
+            PRODUCT_RANK
+        );
+        let rows = sqlx::query_as::<_, ShopProduct>(&sql)
+            .bind(match_query)
+            .fetch_all(&*self.pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| self.with_shop_id(r.into_product()))
+            .collect())
+    }
+
+    /// Products with the given ids, in no particular order
+    pub async fn list_by_ids(&self, ids: &[String]) -> Result<Vec<Product>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT id, sku, type, status, name, slug, gtin_ean, price, promotional_price, cost_price,
+            currency, tax_ncm, is_shippable, weight_g, width_mm, height_mm, depth_mm,
+            attributes, metadata, category_id, brand_id, parent_id, _status, created_at, updated_at
+            FROM products WHERE _status != 'deleted' AND id IN (",
+        );
+        let mut separated = builder.separated(", ");
+        for id in ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(")");
+
+        let rows = builder
+            .build_query_as::<ShopProduct>()
+            .fetch_all(&*self.pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| self.with_shop_id(r.into_product()))
+            .collect())
+    }
+
+    /// List the variants of a parent product, oldest first
+    pub async fn list_variants(&self, parent_id: &str) -> Result<Vec<Product>> {
+        let sql = "SELECT id, sku, type, status, name, slug, gtin_ean, price, promotional_price, cost_price,
+            currency, tax_ncm, is_shippable, weight_g, width_mm, height_mm, depth_mm,
+            attributes, metadata, category_id, brand_id, parent_id, _status, created_at, updated_at
+            FROM products WHERE parent_id = $1 AND _status != 'deleted' ORDER BY created_at ASC";
+
         let rows = sqlx::query_as::<_, ShopProduct>(sql)
-            .bind(search_pattern)
+            .bind(parent_id)
+            .fetch_all(&*self.pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| self.with_shop_id(r.into_product()))
+            .collect())
+    }
+
+    /// List the variants of several parent products, oldest first
+    pub async fn list_variants_by_parents(&self, parent_ids: &[String]) -> Result<Vec<Product>> {
+        if parent_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT id, sku, type, status, name, slug, gtin_ean, price, promotional_price, cost_price,
+            currency, tax_ncm, is_shippable, weight_g, width_mm, height_mm, depth_mm,
+            attributes, metadata, category_id, brand_id, parent_id, _status, created_at, updated_at
+            FROM products WHERE _status != 'deleted' AND parent_id IN (",
+        );
+        let mut separated = builder.separated(", ");
+        for id in parent_ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(") ORDER BY created_at ASC");
+
+        let rows = builder
+            .build_query_as::<ShopProduct>()
             .fetch_all(&*self.pool)
             .await?;
 
@@ -327,6 +401,166 @@ impl ShopProductRepository {
             .map(|r| self.with_shop_id(r.into_product()))
             .collect())
     }
+
+    /// Ids of the top-level products matching the filters either themselves
+    /// or through one of their variants
+    #[allow(clippy::too_many_arguments)]
+    pub async fn list_filtered_root_ids(
+        &self,
+        status: Option<&str>,
+        category_id: Option<&str>,
+        brand_id: Option<&str>,
+        query: Option<&str>,
+        is_shippable: Option<bool>,
+        min_price: Option<f64>,
+        max_price: Option<f64>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<String>> {
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT id FROM products WHERE parent_id IS NULL AND _status != 'deleted'
+            AND id IN (SELECT COALESCE(parent_id, id) FROM products WHERE _status != 'deleted'",
+        );
+
+        push_filters(
+            &mut builder,
+            status,
+            category_id,
+            brand_id,
+            query,
+            is_shippable,
+            min_price,
+            max_price,
+        );
+
+        builder.push(") ORDER BY created_at DESC");
+        builder.push(" LIMIT ");
+        builder.push_bind(limit);
+        builder.push(" OFFSET ");
+        builder.push_bind(offset);
+
+        builder
+            .build_query_scalar::<String>()
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// Apply price and status changes to the variants of a parent product;
+    /// `None` fields are left untouched
+    #[allow(clippy::too_many_arguments)]
+    pub async fn bulk_update_variants_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        parent_id: &str,
+        variant_ids: Option<&[String]>,
+        price: Option<f64>,
+        promotional_price: Option<f64>,
+        cost_price: Option<f64>,
+        status: Option<&str>,
+    ) -> Result<u64> {
+        let mut builder = QueryBuilder::<Sqlite>::new("UPDATE products SET price = COALESCE(");
+        builder.push_bind(price);
+        builder.push(", price), promotional_price = COALESCE(");
+        builder.push_bind(promotional_price);
+        builder.push(", promotional_price), cost_price = COALESCE(");
+        builder.push_bind(cost_price);
+        builder.push(", cost_price), status = COALESCE(");
+        builder.push_bind(status);
+        builder.push(
+            ", status), _status = 'modified', updated_at = datetime('now')
+            WHERE _status != 'deleted' AND parent_id = ",
+        );
+        builder.push_bind(parent_id);
+
+        if let Some(variant_ids) = variant_ids {
+            builder.push(" AND id IN (");
+            let mut separated = builder.separated(", ");
+            for id in variant_ids {
+                separated.push_bind(id);
+            }
+            separated.push_unseparated(")");
+        }
+
+        let result = builder.build().execute(&mut **tx).await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Which of the given products are digital goods or services, deleted
+    /// ones included
+    pub async fn non_stock_ids_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        ids: &[String],
+    ) -> Result<Vec<String>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut builder = QueryBuilder::<Sqlite>::new("SELECT id FROM products WHERE type IN (");
+        let mut separated = builder.separated(", ");
+        for product_type in NON_STOCK_TYPES {
+            separated.push_bind(product_type);
+        }
+        separated.push_unseparated(") AND id IN (");
+        let mut separated = builder.separated(", ");
+        for id in ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(")");
+
+        builder
+            .build_query_scalar::<String>()
+            .fetch_all(&mut **tx)
+            .await
+    }
+}
+
+/// Append the product list filters to a query selecting from products
+#[allow(clippy::too_many_arguments)]
+fn push_filters<'a>(
+    builder: &mut QueryBuilder<'a, Sqlite>,
+    status: Option<&'a str>,
+    category_id: Option<&'a str>,
+    brand_id: Option<&'a str>,
+    query: Option<&'a str>,
+    is_shippable: Option<bool>,
+    min_price: Option<f64>,
+    max_price: Option<f64>,
+) {
+    if let Some(status) = status {
+        builder.push(" AND status = ");
+        builder.push_bind(status);
+    }
+
+    if let Some(category_id) = category_id {
+        builder.push(" AND category_id = ");
+        builder.push_bind(category_id);
+    }
+
+    if let Some(brand_id) = brand_id {
+        builder.push(" AND brand_id = ");
+        builder.push_bind(brand_id);
+    }
+
+    if let Some(match_query) = query.and_then(build_match_query) {
+        builder.push(" AND id IN (");
+        builder.push(PRODUCT_MATCH_IDS);
+        builder.push_bind(match_query);
+        builder.push(")");
+    }
+
+    if let Some(is_shippable) = is_shippable {
+        builder.push(" AND is_shippable = ");
+        builder.push_bind(is_shippable);
+    }
+
+    if let Some(min_price) = min_price {
+        builder.push(" AND price >= ");
+        builder.push_bind(min_price);
+    }
+
+    if let Some(max_price) = max_price {
+        builder.push(" AND price <= ");
+        builder.push_bind(max_price);
+    }
 }
 
 /// Internal struct for deserializing products from shop database (no shop_id column)
@@ -1,2 +1,3 @@
 pub mod product_service;
 pub mod shop_product_service;
+pub mod shop_product_variant_service;
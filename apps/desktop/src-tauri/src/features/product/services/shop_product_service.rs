@@ -3,6 +3,8 @@
 //! This service operates on a shop-specific database where each shop
 //! has its own isolated database file.
 
+use crate::features::barcode::services::gtin;
+use crate::features::price_history::services::shop_price_history_service::ShopPriceHistoryService;
 use crate::features::product::dtos::product_dto::{CreateProductDTO, ProductListFilterDTO, UpdateProductDTO};
 use crate::features::product::models::product_model::Product;
 use crate::features::product::repositories::shop_product_categories_repository::ShopProductCategoriesRepository;
@@ -35,7 +37,11 @@ impl ShopProductService {
     }
 
     pub async fn create_product(&self, payload: CreateProductDTO) -> Result<Product, String> {
-        let (product, categories) = payload.into_models();
+        let (mut product, categories) = payload.into_models();
+        normalize_gtin(&mut product)?;
+        if !product.tracks_inventory() {
+            product.is_shippable = false;
+        }
 
         let mut tx = self
             .pool
@@ -72,13 +78,51 @@ impl ShopProductService {
             .map_err(|e| format!("Failed to fetch product: {}", e))?
             .ok_or_else(|| format!("Product not found: {}", payload.id))?;
 
+        let changed_by = payload.changed_by.clone();
+        let price_changed = payload.price.is_some() || payload.promotional_price.is_some();
+        let gtin_changed = payload.gtin_ean.is_some();
+
         // Merge updates into existing product
-        let updated = merge_product_update(existing, payload);
+        let mut updated = merge_product_update(existing, payload);
+        if gtin_changed {
+            normalize_gtin(&mut updated)?;
+        }
+        if !updated.tracks_inventory() {
+            updated.is_shippable = false;
+        }
 
-        self.repo
-            .update(&updated)
+        let mut tx = self
+            .pool
+            .begin()
             .await
-            .map_err(|e| format!("Failed to update product: {}", e))
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        if price_changed {
+            ShopPriceHistoryService::ensure_open_in_tx(&mut tx, &updated.id).await?;
+        }
+
+        let saved = self
+            .repo
+            .update_in_tx(&mut tx, &updated)
+            .await
+            .map_err(|e| format!("Failed to update product: {}", e))?;
+
+        if price_changed {
+            ShopPriceHistoryService::record_in_tx(
+                &mut tx,
+                &saved.id,
+                "manual",
+                None,
+                changed_by.as_deref(),
+            )
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(saved)
     }
 
     pub async fn delete_product(&self, id: &str) -> Result<(), String> {
@@ -95,8 +139,8 @@ impl ShopProductService {
             .await
             .map_err(|e| format!("Failed to delete product categories: {}", e))?;
 
-        // Soft delete product
-        sqlx::query("UPDATE products SET _status = 'deleted', updated_at = datetime('now') WHERE id = $1")
+        // Soft delete product and its variants
+        sqlx::query("UPDATE products SET _status = 'deleted', updated_at = datetime('now') WHERE id = $1 OR parent_id = $1")
             .bind(id)
             .execute(&mut *tx)
             .await
@@ -155,6 +199,20 @@ impl ShopProductService {
     }
 }
 
+/// Trim the GTIN, dropping it when blank, and validate its check digit
+fn normalize_gtin(product: &mut Product) -> Result<(), String> {
+    product.gtin_ean = product
+        .gtin_ean
+        .as_deref()
+        .map(str::trim)
+        .filter(|gtin| !gtin.is_empty())
+        .map(str::to_string);
+    if let Some(gtin) = &product.gtin_ean {
+        gtin::validate_gtin(gtin)?;
+    }
+    Ok(())
+}
+
 /// Helper function to merge update DTO into existing product
 fn merge_product_update(mut product: Product, update: UpdateProductDTO) -> Product {
     if let Some(sku) = update.sku {
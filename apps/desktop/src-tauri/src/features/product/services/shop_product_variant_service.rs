@@ -0,0 +1,585 @@
+//! Shop-scoped Product Variant Service for Multi-Database Architecture
+//!
+//! A parent product defines option axes (size, color...) and its variants are
+//! child products (`parent_id`) with their own SKU, GTIN, price and stock.
+//! Each variant records its value per axis under `attributes.options`.
+
+use crate::features::inventory::models::inventory_level_model::InventoryLevel;
+use crate::features::inventory::repositories::inventory_movements_repository::InventoryMovementsRepository;
+use crate::features::inventory::repositories::shop_inventory_repository::ShopInventoryRepository;
+use crate::features::price_history::services::shop_price_history_service::ShopPriceHistoryService;
+use crate::features::product::dtos::product_dto::{
+    BulkSetVariantStockDTO, BulkUpdateVariantsDTO, GenerateVariantsDTO, ProductListFilterDTO,
+    SetProductOptionsDTO,
+};
+use crate::features::product::models::product_model::{Product, ProductGroup, ProductOption};
+use crate::features::product::repositories::shop_product_option_repository::ShopProductOptionRepository;
+use crate::features::product::repositories::shop_product_repository::ShopProductRepository;
+use crate::features::transaction::models::transaction_model::InventoryMovement;
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Upper bound on the size of a generated variant matrix
+const MAX_VARIANTS: usize = 500;
+
+/// Option values of a variant, read from `attributes.options`
+fn variant_options(product: &Product) -> Map<String, Value> {
+    product
+        .attributes
+        .as_deref()
+        .and_then(|a| serde_json::from_str::<Value>(a).ok())
+        .and_then(|a| a.get("options").and_then(|o| o.as_object().cloned()))
+        .unwrap_or_default()
+}
+
+/// Turn an option value into a SKU or slug segment
+fn code_part(value: &str) -> String {
+    value
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Every combination of one value per option, in option order
+fn combinations(options: &[(String, Vec<String>)]) -> Vec<Vec<(String, String)>> {
+    let mut result: Vec<Vec<(String, String)>> = vec![Vec::new()];
+    for (name, values) in options {
+        result = result
+            .into_iter()
+            .flat_map(|combination| {
+                values.iter().map(move |value| {
+                    let mut next = combination.clone();
+                    next.push((name.clone(), value.clone()));
+                    next
+                })
+            })
+            .collect();
+    }
+    result
+}
+
+pub struct ShopProductVariantService {
+    pool: Arc<SqlitePool>,
+    repo: ShopProductRepository,
+    option_repo: ShopProductOptionRepository,
+}
+
+impl ShopProductVariantService {
+    pub fn new(pool: Arc<SqlitePool>, shop_id: String) -> Self {
+        let repo = ShopProductRepository::new(pool.clone(), shop_id);
+        let option_repo = ShopProductOptionRepository::new(pool.clone());
+        Self {
+            pool,
+            repo,
+            option_repo,
+        }
+    }
+
+    pub fn pool(&self) -> Arc<SqlitePool> {
+        self.pool.clone()
+    }
+
+    /// Fetch a product that can hold variants (it must not be a variant itself)
+    async fn get_parent(&self, product_id: &str) -> Result<Product, String> {
+        let product = self
+            .repo
+            .get_by_id(product_id)
+            .await
+            .map_err(|e| format!("Failed to fetch product: {}", e))?
+            .ok_or_else(|| format!("Product not found: {}", product_id))?;
+
+        if product.parent_id.is_some() {
+            return Err("A variant cannot have variants of its own".to_string());
+        }
+        Ok(product)
+    }
+
+    // ============================================================
+    // Options
+    // ============================================================
+
+    pub async fn list_options(&self, product_id: &str) -> Result<Vec<ProductOption>, String> {
+        self.option_repo
+            .list_by_product(product_id)
+            .await
+            .map_err(|e| format!("Failed to list product options: {}", e))
+    }
+
+    /// Replace the option axes of a parent product. Existing variants are kept;
+    /// values no longer offered are simply not generated again.
+    pub async fn set_options(
+        &self,
+        mut payload: SetProductOptionsDTO,
+    ) -> Result<Vec<ProductOption>, String> {
+        self.get_parent(&payload.product_id).await?;
+
+        let mut names: Vec<String> = Vec::new();
+        for option in payload.options.iter_mut() {
+            let name = option.name.trim().to_string();
+            if name.is_empty() {
+                return Err("Option name is required".to_string());
+            }
+            if names.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+                return Err(format!("Duplicate option: {}", name));
+            }
+
+            let mut values: Vec<String> = Vec::new();
+            for value in option.values.iter().map(|v| v.trim()) {
+                if !value.is_empty() && !values.iter().any(|v| v.eq_ignore_ascii_case(value)) {
+                    values.push(value.to_string());
+                }
+            }
+            if values.is_empty() {
+                return Err(format!("Option {} has no values", name));
+            }
+
+            option.values = values;
+            names.push(name);
+        }
+
+        let product_id = payload.product_id.clone();
+        let options = payload.into_models();
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let created = ShopProductOptionRepository::replace_in_tx(&mut tx, &product_id, &options)
+            .await
+            .map_err(|e| format!("Failed to save product options: {}", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(created)
+    }
+
+    // ============================================================
+    // Variants
+    // ============================================================
+
+    pub async fn list_variants(&self, product_id: &str) -> Result<Vec<Product>, String> {
+        self.repo
+            .list_variants(product_id)
+            .await
+            .map_err(|e| format!("Failed to list variants: {}", e))
+    }
+
+    /// Create a child product for every option combination that has no variant yet.
+    /// Variants inherit the parent's catalog data; SKU and slug get the values appended.
+    pub async fn generate_variants(
+        &self,
+        payload: GenerateVariantsDTO,
+    ) -> Result<Vec<Product>, String> {
+        let parent = self.get_parent(&payload.product_id).await?;
+        let options = self.list_options(&parent.id).await?;
+        if options.is_empty() {
+            return Err("Product has no options to generate variants from".to_string());
+        }
+
+        let axes: Vec<(String, Vec<String>)> = options
+            .iter()
+            .map(|o| {
+                let values: Vec<String> =
+                    serde_json::from_str(&o.option_values).unwrap_or_default();
+                (o.name.clone(), values)
+            })
+            .collect();
+
+        let matrix_size: usize = axes.iter().map(|(_, values)| values.len()).product();
+        if matrix_size > MAX_VARIANTS {
+            return Err(format!(
+                "Options would generate {} variants; the limit is {}",
+                matrix_size, MAX_VARIANTS
+            ));
+        }
+
+        let existing: Vec<Map<String, Value>> = self
+            .list_variants(&parent.id)
+            .await?
+            .iter()
+            .map(variant_options)
+            .collect();
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let mut created = Vec::new();
+        for combination in combinations(&axes) {
+            let values: Map<String, Value> = combination
+                .iter()
+                .map(|(name, value)| (name.clone(), json!(value)))
+                .collect();
+            if existing.contains(&values) {
+                continue;
+            }
+
+            let labels: Vec<&str> = combination.iter().map(|(_, v)| v.as_str()).collect();
+            let codes: Vec<String> = labels.iter().map(|v| code_part(v)).collect();
+            let now = Utc::now();
+
+            let variant = Product {
+                id: Uuid::new_v4().to_string(),
+                shop_id: parent.shop_id.clone(),
+                sku: format!("{}-{}", parent.sku, codes.join("-").to_uppercase()),
+                r#type: parent.r#type.clone(),
+                status: payload.status.clone().or_else(|| parent.status.clone()),
+                name: format!("{} - {}", parent.name, labels.join(" / ")),
+                slug: format!("{}-{}", parent.slug, codes.join("-").to_lowercase()),
+                gtin_ean: None,
+                price: payload.price.unwrap_or(parent.price),
+                promotional_price: parent.promotional_price,
+                cost_price: parent.cost_price,
+                currency: parent.currency.clone(),
+                tax_ncm: parent.tax_ncm.clone(),
+                is_shippable: parent.is_shippable,
+                weight_g: parent.weight_g,
+                width_mm: parent.width_mm,
+                height_mm: parent.height_mm,
+                depth_mm: parent.depth_mm,
+                attributes: Some(json!({ "options": values }).to_string()),
+                metadata: None,
+                category_id: parent.category_id.clone(),
+                brand_id: parent.brand_id.clone(),
+                parent_id: Some(parent.id.clone()),
+                sync_status: Some("created".to_string()),
+                created_at: Some(now),
+                updated_at: Some(now),
+            };
+
+            let row = self
+                .repo
+                .create_in_tx(&mut tx, &variant)
+                .await
+                .map_err(|e| format!("Failed to create variant {}: {}", variant.sku, e))?;
+            created.push(row);
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(created)
+    }
+
+    /// Set prices and/or status on all (or the selected) variants of a product
+    pub async fn bulk_update_variants(
+        &self,
+        payload: BulkUpdateVariantsDTO,
+    ) -> Result<Vec<Product>, String> {
+        if payload
+            .variant_ids
+            .as_ref()
+            .is_some_and(|ids| ids.is_empty())
+        {
+            return Err("No variants selected".to_string());
+        }
+        for price in [payload.price, payload.promotional_price, payload.cost_price]
+            .into_iter()
+            .flatten()
+        {
+            if price < 0.0 {
+                return Err("Prices cannot be negative".to_string());
+            }
+        }
+
+        // Variants whose prices change get a price history entry
+        let repriced: Vec<String> =
+            if payload.price.is_some() || payload.promotional_price.is_some() {
+                match &payload.variant_ids {
+                    Some(ids) => ids.clone(),
+                    None => self
+                        .list_variants(&payload.product_id)
+                        .await?
+                        .into_iter()
+                        .map(|v| v.id)
+                        .collect(),
+                }
+            } else {
+                Vec::new()
+            };
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        for variant_id in &repriced {
+            ShopPriceHistoryService::ensure_open_in_tx(&mut tx, variant_id).await?;
+        }
+
+        ShopProductRepository::bulk_update_variants_in_tx(
+            &mut tx,
+            &payload.product_id,
+            payload.variant_ids.as_deref(),
+            payload.price,
+            payload.promotional_price,
+            payload.cost_price,
+            payload.status.as_deref(),
+        )
+        .await
+        .map_err(|e| format!("Failed to update variants: {}", e))?;
+
+        for variant_id in &repriced {
+            ShopPriceHistoryService::record_in_tx(
+                &mut tx,
+                variant_id,
+                "bulk",
+                None,
+                payload.changed_by.as_deref(),
+            )
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        self.list_variants(&payload.product_id).await
+    }
+
+    /// Set the sellable stock of several variants at one location. Differences
+    /// are recorded as adjustment movements, which update the levels.
+    pub async fn bulk_set_variant_stock(
+        &self,
+        payload: BulkSetVariantStockDTO,
+    ) -> Result<Vec<InventoryLevel>, String> {
+        let variants = self.list_variants(&payload.product_id).await?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let mut level_ids = Vec::with_capacity(payload.items.len());
+        for item in &payload.items {
+            if item.quantity < 0.0 {
+                return Err("Stock quantity cannot be negative".to_string());
+            }
+            if !variants.iter().any(|v| v.id == item.variant_id) {
+                return Err(format!(
+                    "Product {} is not a variant of {}",
+                    item.variant_id, payload.product_id
+                ));
+            }
+
+            let now = Utc::now();
+            let existing = ShopInventoryRepository::find_level_by_stock_status_in_tx(
+                &mut tx,
+                &item.variant_id,
+                &payload.location_id,
+                "sellable",
+            )
+            .await
+            .map_err(|e| format!("Failed to fetch inventory level: {}", e))?;
+
+            let level = match existing {
+                Some(level) => level,
+                None => {
+                    let level = InventoryLevel {
+                        id: Uuid::new_v4().to_string(),
+                        product_id: item.variant_id.clone(),
+                        location_id: payload.location_id.clone(),
+                        batch_number: None,
+                        serial_number: None,
+                        expiry_date: None,
+                        quantity_on_hand: 0.0,
+                        quantity_reserved: 0.0,
+                        stock_status: Some("sellable".to_string()),
+                        aisle_bin_slot: None,
+                        last_counted_at: None,
+                        sync_status: Some("created".to_string()),
+                        created_at: Some(now),
+                        updated_at: Some(now),
+                    };
+                    ShopInventoryRepository::create_level_in_tx(&mut tx, &level)
+                        .await
+                        .map_err(|e| format!("Failed to create inventory level: {}", e))?
+                }
+            };
+
+            let difference = item.quantity - level.quantity_on_hand;
+            if difference.abs() >= 0.001 {
+                let (movement_type, quantity) = if difference > 0.0 {
+                    ("in", difference)
+                } else {
+                    ("out", difference.abs())
+                };
+
+                let movement = InventoryMovement {
+                    id: Uuid::new_v4().to_string(),
+                    transaction_id: None, // Adjustment doesn't have a transaction
+                    inventory_level_id: Some(level.id.clone()),
+                    movement_type: Some(movement_type.to_string()),
+                    quantity,
+                    previous_balance: Some(level.quantity_on_hand),
+                    new_balance: Some(item.quantity),
+                    sync_status: Some("created".to_string()),
+                    created_at: Some(now),
+                    updated_at: Some(now),
+                };
+
+                // The trigger 'trg_inventory_movement_update_level' updates quantity_on_hand
+                InventoryMovementsRepository::create_with_tx(&mut tx, movement)
+                    .await
+                    .map_err(|e| format!("Failed to create inventory movement: {}", e))?;
+            }
+
+            level_ids.push(level.id);
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        let inventory_repo = ShopInventoryRepository::new(self.pool.clone());
+        let mut levels = Vec::with_capacity(level_ids.len());
+        for id in level_ids {
+            if let Some(level) = inventory_repo
+                .get_level_by_id(&id)
+                .await
+                .map_err(|e| format!("Failed to fetch inventory level: {}", e))?
+            {
+                levels.push(level);
+            }
+        }
+        Ok(levels)
+    }
+
+    // ============================================================
+    // Grouped listing
+    // ============================================================
+
+    /// A product with its options and variants; a variant id resolves to its parent
+    pub async fn get_product_group(&self, id: &str) -> Result<Option<ProductGroup>, String> {
+        let product = match self
+            .repo
+            .get_by_id(id)
+            .await
+            .map_err(|e| format!("Failed to fetch product: {}", e))?
+        {
+            Some(product) => product,
+            None => return Ok(None),
+        };
+
+        let product = match &product.parent_id {
+            Some(parent_id) => match self
+                .repo
+                .get_by_id(parent_id)
+                .await
+                .map_err(|e| format!("Failed to fetch product: {}", e))?
+            {
+                Some(parent) => parent,
+                None => return Ok(None),
+            },
+            None => product,
+        };
+
+        let options = self.list_options(&product.id).await?;
+        let variants = self.list_variants(&product.id).await?;
+        Ok(Some(ProductGroup {
+            product,
+            options,
+            variants,
+        }))
+    }
+
+    /// Groups of top-level products, in the order of `root_ids`, loaded with
+    /// one query each for the products, their options and their variants
+    async fn load_groups(&self, root_ids: Vec<String>) -> Result<Vec<ProductGroup>, String> {
+        let mut products: HashMap<String, Product> = self
+            .repo
+            .list_by_ids(&root_ids)
+            .await
+            .map_err(|e| format!("Failed to fetch products: {}", e))?
+            .into_iter()
+            .map(|p| (p.id.clone(), p))
+            .collect();
+
+        let mut options: HashMap<String, Vec<ProductOption>> = HashMap::new();
+        for option in self
+            .option_repo
+            .list_by_products(&root_ids)
+            .await
+            .map_err(|e| format!("Failed to list product options: {}", e))?
+        {
+            options.entry(option.product_id.clone()).or_default().push(option);
+        }
+
+        let mut variants: HashMap<String, Vec<Product>> = HashMap::new();
+        for variant in self
+            .repo
+            .list_variants_by_parents(&root_ids)
+            .await
+            .map_err(|e| format!("Failed to list variants: {}", e))?
+        {
+            if let Some(parent_id) = variant.parent_id.clone() {
+                variants.entry(parent_id).or_default().push(variant);
+            }
+        }
+
+        Ok(root_ids
+            .iter()
+            .filter_map(|id| {
+                products.remove(id).map(|product| ProductGroup {
+                    options: options.remove(id).unwrap_or_default(),
+                    variants: variants.remove(id).unwrap_or_default(),
+                    product,
+                })
+            })
+            .collect())
+    }
+
+    /// Paginated product listing with variants grouped under their parent.
+    /// A parent is listed when it or any of its variants matches the filters.
+    pub async fn list_products_grouped(
+        &self,
+        filters: ProductListFilterDTO,
+    ) -> Result<Vec<ProductGroup>, String> {
+        let page = filters.page.unwrap_or(1).max(1);
+        let per_page = filters.per_page.unwrap_or(20).clamp(1, 100);
+        let offset = ((page - 1) * per_page) as i64;
+
+        let root_ids = self
+            .repo
+            .list_filtered_root_ids(
+                filters.status.as_deref(),
+                filters.category_id.as_deref(),
+                filters.brand_id.as_deref(),
+                filters.query.as_deref(),
+                filters.is_shippable,
+                filters.min_price,
+                filters.max_price,
+                per_page as i64,
+                offset,
+            )
+            .await
+            .map_err(|e| format!("Failed to list filtered products: {}", e))?;
+
+        self.load_groups(root_ids).await
+    }
+
+    /// Search by name, SKU or GTIN; a matching variant returns its whole group
+    pub async fn search_products_grouped(&self, query: &str) -> Result<Vec<ProductGroup>, String> {
+        let root_ids = self
+            .repo
+            .list_filtered_root_ids(None, None, None, Some(query), None, None, None, 100, 0)
+            .await
+            .map_err(|e| format!("Failed to search products: {}", e))?;
+
+        self.load_groups(root_ids).await
+    }
+}
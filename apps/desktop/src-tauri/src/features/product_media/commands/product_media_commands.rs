@@ -0,0 +1,99 @@
+use crate::db::RepositoryFactory;
+use crate::features::product_media::dtos::product_media_dto::{
+    AttachProductMediaDTO, ReorderProductMediaDTO, UpdateProductMediaDTO,
+};
+use crate::features::product_media::models::product_media_model::{
+    MediaCleanupResult, ProductCover, ProductMediaItem, StorageConfig,
+};
+use crate::features::product_media::services::shop_product_media_service::ShopProductMediaService;
+use crate::features::shop::services::shop_service::ShopService;
+use std::sync::Arc;
+use tauri::State;
+
+/// Media service configured with the shop's storage settings
+pub async fn media_service(
+    repo_factory: &RepositoryFactory,
+    shop_id: String,
+) -> Result<ShopProductMediaService, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let shop = ShopService::new(repo_factory.registry_pool().clone())
+        .get_shop(&shop_id)
+        .await?;
+    let storage_config =
+        StorageConfig::from_shop_config(shop.and_then(|s| s.storage_config).as_deref());
+    let media_dir = repo_factory.pool_manager().get_shop_media_dir(&shop_id);
+
+    ShopProductMediaService::new(pool, shop_id, &storage_config, media_dir)
+}
+
+#[tauri::command]
+pub async fn attach_product_media(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: AttachProductMediaDTO,
+) -> Result<ProductMediaItem, String> {
+    let service = media_service(&repo_factory, shop_id).await?;
+    service.attach_product_media(payload).await
+}
+
+#[tauri::command]
+pub async fn list_product_media(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    product_id: String,
+) -> Result<Vec<ProductMediaItem>, String> {
+    let service = media_service(&repo_factory, shop_id).await?;
+    service.list_product_media(&product_id).await
+}
+
+#[tauri::command]
+pub async fn get_product_covers(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    product_ids: Vec<String>,
+) -> Result<Vec<ProductCover>, String> {
+    let service = media_service(&repo_factory, shop_id).await?;
+    service.get_product_covers(product_ids).await
+}
+
+#[tauri::command]
+pub async fn update_product_media(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: UpdateProductMediaDTO,
+) -> Result<ProductMediaItem, String> {
+    let service = media_service(&repo_factory, shop_id).await?;
+    service.update_product_media(payload).await
+}
+
+#[tauri::command]
+pub async fn reorder_product_media(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: ReorderProductMediaDTO,
+) -> Result<Vec<ProductMediaItem>, String> {
+    let service = media_service(&repo_factory, shop_id).await?;
+    service.reorder_product_media(payload).await
+}
+
+#[tauri::command]
+pub async fn detach_product_media(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+) -> Result<MediaCleanupResult, String> {
+    let service = media_service(&repo_factory, shop_id).await?;
+    service.detach_product_media(&id).await
+}
+
+#[tauri::command]
+pub async fn purge_orphan_media(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+) -> Result<MediaCleanupResult, String> {
+    let service = media_service(&repo_factory, shop_id).await?;
+    service.purge_orphan_media().await
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttachProductMediaDTO {
+    pub product_id: String, // Product or variant
+    pub path: String,       // Local file to import
+    pub alt_text: Option<String>,
+    pub position: Option<i64>, // Appended when absent
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateProductMediaDTO {
+    pub id: String,
+    pub alt_text: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReorderProductMediaDTO {
+    pub product_id: String,
+    pub media_ids: Vec<String>, // Every media entry of the product, in the new order
+}
@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// A stored file, shared by every product that shows it
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct MediaAsset {
+    pub id: String,
+    pub content_hash: String, // SHA-256 of the file, hex
+    pub media_type: String,   // 'image', 'video'
+    pub mime_type: String,
+    pub file_size: i64,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub storage_provider: String, // 'local', 's3'
+    pub storage_key: String,
+    pub url: Option<String>, // Remote storage only
+    pub thumbnail_key: Option<String>,
+    pub thumbnail_url: Option<String>,
+    pub original_filename: Option<String>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A media asset placed on a product or variant
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ProductMedia {
+    pub id: String,
+    pub product_id: String,
+    pub asset_id: String,
+    pub position: i64, // Lowest position is the cover
+    pub alt_text: Option<String>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A product media entry with its asset, ready to display. Local files are
+/// given by absolute path, remote ones by URL.
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ProductMediaItem {
+    pub id: String,
+    pub product_id: String,
+    pub asset_id: String,
+    pub position: i64,
+    pub alt_text: Option<String>,
+    pub media_type: String,
+    pub mime_type: String,
+    pub file_size: i64,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub storage_provider: String,
+    pub storage_key: String,
+    pub url: Option<String>,
+    pub thumbnail_key: Option<String>,
+    pub thumbnail_url: Option<String>,
+    pub original_filename: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    #[sqlx(skip)]
+    pub file_path: Option<String>,
+    #[sqlx(skip)]
+    pub thumbnail_path: Option<String>,
+}
+
+/// Cover image of a product, falling back to its parent's for variants
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProductCover {
+    pub product_id: String,
+    pub inherited: bool, // Taken from the parent product
+    pub media: ProductMediaItem,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MediaCleanupResult {
+    pub assets_removed: usize,
+    pub bytes_freed: i64,
+    pub failed_keys: Vec<String>, // Files that could not be deleted from storage
+}
+
+/// S3-compatible bucket settings, from `storage_config.s3`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct S3Config {
+    pub endpoint: String, // e.g. https://s3.us-east-1.amazonaws.com
+    pub region: String,
+    pub bucket: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub prefix: Option<String>,          // Prepended to every key
+    pub public_base_url: Option<String>, // CDN or public bucket URL
+    #[serde(default = "default_path_style")]
+    pub path_style: bool, // endpoint/bucket/key rather than bucket.endpoint/key
+}
+
+fn default_path_style() -> bool {
+    true
+}
+
+/// The shop's `storage_config` JSON: where new media files are stored
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct StorageConfig {
+    pub provider: Option<String>, // 'local' (default) or 's3'
+    pub s3: Option<S3Config>,
+}
+
+impl StorageConfig {
+    /// Settings from the shop storage_config JSON, local storage when absent
+    pub fn from_shop_config(storage_config: Option<&str>) -> Self {
+        storage_config
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn provider(&self) -> &str {
+        self.provider.as_deref().unwrap_or("local")
+    }
+}
@@ -0,0 +1,234 @@
+//! Shop-scoped Product Media Repository for Multi-Database Architecture
+
+use crate::features::product_media::models::product_media_model::{
+    MediaAsset, ProductMedia, ProductMediaItem,
+};
+use sqlx::{QueryBuilder, Result, Sqlite, SqlitePool, Transaction};
+use std::sync::Arc;
+
+const ITEM_SELECT: &str = r#"
+    SELECT
+        pm.id, pm.product_id, pm.asset_id, pm.position, pm.alt_text,
+        a.media_type, a.mime_type, a.file_size, a.width, a.height,
+        a.storage_provider, a.storage_key, a.url, a.thumbnail_key, a.thumbnail_url,
+        a.original_filename, pm.created_at
+    FROM product_media pm
+    INNER JOIN media_assets a ON a.id = pm.asset_id
+"#;
+
+pub struct ShopProductMediaRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopProductMediaRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    // ============================================================
+    // Assets
+    // ============================================================
+
+    pub async fn find_asset_by_hash(&self, content_hash: &str) -> Result<Option<MediaAsset>> {
+        let sql = "SELECT * FROM media_assets WHERE content_hash = $1";
+        sqlx::query_as::<_, MediaAsset>(sql)
+            .bind(content_hash)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    /// Insert an asset unless one with the same content exists, and return
+    /// the stored one
+    pub async fn create_asset(&self, asset: &MediaAsset) -> Result<MediaAsset> {
+        let sql = r#"
+            INSERT INTO media_assets (
+                id, content_hash, media_type, mime_type, file_size, width, height,
+                storage_provider, storage_key, url, thumbnail_key, thumbnail_url,
+                original_filename, _status, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+            ON CONFLICT (content_hash) DO NOTHING
+        "#;
+        sqlx::query(sql)
+            .bind(&asset.id)
+            .bind(&asset.content_hash)
+            .bind(&asset.media_type)
+            .bind(&asset.mime_type)
+            .bind(asset.file_size)
+            .bind(asset.width)
+            .bind(asset.height)
+            .bind(&asset.storage_provider)
+            .bind(&asset.storage_key)
+            .bind(&asset.url)
+            .bind(&asset.thumbnail_key)
+            .bind(&asset.thumbnail_url)
+            .bind(&asset.original_filename)
+            .bind(&asset.sync_status)
+            .bind(asset.created_at)
+            .bind(asset.updated_at)
+            .execute(&*self.pool)
+            .await?;
+
+        let sql = "SELECT * FROM media_assets WHERE content_hash = $1";
+        sqlx::query_as::<_, MediaAsset>(sql)
+            .bind(&asset.content_hash)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    /// Assets no product refers to
+    pub async fn list_orphan_assets(&self) -> Result<Vec<MediaAsset>> {
+        let sql = r#"
+            SELECT * FROM media_assets a
+            WHERE NOT EXISTS (SELECT 1 FROM product_media pm WHERE pm.asset_id = a.id)
+        "#;
+        sqlx::query_as::<_, MediaAsset>(sql)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// Delete an asset if it is still unreferenced. Returns whether it was
+    /// deleted.
+    pub async fn delete_orphan_asset(&self, id: &str) -> Result<bool> {
+        let sql = r#"
+            DELETE FROM media_assets
+            WHERE id = $1
+              AND NOT EXISTS (SELECT 1 FROM product_media pm WHERE pm.asset_id = $1)
+        "#;
+        let result = sqlx::query(sql).bind(id).execute(&*self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ============================================================
+    // Product media
+    // ============================================================
+
+    pub async fn get_by_id(&self, id: &str) -> Result<Option<ProductMedia>> {
+        let sql = "SELECT * FROM product_media WHERE id = $1";
+        sqlx::query_as::<_, ProductMedia>(sql)
+            .bind(id)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn get_item(&self, id: &str) -> Result<Option<ProductMediaItem>> {
+        let sql = format!("{} WHERE pm.id = $1", ITEM_SELECT);
+        sqlx::query_as::<_, ProductMediaItem>(&sql)
+            .bind(id)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn list_items_by_product(&self, product_id: &str) -> Result<Vec<ProductMediaItem>> {
+        let sql = format!(
+            "{} WHERE pm.product_id = $1 ORDER BY pm.position ASC, pm.created_at ASC",
+            ITEM_SELECT
+        );
+        sqlx::query_as::<_, ProductMediaItem>(&sql)
+            .bind(product_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// First media entry of each product that has one
+    pub async fn list_covers(&self, product_ids: &[String]) -> Result<Vec<ProductMediaItem>> {
+        if product_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut qb = QueryBuilder::<Sqlite>::new(ITEM_SELECT);
+        qb.push(
+            " WHERE pm.id = (SELECT first.id FROM product_media first \
+             WHERE first.product_id = pm.product_id \
+             ORDER BY first.position ASC, first.created_at ASC LIMIT 1) \
+             AND pm.product_id IN (",
+        );
+        let mut separated = qb.separated(", ");
+        for id in product_ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(")");
+        qb.build_query_as::<ProductMediaItem>()
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn list_by_product_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        product_id: &str,
+    ) -> Result<Vec<ProductMedia>> {
+        let sql = r#"
+            SELECT * FROM product_media
+            WHERE product_id = $1
+            ORDER BY position ASC, created_at ASC
+        "#;
+        sqlx::query_as::<_, ProductMedia>(sql)
+            .bind(product_id)
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    pub async fn create_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        media: &ProductMedia,
+    ) -> Result<ProductMedia> {
+        let sql = r#"
+            INSERT INTO product_media (
+                id, product_id, asset_id, position, alt_text, _status, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, ProductMedia>(sql)
+            .bind(&media.id)
+            .bind(&media.product_id)
+            .bind(&media.asset_id)
+            .bind(media.position)
+            .bind(&media.alt_text)
+            .bind(&media.sync_status)
+            .bind(media.created_at)
+            .bind(media.updated_at)
+            .fetch_one(&mut **tx)
+            .await
+    }
+
+    pub async fn set_position_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+        position: i64,
+    ) -> Result<()> {
+        let sql = r#"
+            UPDATE product_media
+            SET position = $2, _status = 'updated', updated_at = datetime('now')
+            WHERE id = $1 AND position != $2
+        "#;
+        sqlx::query(sql)
+            .bind(id)
+            .bind(position)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn update_alt_text(&self, id: &str, alt_text: Option<&str>) -> Result<()> {
+        let sql = r#"
+            UPDATE product_media
+            SET alt_text = $2, _status = 'updated', updated_at = datetime('now')
+            WHERE id = $1
+        "#;
+        sqlx::query(sql)
+            .bind(id)
+            .bind(alt_text)
+            .execute(&*self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_in_tx(tx: &mut Transaction<'_, Sqlite>, id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM product_media WHERE id = $1")
+            .bind(id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1 @@
+pub mod shop_product_media_service;
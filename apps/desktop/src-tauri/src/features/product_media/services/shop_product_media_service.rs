@@ -0,0 +1,523 @@
+//! Shop-scoped Product Media Service for Multi-Database Architecture
+//!
+//! Files are deduplicated by content hash: attaching the same photo to many
+//! products stores it once. Assets nothing refers to any more are removed,
+//! with their files, by `purge_orphan_media`.
+
+use crate::features::product::repositories::shop_product_repository::ShopProductRepository;
+use crate::features::product_media::dtos::product_media_dto::{
+    AttachProductMediaDTO, ReorderProductMediaDTO, UpdateProductMediaDTO,
+};
+use crate::features::product_media::models::product_media_model::{
+    MediaAsset, MediaCleanupResult, ProductCover, ProductMedia, ProductMediaItem, StorageConfig,
+};
+use crate::features::product_media::repositories::shop_product_media_repository::ShopProductMediaRepository;
+use crate::features::product_media::storage::media_storage::MediaStorages;
+use chrono::Utc;
+use image::{GenericImageView, ImageFormat};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const MAX_IMAGE_BYTES: u64 = 25 * 1024 * 1024;
+const MAX_VIDEO_BYTES: u64 = 250 * 1024 * 1024;
+const THUMBNAIL_SIZE: u32 = 320;
+
+/// A file read from disk, identified and ready to store
+struct ProcessedMedia {
+    bytes: Vec<u8>,
+    content_hash: String,
+    media_type: &'static str,
+    mime_type: &'static str,
+    extension: &'static str,
+    width: Option<i64>,
+    height: Option<i64>,
+    thumbnail: Option<(Vec<u8>, &'static str)>, // Bytes and extension
+}
+
+/// Identify a video container from its first bytes
+fn sniff_video(bytes: &[u8]) -> Option<(&'static str, &'static str)> {
+    if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" {
+        if &bytes[8..12] == b"qt  " {
+            return Some(("video/quicktime", "mov"));
+        }
+        return Some(("video/mp4", "mp4"));
+    }
+    if bytes.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        return Some(("video/webm", "webm"));
+    }
+    None
+}
+
+/// Read, identify and hash a media file, and make the thumbnail of images.
+/// The file type is taken from its content, not its name.
+fn process_file(path: &Path) -> Result<ProcessedMedia, String> {
+    let size = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+        .len();
+    if size > MAX_VIDEO_BYTES {
+        return Err(format!(
+            "File is too large ({} bytes, at most {} allowed)",
+            size, MAX_VIDEO_BYTES
+        ));
+    }
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let content_hash = hex::encode(Sha256::digest(&bytes));
+
+    let image_format = image::guess_format(&bytes)
+        .ok()
+        .and_then(|format| match format {
+            ImageFormat::Jpeg => Some((format, "image/jpeg", "jpg")),
+            ImageFormat::Png => Some((format, "image/png", "png")),
+            ImageFormat::Gif => Some((format, "image/gif", "gif")),
+            ImageFormat::WebP => Some((format, "image/webp", "webp")),
+            _ => None,
+        });
+
+    if let Some((format, mime_type, extension)) = image_format {
+        if size > MAX_IMAGE_BYTES {
+            return Err(format!(
+                "Image is too large ({} bytes, at most {} allowed)",
+                size, MAX_IMAGE_BYTES
+            ));
+        }
+        let decoded = image::load_from_memory_with_format(&bytes, format)
+            .map_err(|e| format!("Failed to decode image: {}", e))?;
+        let (width, height) = decoded.dimensions();
+
+        // Keep transparency in PNG thumbnails, JPEG is smaller otherwise
+        let thumbnail = decoded.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+        let mut thumbnail_bytes = Cursor::new(Vec::new());
+        let thumbnail_extension = if thumbnail.color().has_alpha() {
+            thumbnail
+                .write_to(&mut thumbnail_bytes, ImageFormat::Png)
+                .map_err(|e| format!("Failed to create thumbnail: {}", e))?;
+            "png"
+        } else {
+            thumbnail
+                .to_rgb8()
+                .write_to(&mut thumbnail_bytes, ImageFormat::Jpeg)
+                .map_err(|e| format!("Failed to create thumbnail: {}", e))?;
+            "jpg"
+        };
+
+        return Ok(ProcessedMedia {
+            bytes,
+            content_hash,
+            media_type: "image",
+            mime_type,
+            extension,
+            width: Some(width as i64),
+            height: Some(height as i64),
+            thumbnail: Some((thumbnail_bytes.into_inner(), thumbnail_extension)),
+        });
+    }
+
+    if let Some((mime_type, extension)) = sniff_video(&bytes) {
+        return Ok(ProcessedMedia {
+            bytes,
+            content_hash,
+            media_type: "video",
+            mime_type,
+            extension,
+            width: None,
+            height: None,
+            thumbnail: None,
+        });
+    }
+
+    Err("Unsupported media file (expected JPEG, PNG, GIF, WebP, MP4, MOV or WebM)".to_string())
+}
+
+/// Product media service that operates on a shop-specific database.
+pub struct ShopProductMediaService {
+    pool: Arc<SqlitePool>,
+    shop_id: String,
+    repo: ShopProductMediaRepository,
+    product_repo: ShopProductRepository,
+    storages: MediaStorages,
+}
+
+impl ShopProductMediaService {
+    /// `media_dir` holds the shop's files when stored locally
+    pub fn new(
+        pool: Arc<SqlitePool>,
+        shop_id: String,
+        storage_config: &StorageConfig,
+        media_dir: PathBuf,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            repo: ShopProductMediaRepository::new(pool.clone()),
+            product_repo: ShopProductRepository::new(pool.clone(), shop_id.clone()),
+            storages: MediaStorages::new(storage_config, media_dir)?,
+            pool,
+            shop_id,
+        })
+    }
+
+    pub fn shop_id(&self) -> &str {
+        &self.shop_id
+    }
+
+    /// Fill in the local file paths of an item
+    fn resolve_paths(&self, mut item: ProductMediaItem) -> ProductMediaItem {
+        if let Ok(storage) = self.storages.get(&item.storage_provider) {
+            let path_of = |key: Option<&str>| {
+                key.and_then(|key| storage.local_path(key))
+                    .map(|p| p.to_string_lossy().into_owned())
+            };
+            item.file_path = path_of(Some(&item.storage_key));
+            item.thumbnail_path = path_of(item.thumbnail_key.as_deref());
+        }
+        item
+    }
+
+    /// Store a file, or reuse the asset already holding the same content
+    async fn store_asset(&self, path: &Path) -> Result<MediaAsset, String> {
+        let owned_path = path.to_path_buf();
+        let processed = tokio::task::spawn_blocking(move || process_file(&owned_path))
+            .await
+            .map_err(|e| format!("Failed to process media file: {}", e))??;
+
+        if let Some(existing) = self
+            .repo
+            .find_asset_by_hash(&processed.content_hash)
+            .await
+            .map_err(|e| format!("Failed to fetch media asset: {}", e))?
+        {
+            return Ok(existing);
+        }
+
+        let storage = self.storages.default_storage()?;
+        let hash = &processed.content_hash;
+        let storage_key = format!("originals/{}/{}.{}", &hash[..2], hash, processed.extension);
+        let file_size = processed.bytes.len() as i64;
+
+        let (thumbnail_key, thumbnail_url) = match processed.thumbnail {
+            Some((bytes, extension)) => {
+                let key = format!("thumbnails/{}/{}.{}", &hash[..2], hash, extension);
+                let content_type = if extension == "png" {
+                    "image/png"
+                } else {
+                    "image/jpeg"
+                };
+                let url = storage.put(&key, bytes, content_type).await?;
+                (Some(key), url)
+            }
+            None => (None, None),
+        };
+        let url = storage
+            .put(&storage_key, processed.bytes, processed.mime_type)
+            .await?;
+
+        let now = Utc::now();
+        let asset = MediaAsset {
+            id: Uuid::new_v4().to_string(),
+            content_hash: processed.content_hash,
+            media_type: processed.media_type.to_string(),
+            mime_type: processed.mime_type.to_string(),
+            file_size,
+            width: processed.width,
+            height: processed.height,
+            storage_provider: storage.provider().to_string(),
+            storage_key,
+            url,
+            thumbnail_key,
+            thumbnail_url,
+            original_filename: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned()),
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+
+        self.repo
+            .create_asset(&asset)
+            .await
+            .map_err(|e| format!("Failed to create media asset: {}", e))
+    }
+
+    /// Import a file and attach it to a product or variant
+    pub async fn attach_product_media(
+        &self,
+        payload: AttachProductMediaDTO,
+    ) -> Result<ProductMediaItem, String> {
+        self.product_repo
+            .get_by_id(&payload.product_id)
+            .await
+            .map_err(|e| format!("Failed to fetch product: {}", e))?
+            .ok_or_else(|| format!("Product not found: {}", payload.product_id))?;
+        if payload.position.is_some_and(|p| p < 0) {
+            return Err("Position cannot be negative".to_string());
+        }
+
+        let asset = self.store_asset(Path::new(&payload.path)).await?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let existing =
+            ShopProductMediaRepository::list_by_product_in_tx(&mut tx, &payload.product_id)
+                .await
+                .map_err(|e| format!("Failed to list product media: {}", e))?;
+        if existing.iter().any(|m| m.asset_id == asset.id) {
+            return Err("This file is already attached to the product".to_string());
+        }
+
+        let count = existing.len() as i64;
+        let position = payload.position.map(|p| p.min(count)).unwrap_or(count);
+        for (index, media) in existing.iter().enumerate() {
+            let index = index as i64;
+            let new_position = if index >= position { index + 1 } else { index };
+            ShopProductMediaRepository::set_position_in_tx(&mut tx, &media.id, new_position)
+                .await
+                .map_err(|e| format!("Failed to reorder product media: {}", e))?;
+        }
+
+        let now = Utc::now();
+        let media = ProductMedia {
+            id: Uuid::new_v4().to_string(),
+            product_id: payload.product_id,
+            asset_id: asset.id,
+            position,
+            alt_text: payload.alt_text,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+        let media = ShopProductMediaRepository::create_in_tx(&mut tx, &media)
+            .await
+            .map_err(|e| format!("Failed to attach product media: {}", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        self.get_product_media(&media.id)
+            .await?
+            .ok_or_else(|| format!("Product media not found: {}", media.id))
+    }
+
+    pub async fn get_product_media(&self, id: &str) -> Result<Option<ProductMediaItem>, String> {
+        let item = self
+            .repo
+            .get_item(id)
+            .await
+            .map_err(|e| format!("Failed to fetch product media: {}", e))?;
+        Ok(item.map(|item| self.resolve_paths(item)))
+    }
+
+    /// Media of a product, cover first
+    pub async fn list_product_media(
+        &self,
+        product_id: &str,
+    ) -> Result<Vec<ProductMediaItem>, String> {
+        let items = self
+            .repo
+            .list_items_by_product(product_id)
+            .await
+            .map_err(|e| format!("Failed to list product media: {}", e))?;
+        Ok(items
+            .into_iter()
+            .map(|item| self.resolve_paths(item))
+            .collect())
+    }
+
+    /// Cover of each product, for catalog and POS grids. Variants without
+    /// media of their own show their parent's cover; products with no cover
+    /// at all are left out.
+    pub async fn get_product_covers(
+        &self,
+        product_ids: Vec<String>,
+    ) -> Result<Vec<ProductCover>, String> {
+        let products = self
+            .product_repo
+            .list_by_ids(&product_ids)
+            .await
+            .map_err(|e| format!("Failed to fetch products: {}", e))?;
+        let parents: HashMap<String, String> = products
+            .into_iter()
+            .filter_map(|p| p.parent_id.map(|parent_id| (p.id, parent_id)))
+            .collect();
+
+        let mut lookup_ids: Vec<String> = product_ids.clone();
+        lookup_ids.extend(parents.values().cloned());
+        lookup_ids.sort();
+        lookup_ids.dedup();
+
+        let covers: HashMap<String, ProductMediaItem> = self
+            .repo
+            .list_covers(&lookup_ids)
+            .await
+            .map_err(|e| format!("Failed to list product covers: {}", e))?
+            .into_iter()
+            .map(|item| (item.product_id.clone(), item))
+            .collect();
+
+        let mut seen = HashSet::new();
+        let mut result = Vec::new();
+        for product_id in product_ids {
+            if !seen.insert(product_id.clone()) {
+                continue;
+            }
+            let (media, inherited) = match covers.get(&product_id) {
+                Some(media) => (media, false),
+                None => match parents.get(&product_id).and_then(|p| covers.get(p)) {
+                    Some(media) => (media, true),
+                    None => continue,
+                },
+            };
+            result.push(ProductCover {
+                product_id,
+                inherited,
+                media: self.resolve_paths(media.clone()),
+            });
+        }
+        Ok(result)
+    }
+
+    pub async fn update_product_media(
+        &self,
+        payload: UpdateProductMediaDTO,
+    ) -> Result<ProductMediaItem, String> {
+        self.repo
+            .get_by_id(&payload.id)
+            .await
+            .map_err(|e| format!("Failed to fetch product media: {}", e))?
+            .ok_or_else(|| format!("Product media not found: {}", payload.id))?;
+
+        self.repo
+            .update_alt_text(&payload.id, payload.alt_text.as_deref())
+            .await
+            .map_err(|e| format!("Failed to update product media: {}", e))?;
+
+        self.get_product_media(&payload.id)
+            .await?
+            .ok_or_else(|| format!("Product media not found: {}", payload.id))
+    }
+
+    /// Set the order of a product's media; the first one becomes the cover
+    pub async fn reorder_product_media(
+        &self,
+        payload: ReorderProductMediaDTO,
+    ) -> Result<Vec<ProductMediaItem>, String> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let existing =
+            ShopProductMediaRepository::list_by_product_in_tx(&mut tx, &payload.product_id)
+                .await
+                .map_err(|e| format!("Failed to list product media: {}", e))?;
+        let existing_ids: HashSet<&str> = existing.iter().map(|m| m.id.as_str()).collect();
+        let requested_ids: HashSet<&str> = payload.media_ids.iter().map(String::as_str).collect();
+        if requested_ids.len() != payload.media_ids.len() || requested_ids != existing_ids {
+            return Err(
+                "media_ids must list every media entry of the product exactly once".to_string(),
+            );
+        }
+
+        for (position, id) in payload.media_ids.iter().enumerate() {
+            ShopProductMediaRepository::set_position_in_tx(&mut tx, id, position as i64)
+                .await
+                .map_err(|e| format!("Failed to reorder product media: {}", e))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        self.list_product_media(&payload.product_id).await
+    }
+
+    /// Remove a media entry from its product. The file itself is deleted
+    /// once no product uses it.
+    pub async fn detach_product_media(&self, id: &str) -> Result<MediaCleanupResult, String> {
+        let media = self
+            .repo
+            .get_by_id(id)
+            .await
+            .map_err(|e| format!("Failed to fetch product media: {}", e))?
+            .ok_or_else(|| format!("Product media not found: {}", id))?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        ShopProductMediaRepository::delete_in_tx(&mut tx, id)
+            .await
+            .map_err(|e| format!("Failed to detach product media: {}", e))?;
+        let remaining =
+            ShopProductMediaRepository::list_by_product_in_tx(&mut tx, &media.product_id)
+                .await
+                .map_err(|e| format!("Failed to list product media: {}", e))?;
+        for (position, media) in remaining.iter().enumerate() {
+            ShopProductMediaRepository::set_position_in_tx(&mut tx, &media.id, position as i64)
+                .await
+                .map_err(|e| format!("Failed to reorder product media: {}", e))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        self.purge_orphan_media().await
+    }
+
+    /// Delete assets no product uses any more, with their files. The row goes
+    /// first, so a file is never deleted while a product still shows it;
+    /// files that could not be deleted are reported and left behind.
+    pub async fn purge_orphan_media(&self) -> Result<MediaCleanupResult, String> {
+        let orphans = self
+            .repo
+            .list_orphan_assets()
+            .await
+            .map_err(|e| format!("Failed to list orphan media: {}", e))?;
+
+        let mut result = MediaCleanupResult {
+            assets_removed: 0,
+            bytes_freed: 0,
+            failed_keys: Vec::new(),
+        };
+
+        for asset in orphans {
+            let deleted = self
+                .repo
+                .delete_orphan_asset(&asset.id)
+                .await
+                .map_err(|e| format!("Failed to delete media asset: {}", e))?;
+            if !deleted {
+                continue; // Attached again in the meantime
+            }
+            result.assets_removed += 1;
+            result.bytes_freed += asset.file_size;
+
+            let keys = std::iter::once(&asset.storage_key).chain(asset.thumbnail_key.as_ref());
+            for key in keys {
+                let deleted = match self.storages.get(&asset.storage_provider) {
+                    Ok(storage) => storage.delete(key).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = deleted {
+                    eprintln!("[purge_orphan_media] {}", e);
+                    result.failed_keys.push(key.clone());
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,58 @@
+//! Media files under the shop's data directory
+
+use crate::features::product_media::storage::media_storage::MediaStorage;
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+}
+
+#[async_trait]
+impl MediaStorage for LocalStorage {
+    fn provider(&self) -> &'static str {
+        "local"
+    }
+
+    async fn put(
+        &self,
+        key: &str,
+        bytes: Vec<u8>,
+        _content_type: &str,
+    ) -> Result<Option<String>, String> {
+        let path = self.root.join(key);
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir)
+                .await
+                .map_err(|e| format!("Failed to create media directory: {}", e))?;
+        }
+
+        // Write aside and rename, so a file is never seen half-written
+        let partial = path.with_extension("partial");
+        tokio::fs::write(&partial, bytes)
+            .await
+            .map_err(|e| format!("Failed to write media file: {}", e))?;
+        tokio::fs::rename(&partial, &path)
+            .await
+            .map_err(|e| format!("Failed to write media file: {}", e))?;
+        Ok(None)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        match tokio::fs::remove_file(self.root.join(key)).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("Failed to delete media file {}: {}", key, e)),
+        }
+    }
+
+    fn local_path(&self, key: &str) -> Option<PathBuf> {
+        Some(self.root.join(key))
+    }
+}
@@ -0,0 +1,74 @@
+//! Media storage abstraction
+//!
+//! Files are written under content-addressed keys, so storing the same file
+//! twice is harmless. Each asset records the provider it was stored with, so
+//! files stay reachable after the shop switches providers.
+
+use crate::features::product_media::models::product_media_model::StorageConfig;
+use crate::features::product_media::storage::local_storage::LocalStorage;
+use crate::features::product_media::storage::s3_storage::S3Storage;
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+#[async_trait]
+pub trait MediaStorage: Send + Sync {
+    /// Provider identifier, as stored in `media_assets.storage_provider`
+    fn provider(&self) -> &'static str;
+
+    /// Store a file, returning its public URL for remote providers
+    async fn put(
+        &self,
+        key: &str,
+        bytes: Vec<u8>,
+        content_type: &str,
+    ) -> Result<Option<String>, String>;
+
+    /// Delete a file; deleting a missing file is not an error
+    async fn delete(&self, key: &str) -> Result<(), String>;
+
+    /// Absolute path of a file, for local providers
+    fn local_path(&self, key: &str) -> Option<PathBuf>;
+}
+
+/// Storage for new files per the shop settings, plus every provider
+/// existing assets may have been stored with
+pub struct MediaStorages {
+    local: LocalStorage,
+    s3: Option<S3Storage>,
+    default_provider: &'static str,
+}
+
+impl MediaStorages {
+    pub fn new(config: &StorageConfig, media_dir: PathBuf) -> Result<Self, String> {
+        let s3 = config.s3.clone().map(S3Storage::new).transpose()?;
+        let default_provider = match config.provider() {
+            "local" => "local",
+            "s3" if s3.is_some() => "s3",
+            "s3" => return Err("storage_config.s3 is required for the s3 provider".to_string()),
+            other => return Err(format!("Unsupported storage provider: {}", other)),
+        };
+
+        Ok(Self {
+            local: LocalStorage::new(media_dir),
+            s3,
+            default_provider,
+        })
+    }
+
+    /// Where new files go
+    pub fn default_storage(&self) -> Result<&dyn MediaStorage, String> {
+        self.get(self.default_provider)
+    }
+
+    pub fn get(&self, provider: &str) -> Result<&dyn MediaStorage, String> {
+        match provider {
+            "local" => Ok(&self.local),
+            "s3" => self
+                .s3
+                .as_ref()
+                .map(|s| s as &dyn MediaStorage)
+                .ok_or_else(|| "S3 storage is not configured for this shop".to_string()),
+            other => Err(format!("Unsupported storage provider: {}", other)),
+        }
+    }
+}
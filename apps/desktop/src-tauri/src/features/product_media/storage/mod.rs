@@ -0,0 +1,3 @@
+pub mod local_storage;
+pub mod media_storage;
+pub mod s3_storage;
@@ -0,0 +1,207 @@
+//! Media files in an S3-compatible bucket (AWS S3, MinIO, R2, ...)
+//!
+//! Requests are signed with AWS Signature Version 4.
+
+use crate::features::product_media::models::product_media_model::S3Config;
+use crate::features::product_media::storage::media_storage::MediaStorage;
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::{Method, Url};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::time::Duration;
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// Percent-encode a key for the request path, keeping the slashes
+fn encode_key(key: &str) -> String {
+    let mut encoded = String::with_capacity(key.len());
+    for byte in key.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+pub struct S3Storage {
+    client: reqwest::Client,
+    config: S3Config,
+    endpoint: Url,
+}
+
+impl S3Storage {
+    pub fn new(config: S3Config) -> Result<Self, String> {
+        let endpoint = Url::parse(&config.endpoint)
+            .map_err(|e| format!("Invalid S3 endpoint {}: {}", config.endpoint, e))?;
+        if endpoint.host_str().is_none() {
+            return Err(format!("Invalid S3 endpoint: {}", config.endpoint));
+        }
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(120))
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+        Ok(Self {
+            client,
+            config,
+            endpoint,
+        })
+    }
+
+    /// Key in the bucket, with the configured prefix
+    fn object_key(&self, key: &str) -> String {
+        match self.config.prefix.as_deref().map(|p| p.trim_matches('/')) {
+            Some(prefix) if !prefix.is_empty() => format!("{}/{}", prefix, key),
+            _ => key.to_string(),
+        }
+    }
+
+    fn object_url(&self, object_key: &str) -> Result<Url, String> {
+        let mut url = self.endpoint.clone();
+        let base_path = self.endpoint.path().trim_end_matches('/');
+        if self.config.path_style {
+            url.set_path(&format!(
+                "{}/{}/{}",
+                base_path,
+                self.config.bucket,
+                encode_key(object_key)
+            ));
+        } else {
+            let host = format!(
+                "{}.{}",
+                self.config.bucket,
+                self.endpoint.host_str().unwrap_or_default()
+            );
+            url.set_host(Some(&host))
+                .map_err(|e| format!("Invalid S3 bucket host {}: {}", host, e))?;
+            url.set_path(&format!("{}/{}", base_path, encode_key(object_key)));
+        }
+        Ok(url)
+    }
+
+    fn public_url(&self, object_key: &str, object_url: &Url) -> String {
+        match self.config.public_base_url.as_deref() {
+            Some(base) => format!("{}/{}", base.trim_end_matches('/'), encode_key(object_key)),
+            None => object_url.to_string(),
+        }
+    }
+
+    /// Send a request signed with SigV4 (payload hash included)
+    async fn send(
+        &self,
+        method: Method,
+        url: &Url,
+        body: Vec<u8>,
+        content_type: Option<&str>,
+    ) -> Result<reqwest::Response, String> {
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = sha256_hex(&body);
+
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            method.as_str(),
+            url.path(),
+            host,
+            payload_hash,
+            amz_date,
+            signed_headers,
+            payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.config.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            sha256_hex(canonical_request.as_bytes())
+        );
+
+        let secret = format!("AWS4{}", self.config.secret_access_key);
+        let k_date = hmac_sha256(secret.as_bytes(), &date);
+        let k_region = hmac_sha256(&k_date, &self.config.region);
+        let k_service = hmac_sha256(&k_region, "s3");
+        let k_signing = hmac_sha256(&k_service, "aws4_request");
+        let signature = hex::encode(hmac_sha256(&k_signing, &string_to_sign));
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.config.access_key_id, scope, signed_headers, signature
+        );
+
+        let mut request = self
+            .client
+            .request(method, url.clone())
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("authorization", authorization);
+        if let Some(content_type) = content_type {
+            request = request.header("content-type", content_type);
+        }
+        request
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| format!("S3 request failed: {}", e))
+    }
+}
+
+#[async_trait]
+impl MediaStorage for S3Storage {
+    fn provider(&self) -> &'static str {
+        "s3"
+    }
+
+    async fn put(
+        &self,
+        key: &str,
+        bytes: Vec<u8>,
+        content_type: &str,
+    ) -> Result<Option<String>, String> {
+        let object_key = self.object_key(key);
+        let url = self.object_url(&object_key)?;
+        let response = self
+            .send(Method::PUT, &url, bytes, Some(content_type))
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("S3 upload failed with status {}: {}", status, body));
+        }
+        Ok(Some(self.public_url(&object_key, &url)))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        let url = self.object_url(&self.object_key(key))?;
+        let response = self.send(Method::DELETE, &url, Vec::new(), None).await?;
+        let status = response.status();
+        if !status.is_success() && status.as_u16() != 404 {
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("S3 delete failed with status {}: {}", status, body));
+        }
+        Ok(())
+    }
+
+    fn local_path(&self, _key: &str) -> Option<PathBuf> {
+        None
+    }
+}
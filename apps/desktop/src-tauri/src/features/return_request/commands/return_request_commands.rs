@@ -0,0 +1,121 @@
+use crate::db::RepositoryFactory;
+use crate::features::return_request::dtos::return_request_dto::{
+    CreateReturnRequestDTO, ReceiveReturnDTO, ResolveReturnDTO,
+};
+use crate::features::return_request::models::return_request_model::{
+    ReturnItem, ReturnRequest, ReturnRequestDetail,
+};
+use crate::features::return_request::services::shop_return_request_service::ShopReturnRequestService;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn open_return_request(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: CreateReturnRequestDTO,
+) -> Result<ReturnRequestDetail, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopReturnRequestService::new(pool, shop_id);
+    service.open_return(payload).await
+}
+
+#[tauri::command]
+pub async fn receive_return_request(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: ReceiveReturnDTO,
+) -> Result<ReturnRequestDetail, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopReturnRequestService::new(pool, shop_id);
+    service.receive_return(payload).await
+}
+
+#[tauri::command]
+pub async fn resolve_return_request(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: ResolveReturnDTO,
+) -> Result<ReturnRequestDetail, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopReturnRequestService::new(pool, shop_id);
+    service.resolve_return(payload).await
+}
+
+#[tauri::command]
+pub async fn cancel_return_request(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+) -> Result<ReturnRequest, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopReturnRequestService::new(pool, shop_id);
+    service.cancel_return(&id).await
+}
+
+#[tauri::command]
+pub async fn get_return_request(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+) -> Result<Option<ReturnRequestDetail>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopReturnRequestService::new(pool, shop_id);
+    service.get_return(&id).await
+}
+
+#[tauri::command]
+pub async fn list_return_requests(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+) -> Result<Vec<ReturnRequest>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopReturnRequestService::new(pool, shop_id);
+    service.list_returns().await
+}
+
+#[tauri::command]
+pub async fn list_return_requests_by_transaction(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    transaction_id: String,
+) -> Result<Vec<ReturnRequest>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopReturnRequestService::new(pool, shop_id);
+    service.list_returns_by_transaction(&transaction_id).await
+}
+
+#[tauri::command]
+pub async fn list_return_items(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    return_request_id: String,
+) -> Result<Vec<ReturnItem>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopReturnRequestService::new(pool, shop_id);
+    service.list_return_items(&return_request_id).await
+}
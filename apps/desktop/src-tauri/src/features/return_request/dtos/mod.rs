@@ -0,0 +1 @@
+pub mod return_request_dto;
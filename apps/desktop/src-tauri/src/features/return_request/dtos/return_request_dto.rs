@@ -0,0 +1,108 @@
+use crate::features::return_request::models::return_request_model::{ReturnItem, ReturnRequest};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateReturnItemDTO {
+    pub transaction_item_id: String,
+    pub quantity: f64,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateReturnRequestDTO {
+    pub transaction_id: String,
+    pub order_id: Option<String>,
+    pub r#type: Option<String>,
+    pub reason: Option<String>,
+    pub notes: Option<String>,
+    pub created_by: Option<String>,
+    pub items: Vec<CreateReturnItemDTO>,
+}
+
+impl CreateReturnRequestDTO {
+    /// Builds the request and its lines. Product and price are left empty on the
+    /// lines and are copied from the sold transaction item by the service.
+    pub fn into_models(self) -> (ReturnRequest, Vec<ReturnItem>) {
+        let return_request_id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        let return_request = ReturnRequest {
+            id: return_request_id.clone(),
+            transaction_id: self.transaction_id,
+            order_id: self.order_id,
+            customer_id: None,
+            r#type: self.r#type.unwrap_or_else(|| "return".to_string()),
+            status: "requested".to_string(),
+            resolution: None,
+            reason: self.reason,
+            notes: self.notes,
+            return_transaction_id: None,
+            exchange_transaction_id: None,
+            total_amount: Some(0.0),
+            resolved_amount: Some(0.0),
+            created_by: self.created_by,
+            received_at: None,
+            resolved_at: None,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+
+        let items = self
+            .items
+            .into_iter()
+            .map(|i| ReturnItem {
+                id: Uuid::new_v4().to_string(),
+                return_request_id: return_request_id.clone(),
+                transaction_item_id: i.transaction_item_id,
+                product_id: None,
+                quantity: i.quantity,
+                unit_price: 0.0,
+                reason: i.reason,
+                condition: None,
+                location_id: None,
+                inventory_level_id: None,
+                refund_id: None,
+                refund_amount: None,
+                received_at: None,
+                sync_status: Some("created".to_string()),
+                created_at: Some(now),
+                updated_at: Some(now),
+            })
+            .collect();
+
+        (return_request, items)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InspectReturnItemDTO {
+    pub return_item_id: String,
+    pub condition: String, // 'sellable', 'damaged', 'quarantine'
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReceiveReturnDTO {
+    pub return_request_id: String,
+    pub location_id: String,
+    pub staff_id: Option<String>,
+    pub items: Vec<InspectReturnItemDTO>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExchangeItemDTO {
+    pub product_id: String,
+    pub quantity: f64,
+    pub unit_price: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResolveReturnDTO {
+    pub return_request_id: String,
+    pub resolution: String, // 'refund', 'store_credit', 'exchange'
+    pub payment_id: Option<String>,
+    pub created_by: Option<String>,
+    pub exchange_items: Option<Vec<ExchangeItemDTO>>,
+}
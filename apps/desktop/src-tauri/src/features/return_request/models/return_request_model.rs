@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ReturnRequest {
+    pub id: String,
+    pub transaction_id: String,
+    pub order_id: Option<String>,
+    pub customer_id: Option<String>,
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub r#type: String, // 'return', 'exchange'
+    pub status: String, // 'requested', 'received', 'resolved', 'cancelled'
+    pub resolution: Option<String>, // 'refund', 'store_credit', 'exchange'
+    pub reason: Option<String>,
+    pub notes: Option<String>,
+    pub return_transaction_id: Option<String>,
+    pub exchange_transaction_id: Option<String>,
+    pub total_amount: Option<f64>,    // DEFAULT 0
+    pub resolved_amount: Option<f64>, // DEFAULT 0
+    pub created_by: Option<String>,
+    pub received_at: Option<DateTime<Utc>>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ReturnItem {
+    pub id: String,
+    pub return_request_id: String,
+    pub transaction_item_id: String,
+    pub product_id: Option<String>,
+    pub quantity: f64,
+    pub unit_price: f64,
+    pub reason: Option<String>,
+    pub condition: Option<String>, // 'sellable', 'damaged', 'quarantine'
+    pub location_id: Option<String>,
+    pub inventory_level_id: Option<String>,
+    pub refund_id: Option<String>,
+    pub refund_amount: Option<f64>,
+    pub received_at: Option<DateTime<Utc>>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A return request together with its lines
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReturnRequestDetail {
+    pub return_request: ReturnRequest,
+    pub items: Vec<ReturnItem>,
+}
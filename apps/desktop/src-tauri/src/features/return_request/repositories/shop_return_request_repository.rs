@@ -0,0 +1,278 @@
+//! Shop-scoped Return Request Repository for Multi-Database Architecture
+
+use crate::features::return_request::models::return_request_model::{ReturnItem, ReturnRequest};
+use sqlx::{Result, Sqlite, SqlitePool, Transaction};
+use std::sync::Arc;
+
+pub struct ShopReturnRequestRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopReturnRequestRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_by_id(&self, id: &str) -> Result<Option<ReturnRequest>> {
+        let sql = "SELECT * FROM return_requests WHERE id = $1 AND (_status IS NULL OR _status != 'deleted')";
+        sqlx::query_as::<_, ReturnRequest>(sql)
+            .bind(id)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn list(&self) -> Result<Vec<ReturnRequest>> {
+        let sql = "SELECT * FROM return_requests WHERE _status IS NULL OR _status != 'deleted' ORDER BY created_at DESC";
+        sqlx::query_as::<_, ReturnRequest>(sql)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn list_by_transaction(&self, transaction_id: &str) -> Result<Vec<ReturnRequest>> {
+        let sql = r#"
+            SELECT * FROM return_requests
+            WHERE transaction_id = $1 AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY created_at DESC
+        "#;
+        sqlx::query_as::<_, ReturnRequest>(sql)
+            .bind(transaction_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn list_by_transaction_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        transaction_id: &str,
+    ) -> Result<Vec<ReturnRequest>> {
+        let sql = r#"
+            SELECT * FROM return_requests
+            WHERE transaction_id = $1 AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY created_at DESC
+        "#;
+        sqlx::query_as::<_, ReturnRequest>(sql)
+            .bind(transaction_id)
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    pub async fn list_items(&self, return_request_id: &str) -> Result<Vec<ReturnItem>> {
+        let sql = r#"
+            SELECT * FROM return_items
+            WHERE return_request_id = $1 AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY created_at ASC
+        "#;
+        sqlx::query_as::<_, ReturnItem>(sql)
+            .bind(return_request_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn update_status(&self, id: &str, status: &str) -> Result<ReturnRequest> {
+        let sql = r#"
+            UPDATE return_requests SET status = $2, _status = 'modified', updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, ReturnRequest>(sql)
+            .bind(id)
+            .bind(status)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    // ============================================================
+    // Transaction-aware methods for atomic operations
+    // ============================================================
+
+    pub async fn create_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        return_request: &ReturnRequest,
+    ) -> Result<ReturnRequest> {
+        let sql = r#"
+            INSERT INTO return_requests (
+                id, transaction_id, order_id, customer_id, type, status, resolution,
+                reason, notes, return_transaction_id, exchange_transaction_id,
+                total_amount, resolved_amount, created_by, received_at, resolved_at,
+                _status, created_at, updated_at
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19
+            )
+            RETURNING *
+        "#;
+
+        sqlx::query_as::<_, ReturnRequest>(sql)
+            .bind(&return_request.id)
+            .bind(&return_request.transaction_id)
+            .bind(&return_request.order_id)
+            .bind(&return_request.customer_id)
+            .bind(&return_request.r#type)
+            .bind(&return_request.status)
+            .bind(&return_request.resolution)
+            .bind(&return_request.reason)
+            .bind(&return_request.notes)
+            .bind(&return_request.return_transaction_id)
+            .bind(&return_request.exchange_transaction_id)
+            .bind(return_request.total_amount)
+            .bind(return_request.resolved_amount)
+            .bind(&return_request.created_by)
+            .bind(return_request.received_at)
+            .bind(return_request.resolved_at)
+            .bind(&return_request.sync_status)
+            .bind(return_request.created_at)
+            .bind(return_request.updated_at)
+            .fetch_one(&mut **tx)
+            .await
+    }
+
+    pub async fn update_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        return_request: &ReturnRequest,
+    ) -> Result<ReturnRequest> {
+        let sql = r#"
+            UPDATE return_requests SET
+                status = $2, resolution = $3, notes = $4, return_transaction_id = $5,
+                exchange_transaction_id = $6, total_amount = $7, resolved_amount = $8,
+                received_at = $9, resolved_at = $10, _status = 'modified', updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+        "#;
+
+        sqlx::query_as::<_, ReturnRequest>(sql)
+            .bind(&return_request.id)
+            .bind(&return_request.status)
+            .bind(&return_request.resolution)
+            .bind(&return_request.notes)
+            .bind(&return_request.return_transaction_id)
+            .bind(&return_request.exchange_transaction_id)
+            .bind(return_request.total_amount)
+            .bind(return_request.resolved_amount)
+            .bind(return_request.received_at)
+            .bind(return_request.resolved_at)
+            .fetch_one(&mut **tx)
+            .await
+    }
+
+    pub async fn get_by_id_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+    ) -> Result<Option<ReturnRequest>> {
+        let sql = "SELECT * FROM return_requests WHERE id = $1 AND (_status IS NULL OR _status != 'deleted')";
+        sqlx::query_as::<_, ReturnRequest>(sql)
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    pub async fn create_item_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        item: &ReturnItem,
+    ) -> Result<ReturnItem> {
+        let sql = r#"
+            INSERT INTO return_items (
+                id, return_request_id, transaction_item_id, product_id, quantity, unit_price,
+                reason, condition, location_id, inventory_level_id, refund_id, refund_amount,
+                received_at, _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+            RETURNING *
+        "#;
+
+        sqlx::query_as::<_, ReturnItem>(sql)
+            .bind(&item.id)
+            .bind(&item.return_request_id)
+            .bind(&item.transaction_item_id)
+            .bind(&item.product_id)
+            .bind(item.quantity)
+            .bind(item.unit_price)
+            .bind(&item.reason)
+            .bind(&item.condition)
+            .bind(&item.location_id)
+            .bind(&item.inventory_level_id)
+            .bind(&item.refund_id)
+            .bind(item.refund_amount)
+            .bind(item.received_at)
+            .bind(&item.sync_status)
+            .bind(item.created_at)
+            .bind(item.updated_at)
+            .fetch_one(&mut **tx)
+            .await
+    }
+
+    pub async fn list_items_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        return_request_id: &str,
+    ) -> Result<Vec<ReturnItem>> {
+        let sql = r#"
+            SELECT * FROM return_items
+            WHERE return_request_id = $1 AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY created_at ASC
+        "#;
+        sqlx::query_as::<_, ReturnItem>(sql)
+            .bind(return_request_id)
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    /// Quantity of a sold line already claimed by non-cancelled return requests
+    pub async fn get_returned_quantity_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        transaction_item_id: &str,
+    ) -> Result<f64> {
+        let sql = r#"
+            SELECT COALESCE(SUM(ri.quantity), 0.0) as total
+            FROM return_items ri
+            INNER JOIN return_requests rr ON rr.id = ri.return_request_id
+            WHERE ri.transaction_item_id = $1
+              AND rr.status != 'cancelled'
+              AND (ri._status IS NULL OR ri._status != 'deleted')
+              AND (rr._status IS NULL OR rr._status != 'deleted')
+        "#;
+        let result: (f64,) = sqlx::query_as(sql)
+            .bind(transaction_item_id)
+            .fetch_one(&mut **tx)
+            .await?;
+        Ok(result.0)
+    }
+
+    pub async fn mark_item_received_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+        condition: &str,
+        location_id: &str,
+        inventory_level_id: Option<&str>,
+    ) -> Result<ReturnItem> {
+        let sql = r#"
+            UPDATE return_items SET
+                condition = $2, location_id = $3, inventory_level_id = $4,
+                received_at = datetime('now'), _status = 'modified', updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, ReturnItem>(sql)
+            .bind(id)
+            .bind(condition)
+            .bind(location_id)
+            .bind(inventory_level_id)
+            .fetch_one(&mut **tx)
+            .await
+    }
+
+    pub async fn set_item_refund_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+        refund_id: Option<&str>,
+        refund_amount: f64,
+    ) -> Result<ReturnItem> {
+        let sql = r#"
+            UPDATE return_items SET
+                refund_id = $2, refund_amount = $3, _status = 'modified', updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, ReturnItem>(sql)
+            .bind(id)
+            .bind(refund_id)
+            .bind(refund_amount)
+            .fetch_one(&mut **tx)
+            .await
+    }
+}
@@ -0,0 +1 @@
+pub mod shop_return_request_service;
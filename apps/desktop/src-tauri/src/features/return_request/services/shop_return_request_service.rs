@@ -0,0 +1,839 @@
+//! Shop-scoped Return Request Service for Multi-Database Architecture
+//!
+//! RMA flow: a return is opened against the lines of a completed sale,
+//! received and inspected into sellable/damaged/quarantine stock, and
+//! resolved with a refund, store credit or an exchange sale.
+
+use crate::features::bundle::repositories::shop_bundle_repository::ShopBundleRepository;
+use crate::features::bundle::services::shop_bundle_service::ShopBundleService;
+use crate::features::customer::repositories::shop_customer_repository::ShopCustomerRepository;
+use crate::features::customer_group::services::shop_customer_segment_service::ShopCustomerSegmentService;
+use crate::features::inventory::models::inventory_level_model::InventoryLevel;
+use crate::features::inventory::repositories::inventory_movements_repository::InventoryMovementsRepository;
+use crate::features::inventory::repositories::shop_inventory_repository::ShopInventoryRepository;
+use crate::features::payment::repositories::payments_repository::PaymentsRepository;
+use crate::features::payment::repositories::shop_payment_repository::ShopPaymentRepository;
+use crate::features::payment::services::shop_payment_service::ShopPaymentService;
+use crate::features::product::repositories::shop_product_repository::ShopProductRepository;
+use crate::features::refund::models::refund_model::Refund;
+use crate::features::return_request::dtos::return_request_dto::{
+    CreateReturnRequestDTO, ExchangeItemDTO, ReceiveReturnDTO, ResolveReturnDTO,
+};
+use crate::features::return_request::models::return_request_model::{
+    ReturnItem, ReturnRequest, ReturnRequestDetail,
+};
+use crate::features::return_request::repositories::shop_return_request_repository::ShopReturnRequestRepository;
+use crate::features::store_credit::services::shop_store_credit_service::ShopStoreCreditService;
+use crate::features::transaction::models::transaction_model::{
+    InventoryMovement, Transaction, TransactionItem,
+};
+use crate::features::transaction::repositories::shop_transaction_repository::ShopTransactionRepository;
+use crate::features::transaction::repositories::transaction_items_repository::TransactionItemsRepository;
+use chrono::Utc;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const RETURN_CONDITIONS: [&str; 3] = ["sellable", "damaged", "quarantine"];
+const RETURN_RESOLUTIONS: [&str; 3] = ["refund", "store_credit", "exchange"];
+
+pub struct ShopReturnRequestService {
+    pool: Arc<SqlitePool>,
+    shop_id: String,
+    repo: ShopReturnRequestRepository,
+}
+
+impl ShopReturnRequestService {
+    pub fn new(pool: Arc<SqlitePool>, shop_id: String) -> Self {
+        let repo = ShopReturnRequestRepository::new(pool.clone());
+        Self {
+            pool,
+            shop_id,
+            repo,
+        }
+    }
+
+    pub fn shop_id(&self) -> &str {
+        &self.shop_id
+    }
+
+    pub fn pool(&self) -> Arc<SqlitePool> {
+        self.pool.clone()
+    }
+
+    /// Open a return against lines of a completed sale.
+    /// Each line may only be returned up to the quantity sold minus what
+    /// other open or finished return requests already claim.
+    pub async fn open_return(
+        &self,
+        payload: CreateReturnRequestDTO,
+    ) -> Result<ReturnRequestDetail, String> {
+        if payload.items.is_empty() {
+            return Err("A return request needs at least one item".to_string());
+        }
+
+        let (mut return_request, items) = payload.into_models();
+
+        if return_request.r#type != "return" && return_request.r#type != "exchange" {
+            return Err(format!("Invalid return type: {}", return_request.r#type));
+        }
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let sale = ShopTransactionRepository::get_by_id_in_tx(
+            &mut tx,
+            &return_request.transaction_id,
+            self.shop_id.clone(),
+        )
+        .await
+        .map_err(|e| format!("Failed to fetch transaction: {}", e))?
+        .ok_or_else(|| format!("Transaction not found: {}", return_request.transaction_id))?;
+
+        if sale.r#type != "sale" {
+            return Err("Only sale transactions can be returned".to_string());
+        }
+        if sale.status != "completed" {
+            return Err(format!(
+                "Transaction with status '{}' cannot be returned",
+                sale.status
+            ));
+        }
+
+        return_request.customer_id = sale.customer_id.clone();
+
+        // A sale-level discount is spread over the lines in proportion to their
+        // value, so a line is refunded at what was actually paid for it
+        let sold_items = TransactionItemsRepository::find_by_transaction_id_with_tx(&mut tx, &sale.id)
+            .await
+            .map_err(|e| format!("Failed to fetch transaction items: {}", e))?;
+        let subtotal: f64 = sold_items.iter().map(|i| i.quantity * i.unit_price).sum();
+        let discount = sale.total_discount.unwrap_or(0.0);
+        let paid_share = if subtotal > 0.0 && discount > 0.0 {
+            (1.0 - discount / subtotal).max(0.0)
+        } else {
+            1.0
+        };
+
+        let mut requested: HashMap<String, f64> = HashMap::new();
+        let mut prepared_items = Vec::with_capacity(items.len());
+        let mut total_amount = 0.0;
+
+        for mut item in items {
+            if item.quantity <= 0.0 {
+                return Err("Return quantity must be greater than zero".to_string());
+            }
+
+            let sold_item = ShopTransactionRepository::get_item_by_id_in_tx(
+                &mut tx,
+                &item.transaction_item_id,
+            )
+            .await
+            .map_err(|e| format!("Failed to fetch transaction item: {}", e))?
+            .ok_or_else(|| format!("Transaction item not found: {}", item.transaction_item_id))?;
+
+            if sold_item.transaction_id != sale.id {
+                return Err(format!(
+                    "Transaction item {} does not belong to transaction {}",
+                    sold_item.id, sale.id
+                ));
+            }
+
+            let already_returned =
+                ShopReturnRequestRepository::get_returned_quantity_in_tx(&mut tx, &sold_item.id)
+                    .await
+                    .map_err(|e| format!("Failed to get returned quantity: {}", e))?;
+            let claimed = requested.entry(sold_item.id.clone()).or_insert(0.0);
+            let returnable = sold_item.quantity - already_returned - *claimed;
+
+            if item.quantity > returnable + 0.0001 {
+                return Err(format!(
+                    "Return quantity ({}) exceeds returnable quantity ({}) for item {}",
+                    item.quantity, returnable, sold_item.id
+                ));
+            }
+            *claimed += item.quantity;
+
+            // Every sold line records its price, so free and fully discounted
+            // lines are refunded at 0 rather than at a list price
+            item.product_id = sold_item.product_id.clone();
+            item.unit_price = sold_item.unit_price * paid_share;
+            total_amount += item.quantity * item.unit_price;
+            prepared_items.push(item);
+        }
+
+        return_request.total_amount = Some(total_amount);
+
+        let created = ShopReturnRequestRepository::create_in_tx(&mut tx, &return_request)
+            .await
+            .map_err(|e| format!("Failed to create return request: {}", e))?;
+
+        let mut created_items = Vec::with_capacity(prepared_items.len());
+        for item in &prepared_items {
+            let created_item = ShopReturnRequestRepository::create_item_in_tx(&mut tx, item)
+                .await
+                .map_err(|e| format!("Failed to create return item: {}", e))?;
+            created_items.push(created_item);
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(ReturnRequestDetail {
+            return_request: created,
+            items: created_items,
+        })
+    }
+
+    /// Receive and inspect the returned goods.
+    /// Every line is routed to the inventory level matching its inspected
+    /// condition (created when missing) through an IN movement recorded on a
+    /// completed 'return' transaction.
+    pub async fn receive_return(
+        &self,
+        payload: ReceiveReturnDTO,
+    ) -> Result<ReturnRequestDetail, String> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let mut return_request =
+            ShopReturnRequestRepository::get_by_id_in_tx(&mut tx, &payload.return_request_id)
+                .await
+                .map_err(|e| format!("Failed to fetch return request: {}", e))?
+                .ok_or_else(|| {
+                    format!("Return request not found: {}", payload.return_request_id)
+                })?;
+
+        if return_request.status != "requested" {
+            return Err(format!(
+                "Return request with status '{}' cannot be received",
+                return_request.status
+            ));
+        }
+
+        let mut conditions: HashMap<String, String> = HashMap::new();
+        for inspection in payload.items {
+            if !RETURN_CONDITIONS.contains(&inspection.condition.as_str()) {
+                return Err(format!("Invalid item condition: {}", inspection.condition));
+            }
+            conditions.insert(inspection.return_item_id, inspection.condition);
+        }
+
+        let items = ShopReturnRequestRepository::list_items_in_tx(&mut tx, &return_request.id)
+            .await
+            .map_err(|e| format!("Failed to fetch return items: {}", e))?;
+
+        if let Some(missing) = items.iter().find(|i| !conditions.contains_key(&i.id)) {
+            return Err(format!("Missing inspection for return item {}", missing.id));
+        }
+
+        let sale = ShopTransactionRepository::get_by_id_in_tx(
+            &mut tx,
+            &return_request.transaction_id,
+            self.shop_id.clone(),
+        )
+        .await
+        .map_err(|e| format!("Failed to fetch transaction: {}", e))?
+        .ok_or_else(|| format!("Transaction not found: {}", return_request.transaction_id))?;
+
+        let now = Utc::now();
+        let total_amount = return_request.total_amount.unwrap_or(0.0);
+        let return_transaction = Transaction {
+            id: Uuid::new_v4().to_string(),
+            shop_id: self.shop_id.clone(),
+            r#type: "return".to_string(),
+            status: "completed".to_string(),
+            channel: None,
+            customer_id: return_request.customer_id.clone(),
+            supplier_id: None,
+            staff_id: payload.staff_id.clone(),
+            currency: sale.currency.clone(),
+            total_items: Some(total_amount),
+            total_shipping: Some(0.0),
+            total_discount: Some(0.0),
+            total_net: Some(total_amount),
+            shipping_method: None,
+            shipping_address: None,
+            billing_address: None,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+
+        ShopTransactionRepository::create_in_tx(&mut tx, &return_transaction)
+            .await
+            .map_err(|e| format!("Failed to create return transaction: {}", e))?;
+
+        let mut received_items = Vec::with_capacity(items.len());
+        for item in items {
+            let condition = &conditions[&item.id];
+
+            let sold_item =
+                ShopTransactionRepository::get_item_by_id_in_tx(&mut tx, &item.transaction_item_id)
+                    .await
+                    .map_err(|e| format!("Failed to fetch transaction item: {}", e))?;
+
+            let return_line = TransactionItem {
+                id: Uuid::new_v4().to_string(),
+                transaction_id: return_transaction.id.clone(),
+                product_id: item.product_id.clone(),
+                sku_snapshot: sold_item.as_ref().and_then(|s| s.sku_snapshot.clone()),
+                name_snapshot: sold_item.as_ref().and_then(|s| s.name_snapshot.clone()),
+                quantity: item.quantity,
+                unit_price: item.unit_price,
+                unit_cost: sold_item.as_ref().and_then(|s| s.unit_cost),
+                total_line: None,
+                attributes_snapshot: sold_item.as_ref().and_then(|s| s.attributes_snapshot.clone()),
+                tax_details: None,
+                sync_status: Some("created".to_string()),
+                created_at: Some(now),
+                updated_at: Some(now),
+            };
+
+            ShopTransactionRepository::create_item_in_tx(&mut tx, &return_line)
+                .await
+                .map_err(|e| format!("Failed to create return transaction item: {}", e))?;
+
+            // Lines whose product was removed, digital goods and services are
+            // recorded without restocking.
+            // Bundles restock the components recorded at sale time (or, for sales
+            // made before that, their current composition).
+            let level_id = match &item.product_id {
+                Some(product_id) => {
+                    let sold_components = ShopBundleRepository::list_item_components_in_tx(
+                        &mut tx,
+                        &item.transaction_item_id,
+                    )
+                    .await
+                    .map_err(|e| format!("Failed to fetch bundle components: {}", e))?;
+
+                    let stock_lines = match (&sold_item, sold_components.is_empty()) {
+                        (Some(sold), false) if sold.quantity > 0.0 => {
+                            let lines = sold_components
+                                .iter()
+                                .filter_map(|c| {
+                                    c.component_id.clone().map(|component_id| {
+                                        (component_id, c.quantity / sold.quantity * item.quantity)
+                                    })
+                                })
+                                .collect();
+                            ShopBundleService::stocked_lines_in_tx(&mut tx, lines).await?
+                        }
+                        _ => {
+                            ShopBundleService::stock_lines_in_tx(&mut tx, product_id, item.quantity)
+                                .await?
+                        }
+                    };
+                    let is_bundle = stock_lines.len() != 1 || stock_lines[0].0 != *product_id;
+
+                    let mut restocked_level = None;
+                    for (stock_product_id, quantity) in stock_lines {
+                        let level = self
+                            .get_or_create_level(
+                                &mut tx,
+                                &stock_product_id,
+                                &payload.location_id,
+                                condition,
+                            )
+                            .await?;
+
+                        let movement = InventoryMovement {
+                            id: Uuid::new_v4().to_string(),
+                            transaction_id: Some(return_transaction.id.clone()),
+                            inventory_level_id: Some(level.id.clone()),
+                            movement_type: Some("in".to_string()),
+                            quantity,
+                            previous_balance: Some(level.quantity_on_hand),
+                            new_balance: Some(level.quantity_on_hand + quantity),
+                            sync_status: Some("created".to_string()),
+                            created_at: Some(now),
+                            updated_at: Some(now),
+                        };
+
+                        // The trigger 'trg_inventory_movement_update_level' updates quantity_on_hand
+                        InventoryMovementsRepository::create_with_tx(&mut tx, movement)
+                            .await
+                            .map_err(|e| format!("Failed to create inventory movement: {}", e))?;
+
+                        restocked_level = Some(level.id);
+                    }
+
+                    // A bundle spreads over several levels, so none is linked
+                    if is_bundle {
+                        None
+                    } else {
+                        restocked_level
+                    }
+                }
+                None => None,
+            };
+
+            let received = ShopReturnRequestRepository::mark_item_received_in_tx(
+                &mut tx,
+                &item.id,
+                condition,
+                &payload.location_id,
+                level_id.as_deref(),
+            )
+            .await
+            .map_err(|e| format!("Failed to update return item: {}", e))?;
+            received_items.push(received);
+        }
+
+        return_request.status = "received".to_string();
+        return_request.return_transaction_id = Some(return_transaction.id.clone());
+        return_request.received_at = Some(now);
+
+        let updated = ShopReturnRequestRepository::update_in_tx(&mut tx, &return_request)
+            .await
+            .map_err(|e| format!("Failed to update return request: {}", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(ReturnRequestDetail {
+            return_request: updated,
+            items: received_items,
+        })
+    }
+
+    /// Resolve a received return.
+    /// - refund: refunds on the payments of the original sale, spread across them
+    ///   when it was paid with several tenders
+    /// - store_credit: the returned value is credited to the customer's store credit account
+    /// - exchange: a draft sale with the replacement items, discounted by the returned value;
+    ///   any value left over is credited to the customer, or refunded for anonymous sales
+    pub async fn resolve_return(
+        &self,
+        payload: ResolveReturnDTO,
+    ) -> Result<ReturnRequestDetail, String> {
+        if !RETURN_RESOLUTIONS.contains(&payload.resolution.as_str()) {
+            return Err(format!("Invalid return resolution: {}", payload.resolution));
+        }
+
+        // Exchange prices are looked up before the write transaction starts
+        let exchange_lines = match payload.resolution.as_str() {
+            "exchange" => {
+                let exchange_items = payload.exchange_items.unwrap_or_default();
+                if exchange_items.is_empty() {
+                    return Err("An exchange needs at least one replacement item".to_string());
+                }
+                self.build_exchange_lines(exchange_items).await?
+            }
+            _ => Vec::new(),
+        };
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let mut return_request =
+            ShopReturnRequestRepository::get_by_id_in_tx(&mut tx, &payload.return_request_id)
+                .await
+                .map_err(|e| format!("Failed to fetch return request: {}", e))?
+                .ok_or_else(|| {
+                    format!("Return request not found: {}", payload.return_request_id)
+                })?;
+
+        if return_request.status != "received" {
+            return Err(format!(
+                "Return request with status '{}' cannot be resolved",
+                return_request.status
+            ));
+        }
+
+        let items = ShopReturnRequestRepository::list_items_in_tx(&mut tx, &return_request.id)
+            .await
+            .map_err(|e| format!("Failed to fetch return items: {}", e))?;
+        let amount: f64 = items.iter().map(|i| i.quantity * i.unit_price).sum();
+        let now = Utc::now();
+
+        let mut refunds: Vec<Refund> = Vec::new();
+
+        match payload.resolution.as_str() {
+            "refund" => {
+                refunds = self
+                    .refund_sale_payments(
+                        &mut tx,
+                        &return_request,
+                        amount,
+                        payload.payment_id.as_deref(),
+                        payload.created_by.as_deref(),
+                    )
+                    .await?;
+            }
+            "exchange" => {
+                let sale = ShopTransactionRepository::get_by_id_in_tx(
+                    &mut tx,
+                    &return_request.transaction_id,
+                    self.shop_id.clone(),
+                )
+                .await
+                .map_err(|e| format!("Failed to fetch transaction: {}", e))?
+                .ok_or_else(|| format!("Transaction not found: {}", return_request.transaction_id))?;
+
+                let items_total: f64 = exchange_lines.iter().map(|l| l.quantity * l.unit_price).sum();
+                let exchanged_amount = amount.min(items_total);
+
+                let exchange = Transaction {
+                    id: Uuid::new_v4().to_string(),
+                    shop_id: self.shop_id.clone(),
+                    r#type: "sale".to_string(),
+                    status: "draft".to_string(),
+                    channel: None,
+                    customer_id: return_request.customer_id.clone(),
+                    supplier_id: None,
+                    staff_id: payload.created_by.clone(),
+                    currency: sale.currency.clone(),
+                    total_items: Some(items_total),
+                    total_shipping: Some(0.0),
+                    total_discount: Some(exchanged_amount),
+                    total_net: Some(items_total - exchanged_amount),
+                    shipping_method: None,
+                    shipping_address: None,
+                    billing_address: None,
+                    sync_status: Some("created".to_string()),
+                    created_at: Some(now),
+                    updated_at: Some(now),
+                };
+
+                ShopTransactionRepository::create_in_tx(&mut tx, &exchange)
+                    .await
+                    .map_err(|e| format!("Failed to create exchange transaction: {}", e))?;
+
+                for mut line in exchange_lines {
+                    line.transaction_id = exchange.id.clone();
+                    ShopTransactionRepository::create_item_in_tx(&mut tx, &line)
+                        .await
+                        .map_err(|e| format!("Failed to create exchange item: {}", e))?;
+                }
+
+                return_request.exchange_transaction_id = Some(exchange.id);
+
+                let remainder = amount - exchanged_amount;
+                if remainder >= 0.01 {
+                    match return_request.customer_id.as_deref() {
+                        Some(customer_id) => {
+                            ShopStoreCreditService::credit_return_in_tx(
+                                &mut tx,
+                                customer_id,
+                                remainder,
+                                &return_request.id,
+                                payload.created_by.clone(),
+                            )
+                            .await?;
+                        }
+                        None => {
+                            self.refund_sale_payments(
+                                &mut tx,
+                                &return_request,
+                                remainder,
+                                payload.payment_id.as_deref(),
+                                payload.created_by.as_deref(),
+                            )
+                            .await?;
+                        }
+                    }
+                }
+            }
+            _ => {
+                let customer_id = return_request.customer_id.as_deref().ok_or_else(|| {
+                    "Store credit can only be issued for returns linked to a customer".to_string()
+                })?;
+                ShopStoreCreditService::credit_return_in_tx(
+                    &mut tx,
+                    customer_id,
+                    amount,
+                    &return_request.id,
+                    payload.created_by.clone(),
+                )
+                .await?;
+            }
+        }
+
+        // A line refunded across several payments is linked to the refund its
+        // value starts on
+        let mut resolved_items = Vec::with_capacity(items.len());
+        let mut refunded_before = 0.0;
+        for item in &items {
+            let refund_amount = item.quantity * item.unit_price;
+            let updated_item = ShopReturnRequestRepository::set_item_refund_in_tx(
+                &mut tx,
+                &item.id,
+                refund_at(&refunds, refunded_before),
+                refund_amount,
+            )
+            .await
+            .map_err(|e| format!("Failed to update return item: {}", e))?;
+            resolved_items.push(updated_item);
+            refunded_before += refund_amount;
+        }
+
+        return_request.status = "resolved".to_string();
+        return_request.resolution = Some(payload.resolution.clone());
+        return_request.resolved_amount = Some(amount);
+        return_request.resolved_at = Some(now);
+
+        let updated = ShopReturnRequestRepository::update_in_tx(&mut tx, &return_request)
+            .await
+            .map_err(|e| format!("Failed to update return request: {}", e))?;
+
+        // The refund now comes off the customer's spend and the spend
+        // thresholds of dynamic groups
+        if let Some(customer_id) = &return_request.customer_id {
+            ShopCustomerRepository::refresh_stats_in_tx(&mut tx, customer_id, self.shop_id.clone())
+                .await
+                .map_err(|e| format!("Failed to update customer stats: {}", e))?;
+            ShopCustomerSegmentService::refresh_customer_in_tx(&mut tx, customer_id).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(ReturnRequestDetail {
+            return_request: updated,
+            items: resolved_items,
+        })
+    }
+
+    /// Cancel a return that has not been received yet, releasing its quantities
+    pub async fn cancel_return(&self, id: &str) -> Result<ReturnRequest, String> {
+        let return_request = self
+            .repo
+            .get_by_id(id)
+            .await
+            .map_err(|e| format!("Failed to fetch return request: {}", e))?
+            .ok_or_else(|| format!("Return request not found: {}", id))?;
+
+        if return_request.status != "requested" {
+            return Err(format!(
+                "Return request with status '{}' cannot be cancelled",
+                return_request.status
+            ));
+        }
+
+        self.repo
+            .update_status(id, "cancelled")
+            .await
+            .map_err(|e| format!("Failed to cancel return request: {}", e))
+    }
+
+    pub async fn get_return(&self, id: &str) -> Result<Option<ReturnRequestDetail>, String> {
+        let return_request = match self
+            .repo
+            .get_by_id(id)
+            .await
+            .map_err(|e| format!("Failed to fetch return request: {}", e))?
+        {
+            Some(r) => r,
+            None => return Ok(None),
+        };
+
+        let items = self.list_return_items(id).await?;
+        Ok(Some(ReturnRequestDetail {
+            return_request,
+            items,
+        }))
+    }
+
+    pub async fn list_returns(&self) -> Result<Vec<ReturnRequest>, String> {
+        self.repo
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list return requests: {}", e))
+    }
+
+    pub async fn list_returns_by_transaction(
+        &self,
+        transaction_id: &str,
+    ) -> Result<Vec<ReturnRequest>, String> {
+        self.repo
+            .list_by_transaction(transaction_id)
+            .await
+            .map_err(|e| format!("Failed to list return requests: {}", e))
+    }
+
+    pub async fn list_return_items(&self, return_request_id: &str) -> Result<Vec<ReturnItem>, String> {
+        self.repo
+            .list_items(return_request_id)
+            .await
+            .map_err(|e| format!("Failed to list return items: {}", e))
+    }
+
+    async fn get_or_create_level(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        product_id: &str,
+        location_id: &str,
+        stock_status: &str,
+    ) -> Result<InventoryLevel, String> {
+        let existing = ShopInventoryRepository::find_level_by_stock_status_in_tx(
+            tx,
+            product_id,
+            location_id,
+            stock_status,
+        )
+        .await
+        .map_err(|e| format!("Failed to fetch inventory level: {}", e))?;
+
+        if let Some(level) = existing {
+            return Ok(level);
+        }
+
+        let now = Utc::now();
+        let level = InventoryLevel {
+            id: Uuid::new_v4().to_string(),
+            product_id: product_id.to_string(),
+            location_id: location_id.to_string(),
+            batch_number: None,
+            serial_number: None,
+            expiry_date: None,
+            quantity_on_hand: 0.0,
+            quantity_reserved: 0.0,
+            stock_status: Some(stock_status.to_string()),
+            aisle_bin_slot: None,
+            last_counted_at: None,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+
+        ShopInventoryRepository::create_level_in_tx(tx, &level)
+            .await
+            .map_err(|e| format!("Failed to create inventory level: {}", e))
+    }
+
+    /// Refund `amount` on the refundable payments of the original sale, in the
+    /// order they were taken, or only on `payment_id` when given
+    async fn refund_sale_payments(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        return_request: &ReturnRequest,
+        amount: f64,
+        payment_id: Option<&str>,
+        created_by: Option<&str>,
+    ) -> Result<Vec<Refund>, String> {
+        if amount <= 0.0 {
+            return Err("Refund amount must be greater than zero".to_string());
+        }
+
+        let payments =
+            ShopPaymentRepository::list_by_transaction_in_tx(tx, &return_request.transaction_id)
+                .await
+                .map_err(|e| format!("Failed to list payments: {}", e))?;
+
+        let mut refundable = Vec::new();
+        for payment in payments {
+            if let Some(id) = payment_id {
+                if payment.id != id {
+                    continue;
+                }
+            }
+            if payment.status != "captured" && payment.status != "partially_refunded" {
+                continue;
+            }
+            let refunded = PaymentsRepository::get_refunded_amount_with_tx(tx, &payment.id)
+                .await
+                .map_err(|e| format!("Failed to get refunded amount: {}", e))?;
+            if payment.amount - refunded >= 0.01 {
+                refundable.push((payment, refunded));
+            }
+        }
+
+        let available: f64 = refundable.iter().map(|(p, refunded)| p.amount - refunded).sum();
+        if available + 0.001 < amount {
+            return Err(format!(
+                "Refundable payments only cover {:.2} of {:.2} for transaction {}",
+                available, amount, return_request.transaction_id
+            ));
+        }
+
+        let mut remaining = amount;
+        let mut refunds = Vec::new();
+        for (payment, already_refunded) in refundable {
+            if remaining < 0.005 {
+                break;
+            }
+            let share = (payment.amount - already_refunded).min(remaining);
+            let refund = ShopPaymentService::refund_in_tx(
+                tx,
+                &payment,
+                share,
+                Some(format!("Return {}", return_request.id)),
+                created_by,
+            )
+            .await?;
+            remaining -= share;
+            refunds.push(refund);
+        }
+        Ok(refunds)
+    }
+
+    async fn build_exchange_lines(
+        &self,
+        exchange_items: Vec<ExchangeItemDTO>,
+    ) -> Result<Vec<TransactionItem>, String> {
+        let product_repo = ShopProductRepository::new(self.pool.clone(), self.shop_id.clone());
+        let now = Utc::now();
+        let mut lines = Vec::with_capacity(exchange_items.len());
+
+        for item in exchange_items {
+            if item.quantity <= 0.0 {
+                return Err("Exchange quantity must be greater than zero".to_string());
+            }
+
+            let product = product_repo
+                .get_by_id(&item.product_id)
+                .await
+                .map_err(|e| format!("Failed to fetch product: {}", e))?
+                .ok_or_else(|| format!("Product not found: {}", item.product_id))?;
+
+            let unit_price = item
+                .unit_price
+                .unwrap_or_else(|| product.promotional_price.unwrap_or(product.price));
+
+            lines.push(TransactionItem {
+                id: Uuid::new_v4().to_string(),
+                transaction_id: String::new(),
+                product_id: Some(product.id),
+                sku_snapshot: Some(product.sku),
+                name_snapshot: Some(product.name),
+                quantity: item.quantity,
+                unit_price,
+                unit_cost: product.cost_price,
+                total_line: None,
+                attributes_snapshot: product.attributes,
+                tax_details: None,
+                sync_status: Some("created".to_string()),
+                created_at: Some(now),
+                updated_at: Some(now),
+            });
+        }
+
+        Ok(lines)
+    }
+}
+
+/// The refund covering the refunded value at `offset`, refunds taken in order
+fn refund_at(refunds: &[Refund], offset: f64) -> Option<&str> {
+    let mut end = 0.0;
+    refunds
+        .iter()
+        .find(|refund| {
+            end += refund.amount;
+            offset < end - 0.001
+        })
+        .map(|refund| refund.id.as_str())
+}
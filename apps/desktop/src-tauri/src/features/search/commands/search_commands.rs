@@ -0,0 +1,66 @@
+use crate::db::RepositoryFactory;
+use crate::features::search::dtos::search_dto::SearchDTO;
+use crate::features::search::models::search_model::{
+    CustomerSearchHit, ProductSearchHit, SearchIndexStats,
+};
+use crate::features::search::services::shop_search_service::ShopSearchService;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn search_products(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: SearchDTO,
+) -> Result<Vec<ProductSearchHit>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopSearchService::new(pool, shop_id);
+    service.search_products(payload).await
+}
+
+#[tauri::command]
+pub async fn search_customers(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: SearchDTO,
+) -> Result<Vec<CustomerSearchHit>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopSearchService::new(pool, shop_id);
+    service.search_customers(payload).await
+}
+
+#[tauri::command]
+pub async fn rebuild_search_index(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+) -> Result<SearchIndexStats, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopSearchService::new(pool, shop_id);
+    service.rebuild_index().await
+}
+
+#[tauri::command]
+pub async fn get_search_index_stats(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+) -> Result<SearchIndexStats, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopSearchService::new(pool, shop_id);
+    service.get_index_stats().await
+}
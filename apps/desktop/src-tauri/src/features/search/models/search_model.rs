@@ -0,0 +1,35 @@
+use crate::features::customer::models::customer_model::Customer;
+use crate::features::product::models::product_model::Product;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// A full-text match: the matched entity and how it matched
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct SearchMatch {
+    pub entity_id: String,
+    pub score: f64,                // Higher is more relevant
+    pub highlight: Option<String>, // Escaped HTML name, matched terms in <mark>
+    pub snippet: Option<String>,   // Best matching fragment of any column
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProductSearchHit {
+    pub product: Product,
+    pub score: f64,
+    pub highlight: Option<String>,
+    pub snippet: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CustomerSearchHit {
+    pub customer: Customer,
+    pub score: f64,
+    pub highlight: Option<String>,
+    pub snippet: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchIndexStats {
+    pub products: i64,
+    pub customers: i64,
+}
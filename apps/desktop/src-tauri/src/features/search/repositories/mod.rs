@@ -0,0 +1 @@
+pub mod shop_search_repository;
@@ -0,0 +1,198 @@
+//! Shop-scoped Full-Text Search Repository for Multi-Database Architecture
+//!
+//! Queries the FTS5 indexes (`products_fts`, `customers_fts`) that the
+//! schema triggers keep in sync. Each index row's rowid is the entity's
+//! `search_index_keys` rowid.
+
+use crate::features::search::models::search_model::{SearchIndexStats, SearchMatch};
+use sqlx::{QueryBuilder, Result, Sqlite, SqlitePool};
+use std::sync::Arc;
+
+/// Product relevance, weighting name > sku/gtin > brand > category > attributes
+pub const PRODUCT_RANK: &str = "bm25(products_fts, 10.0, 8.0, 8.0, 3.0, 2.0, 1.0)";
+
+/// Customer relevance, weighting name > email/tax id > phone
+pub const CUSTOMER_RANK: &str = "bm25(customers_fts, 10.0, 5.0, 3.0, 5.0, 3.0)";
+
+/// Ids of the products matching an FTS query bound right after this fragment
+pub const PRODUCT_MATCH_IDS: &str = "SELECT k.entity_id FROM products_fts
+    JOIN search_index_keys k ON k.rowid = products_fts.rowid
+    WHERE products_fts MATCH ";
+
+const SNIPPET_TOKENS: i64 = 12;
+
+/// Placeholders FTS5 wraps matched terms in, swapped for `<mark>` tags once
+/// the indexed text has been HTML-escaped
+const MARK_START: char = '\u{2}';
+const MARK_END: char = '\u{3}';
+
+/// Escape indexed text for HTML and mark the matched terms
+fn mark_matches(text: &str) -> String {
+    let mut html = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            MARK_START => html.push_str("<mark>"),
+            MARK_END => html.push_str("</mark>"),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            c => html.push(c),
+        }
+    }
+    html
+}
+
+fn into_marked(mut found: SearchMatch) -> SearchMatch {
+    found.highlight = found.highlight.as_deref().map(mark_matches);
+    found.snippet = found.snippet.as_deref().map(mark_matches);
+    found
+}
+
+/// Turn free text into an FTS5 query: every word must match, as a prefix.
+///
+/// Words are split on anything that isn't alphanumeric (the same way the
+/// unicode61 tokenizer splits), so user input can't inject FTS syntax.
+/// Returns `None` when the input has no searchable words.
+pub fn build_match_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+pub struct ShopSearchRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopSearchRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// Matching products, most relevant first
+    pub async fn search_products(
+        &self,
+        match_query: &str,
+        status: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<SearchMatch>> {
+        let mut builder = QueryBuilder::<Sqlite>::new(format!(
+            "SELECT k.entity_id, -{} AS score,
+                highlight(products_fts, 0, char(2), char(3)) AS highlight,
+                snippet(products_fts, -1, char(2), char(3), '…', {}) AS snippet
+            FROM products_fts
+            JOIN search_index_keys k ON k.rowid = products_fts.rowid
+            JOIN products p ON p.id = k.entity_id
+            WHERE p._status != 'deleted' AND products_fts MATCH ",
+            PRODUCT_RANK, SNIPPET_TOKENS
+        ));
+        builder.push_bind(match_query);
+
+        if let Some(status) = status {
+            builder.push(" AND p.status = ");
+            builder.push_bind(status);
+        }
+
+        builder.push(" ORDER BY score DESC LIMIT ");
+        builder.push_bind(limit);
+        builder.push(" OFFSET ");
+        builder.push_bind(offset);
+
+        let found = builder
+            .build_query_as::<SearchMatch>()
+            .fetch_all(&*self.pool)
+            .await?;
+        Ok(found.into_iter().map(into_marked).collect())
+    }
+
+    /// Matching customers, most relevant first
+    pub async fn search_customers(
+        &self,
+        match_query: &str,
+        status: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<SearchMatch>> {
+        let mut builder = QueryBuilder::<Sqlite>::new(format!(
+            "SELECT k.entity_id, -{} AS score,
+                highlight(customers_fts, 0, char(2), char(3)) AS highlight,
+                snippet(customers_fts, -1, char(2), char(3), '…', {}) AS snippet
+            FROM customers_fts
+            JOIN search_index_keys k ON k.rowid = customers_fts.rowid
+            JOIN customers c ON c.id = k.entity_id
+            WHERE (c._status IS NULL OR c._status != 'deleted') AND customers_fts MATCH ",
+            CUSTOMER_RANK, SNIPPET_TOKENS
+        ));
+        builder.push_bind(match_query);
+
+        if let Some(status) = status {
+            builder.push(" AND c.status = ");
+            builder.push_bind(status);
+        }
+
+        builder.push(" ORDER BY score DESC LIMIT ");
+        builder.push_bind(limit);
+        builder.push(" OFFSET ");
+        builder.push_bind(offset);
+
+        let found = builder
+            .build_query_as::<SearchMatch>()
+            .fetch_all(&*self.pool)
+            .await?;
+        Ok(found.into_iter().map(into_marked).collect())
+    }
+
+    /// Drop and repopulate both indexes from the current rows
+    pub async fn rebuild(&self) -> Result<SearchIndexStats> {
+        let mut tx = self.pool.begin().await?;
+
+        for sql in [
+            "DELETE FROM products_fts",
+            "DELETE FROM customers_fts",
+            "DELETE FROM search_index_keys",
+            "INSERT INTO search_index_keys (entity_type, entity_id) SELECT 'product', id FROM products",
+            "INSERT INTO search_index_keys (entity_type, entity_id) SELECT 'customer', id FROM customers",
+            r#"
+            INSERT INTO products_fts (rowid, name, sku, gtin_ean, brand, category, attributes)
+            SELECT doc_id, name, sku, gtin_ean, brand, category, attributes
+            FROM product_search_documents
+            "#,
+            r#"
+            INSERT INTO customers_fts (rowid, name, email, phone, tax_id, digits)
+            SELECT doc_id, name, email, phone, tax_id, digits
+            FROM customer_search_documents
+            "#,
+            "INSERT INTO products_fts (products_fts) VALUES ('optimize')",
+            "INSERT INTO customers_fts (customers_fts) VALUES ('optimize')",
+        ] {
+            sqlx::query(sql).execute(&mut *tx).await?;
+        }
+
+        tx.commit().await?;
+        self.stats().await
+    }
+
+    /// Number of indexed products and customers
+    pub async fn stats(&self) -> Result<SearchIndexStats> {
+        let (products, customers): (i64, i64) = sqlx::query_as(
+            "SELECT (SELECT COUNT(*) FROM products_fts), (SELECT COUNT(*) FROM customers_fts)",
+        )
+        .fetch_one(&*self.pool)
+        .await?;
+
+        Ok(SearchIndexStats {
+            products,
+            customers,
+        })
+    }
+}
@@ -0,0 +1,145 @@
+//! Shop-scoped Full-Text Search Service for Multi-Database Architecture
+//!
+//! Ranked, accent-insensitive prefix search over products and customers,
+//! returning the matched entities with highlighted names and snippets.
+
+use crate::features::customer::repositories::shop_customer_repository::ShopCustomerRepository;
+use crate::features::product::repositories::shop_product_repository::ShopProductRepository;
+use crate::features::search::dtos::search_dto::SearchDTO;
+use crate::features::search::models::search_model::{
+    CustomerSearchHit, ProductSearchHit, SearchIndexStats,
+};
+use crate::features::search::repositories::shop_search_repository::{
+    build_match_query, ShopSearchRepository,
+};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const DEFAULT_SEARCH_LIMIT: i64 = 20;
+const MAX_SEARCH_LIMIT: i64 = 100;
+
+pub struct ShopSearchService {
+    pool: Arc<SqlitePool>,
+    shop_id: String,
+    repo: ShopSearchRepository,
+}
+
+impl ShopSearchService {
+    pub fn new(pool: Arc<SqlitePool>, shop_id: String) -> Self {
+        let repo = ShopSearchRepository::new(pool.clone());
+        Self {
+            pool,
+            shop_id,
+            repo,
+        }
+    }
+
+    pub fn pool(&self) -> Arc<SqlitePool> {
+        self.pool.clone()
+    }
+
+    pub async fn search_products(
+        &self,
+        payload: SearchDTO,
+    ) -> Result<Vec<ProductSearchHit>, String> {
+        let Some(match_query) = build_match_query(&payload.query) else {
+            return Ok(Vec::new());
+        };
+        let (limit, offset) = page(&payload);
+
+        let matches = self
+            .repo
+            .search_products(&match_query, payload.status.as_deref(), limit, offset)
+            .await
+            .map_err(|e| format!("Failed to search products: {}", e))?;
+
+        let ids: Vec<String> = matches.iter().map(|m| m.entity_id.clone()).collect();
+        let mut products: HashMap<String, _> =
+            ShopProductRepository::new(self.pool.clone(), self.shop_id.clone())
+                .list_by_ids(&ids)
+                .await
+                .map_err(|e| format!("Failed to load products: {}", e))?
+                .into_iter()
+                .map(|p| (p.id.clone(), p))
+                .collect();
+
+        Ok(matches
+            .into_iter()
+            .filter_map(|m| {
+                products
+                    .remove(&m.entity_id)
+                    .map(|product| ProductSearchHit {
+                        product,
+                        score: m.score,
+                        highlight: m.highlight,
+                        snippet: m.snippet,
+                    })
+            })
+            .collect())
+    }
+
+    pub async fn search_customers(
+        &self,
+        payload: SearchDTO,
+    ) -> Result<Vec<CustomerSearchHit>, String> {
+        let Some(match_query) = build_match_query(&payload.query) else {
+            return Ok(Vec::new());
+        };
+        let (limit, offset) = page(&payload);
+
+        let matches = self
+            .repo
+            .search_customers(&match_query, payload.status.as_deref(), limit, offset)
+            .await
+            .map_err(|e| format!("Failed to search customers: {}", e))?;
+
+        let ids: Vec<String> = matches.iter().map(|m| m.entity_id.clone()).collect();
+        let mut customers: HashMap<String, _> =
+            ShopCustomerRepository::new(self.pool.clone(), self.shop_id.clone())
+                .list_by_ids(&ids)
+                .await
+                .map_err(|e| format!("Failed to load customers: {}", e))?
+                .into_iter()
+                .map(|c| (c.id.clone(), c))
+                .collect();
+
+        Ok(matches
+            .into_iter()
+            .filter_map(|m| {
+                customers
+                    .remove(&m.entity_id)
+                    .map(|customer| CustomerSearchHit {
+                        customer,
+                        score: m.score,
+                        highlight: m.highlight,
+                        snippet: m.snippet,
+                    })
+            })
+            .collect())
+    }
+
+    /// Rebuild both indexes from the current products and customers
+    pub async fn rebuild_index(&self) -> Result<SearchIndexStats, String> {
+        self.repo
+            .rebuild()
+            .await
+            .map_err(|e| format!("Failed to rebuild search index: {}", e))
+    }
+
+    pub async fn get_index_stats(&self) -> Result<SearchIndexStats, String> {
+        self.repo
+            .stats()
+            .await
+            .map_err(|e| format!("Failed to get search index stats: {}", e))
+    }
+}
+
+fn page(payload: &SearchDTO) -> (i64, i64) {
+    let limit = payload
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+    let offset = payload.offset.unwrap_or(0).max(0);
+    (limit, offset)
+}
@@ -0,0 +1 @@
+pub mod service_appointment_commands;
@@ -0,0 +1,99 @@
+use crate::db::RepositoryFactory;
+use crate::features::service_booking::dtos::service_appointment_dto::{
+    CreateServiceAppointmentDTO, ServiceAppointmentFilterDTO, UpdateServiceAppointmentDTO,
+};
+use crate::features::service_booking::models::service_appointment_model::ServiceAppointment;
+use crate::features::service_booking::services::shop_service_appointment_service::ShopServiceAppointmentService;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn create_service_appointment(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: CreateServiceAppointmentDTO,
+) -> Result<ServiceAppointment, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopServiceAppointmentService::new(pool, shop_id);
+    service.create_appointment(payload).await
+}
+
+#[tauri::command]
+pub async fn update_service_appointment(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: UpdateServiceAppointmentDTO,
+) -> Result<ServiceAppointment, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopServiceAppointmentService::new(pool, shop_id);
+    service.update_appointment(payload).await
+}
+
+#[tauri::command]
+pub async fn set_service_appointment_status(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+    status: String,
+) -> Result<ServiceAppointment, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopServiceAppointmentService::new(pool, shop_id);
+    service.set_status(&id, &status).await
+}
+
+#[tauri::command]
+pub async fn get_service_appointment(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    id: String,
+) -> Result<Option<ServiceAppointment>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopServiceAppointmentService::new(pool, shop_id);
+    service.get_appointment(&id).await
+}
+
+#[tauri::command]
+pub async fn list_service_appointments(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    filter: Option<ServiceAppointmentFilterDTO>,
+) -> Result<Vec<ServiceAppointment>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopServiceAppointmentService::new(pool, shop_id);
+    service.list_appointments(filter.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn list_item_service_appointments(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    transaction_item_id: String,
+) -> Result<Vec<ServiceAppointment>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopServiceAppointmentService::new(pool, shop_id);
+    service.list_item_appointments(&transaction_item_id).await
+}
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateServiceAppointmentDTO {
+    pub transaction_item_id: String,
+    pub staff_id: Option<String>,
+    pub location_id: Option<String>,
+    pub scheduled_at: Option<DateTime<Utc>>,
+    pub duration_minutes: Option<i64>, // Defaults to 60
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateServiceAppointmentDTO {
+    pub id: String,
+    pub staff_id: Option<String>,
+    pub location_id: Option<String>,
+    pub scheduled_at: Option<DateTime<Utc>>,
+    pub duration_minutes: Option<i64>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ServiceAppointmentFilterDTO {
+    pub staff_id: Option<String>,
+    pub customer_id: Option<String>,
+    pub location_id: Option<String>,
+    pub status: Option<String>,
+    pub from: Option<DateTime<Utc>>, // scheduled_at >= from
+    pub to: Option<DateTime<Utc>>,   // scheduled_at < to
+}
@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// An appointment for one unit of a sold service
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ServiceAppointment {
+    pub id: String,
+    pub transaction_item_id: String,
+    pub product_id: Option<String>,
+    pub customer_id: Option<String>,
+    pub staff_id: Option<String>, // User from the registry
+    pub location_id: Option<String>,
+    pub scheduled_at: Option<DateTime<Utc>>, // Not scheduled yet when None
+    pub duration_minutes: i64,
+    pub status: String, // 'scheduled', 'completed', 'cancelled', 'no_show'
+    pub notes: Option<String>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
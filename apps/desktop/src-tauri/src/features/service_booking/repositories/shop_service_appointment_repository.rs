@@ -0,0 +1,201 @@
+//! Shop-scoped Service Appointment Repository for Multi-Database Architecture
+
+use crate::features::service_booking::dtos::service_appointment_dto::ServiceAppointmentFilterDTO;
+use crate::features::service_booking::models::service_appointment_model::ServiceAppointment;
+use chrono::{DateTime, Utc};
+use sqlx::{QueryBuilder, Result, Sqlite, SqlitePool, Transaction};
+use std::sync::Arc;
+
+pub struct ShopServiceAppointmentRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopServiceAppointmentRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, appointment: &ServiceAppointment) -> Result<ServiceAppointment> {
+        let sql = r#"
+            INSERT INTO service_appointments (
+                id, transaction_item_id, product_id, customer_id, staff_id, location_id,
+                scheduled_at, duration_minutes, status, notes, _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, ServiceAppointment>(sql)
+            .bind(&appointment.id)
+            .bind(&appointment.transaction_item_id)
+            .bind(&appointment.product_id)
+            .bind(&appointment.customer_id)
+            .bind(&appointment.staff_id)
+            .bind(&appointment.location_id)
+            .bind(appointment.scheduled_at)
+            .bind(appointment.duration_minutes)
+            .bind(&appointment.status)
+            .bind(&appointment.notes)
+            .bind(&appointment.sync_status)
+            .bind(appointment.created_at)
+            .bind(appointment.updated_at)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn update(&self, appointment: &ServiceAppointment) -> Result<ServiceAppointment> {
+        let sql = r#"
+            UPDATE service_appointments
+            SET staff_id = $2, location_id = $3, scheduled_at = $4, duration_minutes = $5,
+                notes = $6, _status = 'modified', updated_at = $7
+            WHERE id = $1
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, ServiceAppointment>(sql)
+            .bind(&appointment.id)
+            .bind(&appointment.staff_id)
+            .bind(&appointment.location_id)
+            .bind(appointment.scheduled_at)
+            .bind(appointment.duration_minutes)
+            .bind(&appointment.notes)
+            .bind(appointment.updated_at)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn get_by_id(&self, id: &str) -> Result<Option<ServiceAppointment>> {
+        let sql = "SELECT * FROM service_appointments WHERE id = $1";
+        sqlx::query_as::<_, ServiceAppointment>(sql)
+            .bind(id)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn list_by_transaction_item(
+        &self,
+        transaction_item_id: &str,
+    ) -> Result<Vec<ServiceAppointment>> {
+        let sql = r#"
+            SELECT * FROM service_appointments
+            WHERE transaction_item_id = $1
+            ORDER BY created_at ASC, id ASC
+        "#;
+        sqlx::query_as::<_, ServiceAppointment>(sql)
+            .bind(transaction_item_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// Appointments matching the filter, unscheduled ones last
+    pub async fn list_filtered(
+        &self,
+        filter: &ServiceAppointmentFilterDTO,
+    ) -> Result<Vec<ServiceAppointment>> {
+        let mut builder =
+            QueryBuilder::<Sqlite>::new("SELECT * FROM service_appointments WHERE 1 = 1");
+
+        if let Some(staff_id) = &filter.staff_id {
+            builder.push(" AND staff_id = ");
+            builder.push_bind(staff_id);
+        }
+        if let Some(customer_id) = &filter.customer_id {
+            builder.push(" AND customer_id = ");
+            builder.push_bind(customer_id);
+        }
+        if let Some(location_id) = &filter.location_id {
+            builder.push(" AND location_id = ");
+            builder.push_bind(location_id);
+        }
+        if let Some(status) = &filter.status {
+            builder.push(" AND status = ");
+            builder.push_bind(status);
+        }
+        if let Some(from) = filter.from {
+            builder.push(" AND datetime(scheduled_at) >= datetime(");
+            builder.push_bind(from);
+            builder.push(")");
+        }
+        if let Some(to) = filter.to {
+            builder.push(" AND datetime(scheduled_at) < datetime(");
+            builder.push_bind(to);
+            builder.push(")");
+        }
+
+        builder.push(" ORDER BY scheduled_at IS NULL, datetime(scheduled_at) ASC, created_at ASC");
+
+        let query = builder.build_query_as::<ServiceAppointment>();
+        query.fetch_all(&*self.pool).await
+    }
+
+    /// Appointments of a sold line that still take up one of its units
+    pub async fn count_active_for_item(&self, transaction_item_id: &str) -> Result<i64> {
+        let sql = r#"
+            SELECT COUNT(*) FROM service_appointments
+            WHERE transaction_item_id = $1 AND status != 'cancelled'
+        "#;
+        sqlx::query_scalar::<_, i64>(sql)
+            .bind(transaction_item_id)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    /// A scheduled appointment of the staff member overlapping the given slot
+    pub async fn find_staff_conflict(
+        &self,
+        staff_id: &str,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+        exclude_id: Option<&str>,
+    ) -> Result<Option<ServiceAppointment>> {
+        let sql = r#"
+            SELECT * FROM service_appointments
+            WHERE staff_id = $1 AND status = 'scheduled' AND scheduled_at IS NOT NULL
+              AND datetime(scheduled_at) < datetime($3)
+              AND datetime(scheduled_at, '+' || duration_minutes || ' minutes') > datetime($2)
+              AND ($4 IS NULL OR id != $4)
+            ORDER BY datetime(scheduled_at) ASC
+            LIMIT 1
+        "#;
+        sqlx::query_as::<_, ServiceAppointment>(sql)
+            .bind(staff_id)
+            .bind(starts_at)
+            .bind(ends_at)
+            .bind(exclude_id)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    /// Move a scheduled appointment to its final status. Returns false when
+    /// it was no longer scheduled.
+    pub async fn close(&self, id: &str, status: &str) -> Result<bool> {
+        let sql = r#"
+            UPDATE service_appointments
+            SET status = $2, _status = 'modified', updated_at = datetime('now')
+            WHERE id = $1 AND status = 'scheduled'
+        "#;
+        let result = sqlx::query(sql)
+            .bind(id)
+            .bind(status)
+            .execute(&*self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Cancel the scheduled appointments of every line of a transaction
+    pub async fn cancel_by_transaction_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        transaction_id: &str,
+    ) -> Result<u64> {
+        let sql = r#"
+            UPDATE service_appointments
+            SET status = 'cancelled', _status = 'modified', updated_at = datetime('now')
+            WHERE status = 'scheduled'
+              AND transaction_item_id IN (
+                  SELECT id FROM transaction_items WHERE transaction_id = $1
+              )
+        "#;
+        let result = sqlx::query(sql)
+            .bind(transaction_id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
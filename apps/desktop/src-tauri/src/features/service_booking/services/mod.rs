@@ -0,0 +1 @@
+pub mod shop_service_appointment_service;
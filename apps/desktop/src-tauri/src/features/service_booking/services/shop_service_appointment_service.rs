@@ -0,0 +1,240 @@
+//! Shop-scoped Service Appointment Service for Multi-Database Architecture
+//!
+//! Each unit of a sold service can be booked once, optionally with a time
+//! slot and a staff member. A staff member cannot hold two scheduled
+//! appointments that overlap.
+
+use crate::features::product::repositories::shop_product_repository::ShopProductRepository;
+use crate::features::service_booking::dtos::service_appointment_dto::{
+    CreateServiceAppointmentDTO, ServiceAppointmentFilterDTO, UpdateServiceAppointmentDTO,
+};
+use crate::features::service_booking::models::service_appointment_model::ServiceAppointment;
+use crate::features::service_booking::repositories::shop_service_appointment_repository::ShopServiceAppointmentRepository;
+use crate::features::transaction::repositories::shop_transaction_repository::ShopTransactionRepository;
+use crate::features::transaction::repositories::transaction_items_repository::TransactionItemsRepository;
+use chrono::{Duration, Utc};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const CLOSING_STATUSES: [&str; 3] = ["completed", "cancelled", "no_show"];
+
+pub struct ShopServiceAppointmentService {
+    pool: Arc<SqlitePool>,
+    shop_id: String,
+    repo: ShopServiceAppointmentRepository,
+}
+
+impl ShopServiceAppointmentService {
+    pub fn new(pool: Arc<SqlitePool>, shop_id: String) -> Self {
+        let repo = ShopServiceAppointmentRepository::new(pool.clone());
+        Self {
+            pool,
+            shop_id,
+            repo,
+        }
+    }
+
+    pub fn shop_id(&self) -> &str {
+        &self.shop_id
+    }
+
+    /// Fail when the staff member is already booked during the slot
+    async fn check_staff_free(&self, appointment: &ServiceAppointment) -> Result<(), String> {
+        let (staff_id, starts_at) = match (&appointment.staff_id, appointment.scheduled_at) {
+            (Some(staff_id), Some(starts_at)) => (staff_id, starts_at),
+            _ => return Ok(()),
+        };
+        let ends_at = starts_at + Duration::minutes(appointment.duration_minutes);
+
+        let conflict = self
+            .repo
+            .find_staff_conflict(staff_id, starts_at, ends_at, Some(&appointment.id))
+            .await
+            .map_err(|e| format!("Failed to check staff schedule: {}", e))?;
+        match conflict {
+            Some(other) => Err(format!(
+                "Staff member is already booked at {}",
+                other
+                    .scheduled_at
+                    .map(|at| at.to_rfc3339())
+                    .unwrap_or_default()
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub async fn create_appointment(
+        &self,
+        payload: CreateServiceAppointmentDTO,
+    ) -> Result<ServiceAppointment, String> {
+        let item = TransactionItemsRepository::new((*self.pool).clone())
+            .get_by_id(&payload.transaction_item_id)
+            .await
+            .map_err(|e| format!("Failed to fetch transaction item: {}", e))?
+            .ok_or_else(|| {
+                format!(
+                    "Transaction item not found: {}",
+                    payload.transaction_item_id
+                )
+            })?;
+
+        let product_id = item
+            .product_id
+            .clone()
+            .ok_or_else(|| "Transaction item has no product".to_string())?;
+        let product = ShopProductRepository::new(self.pool.clone(), self.shop_id.clone())
+            .get_by_id(&product_id)
+            .await
+            .map_err(|e| format!("Failed to fetch product: {}", e))?
+            .ok_or_else(|| format!("Product not found: {}", product_id))?;
+        if product.r#type != "service" {
+            return Err(format!("Product {} is not a service", product.sku));
+        }
+
+        let transaction = ShopTransactionRepository::new(self.pool.clone(), self.shop_id.clone())
+            .get_by_id(&item.transaction_id)
+            .await
+            .map_err(|e| format!("Failed to fetch transaction: {}", e))?
+            .ok_or_else(|| format!("Transaction not found: {}", item.transaction_id))?;
+        if transaction.status == "cancelled" {
+            return Err("Cannot book a service of a cancelled transaction".to_string());
+        }
+
+        let booked = self
+            .repo
+            .count_active_for_item(&item.id)
+            .await
+            .map_err(|e| format!("Failed to count appointments: {}", e))?;
+        if booked as f64 >= item.quantity {
+            return Err(format!(
+                "All {} units of this line are already booked",
+                item.quantity
+            ));
+        }
+
+        let duration_minutes = payload.duration_minutes.unwrap_or(60);
+        if duration_minutes <= 0 {
+            return Err("duration_minutes must be greater than zero".to_string());
+        }
+
+        let now = Utc::now();
+        let appointment = ServiceAppointment {
+            id: Uuid::new_v4().to_string(),
+            transaction_item_id: item.id,
+            product_id: Some(product_id),
+            customer_id: transaction.customer_id,
+            staff_id: payload.staff_id,
+            location_id: payload.location_id,
+            scheduled_at: payload.scheduled_at,
+            duration_minutes,
+            status: "scheduled".to_string(),
+            notes: payload.notes,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+        self.check_staff_free(&appointment).await?;
+
+        self.repo
+            .create(&appointment)
+            .await
+            .map_err(|e| format!("Failed to create appointment: {}", e))
+    }
+
+    /// Reschedule or reassign a scheduled appointment
+    pub async fn update_appointment(
+        &self,
+        payload: UpdateServiceAppointmentDTO,
+    ) -> Result<ServiceAppointment, String> {
+        let mut appointment = self
+            .repo
+            .get_by_id(&payload.id)
+            .await
+            .map_err(|e| format!("Failed to fetch appointment: {}", e))?
+            .ok_or_else(|| format!("Appointment not found: {}", payload.id))?;
+        if appointment.status != "scheduled" {
+            return Err(format!(
+                "Appointment with status '{}' cannot be changed",
+                appointment.status
+            ));
+        }
+
+        if let Some(staff_id) = payload.staff_id {
+            appointment.staff_id = Some(staff_id);
+        }
+        if let Some(location_id) = payload.location_id {
+            appointment.location_id = Some(location_id);
+        }
+        if let Some(scheduled_at) = payload.scheduled_at {
+            appointment.scheduled_at = Some(scheduled_at);
+        }
+        if let Some(duration_minutes) = payload.duration_minutes {
+            if duration_minutes <= 0 {
+                return Err("duration_minutes must be greater than zero".to_string());
+            }
+            appointment.duration_minutes = duration_minutes;
+        }
+        if let Some(notes) = payload.notes {
+            appointment.notes = Some(notes);
+        }
+        appointment.updated_at = Some(Utc::now());
+
+        self.check_staff_free(&appointment).await?;
+
+        self.repo
+            .update(&appointment)
+            .await
+            .map_err(|e| format!("Failed to update appointment: {}", e))
+    }
+
+    /// Mark a scheduled appointment as completed, cancelled or no-show
+    pub async fn set_status(&self, id: &str, status: &str) -> Result<ServiceAppointment, String> {
+        if !CLOSING_STATUSES.contains(&status) {
+            return Err(format!(
+                "Invalid appointment status: {} (expected completed, cancelled or no_show)",
+                status
+            ));
+        }
+
+        let closed = self
+            .repo
+            .close(id, status)
+            .await
+            .map_err(|e| format!("Failed to update appointment status: {}", e))?;
+        if !closed {
+            return Err(format!("Appointment {} is not scheduled", id));
+        }
+
+        self.get_appointment(id)
+            .await?
+            .ok_or_else(|| format!("Appointment not found: {}", id))
+    }
+
+    pub async fn get_appointment(&self, id: &str) -> Result<Option<ServiceAppointment>, String> {
+        self.repo
+            .get_by_id(id)
+            .await
+            .map_err(|e| format!("Failed to fetch appointment: {}", e))
+    }
+
+    pub async fn list_appointments(
+        &self,
+        filter: ServiceAppointmentFilterDTO,
+    ) -> Result<Vec<ServiceAppointment>, String> {
+        self.repo
+            .list_filtered(&filter)
+            .await
+            .map_err(|e| format!("Failed to list appointments: {}", e))
+    }
+
+    pub async fn list_item_appointments(
+        &self,
+        transaction_item_id: &str,
+    ) -> Result<Vec<ServiceAppointment>, String> {
+        self.repo
+            .list_by_transaction_item(transaction_item_id)
+            .await
+            .map_err(|e| format!("Failed to list appointments: {}", e))
+    }
+}
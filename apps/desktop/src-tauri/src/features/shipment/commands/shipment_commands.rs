@@ -1,6 +1,6 @@
 use crate::db::RepositoryFactory;
 use crate::features::shipment::dtos::shipment_dto::{CreateShipmentDTO, UpdateShipmentDTO};
-use crate::features::shipment::models::shipment_model::Shipment;
+use crate::features::shipment::models::shipment_model::{Shipment, ShipmentEvent};
 use crate::features::shipment::services::shop_shipment_service::ShopShipmentService;
 use chrono::Utc;
 use std::sync::Arc;
@@ -13,7 +13,7 @@ pub async fn create_shipment(
     payload: CreateShipmentDTO,
 ) -> Result<Shipment, String> {
     let pool = repo_factory
-        .shop_pool(&shop_id)
+        .shop_module_pool(&shop_id, "shipping")
         .await
         .map_err(|e| format!("Failed to get shop pool: {}", e))?;
 
@@ -31,7 +31,7 @@ pub async fn update_shipment(
     payload: UpdateShipmentDTO,
 ) -> Result<Shipment, String> {
     let pool = repo_factory
-        .shop_pool(&shop_id)
+        .shop_module_pool(&shop_id, "shipping")
         .await
         .map_err(|e| format!("Failed to get shop pool: {}", e))?;
 
@@ -80,7 +80,7 @@ pub async fn delete_shipment(
     id: String,
 ) -> Result<(), String> {
     let pool = repo_factory
-        .shop_pool(&shop_id)
+        .shop_module_pool(&shop_id, "shipping")
         .await
         .map_err(|e| format!("Failed to get shop pool: {}", e))?;
 
@@ -95,7 +95,7 @@ pub async fn get_shipment(
     id: String,
 ) -> Result<Option<Shipment>, String> {
     let pool = repo_factory
-        .shop_pool(&shop_id)
+        .shop_module_pool(&shop_id, "shipping")
         .await
         .map_err(|e| format!("Failed to get shop pool: {}", e))?;
 
@@ -109,7 +109,7 @@ pub async fn list_shipments(
     shop_id: String,
 ) -> Result<Vec<Shipment>, String> {
     let pool = repo_factory
-        .shop_pool(&shop_id)
+        .shop_module_pool(&shop_id, "shipping")
         .await
         .map_err(|e| format!("Failed to get shop pool: {}", e))?;
 
@@ -123,10 +123,25 @@ pub async fn list_shipments_by_shop(
     shop_id: String,
 ) -> Result<Vec<Shipment>, String> {
     let pool = repo_factory
-        .shop_pool(&shop_id)
+        .shop_module_pool(&shop_id, "shipping")
         .await
         .map_err(|e| format!("Failed to get shop pool: {}", e))?;
 
     let service = ShopShipmentService::new(pool);
     service.list_shipments().await
 }
+
+#[tauri::command]
+pub async fn list_shipment_events(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    shipment_id: String,
+) -> Result<Vec<ShipmentEvent>, String> {
+    let pool = repo_factory
+        .shop_module_pool(&shop_id, "shipping")
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+    let service = ShopShipmentService::new(pool);
+    service.list_events(&shipment_id).await
+}
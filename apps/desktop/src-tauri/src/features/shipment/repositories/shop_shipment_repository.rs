@@ -1,8 +1,8 @@
 //! Shop-scoped Shipment Repository for Multi-Database Architecture
 
-use crate::features::shipment::models::shipment_model::Shipment;
-use chrono::Utc;
-use sqlx::{Result, SqlitePool};
+use crate::features::shipment::models::shipment_model::{Shipment, ShipmentEvent, ShipmentItem};
+use chrono::{DateTime, Utc};
+use sqlx::{Result, Sqlite, SqlitePool, Transaction};
 use std::sync::Arc;
 
 pub struct ShopShipmentRepository {
@@ -193,4 +193,150 @@ impl ShopShipmentRepository {
             .fetch_one(&*self.pool)
             .await
     }
+
+    /// Shipments handed to an integrated carrier and not yet delivered
+    pub async fn list_trackable(&self) -> Result<Vec<Shipment>> {
+        let sql = r#"
+            SELECT * FROM shipments
+            WHERE carrier_company IS NOT NULL
+              AND (tracking_number IS NOT NULL OR metadata IS NOT NULL)
+              AND COALESCE(status, 'pending') NOT IN ('delivered', 'cancelled', 'returned')
+              AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY created_at ASC
+        "#;
+        sqlx::query_as::<_, Shipment>(sql)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn list_events(&self, shipment_id: &str) -> Result<Vec<ShipmentEvent>> {
+        let sql = r#"
+            SELECT * FROM shipment_events
+            WHERE shipment_id = $1 AND (_status IS NULL OR _status != 'deleted')
+            ORDER BY happened_at ASC
+        "#;
+        sqlx::query_as::<_, ShipmentEvent>(sql)
+            .bind(shipment_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    // ============================================================
+    // Transaction-aware methods for atomic operations
+    // ============================================================
+
+    pub async fn get_by_id_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+    ) -> Result<Option<Shipment>> {
+        let sql = "SELECT * FROM shipments WHERE id = $1 AND (_status IS NULL OR _status != 'deleted')";
+        sqlx::query_as::<_, Shipment>(sql)
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    pub async fn list_items_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        shipment_id: &str,
+    ) -> Result<Vec<ShipmentItem>> {
+        let sql = "SELECT * FROM shipment_items WHERE shipment_id = $1 AND (_status IS NULL OR _status != 'deleted')";
+        sqlx::query_as::<_, ShipmentItem>(sql)
+            .bind(shipment_id)
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    pub async fn mark_shipped_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+        tracking_number: Option<&str>,
+    ) -> Result<Shipment> {
+        let sql = r#"
+            UPDATE shipments
+            SET status = 'shipped', shipped_at = $2, tracking_number = COALESCE($3, tracking_number), _status = 'modified', updated_at = $4
+            WHERE id = $1
+            RETURNING *
+        "#;
+        let now = Utc::now();
+        sqlx::query_as::<_, Shipment>(sql)
+            .bind(id)
+            .bind(now)
+            .bind(tracking_number)
+            .bind(now)
+            .fetch_one(&mut **tx)
+            .await
+    }
+
+    pub async fn event_exists_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        shipment_id: &str,
+        status: &str,
+        happened_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        let sql = r#"
+            SELECT COUNT(*) FROM shipment_events
+            WHERE shipment_id = $1 AND status = $2 AND happened_at = $3
+        "#;
+        let (count,): (i64,) = sqlx::query_as(sql)
+            .bind(shipment_id)
+            .bind(status)
+            .bind(happened_at)
+            .fetch_one(&mut **tx)
+            .await?;
+        Ok(count > 0)
+    }
+
+    pub async fn create_event_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        event: &ShipmentEvent,
+    ) -> Result<ShipmentEvent> {
+        let sql = r#"
+            INSERT INTO shipment_events (
+                id, shipment_id, status, description, location,
+                happened_at, raw_data, _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, ShipmentEvent>(sql)
+            .bind(&event.id)
+            .bind(&event.shipment_id)
+            .bind(&event.status)
+            .bind(&event.description)
+            .bind(&event.location)
+            .bind(event.happened_at)
+            .bind(&event.raw_data)
+            .bind(&event.sync_status)
+            .bind(event.created_at)
+            .bind(event.updated_at)
+            .fetch_one(&mut **tx)
+            .await
+    }
+
+    /// Move a shipment to a tracking status, stamping shipped_at/delivered_at on the way
+    pub async fn advance_status_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+        status: &str,
+        happened_at: DateTime<Utc>,
+    ) -> Result<Shipment> {
+        let sql = r#"
+            UPDATE shipments SET
+                status = $2,
+                shipped_at = CASE WHEN $2 IN ('shipped', 'in_transit', 'out_for_delivery', 'delivered')
+                    THEN COALESCE(shipped_at, $3) ELSE shipped_at END,
+                delivered_at = CASE WHEN $2 = 'delivered' THEN $3 ELSE delivered_at END,
+                _status = 'modified',
+                updated_at = $4
+            WHERE id = $1
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, Shipment>(sql)
+            .bind(id)
+            .bind(status)
+            .bind(happened_at)
+            .bind(Utc::now())
+            .fetch_one(&mut **tx)
+            .await
+    }
 }
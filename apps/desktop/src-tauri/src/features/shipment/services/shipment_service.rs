@@ -3,6 +3,7 @@ use crate::features::shipment::models::shipment_model::{Shipment, ShipmentEvent,
 use crate::features::shipment::repositories::shipment_events_repository::ShipmentEventsRepository;
 use crate::features::shipment::repositories::shipment_items_repository::ShipmentItemsRepository;
 use crate::features::shipment::repositories::shipments_repository::ShipmentsRepository;
+use crate::features::shipment::services::shop_shipment_service::ShopShipmentService;
 use chrono::Utc;
 use sqlx::SqlitePool;
 use uuid::Uuid;
@@ -44,14 +45,29 @@ impl ShipmentService {
             .await
             .map_err(|e| format!("Erro ao iniciar transação: {}", e))?;
 
+        let created_shipment = Self::create_full_shipment_in_tx(&mut tx, shipment, items).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Erro ao confirmar transação: {}", e))?;
+
+        Ok(created_shipment)
+    }
+
+    /// Create a full shipment with items inside the caller's transaction
+    pub async fn create_full_shipment_in_tx(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        shipment: Shipment,
+        items: Vec<ShipmentItem>,
+    ) -> Result<Shipment, String> {
         // Create shipment
-        let created_shipment = ShipmentsRepository::create_with_tx(&mut tx, shipment)
+        let created_shipment = ShipmentsRepository::create_with_tx(tx, shipment)
             .await
             .map_err(|e| format!("Erro ao criar envio: {}", e))?;
 
         // Create items if any
         if !items.is_empty() {
-            ShipmentItemsRepository::create_many_with_tx(&mut tx, items)
+            ShipmentItemsRepository::create_many_with_tx(tx, items)
                 .await
                 .map_err(|e| format!("Erro ao criar itens do envio: {}", e))?;
         }
@@ -70,14 +86,10 @@ impl ShipmentService {
             updated_at: Some(Utc::now()),
         };
 
-        ShipmentEventsRepository::create_with_tx(&mut tx, initial_event)
+        ShipmentEventsRepository::create_with_tx(tx, initial_event)
             .await
             .map_err(|e| format!("Erro ao criar evento inicial: {}", e))?;
 
-        tx.commit()
-            .await
-            .map_err(|e| format!("Erro ao confirmar transação: {}", e))?;
-
         Ok(created_shipment)
     }
 
@@ -137,12 +149,22 @@ impl ShipmentService {
             .await
             .map_err(|e| format!("Erro ao iniciar transação: {}", e))?;
 
+        let shipment = ShipmentsRepository::get_by_id_with_tx(&mut tx, shipment_id)
+            .await
+            .map_err(|e| format!("Erro ao buscar envio: {}", e))?
+            .ok_or_else(|| "Envio não encontrado".to_string())?;
+
         // Update shipped_at
         let updated_shipment =
             ShipmentsRepository::update_shipped_at_with_tx(&mut tx, shipment_id, tracking_number)
                 .await
                 .map_err(|e| format!("Erro ao marcar como enviado: {}", e))?;
 
+        // Items leave stock on first dispatch (components for bundles)
+        if shipment.shipped_at.is_none() {
+            ShopShipmentService::fulfil_stock_in_tx(&mut tx, &updated_shipment).await?;
+        }
+
         // Create tracking event
         let event = ShipmentEvent {
             id: Uuid::new_v4().to_string(),
@@ -1,8 +1,11 @@
 //! Shop-scoped Shipment Service for Multi-Database Architecture
 
-use crate::features::shipment::models::shipment_model::Shipment;
+use crate::features::bundle::services::shop_bundle_service::ShopBundleService;
+use crate::features::inventory::repositories::shop_inventory_repository::ShopInventoryRepository;
+use crate::features::shipment::models::shipment_model::{Shipment, ShipmentEvent};
 use crate::features::shipment::repositories::shop_shipment_repository::ShopShipmentRepository;
-use sqlx::SqlitePool;
+use crate::features::transaction::repositories::shop_transaction_repository::ShopTransactionRepository;
+use sqlx::{Sqlite, SqlitePool, Transaction};
 use std::sync::Arc;
 
 pub struct ShopShipmentService {
@@ -74,10 +77,30 @@ impl ShopShipmentService {
         id: &str,
         tracking_number: Option<&str>,
     ) -> Result<Shipment, String> {
-        self.repo
-            .mark_shipped(id, tracking_number)
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let shipment = ShopShipmentRepository::get_by_id_in_tx(&mut tx, id)
+            .await
+            .map_err(|e| format!("Failed to get shipment: {}", e))?
+            .ok_or_else(|| format!("Shipment not found: {}", id))?;
+
+        let updated = ShopShipmentRepository::mark_shipped_in_tx(&mut tx, id, tracking_number)
             .await
-            .map_err(|e| format!("Failed to mark shipment as shipped: {}", e))
+            .map_err(|e| format!("Failed to mark shipment as shipped: {}", e))?;
+
+        if shipment.shipped_at.is_none() {
+            Self::fulfil_stock_in_tx(&mut tx, &updated).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(updated)
     }
 
     pub async fn mark_delivered(&self, id: &str) -> Result<Shipment, String> {
@@ -86,4 +109,61 @@ impl ShopShipmentService {
             .await
             .map_err(|e| format!("Failed to mark shipment as delivered: {}", e))
     }
+
+    pub async fn list_events(&self, shipment_id: &str) -> Result<Vec<ShipmentEvent>, String> {
+        self.repo
+            .list_events(shipment_id)
+            .await
+            .map_err(|e| format!("Failed to list shipment events: {}", e))
+    }
+
+    /// Take the shipped items out of stock at the shipment's location. Call it
+    /// once, when the shipment is first dispatched. Items of a sale that already
+    /// moved stock when it was completed are skipped, as are items that cannot
+    /// be traced to a product; bundles move their components.
+    pub async fn fulfil_stock_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        shipment: &Shipment,
+    ) -> Result<(), String> {
+        let location_id = match &shipment.location_id {
+            Some(location_id) => location_id,
+            None => return Ok(()),
+        };
+
+        let items = ShopShipmentRepository::list_items_in_tx(tx, &shipment.id)
+            .await
+            .map_err(|e| format!("Failed to list shipment items: {}", e))?;
+
+        for item in items {
+            let sold_item =
+                ShopTransactionRepository::get_item_by_id_in_tx(tx, &item.order_item_id)
+                    .await
+                    .map_err(|e| format!("Failed to fetch transaction item: {}", e))?;
+            let (transaction_id, product_id) = match sold_item {
+                Some(sold) => match sold.product_id {
+                    Some(product_id) => (sold.transaction_id, product_id),
+                    None => continue,
+                },
+                None => continue,
+            };
+
+            let already_moved =
+                ShopInventoryRepository::has_transaction_movements_in_tx(tx, &transaction_id)
+                    .await
+                    .map_err(|e| format!("Failed to check inventory movements: {}", e))?;
+            if already_moved {
+                continue;
+            }
+
+            ShopBundleService::stock_out_in_tx(
+                tx,
+                &product_id,
+                item.quantity as f64,
+                location_id,
+                None,
+            )
+            .await?;
+        }
+        Ok(())
+    }
 }
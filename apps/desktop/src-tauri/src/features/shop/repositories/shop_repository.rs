@@ -1,5 +1,5 @@
 use crate::features::shop::models::shop_model::Shop;
-use sqlx::{Result, SqlitePool};
+use sqlx::{Result, Sqlite, SqlitePool, Transaction};
 
 pub struct ShopsRepository {
     pool: SqlitePool,
@@ -95,6 +95,16 @@ impl ShopsRepository {
             .await
     }
 
+    /// Shops of an owner that were not deleted
+    pub async fn list_by_owner(&self, owner_id: &str) -> Result<Vec<Shop>> {
+        sqlx::query_as::<_, Shop>(
+            "SELECT * FROM shops WHERE owner_id = ? AND _status != 'deleted' ORDER BY name ASC",
+        )
+        .bind(owner_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
     pub async fn delete(&self, id: &str) -> Result<()> {
         sqlx::query("DELETE FROM shops WHERE id = ?")
             .bind(id)
@@ -102,4 +112,47 @@ impl ShopsRepository {
             .await?;
         Ok(())
     }
+
+    pub async fn update_features_config_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+        features_config: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE shops SET features_config = ?, _status = 'modified', updated_at = datetime('now') WHERE id = ?",
+        )
+        .bind(features_config)
+        .bind(id)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_database_config_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+        database_type: &str,
+        database_config: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE shops SET database_type = ?, database_config = ?, _status = 'modified', updated_at = datetime('now') WHERE id = ?",
+        )
+        .bind(database_type)
+        .bind(database_config)
+        .bind(id)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_settings(&self, id: &str, settings: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE shops SET settings = ?, _status = 'modified', updated_at = datetime('now') WHERE id = ?",
+        )
+        .bind(settings)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
 }
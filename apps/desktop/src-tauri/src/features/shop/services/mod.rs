@@ -1 +1,2 @@
+pub mod shop_scheduler;
 pub mod shop_service;
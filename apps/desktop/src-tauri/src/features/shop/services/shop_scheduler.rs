@@ -0,0 +1,100 @@
+//! Shared loop of the per-shop background jobs
+//!
+//! Each job runs on the SQLite shops whose database is open, so the jobs
+//! don't keep idle shops open. A shop that was closed at a tick (at app
+//! start no shop is open yet) is caught up as soon as its database opens,
+//! unless the job already ran on it within the last interval.
+
+use crate::db::RepositoryFactory;
+use crate::features::shop::services::shop_service::ShopService;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::MissedTickBehavior;
+
+/// Run `job` for every open SQLite shop right away and then every
+/// `interval`, and for each shop whose database opens in between.
+/// Errors are logged under `[tag]` and never stop the loop.
+pub async fn run_for_open_shops<F, Fut>(
+    repo_factory: Arc<RepositoryFactory>,
+    interval: Duration,
+    tag: &str,
+    job: F,
+) where
+    F: Fn(String, Arc<SqlitePool>) -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    let mut opened = repo_factory.pool_manager().subscribe_shop_opened();
+    let mut ticks = tokio::time::interval(interval);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // Last successful run per shop
+    let mut last_runs: HashMap<String, Instant> = HashMap::new();
+
+    loop {
+        tokio::select! {
+            _ = ticks.tick() => {
+                for (shop_id, pool) in open_shops(&repo_factory, tag).await {
+                    run_job(&job, tag, shop_id, pool, &mut last_runs).await;
+                }
+            }
+            shop_id = opened.recv() => {
+                let shop_id = match shop_id {
+                    Ok(shop_id) => shop_id,
+                    // Missed shops are picked up at the next tick
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return,
+                };
+                if last_runs.get(&shop_id).is_some_and(|at| at.elapsed() < interval) {
+                    continue;
+                }
+                let Some(pool) = repo_factory.pool_manager().peek_shop_pool(&shop_id) else {
+                    continue;
+                };
+                run_job(&job, tag, shop_id, pool, &mut last_runs).await;
+            }
+        }
+    }
+}
+
+async fn run_job<F, Fut>(
+    job: &F,
+    tag: &str,
+    shop_id: String,
+    pool: Arc<SqlitePool>,
+    last_runs: &mut HashMap<String, Instant>,
+) where
+    F: Fn(String, Arc<SqlitePool>) -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    match job(shop_id.clone(), pool).await {
+        Ok(()) => {
+            last_runs.insert(shop_id, Instant::now());
+        }
+        Err(e) => eprintln!("[{}] shop {}: {}", tag, shop_id, e),
+    }
+}
+
+async fn open_shops(repo_factory: &RepositoryFactory, tag: &str) -> Vec<(String, Arc<SqlitePool>)> {
+    let shops = match ShopService::new(repo_factory.registry_pool().clone())
+        .list_shops()
+        .await
+    {
+        Ok(shops) => shops,
+        Err(e) => {
+            eprintln!("[{}] {}", tag, e);
+            return Vec::new();
+        }
+    };
+
+    shops
+        .into_iter()
+        .filter(|s| s.database_type == "sqlite")
+        .filter_map(|shop| {
+            let pool = repo_factory.pool_manager().peek_shop_pool(&shop.id)?;
+            Some((shop.id, pool))
+        })
+        .collect()
+}
@@ -2,6 +2,7 @@ use crate::db::RepositoryFactory;
 use crate::features::shop::dtos::shop_dto::{CreateShopDTO, UpdateShopDTO};
 use crate::features::shop::models::shop_model::Shop;
 use crate::features::shop::repositories::shop_repository::ShopsRepository;
+use crate::features::shop_template::services::shop_template_apply_service::ShopTemplateApplyService;
 use crate::features::shop_template::services::shop_templates_service::ShopTemplatesService;
 use sqlx::SqlitePool;
 use std::sync::Arc;
@@ -64,17 +65,29 @@ impl ShopService {
         let mut create_payload = payload;
 
         // Se um template foi especificado, buscar e aplicar sua configuração
-        if let Some(template_code) = template_code {
+        if let Some(template_code) = &template_code {
             let template_service = ShopTemplatesService::new(self.pool.clone());
             let template = template_service
-                .get_template_by_code(&template_code)
+                .get_template_by_code(template_code)
                 .await
                 .map_err(|e| format!("Erro ao buscar template: {}", e))?
                 .ok_or_else(|| format!("Template não encontrado: {}", template_code))?;
 
-            // Aplicar features_config do template se não foi especificado
+            // Aplicar features_config do template se não foi especificado,
+            // ligando também os módulos recomendados
             if create_payload.features_config.is_none() {
-                create_payload.features_config = Some(template.features_config);
+                let mut features: serde_json::Map<String, serde_json::Value> =
+                    serde_json::from_str(&template.features_config).unwrap_or_default();
+                let recommended: Vec<String> = template
+                    .recommended_modules
+                    .as_deref()
+                    .and_then(|codes| serde_json::from_str(codes).ok())
+                    .unwrap_or_default();
+                for code in recommended {
+                    features.insert(code, serde_json::Value::Bool(true));
+                }
+                create_payload.features_config =
+                    Some(serde_json::Value::Object(features).to_string());
             }
 
             // Aplicar default_settings do template se settings não foi especificado
@@ -83,7 +96,17 @@ impl ShopService {
             }
         }
 
-        self.create_shop(create_payload).await
+        let shop = self.create_shop(create_payload).await?;
+
+        // Popular os dados iniciais do template (locais, categorias, grupos...)
+        if let (Some(template_code), Some(repo_factory)) = (template_code, &self.repo_factory) {
+            ShopTemplateApplyService::new(self.pool.clone(), repo_factory.clone())
+                .seed_new_shop(&shop.id, &template_code)
+                .await
+                .map_err(|e| format!("Erro ao aplicar dados do template: {}", e))?;
+        }
+
+        Ok(shop)
     }
 
     pub async fn update_shop(&self, payload: UpdateShopDTO) -> Result<Shop, String> {
@@ -1,6 +1,14 @@
-use crate::features::shop_template::dtos::shop_template_dto::ShopTemplateDto;
+use crate::db::RepositoryFactory;
+use crate::features::shop_template::dtos::shop_template_dto::{
+    ApplyShopTemplateDTO, ExportShopTemplateDTO, ShopTemplateDto,
+};
+use crate::features::shop_template::models::shop_template_model::{
+    ShopTemplateApplication, ShopTemplateApplyResult, ShopTemplateDiff, TemplateSeedData,
+};
+use crate::features::shop_template::services::shop_template_apply_service::ShopTemplateApplyService;
 use crate::features::shop_template::services::shop_templates_service::ShopTemplatesService;
 use sqlx::SqlitePool;
+use std::sync::Arc;
 use tauri::State;
 
 #[tauri::command]
@@ -37,3 +45,65 @@ pub async fn list_shop_templates_by_category(
     let service = ShopTemplatesService::new(pool.inner().clone());
     service.list_templates_by_category(&category).await
 }
+
+#[tauri::command]
+pub async fn diff_shop_template(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    template_code: String,
+) -> Result<ShopTemplateDiff, String> {
+    let service = ShopTemplateApplyService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.diff_template(&shop_id, &template_code).await
+}
+
+#[tauri::command]
+pub async fn apply_shop_template(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    payload: ApplyShopTemplateDTO,
+) -> Result<ShopTemplateApplyResult, String> {
+    let service = ShopTemplateApplyService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.apply_template(payload).await
+}
+
+#[tauri::command]
+pub async fn upgrade_shop_template(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    applied_by: Option<String>,
+) -> Result<ShopTemplateApplyResult, String> {
+    let service = ShopTemplateApplyService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.upgrade_template(&shop_id, applied_by).await
+}
+
+#[tauri::command]
+pub async fn export_shop_as_template(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    payload: ExportShopTemplateDTO,
+) -> Result<ShopTemplateDto, String> {
+    let service = ShopTemplateApplyService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.export_shop_as_template(payload).await
+}
+
+#[tauri::command]
+pub async fn get_shop_template_seed(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    code: String,
+) -> Result<TemplateSeedData, String> {
+    let service = ShopTemplateApplyService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.get_template_seed(&code).await
+}
+
+#[tauri::command]
+pub async fn list_shop_template_applications(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+) -> Result<Vec<ShopTemplateApplication>, String> {
+    let service = ShopTemplateApplyService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.list_applications(&shop_id).await
+}
@@ -31,3 +31,34 @@ impl From<ShopTemplate> for ShopTemplateDto {
         }
     }
 }
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApplyShopTemplateDTO {
+    pub shop_id: String,
+    pub template_code: String,
+    #[serde(default = "default_true")]
+    pub apply_modules: bool,
+    #[serde(default)]
+    pub enable_recommended: bool, // Also switch on recommended_modules
+    #[serde(default)]
+    pub overwrite_settings: bool, // Otherwise only missing settings are added
+    #[serde(default = "default_true")]
+    pub seed_data: bool,
+    pub applied_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportShopTemplateDTO {
+    pub shop_id: String,
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub icon: Option<String>,
+    #[serde(default = "default_true")]
+    pub include_seed_data: bool,
+}
@@ -20,3 +20,153 @@ pub struct ShopTemplate {
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
 }
+
+/// Seed data and version of a template
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ShopTemplateSeed {
+    pub template_id: String,
+    pub version: i64,
+    pub seed_data: String, // JSON stored as TEXT (TemplateSeedData)
+    pub source_shop_id: Option<String>, // Reference shop of exported templates
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A template applied to a shop
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ShopTemplateApplication {
+    pub id: String,
+    pub shop_id: String,
+    pub template_id: String,
+    pub template_version: i64,
+    pub mode: String, // 'create', 'apply', 'upgrade'
+    pub applied_by: Option<String>,
+    pub summary: Option<String>, // JSON stored as TEXT
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Records a template creates in a shop. Existing records are matched by
+/// location name, category slug, customer group code (or name), role name
+/// and tax rule class/region, and are never overwritten.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TemplateSeedData {
+    #[serde(default)]
+    pub locations: Vec<SeedLocation>,
+    #[serde(default)]
+    pub categories: Vec<SeedCategory>, // Parents before children
+    #[serde(default)]
+    pub customer_groups: Vec<SeedCustomerGroup>,
+    #[serde(default)]
+    pub roles: Vec<SeedRole>,
+    #[serde(default)]
+    pub tax_rules: Vec<SeedTaxRule>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SeedLocation {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: String, // warehouse, store, transit, virtual
+    #[serde(default = "default_true")]
+    pub is_sellable: bool,
+    pub address_data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SeedCategory {
+    pub name: String,
+    pub slug: String,
+    pub parent_slug: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub sort_order: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SeedCustomerGroup {
+    pub name: String,
+    pub code: Option<String>,
+    pub description: Option<String>,
+    pub default_discount_percentage: Option<f64>,
+    pub tax_class: Option<String>,
+    pub min_order_amount: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SeedRole {
+    pub name: String,
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+/// Tax rate for a tax class, kept in the shop settings under "tax_rules"
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SeedTaxRule {
+    pub tax_class: String,
+    pub region: Option<String>, // State code; all regions when None
+    pub name: Option<String>,
+    pub rate: f64, // Percentage
+    #[serde(default)]
+    pub inclusive: bool,
+}
+
+impl SeedTaxRule {
+    pub fn key(&self) -> String {
+        match &self.region {
+            Some(region) => format!("{}/{}", self.tax_class, region),
+            None => self.tax_class.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModuleDiff {
+    pub code: String,
+    pub current: bool,
+    pub template: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SettingDiff {
+    pub key: String,
+    pub current: Option<serde_json::Value>,
+    pub template: serde_json::Value,
+}
+
+/// Seed records by kind, named by their matching key
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SeedRecordNames {
+    pub locations: Vec<String>,
+    pub categories: Vec<String>,
+    pub customer_groups: Vec<String>,
+    pub roles: Vec<String>,
+    pub tax_rules: Vec<String>,
+}
+
+/// What applying a template would change in a shop
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShopTemplateDiff {
+    pub template_code: String,
+    pub template_version: i64,
+    pub applied_version: Option<i64>, // Last version of this template applied to the shop
+    pub modules: Vec<ModuleDiff>,
+    pub recommended_disabled: Vec<String>,
+    pub settings: Vec<SettingDiff>,
+    pub missing: SeedRecordNames,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShopTemplateApplyResult {
+    pub application: ShopTemplateApplication,
+    pub modules_enabled: Vec<String>,
+    pub modules_disabled: Vec<String>,
+    pub settings_changed: Vec<String>,
+    pub created: SeedRecordNames,
+}
@@ -1,4 +1,6 @@
-use crate::features::shop_template::models::shop_template_model::ShopTemplate;
+use crate::features::shop_template::models::shop_template_model::{
+    ShopTemplate, ShopTemplateApplication, ShopTemplateSeed,
+};
 use sqlx::{Result, SqlitePool};
 
 pub struct ShopTemplatesRepository {
@@ -36,4 +38,124 @@ impl ShopTemplatesRepository {
             .fetch_all(&self.pool)
             .await
     }
+
+    pub async fn create(&self, template: &ShopTemplate) -> Result<ShopTemplate> {
+        sqlx::query_as::<_, ShopTemplate>(
+            r#"
+            INSERT INTO shop_templates (
+                id, code, name, description, category, icon, features_config,
+                default_settings, recommended_modules, metadata, _status, created_at, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING *
+            "#,
+        )
+        .bind(&template.id)
+        .bind(&template.code)
+        .bind(&template.name)
+        .bind(&template.description)
+        .bind(&template.category)
+        .bind(&template.icon)
+        .bind(&template.features_config)
+        .bind(&template.default_settings)
+        .bind(&template.recommended_modules)
+        .bind(&template.metadata)
+        .bind(&template.sync_status)
+        .bind(template.created_at)
+        .bind(template.updated_at)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn update(&self, template: &ShopTemplate) -> Result<ShopTemplate> {
+        sqlx::query_as::<_, ShopTemplate>(
+            r#"
+            UPDATE shop_templates SET
+                name = ?, description = ?, category = ?, icon = ?, features_config = ?,
+                default_settings = ?, recommended_modules = ?, metadata = ?,
+                _status = 'modified', updated_at = ?
+            WHERE id = ?
+            RETURNING *
+            "#,
+        )
+        .bind(&template.name)
+        .bind(&template.description)
+        .bind(&template.category)
+        .bind(&template.icon)
+        .bind(&template.features_config)
+        .bind(&template.default_settings)
+        .bind(&template.recommended_modules)
+        .bind(&template.metadata)
+        .bind(template.updated_at)
+        .bind(&template.id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn find_seed(&self, template_id: &str) -> Result<Option<ShopTemplateSeed>> {
+        sqlx::query_as::<_, ShopTemplateSeed>(
+            "SELECT * FROM shop_template_seeds WHERE template_id = ?",
+        )
+        .bind(template_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn upsert_seed(&self, seed: &ShopTemplateSeed) -> Result<ShopTemplateSeed> {
+        sqlx::query_as::<_, ShopTemplateSeed>(
+            r#"
+            INSERT INTO shop_template_seeds (
+                template_id, version, seed_data, source_shop_id, _status, created_at, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (template_id) DO UPDATE SET
+                version = excluded.version,
+                seed_data = excluded.seed_data,
+                source_shop_id = excluded.source_shop_id,
+                _status = 'modified',
+                updated_at = excluded.updated_at
+            RETURNING *
+            "#,
+        )
+        .bind(&seed.template_id)
+        .bind(seed.version)
+        .bind(&seed.seed_data)
+        .bind(&seed.source_shop_id)
+        .bind(&seed.sync_status)
+        .bind(seed.created_at)
+        .bind(seed.updated_at)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn create_application(
+        &self,
+        application: &ShopTemplateApplication,
+    ) -> Result<ShopTemplateApplication> {
+        sqlx::query_as::<_, ShopTemplateApplication>(
+            r#"
+            INSERT INTO shop_template_applications (
+                id, shop_id, template_id, template_version, mode, applied_by, summary, created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING *
+            "#,
+        )
+        .bind(&application.id)
+        .bind(&application.shop_id)
+        .bind(&application.template_id)
+        .bind(application.template_version)
+        .bind(&application.mode)
+        .bind(&application.applied_by)
+        .bind(&application.summary)
+        .bind(application.created_at)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn list_applications(&self, shop_id: &str) -> Result<Vec<ShopTemplateApplication>> {
+        sqlx::query_as::<_, ShopTemplateApplication>(
+            "SELECT * FROM shop_template_applications WHERE shop_id = ? ORDER BY created_at DESC, rowid DESC",
+        )
+        .bind(shop_id)
+        .fetch_all(&self.pool)
+        .await
+    }
 }
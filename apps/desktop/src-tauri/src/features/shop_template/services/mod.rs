@@ -1 +1,2 @@
 pub mod shop_templates_service;
+pub mod shop_template_apply_service;
@@ -0,0 +1,802 @@
+//! Applies shop templates to existing shops and exports shops as templates
+//!
+//! A template carries a features_config, default settings, recommended
+//! modules and versioned seed data (locations, categories, customer groups,
+//! roles and tax rules). Applying it never deletes or overwrites shop
+//! records: seed records are only created when missing, so a template can
+//! be re-applied or upgraded to a newer version safely.
+
+use crate::db::RepositoryFactory;
+use crate::features::category::models::category_model::Category;
+use crate::features::category::repositories::shop_category_repository::ShopCategoryRepository;
+use crate::features::customer_group::models::customer_group_model::CustomerGroup;
+use crate::features::customer_group::repositories::shop_customer_group_repository::ShopCustomerGroupRepository;
+use crate::features::location::models::location_model::Location;
+use crate::features::location::repositories::shop_location_repository::ShopLocationRepository;
+use crate::features::module::dtos::module_dto::SetShopModulesDTO;
+use crate::features::module::services::modules_service::ModulesService;
+use crate::features::module::utils::module_checker;
+use crate::features::role::models::role_model::Role;
+use crate::features::role::repositories::roles_repository::RoleRepository;
+use crate::features::shop::models::shop_model::Shop;
+use crate::features::shop::repositories::shop_repository::ShopsRepository;
+use crate::features::shop_template::dtos::shop_template_dto::{
+    ApplyShopTemplateDTO, ExportShopTemplateDTO, ShopTemplateDto,
+};
+use crate::features::shop_template::models::shop_template_model::{
+    ModuleDiff, SeedCategory, SeedCustomerGroup, SeedLocation, SeedRecordNames, SeedTaxRule,
+    SettingDiff, ShopTemplate, ShopTemplateApplication, ShopTemplateApplyResult, ShopTemplateDiff,
+    ShopTemplateSeed, TemplateSeedData,
+};
+use crate::features::shop_template::repositories::shop_templates_repository::ShopTemplatesRepository;
+use chrono::Utc;
+use serde_json::{Map, Value};
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Settings key holding the shop's tax rules
+const TAX_RULES_KEY: &str = "tax_rules";
+
+fn parse_object(json: Option<&str>) -> Map<String, Value> {
+    json.and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default()
+}
+
+fn parse_codes(json: Option<&str>) -> Vec<String> {
+    json.and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default()
+}
+
+fn tax_rules_of(settings: &Map<String, Value>) -> Vec<SeedTaxRule> {
+    settings
+        .get(TAX_RULES_KEY)
+        .and_then(|rules| serde_json::from_value(rules.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Keys identifying the records a shop already has
+struct ShopRecords {
+    locations: HashSet<String>,
+    categories: HashMap<String, String>, // slug -> id
+    customer_groups: HashSet<String>,
+    roles: HashSet<String>,
+    tax_rules: HashSet<String>,
+}
+
+fn group_key(code: Option<&str>, name: &str) -> String {
+    code.unwrap_or(name).to_string()
+}
+
+pub struct ShopTemplateApplyService {
+    pool: SqlitePool,
+    repo_factory: Arc<RepositoryFactory>,
+    repo: ShopTemplatesRepository,
+    shops_repo: ShopsRepository,
+}
+
+impl ShopTemplateApplyService {
+    pub fn new(pool: SqlitePool, repo_factory: Arc<RepositoryFactory>) -> Self {
+        let repo = ShopTemplatesRepository::new(pool.clone());
+        let shops_repo = ShopsRepository::new(pool.clone());
+        Self {
+            pool,
+            repo_factory,
+            repo,
+            shops_repo,
+        }
+    }
+
+    async fn get_shop(&self, shop_id: &str) -> Result<Shop, String> {
+        self.shops_repo
+            .find_by_id(shop_id)
+            .await
+            .map_err(|e| format!("Failed to fetch shop: {}", e))?
+            .ok_or_else(|| format!("Shop not found: {}", shop_id))
+    }
+
+    /// A template with its seed version and data (version 1, no data when
+    /// the template has no seed row)
+    async fn load_template(
+        &self,
+        code: &str,
+    ) -> Result<(ShopTemplate, Option<ShopTemplateSeed>, TemplateSeedData), String> {
+        let template = self
+            .repo
+            .find_by_code(code)
+            .await
+            .map_err(|e| format!("Failed to fetch template: {}", e))?
+            .ok_or_else(|| format!("Template not found: {}", code))?;
+        let seed = self
+            .repo
+            .find_seed(&template.id)
+            .await
+            .map_err(|e| format!("Failed to fetch template seed data: {}", e))?;
+        let data = match &seed {
+            Some(seed) => serde_json::from_str(&seed.seed_data)
+                .map_err(|e| format!("Invalid seed data for template {}: {}", code, e))?,
+            None => TemplateSeedData::default(),
+        };
+        Ok((template, seed, data))
+    }
+
+    async fn shop_records(&self, shop: &Shop) -> Result<ShopRecords, String> {
+        let pool = self
+            .repo_factory
+            .shop_pool(&shop.id)
+            .await
+            .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+        let locations = ShopLocationRepository::new(pool.clone(), shop.id.clone())
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list locations: {}", e))?;
+        let categories = ShopCategoryRepository::new(pool.clone(), shop.id.clone())
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list categories: {}", e))?;
+        let groups = ShopCustomerGroupRepository::new(pool, shop.id.clone())
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list customer groups: {}", e))?;
+        let roles = RoleRepository::new(self.pool.clone())
+            .list_all()
+            .await
+            .map_err(|e| format!("Failed to list roles: {}", e))?;
+        let settings = parse_object(shop.settings.as_deref());
+
+        Ok(ShopRecords {
+            locations: locations.into_iter().map(|l| l.name).collect(),
+            categories: categories.into_iter().map(|c| (c.slug, c.id)).collect(),
+            customer_groups: groups
+                .iter()
+                .map(|g| group_key(g.code.as_deref(), &g.name))
+                .collect(),
+            roles: roles.into_iter().map(|r| r.name).collect(),
+            tax_rules: tax_rules_of(&settings).iter().map(|r| r.key()).collect(),
+        })
+    }
+
+    fn missing_records(data: &TemplateSeedData, records: &ShopRecords) -> SeedRecordNames {
+        SeedRecordNames {
+            locations: data
+                .locations
+                .iter()
+                .filter(|l| !records.locations.contains(&l.name))
+                .map(|l| l.name.clone())
+                .collect(),
+            categories: data
+                .categories
+                .iter()
+                .filter(|c| !records.categories.contains_key(&c.slug))
+                .map(|c| c.slug.clone())
+                .collect(),
+            customer_groups: data
+                .customer_groups
+                .iter()
+                .map(|g| group_key(g.code.as_deref(), &g.name))
+                .filter(|key| !records.customer_groups.contains(key))
+                .collect(),
+            roles: data
+                .roles
+                .iter()
+                .filter(|r| !records.roles.contains(&r.name))
+                .map(|r| r.name.clone())
+                .collect(),
+            tax_rules: data
+                .tax_rules
+                .iter()
+                .map(|r| r.key())
+                .filter(|key| !records.tax_rules.contains(key))
+                .collect(),
+        }
+    }
+
+    /// Preview what applying a template would change in a shop
+    pub async fn diff_template(
+        &self,
+        shop_id: &str,
+        template_code: &str,
+    ) -> Result<ShopTemplateDiff, String> {
+        let shop = self.get_shop(shop_id).await?;
+        let (template, seed, data) = self.load_template(template_code).await?;
+        let features_config = shop.features_config.as_deref();
+
+        let mut modules: Vec<ModuleDiff> = parse_object(Some(&template.features_config))
+            .into_iter()
+            .filter_map(|(code, value)| {
+                let wanted = value.as_bool()?;
+                let current = module_checker::is_module_enabled_or_core(features_config, &code);
+                (current != wanted).then_some(ModuleDiff {
+                    code,
+                    current,
+                    template: wanted,
+                })
+            })
+            .collect();
+        modules.sort_by(|a, b| a.code.cmp(&b.code));
+
+        let recommended_disabled = parse_codes(template.recommended_modules.as_deref())
+            .into_iter()
+            .filter(|code| !module_checker::is_module_enabled_or_core(features_config, code))
+            .collect();
+
+        let current_settings = parse_object(shop.settings.as_deref());
+        let mut settings: Vec<SettingDiff> = parse_object(template.default_settings.as_deref())
+            .into_iter()
+            .filter(|(key, value)| current_settings.get(key) != Some(value))
+            .map(|(key, value)| SettingDiff {
+                current: current_settings.get(&key).cloned(),
+                key,
+                template: value,
+            })
+            .collect();
+        settings.sort_by(|a, b| a.key.cmp(&b.key));
+
+        let records = self.shop_records(&shop).await?;
+        let applied_version = self
+            .repo
+            .list_applications(shop_id)
+            .await
+            .map_err(|e| format!("Failed to list template applications: {}", e))?
+            .into_iter()
+            .filter(|a| a.template_id == template.id)
+            .map(|a| a.template_version)
+            .max();
+
+        Ok(ShopTemplateDiff {
+            template_code: template.code,
+            template_version: seed.map(|s| s.version).unwrap_or(1),
+            applied_version,
+            modules,
+            recommended_disabled,
+            settings,
+            missing: Self::missing_records(&data, &records),
+        })
+    }
+
+    /// Apply a template to an existing shop
+    pub async fn apply_template(
+        &self,
+        payload: ApplyShopTemplateDTO,
+    ) -> Result<ShopTemplateApplyResult, String> {
+        self.apply(payload, "apply").await
+    }
+
+    /// Re-apply the last template of a shop once a newer version exists.
+    /// Modules are only switched on and settings only added, so changes
+    /// made in the shop since are kept.
+    pub async fn upgrade_template(
+        &self,
+        shop_id: &str,
+        applied_by: Option<String>,
+    ) -> Result<ShopTemplateApplyResult, String> {
+        let last = self
+            .repo
+            .list_applications(shop_id)
+            .await
+            .map_err(|e| format!("Failed to list template applications: {}", e))?
+            .into_iter()
+            .next()
+            .ok_or_else(|| "No template has been applied to this shop".to_string())?;
+        let template = self
+            .repo
+            .find_by_id(&last.template_id)
+            .await
+            .map_err(|e| format!("Failed to fetch template: {}", e))?
+            .ok_or_else(|| format!("Template not found: {}", last.template_id))?;
+        let version = self
+            .repo
+            .find_seed(&template.id)
+            .await
+            .map_err(|e| format!("Failed to fetch template seed data: {}", e))?
+            .map(|s| s.version)
+            .unwrap_or(1);
+        if version <= last.template_version {
+            return Err(format!(
+                "Shop is already on version {} of template {}",
+                last.template_version, template.code
+            ));
+        }
+
+        let payload = ApplyShopTemplateDTO {
+            shop_id: shop_id.to_string(),
+            template_code: template.code,
+            apply_modules: true,
+            enable_recommended: false,
+            overwrite_settings: false,
+            seed_data: true,
+            applied_by,
+        };
+        self.apply(payload, "upgrade").await
+    }
+
+    /// Seed a shop just created from a template. Its features_config and
+    /// settings were already copied from the template.
+    pub async fn seed_new_shop(
+        &self,
+        shop_id: &str,
+        template_code: &str,
+    ) -> Result<ShopTemplateApplyResult, String> {
+        let payload = ApplyShopTemplateDTO {
+            shop_id: shop_id.to_string(),
+            template_code: template_code.to_string(),
+            apply_modules: false,
+            enable_recommended: false,
+            overwrite_settings: false,
+            seed_data: true,
+            applied_by: None,
+        };
+        self.apply(payload, "create").await
+    }
+
+    async fn apply(
+        &self,
+        payload: ApplyShopTemplateDTO,
+        mode: &str,
+    ) -> Result<ShopTemplateApplyResult, String> {
+        let (template, seed, data) = self.load_template(&payload.template_code).await?;
+        self.get_shop(&payload.shop_id).await?;
+
+        // 1. Modules, validated and recorded by the module service
+        let mut modules_enabled = Vec::new();
+        let mut modules_disabled = Vec::new();
+        if payload.apply_modules || payload.enable_recommended {
+            let modules_service = ModulesService::new(self.pool.clone());
+            let known: HashSet<String> = modules_service
+                .list_modules()
+                .await?
+                .into_iter()
+                .map(|m| m.code)
+                .collect();
+
+            let mut wanted: HashMap<String, bool> = HashMap::new();
+            if payload.apply_modules {
+                for (code, value) in parse_object(Some(&template.features_config)) {
+                    match value.as_bool() {
+                        // Upgrades never switch modules off
+                        Some(false) if mode == "upgrade" => {}
+                        Some(enabled) => {
+                            wanted.insert(code, enabled);
+                        }
+                        None => {}
+                    }
+                }
+            }
+            if payload.enable_recommended {
+                for code in parse_codes(template.recommended_modules.as_deref()) {
+                    wanted.insert(code, true);
+                }
+            }
+            wanted.retain(|code, _| known.contains(code));
+
+            if !wanted.is_empty() {
+                let result = modules_service
+                    .set_shop_modules(SetShopModulesDTO {
+                        shop_id: payload.shop_id.clone(),
+                        modules: wanted,
+                        changed_by: payload.applied_by.clone(),
+                        reason: Some(format!("Template {}", template.code)),
+                    })
+                    .await?;
+                modules_enabled = result.enabled;
+                modules_disabled = result.disabled;
+            }
+        }
+
+        // 2. Settings and tax rules (re-read: the module change touched the shop)
+        let shop = self.get_shop(&payload.shop_id).await?;
+        let records = self.shop_records(&shop).await?;
+        let missing = if payload.seed_data {
+            Self::missing_records(&data, &records)
+        } else {
+            SeedRecordNames::default()
+        };
+
+        let mut settings = parse_object(shop.settings.as_deref());
+        let mut settings_changed = Vec::new();
+        if mode != "create" {
+            for (key, value) in parse_object(template.default_settings.as_deref()) {
+                let present = settings.contains_key(&key);
+                if (!present || payload.overwrite_settings) && settings.get(&key) != Some(&value) {
+                    settings.insert(key.clone(), value);
+                    settings_changed.push(key);
+                }
+            }
+        }
+        if !missing.tax_rules.is_empty() {
+            let mut tax_rules = tax_rules_of(&settings);
+            tax_rules.extend(
+                data.tax_rules
+                    .iter()
+                    .filter(|r| missing.tax_rules.contains(&r.key()))
+                    .cloned(),
+            );
+            settings.insert(
+                TAX_RULES_KEY.to_string(),
+                serde_json::to_value(tax_rules)
+                    .map_err(|e| format!("Failed to serialize tax rules: {}", e))?,
+            );
+            settings_changed.push(TAX_RULES_KEY.to_string());
+        }
+        settings_changed.sort();
+        settings_changed.dedup();
+        if !settings_changed.is_empty() {
+            self.shops_repo
+                .update_settings(&shop.id, &Value::Object(settings).to_string())
+                .await
+                .map_err(|e| format!("Failed to update shop settings: {}", e))?;
+        }
+
+        // 3. Seed records
+        if payload.seed_data {
+            self.create_seed_records(&shop.id, &data, &missing, records.categories)
+                .await?;
+        }
+
+        let mut application = ShopTemplateApplication {
+            id: Uuid::new_v4().to_string(),
+            shop_id: shop.id,
+            template_id: template.id,
+            template_version: seed.map(|s| s.version).unwrap_or(1),
+            mode: mode.to_string(),
+            applied_by: payload.applied_by,
+            summary: None,
+            created_at: Some(Utc::now()),
+        };
+        application.summary = Some(
+            serde_json::json!({
+                "modules_enabled": modules_enabled,
+                "modules_disabled": modules_disabled,
+                "settings_changed": settings_changed,
+                "created": missing,
+            })
+            .to_string(),
+        );
+        let application = self
+            .repo
+            .create_application(&application)
+            .await
+            .map_err(|e| format!("Failed to record template application: {}", e))?;
+
+        Ok(ShopTemplateApplyResult {
+            application,
+            modules_enabled,
+            modules_disabled,
+            settings_changed,
+            created: missing,
+        })
+    }
+
+    async fn create_seed_records(
+        &self,
+        shop_id: &str,
+        data: &TemplateSeedData,
+        missing: &SeedRecordNames,
+        mut category_ids: HashMap<String, String>,
+    ) -> Result<(), String> {
+        let pool = self
+            .repo_factory
+            .shop_pool(shop_id)
+            .await
+            .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+        let now = Utc::now();
+
+        let locations_repo = ShopLocationRepository::new(pool.clone(), shop_id.to_string());
+        for seed in data
+            .locations
+            .iter()
+            .filter(|l| missing.locations.contains(&l.name))
+        {
+            let location = Location {
+                id: Uuid::new_v4().to_string(),
+                shop_id: shop_id.to_string(),
+                name: seed.name.clone(),
+                type_: seed.type_.clone(),
+                is_sellable: seed.is_sellable,
+                address_data: seed.address_data.as_ref().map(|a| a.to_string()),
+                sync_status: Some("created".to_string()),
+                created_at: Some(now),
+                updated_at: Some(now),
+            };
+            locations_repo
+                .create(&location)
+                .await
+                .map_err(|e| format!("Failed to create location {}: {}", seed.name, e))?;
+        }
+
+        let categories_repo = ShopCategoryRepository::new(pool.clone(), shop_id.to_string());
+        for seed in data
+            .categories
+            .iter()
+            .filter(|c| missing.categories.contains(&c.slug))
+        {
+            let parent_id = match &seed.parent_slug {
+                Some(parent_slug) => {
+                    Some(category_ids.get(parent_slug).cloned().ok_or_else(|| {
+                        format!("Parent category {} of {} not found", parent_slug, seed.slug)
+                    })?)
+                }
+                None => None,
+            };
+            let category = Category {
+                id: Uuid::new_v4().to_string(),
+                shop_id: shop_id.to_string(),
+                parent_id,
+                name: seed.name.clone(),
+                slug: seed.slug.clone(),
+                description: seed.description.clone(),
+                image_url: None,
+                banner_url: None,
+                r#type: Some("manual".to_string()),
+                rules: Some("[]".to_string()),
+                is_visible: true,
+                sort_order: seed.sort_order,
+                seo_title: None,
+                seo_description: None,
+                template_suffix: None,
+                metadata: Some("{}".to_string()),
+                sync_status: Some("created".to_string()),
+                created_at: now,
+                updated_at: now,
+            };
+            let created = categories_repo
+                .create(&category)
+                .await
+                .map_err(|e| format!("Failed to create category {}: {}", seed.slug, e))?;
+            category_ids.insert(created.slug, created.id);
+        }
+
+        let groups_repo = ShopCustomerGroupRepository::new(pool, shop_id.to_string());
+        for seed in data.customer_groups.iter().filter(|g| {
+            missing
+                .customer_groups
+                .contains(&group_key(g.code.as_deref(), &g.name))
+        }) {
+            let group = CustomerGroup {
+                id: Uuid::new_v4().to_string(),
+                shop_id: shop_id.to_string(),
+                name: seed.name.clone(),
+                code: seed.code.clone(),
+                description: seed.description.clone(),
+                r#type: Some("manual".to_string()),
+                rules: Some("[]".to_string()),
+                default_discount_percentage: seed.default_discount_percentage,
+                price_list_id: None,
+                tax_class: seed.tax_class.clone(),
+                allowed_payment_methods: None,
+                min_order_amount: seed.min_order_amount,
+                metadata: Some("{}".to_string()),
+                sync_status: Some("created".to_string()),
+                created_at: Some(now),
+                updated_at: Some(now),
+            };
+            groups_repo
+                .create(&group)
+                .await
+                .map_err(|e| format!("Failed to create customer group {}: {}", seed.name, e))?;
+        }
+
+        // Roles live in the registry and are shared by every shop
+        let roles_repo = RoleRepository::new(self.pool.clone());
+        for seed in data
+            .roles
+            .iter()
+            .filter(|r| missing.roles.contains(&r.name))
+        {
+            let role = Role {
+                id: Uuid::new_v4().to_string(),
+                name: seed.name.clone(),
+                permissions: Some(Value::from(seed.permissions.clone()).to_string()),
+                status_internal: "created".to_string(),
+                created_at: now,
+                updated_at: now,
+            };
+            roles_repo
+                .create(role)
+                .await
+                .map_err(|e| format!("Failed to create role {}: {}", seed.name, e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Save a shop's modules, settings and reference data as a template.
+    /// Exporting again to a template exported from the same shop replaces
+    /// its content and bumps its version, so shops created from it can
+    /// upgrade.
+    pub async fn export_shop_as_template(
+        &self,
+        payload: ExportShopTemplateDTO,
+    ) -> Result<ShopTemplateDto, String> {
+        let shop = self.get_shop(&payload.shop_id).await?;
+        let existing = self
+            .repo
+            .find_by_code(&payload.code)
+            .await
+            .map_err(|e| format!("Failed to fetch template: {}", e))?;
+        let existing_seed = match &existing {
+            Some(template) => self
+                .repo
+                .find_seed(&template.id)
+                .await
+                .map_err(|e| format!("Failed to fetch template seed data: {}", e))?,
+            None => None,
+        };
+        if existing.is_some()
+            && existing_seed
+                .as_ref()
+                .and_then(|s| s.source_shop_id.as_deref())
+                != Some(&shop.id)
+        {
+            return Err(format!(
+                "Template code {} is already used by another template",
+                payload.code
+            ));
+        }
+
+        let features_config = shop
+            .features_config
+            .clone()
+            .unwrap_or_else(|| "{}".to_string());
+        let recommended: Vec<String> = ModulesService::new(self.pool.clone())
+            .get_shop_modules(&shop.id)
+            .await?
+            .into_iter()
+            .filter(|m| m.active && !m.is_core)
+            .map(|m| m.code)
+            .collect();
+        let mut settings = parse_object(shop.settings.as_deref());
+        let tax_rules = tax_rules_of(&settings);
+        settings.remove(TAX_RULES_KEY);
+
+        let seed_data = if payload.include_seed_data {
+            self.export_seed_data(&shop.id, tax_rules).await?
+        } else {
+            TemplateSeedData::default()
+        };
+
+        let now = Utc::now();
+        let template = ShopTemplate {
+            id: existing
+                .as_ref()
+                .map(|t| t.id.clone())
+                .unwrap_or_else(|| Uuid::new_v4().to_string()),
+            code: payload.code,
+            name: payload.name,
+            description: payload.description,
+            category: payload.category,
+            icon: payload.icon,
+            features_config,
+            default_settings: Some(Value::Object(settings).to_string()),
+            recommended_modules: Some(Value::from(recommended).to_string()),
+            metadata: Some(serde_json::json!({ "source_shop_id": shop.id }).to_string()),
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+        let template = match existing {
+            Some(_) => self.repo.update(&template).await,
+            None => self.repo.create(&template).await,
+        }
+        .map_err(|e| format!("Failed to save template: {}", e))?;
+
+        let seed = ShopTemplateSeed {
+            template_id: template.id.clone(),
+            version: existing_seed.map(|s| s.version + 1).unwrap_or(1),
+            seed_data: serde_json::to_string(&seed_data)
+                .map_err(|e| format!("Failed to serialize seed data: {}", e))?,
+            source_shop_id: Some(shop.id),
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+        self.repo
+            .upsert_seed(&seed)
+            .await
+            .map_err(|e| format!("Failed to save template seed data: {}", e))?;
+
+        Ok(ShopTemplateDto::from(template))
+    }
+
+    async fn export_seed_data(
+        &self,
+        shop_id: &str,
+        tax_rules: Vec<SeedTaxRule>,
+    ) -> Result<TemplateSeedData, String> {
+        let pool = self
+            .repo_factory
+            .shop_pool(shop_id)
+            .await
+            .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+
+        let locations = ShopLocationRepository::new(pool.clone(), shop_id.to_string())
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list locations: {}", e))?;
+        let categories = ShopCategoryRepository::new(pool.clone(), shop_id.to_string())
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list categories: {}", e))?;
+        let groups = ShopCustomerGroupRepository::new(pool, shop_id.to_string())
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list customer groups: {}", e))?;
+
+        // Parents must come before their children when seeding
+        let slugs: HashMap<&str, &str> = categories
+            .iter()
+            .map(|c| (c.id.as_str(), c.slug.as_str()))
+            .collect();
+        let mut ordered = Vec::new();
+        let mut placed: HashSet<&str> = HashSet::new();
+        while ordered.len() < categories.len() {
+            let before = ordered.len();
+            for category in &categories {
+                let parent_placed = category
+                    .parent_id
+                    .as_deref()
+                    .is_none_or(|parent| placed.contains(parent) || !slugs.contains_key(parent));
+                if !placed.contains(category.id.as_str()) && parent_placed {
+                    placed.insert(&category.id);
+                    ordered.push(SeedCategory {
+                        name: category.name.clone(),
+                        slug: category.slug.clone(),
+                        parent_slug: category
+                            .parent_id
+                            .as_deref()
+                            .and_then(|parent| slugs.get(parent))
+                            .map(|slug| slug.to_string()),
+                        description: category.description.clone(),
+                        sort_order: category.sort_order,
+                    });
+                }
+            }
+            if ordered.len() == before {
+                break; // Cycle in parent_id; leave the rest out
+            }
+        }
+
+        Ok(TemplateSeedData {
+            locations: locations
+                .into_iter()
+                .map(|l| SeedLocation {
+                    name: l.name,
+                    type_: l.type_,
+                    is_sellable: l.is_sellable,
+                    address_data: l.address_data.and_then(|a| serde_json::from_str(&a).ok()),
+                })
+                .collect(),
+            categories: ordered,
+            customer_groups: groups
+                .into_iter()
+                .map(|g| SeedCustomerGroup {
+                    name: g.name,
+                    code: g.code,
+                    description: g.description,
+                    default_discount_percentage: g.default_discount_percentage,
+                    tax_class: g.tax_class,
+                    min_order_amount: g.min_order_amount,
+                })
+                .collect(),
+            // Roles are shared by all shops, so they are not part of a shop export
+            roles: Vec::new(),
+            tax_rules,
+        })
+    }
+
+    pub async fn list_applications(
+        &self,
+        shop_id: &str,
+    ) -> Result<Vec<ShopTemplateApplication>, String> {
+        self.repo
+            .list_applications(shop_id)
+            .await
+            .map_err(|e| format!("Failed to list template applications: {}", e))
+    }
+
+    pub async fn get_template_seed(&self, code: &str) -> Result<TemplateSeedData, String> {
+        self.load_template(code).await.map(|(_, _, data)| data)
+    }
+}
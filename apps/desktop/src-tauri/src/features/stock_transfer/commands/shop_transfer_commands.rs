@@ -0,0 +1,89 @@
+use crate::db::RepositoryFactory;
+use crate::features::stock_transfer::dtos::shop_transfer_dto::CreateShopTransferDTO;
+use crate::features::stock_transfer::models::shop_transfer_model::{
+    ShopTransfer, ShopTransferDetail, ShopTransferRecoveryResult,
+};
+use crate::features::stock_transfer::services::shop_transfer_service::ShopTransferService;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn create_shop_transfer(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    payload: CreateShopTransferDTO,
+) -> Result<ShopTransferDetail, String> {
+    let service = ShopTransferService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.create_transfer(payload).await
+}
+
+#[tauri::command]
+pub async fn get_shop_transfer(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    id: String,
+) -> Result<Option<ShopTransferDetail>, String> {
+    let service = ShopTransferService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.get_transfer(&id).await
+}
+
+#[tauri::command]
+pub async fn list_shop_transfers(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    status: Option<String>,
+) -> Result<Vec<ShopTransfer>, String> {
+    let service = ShopTransferService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.list_transfers(&shop_id, status).await
+}
+
+#[tauri::command]
+pub async fn dispatch_shop_transfer(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    id: String,
+) -> Result<ShopTransferDetail, String> {
+    let service = ShopTransferService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.dispatch_transfer(&id).await
+}
+
+#[tauri::command]
+pub async fn receive_shop_transfer(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    id: String,
+) -> Result<ShopTransferDetail, String> {
+    let service = ShopTransferService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.receive_transfer(&id).await
+}
+
+#[tauri::command]
+pub async fn cancel_shop_transfer(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    id: String,
+) -> Result<ShopTransferDetail, String> {
+    let service = ShopTransferService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.cancel_transfer(&id).await
+}
+
+#[tauri::command]
+pub async fn resume_shop_transfer(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    id: String,
+) -> Result<ShopTransferDetail, String> {
+    let service = ShopTransferService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.resume_transfer(&id).await
+}
+
+#[tauri::command]
+pub async fn recover_shop_transfers(
+    pool: State<'_, SqlitePool>,
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+) -> Result<ShopTransferRecoveryResult, String> {
+    let service = ShopTransferService::new(pool.inner().clone(), repo_factory.inner().clone());
+    service.recover_pending_transfers().await
+}
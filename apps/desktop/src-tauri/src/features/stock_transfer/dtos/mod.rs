@@ -0,0 +1 @@
+pub mod shop_transfer_dto;
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// A product to transfer, identified by SKU or GTIN in the source shop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShopTransferItemInput {
+    pub sku: Option<String>,
+    pub gtin: Option<String>,
+    pub quantity: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateShopTransferDTO {
+    pub source_shop_id: String,
+    pub source_location_id: String,
+    pub transit_location_id: Option<String>, // Defaults to the source shop's transit location
+    pub dest_shop_id: String,
+    pub dest_location_id: String,
+    pub items: Vec<ShopTransferItemInput>,
+    pub notes: Option<String>,
+    pub created_by: Option<String>,
+}
@@ -0,0 +1,5 @@
+pub mod commands;
+pub mod dtos;
+pub mod models;
+pub mod repositories;
+pub mod services;
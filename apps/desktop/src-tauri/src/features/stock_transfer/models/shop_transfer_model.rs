@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Stock moved from one shop database to another, coordinated in the registry
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ShopTransfer {
+    pub id: String,
+    pub source_shop_id: String,
+    pub source_location_id: String,
+    pub transit_location_id: String,
+    pub dest_shop_id: String,
+    pub dest_location_id: String,
+    pub status: String, // draft, dispatching, in_transit, receiving, received, cancelling, cancelled
+    pub notes: Option<String>,
+    pub last_error: Option<String>,
+    pub created_by: Option<String>,
+    pub dispatched_at: Option<DateTime<Utc>>,
+    pub received_at: Option<DateTime<Utc>>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ShopTransferItem {
+    pub id: String,
+    pub transfer_id: String,
+    pub sku: String,
+    pub gtin: Option<String>,
+    pub product_name: String,
+    pub quantity: f64,
+    pub source_product_id: String,
+    pub dest_product_id: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShopTransferDetail {
+    #[serde(flatten)]
+    pub transfer: ShopTransfer,
+    pub items: Vec<ShopTransferItem>,
+}
+
+/// Marker of a transfer step applied to a shop's stock (shop database)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ShopTransferPhase {
+    pub transfer_id: String,
+    pub phase: String,        // dispatch, receive, settle, return
+    pub movement_ids: String, // JSON array
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShopTransferRecoveryFailure {
+    pub transfer_id: String,
+    pub error: String,
+}
+
+/// Outcome of resuming the transfers left half-way
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShopTransferRecoveryResult {
+    pub resumed: Vec<ShopTransferDetail>,
+    pub failed: Vec<ShopTransferRecoveryFailure>,
+}
@@ -0,0 +1,2 @@
+pub mod shop_transfer_phases_repository;
+pub mod shop_transfers_repository;
@@ -0,0 +1,55 @@
+//! Shop-scoped Transfer Phase Repository for Multi-Database Architecture
+
+use crate::features::stock_transfer::models::shop_transfer_model::ShopTransferPhase;
+use sqlx::{Result, Sqlite, SqlitePool, Transaction};
+use std::sync::Arc;
+
+pub struct ShopTransferPhasesRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopTransferPhasesRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find(&self, transfer_id: &str, phase: &str) -> Result<Option<ShopTransferPhase>> {
+        let sql = "SELECT * FROM shop_transfer_phases WHERE transfer_id = $1 AND phase = $2";
+        sqlx::query_as::<_, ShopTransferPhase>(sql)
+            .bind(transfer_id)
+            .bind(phase)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn find_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        transfer_id: &str,
+        phase: &str,
+    ) -> Result<Option<ShopTransferPhase>> {
+        let sql = "SELECT * FROM shop_transfer_phases WHERE transfer_id = $1 AND phase = $2";
+        sqlx::query_as::<_, ShopTransferPhase>(sql)
+            .bind(transfer_id)
+            .bind(phase)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    pub async fn create_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        phase: &ShopTransferPhase,
+    ) -> Result<ShopTransferPhase> {
+        let sql = r#"
+            INSERT INTO shop_transfer_phases (transfer_id, phase, movement_ids, created_at)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, ShopTransferPhase>(sql)
+            .bind(&phase.transfer_id)
+            .bind(&phase.phase)
+            .bind(&phase.movement_ids)
+            .bind(phase.created_at)
+            .fetch_one(&mut **tx)
+            .await
+    }
+}
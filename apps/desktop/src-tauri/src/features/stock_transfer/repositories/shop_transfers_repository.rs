@@ -0,0 +1,144 @@
+use crate::features::stock_transfer::models::shop_transfer_model::{
+    ShopTransfer, ShopTransferItem,
+};
+use sqlx::{Result, Sqlite, SqlitePool, Transaction};
+
+pub struct ShopTransfersRepository {
+    pool: SqlitePool,
+}
+
+impl ShopTransfersRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        transfer: &ShopTransfer,
+    ) -> Result<ShopTransfer> {
+        let sql = r#"
+            INSERT INTO shop_transfers (
+                id, source_shop_id, source_location_id, transit_location_id, dest_shop_id,
+                dest_location_id, status, notes, created_by, _status, created_at, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, ShopTransfer>(sql)
+            .bind(&transfer.id)
+            .bind(&transfer.source_shop_id)
+            .bind(&transfer.source_location_id)
+            .bind(&transfer.transit_location_id)
+            .bind(&transfer.dest_shop_id)
+            .bind(&transfer.dest_location_id)
+            .bind(&transfer.status)
+            .bind(&transfer.notes)
+            .bind(&transfer.created_by)
+            .bind(&transfer.sync_status)
+            .bind(transfer.created_at)
+            .bind(transfer.updated_at)
+            .fetch_one(&mut **tx)
+            .await
+    }
+
+    pub async fn create_item_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        item: &ShopTransferItem,
+    ) -> Result<ShopTransferItem> {
+        let sql = r#"
+            INSERT INTO shop_transfer_items (
+                id, transfer_id, sku, gtin, product_name, quantity,
+                source_product_id, dest_product_id, created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING *
+        "#;
+        sqlx::query_as::<_, ShopTransferItem>(sql)
+            .bind(&item.id)
+            .bind(&item.transfer_id)
+            .bind(&item.sku)
+            .bind(&item.gtin)
+            .bind(&item.product_name)
+            .bind(item.quantity)
+            .bind(&item.source_product_id)
+            .bind(&item.dest_product_id)
+            .bind(item.created_at)
+            .fetch_one(&mut **tx)
+            .await
+    }
+
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<ShopTransfer>> {
+        sqlx::query_as::<_, ShopTransfer>("SELECT * FROM shop_transfers WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn list_items(&self, transfer_id: &str) -> Result<Vec<ShopTransferItem>> {
+        let sql = "SELECT * FROM shop_transfer_items WHERE transfer_id = ? ORDER BY rowid ASC";
+        sqlx::query_as::<_, ShopTransferItem>(sql)
+            .bind(transfer_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// Transfers sent or received by a shop, newest first
+    pub async fn list_by_shop(
+        &self,
+        shop_id: &str,
+        status: Option<&str>,
+    ) -> Result<Vec<ShopTransfer>> {
+        let sql = r#"
+            SELECT * FROM shop_transfers
+            WHERE (source_shop_id = ?1 OR dest_shop_id = ?1)
+              AND (?2 IS NULL OR status = ?2)
+            ORDER BY created_at DESC, rowid DESC
+        "#;
+        sqlx::query_as::<_, ShopTransfer>(sql)
+            .bind(shop_id)
+            .bind(status)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// Transfers stopped in the middle of a step
+    pub async fn list_pending(&self) -> Result<Vec<ShopTransfer>> {
+        let sql = r#"
+            SELECT * FROM shop_transfers
+            WHERE status IN ('dispatching', 'receiving', 'cancelling')
+            ORDER BY updated_at ASC
+        "#;
+        sqlx::query_as::<_, ShopTransfer>(sql)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// Move a transfer from `from` to `to`, stamping the dispatch, receipt or
+    /// cancellation time. Returns false when it was no longer in `from`.
+    pub async fn transition(
+        &self,
+        id: &str,
+        from: &str,
+        to: &str,
+        last_error: Option<&str>,
+    ) -> Result<bool> {
+        let sql = r#"
+            UPDATE shop_transfers SET
+                status = ?3,
+                last_error = ?4,
+                dispatched_at = CASE WHEN ?3 = 'in_transit' AND dispatched_at IS NULL
+                    THEN datetime('now') ELSE dispatched_at END,
+                received_at = CASE WHEN ?3 = 'received' THEN datetime('now') ELSE received_at END,
+                cancelled_at = CASE WHEN ?3 = 'cancelled' THEN datetime('now') ELSE cancelled_at END,
+                _status = 'modified',
+                updated_at = datetime('now')
+            WHERE id = ?1 AND status = ?2
+        "#;
+        let result = sqlx::query(sql)
+            .bind(id)
+            .bind(from)
+            .bind(to)
+            .bind(last_error)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,752 @@
+//! Stock transfers between shops, whose stock lives in separate databases
+//!
+//! A transfer is coordinated from the registry and applied to the shop
+//! databases in steps:
+//! - dispatch: source location -> transit location (source shop)
+//! - receive: -> destination location (destination shop), then
+//!   settle: transit location -> (source shop)
+//! - return (cancel while in transit): transit -> source location
+//!
+//! Each step commits its stock movements together with a marker row in the
+//! shop database, so replaying a step never applies it twice. The registry
+//! status is moved to an intermediate state ('dispatching', 'receiving',
+//! 'cancelling') before a step runs; a transfer left in one of those states
+//! by a crash is resumed by `recover_pending_transfers` at startup.
+
+use crate::db::RepositoryFactory;
+use crate::features::barcode::repositories::shop_barcode_repository::ShopBarcodeRepository;
+use crate::features::barcode::services::gtin;
+use crate::features::inventory::models::inventory_level_model::InventoryLevel;
+use crate::features::inventory::repositories::inventory_movements_repository::InventoryMovementsRepository;
+use crate::features::inventory::repositories::shop_inventory_repository::ShopInventoryRepository;
+use crate::features::location::models::location_model::Location;
+use crate::features::location::repositories::shop_location_repository::ShopLocationRepository;
+use crate::features::product::models::product_model::{Product, NON_STOCK_TYPES};
+use crate::features::product::repositories::shop_product_repository::ShopProductRepository;
+use crate::features::shop::repositories::shop_repository::ShopsRepository;
+use crate::features::stock_transfer::dtos::shop_transfer_dto::CreateShopTransferDTO;
+use crate::features::stock_transfer::models::shop_transfer_model::{
+    ShopTransfer, ShopTransferDetail, ShopTransferItem, ShopTransferPhase,
+    ShopTransferRecoveryFailure, ShopTransferRecoveryResult,
+};
+use crate::features::stock_transfer::repositories::shop_transfer_phases_repository::ShopTransferPhasesRepository;
+use crate::features::stock_transfer::repositories::shop_transfers_repository::ShopTransfersRepository;
+use crate::features::transaction::models::transaction_model::InventoryMovement;
+use chrono::Utc;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const PHASE_DISPATCH: &str = "dispatch";
+const PHASE_RECEIVE: &str = "receive";
+const PHASE_SETTLE: &str = "settle";
+const PHASE_RETURN: &str = "return";
+
+/// Stock of one product moved by a step. A missing location is the other
+/// shop: nothing is taken out or put in on that side.
+struct StockLeg {
+    product_id: String,
+    sku: String,
+    from_location_id: Option<String>,
+    to_location_id: Option<String>,
+    quantity: f64,
+}
+
+/// Resume the transfers a crash left in the middle of a step
+pub async fn resume_pending_transfers(repo_factory: Arc<RepositoryFactory>) {
+    let service = ShopTransferService::new(repo_factory.registry_pool().clone(), repo_factory);
+    match service.recover_pending_transfers().await {
+        Ok(result) => {
+            for failure in result.failed {
+                eprintln!(
+                    "[shop_transfers] transfer {}: {}",
+                    failure.transfer_id, failure.error
+                );
+            }
+        }
+        Err(e) => eprintln!("[shop_transfers] {}", e),
+    }
+}
+
+pub struct ShopTransferService {
+    pool: SqlitePool,
+    repo_factory: Arc<RepositoryFactory>,
+    repo: ShopTransfersRepository,
+}
+
+impl ShopTransferService {
+    pub fn new(pool: SqlitePool, repo_factory: Arc<RepositoryFactory>) -> Self {
+        let repo = ShopTransfersRepository::new(pool.clone());
+        Self {
+            pool,
+            repo_factory,
+            repo,
+        }
+    }
+
+    async fn shop_pool(&self, shop_id: &str) -> Result<Arc<SqlitePool>, String> {
+        self.repo_factory
+            .shop_pool(shop_id)
+            .await
+            .map_err(|e| format!("Failed to get shop pool: {}", e))
+    }
+
+    /// Product by SKU, falling back to GTIN
+    async fn find_product(
+        pool: &Arc<SqlitePool>,
+        shop_id: &str,
+        sku: Option<&str>,
+        gtin_code: Option<&str>,
+    ) -> Result<Option<Product>, String> {
+        let barcodes = ShopBarcodeRepository::new(pool.clone());
+        let mut product_id = None;
+        if let Some(sku) = sku.filter(|s| !s.trim().is_empty()) {
+            product_id = barcodes
+                .find_product_id_by_sku(sku.trim())
+                .await
+                .map_err(|e| format!("Failed to look up SKU: {}", e))?;
+        }
+        if product_id.is_none() {
+            if let Some(code) = gtin_code.filter(|g| gtin::is_valid_gtin(g.trim())) {
+                product_id = barcodes
+                    .find_product_id_by_gtin(&gtin::gtin_variants(code.trim()))
+                    .await
+                    .map_err(|e| format!("Failed to look up GTIN: {}", e))?;
+            }
+        }
+
+        match product_id {
+            Some(id) => ShopProductRepository::new(pool.clone(), shop_id.to_string())
+                .get_by_id(&id)
+                .await
+                .map_err(|e| format!("Failed to fetch product: {}", e)),
+            None => Ok(None),
+        }
+    }
+
+    async fn check_location(
+        pool: &Arc<SqlitePool>,
+        shop_id: &str,
+        location_id: &str,
+    ) -> Result<Location, String> {
+        ShopLocationRepository::new(pool.clone(), shop_id.to_string())
+            .get_by_id(location_id)
+            .await
+            .map_err(|e| format!("Failed to fetch location: {}", e))?
+            .ok_or_else(|| format!("Location {} not found in shop {}", location_id, shop_id))
+    }
+
+    /// The given transit location, else the shop's first one, created when
+    /// the shop has none
+    async fn transit_location(
+        pool: &Arc<SqlitePool>,
+        shop_id: &str,
+        location_id: Option<&str>,
+    ) -> Result<Location, String> {
+        if let Some(location_id) = location_id {
+            let location = Self::check_location(pool, shop_id, location_id).await?;
+            if location.type_ != "transit" {
+                return Err(format!(
+                    "Location {} is not a transit location",
+                    location.name
+                ));
+            }
+            return Ok(location);
+        }
+
+        let locations_repo = ShopLocationRepository::new(pool.clone(), shop_id.to_string());
+        let existing = locations_repo
+            .list_by_type("transit")
+            .await
+            .map_err(|e| format!("Failed to list locations: {}", e))?;
+        if let Some(location) = existing.into_iter().next() {
+            return Ok(location);
+        }
+
+        let now = Utc::now();
+        let location = Location {
+            id: Uuid::new_v4().to_string(),
+            shop_id: shop_id.to_string(),
+            name: "In Transit".to_string(),
+            type_: "transit".to_string(),
+            is_sellable: false,
+            address_data: None,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+        locations_repo
+            .create(&location)
+            .await
+            .map_err(|e| format!("Failed to create transit location: {}", e))
+    }
+
+    pub async fn create_transfer(
+        &self,
+        payload: CreateShopTransferDTO,
+    ) -> Result<ShopTransferDetail, String> {
+        if payload.source_shop_id == payload.dest_shop_id {
+            return Err("Source and destination shop must differ".to_string());
+        }
+        if payload.items.is_empty() {
+            return Err("A transfer needs at least one item".to_string());
+        }
+
+        let shops_repo = ShopsRepository::new(self.pool.clone());
+        for shop_id in [&payload.source_shop_id, &payload.dest_shop_id] {
+            shops_repo
+                .find_by_id(shop_id)
+                .await
+                .map_err(|e| format!("Failed to fetch shop: {}", e))?
+                .filter(|shop| shop.sync_status != "deleted")
+                .ok_or_else(|| format!("Shop not found: {}", shop_id))?;
+        }
+
+        let source_pool = self
+            .repo_factory
+            .shop_module_pool(&payload.source_shop_id, "inventory")
+            .await
+            .map_err(|e| format!("Failed to get source shop pool: {}", e))?;
+        let dest_pool = self
+            .repo_factory
+            .shop_module_pool(&payload.dest_shop_id, "inventory")
+            .await
+            .map_err(|e| format!("Failed to get destination shop pool: {}", e))?;
+
+        Self::check_location(
+            &source_pool,
+            &payload.source_shop_id,
+            &payload.source_location_id,
+        )
+        .await?;
+        Self::check_location(&dest_pool, &payload.dest_shop_id, &payload.dest_location_id).await?;
+        let transit = Self::transit_location(
+            &source_pool,
+            &payload.source_shop_id,
+            payload.transit_location_id.as_deref(),
+        )
+        .await?;
+        if transit.id == payload.source_location_id {
+            return Err("Source location cannot be the transit location".to_string());
+        }
+
+        let now = Utc::now();
+        let transfer_id = Uuid::new_v4().to_string();
+        let mut items: Vec<ShopTransferItem> = Vec::new();
+        for input in &payload.items {
+            if !input.quantity.is_finite() || input.quantity <= 0.0 {
+                return Err("Item quantity must be greater than zero".to_string());
+            }
+            let reference = input
+                .sku
+                .clone()
+                .or_else(|| input.gtin.clone())
+                .ok_or_else(|| "Each item needs a SKU or a GTIN".to_string())?;
+
+            let source = Self::find_product(
+                &source_pool,
+                &payload.source_shop_id,
+                input.sku.as_deref(),
+                input.gtin.as_deref(),
+            )
+            .await?
+            .ok_or_else(|| format!("Product {} not found in source shop", reference))?;
+            if NON_STOCK_TYPES.contains(&source.r#type.as_str()) {
+                return Err(format!("Product {} does not hold stock", source.sku));
+            }
+
+            if let Some(item) = items.iter_mut().find(|i| i.source_product_id == source.id) {
+                item.quantity += input.quantity;
+                continue;
+            }
+
+            let gtin_code = input.gtin.clone().or_else(|| source.gtin_ean.clone());
+            let dest = Self::find_product(
+                &dest_pool,
+                &payload.dest_shop_id,
+                Some(&source.sku),
+                gtin_code.as_deref(),
+            )
+            .await?
+            .ok_or_else(|| format!("Product {} not found in destination shop", source.sku))?;
+            if NON_STOCK_TYPES.contains(&dest.r#type.as_str()) {
+                return Err(format!(
+                    "Product {} does not hold stock in destination shop",
+                    dest.sku
+                ));
+            }
+
+            items.push(ShopTransferItem {
+                id: Uuid::new_v4().to_string(),
+                transfer_id: transfer_id.clone(),
+                sku: source.sku,
+                gtin: gtin_code,
+                product_name: source.name,
+                quantity: input.quantity,
+                source_product_id: source.id,
+                dest_product_id: dest.id,
+                created_at: Some(now),
+            });
+        }
+
+        let transfer = ShopTransfer {
+            id: transfer_id,
+            source_shop_id: payload.source_shop_id,
+            source_location_id: payload.source_location_id,
+            transit_location_id: transit.id,
+            dest_shop_id: payload.dest_shop_id,
+            dest_location_id: payload.dest_location_id,
+            status: "draft".to_string(),
+            notes: payload.notes,
+            last_error: None,
+            created_by: payload.created_by,
+            dispatched_at: None,
+            received_at: None,
+            cancelled_at: None,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+        let transfer = ShopTransfersRepository::create_in_tx(&mut tx, &transfer)
+            .await
+            .map_err(|e| format!("Failed to create transfer: {}", e))?;
+        let mut created_items = Vec::with_capacity(items.len());
+        for item in &items {
+            created_items.push(
+                ShopTransfersRepository::create_item_in_tx(&mut tx, item)
+                    .await
+                    .map_err(|e| format!("Failed to create transfer item: {}", e))?,
+            );
+        }
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(ShopTransferDetail {
+            transfer,
+            items: created_items,
+        })
+    }
+
+    pub async fn get_transfer(&self, id: &str) -> Result<Option<ShopTransferDetail>, String> {
+        let transfer = match self
+            .repo
+            .find_by_id(id)
+            .await
+            .map_err(|e| format!("Failed to fetch transfer: {}", e))?
+        {
+            Some(transfer) => transfer,
+            None => return Ok(None),
+        };
+        let items = self
+            .repo
+            .list_items(id)
+            .await
+            .map_err(|e| format!("Failed to list transfer items: {}", e))?;
+        Ok(Some(ShopTransferDetail { transfer, items }))
+    }
+
+    async fn require_transfer(&self, id: &str) -> Result<ShopTransferDetail, String> {
+        self.get_transfer(id)
+            .await?
+            .ok_or_else(|| format!("Transfer not found: {}", id))
+    }
+
+    pub async fn list_transfers(
+        &self,
+        shop_id: &str,
+        status: Option<String>,
+    ) -> Result<Vec<ShopTransfer>, String> {
+        self.repo
+            .list_by_shop(shop_id, status.as_deref())
+            .await
+            .map_err(|e| format!("Failed to list transfers: {}", e))
+    }
+
+    async fn transition(&self, id: &str, from: &str, to: &str) -> Result<(), String> {
+        let moved = self
+            .repo
+            .transition(id, from, to, None)
+            .await
+            .map_err(|e| format!("Failed to update transfer status: {}", e))?;
+        if !moved {
+            return Err(format!("Transfer {} is no longer {}", id, from));
+        }
+        Ok(())
+    }
+
+    /// Keep the transfer in its current step, noting why it stopped
+    async fn record_error(&self, id: &str, status: &str, error: &str) {
+        if let Err(e) = self.repo.transition(id, status, status, Some(error)).await {
+            eprintln!(
+                "[shop_transfers] transfer {}: failed to record error: {}",
+                id, e
+            );
+        }
+    }
+
+    async fn get_or_create_level(
+        tx: &mut Transaction<'_, Sqlite>,
+        product_id: &str,
+        location_id: &str,
+    ) -> Result<InventoryLevel, String> {
+        let existing = ShopInventoryRepository::find_level_by_stock_status_in_tx(
+            tx,
+            product_id,
+            location_id,
+            "sellable",
+        )
+        .await
+        .map_err(|e| format!("Failed to fetch inventory level: {}", e))?;
+        if let Some(level) = existing {
+            return Ok(level);
+        }
+
+        let now = Utc::now();
+        let level = InventoryLevel {
+            id: Uuid::new_v4().to_string(),
+            product_id: product_id.to_string(),
+            location_id: location_id.to_string(),
+            batch_number: None,
+            serial_number: None,
+            expiry_date: None,
+            quantity_on_hand: 0.0,
+            quantity_reserved: 0.0,
+            stock_status: Some("sellable".to_string()),
+            aisle_bin_slot: None,
+            last_counted_at: None,
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+        ShopInventoryRepository::create_level_in_tx(tx, &level)
+            .await
+            .map_err(|e| format!("Failed to create inventory level: {}", e))
+    }
+
+    async fn create_movement(
+        tx: &mut Transaction<'_, Sqlite>,
+        level: &InventoryLevel,
+        movement_type: &str,
+        quantity: f64,
+    ) -> Result<String, String> {
+        let new_balance = match movement_type {
+            "out" => level.quantity_on_hand - quantity,
+            _ => level.quantity_on_hand + quantity,
+        };
+        let now = Utc::now();
+        let movement = InventoryMovement {
+            id: Uuid::new_v4().to_string(),
+            transaction_id: None,
+            inventory_level_id: Some(level.id.clone()),
+            movement_type: Some(movement_type.to_string()),
+            quantity,
+            previous_balance: Some(level.quantity_on_hand),
+            new_balance: Some(new_balance),
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+        };
+        // The movement trigger updates quantity_on_hand
+        InventoryMovementsRepository::create_with_tx(tx, movement)
+            .await
+            .map(|m| m.id)
+            .map_err(|e| format!("Failed to create inventory movement: {}", e))
+    }
+
+    /// Apply a step to a shop's stock, unless its marker shows it was
+    /// applied already
+    async fn apply_phase(
+        &self,
+        shop_id: &str,
+        transfer_id: &str,
+        phase: &str,
+        legs: &[StockLeg],
+    ) -> Result<(), String> {
+        let pool = self.shop_pool(shop_id).await?;
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let applied = ShopTransferPhasesRepository::find_in_tx(&mut tx, transfer_id, phase)
+            .await
+            .map_err(|e| format!("Failed to fetch transfer phase: {}", e))?;
+        if applied.is_some() {
+            return Ok(());
+        }
+
+        let mut movement_ids = Vec::new();
+        for leg in legs {
+            if let Some(location_id) = &leg.from_location_id {
+                let level = ShopInventoryRepository::find_level_by_stock_status_in_tx(
+                    &mut tx,
+                    &leg.product_id,
+                    location_id,
+                    "sellable",
+                )
+                .await
+                .map_err(|e| format!("Failed to fetch inventory level: {}", e))?
+                .ok_or_else(|| format!("No stock of {} at location {}", leg.sku, location_id))?;
+                let available = level.quantity_on_hand - level.quantity_reserved;
+                if available < leg.quantity {
+                    return Err(format!(
+                        "Insufficient stock of {}. Available: {}, requested: {}",
+                        leg.sku, available, leg.quantity
+                    ));
+                }
+                movement_ids
+                    .push(Self::create_movement(&mut tx, &level, "out", leg.quantity).await?);
+            }
+            if let Some(location_id) = &leg.to_location_id {
+                let level =
+                    Self::get_or_create_level(&mut tx, &leg.product_id, location_id).await?;
+                movement_ids
+                    .push(Self::create_movement(&mut tx, &level, "in", leg.quantity).await?);
+            }
+        }
+
+        let marker = ShopTransferPhase {
+            transfer_id: transfer_id.to_string(),
+            phase: phase.to_string(),
+            movement_ids: serde_json::to_string(&movement_ids)
+                .map_err(|e| format!("Failed to serialize movements: {}", e))?,
+            created_at: Some(Utc::now()),
+        };
+        ShopTransferPhasesRepository::create_in_tx(&mut tx, &marker)
+            .await
+            .map_err(|e| format!("Failed to record transfer phase: {}", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))
+    }
+
+    async fn phase_applied(
+        &self,
+        shop_id: &str,
+        transfer_id: &str,
+        phase: &str,
+    ) -> Result<bool, String> {
+        let pool = self.shop_pool(shop_id).await?;
+        ShopTransferPhasesRepository::new(pool)
+            .find(transfer_id, phase)
+            .await
+            .map(|marker| marker.is_some())
+            .map_err(|e| format!("Failed to fetch transfer phase: {}", e))
+    }
+
+    /// Apply a step; when it failed without touching the shop's stock, move
+    /// the transfer back to `rollback`. When that cannot be verified it stays
+    /// in `running` to be resumed.
+    async fn apply_or_rollback(
+        &self,
+        transfer: &ShopTransfer,
+        running: &str,
+        shop_id: &str,
+        phase: &str,
+        legs: &[StockLeg],
+        rollback: &str,
+    ) -> Result<(), String> {
+        let error = match self.apply_phase(shop_id, &transfer.id, phase, legs).await {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+
+        match self.phase_applied(shop_id, &transfer.id, phase).await {
+            // Failed after the commit went through
+            Ok(true) => Ok(()),
+            Ok(false) => {
+                if let Err(e) = self
+                    .repo
+                    .transition(&transfer.id, running, rollback, Some(&error))
+                    .await
+                {
+                    eprintln!("[shop_transfers] transfer {}: {}", transfer.id, e);
+                }
+                Err(error)
+            }
+            Err(_) => {
+                self.record_error(&transfer.id, running, &error).await;
+                Err(format!("{} (the transfer will be resumed)", error))
+            }
+        }
+    }
+
+    /// Take the stock out of the source location into transit
+    pub async fn dispatch_transfer(&self, id: &str) -> Result<ShopTransferDetail, String> {
+        let detail = self.require_transfer(id).await?;
+        match detail.transfer.status.as_str() {
+            "draft" => self.transition(id, "draft", "dispatching").await?,
+            "dispatching" => {}
+            other => return Err(format!("Transfer is {} and cannot be dispatched", other)),
+        }
+
+        let transfer = &detail.transfer;
+        let legs: Vec<StockLeg> = detail
+            .items
+            .iter()
+            .map(|item| StockLeg {
+                product_id: item.source_product_id.clone(),
+                sku: item.sku.clone(),
+                from_location_id: Some(transfer.source_location_id.clone()),
+                to_location_id: Some(transfer.transit_location_id.clone()),
+                quantity: item.quantity,
+            })
+            .collect();
+        self.apply_or_rollback(
+            transfer,
+            "dispatching",
+            &transfer.source_shop_id,
+            PHASE_DISPATCH,
+            &legs,
+            "draft",
+        )
+        .await?;
+
+        self.transition(id, "dispatching", "in_transit").await?;
+        self.require_transfer(id).await
+    }
+
+    /// Put the stock into the destination location, then clear it from the
+    /// source shop's transit location
+    pub async fn receive_transfer(&self, id: &str) -> Result<ShopTransferDetail, String> {
+        let detail = self.require_transfer(id).await?;
+        match detail.transfer.status.as_str() {
+            "in_transit" => self.transition(id, "in_transit", "receiving").await?,
+            "receiving" => {}
+            other => return Err(format!("Transfer is {} and cannot be received", other)),
+        }
+
+        let transfer = &detail.transfer;
+        let receive_legs: Vec<StockLeg> = detail
+            .items
+            .iter()
+            .map(|item| StockLeg {
+                product_id: item.dest_product_id.clone(),
+                sku: item.sku.clone(),
+                from_location_id: None,
+                to_location_id: Some(transfer.dest_location_id.clone()),
+                quantity: item.quantity,
+            })
+            .collect();
+        self.apply_or_rollback(
+            transfer,
+            "receiving",
+            &transfer.dest_shop_id,
+            PHASE_RECEIVE,
+            &receive_legs,
+            "in_transit",
+        )
+        .await?;
+
+        // The destination holds the stock now: from here on the transfer
+        // can only go forward
+        let settle_legs: Vec<StockLeg> = detail
+            .items
+            .iter()
+            .map(|item| StockLeg {
+                product_id: item.source_product_id.clone(),
+                sku: item.sku.clone(),
+                from_location_id: Some(transfer.transit_location_id.clone()),
+                to_location_id: None,
+                quantity: item.quantity,
+            })
+            .collect();
+        if let Err(e) = self
+            .apply_phase(&transfer.source_shop_id, id, PHASE_SETTLE, &settle_legs)
+            .await
+        {
+            if !matches!(
+                self.phase_applied(&transfer.source_shop_id, id, PHASE_SETTLE)
+                    .await,
+                Ok(true)
+            ) {
+                self.record_error(id, "receiving", &e).await;
+                return Err(format!(
+                    "Stock was received but could not be cleared from transit, retry later: {}",
+                    e
+                ));
+            }
+        }
+
+        self.transition(id, "receiving", "received").await?;
+        self.require_transfer(id).await
+    }
+
+    /// Cancel a draft, or return the stock in transit to the source location
+    pub async fn cancel_transfer(&self, id: &str) -> Result<ShopTransferDetail, String> {
+        let detail = self.require_transfer(id).await?;
+        match detail.transfer.status.as_str() {
+            "draft" => {
+                self.transition(id, "draft", "cancelled").await?;
+                return self.require_transfer(id).await;
+            }
+            "in_transit" => self.transition(id, "in_transit", "cancelling").await?,
+            "cancelling" => {}
+            other => return Err(format!("Transfer is {} and cannot be cancelled", other)),
+        }
+
+        let transfer = &detail.transfer;
+        let legs: Vec<StockLeg> = detail
+            .items
+            .iter()
+            .map(|item| StockLeg {
+                product_id: item.source_product_id.clone(),
+                sku: item.sku.clone(),
+                from_location_id: Some(transfer.transit_location_id.clone()),
+                to_location_id: Some(transfer.source_location_id.clone()),
+                quantity: item.quantity,
+            })
+            .collect();
+        self.apply_or_rollback(
+            transfer,
+            "cancelling",
+            &transfer.source_shop_id,
+            PHASE_RETURN,
+            &legs,
+            "in_transit",
+        )
+        .await?;
+
+        self.transition(id, "cancelling", "cancelled").await?;
+        self.require_transfer(id).await
+    }
+
+    /// Finish the step a transfer was stopped in, if any
+    pub async fn resume_transfer(&self, id: &str) -> Result<ShopTransferDetail, String> {
+        let detail = self.require_transfer(id).await?;
+        match detail.transfer.status.as_str() {
+            "dispatching" => self.dispatch_transfer(id).await,
+            "receiving" => self.receive_transfer(id).await,
+            "cancelling" => self.cancel_transfer(id).await,
+            _ => Ok(detail),
+        }
+    }
+
+    pub async fn recover_pending_transfers(&self) -> Result<ShopTransferRecoveryResult, String> {
+        let pending = self
+            .repo
+            .list_pending()
+            .await
+            .map_err(|e| format!("Failed to list pending transfers: {}", e))?;
+
+        let mut result = ShopTransferRecoveryResult::default();
+        for transfer in pending {
+            match self.resume_transfer(&transfer.id).await {
+                Ok(detail) => result.resumed.push(detail),
+                Err(error) => result.failed.push(ShopTransferRecoveryFailure {
+                    transfer_id: transfer.id,
+                    error,
+                }),
+            }
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,78 @@
+use crate::db::RepositoryFactory;
+use crate::features::store_credit::dtos::store_credit_dto::AdjustStoreCreditDTO;
+use crate::features::store_credit::models::store_credit_model::{
+    StoreCreditAccount, StoreCreditLedgerEntry,
+};
+use crate::features::store_credit::services::shop_store_credit_service::ShopStoreCreditService;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_store_credit_account(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    customer_id: String,
+) -> Result<Option<StoreCreditAccount>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopStoreCreditService::new(pool);
+    service.get_account(&customer_id).await
+}
+
+#[tauri::command]
+pub async fn list_store_credit_accounts(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+) -> Result<Vec<StoreCreditAccount>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopStoreCreditService::new(pool);
+    service.list_accounts().await
+}
+
+#[tauri::command]
+pub async fn list_store_credit_ledger(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    customer_id: String,
+) -> Result<Vec<StoreCreditLedgerEntry>, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopStoreCreditService::new(pool);
+    service.list_ledger(&customer_id).await
+}
+
+#[tauri::command]
+pub async fn adjust_store_credit(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    payload: AdjustStoreCreditDTO,
+) -> Result<StoreCreditAccount, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopStoreCreditService::new(pool);
+    service.adjust_balance(payload).await
+}
+
+#[tauri::command]
+pub async fn update_store_credit_status(
+    repo_factory: State<'_, Arc<RepositoryFactory>>,
+    shop_id: String,
+    customer_id: String,
+    status: String,
+) -> Result<StoreCreditAccount, String> {
+    let pool = repo_factory
+        .shop_pool(&shop_id)
+        .await
+        .map_err(|e| format!("Failed to get shop pool: {}", e))?;
+    let service = ShopStoreCreditService::new(pool);
+    service.set_status(&customer_id, &status).await
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdjustStoreCreditDTO {
+    pub customer_id: String,
+    pub r#type: String, // 'credit', 'debit'
+    pub amount: f64,
+    pub notes: Option<String>,
+    pub created_by: Option<String>,
+}
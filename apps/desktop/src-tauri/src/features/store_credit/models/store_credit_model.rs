@@ -0,0 +1,38 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct StoreCreditAccount {
+    pub id: String,
+    pub customer_id: String,
+    pub currency: Option<String>, // DEFAULT 'BRL'
+    pub status: String,           // 'active', 'frozen'
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    #[sqlx(default)]
+    pub balance: f64, // Sum of store_credit_ledger, not a column
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct StoreCreditLedgerEntry {
+    pub id: String,
+    pub account_id: String,
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub entry_type: String, // 'credit', 'debit'
+    pub amount: f64,
+    pub reason: String, // 'return', 'redeem', 'refund', 'adjustment'
+    pub payment_id: Option<String>,
+    pub transaction_id: Option<String>,
+    pub return_request_id: Option<String>,
+    pub notes: Option<String>,
+    pub created_by: Option<String>,
+    #[serde(rename = "_status")]
+    #[sqlx(rename = "_status")]
+    pub sync_status: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
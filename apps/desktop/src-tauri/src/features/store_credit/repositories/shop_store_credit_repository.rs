@@ -0,0 +1,172 @@
+//! Shop-scoped Store Credit Repository for Multi-Database Architecture
+//!
+//! The ledger is append-only: entries are inserted, never updated or deleted,
+//! and an account's balance is always computed from it.
+
+use crate::features::store_credit::models::store_credit_model::{
+    StoreCreditAccount, StoreCreditLedgerEntry,
+};
+use sqlx::{Result, Sqlite, SqlitePool, Transaction};
+use std::sync::Arc;
+
+const SELECT_WITH_BALANCE: &str = r#"
+    SELECT a.*,
+        (SELECT COALESCE(SUM(CASE WHEN l.type = 'credit' THEN l.amount ELSE -l.amount END), 0.0)
+         FROM store_credit_ledger l WHERE l.account_id = a.id) AS balance
+    FROM store_credit_accounts a
+"#;
+
+pub struct ShopStoreCreditRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl ShopStoreCreditRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_by_customer(&self, customer_id: &str) -> Result<Option<StoreCreditAccount>> {
+        let sql = format!(
+            "{} WHERE a.customer_id = $1 AND (a._status IS NULL OR a._status != 'deleted')",
+            SELECT_WITH_BALANCE
+        );
+        sqlx::query_as::<_, StoreCreditAccount>(&sql)
+            .bind(customer_id)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn list(&self) -> Result<Vec<StoreCreditAccount>> {
+        let sql = format!(
+            "{} WHERE a._status IS NULL OR a._status != 'deleted' ORDER BY a.created_at DESC",
+            SELECT_WITH_BALANCE
+        );
+        sqlx::query_as::<_, StoreCreditAccount>(&sql)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn update_status(&self, id: &str, status: &str) -> Result<()> {
+        let sql = r#"
+            UPDATE store_credit_accounts SET status = $2, _status = 'modified', updated_at = datetime('now')
+            WHERE id = $1
+        "#;
+        sqlx::query(sql)
+            .bind(id)
+            .bind(status)
+            .execute(&*self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn list_entries(&self, account_id: &str) -> Result<Vec<StoreCreditLedgerEntry>> {
+        let sql = "SELECT * FROM store_credit_ledger WHERE account_id = $1 ORDER BY created_at ASC";
+        sqlx::query_as::<_, StoreCreditLedgerEntry>(sql)
+            .bind(account_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    // ============================================================
+    // Transaction-aware methods for atomic operations
+    // ============================================================
+
+    pub async fn get_by_customer_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        customer_id: &str,
+    ) -> Result<Option<StoreCreditAccount>> {
+        let sql = format!(
+            "{} WHERE a.customer_id = $1 AND (a._status IS NULL OR a._status != 'deleted')",
+            SELECT_WITH_BALANCE
+        );
+        sqlx::query_as::<_, StoreCreditAccount>(&sql)
+            .bind(customer_id)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    pub async fn create_account_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        account: &StoreCreditAccount,
+    ) -> Result<()> {
+        let sql = r#"
+            INSERT INTO store_credit_accounts (
+                id, customer_id, currency, status, _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#;
+
+        sqlx::query(sql)
+            .bind(&account.id)
+            .bind(&account.customer_id)
+            .bind(&account.currency)
+            .bind(&account.status)
+            .bind(&account.sync_status)
+            .bind(account.created_at)
+            .bind(account.updated_at)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    /// Hand an account over to another customer, e.g. on a customer merge
+    pub async fn reassign_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: &str,
+        customer_id: &str,
+    ) -> Result<()> {
+        let sql = r#"
+            UPDATE store_credit_accounts SET customer_id = $2, _status = 'modified', updated_at = datetime('now')
+            WHERE id = $1
+        "#;
+        sqlx::query(sql)
+            .bind(id)
+            .bind(customer_id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_payment_debit_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        payment_id: &str,
+    ) -> Result<Option<StoreCreditLedgerEntry>> {
+        let sql = r#"
+            SELECT * FROM store_credit_ledger
+            WHERE payment_id = $1 AND type = 'debit' AND reason = 'redeem'
+            LIMIT 1
+        "#;
+        sqlx::query_as::<_, StoreCreditLedgerEntry>(sql)
+            .bind(payment_id)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    pub async fn append_entry_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        entry: &StoreCreditLedgerEntry,
+    ) -> Result<StoreCreditLedgerEntry> {
+        let sql = r#"
+            INSERT INTO store_credit_ledger (
+                id, account_id, type, amount, reason, payment_id, transaction_id,
+                return_request_id, notes, created_by, _status, created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING *
+        "#;
+
+        sqlx::query_as::<_, StoreCreditLedgerEntry>(sql)
+            .bind(&entry.id)
+            .bind(&entry.account_id)
+            .bind(&entry.entry_type)
+            .bind(entry.amount)
+            .bind(&entry.reason)
+            .bind(&entry.payment_id)
+            .bind(&entry.transaction_id)
+            .bind(&entry.return_request_id)
+            .bind(&entry.notes)
+            .bind(&entry.created_by)
+            .bind(&entry.sync_status)
+            .bind(entry.created_at)
+            .fetch_one(&mut **tx)
+            .await
+    }
+}
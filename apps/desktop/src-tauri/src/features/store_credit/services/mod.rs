@@ -0,0 +1 @@
+pub mod shop_store_credit_service;
@@ -0,0 +1,291 @@
+//! Shop-scoped Store Credit Service for Multi-Database Architecture
+//!
+//! Each customer has at most one store credit account, opened on first credit.
+
+use crate::features::store_credit::dtos::store_credit_dto::AdjustStoreCreditDTO;
+use crate::features::store_credit::models::store_credit_model::{
+    StoreCreditAccount, StoreCreditLedgerEntry,
+};
+use crate::features::store_credit::repositories::shop_store_credit_repository::ShopStoreCreditRepository;
+use chrono::Utc;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct ShopStoreCreditService {
+    pool: Arc<SqlitePool>,
+    repo: ShopStoreCreditRepository,
+}
+
+impl ShopStoreCreditService {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        let repo = ShopStoreCreditRepository::new(pool.clone());
+        Self { pool, repo }
+    }
+
+    pub fn pool(&self) -> Arc<SqlitePool> {
+        self.pool.clone()
+    }
+
+    pub async fn get_account(&self, customer_id: &str) -> Result<Option<StoreCreditAccount>, String> {
+        self.repo
+            .get_by_customer(customer_id)
+            .await
+            .map_err(|e| format!("Failed to fetch store credit account: {}", e))
+    }
+
+    pub async fn list_accounts(&self) -> Result<Vec<StoreCreditAccount>, String> {
+        self.repo
+            .list()
+            .await
+            .map_err(|e| format!("Failed to list store credit accounts: {}", e))
+    }
+
+    pub async fn list_ledger(&self, customer_id: &str) -> Result<Vec<StoreCreditLedgerEntry>, String> {
+        match self.get_account(customer_id).await? {
+            Some(account) => self
+                .repo
+                .list_entries(&account.id)
+                .await
+                .map_err(|e| format!("Failed to list store credit ledger: {}", e)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub async fn set_status(&self, customer_id: &str, status: &str) -> Result<StoreCreditAccount, String> {
+        if status != "active" && status != "frozen" {
+            return Err(format!("Invalid store credit account status: {}", status));
+        }
+        let account = self
+            .get_account(customer_id)
+            .await?
+            .ok_or_else(|| format!("Store credit account not found for customer {}", customer_id))?;
+
+        self.repo
+            .update_status(&account.id, status)
+            .await
+            .map_err(|e| format!("Failed to update store credit account: {}", e))?;
+
+        self.get_account(customer_id)
+            .await?
+            .ok_or_else(|| format!("Store credit account not found for customer {}", customer_id))
+    }
+
+    /// Manual credit or debit, e.g. for goodwill or corrections
+    pub async fn adjust_balance(&self, payload: AdjustStoreCreditDTO) -> Result<StoreCreditAccount, String> {
+        if payload.amount <= 0.0 {
+            return Err("Adjustment amount must be greater than zero".to_string());
+        }
+        if payload.r#type != "credit" && payload.r#type != "debit" {
+            return Err(format!("Invalid ledger entry type: {}", payload.r#type));
+        }
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let account = Self::get_or_create_account_in_tx(&mut tx, &payload.customer_id).await?;
+
+        if payload.r#type == "debit" && payload.amount > account.balance + 0.001 {
+            return Err(format!(
+                "Insufficient store credit. Available: {}, Requested: {}",
+                account.balance, payload.amount
+            ));
+        }
+
+        let mut entry = Self::new_entry(&account.id, &payload.r#type, payload.amount, "adjustment", payload.created_by);
+        entry.notes = payload.notes;
+        ShopStoreCreditRepository::append_entry_in_tx(&mut tx, &entry)
+            .await
+            .map_err(|e| format!("Failed to record store credit adjustment: {}", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        self.get_account(&payload.customer_id)
+            .await?
+            .ok_or_else(|| format!("Store credit account not found for customer {}", payload.customer_id))
+    }
+
+    // ============================================================
+    // Transaction-aware methods used by payments and returns
+    // ============================================================
+
+    /// Credit the value of a resolved return to the customer's account
+    pub async fn credit_return_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        customer_id: &str,
+        amount: f64,
+        return_request_id: &str,
+        created_by: Option<String>,
+    ) -> Result<StoreCreditLedgerEntry, String> {
+        let account = Self::get_or_create_account_in_tx(tx, customer_id).await?;
+
+        let mut entry = Self::new_entry(&account.id, "credit", amount, "return", created_by);
+        entry.return_request_id = Some(return_request_id.to_string());
+        ShopStoreCreditRepository::append_entry_in_tx(tx, &entry)
+            .await
+            .map_err(|e| format!("Failed to record store credit: {}", e))
+    }
+
+    /// Debit the customer's account to pay for a transaction
+    pub async fn redeem_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        customer_id: &str,
+        amount: f64,
+        payment_id: &str,
+        transaction_id: &str,
+        created_by: Option<String>,
+    ) -> Result<StoreCreditAccount, String> {
+        let account = ShopStoreCreditRepository::get_by_customer_in_tx(tx, customer_id)
+            .await
+            .map_err(|e| format!("Failed to fetch store credit account: {}", e))?
+            .ok_or_else(|| format!("Customer {} has no store credit", customer_id))?;
+
+        if account.status != "active" {
+            return Err("Store credit account is frozen".to_string());
+        }
+        if amount > account.balance + 0.001 {
+            return Err(format!(
+                "Insufficient store credit. Available: {}, Requested: {}",
+                account.balance, amount
+            ));
+        }
+
+        let mut entry = Self::new_entry(&account.id, "debit", amount, "redeem", created_by);
+        entry.payment_id = Some(payment_id.to_string());
+        entry.transaction_id = Some(transaction_id.to_string());
+        ShopStoreCreditRepository::append_entry_in_tx(tx, &entry)
+            .await
+            .map_err(|e| format!("Failed to record store credit redemption: {}", e))?;
+
+        Ok(account)
+    }
+
+    /// Credit back the account that paid for a refunded payment
+    pub async fn restore_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        payment_id: &str,
+        amount: f64,
+        created_by: Option<String>,
+    ) -> Result<StoreCreditLedgerEntry, String> {
+        let debit = ShopStoreCreditRepository::get_payment_debit_in_tx(tx, payment_id)
+            .await
+            .map_err(|e| format!("Failed to fetch store credit redemption: {}", e))?
+            .ok_or_else(|| format!("No store credit redemption found for payment {}", payment_id))?;
+
+        let mut entry = Self::new_entry(&debit.account_id, "credit", amount, "refund", created_by);
+        entry.payment_id = Some(payment_id.to_string());
+        entry.transaction_id = debit.transaction_id.clone();
+        ShopStoreCreditRepository::append_entry_in_tx(tx, &entry)
+            .await
+            .map_err(|e| format!("Failed to record store credit refund: {}", e))
+    }
+
+    /// Move a merged customer's store credit to the surviving customer: the
+    /// account itself when the survivor has none, otherwise its balance as a
+    /// pair of adjustments. Returns the balance moved.
+    pub async fn transfer_customer_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        from_customer_id: &str,
+        to_customer_id: &str,
+        created_by: Option<String>,
+    ) -> Result<f64, String> {
+        let Some(from) = ShopStoreCreditRepository::get_by_customer_in_tx(tx, from_customer_id)
+            .await
+            .map_err(|e| format!("Failed to fetch store credit account: {}", e))?
+        else {
+            return Ok(0.0);
+        };
+
+        let to = ShopStoreCreditRepository::get_by_customer_in_tx(tx, to_customer_id)
+            .await
+            .map_err(|e| format!("Failed to fetch store credit account: {}", e))?;
+        let Some(to) = to else {
+            ShopStoreCreditRepository::reassign_in_tx(tx, &from.id, to_customer_id)
+                .await
+                .map_err(|e| format!("Failed to move store credit account: {}", e))?;
+            return Ok(from.balance);
+        };
+
+        if from.balance > 0.001 {
+            let notes = Some(format!(
+                "Customer {} merged into {}",
+                from_customer_id, to_customer_id
+            ));
+            let mut debit = Self::new_entry(
+                &from.id,
+                "debit",
+                from.balance,
+                "adjustment",
+                created_by.clone(),
+            );
+            debit.notes = notes.clone();
+            let mut credit =
+                Self::new_entry(&to.id, "credit", from.balance, "adjustment", created_by);
+            credit.notes = notes;
+            for entry in [debit, credit] {
+                ShopStoreCreditRepository::append_entry_in_tx(tx, &entry)
+                    .await
+                    .map_err(|e| format!("Failed to move store credit: {}", e))?;
+            }
+        }
+        Ok(from.balance.max(0.0))
+    }
+
+    async fn get_or_create_account_in_tx(
+        tx: &mut Transaction<'_, Sqlite>,
+        customer_id: &str,
+    ) -> Result<StoreCreditAccount, String> {
+        if let Some(account) = ShopStoreCreditRepository::get_by_customer_in_tx(tx, customer_id)
+            .await
+            .map_err(|e| format!("Failed to fetch store credit account: {}", e))?
+        {
+            return Ok(account);
+        }
+
+        let now = Utc::now();
+        let account = StoreCreditAccount {
+            id: Uuid::new_v4().to_string(),
+            customer_id: customer_id.to_string(),
+            currency: Some("BRL".to_string()),
+            status: "active".to_string(),
+            sync_status: Some("created".to_string()),
+            created_at: Some(now),
+            updated_at: Some(now),
+            balance: 0.0,
+        };
+
+        ShopStoreCreditRepository::create_account_in_tx(tx, &account)
+            .await
+            .map_err(|e| format!("Failed to create store credit account: {}", e))?;
+
+        Ok(account)
+    }
+
+    fn new_entry(
+        account_id: &str,
+        entry_type: &str,
+        amount: f64,
+        reason: &str,
+        created_by: Option<String>,
+    ) -> StoreCreditLedgerEntry {
+        StoreCreditLedgerEntry {
+            id: Uuid::new_v4().to_string(),
+            account_id: account_id.to_string(),
+            entry_type: entry_type.to_string(),
+            amount,
+            reason: reason.to_string(),
+            payment_id: None,
+            transaction_id: None,
+            return_request_id: None,
+            notes: None,
+            created_by,
+            sync_status: Some("created".to_string()),
+            created_at: Some(Utc::now()),
+        }
+    }
+}
@@ -7,6 +7,7 @@ use uuid::Uuid;
 pub struct CreateTransactionItemDTO {
     pub product_id: String,
     pub quantity: f64,
+    pub unit_price: Option<f64>, // Overrides the resolved price on sales
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -65,7 +66,7 @@ impl CreateTransactionDTO {
                 sku_snapshot: None,
                 name_snapshot: None,
                 quantity: i.quantity,
-                unit_price: 0.0, // To be enriched by the service
+                unit_price: i.unit_price.unwrap_or(0.0), // Priced by the service if unset
                 unit_cost: None,
                 total_line: None,
                 attributes_snapshot: None,
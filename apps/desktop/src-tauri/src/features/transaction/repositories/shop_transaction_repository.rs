@@ -1,6 +1,6 @@
 //! Shop-scoped Transaction Repository for Multi-Database Architecture
 
-use crate::features::transaction::models::transaction_model::Transaction;
+use crate::features::transaction::models::transaction_model::{Transaction, TransactionItem};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, Result, Sqlite, SqlitePool, Transaction as SqlxTransaction};
@@ -197,4 +197,105 @@ impl ShopTransactionRepository {
 
         Ok(result.map(|t| t.into_transaction(shop_id)))
     }
+
+    pub async fn update_status_in_tx(
+        tx: &mut SqlxTransaction<'_, Sqlite>,
+        id: &str,
+        status: &str,
+        shop_id: String,
+    ) -> Result<Transaction> {
+        let sql = r#"
+            UPDATE transactions SET status = $2, _status = 'modified', updated_at = datetime('now')
+            WHERE id = $1
+            RETURNING *
+        "#;
+        let shop_tx = sqlx::query_as::<_, ShopTransaction>(sql)
+            .bind(id)
+            .bind(status)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(shop_tx.into_transaction(shop_id))
+    }
+
+    pub async fn create_in_tx(
+        tx: &mut SqlxTransaction<'_, Sqlite>,
+        transaction: &Transaction,
+    ) -> Result<()> {
+        let sql = r#"
+            INSERT INTO transactions (
+                id, type, status, channel, customer_id, supplier_id, staff_id,
+                currency, total_items, total_shipping, total_discount, total_net,
+                shipping_method, shipping_address, billing_address, _status,
+                created_at, updated_at
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18
+            )
+        "#;
+
+        sqlx::query(sql)
+            .bind(&transaction.id)
+            .bind(&transaction.r#type)
+            .bind(&transaction.status)
+            .bind(&transaction.channel)
+            .bind(&transaction.customer_id)
+            .bind(&transaction.supplier_id)
+            .bind(&transaction.staff_id)
+            .bind(&transaction.currency)
+            .bind(transaction.total_items)
+            .bind(transaction.total_shipping)
+            .bind(transaction.total_discount)
+            .bind(transaction.total_net)
+            .bind(&transaction.shipping_method)
+            .bind(&transaction.shipping_address)
+            .bind(&transaction.billing_address)
+            .bind(&transaction.sync_status)
+            .bind(transaction.created_at)
+            .bind(transaction.updated_at)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn create_item_in_tx(
+        tx: &mut SqlxTransaction<'_, Sqlite>,
+        item: &TransactionItem,
+    ) -> Result<()> {
+        let sql = r#"
+            INSERT INTO transaction_items (
+                id, transaction_id, product_id, sku_snapshot, name_snapshot,
+                quantity, unit_price, unit_cost, attributes_snapshot, tax_details,
+                _status, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        "#;
+
+        sqlx::query(sql)
+            .bind(&item.id)
+            .bind(&item.transaction_id)
+            .bind(&item.product_id)
+            .bind(&item.sku_snapshot)
+            .bind(&item.name_snapshot)
+            .bind(item.quantity)
+            .bind(item.unit_price)
+            .bind(item.unit_cost)
+            .bind(&item.attributes_snapshot)
+            .bind(&item.tax_details)
+            .bind(&item.sync_status)
+            .bind(item.created_at)
+            .bind(item.updated_at)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_item_by_id_in_tx(
+        tx: &mut SqlxTransaction<'_, Sqlite>,
+        id: &str,
+    ) -> Result<Option<TransactionItem>> {
+        let sql = "SELECT * FROM transaction_items WHERE id = $1 AND (_status IS NULL OR _status != 'deleted')";
+        sqlx::query_as::<_, TransactionItem>(sql)
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await
+    }
 }
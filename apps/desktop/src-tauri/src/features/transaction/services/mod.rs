@@ -1,2 +1 @@
 pub mod shop_transaction_service;
-pub mod transaction_service;
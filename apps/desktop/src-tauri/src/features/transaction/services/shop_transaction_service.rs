@@ -1,8 +1,19 @@
 //! Shop-scoped Transaction Service for Multi-Database Architecture
 
+use crate::features::bundle::services::shop_bundle_service::ShopBundleService;
+use crate::features::customer::repositories::shop_customer_repository::ShopCustomerRepository;
+use crate::features::customer_group::services::shop_customer_segment_service::ShopCustomerSegmentService;
+use crate::features::digital_delivery::services::shop_digital_delivery_service::ShopDigitalDeliveryService;
+use crate::features::payment::repositories::shop_payment_repository::ShopPaymentRepository;
+use crate::features::payment::services::shop_payment_service::ShopPaymentService;
+use crate::features::price_list::services::shop_pricing_service::ShopPricingService;
+use crate::features::product::repositories::shop_product_repository::ShopProductRepository;
+use crate::features::return_request::repositories::shop_return_request_repository::ShopReturnRequestRepository;
+use crate::features::service_booking::repositories::shop_service_appointment_repository::ShopServiceAppointmentRepository;
 use crate::features::transaction::dtos::transaction_dto::{CreateTransactionDTO, UpdateTransactionDTO};
 use crate::features::transaction::models::transaction_model::Transaction;
 use crate::features::transaction::repositories::shop_transaction_repository::ShopTransactionRepository;
+use crate::features::transaction::repositories::transaction_items_repository::TransactionItemsRepository;
 use sqlx::SqlitePool;
 use std::sync::Arc;
 
@@ -31,7 +42,51 @@ impl ShopTransactionService {
     }
 
     pub async fn create_transaction(&self, payload: CreateTransactionDTO) -> Result<Transaction, String> {
-        let (transaction, items) = payload.into_models();
+        let price_given: Vec<bool> = payload.items.iter().map(|i| i.unit_price.is_some()).collect();
+        let (mut transaction, mut items) = payload.into_models();
+
+        // Snapshot the products and, for sales, price the lines the caller left
+        // unpriced for the customer
+        let product_repo = ShopProductRepository::new(self.pool.clone(), self.shop_id.clone());
+        let pricing = ShopPricingService::new(self.pool.clone(), self.shop_id.clone());
+        for (item, price_given) in items.iter_mut().zip(price_given) {
+            let product_id = match &item.product_id {
+                Some(product_id) => product_id.clone(),
+                None => continue,
+            };
+            let product = product_repo
+                .get_by_id(&product_id)
+                .await
+                .map_err(|e| format!("Failed to fetch product: {}", e))?
+                .ok_or_else(|| format!("Product not found: {}", product_id))?;
+
+            item.sku_snapshot = Some(product.sku.clone());
+            item.name_snapshot = Some(product.name.clone());
+            item.unit_cost = product.cost_price;
+
+            if transaction.r#type == "sale" && !price_given {
+                let price = pricing
+                    .resolve_product(
+                        &product,
+                        transaction.customer_id.as_deref(),
+                        item.quantity,
+                        None,
+                    )
+                    .await?;
+                item.unit_price = price.unit_price;
+            }
+        }
+
+        if transaction.r#type == "sale" {
+            let subtotal: f64 = items
+                .iter()
+                .map(|i| (i.quantity * i.unit_price * 100.0).round() / 100.0)
+                .sum();
+            transaction.total_net = Some(
+                subtotal - transaction.total_discount.unwrap_or(0.0)
+                    + transaction.total_shipping.unwrap_or(0.0),
+            );
+        }
 
         let mut tx = self
             .pool
@@ -39,66 +94,12 @@ impl ShopTransactionService {
             .await
             .map_err(|e| format!("Failed to start transaction: {}", e))?;
 
-        // Create the transaction
-        let sql = r#"
-            INSERT INTO transactions (
-                id, type, status, channel, customer_id, supplier_id, staff_id,
-                currency, total_items, total_shipping, total_discount, total_net,
-                shipping_method, shipping_address, billing_address, _status,
-                created_at, updated_at
-            ) VALUES (
-                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18
-            )
-        "#;
-
-        sqlx::query(sql)
-            .bind(&transaction.id)
-            .bind(&transaction.r#type)
-            .bind(&transaction.status)
-            .bind(&transaction.channel)
-            .bind(&transaction.customer_id)
-            .bind(&transaction.supplier_id)
-            .bind(&transaction.staff_id)
-            .bind(&transaction.currency)
-            .bind(&transaction.total_items)
-            .bind(&transaction.total_shipping)
-            .bind(&transaction.total_discount)
-            .bind(&transaction.total_net)
-            .bind(&transaction.shipping_method)
-            .bind(&transaction.shipping_address)
-            .bind(&transaction.billing_address)
-            .bind(&transaction.sync_status)
-            .bind(&transaction.created_at)
-            .bind(&transaction.updated_at)
-            .execute(&mut *tx)
+        ShopTransactionRepository::create_in_tx(&mut tx, &transaction)
             .await
             .map_err(|e| format!("Failed to create transaction: {}", e))?;
 
-        // Create transaction items
-        for item in items {
-            let item_sql = r#"
-                INSERT INTO transaction_items (
-                    id, transaction_id, product_id, sku_snapshot, name_snapshot,
-                    quantity, unit_price, unit_cost, attributes_snapshot, tax_details,
-                    _status, created_at, updated_at
-                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
-            "#;
-
-            sqlx::query(item_sql)
-                .bind(&item.id)
-                .bind(&item.transaction_id)
-                .bind(&item.product_id)
-                .bind(&item.sku_snapshot)
-                .bind(&item.name_snapshot)
-                .bind(item.quantity)
-                .bind(item.unit_price)
-                .bind(&item.unit_cost)
-                .bind(&item.attributes_snapshot)
-                .bind(&item.tax_details)
-                .bind(&item.sync_status)
-                .bind(&item.created_at)
-                .bind(&item.updated_at)
-                .execute(&mut *tx)
+        for item in &items {
+            ShopTransactionRepository::create_item_in_tx(&mut tx, item)
                 .await
                 .map_err(|e| format!("Failed to create transaction item: {}", e))?;
         }
@@ -157,11 +158,159 @@ impl ShopTransactionService {
             .map_err(|e| format!("Failed to update transaction status: {}", e))
     }
 
+    /// Cancel a transaction and the service appointments booked for it.
+    /// Gift card and store credit payments are refunded onto their balances,
+    /// license keys and download links are revoked, and a completed sale puts
+    /// its stock back. Refused while the transaction has open or settled
+    /// returns, or other payments that have not been refunded yet.
     pub async fn cancel_transaction(&self, id: &str) -> Result<Transaction, String> {
-        self.update_status(id, "cancelled").await
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let transaction =
+            ShopTransactionRepository::get_by_id_in_tx(&mut tx, id, self.shop_id.clone())
+                .await
+                .map_err(|e| format!("Failed to fetch transaction: {}", e))?
+                .ok_or_else(|| format!("Transaction not found: {}", id))?;
+
+        // Returned goods are already back in stock and refunded
+        let returns = ShopReturnRequestRepository::list_by_transaction_in_tx(&mut tx, id)
+            .await
+            .map_err(|e| format!("Failed to fetch return requests: {}", e))?;
+        if returns.iter().any(|r| r.status != "cancelled") {
+            return Err("Transaction has return requests and cannot be cancelled".to_string());
+        }
+
+        // Only gift card and store credit payments can be refunded here
+        let unrefunded = ShopPaymentService::unrefunded_external_in_tx(&mut tx, id).await?;
+        if unrefunded >= 0.01 {
+            return Err(format!(
+                "Refund the transaction's other payments before cancelling it. Still to refund: {:.2}",
+                unrefunded
+            ));
+        }
+
+        if transaction.status == "completed" {
+            ShopBundleService::restock_transaction_in_tx(&mut tx, id).await?;
+        }
+
+        let updated =
+            ShopTransactionRepository::update_status_in_tx(&mut tx, id, "cancelled", self.shop_id.clone())
+                .await
+                .map_err(|e| format!("Failed to update transaction status: {}", e))?;
+        ShopServiceAppointmentRepository::cancel_by_transaction_in_tx(&mut tx, id)
+            .await
+            .map_err(|e| format!("Failed to cancel service appointments: {}", e))?;
+        ShopDigitalDeliveryService::revoke_transaction_in_tx(&mut tx, id).await?;
+        ShopPaymentService::reverse_stored_value_in_tx(
+            &mut tx,
+            id,
+            &format!("Transaction {} cancelled", id),
+            None,
+        )
+        .await?;
+
+        // A cancelled sale no longer counts towards the customer's spend
+        if transaction.status == "completed" {
+            if let Some(customer_id) = &transaction.customer_id {
+                ShopCustomerRepository::refresh_stats_in_tx(
+                    &mut tx,
+                    customer_id,
+                    self.shop_id.clone(),
+                )
+                .await
+                .map_err(|e| format!("Failed to update customer stats: {}", e))?;
+                ShopCustomerSegmentService::refresh_customer_in_tx(&mut tx, customer_id).await?;
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(updated)
     }
 
-    pub async fn complete_sale(&self, id: &str, _location_id: &str) -> Result<Transaction, String> {
-        self.update_status(id, "completed").await
+    /// Complete a sale once its payments cover the net total, taking its items
+    /// out of stock at `location_id`
+    pub async fn complete_sale(&self, id: &str, location_id: &str) -> Result<Transaction, String> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let transaction = ShopTransactionRepository::get_by_id_in_tx(&mut tx, id, self.shop_id.clone())
+            .await
+            .map_err(|e| format!("Failed to fetch transaction: {}", e))?
+            .ok_or_else(|| format!("Transaction not found: {}", id))?;
+
+        if transaction.r#type != "sale" {
+            return Err("Only sale transactions can be completed".to_string());
+        }
+        if transaction.status == "completed" || transaction.status == "cancelled" {
+            return Err(format!(
+                "Transaction with status '{}' cannot be completed",
+                transaction.status
+            ));
+        }
+
+        let total_net = transaction
+            .total_net
+            .ok_or_else(|| "Sale has no net total".to_string())?;
+        let paid = ShopPaymentRepository::get_paid_amount_in_tx(&mut tx, id)
+            .await
+            .map_err(|e| format!("Failed to get paid amount: {}", e))?;
+        let remaining = total_net - paid;
+        if remaining >= 0.01 {
+            return Err(format!(
+                "Sale is not fully paid. Remaining balance: {:.2}",
+                remaining
+            ));
+        }
+
+        // Bundles move their components' stock and split the line price across
+        // them for reports and returns; digital goods get their license keys
+        // and download links
+        let items = TransactionItemsRepository::find_by_transaction_id_with_tx(&mut tx, id)
+            .await
+            .map_err(|e| format!("Failed to fetch transaction items: {}", e))?;
+        for item in &items {
+            if let Some(product_id) = &item.product_id {
+                ShopBundleService::stock_out_in_tx(
+                    &mut tx,
+                    product_id,
+                    item.quantity,
+                    location_id,
+                    Some(id),
+                )
+                .await?;
+            }
+            ShopBundleService::record_allocation_in_tx(&mut tx, item).await?;
+            ShopDigitalDeliveryService::deliver_in_tx(&mut tx, &transaction, item).await?;
+        }
+
+        let updated =
+            ShopTransactionRepository::update_status_in_tx(&mut tx, id, "completed", self.shop_id.clone())
+                .await
+                .map_err(|e| format!("Failed to update transaction status: {}", e))?;
+
+        // The sale counts towards the customer's spend, and what was bought can
+        // move the customer into "purchased" segments
+        if let Some(customer_id) = &transaction.customer_id {
+            ShopCustomerRepository::refresh_stats_in_tx(&mut tx, customer_id, self.shop_id.clone())
+                .await
+                .map_err(|e| format!("Failed to update customer stats: {}", e))?;
+            ShopCustomerSegmentService::refresh_customer_in_tx(&mut tx, customer_id).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        Ok(updated)
     }
 }
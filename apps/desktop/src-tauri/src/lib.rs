@@ -1,11 +1,16 @@
 pub mod db;
 pub mod features;
 
-use crate::db::{MigrationService, PoolManager, RepositoryFactory};
+use crate::db::{run_pool_maintenance, MigrationService, PoolManager, RepositoryFactory};
 
 use crate::features::analytics::commands::analytics_commands::{
     get_average_order_value,
     get_category_distribution,
+    get_consolidated_average_order_value,
+    get_consolidated_customer_growth,
+    get_consolidated_revenue,
+    get_consolidated_stock_status,
+    get_consolidated_top_products,
     get_conversion_rate,
     get_cumulative_revenue,
     get_customer_group_distribution,
@@ -19,6 +24,7 @@ use crate::features::analytics::commands::analytics_commands::{
     get_monthly_sales_progress,
     get_order_status_distribution,
     get_payment_method_distribution,
+    get_price_realization,
     get_product_metrics,
     get_product_ranking,
     get_product_review_analytics,
@@ -34,19 +40,25 @@ use crate::features::analytics::commands::analytics_commands::{
     get_top_rated_products,
     get_year_to_date_sales,
 };
+use crate::features::analytics::commands::customer_metrics_commands::{
+    get_customer_cohorts, get_customer_metrics, get_rfm_segment_summary, list_customer_metrics,
+    refresh_customer_metrics,
+};
+use crate::features::analytics::services::customer_metrics_scheduler::run_customer_metrics_scheduler;
 use crate::features::brand::commands::brand_commands::{
     create_brand, delete_brand, get_brand, list_brands, list_brands_by_shop, update_brand,
 };
 use crate::features::category::commands::category_commands::{
-    create_category, delete_category, get_category, list_categories, list_categories_by_shop,
-    update_category,
+    create_category, delete_category, get_category, get_category_path, get_category_product_counts,
+    get_category_tree, list_categories, list_categories_by_shop, move_category, update_category,
 };
 use crate::features::checkout::commands::checkout_commands::{
     create_checkout, delete_checkout, get_checkout, get_checkout_by_token, list_checkouts,
     list_checkouts_by_shop, update_checkout,
 };
 use crate::features::customer::commands::customer_commands::{
-    create_customer, delete_customer, get_customer, list_customers, list_customers_by_shop,
+    create_customer, delete_customer, dismiss_duplicate_customers, find_duplicate_customers,
+    get_customer, list_customer_merges, list_customers, list_customers_by_shop, merge_customers,
     update_customer,
 };
 use crate::features::customer_address::commands::customer_address_commands::{
@@ -55,39 +67,85 @@ use crate::features::customer_address::commands::customer_address_commands::{
 };
 use crate::features::customer_group::commands::customer_group_commands::{
     create_customer_group, delete_customer_group, get_customer_group, list_customer_groups,
-    list_customer_groups_by_shop, update_customer_group,
+    list_customer_groups_by_shop, preview_customer_group_rules, refresh_customer_group,
+    refresh_customer_groups, update_customer_group,
 };
+use crate::features::customer_group::services::customer_segment_scheduler::run_customer_segment_scheduler;
 use crate::features::customer_group_membership::commands::customer_group_membership_commands::{
     assign_customer_groups, delete_customer_group_membership,
     list_customer_group_memberships_by_customer, list_customer_group_memberships_by_group,
 };
+use crate::features::gift_card::commands::gift_card_commands::{
+    adjust_gift_card_balance, get_gift_card, get_gift_card_by_code, issue_gift_card,
+    list_gift_card_ledger, list_gift_cards, list_gift_cards_by_customer, update_gift_card_status,
+};
 use crate::features::inquiry::commands::inquiry_commands::{
     create_inquiry, delete_inquiry, get_inquiry, list_inquiries, list_inquiries_by_shop,
 };
 use crate::features::module::commands::modules_commands::{
-    get_module, get_module_by_code, list_core_modules, list_modules, list_modules_by_category,
+    get_module, get_module_by_code, get_shop_modules, list_core_modules, list_modules,
+    list_modules_by_category, list_shop_module_changes, set_shop_modules,
 };
 use crate::features::order::commands::order_commands::{
     cancel_order, create_order, delete_order, get_order, list_orders, list_orders_by_shop,
     update_order, update_order_fulfillment_status, update_order_payment_status,
 };
+use crate::features::payment::commands::installment_commands::{
+    delete_installment_rate, list_installment_rates, list_payment_installments,
+    quote_installments, upsert_installment_rate,
+};
 use crate::features::payment::commands::payment_commands::{
-    get_payment, list_payments, list_payments_by_shop, update_payment_status,
+    create_payment, get_payment, get_payment_summary, list_payments, list_payments_by_shop,
+    process_refund, update_payment_status,
 };
 use crate::features::product::commands::product_commands::{
     create_product, delete_product, get_product, list_products, list_products_filtered,
-    update_product,
+    list_products_priced, update_product,
+};
+use crate::features::price_history::commands::price_history_commands::{
+    apply_due_price_schedules, cancel_price_schedule, create_price_schedule, get_price_on_date,
+    get_promo_disclosure, list_price_history, list_price_schedules,
+};
+use crate::features::price_history::services::price_scheduler::run_price_scheduler;
+use crate::features::price_list::commands::price_list_commands::{
+    create_price_list, create_price_list_rule, delete_price_list, delete_price_list_rule,
+    get_price_list, list_price_list_rules, list_price_lists, resolve_price, update_price_list,
+    update_price_list_rule,
+};
+use crate::features::bundle::commands::bundle_commands::{
+    get_bundle_availability, list_bundle_components, list_transaction_bundle_components,
+    set_bundle_components,
+};
+use crate::features::product::commands::product_variant_commands::{
+    bulk_set_variant_stock, bulk_update_product_variants, generate_product_variants,
+    get_product_group, list_product_options, list_product_variants, list_products_grouped,
+    search_products_grouped, set_product_options,
 };
 use crate::features::refund::commands::refund_commands::{
     create_refund, delete_refund, get_refund, list_refunds, list_refunds_by_payment, update_refund,
     update_refund_status,
 };
+use crate::features::return_request::commands::return_request_commands::{
+    cancel_return_request, get_return_request, list_return_items, list_return_requests,
+    list_return_requests_by_transaction, open_return_request, receive_return_request,
+    resolve_return_request,
+};
 use crate::features::shop::commands::shop_commands::{
     create_shop, create_shop_from_template, delete_shop, get_shop, list_shops, update_shop,
 };
 use crate::features::shop_template::commands::shop_templates_commands::{
-    get_shop_template, get_shop_template_by_code, list_shop_templates,
-    list_shop_templates_by_category,
+    apply_shop_template, diff_shop_template, export_shop_as_template, get_shop_template,
+    get_shop_template_by_code, get_shop_template_seed, list_shop_template_applications,
+    list_shop_templates, list_shop_templates_by_category, upgrade_shop_template,
+};
+use crate::features::stock_transfer::commands::shop_transfer_commands::{
+    cancel_shop_transfer, create_shop_transfer, dispatch_shop_transfer, get_shop_transfer,
+    list_shop_transfers, receive_shop_transfer, recover_shop_transfers, resume_shop_transfer,
+};
+use crate::features::stock_transfer::services::shop_transfer_service::resume_pending_transfers;
+use crate::features::store_credit::commands::store_credit_commands::{
+    adjust_store_credit, get_store_credit_account, list_store_credit_accounts,
+    list_store_credit_ledger, update_store_credit_status,
 };
 use crate::features::transaction::commands::transaction_commands::{
     cancel_transaction, complete_sale_transaction, create_transaction, delete_transaction,
@@ -114,9 +172,52 @@ use crate::features::pos_session::commands::pos_session_commands::{
 use crate::features::review::commands::review_commands::{
     create_review, delete_review, get_review, list_reviews, list_reviews_by_shop, update_review,
 };
+use crate::features::barcode::commands::barcode_commands::{
+    create_product_barcode, delete_product_barcode, generate_product_labels,
+    list_product_barcodes, scan_barcode,
+};
+use crate::features::data_transfer::commands::data_transfer_commands::{
+    export_data_file, import_data_file, list_import_fields, preview_import_file,
+};
+use crate::features::product_media::commands::product_media_commands::{
+    attach_product_media, detach_product_media, get_product_covers, list_product_media,
+    purge_orphan_media, reorder_product_media, update_product_media,
+};
+use crate::features::database::commands::database_commands::{
+    get_shop_database_config, list_database_maintenance_runs, pool_stats, run_database_maintenance,
+    set_shop_sqlite_tuning,
+};
+use crate::features::database::commands::shop_database_migration_commands::{
+    get_shop_database_migration, list_shop_database_migrations, migrate_shop_database,
+    rollback_shop_database_migration,
+};
+use crate::features::database::services::sqlite_maintenance_scheduler::run_sqlite_maintenance;
+use crate::features::digital_delivery::commands::digital_delivery_commands::{
+    add_license_keys, get_digital_product, get_license_key_stock,
+    list_customer_digital_deliveries, list_digital_deliveries, list_license_keys,
+    redeem_download, revoke_digital_delivery, revoke_license_key, upsert_digital_product,
+};
+use crate::features::exchange_rate::commands::exchange_rate_commands::{
+    delete_exchange_rate, list_exchange_rates, set_exchange_rate,
+};
+use crate::features::service_booking::commands::service_appointment_commands::{
+    create_service_appointment, get_service_appointment, list_item_service_appointments,
+    list_service_appointments, set_service_appointment_status, update_service_appointment,
+};
+use crate::features::search::commands::search_commands::{
+    get_search_index_stats, rebuild_search_index, search_customers, search_products,
+};
 use crate::features::shipment::commands::shipment_commands::{
-    create_shipment, delete_shipment, get_shipment, list_shipments, list_shipments_by_shop,
-    update_shipment,
+    create_shipment, delete_shipment, get_shipment, list_shipment_events, list_shipments,
+    list_shipments_by_shop, update_shipment,
+};
+use crate::features::carrier::commands::carrier_commands::{
+    buy_shipping_label, delete_carrier_account, list_carrier_accounts, poll_shipment_tracking,
+    quote_shipping_rates, refresh_shipment_tracking, upsert_carrier_account,
+};
+use crate::features::packing::commands::packing_commands::{
+    compute_packing_plan, create_packed_shipments, create_shipping_box, delete_shipping_box,
+    list_shipping_boxes, update_shipping_box,
 };
 use crate::features::transaction::commands::transaction_item_commands::{
     create_transaction_item, delete_transaction_item, get_transaction_item, list_transaction_items,
@@ -170,6 +271,24 @@ pub fn run() {
             get_product_review_analytics,
             get_review_stats_summary,
             get_rating_distribution,
+            // Price Analytics
+            get_price_realization,
+            // Consolidated Analytics
+            get_consolidated_revenue,
+            get_consolidated_top_products,
+            get_consolidated_stock_status,
+            get_consolidated_average_order_value,
+            get_consolidated_customer_growth,
+            // Customer Analytics
+            refresh_customer_metrics,
+            list_customer_metrics,
+            get_customer_metrics,
+            get_rfm_segment_summary,
+            get_customer_cohorts,
+            // Exchange Rates
+            set_exchange_rate,
+            list_exchange_rates,
+            delete_exchange_rate,
             // Products
             create_product,
             update_product,
@@ -177,6 +296,83 @@ pub fn run() {
             get_product,
             list_products,
             list_products_filtered,
+            list_products_priced,
+            // Product Variants
+            list_product_options,
+            set_product_options,
+            list_product_variants,
+            generate_product_variants,
+            bulk_update_product_variants,
+            bulk_set_variant_stock,
+            get_product_group,
+            list_products_grouped,
+            search_products_grouped,
+            // Bundles
+            list_bundle_components,
+            set_bundle_components,
+            get_bundle_availability,
+            list_transaction_bundle_components,
+            // Price Lists
+            create_price_list,
+            update_price_list,
+            delete_price_list,
+            get_price_list,
+            list_price_lists,
+            create_price_list_rule,
+            update_price_list_rule,
+            delete_price_list_rule,
+            list_price_list_rules,
+            resolve_price,
+            // Price History
+            list_price_history,
+            get_price_on_date,
+            get_promo_disclosure,
+            create_price_schedule,
+            list_price_schedules,
+            cancel_price_schedule,
+            apply_due_price_schedules,
+            // Search
+            search_products,
+            search_customers,
+            rebuild_search_index,
+            get_search_index_stats,
+            // Barcodes
+            create_product_barcode,
+            delete_product_barcode,
+            list_product_barcodes,
+            scan_barcode,
+            generate_product_labels,
+            // Data Transfer
+            list_import_fields,
+            preview_import_file,
+            import_data_file,
+            export_data_file,
+            // Product Media
+            attach_product_media,
+            list_product_media,
+            get_product_covers,
+            update_product_media,
+            reorder_product_media,
+            detach_product_media,
+            purge_orphan_media,
+            // Digital Delivery
+            upsert_digital_product,
+            get_digital_product,
+            add_license_keys,
+            list_license_keys,
+            get_license_key_stock,
+            revoke_license_key,
+            list_digital_deliveries,
+            list_customer_digital_deliveries,
+            redeem_download,
+            revoke_digital_delivery,
+            // Service Appointments
+            create_service_appointment,
+            update_service_appointment,
+            set_service_appointment_status,
+            get_service_appointment,
+            list_service_appointments,
+            list_item_service_appointments,
             // Brands
             create_brand,
             update_brand,
@@ -191,6 +387,10 @@ pub fn run() {
             get_category,
             list_categories_by_shop,
             list_categories,
+            get_category_tree,
+            move_category,
+            get_category_path,
+            get_category_product_counts,
             // Orders
             create_order,
             update_order,
@@ -209,11 +409,44 @@ pub fn run() {
             list_refunds,
             list_refunds_by_payment,
             update_refund_status,
+            // Returns (RMA)
+            open_return_request,
+            receive_return_request,
+            resolve_return_request,
+            cancel_return_request,
+            get_return_request,
+            list_return_requests,
+            list_return_requests_by_transaction,
+            list_return_items,
             // Payments
             list_payments,
             list_payments_by_shop,
             get_payment,
             update_payment_status,
+            create_payment,
+            process_refund,
+            get_payment_summary,
+            // Installments
+            list_installment_rates,
+            upsert_installment_rate,
+            delete_installment_rate,
+            quote_installments,
+            list_payment_installments,
+            // Gift Cards
+            issue_gift_card,
+            adjust_gift_card_balance,
+            update_gift_card_status,
+            get_gift_card,
+            get_gift_card_by_code,
+            list_gift_cards,
+            list_gift_cards_by_customer,
+            list_gift_card_ledger,
+            // Store Credit
+            get_store_credit_account,
+            list_store_credit_accounts,
+            list_store_credit_ledger,
+            adjust_store_credit,
+            update_store_credit_status,
             // Checkouts
             create_checkout,
             update_checkout,
@@ -229,6 +462,10 @@ pub fn run() {
             get_customer,
             list_customers,
             list_customers_by_shop,
+            find_duplicate_customers,
+            merge_customers,
+            dismiss_duplicate_customers,
+            list_customer_merges,
             // Customer Addresses
             create_customer_address,
             update_customer_address,
@@ -243,6 +480,9 @@ pub fn run() {
             get_customer_group,
             list_customer_groups,
             list_customer_groups_by_shop,
+            preview_customer_group_rules,
+            refresh_customer_group,
+            refresh_customer_groups,
             // Customer Group Memberships
             assign_customer_groups,
             list_customer_group_memberships_by_customer,
@@ -295,6 +535,22 @@ pub fn run() {
             get_shipment,
             list_shipments,
             list_shipments_by_shop,
+            list_shipment_events,
+            // Carriers
+            list_carrier_accounts,
+            upsert_carrier_account,
+            delete_carrier_account,
+            quote_shipping_rates,
+            buy_shipping_label,
+            refresh_shipment_tracking,
+            poll_shipment_tracking,
+            // Packing
+            list_shipping_boxes,
+            create_shipping_box,
+            update_shipping_box,
+            delete_shipping_box,
+            compute_packing_plan,
+            create_packed_shipments,
             // Reviews
             list_reviews_by_shop,
             list_reviews,
@@ -314,11 +570,29 @@ pub fn run() {
             list_modules,
             list_modules_by_category,
             list_core_modules,
+            get_shop_modules,
+            set_shop_modules,
+            list_shop_module_changes,
             // Shop Templates
             get_shop_template,
             get_shop_template_by_code,
             list_shop_templates,
             list_shop_templates_by_category,
+            get_shop_template_seed,
+            diff_shop_template,
+            apply_shop_template,
+            upgrade_shop_template,
+            export_shop_as_template,
+            list_shop_template_applications,
+            // Shop Transfers
+            create_shop_transfer,
+            get_shop_transfer,
+            list_shop_transfers,
+            dispatch_shop_transfer,
+            receive_shop_transfer,
+            cancel_shop_transfer,
+            resume_shop_transfer,
+            recover_shop_transfers,
             // POS Sessions
             create_pos_session,
             update_pos_session,
@@ -335,6 +609,16 @@ pub fn run() {
             delete_shop,
             get_shop,
             list_shops,
+            // Database
+            pool_stats,
+            get_shop_database_config,
+            set_shop_sqlite_tuning,
+            run_database_maintenance,
+            list_database_maintenance_runs,
+            migrate_shop_database,
+            rollback_shop_database_migration,
+            get_shop_database_migration,
+            list_shop_database_migrations,
             // Users
             create_user,
             update_user,
@@ -370,6 +654,24 @@ pub fn run() {
             // Create RepositoryFactory for dependency injection
             let repo_factory = std::sync::Arc::new(RepositoryFactory::new(pool_manager.clone()));
 
+            // Probe shop pools and close the idle ones in the background
+            tauri::async_runtime::spawn(run_pool_maintenance(pool_manager.clone()));
+
+            // Apply scheduled price changes and promotional windows in the background
+            tauri::async_runtime::spawn(run_price_scheduler(repo_factory.clone()));
+
+            // Optimize, check and checkpoint the open shop databases
+            tauri::async_runtime::spawn(run_sqlite_maintenance(repo_factory.clone()));
+
+            // Recompute dynamic customer groups, for rules that depend on the date
+            tauri::async_runtime::spawn(run_customer_segment_scheduler(repo_factory.clone()));
+
+            // Rescore customers daily so RFM recency and churn follow the calendar
+            tauri::async_runtime::spawn(run_customer_metrics_scheduler(repo_factory.clone()));
+
+            // Finish inter-shop transfers interrupted between the two shop databases
+            tauri::async_runtime::spawn(resume_pending_transfers(repo_factory.clone()));
+
             // Manage the new infrastructure
             app.manage(pool_manager.clone());
             app.manage(repo_factory);
@@ -409,6 +711,14 @@ pub fn run() {
             }
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            // Let in-flight queries finish and close every pool before exiting
+            if let tauri::RunEvent::Exit = event {
+                if let Some(pool_manager) = app_handle.try_state::<std::sync::Arc<PoolManager>>() {
+                    tauri::async_runtime::block_on(pool_manager.shutdown());
+                }
+            }
+        });
 }